@@ -0,0 +1,47 @@
+use std::{env, str::FromStr};
+
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+};
+use solana_program::pubkey::Pubkey;
+
+use movie_review_client::{
+    decode::decode,
+    filters::reviews_by_reviewer_filters,
+    pda::get_comment_counter_address,
+    ReviewCommentCounterState, ReviewState,
+};
+
+// usage: fetch_reviews <rpc url> <program id> <reviewer>
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    let [_, rpc_url, program_id, reviewer] = args.as_slice() else {
+        return Err("usage: fetch_reviews <rpc url> <program id> <reviewer>".into());
+    };
+
+    let client = RpcClient::new(rpc_url.clone());
+    let program_id = Pubkey::from_str(program_id)?;
+    let reviewer = Pubkey::from_str(reviewer)?;
+
+    let reviews = client.get_program_accounts_with_config(
+        &program_id,
+        RpcProgramAccountsConfig {
+            filters: Some(reviews_by_reviewer_filters(&reviewer)),
+            account_config: RpcAccountInfoConfig::default(),
+            ..RpcProgramAccountsConfig::default()
+        },
+    )?;
+
+    for (address, account) in reviews {
+        let review = decode::<ReviewState>(&account.data)?;
+
+        let counter_address = get_comment_counter_address(&program_id, &address);
+        let comments = decode::<ReviewCommentCounterState>(&client.get_account_data(&counter_address)?)?.counter;
+
+        println!("{address}: {} ({}/5, {comments} comments)", review.title, review.rating);
+    }
+
+    Ok(())
+}
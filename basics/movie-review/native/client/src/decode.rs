@@ -0,0 +1,107 @@
+use borsh::BorshDeserialize;
+use solana_program::program_pack::IsInitialized;
+use thiserror::Error;
+
+use program::state::{
+    STATE_VERSION,
+    ConfigState, MovieState, ReviewState, ReviewStakeState, ReviewCommentCounterState, ReviewCommentState,
+    ReviewCommentPageState, CommenterProfileState, CommentVoteReceiptState, ReviewRewardReceiptState,
+};
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error("Account data does not start with a discriminator and version")]
+    InvalidHeader,
+    #[error("Expected a `{expected}` account, found `{found}`")]
+    WrongDiscriminator {
+        expected: &'static str,
+        found: String,
+    },
+    #[error("Account is on state version {found}, expected {expected}; it needs MigrateAccount")]
+    OutdatedVersion {
+        found: u8,
+        expected: u8,
+    },
+    #[error("`{0}` account is not initialized")]
+    Uninitialized(&'static str),
+    #[error("Account data does not match the `{0}` layout: {1}")]
+    InvalidLayout(&'static str, std::io::Error),
+}
+
+pub trait ProgramAccount: BorshDeserialize + IsInitialized {
+    const DISCRIMINATOR: &'static str;
+}
+
+impl ProgramAccount for ConfigState {
+    const DISCRIMINATOR: &'static str = ConfigState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for MovieState {
+    const DISCRIMINATOR: &'static str = MovieState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for ReviewState {
+    const DISCRIMINATOR: &'static str = ReviewState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for ReviewStakeState {
+    const DISCRIMINATOR: &'static str = ReviewStakeState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for ReviewCommentCounterState {
+    const DISCRIMINATOR: &'static str = ReviewCommentCounterState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for ReviewCommentState {
+    const DISCRIMINATOR: &'static str = ReviewCommentState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for ReviewCommentPageState {
+    const DISCRIMINATOR: &'static str = ReviewCommentPageState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for CommenterProfileState {
+    const DISCRIMINATOR: &'static str = CommenterProfileState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for CommentVoteReceiptState {
+    const DISCRIMINATOR: &'static str = CommentVoteReceiptState::DISCRIMINATOR;
+}
+
+impl ProgramAccount for ReviewRewardReceiptState {
+    const DISCRIMINATOR: &'static str = ReviewRewardReceiptState::DISCRIMINATOR;
+}
+
+// Checks the discriminator and version before decoding the rest, so a wrong or
+// unmigrated account is reported as such instead of as a Borsh error. Trailing
+// bytes are ignored, since accounts are allocated with room to grow.
+pub fn decode<T: ProgramAccount>(data: &[u8]) -> Result<T, DecodeError> {
+    let mut header = data;
+
+    let discriminator = String::deserialize(&mut header).map_err(|_| DecodeError::InvalidHeader)?;
+
+    if discriminator != T::DISCRIMINATOR {
+        return Err(DecodeError::WrongDiscriminator {
+            expected: T::DISCRIMINATOR,
+            found: discriminator,
+        });
+    }
+
+    let version = *header.first().ok_or(DecodeError::InvalidHeader)?;
+
+    if version != STATE_VERSION {
+        return Err(DecodeError::OutdatedVersion {
+            found: version,
+            expected: STATE_VERSION,
+        });
+    }
+
+    let account = T::deserialize(&mut &data[..])
+        .map_err(|e| DecodeError::InvalidLayout(T::DISCRIMINATOR, e))?;
+
+    if !account.is_initialized() {
+        return Err(DecodeError::Uninitialized(T::DISCRIMINATOR));
+    }
+
+    Ok(account)
+}
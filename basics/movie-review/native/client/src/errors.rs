@@ -0,0 +1,17 @@
+use solana_program::{instruction::InstructionError, program_error::ProgramError};
+
+use program::error::ReviewError;
+
+pub fn review_error(error: &ProgramError) -> Option<ReviewError> {
+    match error {
+        ProgramError::Custom(code) => ReviewError::try_from(*code).ok(),
+        _ => None,
+    }
+}
+
+pub fn review_error_from_instruction_error(error: &InstructionError) -> Option<ReviewError> {
+    match error {
+        InstructionError::Custom(code) => ReviewError::try_from(*code).ok(),
+        _ => None,
+    }
+}
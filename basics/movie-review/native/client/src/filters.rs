@@ -0,0 +1,49 @@
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_program::pubkey::Pubkey;
+
+use program::state::{ReviewState, ReviewCommentState};
+
+pub fn reviews_by_reviewer_filters(reviewer: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        discriminator_filter(ReviewState::DISCRIMINATOR),
+        RpcFilterType::Memcmp(
+            Memcmp::new_base58_encoded(ReviewState::REVIEWER_OFFSET, reviewer.as_ref())
+        ),
+    ]
+}
+
+pub fn reviews_by_owner_filters(owner: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        discriminator_filter(ReviewState::DISCRIMINATOR),
+        RpcFilterType::Memcmp(
+            Memcmp::new_base58_encoded(ReviewState::OWNER_OFFSET, owner.as_ref())
+        ),
+    ]
+}
+
+pub fn reviews_by_movie_filters(movie: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        discriminator_filter(ReviewState::DISCRIMINATOR),
+        RpcFilterType::Memcmp(
+            Memcmp::new_base58_encoded(ReviewState::MOVIE_OFFSET, movie.as_ref())
+        ),
+    ]
+}
+
+pub fn comments_by_review_filters(review: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        discriminator_filter(ReviewCommentState::DISCRIMINATOR),
+        RpcFilterType::Memcmp(
+            Memcmp::new_base58_encoded(ReviewCommentState::REVIEW_OFFSET, review.as_ref())
+        ),
+    ]
+}
+
+// Discriminators are Borsh strings, so the filter has to match the u32 length
+// prefix as well as the bytes.
+fn discriminator_filter(discriminator: &str) -> RpcFilterType {
+    let mut bytes = (discriminator.len() as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(discriminator.as_bytes());
+
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &bytes))
+}
@@ -0,0 +1,358 @@
+use std::io;
+
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_loader_v3_interface::get_program_data_address;
+use solana_system_interface::program::id as system_program_id;
+use spl_associated_token_account::id as associated_token_program_id;
+use spl_token::id as token_program_id;
+
+use program::state::{CategoryRatings, ReviewCommentPageState};
+
+use crate::pda::{
+    get_config_address, get_token_mint_address, get_mint_authority_address, get_reward_token_address,
+    get_movie_address, get_review_address, get_comment_counter_address, get_comment_address,
+    get_comment_page_address, get_commenter_profile_address, get_vote_receipt_address, get_reward_receipt_address,
+    get_stake_address, get_escrow_authority_address,
+};
+
+// `payer` has to be the program's upgrade authority the first time.
+pub fn initialize_token_mint_ix(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[3],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_config_address(program_id), false),
+            AccountMeta::new(get_token_mint_address(program_id), false),
+            AccountMeta::new_readonly(get_mint_authority_address(program_id), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(get_program_data_address(program_id), false),
+        ],
+    )
+}
+
+pub fn register_movie_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    title: &str,
+    imdb_id: &str,
+) -> io::Result<Instruction> {
+    let mut register_movie_ix_data = vec![4];
+
+    RegisterMoviePayload {
+        title: title.to_string(),
+        imdb_id: imdb_id.to_string(),
+    }.serialize(&mut register_movie_ix_data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &register_movie_ix_data,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_movie_address(program_id, imdb_id), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn add_movie_review_ix(
+    program_id: &Pubkey,
+    reviewer: &Pubkey,
+    movie: &Pubkey,
+    ratings: CategoryRatings,
+    description: &str,
+) -> io::Result<Instruction> {
+    let movie_review = get_review_address(program_id, reviewer, movie);
+
+    let mut add_movie_review_ix_data = vec![0];
+
+    MovieReviewPayload {
+        ratings,
+        description: description.to_string(),
+    }.serialize(&mut add_movie_review_ix_data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &add_movie_review_ix_data,
+        vec![
+            AccountMeta::new(*reviewer, true),
+            AccountMeta::new_readonly(*movie, false),
+            AccountMeta::new(movie_review, false),
+            AccountMeta::new(get_comment_counter_address(program_id, &movie_review), false),
+            AccountMeta::new(get_reward_receipt_address(program_id, movie, reviewer), false),
+            AccountMeta::new(get_config_address(program_id), false),
+            AccountMeta::new(get_token_mint_address(program_id), false),
+            AccountMeta::new_readonly(get_mint_authority_address(program_id), false),
+            AccountMeta::new(get_reward_token_address(program_id, reviewer), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+        ],
+    ))
+}
+
+pub fn update_movie_review_ix(
+    program_id: &Pubkey,
+    reviewer: &Pubkey,
+    movie_review: &Pubkey,
+    ratings: CategoryRatings,
+    description: &str,
+) -> io::Result<Instruction> {
+    let mut update_movie_review_ix_data = vec![1];
+
+    MovieReviewPayload {
+        ratings,
+        description: description.to_string(),
+    }.serialize(&mut update_movie_review_ix_data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &update_movie_review_ix_data,
+        vec![
+            AccountMeta::new(*reviewer, true),
+            AccountMeta::new(*movie_review, false),
+            AccountMeta::new_readonly(get_config_address(program_id), false),
+        ],
+    ))
+}
+
+pub fn add_comment_ix(
+    program_id: &Pubkey,
+    commenter: &Pubkey,
+    movie_review: &Pubkey,
+    count: u64,
+    comment: &str,
+) -> io::Result<Instruction> {
+    let mut add_comment_ix_data = vec![2];
+
+    CommentPayload {
+        comment: comment.to_string(),
+    }.serialize(&mut add_comment_ix_data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &add_comment_ix_data,
+        vec![
+            AccountMeta::new(*commenter, true),
+            AccountMeta::new_readonly(*movie_review, false),
+            AccountMeta::new(get_comment_counter_address(program_id, movie_review), false),
+            AccountMeta::new(get_comment_address(program_id, movie_review, count), false),
+            AccountMeta::new(get_commenter_profile_address(program_id, commenter), false),
+            AccountMeta::new(
+                get_comment_page_address(program_id, movie_review, ReviewCommentPageState::page_for(count)),
+                false,
+            ),
+            AccountMeta::new_readonly(get_config_address(program_id), false),
+            AccountMeta::new(get_token_mint_address(program_id), false),
+            AccountMeta::new_readonly(get_mint_authority_address(program_id), false),
+            AccountMeta::new(get_reward_token_address(program_id, commenter), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+        ],
+    ))
+}
+
+// `page_payers` holds the `payer` of each of the review's comment pages, in
+// page order; the pages are closed along with the review.
+pub fn delete_movie_review_ix(
+    program_id: &Pubkey,
+    reviewer: &Pubkey,
+    movie_review: &Pubkey,
+    page_payers: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*reviewer, true),
+        AccountMeta::new(*movie_review, false),
+        AccountMeta::new(get_comment_counter_address(program_id, movie_review), false),
+    ];
+
+    for (page, payer) in page_payers.iter().enumerate() {
+        accounts.push(AccountMeta::new(get_comment_page_address(program_id, movie_review, page as u64), false));
+        accounts.push(AccountMeta::new(*payer, false));
+    }
+
+    Instruction::new_with_bytes(*program_id, &[5], accounts)
+}
+
+pub fn close_comments_ix(
+    program_id: &Pubkey,
+    movie_review: &Pubkey,
+    comments: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(*movie_review, false)];
+
+    for (comment, commenter) in comments {
+        accounts.push(AccountMeta::new(*comment, false));
+        accounts.push(AccountMeta::new(*commenter, false));
+    }
+
+    Instruction::new_with_bytes(*program_id, &[6], accounts)
+}
+
+pub fn transfer_review_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    movie_review: &Pubkey,
+    new_owner: &Pubkey,
+) -> io::Result<Instruction> {
+    let mut transfer_review_ix_data = vec![7];
+
+    TransferReviewPayload {
+        new_owner: *new_owner,
+    }.serialize(&mut transfer_review_ix_data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &transfer_review_ix_data,
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*movie_review, false),
+        ],
+    ))
+}
+
+pub fn accept_review_ix(
+    program_id: &Pubkey,
+    new_owner: &Pubkey,
+    movie_review: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[8],
+        vec![
+            AccountMeta::new_readonly(*new_owner, true),
+            AccountMeta::new(*movie_review, false),
+        ],
+    )
+}
+
+pub fn migrate_account_ix(program_id: &Pubkey, payer: &Pubkey, account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[9],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    )
+}
+
+pub fn vote_on_comment_ix(
+    program_id: &Pubkey,
+    voter: &Pubkey,
+    comment: &Pubkey,
+    commenter: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[12],
+        vec![
+            AccountMeta::new(*voter, true),
+            AccountMeta::new(*comment, false),
+            AccountMeta::new(get_vote_receipt_address(program_id, comment, voter), false),
+            AccountMeta::new(get_commenter_profile_address(program_id, commenter), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    )
+}
+
+pub fn refund_comment_deposit_ix(program_id: &Pubkey, commenter: &Pubkey, comment: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[13],
+        vec![
+            AccountMeta::new(*commenter, true),
+            AccountMeta::new(*comment, false),
+            AccountMeta::new_readonly(get_config_address(program_id), false),
+        ],
+    )
+}
+
+pub fn stake_on_review_ix(
+    program_id: &Pubkey,
+    staker: &Pubkey,
+    movie_review: &Pubkey,
+    amount: u64,
+) -> io::Result<Instruction> {
+    let escrow_auth = get_escrow_authority_address(program_id, movie_review);
+
+    let mut stake_on_review_ix_data = vec![10];
+
+    StakePayload { amount }.serialize(&mut stake_on_review_ix_data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &stake_on_review_ix_data,
+        vec![
+            AccountMeta::new(*staker, true),
+            AccountMeta::new(*movie_review, false),
+            AccountMeta::new(get_stake_address(program_id, movie_review, staker), false),
+            AccountMeta::new_readonly(escrow_auth, false),
+            AccountMeta::new(get_reward_token_address(program_id, &escrow_auth), false),
+            AccountMeta::new(get_reward_token_address(program_id, staker), false),
+            AccountMeta::new_readonly(get_token_mint_address(program_id), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+        ],
+    ))
+}
+
+pub fn unstake_from_review_ix(
+    program_id: &Pubkey,
+    staker: &Pubkey,
+    movie_review: &Pubkey,
+) -> Instruction {
+    let escrow_auth = get_escrow_authority_address(program_id, movie_review);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &[11],
+        vec![
+            AccountMeta::new(*staker, true),
+            AccountMeta::new(*movie_review, false),
+            AccountMeta::new(get_stake_address(program_id, movie_review, staker), false),
+            AccountMeta::new_readonly(escrow_auth, false),
+            AccountMeta::new(get_reward_token_address(program_id, &escrow_auth), false),
+            AccountMeta::new(get_reward_token_address(program_id, staker), false),
+            AccountMeta::new_readonly(get_token_mint_address(program_id), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+    )
+}
+
+// Payload structs mirror the program's private instruction payloads; each
+// instruction's data is its tag byte followed by the Borsh-encoded payload.
+#[derive(BorshSerialize)]
+struct MovieReviewPayload {
+    ratings: CategoryRatings,
+    description: String,
+}
+
+#[derive(BorshSerialize)]
+struct CommentPayload {
+    comment: String,
+}
+
+#[derive(BorshSerialize)]
+struct RegisterMoviePayload {
+    title: String,
+    imdb_id: String,
+}
+
+#[derive(BorshSerialize)]
+struct TransferReviewPayload {
+    new_owner: Pubkey,
+}
+
+#[derive(BorshSerialize)]
+struct StakePayload {
+    amount: u64,
+}
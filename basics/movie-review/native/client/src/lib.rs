@@ -0,0 +1,9 @@
+pub mod decode;
+pub mod errors;
+pub mod filters;
+pub mod instructions;
+pub mod pda;
+
+pub use program::error::ReviewError;
+pub use program::events::ReviewEvent;
+pub use program::state::*;
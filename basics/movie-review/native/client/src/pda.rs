@@ -0,0 +1,78 @@
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+use program::state::ReviewCommentPageState;
+
+pub fn get_config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], program_id).0
+}
+
+pub fn get_token_mint_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"token_mint"], program_id).0
+}
+
+pub fn get_mint_authority_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"mint_auth"], program_id).0
+}
+
+pub fn get_reward_token_address(program_id: &Pubkey, owner: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, &get_token_mint_address(program_id))
+}
+
+pub fn get_movie_address(program_id: &Pubkey, imdb_id: &str) -> Pubkey {
+    Pubkey::find_program_address(&[b"movie", imdb_id.as_bytes()], program_id).0
+}
+
+pub fn get_review_address(program_id: &Pubkey, reviewer: &Pubkey, movie: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[reviewer.as_ref(), movie.as_ref()], program_id).0
+}
+
+pub fn get_reward_receipt_address(program_id: &Pubkey, movie: &Pubkey, reviewer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"reward", movie.as_ref(), reviewer.as_ref()], program_id).0
+}
+
+pub fn get_comment_counter_address(program_id: &Pubkey, review: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[review.as_ref(), b"counter"], program_id).0
+}
+
+pub fn get_comment_address(program_id: &Pubkey, review: &Pubkey, count: u64) -> Pubkey {
+    Pubkey::find_program_address(&[review.as_ref(), count.to_be_bytes().as_ref()], program_id).0
+}
+
+pub fn get_comment_page_address(program_id: &Pubkey, review: &Pubkey, page: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[review.as_ref(), b"page", page.to_be_bytes().as_ref()],
+        program_id,
+    ).0
+}
+
+// Addresses of every comment page a review with `comment_count` comments can
+// have, for a single getMultipleAccounts call.
+pub fn get_comment_page_addresses(program_id: &Pubkey, review: &Pubkey, comment_count: u64) -> Vec<Pubkey> {
+    (0..comment_count.div_ceil(ReviewCommentPageState::COMMENTS_PER_PAGE as u64))
+        .map(|page| get_comment_page_address(program_id, review, page))
+        .collect()
+}
+
+pub fn get_commenter_profile_address(program_id: &Pubkey, commenter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"profile", commenter.as_ref()], program_id).0
+}
+
+pub fn get_vote_receipt_address(program_id: &Pubkey, comment: &Pubkey, voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vote", comment.as_ref(), voter.as_ref()], program_id).0
+}
+
+pub fn get_stake_address(program_id: &Pubkey, review: &Pubkey, staker: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"stake", review.as_ref(), staker.as_ref()], program_id).0
+}
+
+pub fn get_escrow_authority_address(program_id: &Pubkey, review: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"escrow", review.as_ref()], program_id).0
+}
+
+pub fn get_escrow_token_address(program_id: &Pubkey, review: &Pubkey) -> Pubkey {
+    get_associated_token_address(
+        &get_escrow_authority_address(program_id, review),
+        &get_token_mint_address(program_id),
+    )
+}
@@ -0,0 +1,156 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use mollusk_svm::{program::keyed_account_for_system_program, Mollusk};
+use mollusk_svm_programs_token::{associated_token, token};
+
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+
+use movie_review_client::{
+    instructions::{
+        initialize_token_mint_ix, register_movie_ix, add_movie_review_ix, update_movie_review_ix,
+        add_comment_ix, vote_on_comment_ix, stake_on_review_ix, transfer_review_ix, accept_review_ix,
+    },
+    pda::{get_movie_address, get_review_address, get_comment_address},
+};
+use program::state::CategoryRatings;
+
+// Checked-in CU numbers, one `<instruction> <units>` pair per line. Run with
+// `UPDATE_CU_BASELINE=1` to rewrite it after an intentional change.
+const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/compute_units.baseline");
+const REGRESSION_THRESHOLD_PERCENT: u64 = 5;
+
+const MOVIE_TITLE: &str = "Interstellar";
+const MOVIE_IMDB_ID: &str = "tt0816692";
+
+fn main() {
+    let program_id = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let commenter = Pubkey::new_unique();
+
+    // Mollusk loads `program.so` from `target/deploy`, so `cargo build-sbf`
+    // has to have been run first.
+    let mut mollusk = Mollusk::new(&program_id, "program");
+    token::add_program(&mut mollusk);
+    associated_token::add_program(&mut mollusk);
+
+    let mut accounts = HashMap::from([
+        keyed_account_for_system_program(),
+        token::keyed_account(),
+        associated_token::keyed_account(),
+        (payer, Account::new(100 * LAMPORTS_PER_SOL, 0, &Pubkey::default())),
+        (commenter, Account::new(100 * LAMPORTS_PER_SOL, 0, &Pubkey::default())),
+        (get_program_data_address(&program_id), program_data_account(&payer)),
+    ]);
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review = get_review_address(&program_id, &payer, &movie);
+    let comment = get_comment_address(&program_id, &movie_review, 0);
+
+    let ratings = CategoryRatings { plot: 5, acting: 4, visuals: 5, soundtrack: 5 };
+
+    let instructions = [
+        ("initialize_token_mint", initialize_token_mint_ix(&program_id, &payer)),
+        ("register_movie", register_movie_ix(&program_id, &payer, MOVIE_TITLE, MOVIE_IMDB_ID).unwrap()),
+        ("add_movie_review", add_movie_review_ix(&program_id, &payer, &movie, ratings, "Space and time.").unwrap()),
+        (
+            "update_movie_review",
+            update_movie_review_ix(&program_id, &payer, &movie_review, ratings, "Space, time and Hans Zimmer.").unwrap(),
+        ),
+        ("add_comment", add_comment_ix(&program_id, &commenter, &movie_review, 0, "Agreed.").unwrap()),
+        ("vote_on_comment", vote_on_comment_ix(&program_id, &payer, &comment, &commenter)),
+        ("stake_on_review", stake_on_review_ix(&program_id, &commenter, &movie_review, LAMPORTS_PER_SOL).unwrap()),
+        ("transfer_review", transfer_review_ix(&program_id, &payer, &movie_review, &commenter).unwrap()),
+        ("accept_review", accept_review_ix(&program_id, &commenter, &movie_review)),
+    ];
+
+    let mut measured = Vec::new();
+
+    for (name, instruction) in &instructions {
+        let units = process(&mollusk, &mut accounts, instruction, name);
+        measured.push((name.to_string(), units));
+    }
+
+    let baseline_path = Path::new(BASELINE_PATH);
+
+    if std::env::var_os("UPDATE_CU_BASELINE").is_some() || !baseline_path.exists() {
+        let baseline: String = measured.iter().map(|(name, units)| format!("{name} {units}\n")).collect();
+        fs::write(baseline_path, baseline).unwrap();
+
+        println!("Wrote compute unit baseline to {BASELINE_PATH}");
+    }
+
+    let baseline = read_baseline(baseline_path);
+    let mut regressions = Vec::new();
+
+    println!("{:<24} {:>10} {:>10}", "instruction", "baseline", "measured");
+
+    for (name, units) in &measured {
+        let Some(&baseline_units) = baseline.get(name) else {
+            regressions.push(format!("{name}: missing from {BASELINE_PATH}"));
+            continue;
+        };
+
+        println!("{name:<24} {baseline_units:>10} {units:>10}");
+
+        if *units > baseline_units + baseline_units * REGRESSION_THRESHOLD_PERCENT / 100 {
+            regressions.push(format!(
+                "{name}: {units} CUs exceeds baseline {baseline_units} by more than {REGRESSION_THRESHOLD_PERCENT}%"
+            ));
+        }
+    }
+
+    if !regressions.is_empty() {
+        panic!("compute unit regressions:\n{}", regressions.join("\n"));
+    }
+}
+
+// InitializeMint only accepts the upgrade authority this names.
+fn program_data_account(upgrade_authority: &Pubkey) -> Account {
+    let mut data = vec![0; UpgradeableLoaderState::size_of_programdata_metadata()];
+    data[..4].copy_from_slice(&3u32.to_le_bytes());
+    data[12] = 1;
+    data[13..].copy_from_slice(upgrade_authority.as_ref());
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: solana_sdk_ids::bpf_loader_upgradeable::id(),
+        ..Account::default()
+    }
+}
+
+// Runs a single instruction against the shared account set and writes the
+// resulting accounts back, so each step sees the state left by the previous one.
+fn process(mollusk: &Mollusk, accounts: &mut HashMap<Pubkey, Account>, instruction: &Instruction, name: &str) -> u64 {
+    let instruction_accounts: Vec<(Pubkey, Account)> = instruction
+        .accounts
+        .iter()
+        .map(|meta| (meta.pubkey, accounts.get(&meta.pubkey).cloned().unwrap_or_default()))
+        .collect();
+
+    let result = mollusk.process_instruction(instruction, &instruction_accounts);
+
+    assert!(!result.program_result.is_err(), "{name} failed: {:?}", result.program_result);
+
+    accounts.extend(result.resulting_accounts);
+
+    result.compute_units_consumed
+}
+
+fn read_baseline(path: &Path) -> HashMap<String, u64> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter_map(|line| {
+            let (name, units) = line.split_once(' ')?;
+            Some((name.to_string(), units.trim().parse().ok()?))
+        })
+        .collect()
+}
@@ -2,7 +2,7 @@ use solana_program::program_error::ProgramError;
 
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReviewError {
     // Error 0
     #[error("Input data exceeds max length")]
@@ -12,11 +12,92 @@ pub enum ReviewError {
     InvalidRating,
     // Error 2
     #[error("Accounts do not match")]
-    IncorrectAccountError
+    IncorrectAccountError,
+    // Error 3
+    #[error("Account is not a registered movie")]
+    InvalidMovieAccount,
+    // Error 4
+    #[error("Review account has not been deleted")]
+    ReviewNotDeleted,
+    // Error 5
+    #[error("Too many comment accounts passed in a single instruction")]
+    TooManyComments,
+    // Error 6
+    #[error("Signer is not the review owner")]
+    NotReviewOwner,
+    // Error 7
+    #[error("Signer is not the pending review owner")]
+    NotPendingOwner,
+    // Error 8
+    #[error("Account must be migrated to the current state version")]
+    OutdatedAccountVersion,
+    // Error 9
+    #[error("Stake amount must be greater than zero")]
+    ZeroStakeAmount,
+    // Error 10
+    #[error("Stake is still within its unstake cooldown")]
+    StakeCooldownActive,
+    // Error 11
+    #[error("Review still has tokens staked on it")]
+    ReviewHasStakes,
+    // Error 12
+    #[error("Commenter must wait before posting another comment")]
+    CommentRateLimited,
+    // Error 13
+    #[error("Commenters cannot vote on their own comments")]
+    SelfVote,
+    // Error 14
+    #[error("Signer is not the program config admin")]
+    NotConfigAdmin,
+    // Error 15
+    #[error("Comment deposit is still within its dispute window")]
+    DisputeWindowActive,
+    // Error 16
+    #[error("Comment has no deposit to refund")]
+    NoDepositToRefund,
+    // Error 17
+    #[error("Review can no longer be edited")]
+    EditWindowExpired,
+    // Error 18
+    #[error("Account version is not one MigrateAccount can upgrade")]
+    UnknownAccountVersion,
+    // Error 19
+    #[error("Signer is not the program's upgrade authority")]
+    NotUpgradeAuthority,
 }
 
 impl From<ReviewError> for ProgramError {
     fn from(e: ReviewError) -> Self {
         ProgramError::Custom(e as u32)
     }
-}
\ No newline at end of file
+}
+
+impl TryFrom<u32> for ReviewError {
+    type Error = ProgramError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(Self::InvalidDataLength),
+            1 => Ok(Self::InvalidRating),
+            2 => Ok(Self::IncorrectAccountError),
+            3 => Ok(Self::InvalidMovieAccount),
+            4 => Ok(Self::ReviewNotDeleted),
+            5 => Ok(Self::TooManyComments),
+            6 => Ok(Self::NotReviewOwner),
+            7 => Ok(Self::NotPendingOwner),
+            8 => Ok(Self::OutdatedAccountVersion),
+            9 => Ok(Self::ZeroStakeAmount),
+            10 => Ok(Self::StakeCooldownActive),
+            11 => Ok(Self::ReviewHasStakes),
+            12 => Ok(Self::CommentRateLimited),
+            13 => Ok(Self::SelfVote),
+            14 => Ok(Self::NotConfigAdmin),
+            15 => Ok(Self::DisputeWindowActive),
+            16 => Ok(Self::NoDepositToRefund),
+            17 => Ok(Self::EditWindowExpired),
+            18 => Ok(Self::UnknownAccountVersion),
+            19 => Ok(Self::NotUpgradeAuthority),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
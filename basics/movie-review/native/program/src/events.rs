@@ -0,0 +1,61 @@
+use solana_program::{
+    entrypoint::ProgramResult,
+    log::sol_log_data,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshSerialize, BorshDeserialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub enum ReviewEvent {
+    ReviewAdded {
+        review: Pubkey,
+        movie: Pubkey,
+        reviewer: Pubkey,
+        rating: u8,
+    },
+    ReviewUpdated {
+        review: Pubkey,
+        owner: Pubkey,
+        rating: u8,
+    },
+    CommentAdded {
+        review: Pubkey,
+        comment: Pubkey,
+        commenter: Pubkey,
+        count: u64,
+    },
+    CommentUpvoted {
+        comment: Pubkey,
+        voter: Pubkey,
+        upvotes: u64,
+    },
+}
+
+impl ReviewEvent {
+    pub fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&borsh::to_vec(self)?]);
+
+        Ok(())
+    }
+}
+
+// sol_log_data shows up in the transaction logs as "Program data: " followed
+// by the base64 of each slice.
+#[cfg(not(target_os = "solana"))]
+impl ReviewEvent {
+    const LOG_PREFIX: &'static str = "Program data: ";
+
+    pub fn from_log(log: &str) -> Option<Self> {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let data = log.strip_prefix(Self::LOG_PREFIX)?;
+        let data = STANDARD.decode(data).ok()?;
+
+        Self::try_from_slice(&data).ok()
+    }
+
+    pub fn from_logs(logs: &[String]) -> Vec<Self> {
+        logs.iter().filter_map(|log| Self::from_log(log)).collect()
+    }
+}
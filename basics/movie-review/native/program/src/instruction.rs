@@ -1,22 +1,39 @@
 use borsh::BorshDeserialize;
 
-use solana_program::program_error::ProgramError;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::state::CategoryRatings;
 
 pub enum MovieInstruction {
     AddMovieReview {
-        title: String,
-        rating: u8,
+        ratings: CategoryRatings,
         description: String,
     },
     UpdateMovieReview {
-        title: String,
-        rating: u8,
+        ratings: CategoryRatings,
         description: String,
     },
     AddComment {
         comment: String,
     },
     InitializeMint,
+    RegisterMovie {
+        title: String,
+        imdb_id: String,
+    },
+    DeleteMovieReview,
+    CloseComments,
+    TransferReview {
+        new_owner: Pubkey,
+    },
+    AcceptReview,
+    MigrateAccount,
+    StakeOnReview {
+        amount: u64,
+    },
+    UnstakeFromReview,
+    VoteOnComment,
+    RefundCommentDeposit,
 }
 
 impl MovieInstruction {
@@ -31,8 +48,7 @@ impl MovieInstruction {
                         .map_err(|_| ProgramError::InvalidInstructionData)?;
 
                     Self::AddMovieReview { 
-                        title: payload.title, 
-                        rating: payload.rating, 
+                        ratings: payload.ratings, 
                         description: payload.description 
                     }
                 },
@@ -41,8 +57,7 @@ impl MovieInstruction {
                         .map_err(|_| ProgramError::InvalidInstructionData)?;
                     
                     Self::UpdateMovieReview { 
-                        title: payload.title, 
-                        rating: payload.rating, 
+                        ratings: payload.ratings, 
                         description: payload.description 
                     }
                 },
@@ -57,6 +72,52 @@ impl MovieInstruction {
                 3 => {
                     Self::InitializeMint
                 },
+                4 => {
+                    let payload = RegisterMoviePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::RegisterMovie { 
+                        title: payload.title, 
+                        imdb_id: payload.imdb_id 
+                    }
+                },
+                5 => {
+                    Self::DeleteMovieReview
+                },
+                6 => {
+                    Self::CloseComments
+                },
+                7 => {
+                    let payload = TransferReviewPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::TransferReview { 
+                        new_owner: payload.new_owner 
+                    }
+                },
+                8 => {
+                    Self::AcceptReview
+                },
+                9 => {
+                    Self::MigrateAccount
+                },
+                10 => {
+                    let payload = StakePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::StakeOnReview { 
+                        amount: payload.amount 
+                    }
+                },
+                11 => {
+                    Self::UnstakeFromReview
+                },
+                12 => {
+                    Self::VoteOnComment
+                },
+                13 => {
+                    Self::RefundCommentDeposit
+                },
                 _ => return Err(ProgramError::InvalidInstructionData)
             }
         )
@@ -65,12 +126,27 @@ impl MovieInstruction {
 
 #[derive(BorshDeserialize)]
 struct MovieReviewPayload {
-    title: String,
-    rating: u8,
+    ratings: CategoryRatings,
     description: String,
 }
 
 #[derive(BorshDeserialize)]
 struct CommentPayload {
     comment: String,
-} 
\ No newline at end of file
+}
+
+#[derive(BorshDeserialize)]
+struct RegisterMoviePayload {
+    title: String,
+    imdb_id: String,
+} 
+
+#[derive(BorshDeserialize)]
+struct TransferReviewPayload {
+    new_owner: Pubkey,
+}
+
+#[derive(BorshDeserialize)]
+struct StakePayload {
+    amount: u64,
+}
\ No newline at end of file
@@ -1,5 +1,7 @@
+#[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 pub mod processor;
 pub mod instruction;
 pub mod state;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod events;
\ No newline at end of file
@@ -1,28 +1,48 @@
 use solana_program::{
     entrypoint::ProgramResult,
     program_error::ProgramError,
-    pubkey::Pubkey,
+    pubkey::{Pubkey, MAX_SEED_LEN},
     account_info::{AccountInfo, next_account_info},
-    sysvar::{Sysvar, rent::Rent},
-    program::invoke_signed,
+    sysvar::{Sysvar, rent::Rent, clock::Clock},
+    program::{invoke, invoke_signed},
     program_pack::IsInitialized,
     borsh1::try_from_slice_unchecked,
     native_token::LAMPORTS_PER_SOL,
     program_pack::Pack,
 };
-use solana_system_interface::instruction::create_account;
+use solana_system_interface::{
+    instruction::{create_account, transfer},
+    program::id as system_program_id,
+};
 use spl_token::{
     id as token_program_id, 
-    instruction::{initialize_mint2, mint_to},
+    instruction::{initialize_mint2, mint_to, transfer_checked},
     state::Mint,
 };
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::{
+    id as associated_token_program_id,
+    get_associated_token_address,
+    instruction::create_associated_token_account_idempotent,
+};
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_sdk_ids::bpf_loader_upgradeable;
 
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 
 use crate::instruction::MovieInstruction;
-use crate::state::{ReviewState, ReviewCommentCounterState, ReviewCommentState};
+use crate::state::{
+    STATE_VERSION,
+    CategoryRatings,
+    ConfigState, MovieState, ReviewState, ReviewStakeState, ReviewCommentCounterState, ReviewCommentState,
+    ReviewCommentPageState, CommenterProfileState, CommentVoteReceiptState, ReviewRewardReceiptState,
+    MovieStateV1, ReviewStateV1, ReviewCommentCounterStateV1, ReviewCommentStateV1,
+};
 use crate::error::ReviewError;
+use crate::events::ReviewEvent;
+
+pub const MAX_COMMENTS_PER_CLOSE: usize = 10;
+pub const REVIEW_REWARD: u64 = 10 * LAMPORTS_PER_SOL;
+pub const COMMENT_REWARD: u64 = 5 * LAMPORTS_PER_SOL;
 
 pub fn process_instruction(
     program_id: &Pubkey,
@@ -32,45 +52,174 @@ pub fn process_instruction(
     let instruction = MovieInstruction::unpack(instruction_data)?;
 
     match instruction {
-        MovieInstruction::AddMovieReview { title, rating, description } => {
-            process_add_movie_review(program_id, accounts, title, rating, description)
+        MovieInstruction::AddMovieReview { ratings, description } => {
+            process_add_movie_review(program_id, accounts, ratings, description)
         },
-        MovieInstruction::UpdateMovieReview { title, rating, description } => {
-            process_update_movie_review(program_id, accounts, title, rating, description)
+        MovieInstruction::UpdateMovieReview { ratings, description } => {
+            process_update_movie_review(program_id, accounts, ratings, description)
         },
         MovieInstruction::AddComment { comment } => {
             process_add_comment(program_id, accounts, comment)
         },
         MovieInstruction::InitializeMint => {
             initialize_token_mint(program_id, accounts)
+        },
+        MovieInstruction::RegisterMovie { title, imdb_id } => {
+            process_register_movie(program_id, accounts, title, imdb_id)
+        },
+        MovieInstruction::DeleteMovieReview => {
+            process_delete_movie_review(program_id, accounts)
+        },
+        MovieInstruction::CloseComments => {
+            process_close_comments(program_id, accounts)
+        },
+        MovieInstruction::TransferReview { new_owner } => {
+            process_transfer_review(program_id, accounts, new_owner)
+        },
+        MovieInstruction::AcceptReview => {
+            process_accept_review(program_id, accounts)
+        },
+        MovieInstruction::MigrateAccount => {
+            process_migrate_account(program_id, accounts)
+        },
+        MovieInstruction::StakeOnReview { amount } => {
+            process_stake_on_review(program_id, accounts, amount)
+        },
+        MovieInstruction::UnstakeFromReview => {
+            process_unstake_from_review(program_id, accounts)
+        },
+        MovieInstruction::VoteOnComment => {
+            process_vote_on_comment(program_id, accounts)
+        },
+        MovieInstruction::RefundCommentDeposit => {
+            process_refund_comment_deposit(program_id, accounts)
         }
     }
 }
 
-pub fn process_add_movie_review(
+pub fn process_register_movie(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     title: String,
-    rating: u8,
+    imdb_id: String,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let movie = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if imdb_id.is_empty() || imdb_id.len() > MAX_SEED_LEN {
+        return Err(ReviewError::InvalidDataLength.into());
+    }
+
+    let (movie_pda, movie_bump) = Pubkey::find_program_address(
+        &[b"movie", imdb_id.as_bytes()], 
+        program_id,
+    );
+
+    if *movie.key != movie_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let movie_account_space = MovieState::space(&imdb_id, &title);
+    if movie_account_space > MovieState::MAX_SPACE {
+        return Err(ReviewError::InvalidDataLength.into());
+    }
+
+    let rent = Rent::get()?;
+    let movie_account_rent = rent.minimum_balance(movie_account_space);
+
+    invoke_signed(
+        &create_account(
+            payer.key, 
+            movie.key, 
+            movie_account_rent, 
+            movie_account_space as u64, 
+            program_id,
+        ), 
+        &[
+            payer.clone(), 
+            movie.clone(), 
+            system_program.clone(),
+        ], 
+        &[
+            &[
+                b"movie", 
+                imdb_id.as_bytes(), 
+                &[movie_bump],
+            ],
+        ],
+    )?;
+
+    let mut movie_account_data =
+        try_from_slice_unchecked::<MovieState>(&movie.data.borrow())?;
+
+    if movie_account_data.is_initialized() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    movie_account_data.discriminator = MovieState::DISCRIMINATOR.to_string();
+    movie_account_data.version = STATE_VERSION;
+    movie_account_data.imdb_id = imdb_id;
+    movie_account_data.title = title;
+    movie_account_data.is_initialized = true;
+
+    movie_account_data.serialize(&mut &mut movie.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_add_movie_review(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ratings: CategoryRatings,
     description: String,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     
     let reviewer = next_account_info(accounts_iter)?;
+    let movie = next_account_info(accounts_iter)?;
     let movie_review = next_account_info(accounts_iter)?;
     let counter = next_account_info(accounts_iter)?;
+    let reward_receipt = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
     let token_mint = next_account_info(accounts_iter)?;
     let mint_auth = next_account_info(accounts_iter)?;
     let user_ata = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
 
     if !reviewer.is_signer {
         return Err(ProgramError::MissingRequiredSignature)
     }
 
+    if movie.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let movie_account_data = 
+        try_from_slice_unchecked::<MovieState>(&movie.data.borrow())?;
+
+    if !movie_account_data.is_initialized() 
+        || movie_account_data.discriminator != MovieState::DISCRIMINATOR 
+    {
+        return Err(ReviewError::InvalidMovieAccount.into());
+    }
+
+    if movie_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    let title = movie_account_data.title;
+
     let (movie_review_pda, movie_review_bump) = Pubkey::find_program_address(
-        &[reviewer.key.as_ref(), title.as_bytes().as_ref()], 
+        &[reviewer.key.as_ref(), movie.key.as_ref()], 
         program_id,
     );
 
@@ -78,10 +227,12 @@ pub fn process_add_movie_review(
         return Err(ProgramError::InvalidSeeds);
     }
 
-    if rating < 1 || rating > 5 {
+    if !ratings.is_valid() {
         return Err(ReviewError::InvalidRating.into());
     }
 
+    let rating = ratings.overall();
+
     let total_len = ReviewState::space(&title, &description);
     if total_len > ReviewState::MAX_SPACE {
         return Err(ReviewError::InvalidDataLength.into());
@@ -107,7 +258,7 @@ pub fn process_add_movie_review(
         &[
             &[
                 reviewer.key.as_ref(), 
-                title.as_bytes().as_ref(),
+                movie.key.as_ref(),
                 &[movie_review_bump],
             ],
         ]
@@ -122,14 +273,28 @@ pub fn process_add_movie_review(
     }
 
     movie_review_account_data.discriminator = ReviewState::DISCRIMINATOR.to_string();
+    movie_review_account_data.version = STATE_VERSION;
     movie_review_account_data.reviewer = *reviewer.key;
+    movie_review_account_data.movie = *movie.key;
+    movie_review_account_data.owner = *reviewer.key;
+    movie_review_account_data.pending_owner = Pubkey::default();
     movie_review_account_data.title = title;
     movie_review_account_data.rating = rating;
+    movie_review_account_data.ratings = ratings;
     movie_review_account_data.description = description;
+    movie_review_account_data.created_at = Clock::get()?.unix_timestamp;
+    movie_review_account_data.updated_at = movie_review_account_data.created_at;
     movie_review_account_data.is_initialized = true;
 
     movie_review_account_data.serialize(&mut &mut movie_review.data.borrow_mut()[..])?;
 
+    ReviewEvent::ReviewAdded {
+        review: *movie_review.key,
+        movie: *movie.key,
+        reviewer: *reviewer.key,
+        rating,
+    }.emit()?;
+
     let counter_rent = rent.minimum_balance(ReviewCommentCounterState::SPACE);
 
     let (counter_pda, counter_bump) = Pubkey::find_program_address(
@@ -170,6 +335,7 @@ pub fn process_add_movie_review(
     }
 
     counter_data.discriminator = ReviewCommentCounterState::DISCRIMINATOR.to_string();
+    counter_data.version = STATE_VERSION;
     counter_data.counter = 0;
     counter_data.is_initialized = true;
 
@@ -196,6 +362,72 @@ pub fn process_add_movie_review(
         return Err(ReviewError::IncorrectAccountError.into());
     }
 
+    if *associated_token_program.key != associated_token_program_id() {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    invoke(
+        &create_associated_token_account_idempotent(
+            reviewer.key, 
+            reviewer.key, 
+            token_mint.key, 
+            token_program.key,
+        ), 
+        &[
+            reviewer.clone(), 
+            user_ata.clone(), 
+            token_mint.clone(), 
+            system_program.clone(), 
+            token_program.clone(),
+        ],
+    )?;
+
+    let (reward_receipt_pda, reward_receipt_bump) = Pubkey::find_program_address(
+        &[b"reward", movie.key.as_ref(), reviewer.key.as_ref()], 
+        program_id,
+    );
+
+    if *reward_receipt.key != reward_receipt_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // A reviewer is rewarded once per movie. The receipt outlives the review,
+    // so deleting it and adding it again doesn't mint a second reward.
+    if !reward_receipt.data_is_empty() {
+        return Ok(());
+    }
+
+    invoke_signed(
+        &create_account(
+            reviewer.key, 
+            reward_receipt.key, 
+            rent.minimum_balance(ReviewRewardReceiptState::SPACE), 
+            ReviewRewardReceiptState::SPACE as u64, 
+            program_id,
+        ), 
+        &[reviewer.clone(), reward_receipt.clone(), system_program.clone()], 
+        &[
+            &[b"reward", movie.key.as_ref(), reviewer.key.as_ref(), &[reward_receipt_bump]],
+        ],
+    )?;
+
+    ReviewRewardReceiptState {
+        discriminator: ReviewRewardReceiptState::DISCRIMINATOR.to_string(),
+        version: STATE_VERSION,
+        is_initialized: true,
+        movie: *movie.key,
+        reviewer: *reviewer.key,
+    }.serialize(&mut &mut reward_receipt.data.borrow_mut()[..])?;
+
+    let mut config_data = load_config(program_id, config)?;
+
+    let reward = config_data.reward(REVIEW_REWARD, Clock::get()?.unix_timestamp);
+
+    config_data.reviews_rewarded = 
+        config_data.reviews_rewarded.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
     invoke_signed(
         &mint_to(
             token_program.key, 
@@ -203,7 +435,7 @@ pub fn process_add_movie_review(
             user_ata.key, 
             mint_auth.key, 
             &[], 
-            10 * LAMPORTS_PER_SOL,
+            reward,
         )?, 
         &[token_mint.clone(), user_ata.clone(), mint_auth.clone()], 
         &[
@@ -217,14 +449,14 @@ pub fn process_add_movie_review(
 pub fn process_update_movie_review(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    title: String,
-    rating: u8,
+    ratings: CategoryRatings,
     description: String
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
     let reviewer = next_account_info(accounts_iter)?;
     let movie_review_account = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
 
     if !reviewer.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
@@ -234,8 +466,22 @@ pub fn process_update_movie_review(
         return Err(ProgramError::InvalidAccountOwner);
     }
 
+    let mut movie_review_account_data = 
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account.data.borrow())?;
+
+    if !movie_review_account_data.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if movie_review_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
     let (pda, _bump_seed) = Pubkey::find_program_address(
-        &[reviewer.key.as_ref(), title.as_bytes().as_ref()], 
+        &[
+            movie_review_account_data.reviewer.as_ref(), 
+            movie_review_account_data.movie.as_ref(),
+        ], 
         program_id,
     );
 
@@ -243,27 +489,46 @@ pub fn process_update_movie_review(
         return Err(ProgramError::InvalidSeeds);
     }
 
-    let mut movie_review_account_data = 
-        try_from_slice_unchecked::<ReviewState>(&movie_review_account.data.borrow())?;
+    if movie_review_account_data.owner != *reviewer.key {
+        return Err(ReviewError::NotReviewOwner.into());
+    }
 
-    if !movie_review_account_data.is_initialized() {
-        return Err(ProgramError::UninitializedAccount);
+    let config_data = load_config(program_id, config)?;
+
+    let editable_until = movie_review_account_data.created_at
+        .checked_add(config_data.review_edit_window_seconds)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let now = Clock::get()?.unix_timestamp;
+
+    if now > editable_until {
+        return Err(ReviewError::EditWindowExpired.into());
     }
 
-    if rating < 1 || rating > 5 {
+    if !ratings.is_valid() {
         return Err(ReviewError::InvalidRating.into());
     }
 
-    let total_len = ReviewState::space(&title, &description);
+    let rating = ratings.overall();
+
+    let total_len = ReviewState::space(&movie_review_account_data.title, &description);
     if total_len > ReviewState::MAX_SPACE {
         return Err(ReviewError::InvalidDataLength.into());
     }
 
     movie_review_account_data.rating = rating;
+    movie_review_account_data.ratings = ratings;
     movie_review_account_data.description = description;
+    movie_review_account_data.updated_at = now;
 
     movie_review_account_data.serialize(&mut &mut movie_review_account.data.borrow_mut()[..])?;
 
+    ReviewEvent::ReviewUpdated {
+        review: *movie_review_account.key,
+        owner: *reviewer.key,
+        rating,
+    }.emit()?;
+
     Ok(())
 }
 
@@ -278,19 +543,53 @@ pub fn process_add_comment(
     let movie_review = next_account_info(accounts_iter)?;
     let counter = next_account_info(accounts_iter)?;
     let comment_account = next_account_info(accounts_iter)?;
+    let commenter_profile = next_account_info(accounts_iter)?;
+    let comment_page = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
     let token_mint = next_account_info(accounts_iter)?;
     let mint_auth = next_account_info(accounts_iter)?;
     let user_ata = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
 
     let mut counter_data = 
         try_from_slice_unchecked::<ReviewCommentCounterState>(&counter.data.borrow())?;
 
+    if counter_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    let config_data = load_config(program_id, config)?;
+
+    let mut commenter_profile_data = load_or_create_commenter_profile(
+        program_id, 
+        commenter, 
+        commenter.key, 
+        commenter_profile, 
+        system_program,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+
+    let next_comment_at = commenter_profile_data.last_comment_at
+        .checked_add(commenter_profile_data.comment_cooldown())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if commenter_profile_data.last_comment_at != 0 && now < next_comment_at {
+        return Err(ReviewError::CommentRateLimited.into());
+    }
+
+    commenter_profile_data.last_comment_at = now;
+
+    commenter_profile_data.serialize(&mut &mut commenter_profile.data.borrow_mut()[..])?;
+
     let comment_account_space = ReviewCommentState::space(&comment);
 
     let rent = Rent::get()?;
-    let comment_account_rent = rent.minimum_balance(comment_account_space);
+    let comment_account_rent = rent.minimum_balance(comment_account_space)
+        .checked_add(config_data.comment_deposit)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
 
     let (comment_pda, comment_pda_bump) = Pubkey::find_program_address(
         &[
@@ -334,14 +633,90 @@ pub fn process_add_comment(
     }
 
     comment_account_data.discriminator = ReviewCommentState::DISCRIMINATOR.to_string();
+    comment_account_data.version = STATE_VERSION;
     comment_account_data.review = *movie_review.key;
     comment_account_data.commenter = *commenter.key;
     comment_account_data.comment = comment;
     comment_account_data.count = counter_data.counter;
+    comment_account_data.upvotes = 0;
+    comment_account_data.deposit = config_data.comment_deposit;
+    comment_account_data.created_at = now;
+    comment_account_data.updated_at = now;
     comment_account_data.is_initialized = true;
 
     comment_account_data.serialize(&mut &mut comment_account.data.borrow_mut()[..])?;
 
+    let page = ReviewCommentPageState::page_for(counter_data.counter);
+
+    let (comment_page_pda, comment_page_bump) = Pubkey::find_program_address(
+        &[
+            movie_review.key.as_ref(),
+            b"page",
+            page.to_be_bytes().as_ref(),
+        ], 
+        program_id,
+    );
+
+    if *comment_page.key != comment_page_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut comment_page_data = if comment_page.data_is_empty() {
+        invoke_signed(
+            &create_account(
+                commenter.key, 
+                comment_page.key, 
+                rent.minimum_balance(ReviewCommentPageState::SPACE), 
+                ReviewCommentPageState::SPACE as u64, 
+                program_id,
+            ), 
+            &[
+                commenter.clone(),
+                comment_page.clone(),
+                system_program.clone(),
+            ], 
+            &[
+                &[
+                    movie_review.key.as_ref(),
+                    b"page",
+                    page.to_be_bytes().as_ref(),
+                    &[comment_page_bump],
+                ]
+            ],
+        )?;
+
+        ReviewCommentPageState {
+            discriminator: ReviewCommentPageState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            review: *movie_review.key,
+            page,
+            payer: *commenter.key,
+            comments: Vec::with_capacity(ReviewCommentPageState::COMMENTS_PER_PAGE),
+        }
+    } else {
+        if comment_page.owner != program_id {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        try_from_slice_unchecked::<ReviewCommentPageState>(&comment_page.data.borrow())?
+    };
+
+    if comment_page_data.comments.len() >= ReviewCommentPageState::COMMENTS_PER_PAGE {
+        return Err(ReviewError::InvalidDataLength.into());
+    }
+
+    comment_page_data.comments.push(*comment_account.key);
+
+    comment_page_data.serialize(&mut &mut comment_page.data.borrow_mut()[..])?;
+
+    ReviewEvent::CommentAdded {
+        review: *movie_review.key,
+        comment: *comment_account.key,
+        commenter: *commenter.key,
+        count: comment_account_data.count,
+    }.emit()?;
+
     counter_data.counter = 
         counter_data.counter.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
         
@@ -368,6 +743,26 @@ pub fn process_add_comment(
         return Err(ReviewError::IncorrectAccountError.into());
     }
 
+    if *associated_token_program.key != associated_token_program_id() {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    invoke(
+        &create_associated_token_account_idempotent(
+            commenter.key, 
+            commenter.key, 
+            token_mint.key, 
+            token_program.key,
+        ), 
+        &[
+            commenter.clone(), 
+            user_ata.clone(), 
+            token_mint.clone(), 
+            system_program.clone(), 
+            token_program.clone(),
+        ],
+    )?;
+
     invoke_signed(
         &mint_to(
             token_program.key, 
@@ -375,7 +770,7 @@ pub fn process_add_comment(
             user_ata.key, 
             mint_auth.key, 
             &[], 
-            5 * LAMPORTS_PER_SOL
+            config_data.reward(COMMENT_REWARD, now),
         )?, 
         &[mint_auth.clone(), user_ata.clone(), token_mint.clone()], 
         &[
@@ -393,10 +788,71 @@ pub fn initialize_token_mint(
     let accounts_iter = &mut accounts.iter();
 
     let initializer = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
     let token_mint = next_account_info(accounts_iter)?;
     let mint_auth = next_account_info(accounts_iter)?;
     let system_program =next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
+    let program_data = next_account_info(accounts_iter)?;
+
+    if !initializer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (config_pda, config_bump) = 
+        Pubkey::find_program_address(&[b"config"], program_id);
+
+    if *config.key != config_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    if config.data_is_empty() {
+        // the config's admin is pinned to the program's upgrade authority, so
+        // nobody can claim it between the deploy and InitializeMint
+        if upgrade_authority(program_id, program_data)? != Some(*initializer.key) {
+            return Err(ReviewError::NotUpgradeAuthority.into());
+        }
+
+        invoke_signed(
+            &create_account(
+                initializer.key, 
+                config.key, 
+                rent.minimum_balance(ConfigState::SPACE), 
+                ConfigState::SPACE as u64, 
+                program_id,
+            ), 
+            &[initializer.clone(), config.clone(), system_program.clone()], 
+            &[
+                &[b"config", &[config_bump]],
+            ],
+        )?;
+
+        ConfigState {
+            discriminator: ConfigState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            admin: *initializer.key,
+            emission_start_at: Clock::get()?.unix_timestamp,
+            halving_period_seconds: ConfigState::DEFAULT_HALVING_PERIOD_SECONDS,
+            reviews_per_halving: ConfigState::DEFAULT_REVIEWS_PER_HALVING,
+            reviews_rewarded: 0,
+            comment_deposit: ConfigState::DEFAULT_COMMENT_DEPOSIT,
+            comment_dispute_window_seconds: ConfigState::DEFAULT_COMMENT_DISPUTE_WINDOW_SECONDS,
+            review_edit_window_seconds: ConfigState::DEFAULT_REVIEW_EDIT_WINDOW_SECONDS,
+        }.serialize(&mut &mut config.data.borrow_mut()[..])?;
+    } else {
+        if config.owner != program_id {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let config_data = try_from_slice_unchecked::<ConfigState>(&config.data.borrow())?;
+
+        if config_data.admin != *initializer.key {
+            return Err(ReviewError::NotConfigAdmin.into());
+        }
+    }
 
     let (mint_pda, mint_bump) = 
         Pubkey::find_program_address(&[b"token_mint"], program_id);
@@ -415,7 +871,9 @@ pub fn initialize_token_mint(
         return Err(ReviewError::IncorrectAccountError.into());
     }
 
-    let rent = Rent::get()?;
+    if !token_mint.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
 
     let mint_rent = rent.minimum_balance(Mint::LEN);
 
@@ -448,4 +906,916 @@ pub fn initialize_token_mint(
     )?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+// The upgrade authority recorded in the program's ProgramData account, None
+// once the program is immutable. The account is bincode: a u32 variant (3 for
+// ProgramData), the u64 slot of the last deploy, then an Option<Pubkey>.
+fn upgrade_authority(program_id: &Pubkey, program_data: &AccountInfo) -> Result<Option<Pubkey>, ProgramError> {
+    if *program_data.key != get_program_data_address(program_id) || *program_data.owner != bpf_loader_upgradeable::id() {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    let data = program_data.data.borrow();
+
+    if data.len() < UpgradeableLoaderState::size_of_programdata_metadata() || data[..4] != 3u32.to_le_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    match data[12] {
+        0 => Ok(None),
+        _ => Ok(Some(Pubkey::try_from(&data[13..45]).map_err(|_| ProgramError::InvalidAccountData)?)),
+    }
+}
+
+pub fn process_delete_movie_review(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let reviewer = next_account_info(accounts_iter)?;
+    let movie_review_account = next_account_info(accounts_iter)?;
+    let counter = next_account_info(accounts_iter)?;
+    // followed by a (page, payer) pair for each of the review's comment pages
+
+    if !reviewer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if movie_review_account.owner != program_id || counter.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let movie_review_account_data = 
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account.data.borrow())?;
+
+    if !movie_review_account_data.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if movie_review_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    let (pda, _bump_seed) = Pubkey::find_program_address(
+        &[
+            movie_review_account_data.reviewer.as_ref(), 
+            movie_review_account_data.movie.as_ref(),
+        ], 
+        program_id,
+    );
+
+    if *movie_review_account.key != pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if movie_review_account_data.owner != *reviewer.key {
+        return Err(ReviewError::NotReviewOwner.into());
+    }
+
+    let (counter_pda, _counter_bump) = Pubkey::find_program_address(
+        &[movie_review_account.key.as_ref(), b"counter"], 
+        program_id,
+    );
+
+    if *counter.key != counter_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if movie_review_account_data.staked_amount > 0 {
+        return Err(ReviewError::ReviewHasStakes.into());
+    }
+
+    let counter_data =
+        try_from_slice_unchecked::<ReviewCommentCounterState>(&counter.data.borrow())?;
+
+    if counter_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    // The pages share the review's address, so one left behind would be picked
+    // up by a review added again later. They all go with the review, each
+    // refunded to the commenter who paid for it.
+    let page_count = counter_data.counter.div_ceil(ReviewCommentPageState::COMMENTS_PER_PAGE as u64);
+
+    for page in 0..page_count {
+        let comment_page = next_account_info(accounts_iter)?;
+        let page_payer = next_account_info(accounts_iter)?;
+
+        let (comment_page_pda, _comment_page_bump) = Pubkey::find_program_address(
+            &[
+                movie_review_account.key.as_ref(),
+                b"page",
+                page.to_be_bytes().as_ref(),
+            ], 
+            program_id,
+        );
+
+        if *comment_page.key != comment_page_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if comment_page.owner != program_id {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let comment_page_data =
+            try_from_slice_unchecked::<ReviewCommentPageState>(&comment_page.data.borrow())?;
+
+        if comment_page_data.payer != *page_payer.key {
+            return Err(ReviewError::IncorrectAccountError.into());
+        }
+
+        close_account(comment_page, page_payer)?;
+    }
+
+    close_account(movie_review_account, reviewer)?;
+    close_account(counter, reviewer)?;
+
+    Ok(())
+}
+
+pub fn process_transfer_review(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_owner: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let movie_review_account = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if movie_review_account.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut movie_review_account_data = 
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account.data.borrow())?;
+
+    if !movie_review_account_data.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if movie_review_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    if movie_review_account_data.owner != *owner.key {
+        return Err(ReviewError::NotReviewOwner.into());
+    }
+
+    // Transferring to Pubkey::default() cancels a pending transfer.
+    movie_review_account_data.pending_owner = new_owner;
+
+    movie_review_account_data.serialize(&mut &mut movie_review_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_accept_review(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let new_owner = next_account_info(accounts_iter)?;
+    let movie_review_account = next_account_info(accounts_iter)?;
+
+    if !new_owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if movie_review_account.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut movie_review_account_data = 
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account.data.borrow())?;
+
+    if !movie_review_account_data.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if movie_review_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    if movie_review_account_data.pending_owner == Pubkey::default() 
+        || movie_review_account_data.pending_owner != *new_owner.key 
+    {
+        return Err(ReviewError::NotPendingOwner.into());
+    }
+
+    movie_review_account_data.owner = *new_owner.key;
+    movie_review_account_data.pending_owner = Pubkey::default();
+
+    movie_review_account_data.serialize(&mut &mut movie_review_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Upvotes are one per voter per comment, enforced by the vote receipt PDA:
+// a second vote fails when the system program refuses to create it again.
+pub fn process_vote_on_comment(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let voter = next_account_info(accounts_iter)?;
+    let comment_account = next_account_info(accounts_iter)?;
+    let vote_receipt = next_account_info(accounts_iter)?;
+    let commenter_profile = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !voter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if comment_account.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut comment_account_data =
+        try_from_slice_unchecked::<ReviewCommentState>(&comment_account.data.borrow())?;
+
+    if !comment_account_data.is_initialized() 
+        || comment_account_data.discriminator != ReviewCommentState::DISCRIMINATOR 
+    {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if comment_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    if comment_account_data.commenter == *voter.key {
+        return Err(ReviewError::SelfVote.into());
+    }
+
+    let (vote_receipt_pda, vote_receipt_bump) = Pubkey::find_program_address(
+        &[b"vote", comment_account.key.as_ref(), voter.key.as_ref()], 
+        program_id,
+    );
+
+    if *vote_receipt.key != vote_receipt_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &create_account(
+            voter.key, 
+            vote_receipt.key, 
+            rent.minimum_balance(CommentVoteReceiptState::SPACE), 
+            CommentVoteReceiptState::SPACE as u64, 
+            program_id,
+        ), 
+        &[voter.clone(), vote_receipt.clone(), system_program.clone()], 
+        &[
+            &[b"vote", comment_account.key.as_ref(), voter.key.as_ref(), &[vote_receipt_bump]],
+        ],
+    )?;
+
+    CommentVoteReceiptState {
+        discriminator: CommentVoteReceiptState::DISCRIMINATOR.to_string(),
+        version: STATE_VERSION,
+        is_initialized: true,
+        comment: *comment_account.key,
+        voter: *voter.key,
+    }.serialize(&mut &mut vote_receipt.data.borrow_mut()[..])?;
+
+    comment_account_data.upvotes = 
+        comment_account_data.upvotes.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    comment_account_data.serialize(&mut &mut comment_account.data.borrow_mut()[..])?;
+
+    let mut commenter_profile_data = load_or_create_commenter_profile(
+        program_id, 
+        voter, 
+        &comment_account_data.commenter, 
+        commenter_profile, 
+        system_program,
+    )?;
+
+    commenter_profile_data.reputation = 
+        commenter_profile_data.reputation.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    commenter_profile_data.serialize(&mut &mut commenter_profile.data.borrow_mut()[..])?;
+
+    ReviewEvent::CommentUpvoted {
+        comment: *comment_account.key,
+        voter: *voter.key,
+        upvotes: comment_account_data.upvotes,
+    }.emit()?;
+
+    Ok(())
+}
+
+pub fn process_refund_comment_deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let commenter = next_account_info(accounts_iter)?;
+    let comment_account = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+
+    if !commenter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if comment_account.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut comment_account_data =
+        try_from_slice_unchecked::<ReviewCommentState>(&comment_account.data.borrow())?;
+
+    if !comment_account_data.is_initialized() 
+        || comment_account_data.discriminator != ReviewCommentState::DISCRIMINATOR 
+    {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if comment_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    if comment_account_data.commenter != *commenter.key {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if comment_account_data.deposit == 0 {
+        return Err(ReviewError::NoDepositToRefund.into());
+    }
+
+    let config_data = load_config(program_id, config)?;
+
+    let refundable_at = comment_account_data.created_at
+        .checked_add(config_data.comment_dispute_window_seconds)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if Clock::get()?.unix_timestamp < refundable_at {
+        return Err(ReviewError::DisputeWindowActive.into());
+    }
+
+    let deposit = comment_account_data.deposit;
+
+    comment_account_data.deposit = 0;
+
+    comment_account_data.serialize(&mut &mut comment_account.data.borrow_mut()[..])?;
+
+    **comment_account.try_borrow_mut_lamports()? = comment_account.lamports()
+        .checked_sub(deposit)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **commenter.try_borrow_mut_lamports()? = commenter.lamports()
+        .checked_add(deposit)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+// Comments outlive their review: deleting a review leaves every comment PDA
+// behind, and since comment seeds restart from zero a re-created review would
+// collide with them. This crank lets anyone sweep them up in batches, with
+// the rent going back to whoever paid for each comment.
+//
+// Remaining accounts are (comment, commenter) pairs.
+pub fn process_close_comments(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let movie_review = next_account_info(accounts_iter)?;
+
+    if movie_review.owner == program_id && !movie_review.data_is_empty() {
+        return Err(ReviewError::ReviewNotDeleted.into());
+    }
+
+    let comment_pairs = accounts_iter.as_slice().chunks_exact(2);
+
+    if !comment_pairs.remainder().is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    if comment_pairs.len() > MAX_COMMENTS_PER_CLOSE {
+        return Err(ReviewError::TooManyComments.into());
+    }
+
+    for pair in comment_pairs {
+        let comment_account = &pair[0];
+        let commenter = &pair[1];
+
+        if comment_account.owner != program_id {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let comment_account_data =
+            try_from_slice_unchecked::<ReviewCommentState>(&comment_account.data.borrow())?;
+
+        if !comment_account_data.is_initialized() 
+            || comment_account_data.discriminator != ReviewCommentState::DISCRIMINATOR 
+        {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        if comment_account_data.version != STATE_VERSION {
+            return Err(ReviewError::OutdatedAccountVersion.into());
+        }
+
+        if comment_account_data.review != *movie_review.key {
+            return Err(ReviewError::IncorrectAccountError.into());
+        }
+
+        if comment_account_data.commenter != *commenter.key {
+            return Err(ReviewError::IncorrectAccountError.into());
+        }
+
+        close_account(comment_account, commenter)?;
+    }
+
+    Ok(())
+}
+
+// Staked reward tokens sit in an escrow ATA owned by a per-review PDA, so
+// only this program can move them back out. Each staker gets a stake record
+// PDA holding their share, and the review keeps the running total.
+pub fn process_stake_on_review(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let staker = next_account_info(accounts_iter)?;
+    let movie_review = next_account_info(accounts_iter)?;
+    let stake_account = next_account_info(accounts_iter)?;
+    let escrow_auth = next_account_info(accounts_iter)?;
+    let escrow_ata = next_account_info(accounts_iter)?;
+    let staker_ata = next_account_info(accounts_iter)?;
+    let token_mint = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    if !staker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(ReviewError::ZeroStakeAmount.into());
+    }
+
+    if movie_review.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut movie_review_account_data = 
+        try_from_slice_unchecked::<ReviewState>(&movie_review.data.borrow())?;
+
+    if !movie_review_account_data.is_initialized() 
+        || movie_review_account_data.discriminator != ReviewState::DISCRIMINATOR 
+    {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if movie_review_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    let (mint_pda, _mint_bump) = 
+        Pubkey::find_program_address(&[b"token_mint"], program_id);
+    let (escrow_auth_pda, _escrow_auth_bump) = 
+        Pubkey::find_program_address(&[b"escrow", movie_review.key.as_ref()], program_id);
+
+    if *token_mint.key != mint_pda {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *escrow_auth.key != escrow_auth_pda {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *escrow_ata.key != get_associated_token_address(escrow_auth.key, token_mint.key) {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *staker_ata.key != get_associated_token_address(staker.key, token_mint.key) {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *associated_token_program.key != associated_token_program_id() {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    let (stake_pda, stake_bump) = Pubkey::find_program_address(
+        &[b"stake", movie_review.key.as_ref(), staker.key.as_ref()], 
+        program_id,
+    );
+
+    if *stake_account.key != stake_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut stake_account_data = if stake_account.data_is_empty() {
+        let rent = Rent::get()?;
+
+        invoke_signed(
+            &create_account(
+                staker.key, 
+                stake_account.key, 
+                rent.minimum_balance(ReviewStakeState::SPACE), 
+                ReviewStakeState::SPACE as u64, 
+                program_id,
+            ), 
+            &[staker.clone(), stake_account.clone(), system_program.clone()], 
+            &[
+                &[b"stake", movie_review.key.as_ref(), staker.key.as_ref(), &[stake_bump]],
+            ],
+        )?;
+
+        ReviewStakeState {
+            discriminator: ReviewStakeState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            review: *movie_review.key,
+            staker: *staker.key,
+            amount: 0,
+            last_staked_at: 0,
+        }
+    } else {
+        if stake_account.owner != program_id {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let stake_account_data = 
+            try_from_slice_unchecked::<ReviewStakeState>(&stake_account.data.borrow())?;
+
+        if stake_account_data.version != STATE_VERSION {
+            return Err(ReviewError::OutdatedAccountVersion.into());
+        }
+
+        stake_account_data
+    };
+
+    invoke(
+        &create_associated_token_account_idempotent(
+            staker.key, 
+            escrow_auth.key, 
+            token_mint.key, 
+            token_program.key,
+        ), 
+        &[
+            staker.clone(), 
+            escrow_ata.clone(), 
+            escrow_auth.clone(), 
+            token_mint.clone(), 
+            system_program.clone(), 
+            token_program.clone(),
+        ],
+    )?;
+
+    let mint_data = Mint::unpack(&token_mint.data.borrow())?;
+
+    invoke(
+        &transfer_checked(
+            token_program.key, 
+            staker_ata.key, 
+            token_mint.key, 
+            escrow_ata.key, 
+            staker.key, 
+            &[], 
+            amount, 
+            mint_data.decimals,
+        )?, 
+        &[staker_ata.clone(), token_mint.clone(), escrow_ata.clone(), staker.clone()],
+    )?;
+
+    stake_account_data.amount = 
+        stake_account_data.amount.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    stake_account_data.last_staked_at = Clock::get()?.unix_timestamp;
+
+    stake_account_data.serialize(&mut &mut stake_account.data.borrow_mut()[..])?;
+
+    movie_review_account_data.staked_amount = movie_review_account_data.staked_amount
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    movie_review_account_data.serialize(&mut &mut movie_review.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Unstaking returns the whole stake and closes the stake record, but only
+// once UNSTAKE_COOLDOWN_SECONDS have passed since the last top-up, so a
+// boost can't be flashed on and off around a ranking snapshot.
+pub fn process_unstake_from_review(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let staker = next_account_info(accounts_iter)?;
+    let movie_review = next_account_info(accounts_iter)?;
+    let stake_account = next_account_info(accounts_iter)?;
+    let escrow_auth = next_account_info(accounts_iter)?;
+    let escrow_ata = next_account_info(accounts_iter)?;
+    let staker_ata = next_account_info(accounts_iter)?;
+    let token_mint = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !staker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if movie_review.owner != program_id || stake_account.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (stake_pda, _stake_bump) = Pubkey::find_program_address(
+        &[b"stake", movie_review.key.as_ref(), staker.key.as_ref()], 
+        program_id,
+    );
+
+    if *stake_account.key != stake_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let stake_account_data = 
+        try_from_slice_unchecked::<ReviewStakeState>(&stake_account.data.borrow())?;
+
+    if !stake_account_data.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if stake_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    let unlocks_at = stake_account_data.last_staked_at
+        .checked_add(ReviewStakeState::UNSTAKE_COOLDOWN_SECONDS)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if Clock::get()?.unix_timestamp < unlocks_at {
+        return Err(ReviewError::StakeCooldownActive.into());
+    }
+
+    let mut movie_review_account_data = 
+        try_from_slice_unchecked::<ReviewState>(&movie_review.data.borrow())?;
+
+    if movie_review_account_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    let (mint_pda, _mint_bump) = 
+        Pubkey::find_program_address(&[b"token_mint"], program_id);
+    let (escrow_auth_pda, escrow_auth_bump) = 
+        Pubkey::find_program_address(&[b"escrow", movie_review.key.as_ref()], program_id);
+
+    if *token_mint.key != mint_pda {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *escrow_auth.key != escrow_auth_pda {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *escrow_ata.key != get_associated_token_address(escrow_auth.key, token_mint.key) {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *staker_ata.key != get_associated_token_address(staker.key, token_mint.key) {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ReviewError::IncorrectAccountError.into());
+    }
+
+    let mint_data = Mint::unpack(&token_mint.data.borrow())?;
+
+    invoke_signed(
+        &transfer_checked(
+            token_program.key, 
+            escrow_ata.key, 
+            token_mint.key, 
+            staker_ata.key, 
+            escrow_auth.key, 
+            &[], 
+            stake_account_data.amount, 
+            mint_data.decimals,
+        )?, 
+        &[escrow_ata.clone(), token_mint.clone(), staker_ata.clone(), escrow_auth.clone()],
+        &[
+            &[b"escrow", movie_review.key.as_ref(), &[escrow_auth_bump]],
+        ],
+    )?;
+
+    movie_review_account_data.staked_amount = movie_review_account_data.staked_amount
+        .checked_sub(stake_account_data.amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    movie_review_account_data.serialize(&mut &mut movie_review.data.borrow_mut()[..])?;
+
+    close_account(stake_account, staker)?;
+
+    Ok(())
+}
+
+// Upgrades a v1 account to the current layout in place. Anyone can run it,
+// since the contents are carried over unchanged; the payer covers the rent
+// for any bytes the account grows by. Accounts already on the current version
+// are left untouched.
+pub fn process_migrate_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if account.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (discriminator, version) = {
+        let data = account.data.borrow();
+        let mut data_slice: &[u8] = &data;
+
+        let discriminator = String::deserialize(&mut data_slice)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let version = *data_slice.first().ok_or(ProgramError::InvalidAccountData)?;
+
+        (discriminator, version)
+    };
+
+    match version {
+        STATE_VERSION => return Ok(()),
+        1 => {},
+        _ => return Err(ReviewError::UnknownAccountVersion.into()),
+    }
+
+    let migrated_data = match discriminator.as_str() {
+        MovieState::DISCRIMINATOR => borsh::to_vec(&MovieState::from(
+            try_from_slice_unchecked::<MovieStateV1>(&account.data.borrow())?
+        ))?,
+        ReviewState::DISCRIMINATOR => {
+            let mut movie_review_account_data = ReviewState::from(
+                try_from_slice_unchecked::<ReviewStateV1>(&account.data.borrow())?
+            );
+
+            // v1 reviews never recorded when they were written, so their edit
+            // window starts at migration instead.
+            movie_review_account_data.created_at = Clock::get()?.unix_timestamp;
+            movie_review_account_data.updated_at = movie_review_account_data.created_at;
+
+            borsh::to_vec(&movie_review_account_data)?
+        },
+        ReviewCommentCounterState::DISCRIMINATOR => borsh::to_vec(&ReviewCommentCounterState::from(
+            try_from_slice_unchecked::<ReviewCommentCounterStateV1>(&account.data.borrow())?
+        ))?,
+        ReviewCommentState::DISCRIMINATOR => borsh::to_vec(&ReviewCommentState::from(
+            try_from_slice_unchecked::<ReviewCommentStateV1>(&account.data.borrow())?
+        ))?,
+        _ => return Err(ProgramError::InvalidAccountData),
+    };
+
+    if migrated_data.len() > account.data_len() {
+        let rent = Rent::get()?;
+        let rent_top_up = rent
+            .minimum_balance(migrated_data.len())
+            .saturating_sub(account.lamports());
+
+        if rent_top_up > 0 {
+            invoke(
+                &transfer(payer.key, account.key, rent_top_up),
+                &[payer.clone(), account.clone(), system_program.clone()],
+            )?;
+        }
+
+        account.resize(migrated_data.len())?;
+    }
+
+    account.data.borrow_mut()[..migrated_data.len()].copy_from_slice(&migrated_data);
+
+    Ok(())
+}
+
+fn load_config(program_id: &Pubkey, config: &AccountInfo) -> Result<ConfigState, ProgramError> {
+    let (config_pda, _config_bump) = 
+        Pubkey::find_program_address(&[b"config"], program_id);
+
+    if *config.key != config_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if config.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let config_data = try_from_slice_unchecked::<ConfigState>(&config.data.borrow())?;
+
+    if !config_data.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if config_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    Ok(config_data)
+}
+
+// Profiles are created on first use by whoever touches them first, so
+// commenters from before profiles existed pick one up on their next comment
+// or upvote.
+fn load_or_create_commenter_profile<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    commenter: &Pubkey,
+    commenter_profile: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> Result<CommenterProfileState, ProgramError> {
+    let (commenter_profile_pda, commenter_profile_bump) = Pubkey::find_program_address(
+        &[b"profile", commenter.as_ref()], 
+        program_id,
+    );
+
+    if *commenter_profile.key != commenter_profile_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if commenter_profile.data_is_empty() {
+        let rent = Rent::get()?;
+
+        invoke_signed(
+            &create_account(
+                payer.key, 
+                commenter_profile.key, 
+                rent.minimum_balance(CommenterProfileState::SPACE), 
+                CommenterProfileState::SPACE as u64, 
+                program_id,
+            ), 
+            &[payer.clone(), commenter_profile.clone(), system_program.clone()], 
+            &[
+                &[b"profile", commenter.as_ref(), &[commenter_profile_bump]],
+            ],
+        )?;
+
+        return Ok(CommenterProfileState {
+            discriminator: CommenterProfileState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            commenter: *commenter,
+            reputation: 0,
+            last_comment_at: 0,
+        });
+    }
+
+    if commenter_profile.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let commenter_profile_data = 
+        try_from_slice_unchecked::<CommenterProfileState>(&commenter_profile.data.borrow())?;
+
+    if commenter_profile_data.version != STATE_VERSION {
+        return Err(ReviewError::OutdatedAccountVersion.into());
+    }
+
+    Ok(commenter_profile_data)
+}
+
+fn close_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let account_lamports = account.lamports();
+
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(account_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    account.assign(&system_program_id());
+    account.resize(0)?;
+
+    Ok(())
+}
@@ -5,73 +5,335 @@ use solana_program::{
 
 use borsh::{BorshSerialize, BorshDeserialize};
 
+// Every account carries a version byte right after its discriminator. The
+// unversioned v1 layouts kept `is_initialized` in that slot, so a 0 or 1 there
+// marks a v1 account that still needs to go through MigrateAccount.
+pub const STATE_VERSION: u8 = 2;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct CategoryRatings {
+    pub plot: u8,
+    pub acting: u8,
+    pub visuals: u8,
+    pub soundtrack: u8,
+}
+
+// Program-wide settings. The first caller of InitializeMint creates this and
+// is recorded as the admin.
+//
+// Rewards halve once every `halving_period_seconds` since `emission_start_at`
+// and once every `reviews_per_halving` rewarded reviews, whichever adds up to
+// more halvings.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ConfigState {
+    pub discriminator: String,
+    pub version: u8,
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    pub emission_start_at: i64,
+    pub halving_period_seconds: i64,
+    pub reviews_per_halving: u64,
+    pub reviews_rewarded: u64,
+    pub comment_deposit: u64,
+    pub comment_dispute_window_seconds: i64,
+    pub review_edit_window_seconds: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct MovieState {
+    pub discriminator: String,
+    pub version: u8,
+    pub is_initialized: bool,
+    pub imdb_id: String,
+    pub title: String,
+}
+
+// Fixed-size fields come before the strings so getProgramAccounts memcmp
+// filters can target them at constant offsets.
+//
+// `reviewer` is the seed the PDA was derived from and never changes. `owner`
+// is who currently controls the review; it starts out as the reviewer and
+// moves through `pending_owner` (Pubkey::default() when no transfer is in
+// flight) once the new owner accepts.
+//
+// Reviews are seeded by (reviewer, movie PDA) rather than (reviewer, title).
+// Accounts created under the old title seeds are left as they are: they can
+// still be read, but updates and comments go through the new seeds, so those
+// reviews have to be re-created against a registered movie.
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct ReviewState {
     pub discriminator: String,
+    pub version: u8,
     pub is_initialized: bool,
     pub reviewer: Pubkey,
+    pub movie: Pubkey,
     pub rating: u8,
+    pub ratings: CategoryRatings,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub staked_amount: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
     pub title: String,
     pub description: String,
 }
 
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ReviewStakeState {
+    pub discriminator: String,
+    pub version: u8,
+    pub is_initialized: bool,
+    pub review: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub last_staked_at: i64,
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct ReviewCommentCounterState {
     pub discriminator: String,
+    pub version: u8,
     pub is_initialized: bool,
     pub counter: u64,
 }
 
+// Comment accounts hold `deposit` lamports on top of their rent. The deposit
+// goes back to the commenter when the comment is closed, or earlier through
+// RefundCommentDeposit once the config's dispute window has passed.
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct ReviewCommentState {
     pub discriminator: String,
+    pub version: u8,
     pub is_initialized: bool,
     pub review: Pubkey,
     pub commenter: Pubkey,
-    pub comment: String,
     pub count: u64,
+    pub upvotes: u64,
+    pub deposit: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub comment: String,
+}
+
+// Pages list a review's comments in order, COMMENTS_PER_PAGE at a time, so
+// clients can read them by address instead of scanning program accounts.
+// Comments posted before pages existed are not back-filled.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ReviewCommentPageState {
+    pub discriminator: String,
+    pub version: u8,
+    pub is_initialized: bool,
+    pub review: Pubkey,
+    pub page: u64,
+    pub payer: Pubkey,
+    pub comments: Vec<Pubkey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CommenterProfileState {
+    pub discriminator: String,
+    pub version: u8,
+    pub is_initialized: bool,
+    pub commenter: Pubkey,
+    pub reputation: u64,
+    pub last_comment_at: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CommentVoteReceiptState {
+    pub discriminator: String,
+    pub version: u8,
+    pub is_initialized: bool,
+    pub comment: Pubkey,
+    pub voter: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ReviewRewardReceiptState {
+    pub discriminator: String,
+    pub version: u8,
+    pub is_initialized: bool,
+    pub movie: Pubkey,
+    pub reviewer: Pubkey,
+}
+
+impl CategoryRatings {
+    pub const SPACE: usize = 4;
+
+    pub fn is_valid(&self) -> bool {
+        [self.plot, self.acting, self.visuals, self.soundtrack]
+            .iter()
+            .all(|rating| (1..=5).contains(rating))
+    }
+
+    // The overall rating is the average of the categories, rounded half up.
+    pub fn overall(&self) -> u8 {
+        let total = self.plot as u16 + self.acting as u16 + self.visuals as u16 + self.soundtrack as u16;
+
+        ((total + 2) / 4) as u8
+    }
+}
+
+impl ConfigState {
+    pub const DISCRIMINATOR: &'static str = "config";
+    pub const SPACE: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 1 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+    pub const DEFAULT_HALVING_PERIOD_SECONDS: i64 = 365 * 24 * 60 * 60;
+    pub const DEFAULT_REVIEWS_PER_HALVING: u64 = 10_000;
+    pub const DEFAULT_COMMENT_DEPOSIT: u64 = 10_000_000;
+    pub const DEFAULT_COMMENT_DISPUTE_WINDOW_SECONDS: i64 = 7 * 24 * 60 * 60;
+    pub const DEFAULT_REVIEW_EDIT_WINDOW_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+    pub fn halvings(&self, now: i64) -> u64 {
+        let by_time = now.saturating_sub(self.emission_start_at).max(0) / self.halving_period_seconds;
+        let by_reviews = self.reviews_rewarded / self.reviews_per_halving;
+
+        (by_time as u64).saturating_add(by_reviews)
+    }
+
+    pub fn reward(&self, base_reward: u64, now: i64) -> u64 {
+        base_reward.checked_shr(self.halvings(now).min(u32::MAX as u64) as u32).unwrap_or(0)
+    }
+}
+
+impl MovieState {
+    pub const DISCRIMINATOR: &'static str = "movie";
+    pub const MAX_SPACE: usize = 500;
+
+    pub fn space(imdb_id: &str, title: &str) -> usize {
+        (4 + Self::DISCRIMINATOR.len())
+        + 1
+        + 1
+        + (4 + imdb_id.len())
+        + (4 + title.len())
+    }
 }
 
 impl ReviewState {
     pub const DISCRIMINATOR: &'static str = "review";
     pub const MAX_SPACE: usize = 1000;
+    pub const REVIEWER_OFFSET: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 1;
+    pub const MOVIE_OFFSET: usize = Self::REVIEWER_OFFSET + 32;
+    pub const RATING_OFFSET: usize = Self::MOVIE_OFFSET + 32;
+    pub const RATINGS_OFFSET: usize = Self::RATING_OFFSET + 1;
+    pub const OWNER_OFFSET: usize = Self::RATINGS_OFFSET + CategoryRatings::SPACE;
+    pub const PENDING_OWNER_OFFSET: usize = Self::OWNER_OFFSET + 32;
+    pub const STAKED_AMOUNT_OFFSET: usize = Self::PENDING_OWNER_OFFSET + 32;
+    pub const CREATED_AT_OFFSET: usize = Self::STAKED_AMOUNT_OFFSET + 8;
+    pub const UPDATED_AT_OFFSET: usize = Self::CREATED_AT_OFFSET + 8;
 
     pub fn space(title: &str, description: &str) -> usize {
         (4 + Self::DISCRIMINATOR.len())
         + 1
+        + 1
+        + 32
         + 32
         + 1
+        + CategoryRatings::SPACE
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
         + (4 + title.len())
         + (4 + description.len())
     }
 }
 
+impl ReviewStakeState {
+    pub const DISCRIMINATOR: &'static str = "stake";
+    pub const SPACE: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 1 + 32 + 32 + 8 + 8;
+    pub const UNSTAKE_COOLDOWN_SECONDS: i64 = 3 * 24 * 60 * 60;
+}
+
 impl ReviewCommentCounterState {
     pub const DISCRIMINATOR: &'static str = "counter";
-    pub const SPACE: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 8;
+    pub const SPACE: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 1 + 8;
 }
 
 impl ReviewCommentState {
     pub const DISCRIMINATOR: &'static str = "comment";
+    pub const REVIEW_OFFSET: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 1;
+    pub const COMMENTER_OFFSET: usize = Self::REVIEW_OFFSET + 32;
 
     pub fn space(comment: &str) -> usize {
         (4 + Self::DISCRIMINATOR.len())
         + 1
+        + 1
         + 32
         + 32
-        + (4 + comment.len())
         + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + (4 + comment.len())
+    }
+}
+
+impl ReviewCommentPageState {
+    pub const DISCRIMINATOR: &'static str = "page";
+    pub const COMMENTS_PER_PAGE: usize = 32;
+    pub const SPACE: usize = 
+        (4 + Self::DISCRIMINATOR.len()) + 1 + 1 + 32 + 8 + 32 + (4 + 32 * Self::COMMENTS_PER_PAGE);
+
+    pub fn page_for(count: u64) -> u64 {
+        count / Self::COMMENTS_PER_PAGE as u64
     }
 }
 
+impl CommenterProfileState {
+    pub const DISCRIMINATOR: &'static str = "profile";
+    pub const SPACE: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 1 + 32 + 8 + 8;
+    pub const BASE_COMMENT_COOLDOWN_SECONDS: i64 = 60;
+    pub const COOLDOWN_REDUCTION_PER_REPUTATION: i64 = 5;
+
+    // Every upvote a commenter has received takes a few seconds off the wait
+    // between their comments, down to no wait at all.
+    pub fn comment_cooldown(&self) -> i64 {
+        let max_useful_reputation = 
+            Self::BASE_COMMENT_COOLDOWN_SECONDS / Self::COOLDOWN_REDUCTION_PER_REPUTATION;
+        let reputation = self.reputation.min(max_useful_reputation as u64) as i64;
+
+        Self::BASE_COMMENT_COOLDOWN_SECONDS - reputation * Self::COOLDOWN_REDUCTION_PER_REPUTATION
+    }
+}
+
+impl CommentVoteReceiptState {
+    pub const DISCRIMINATOR: &'static str = "vote";
+    pub const SPACE: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 1 + 32 + 32;
+}
+
+impl ReviewRewardReceiptState {
+    pub const DISCRIMINATOR: &'static str = "reward";
+    pub const SPACE: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 1 + 32 + 32;
+}
+
 impl Sealed for ReviewState {}
 
+impl IsInitialized for ConfigState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for MovieState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
 impl IsInitialized for ReviewState {
     fn is_initialized(&self) -> bool {
         self.is_initialized
     }
 }
 
+impl IsInitialized for ReviewStakeState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
 impl IsInitialized for ReviewCommentCounterState {
     fn is_initialized(&self) -> bool {
         self.is_initialized
@@ -83,3 +345,132 @@ impl IsInitialized for ReviewCommentState {
         self.is_initialized
     }
 }
+
+impl IsInitialized for ReviewCommentPageState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for CommenterProfileState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for CommentVoteReceiptState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for ReviewRewardReceiptState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct MovieStateV1 {
+    pub discriminator: String,
+    pub is_initialized: bool,
+    pub imdb_id: String,
+    pub title: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ReviewStateV1 {
+    pub discriminator: String,
+    pub is_initialized: bool,
+    pub reviewer: Pubkey,
+    pub movie: Pubkey,
+    pub rating: u8,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub title: String,
+    pub description: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ReviewCommentCounterStateV1 {
+    pub discriminator: String,
+    pub is_initialized: bool,
+    pub counter: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ReviewCommentStateV1 {
+    pub discriminator: String,
+    pub is_initialized: bool,
+    pub review: Pubkey,
+    pub commenter: Pubkey,
+    pub count: u64,
+    pub comment: String,
+}
+
+impl From<MovieStateV1> for MovieState {
+    fn from(v1: MovieStateV1) -> Self {
+        Self {
+            discriminator: v1.discriminator,
+            version: STATE_VERSION,
+            is_initialized: v1.is_initialized,
+            imdb_id: v1.imdb_id,
+            title: v1.title,
+        }
+    }
+}
+
+impl From<ReviewStateV1> for ReviewState {
+    fn from(v1: ReviewStateV1) -> Self {
+        Self {
+            discriminator: v1.discriminator,
+            version: STATE_VERSION,
+            is_initialized: v1.is_initialized,
+            reviewer: v1.reviewer,
+            movie: v1.movie,
+            rating: v1.rating,
+            ratings: CategoryRatings {
+                plot: v1.rating,
+                acting: v1.rating,
+                visuals: v1.rating,
+                soundtrack: v1.rating,
+            },
+            owner: v1.owner,
+            pending_owner: v1.pending_owner,
+            staked_amount: 0,
+            created_at: 0,
+            updated_at: 0,
+            title: v1.title,
+            description: v1.description,
+        }
+    }
+}
+
+impl From<ReviewCommentCounterStateV1> for ReviewCommentCounterState {
+    fn from(v1: ReviewCommentCounterStateV1) -> Self {
+        Self {
+            discriminator: v1.discriminator,
+            version: STATE_VERSION,
+            is_initialized: v1.is_initialized,
+            counter: v1.counter,
+        }
+    }
+}
+
+impl From<ReviewCommentStateV1> for ReviewCommentState {
+    fn from(v1: ReviewCommentStateV1) -> Self {
+        Self {
+            discriminator: v1.discriminator,
+            version: STATE_VERSION,
+            is_initialized: v1.is_initialized,
+            review: v1.review,
+            commenter: v1.commenter,
+            count: v1.count,
+            upvotes: 0,
+            deposit: 0,
+            created_at: 0,
+            updated_at: 0,
+            comment: v1.comment,
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use std::sync::Once;
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+};
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use solana_system_interface::program::id as system_program_id;
+
+use movie_review_client::{
+    instructions::{
+        initialize_token_mint_ix, register_movie_ix, add_movie_review_ix, update_movie_review_ix, add_comment_ix,
+    },
+    pda::{get_movie_address, get_review_address, get_comment_address},
+};
+use program::events::ReviewEvent;
+use program::processor::process_instruction;
+use program::state::CategoryRatings;
+
+const MOVIE_TITLE: &str = "Interstellar";
+const MOVIE_IMDB_ID: &str = "tt0816692";
+const MOVIE_DESCRIPTION: &str = "Space, time and Hans Zimmer.";
+
+#[tokio::test]
+async fn review_events_are_logged_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    install_log_data_stubs();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
+    );
+
+    let add_movie_review_tx_result =
+        banks_client.process_transaction_with_metadata(add_movie_review_tx).await?;
+
+    assert!(add_movie_review_tx_result.result.is_ok());
+    assert_eq!(
+        review_events(&add_movie_review_tx_result.metadata.unwrap().log_messages),
+        vec![ReviewEvent::ReviewAdded {
+            review: movie_review_account,
+            movie,
+            reviewer: payer.pubkey(),
+            rating: 5,
+        }],
+    );
+
+    let update_and_comment_tx = Transaction::new_signed_with_payer(
+        &[
+            update_movie_review_ix(&program_id, &payer.pubkey(), &movie_review_account, uniform_ratings(3), "Not bad.")?,
+            add_comment_ix(&program_id, &payer.pubkey(), &movie_review_account, 0, "Totally agree!")?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let update_and_comment_tx_result =
+        banks_client.process_transaction_with_metadata(update_and_comment_tx).await?;
+
+    assert!(update_and_comment_tx_result.result.is_ok());
+    assert_eq!(
+        review_events(&update_and_comment_tx_result.metadata.unwrap().log_messages),
+        vec![
+            ReviewEvent::ReviewUpdated {
+                review: movie_review_account,
+                owner: payer.pubkey(),
+                rating: 3,
+            },
+            ReviewEvent::CommentAdded {
+                review: movie_review_account,
+                comment: get_comment_address(&program_id, &movie_review_account, 0),
+                commenter: payer.pubkey(),
+                count: 0,
+            },
+        ],
+    );
+
+    Ok(())
+}
+
+// processor! runs the program natively, where sol_log_data falls through to a
+// stub that only prints to stdout. Wrapping the stubs ProgramTest installs lets
+// the data reach the transaction logs, as a "Program log: Program data: " line.
+// The swap has to happen before any transaction runs, which is why these tests
+// live in their own binary.
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_sysvar(&self, sysvar_id_addr: *const u8, var_addr: *mut u8, offset: u64, length: u64) -> u64 {
+        self.0.sol_get_sysvar(sysvar_id_addr, var_addr, offset, length)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    fn sol_get_epoch_stake(&self, vote_address: *const u8) -> u64 {
+        self.0.sol_get_epoch_stake(vote_address)
+    }
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memcpy(dst, src, n)
+    }
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memmove(dst, src, n)
+    }
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.0.sol_memcmp(s1, s2, n, result)
+    }
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.0.sol_memset(s, c, n)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+
+        self.0.sol_log(&format!("Program data: {}", fields.join(" ")));
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+// ProgramTest sets its stubs on the first start, so call this after starting.
+fn install_log_data_stubs() {
+    static ONCE: Once = Once::new();
+
+    ONCE.call_once(|| {
+        struct NoStubs;
+        impl SyscallStubs for NoStubs {}
+
+        let program_test_stubs = set_syscall_stubs(Box::new(NoStubs));
+        set_syscall_stubs(Box::new(LogDataStubs(program_test_stubs)));
+    });
+}
+
+// processor! doesn't deploy through the upgradeable loader, so the ProgramData
+// account naming the upgrade authority InitializeMint checks is added by hand.
+// The authority gets enough SOL to pay for the config and the mint.
+fn add_upgrade_authority(program_test: &mut ProgramTest, program_id: &Pubkey) -> Keypair {
+    let upgrade_authority = Keypair::new();
+
+    let mut data = vec![0; UpgradeableLoaderState::size_of_programdata_metadata()];
+    data[..4].copy_from_slice(&3u32.to_le_bytes());
+    data[12] = 1;
+    data[13..].copy_from_slice(upgrade_authority.pubkey().as_ref());
+
+    program_test.add_account(
+        get_program_data_address(program_id),
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: solana_sdk_ids::bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+
+    program_test.add_account(
+        upgrade_authority.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            owner: system_program_id(),
+            ..Account::default()
+        },
+    );
+
+    upgrade_authority
+}
+
+fn review_events(logs: &[String]) -> Vec<ReviewEvent> {
+    logs.iter()
+        .filter_map(|log| ReviewEvent::from_log(log.strip_prefix("Program log: ")?))
+        .collect()
+}
+
+fn uniform_ratings(rating: u8) -> CategoryRatings {
+    CategoryRatings {
+        plot: rating,
+        acting: rating,
+        visuals: rating,
+        soundtrack: rating,
+    }
+}
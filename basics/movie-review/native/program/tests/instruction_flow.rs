@@ -1,66 +1,74 @@
 use anyhow::Result;
-use borsh::BorshSerialize;
 
 use solana_program_test::*;
 
 use solana_sdk::{
-    borsh1::try_from_slice_unchecked, instruction::{AccountMeta, Instruction}, program_pack::Pack, pubkey::Pubkey, signature::{Keypair, Signer}, transaction::Transaction,
+    account::Account,
+    clock::Clock,
+    rent::Rent,
+    borsh1::try_from_slice_unchecked, instruction::{AccountMeta, InstructionError}, program_pack::Pack, pubkey::Pubkey, signature::{Keypair, Signer}, transaction::{Transaction, TransactionError},
     native_token::LAMPORTS_PER_SOL,
 };
+use solana_client::rpc_filter::RpcFilterType;
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
 use solana_system_interface::program::id as system_program_id;
-use spl_token::id as token_program_id; 
 
-use program::processor::process_instruction;
-use program::state::{ReviewState, ReviewCommentCounterState, ReviewCommentState};
+use movie_review_client::{
+    decode::decode,
+    errors::review_error_from_instruction_error,
+    filters::{reviews_by_reviewer_filters, reviews_by_movie_filters, comments_by_review_filters},
+    instructions::{
+        initialize_token_mint_ix, register_movie_ix, add_movie_review_ix, update_movie_review_ix,
+        add_comment_ix, delete_movie_review_ix, close_comments_ix, transfer_review_ix, accept_review_ix,
+        migrate_account_ix, vote_on_comment_ix, refund_comment_deposit_ix, stake_on_review_ix,
+        unstake_from_review_ix,
+    },
+    pda::{
+        get_config_address, get_reward_token_address, get_movie_address, get_review_address,
+        get_comment_counter_address, get_comment_address, get_comment_page_address,
+        get_comment_page_addresses, get_commenter_profile_address, get_vote_receipt_address,
+        get_stake_address, get_escrow_authority_address, get_reward_receipt_address,
+    },
+};
+use program::error::ReviewError;
+use program::processor::{process_instruction, REVIEW_REWARD, COMMENT_REWARD};
+use program::state::{
+    STATE_VERSION,
+    CategoryRatings,
+    ConfigState, MovieState, ReviewState, ReviewStakeState, ReviewCommentCounterState, ReviewCommentState,
+    ReviewCommentPageState, CommenterProfileState, CommentVoteReceiptState, ReviewRewardReceiptState,
+    MovieStateV1, ReviewStateV1, ReviewCommentCounterStateV1, ReviewCommentStateV1,
+};
+
+const MOVIE_TITLE: &str = "Interstellar";
+const MOVIE_IMDB_ID: &str = "tt0816692";
+const MOVIE_DESCRIPTION: &str =
+    "Sometimes I just need to see the start. Or end. Or a trailer.
+    Or the music and theme from Hans Zimmer. Or the whole movie.
+    Just to feel that thing, I only get from this movie.
+    That the earth, space and time are something special, mystical";
 
 #[tokio::test]
 async fn initialize_token_mint_ix_test() -> Result<()> {
     let program_id = Pubkey::new_unique();
 
-    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
-        "program", 
-        program_id, 
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
         processor!(process_instruction),
-    ).start().await;
+    );
 
-    let (token_mint, _token_mint_bump) =
-        Pubkey::find_program_address(&[b"token_mint"], &program_id);
-    let (mint_auth, _mint_auth_bump) =
-        Pubkey::find_program_address(&[b"mint_auth"], &program_id);
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
 
-    let initialize_token_mint_ix_data = vec![3];
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
 
-    let initialize_token_mint_ix = Instruction::new_with_bytes(
-        program_id, 
-        &initialize_token_mint_ix_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                token_mint, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                mint_auth, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
-        ],
-    );
+    let (token_mint, _token_mint_bump) =
+        Pubkey::find_program_address(&[b"token_mint"], &program_id);
 
     let initialize_token_mint_tx = Transaction::new_signed_with_payer(
-        &[initialize_token_mint_ix], 
-        Some(&payer.pubkey()), 
-        &[&payer], 
+        &[initialize_token_mint_ix(&program_id, &admin.pubkey())],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
         recent_blockhash,
     );
 
@@ -69,143 +77,192 @@ async fn initialize_token_mint_ix_test() -> Result<()> {
 
     assert!(initialize_token_mint_tx_result.is_ok());
 
-    let mint_account = 
+    let mint_account =
         banks_client.get_account(token_mint).await?.unwrap();
 
-    let mint_account = 
+    let mint_account =
         spl_token::state::Mint::unpack(&mint_account.data);
 
     assert!(mint_account.is_ok());
 
+    let config_state = banks_client.get_account(get_config_address(&program_id)).await?.unwrap();
+
+    assert_eq!(config_state.data.len(), ConfigState::SPACE);
+
+    let config_state = decode::<ConfigState>(&config_state.data)?;
+
+    assert_eq!(config_state.admin, admin.pubkey());
+
     Ok(())
 }
 
 #[tokio::test]
-async fn add_movie_review_ix_test() -> Result<()> {
+async fn initialize_token_mint_ix_twice_test() -> Result<()> {
     let program_id = Pubkey::new_unique();
 
-    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
-        "program", 
-        program_id, 
-        processor!(process_instruction)
-    ).start().await;
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction),
+    );
 
-    let movie_title = String::from("Interstellar");
-    let movie_rating = 5;
-    let movie_description = String::from(
-        "Sometimes I just need to see the start. Or end. Or a trailer. 
-        Or the music and theme from Hans Zimmer. Or the whole movie. 
-        Just to feel that thing, I only get from this movie. 
-        That the earth, space and time are something special, mystical"
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let intruder = Keypair::new();
+
+    let fund_intruder_ix = solana_system_interface::instruction::transfer(
+        &payer.pubkey(),
+        &intruder.pubkey(),
+        LAMPORTS_PER_SOL,
     );
 
-    let (movie_review_account, _bump) = Pubkey::find_program_address(
-        &[payer.pubkey().as_ref(), movie_title.as_bytes().as_ref()], 
-        &program_id,
+    let initialize_token_mint_tx = Transaction::new_signed_with_payer(
+        &[initialize_token_mint_ix(&program_id, &admin.pubkey()), fund_intruder_ix],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
     );
-    let (comment_counter, _bump) = Pubkey::find_program_address(
-        &[movie_review_account.as_ref(), "counter".as_ref()], 
-        &program_id,
+
+    banks_client.process_transaction(initialize_token_mint_tx).await?;
+
+    let initialize_token_mint_by_intruder_tx = Transaction::new_signed_with_payer(
+        &[initialize_token_mint_ix(&program_id, &intruder.pubkey())],
+        Some(&intruder.pubkey()),
+        &[&intruder],
+        recent_blockhash,
     );
-    let (token_mint, _token_mint_bump) =
-        Pubkey::find_program_address(&[b"token_mint"], &program_id);
-    let (mint_auth, _mint_auth_bump) =
-        Pubkey::find_program_address(&[b"mint_auth"], &program_id);
-    let user_ata = spl_associated_token_account::get_associated_token_address(
-        &payer.pubkey(), 
-        &token_mint,
+
+    let initialize_token_mint_by_intruder_tx_result =
+        banks_client.process_transaction(initialize_token_mint_by_intruder_tx).await;
+
+    assert_eq!(
+        review_error(initialize_token_mint_by_intruder_tx_result),
+        Some(ReviewError::NotConfigAdmin),
     );
 
-    let initialize_token_mint_ix_data = vec![3];
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await?;
 
-    let initialize_token_mint_ix = Instruction::new_with_bytes(
-        program_id, 
-        &initialize_token_mint_ix_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                token_mint, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                mint_auth, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
-        ],
+    let initialize_token_mint_again_tx = Transaction::new_signed_with_payer(
+        &[initialize_token_mint_ix(&program_id, &admin.pubkey())],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
     );
 
-    let create_user_ata_ix = 
-        spl_associated_token_account::instruction::create_associated_token_account(
-            &payer.pubkey(), 
-            &payer.pubkey(), 
-            &token_mint, 
-            &token_program_id(),
-        );
+    let initialize_token_mint_again_tx_result =
+        banks_client.process_transaction(initialize_token_mint_again_tx).await;
 
-    let movie_review_payload = MovieReviewPayload {
-        title: movie_title.clone(),
-        rating: movie_rating,
-        description: movie_description.clone()
-    };
+    assert_eq!(
+        initialize_token_mint_again_tx_result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized),
+    );
 
-    let mut add_movie_instruction_data = vec![0];
+    Ok(())
+}
 
-    movie_review_payload.serialize(&mut add_movie_instruction_data)?;
+#[tokio::test]
+async fn initialize_token_mint_ix_not_upgrade_authority_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
 
-    let add_movie_review_ix = Instruction::new_with_bytes(
-        program_id, 
-        &add_movie_instruction_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                movie_review_account, 
-                false,
-            ),
-            AccountMeta::new(
-                comment_counter,
-                false,
-            ),
-            AccountMeta::new(
-                token_mint,
-                false
-            ),
-            AccountMeta::new_readonly(
-                mint_auth,
-                false
-            ),
-            AccountMeta::new(
-                user_ata,
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
-        ],
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction),
+    );
+
+    add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // the payer isn't the upgrade authority, so it can't become the admin by
+    // getting there first
+    let initialize_token_mint_tx = Transaction::new_signed_with_payer(
+        &[initialize_token_mint_ix(&program_id, &payer.pubkey())],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let initialize_token_mint_tx_result =
+        banks_client.process_transaction(initialize_token_mint_tx).await;
+
+    assert_eq!(review_error(initialize_token_mint_tx_result), Some(ReviewError::NotUpgradeAuthority));
+    assert!(banks_client.get_account(get_config_address(&program_id)).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn register_movie_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    ).start().await;
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
+    let register_movie_tx = Transaction::new_signed_with_payer(
+        &[register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let register_movie_tx_result = banks_client.process_transaction(register_movie_tx).await;
+
+    assert!(register_movie_tx_result.is_ok());
+
+    let movie_account_state =
+        banks_client.get_account(movie).await?.unwrap();
+
+    assert_eq!(movie_account_state.data.len(), MovieState::space(MOVIE_IMDB_ID, MOVIE_TITLE));
+
+    let movie_account_state =
+        try_from_slice_unchecked::<MovieState>(&movie_account_state.data)?;
+
+    assert_eq!(movie_account_state.discriminator, MovieState::DISCRIMINATOR);
+    assert_eq!(movie_account_state.version, STATE_VERSION);
+    assert_eq!(movie_account_state.is_initialized, true);
+    assert_eq!(movie_account_state.imdb_id, MOVIE_IMDB_ID);
+    assert_eq!(movie_account_state.title, MOVIE_TITLE);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_movie_review_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
     );
 
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let movie_ratings = CategoryRatings { plot: 5, acting: 4, visuals: 5, soundtrack: 3 };
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+    let comment_counter = get_comment_counter_address(&program_id, &movie_review_account);
+    let user_ata = get_reward_token_address(&program_id, &payer.pubkey());
+
     let add_movie_review_tx = Transaction::new_signed_with_payer(
-        &[initialize_token_mint_ix, create_user_ata_ix, add_movie_review_ix], 
-        Some(&payer.pubkey()), 
-        &[&payer], 
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, movie_ratings, MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
         recent_blockhash,
     );
 
@@ -213,36 +270,44 @@ async fn add_movie_review_ix_test() -> Result<()> {
 
     assert!(add_movie_review_tx_result.is_ok());
 
-    let movie_review_account_state = 
+    let movie_review_account_state =
         banks_client.get_account(movie_review_account).await?.unwrap();
 
     assert_eq!(movie_review_account_state.data.len(), ReviewState::MAX_SPACE);
 
-    let movie_review_account_state = 
+    let movie_review_account_state =
         try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
 
     assert_eq!(movie_review_account_state.discriminator, ReviewState::DISCRIMINATOR);
+    assert_eq!(movie_review_account_state.version, STATE_VERSION);
     assert_eq!(movie_review_account_state.is_initialized, true);
     assert_eq!(movie_review_account_state.reviewer, payer.pubkey());
-    assert_eq!(movie_review_account_state.rating, movie_rating);
-    assert_eq!(movie_review_account_state.title, movie_title);
-    assert_eq!(movie_review_account_state.description, movie_description);
-
-    let comment_counter_state = 
+    assert_eq!(movie_review_account_state.movie, movie);
+    assert_eq!(movie_review_account_state.rating, 4);
+    assert_eq!(movie_review_account_state.ratings, movie_ratings);
+    assert_eq!(movie_review_account_state.owner, payer.pubkey());
+    assert_eq!(movie_review_account_state.pending_owner, Pubkey::default());
+    assert_eq!(movie_review_account_state.staked_amount, 0);
+    assert_eq!(movie_review_account_state.updated_at, movie_review_account_state.created_at);
+    assert_eq!(movie_review_account_state.title, MOVIE_TITLE);
+    assert_eq!(movie_review_account_state.description, MOVIE_DESCRIPTION);
+
+    let comment_counter_state =
         banks_client.get_account(comment_counter).await?.unwrap();
 
     assert_eq!(comment_counter_state.data.len(), ReviewCommentCounterState::SPACE);
 
-    let comment_counter_state = 
+    let comment_counter_state =
         try_from_slice_unchecked::<ReviewCommentCounterState>(&comment_counter_state.data)?;
 
     assert_eq!(comment_counter_state.discriminator, ReviewCommentCounterState::DISCRIMINATOR);
+    assert_eq!(comment_counter_state.version, STATE_VERSION);
     assert_eq!(comment_counter_state.is_initialized, true);
     assert_eq!(comment_counter_state.counter, 0);
 
-    let ata = 
+    let ata =
         banks_client.get_account(user_ata).await?.unwrap();
-    let ata =  
+    let ata =
         spl_token::state::Account::unpack(&ata.data)?;
 
     assert_eq!(ata.amount, 10 * LAMPORTS_PER_SOL);
@@ -254,131 +319,69 @@ async fn add_movie_review_ix_test() -> Result<()> {
 async fn add_movie_review_ix_with_invalid_movie_review_account_test() -> Result<()> {
     let program_id = Pubkey::new_unique();
 
-    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
-        "program", 
-        program_id, 
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
         processor!(process_instruction)
-    ).start().await;
-
-    let movie_title = String::from("Interstellar");
-    let movie_rating = 5;
-    let movie_description = String::from(
-        "Sometimes I just need to see the start. Or end. Or a trailer. 
-        Or the music and theme from Hans Zimmer. Or the whole movie. 
-        Just to feel that thing, I only get from this movie. 
-        That the earth, space and time are something special, mystical"
     );
 
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
     let another_reviewer = Keypair::new();
 
-    let (movie_review_account, _bump) = Pubkey::find_program_address(
-        &[another_reviewer.pubkey().as_ref(), movie_title.as_bytes().as_ref()], 
-        &program_id,
-    );
-    let (comment_counter, _bump) = Pubkey::find_program_address(
-        &[movie_review_account.as_ref(), "counter".as_ref()], 
-        &program_id,
-    );
-    let (token_mint, _token_mint_bump) =
-        Pubkey::find_program_address(&[b"token_mint"], &program_id);
-    let (mint_auth, _mint_auth_bump) =
-        Pubkey::find_program_address(&[b"mint_auth"], &program_id);
-    let user_ata = spl_associated_token_account::get_associated_token_address(
-        &payer.pubkey(), 
-        &token_mint,
-    );
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
 
-    let initialize_token_mint_ix_data = vec![3];
+    let mut add_movie_review_ix =
+        add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?;
 
-    let initialize_token_mint_ix = Instruction::new_with_bytes(
-        program_id, 
-        &initialize_token_mint_ix_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                token_mint, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                mint_auth, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
+    let movie_review_account = get_review_address(&program_id, &another_reviewer.pubkey(), &movie);
+    add_movie_review_ix.accounts[2] = AccountMeta::new(movie_review_account, false);
+    add_movie_review_ix.accounts[3] =
+        AccountMeta::new(get_comment_counter_address(&program_id, &movie_review_account), false);
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix,
         ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
     );
 
-    let create_user_ata_ix = 
-        spl_associated_token_account::instruction::create_associated_token_account(
-            &payer.pubkey(), 
-            &payer.pubkey(), 
-            &token_mint, 
-            &token_program_id(),
-        );
-
+    let add_movie_review_tx_result = banks_client.process_transaction(add_movie_review_tx).await;
 
-    let movie_review_payload = MovieReviewPayload {
-        title: movie_title.clone(),
-        rating: movie_rating,
-        description: movie_description.clone()
-    };
+    assert!(add_movie_review_tx_result.is_err());
 
-    let mut add_movie_instruction_data = vec![0];
+    Ok(())
+}
 
-    movie_review_payload.serialize(&mut add_movie_instruction_data)?;
+#[tokio::test]
+async fn add_movie_review_ix_with_unregistered_movie_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
 
-   let add_movie_review_ix = Instruction::new_with_bytes(
-        program_id, 
-        &add_movie_instruction_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                movie_review_account, 
-                false,
-            ),
-            AccountMeta::new(
-                comment_counter,
-                false,
-            ),
-            AccountMeta::new(
-                token_mint,
-                false
-            ),
-            AccountMeta::new_readonly(
-                mint_auth,
-                false
-            ),
-            AccountMeta::new(
-                user_ata,
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
-        ],
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
     );
 
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
     let add_movie_review_tx = Transaction::new_signed_with_payer(
-        &[initialize_token_mint_ix, create_user_ata_ix, add_movie_review_ix], 
-        Some(&payer.pubkey()), 
-        &[&payer], 
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
         recent_blockhash,
     );
 
@@ -390,195 +393,231 @@ async fn add_movie_review_ix_with_invalid_movie_review_account_test() -> Result<
 }
 
 #[tokio::test]
-async fn update_movie_review_ix_test() -> Result<()> {
+async fn add_movie_review_ix_with_invalid_category_rating_test() -> Result<()> {
     let program_id = Pubkey::new_unique();
 
-    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
-        "program", 
-        program_id, 
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
         processor!(process_instruction)
-    ).start().await;
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
 
-    let movie_title = String::from("Interstellar");
-    let movie_rating = 5;
-    let movie_description = String::from(
-        "Sometimes I just need to see the start. Or end. Or a trailer. 
-        Or the music and theme from Hans Zimmer. Or the whole movie. 
-        Just to feel that thing, I only get from this movie. 
-        That the earth, space and time are something special, mystical"
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
+    let invalid_ratings = CategoryRatings { plot: 5, acting: 5, visuals: 0, soundtrack: 5 };
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, invalid_ratings, MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
     );
 
-    let (movie_review_account, _bump) = Pubkey::find_program_address(
-        &[payer.pubkey().as_ref(), movie_title.as_bytes().as_ref()], 
-        &program_id,
+    let add_movie_review_tx_result = banks_client.process_transaction(add_movie_review_tx).await;
+
+    assert!(add_movie_review_tx_result.is_err());
+
+    Ok(())
+}
+
+// Reviews used to be seeded by (reviewer, title). Those addresses are no
+// longer accepted, so legacy reviews have to be re-created against a
+// registered movie.
+#[tokio::test]
+async fn add_movie_review_ix_with_legacy_title_seeds_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
     );
 
-    let (comment_counter, _bump) = Pubkey::find_program_address(
-        &[movie_review_account.as_ref(), "counter".as_ref()], 
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
+    let (legacy_movie_review_account, _bump) = Pubkey::find_program_address(
+        &[payer.pubkey().as_ref(), MOVIE_TITLE.as_bytes()],
         &program_id,
     );
 
-    let (token_mint, _token_mint_bump) =
-        Pubkey::find_program_address(&[b"token_mint"], &program_id);
-    let (mint_auth, _mint_auth_bump) =
-        Pubkey::find_program_address(&[b"mint_auth"], &program_id);
-    let user_ata = spl_associated_token_account::get_associated_token_address(
-        &payer.pubkey(), 
-        &token_mint,
-    );
+    let mut add_movie_review_ix =
+        add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?;
 
-    let initialize_token_mint_ix_data = vec![3];
+    add_movie_review_ix.accounts[2] = AccountMeta::new(legacy_movie_review_account, false);
+    add_movie_review_ix.accounts[3] =
+        AccountMeta::new(get_comment_counter_address(&program_id, &legacy_movie_review_account), false);
 
-    let initialize_token_mint_ix = Instruction::new_with_bytes(
-        program_id, 
-        &initialize_token_mint_ix_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                token_mint, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                mint_auth, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix,
         ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
     );
 
-    let create_user_ata_ix = 
-        spl_associated_token_account::instruction::create_associated_token_account(
-            &payer.pubkey(), 
-            &payer.pubkey(), 
-            &token_mint, 
-            &token_program_id(),
-        );
+    let add_movie_review_tx_result = banks_client.process_transaction(add_movie_review_tx).await;
 
-    let movie_review_payload = MovieReviewPayload {
-        title: movie_title.clone(),
-        rating: movie_rating,
-        description: movie_description.clone()
-    };
+    assert!(add_movie_review_tx_result.is_err());
 
-    let mut add_movie_instruction_data = vec![0];
+    Ok(())
+}
 
-    movie_review_payload.serialize(&mut add_movie_instruction_data)?;
-    
-    let add_movie_review_ix = Instruction::new_with_bytes(
-        program_id, 
-        &add_movie_instruction_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                movie_review_account, 
-                false,
-            ),
-            AccountMeta::new(
-                comment_counter,
-                false,
-            ),
-            AccountMeta::new(
-                token_mint,
-                false
-            ),
-            AccountMeta::new_readonly(
-                mint_auth,
-                false
-            ),
-            AccountMeta::new(
-                user_ata,
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
-        ],
+#[tokio::test]
+async fn add_movie_review_ix_with_shared_movie_and_long_title_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
     );
 
-    let add_movie_review_tx = Transaction::new_signed_with_payer(
-        &[initialize_token_mint_ix, create_user_ata_ix, add_movie_review_ix], 
-        Some(&payer.pubkey()), 
-        &[&payer], 
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let movie_title = "Dr. Strangelove or: How I Learned to Stop Worrying and Love the Bomb";
+    let movie_imdb_id = "tt0057012";
+
+    assert!(movie_title.len() > 32);
+
+    let another_reviewer = Keypair::new();
+
+    let movie = get_movie_address(&program_id, movie_imdb_id);
+
+    let fund_another_reviewer_ix = solana_system_interface::instruction::transfer(
+        &payer.pubkey(),
+        &another_reviewer.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let add_movie_reviews_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            fund_another_reviewer_ix,
+            register_movie_ix(&program_id, &payer.pubkey(), movie_title, movie_imdb_id)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), "A classic.")?,
+            add_movie_review_ix(&program_id, &another_reviewer.pubkey(), &movie, uniform_ratings(4), "Still funny.")?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &another_reviewer, &admin],
         recent_blockhash,
     );
 
-    banks_client.process_transaction(add_movie_review_tx).await?;
+    let add_movie_reviews_tx_result = banks_client.process_transaction(add_movie_reviews_tx).await;
 
-    let movie_title = String::from("Interstellar");
-    let new_movie_rating = 3;
-    let new_movie_description = String::from("Not bad.");
+    assert!(add_movie_reviews_tx_result.is_ok());
 
-    let (movie_review_account, _bump) = Pubkey::find_program_address(
-        &[payer.pubkey().as_ref(), movie_title.as_bytes().as_ref()], 
-        &program_id,
+    for reviewer in [payer.pubkey(), another_reviewer.pubkey()] {
+        let movie_review_account_state = banks_client
+            .get_account(get_review_address(&program_id, &reviewer, &movie))
+            .await?
+            .unwrap();
+
+        let movie_review_account_state =
+            try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
+
+        assert_eq!(movie_review_account_state.reviewer, reviewer);
+        assert_eq!(movie_review_account_state.movie, movie);
+        assert_eq!(movie_review_account_state.title, movie_title);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn update_movie_review_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
     );
 
-    let movie_review_payload = MovieReviewPayload {
-        title: movie_title.clone(),
-        rating: new_movie_rating,
-        description: new_movie_description.clone(),
-    };
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
 
-    let mut update_movie_review_ix_data = vec![1];
+    let mut context = program_test.start_with_context().await;
 
-    movie_review_payload.serialize(&mut update_movie_review_ix_data)?;
+    let payer = context.payer.pubkey();
 
-    let update_movie_review_ix = Instruction::new_with_bytes(
-        program_id, 
-        &update_movie_review_ix_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                movie_review_account,
-                false,
-            ),
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer, &movie);
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer, MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer, &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
         ],
+        Some(&payer),
+        &[&context.payer, &admin],
+        context.last_blockhash,
     );
 
+    context.banks_client.process_transaction(add_movie_review_tx).await?;
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+    let created_at = clock.unix_timestamp;
+    clock.unix_timestamp += 60;
+    context.set_sysvar(&clock);
+
+    let new_movie_ratings = uniform_ratings(3);
+    let new_movie_description = String::from("Not bad.");
+
+    let recent_blockhash =
+        context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await?;
+
     let update_movie_review_tx = Transaction::new_signed_with_payer(
-        &[update_movie_review_ix], 
-        Some(&payer.pubkey()), 
-        &[&payer], 
+        &[
+            update_movie_review_ix(
+                &program_id,
+                &payer,
+                &movie_review_account,
+                new_movie_ratings,
+                &new_movie_description,
+            )?,
+        ],
+        Some(&payer),
+        &[&context.payer],
         recent_blockhash,
     );
 
-    let update_movie_review_tx_result = 
-        banks_client.process_transaction(update_movie_review_tx).await;
+    let update_movie_review_tx_result =
+        context.banks_client.process_transaction(update_movie_review_tx).await;
 
     assert!(update_movie_review_tx_result.is_ok());
 
-    let movie_review_account_state = 
-        banks_client.get_account(movie_review_account).await?.unwrap();
+    let movie_review_account_state =
+        context.banks_client.get_account(movie_review_account).await?.unwrap();
 
-    let movie_review_account_state = 
+    let movie_review_account_state =
         try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
 
     assert_eq!(movie_review_account_state.discriminator, ReviewState::DISCRIMINATOR);
     assert_eq!(movie_review_account_state.is_initialized, true);
-    assert_eq!(movie_review_account_state.reviewer, payer.pubkey());
-    assert_eq!(movie_review_account_state.rating, new_movie_rating);
-    assert_eq!(movie_review_account_state.title, movie_title);
+    assert_eq!(movie_review_account_state.reviewer, payer);
+    assert_eq!(movie_review_account_state.movie, movie);
+    assert_eq!(movie_review_account_state.rating, 3);
+    assert_eq!(movie_review_account_state.ratings, new_movie_ratings);
+    assert_eq!(movie_review_account_state.created_at, created_at);
+    assert_eq!(movie_review_account_state.updated_at, created_at + 60);
+    assert_eq!(movie_review_account_state.title, MOVIE_TITLE);
     assert_eq!(movie_review_account_state.description, new_movie_description);
 
     Ok(())
@@ -586,205 +625,60 @@ async fn update_movie_review_ix_test() -> Result<()> {
 
 #[tokio::test]
 async fn add_comment_ix_test() -> Result<()> {
-        let program_id = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
 
-    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
-        "program", 
-        program_id, 
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
         processor!(process_instruction)
-    ).start().await;
-
-    let movie_title = String::from("Interstellar");
-    let movie_rating = 5;
-    let movie_description = String::from(
-        "Sometimes I just need to see the start. Or end. Or a trailer. 
-        Or the music and theme from Hans Zimmer. Or the whole movie. 
-        Just to feel that thing, I only get from this movie. 
-        That the earth, space and time are something special, mystical"
-    );
-
-    let (movie_review_account, _bump) = Pubkey::find_program_address(
-        &[payer.pubkey().as_ref(), movie_title.as_bytes().as_ref()], 
-        &program_id,
-    );
-
-    let (comment_counter, _bump) = Pubkey::find_program_address(
-        &[movie_review_account.as_ref(), "counter".as_ref()], 
-        &program_id,
-    );
-    let (token_mint, _token_mint_bump) =
-        Pubkey::find_program_address(&[b"token_mint"], &program_id);
-    let (mint_auth, _mint_auth_bump) =
-        Pubkey::find_program_address(&[b"mint_auth"], &program_id);
-    let user_ata = spl_associated_token_account::get_associated_token_address(
-        &payer.pubkey(), 
-        &token_mint,
-    );
-
-    let initialize_token_mint_ix_data = vec![3];
-
-    let initialize_token_mint_ix = Instruction::new_with_bytes(
-        program_id, 
-        &initialize_token_mint_ix_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                token_mint, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                mint_auth, 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
-        ],
     );
 
-    let create_user_ata_ix = 
-        spl_associated_token_account::instruction::create_associated_token_account(
-            &payer.pubkey(), 
-            &payer.pubkey(), 
-            &token_mint, 
-            &token_program_id(),
-        );
-
-    let movie_review_payload = MovieReviewPayload {
-        title: movie_title.clone(),
-        rating: movie_rating,
-        description: movie_description.clone()
-    };
-
-    let mut add_movie_instruction_data = vec![0];
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
 
-    movie_review_payload.serialize(&mut add_movie_instruction_data)?;
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
 
-    let add_movie_review_ix = Instruction::new_with_bytes(
-        program_id, 
-        &add_movie_instruction_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new(
-                movie_review_account, 
-                false,
-            ),
-            AccountMeta::new(
-                comment_counter,
-                false,
-            ),
-            AccountMeta::new(
-                token_mint,
-                false
-            ),
-            AccountMeta::new_readonly(
-                mint_auth,
-                false
-            ),
-            AccountMeta::new(
-                user_ata,
-                false,
-            ),
-            AccountMeta::new_readonly(
-                system_program_id(), 
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
-        ],
-    );
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+    let comment_counter = get_comment_counter_address(&program_id, &movie_review_account);
+    let user_ata = get_reward_token_address(&program_id, &payer.pubkey());
 
     let add_movie_review_tx = Transaction::new_signed_with_payer(
-        &[initialize_token_mint_ix, create_user_ata_ix, add_movie_review_ix], 
-        Some(&payer.pubkey()), 
-        &[&payer], 
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
         recent_blockhash,
     );
 
     banks_client.process_transaction(add_movie_review_tx).await?;
 
-    let comment_counter_state = 
+    let comment_counter_state =
         banks_client.get_account(comment_counter).await?.unwrap();
 
-    let comment_counter_state = 
+    let comment_counter_state =
         try_from_slice_unchecked::<ReviewCommentCounterState>(&comment_counter_state.data)?;
 
     let current_comment_count = comment_counter_state.counter;
 
-    let (comment_account_pda, _comment_account_bump) = Pubkey::find_program_address(
-        &[movie_review_account.as_ref(), current_comment_count.to_be_bytes().as_ref()], 
-        &program_id,
-    );
+    let comment_account_pda = get_comment_address(&program_id, &movie_review_account, current_comment_count);
 
     let comment = String::from("Totally agree!");
-    
-    let comment_payload = CommentPayload {
-        comment: comment.clone(),
-    };
-
-    let mut add_comment_ix_data = vec![2];
-    comment_payload.serialize(&mut add_comment_ix_data)?;
-
-    let add_comment_ix = Instruction::new_with_bytes(
-        program_id, 
-        &add_comment_ix_data, 
-        vec![
-            AccountMeta::new(
-                payer.pubkey(), 
-                true,
-            ),
-            AccountMeta::new_readonly(
-                movie_review_account, 
-                false,
-            ),
-            AccountMeta::new(
-                comment_counter, 
-                false,
-            ),
-            AccountMeta::new(
-                comment_account_pda, 
-                false,
-            ),
-            AccountMeta::new(
-                token_mint,
-                false,
-            ),
-            AccountMeta::new_readonly(
-                mint_auth,
-                false,
-            ),
-            AccountMeta::new(
-                user_ata,
-                false
-            ),
-            AccountMeta::new_readonly(
-                solana_system_interface::program::id(),
-                false,
-            ),
-            AccountMeta::new_readonly(
-                token_program_id(), 
-                false,
-            ),
-        ],
-    );
 
     let add_comment_tx = Transaction::new_signed_with_payer(
-        &[add_comment_ix], 
-        Some(&payer.pubkey()), 
-        &[&payer], 
+        &[
+            add_comment_ix(
+                &program_id,
+                &payer.pubkey(),
+                &movie_review_account,
+                current_comment_count,
+                &comment,
+            )?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
         recent_blockhash,
     );
 
@@ -799,24 +693,38 @@ async fn add_comment_ix_test() -> Result<()> {
     let comment_account_state = try_from_slice_unchecked::<ReviewCommentState>(&comment_account_state.data)?;
 
     assert_eq!(comment_account_state.discriminator, ReviewCommentState::DISCRIMINATOR.to_string());
+    assert_eq!(comment_account_state.version, STATE_VERSION);
     assert_eq!(comment_account_state.is_initialized, true);
     assert_eq!(comment_account_state.review, movie_review_account);
     assert_eq!(comment_account_state.commenter, payer.pubkey());
     assert_eq!(comment_account_state.comment, comment);
     assert_eq!(comment_account_state.count, 0);
+    assert!(comment_account_state.created_at > 0);
+    assert_eq!(comment_account_state.updated_at, comment_account_state.created_at);
 
-    let comment_counter_state = 
+    let comment_counter_state =
         banks_client.get_account(comment_counter).await?.unwrap();
 
-    let comment_counter_state = 
+    let comment_counter_state =
         try_from_slice_unchecked::<ReviewCommentCounterState>(&comment_counter_state.data)?;
 
     assert_eq!(comment_counter_state.counter, 1);
 
-    
-    let ata = 
+    let comment_page_state =
+        banks_client.get_account(get_comment_page_address(&program_id, &movie_review_account, 0)).await?.unwrap();
+
+    assert_eq!(comment_page_state.data.len(), ReviewCommentPageState::SPACE);
+
+    let comment_page_state =
+        try_from_slice_unchecked::<ReviewCommentPageState>(&comment_page_state.data)?;
+
+    assert_eq!(comment_page_state.review, movie_review_account);
+    assert_eq!(comment_page_state.page, 0);
+    assert_eq!(comment_page_state.comments, vec![comment_account_pda]);
+
+    let ata =
         banks_client.get_account(user_ata).await?.unwrap();
-    let ata =  
+    let ata =
         spl_token::state::Account::unpack(&ata.data)?;
 
     assert_eq!(ata.amount, 15 * LAMPORTS_PER_SOL);
@@ -824,14 +732,1616 @@ async fn add_comment_ix_test() -> Result<()> {
     Ok(())
 }
 
-#[derive(BorshSerialize)]
-struct MovieReviewPayload {
-    title: String,
-    rating: u8,
-    description: String,
+#[tokio::test]
+async fn fetch_reviews_with_memcmp_filters_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let another_imdb_id = "tt1375666";
+    let another_reviewer = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let another_movie = get_movie_address(&program_id, another_imdb_id);
+
+    let fund_another_reviewer_ix = solana_system_interface::instruction::transfer(
+        &payer.pubkey(),
+        &another_reviewer.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let add_movie_reviews_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            fund_another_reviewer_ix,
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            register_movie_ix(&program_id, &payer.pubkey(), "Inception", another_imdb_id)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &another_movie, uniform_ratings(4), "Dreams.")?,
+            add_movie_review_ix(&program_id, &another_reviewer.pubkey(), &movie, uniform_ratings(3), "Long.")?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &another_reviewer, &admin],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(add_movie_reviews_tx).await?;
+
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+
+    let add_comment_tx = Transaction::new_signed_with_payer(
+        &[add_comment_ix(&program_id, &payer.pubkey(), &movie_review_account, 0, "Agreed.")?],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(add_comment_tx).await?;
+
+    // BanksClient has no getProgramAccounts, so run the RPC filters over
+    // every account the program owns instead.
+    let program_accounts = vec![
+        movie,
+        another_movie,
+        movie_review_account,
+        get_review_address(&program_id, &payer.pubkey(), &another_movie),
+        get_review_address(&program_id, &another_reviewer.pubkey(), &movie),
+        get_comment_counter_address(&program_id, &movie_review_account),
+        get_comment_address(&program_id, &movie_review_account, 0),
+    ];
+
+    let mut fetched_accounts = Vec::new();
+    for address in program_accounts {
+        let account = banks_client.get_account(address).await?.unwrap();
+        fetched_accounts.push((address, account));
+    }
+
+    let filter_accounts = |filters: Vec<RpcFilterType>| {
+        fetched_accounts
+            .iter()
+            .filter(|(_, account)| filters.iter().all(|filter| filter_allows(filter, &account.data)))
+            .map(|(address, _)| *address)
+            .collect::<Vec<Pubkey>>()
+    };
+
+    assert_eq!(
+        filter_accounts(reviews_by_reviewer_filters(&payer.pubkey())),
+        vec![
+            movie_review_account,
+            get_review_address(&program_id, &payer.pubkey(), &another_movie),
+        ],
+    );
+    assert_eq!(
+        filter_accounts(reviews_by_reviewer_filters(&another_reviewer.pubkey())),
+        vec![get_review_address(&program_id, &another_reviewer.pubkey(), &movie)],
+    );
+    assert_eq!(
+        filter_accounts(reviews_by_movie_filters(&movie)),
+        vec![
+            movie_review_account,
+            get_review_address(&program_id, &another_reviewer.pubkey(), &movie),
+        ],
+    );
+    assert_eq!(
+        filter_accounts(comments_by_review_filters(&movie_review_account)),
+        vec![get_comment_address(&program_id, &movie_review_account, 0)],
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_movie_review_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+    let comment_counter = get_comment_counter_address(&program_id, &movie_review_account);
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(add_movie_review_tx).await?;
+
+    let delete_movie_review_tx = Transaction::new_signed_with_payer(
+        &[delete_movie_review_ix(&program_id, &payer.pubkey(), &movie_review_account, &[])],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let delete_movie_review_tx_result =
+        banks_client.process_transaction(delete_movie_review_tx).await;
+
+    assert!(delete_movie_review_tx_result.is_ok());
+
+    assert!(banks_client.get_account(movie_review_account).await?.is_none());
+    assert!(banks_client.get_account(comment_counter).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn re_add_deleted_movie_review_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+    let reward_receipt = get_reward_receipt_address(&program_id, &movie, &payer.pubkey());
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(add_movie_review_tx).await?;
+
+    let delete_and_re_add_tx = Transaction::new_signed_with_payer(
+        &[
+            delete_movie_review_ix(&program_id, &payer.pubkey(), &movie_review_account, &[]),
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(4), "Second thoughts.")?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(delete_and_re_add_tx).await?;
+
+    let movie_review_state = banks_client.get_account(movie_review_account).await?.unwrap();
+    let movie_review_state = try_from_slice_unchecked::<ReviewState>(&movie_review_state.data)?;
+
+    assert_eq!(movie_review_state.description, "Second thoughts.");
+
+    let ata = banks_client.get_account(get_reward_token_address(&program_id, &payer.pubkey())).await?.unwrap();
+    let ata = spl_token::state::Account::unpack(&ata.data)?;
+
+    assert_eq!(ata.amount, REVIEW_REWARD);
+
+    let config_state = banks_client.get_account(get_config_address(&program_id)).await?.unwrap();
+    let config_state = try_from_slice_unchecked::<ConfigState>(&config_state.data)?;
+
+    assert_eq!(config_state.reviews_rewarded, 1);
+
+    let reward_receipt_state = banks_client.get_account(reward_receipt).await?.unwrap();
+    let reward_receipt_state = try_from_slice_unchecked::<ReviewRewardReceiptState>(&reward_receipt_state.data)?;
+
+    assert_eq!(reward_receipt_state.movie, movie);
+    assert_eq!(reward_receipt_state.reviewer, payer.pubkey());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_comments_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let commenter = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+    let first_comment = get_comment_address(&program_id, &movie_review_account, 0);
+    let second_comment = get_comment_address(&program_id, &movie_review_account, 1);
+
+    let fund_commenter_ix = solana_system_interface::instruction::transfer(
+        &payer.pubkey(),
+        &commenter.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let add_comments_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            fund_commenter_ix,
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+            add_comment_ix(&program_id, &payer.pubkey(), &movie_review_account, 0, "First!")?,
+            add_comment_ix(&program_id, &commenter.pubkey(), &movie_review_account, 1, "Second.")?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &commenter, &admin],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(add_comments_tx).await?;
+
+    let comment_pages = get_comment_page_addresses(&program_id, &movie_review_account, 2);
+
+    assert_eq!(comment_pages, vec![get_comment_page_address(&program_id, &movie_review_account, 0)]);
+
+    let comment_page_state = banks_client.get_account(comment_pages[0]).await?.unwrap();
+    let comment_page_state =
+        try_from_slice_unchecked::<ReviewCommentPageState>(&comment_page_state.data)?;
+
+    assert_eq!(comment_page_state.comments, vec![first_comment, second_comment]);
+
+    let close_comments_before_delete_tx = Transaction::new_signed_with_payer(
+        &[
+            close_comments_ix(
+                &program_id,
+                &movie_review_account,
+                &[(first_comment, payer.pubkey()), (second_comment, commenter.pubkey())],
+            ),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let close_comments_before_delete_tx_result =
+        banks_client.process_transaction(close_comments_before_delete_tx).await;
+
+    assert!(close_comments_before_delete_tx_result.is_err());
+
+    let delete_movie_review_tx = Transaction::new_signed_with_payer(
+        &[delete_movie_review_ix(&program_id, &payer.pubkey(), &movie_review_account, &[payer.pubkey()])],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(delete_movie_review_tx).await?;
+
+    assert!(banks_client.get_account(comment_pages[0]).await?.is_none());
+
+    let close_comments_with_wrong_commenter_tx = Transaction::new_signed_with_payer(
+        &[
+            close_comments_ix(
+                &program_id,
+                &movie_review_account,
+                &[(second_comment, payer.pubkey())],
+            ),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let close_comments_with_wrong_commenter_tx_result =
+        banks_client.process_transaction(close_comments_with_wrong_commenter_tx).await;
+
+    assert!(close_comments_with_wrong_commenter_tx_result.is_err());
+
+    let commenter_balance = banks_client.get_balance(commenter.pubkey()).await?;
+    let second_comment_rent = banks_client.get_balance(second_comment).await?;
+
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await?;
+
+    let close_comments_tx = Transaction::new_signed_with_payer(
+        &[
+            close_comments_ix(
+                &program_id,
+                &movie_review_account,
+                &[(first_comment, payer.pubkey()), (second_comment, commenter.pubkey())],
+            ),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let close_comments_tx_result = banks_client.process_transaction(close_comments_tx).await;
+
+    assert!(close_comments_tx_result.is_ok());
+
+    assert!(banks_client.get_account(first_comment).await?.is_none());
+    assert!(banks_client.get_account(second_comment).await?.is_none());
+
+    assert_eq!(
+        banks_client.get_balance(commenter.pubkey()).await?,
+        commenter_balance + second_comment_rent,
+    );
+
+    Ok(())
 }
 
-#[derive(BorshSerialize)]
-struct CommentPayload {
-    comment: String,
-}
\ No newline at end of file
+#[tokio::test]
+async fn delete_movie_review_ix_closes_comment_pages_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+    let commenter = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer, &movie);
+
+    let fund_commenter_ix = solana_system_interface::instruction::transfer(
+        &payer,
+        &commenter.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            fund_commenter_ix,
+            register_movie_ix(&program_id, &payer, MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer, &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer),
+        &[&context.payer, &admin],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(add_movie_review_tx).await?;
+
+    // fills the first page, waiting out the cooldown between comments
+    for count in 0..ReviewCommentPageState::COMMENTS_PER_PAGE as u64 {
+        let add_comment_tx = Transaction::new_signed_with_payer(
+            &[add_comment_ix(&program_id, &payer, &movie_review_account, count, &format!("Comment #{count}"))?],
+            Some(&payer),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(add_comment_tx).await?;
+
+        let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+        clock.unix_timestamp += CommenterProfileState::BASE_COMMENT_COOLDOWN_SECONDS;
+        context.set_sysvar(&clock);
+    }
+
+    let page_boundary = ReviewCommentPageState::COMMENTS_PER_PAGE as u64;
+
+    let add_comment_past_page_boundary_tx = Transaction::new_signed_with_payer(
+        &[add_comment_ix(&program_id, &commenter.pubkey(), &movie_review_account, page_boundary, "Page two.")?],
+        Some(&payer),
+        &[&context.payer, &commenter],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(add_comment_past_page_boundary_tx).await?;
+
+    let comment_pages = get_comment_page_addresses(&program_id, &movie_review_account, page_boundary + 1);
+
+    assert_eq!(comment_pages.len(), 2);
+
+    let first_page_state = context.banks_client.get_account(comment_pages[0]).await?.unwrap();
+    let first_page_state = try_from_slice_unchecked::<ReviewCommentPageState>(&first_page_state.data)?;
+
+    assert_eq!(first_page_state.payer, payer);
+    assert_eq!(first_page_state.comments.len(), ReviewCommentPageState::COMMENTS_PER_PAGE);
+    assert_eq!(first_page_state.comments[0], get_comment_address(&program_id, &movie_review_account, 0));
+
+    let second_page_state = context.banks_client.get_account(comment_pages[1]).await?.unwrap();
+    let second_page_state = try_from_slice_unchecked::<ReviewCommentPageState>(&second_page_state.data)?;
+
+    assert_eq!(second_page_state.page, 1);
+    assert_eq!(second_page_state.payer, commenter.pubkey());
+    assert_eq!(
+        second_page_state.comments,
+        vec![get_comment_address(&program_id, &movie_review_account, page_boundary)],
+    );
+
+    let delete_without_pages_tx = Transaction::new_signed_with_payer(
+        &[delete_movie_review_ix(&program_id, &payer, &movie_review_account, &[payer])],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let delete_without_pages_tx_result =
+        context.banks_client.process_transaction(delete_without_pages_tx).await;
+
+    assert!(delete_without_pages_tx_result.is_err());
+
+    let delete_with_wrong_page_payer_tx = Transaction::new_signed_with_payer(
+        &[delete_movie_review_ix(&program_id, &payer, &movie_review_account, &[payer, payer])],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let delete_with_wrong_page_payer_tx_result =
+        context.banks_client.process_transaction(delete_with_wrong_page_payer_tx).await;
+
+    assert_eq!(review_error(delete_with_wrong_page_payer_tx_result), Some(ReviewError::IncorrectAccountError));
+
+    let commenter_balance = context.banks_client.get_balance(commenter.pubkey()).await?;
+    let second_page_rent = context.banks_client.get_balance(comment_pages[1]).await?;
+
+    let delete_movie_review_tx = Transaction::new_signed_with_payer(
+        &[delete_movie_review_ix(&program_id, &payer, &movie_review_account, &[payer, commenter.pubkey()])],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(delete_movie_review_tx).await?;
+
+    assert!(context.banks_client.get_account(comment_pages[0]).await?.is_none());
+    assert!(context.banks_client.get_account(comment_pages[1]).await?.is_none());
+
+    assert_eq!(
+        context.banks_client.get_balance(commenter.pubkey()).await?,
+        commenter_balance + second_page_rent,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn comment_on_re_added_movie_review_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let commenter = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+    let comment_account = get_comment_address(&program_id, &movie_review_account, 0);
+    let comment_page = get_comment_page_address(&program_id, &movie_review_account, 0);
+
+    let fund_commenter_ix = solana_system_interface::instruction::transfer(
+        &payer.pubkey(),
+        &commenter.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let add_comment_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            fund_commenter_ix,
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+            add_comment_ix(&program_id, &commenter.pubkey(), &movie_review_account, 0, "First!")?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &commenter, &admin],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(add_comment_tx).await?;
+
+    let delete_and_close_comments_tx = Transaction::new_signed_with_payer(
+        &[
+            delete_movie_review_ix(&program_id, &payer.pubkey(), &movie_review_account, &[commenter.pubkey()]),
+            close_comments_ix(&program_id, &movie_review_account, &[(comment_account, commenter.pubkey())]),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(delete_and_close_comments_tx).await?;
+
+    assert!(banks_client.get_account(comment_page).await?.is_none());
+
+    let re_add_and_comment_tx = Transaction::new_signed_with_payer(
+        &[
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(4), "Second thoughts.")?,
+            add_comment_ix(&program_id, &payer.pubkey(), &movie_review_account, 0, "First again!")?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(re_add_and_comment_tx).await?;
+
+    let comment_page_state = banks_client.get_account(comment_page).await?.unwrap();
+    let comment_page_state = try_from_slice_unchecked::<ReviewCommentPageState>(&comment_page_state.data)?;
+
+    assert_eq!(comment_page_state.payer, payer.pubkey());
+    assert_eq!(comment_page_state.comments, vec![comment_account]);
+
+    let comment_state = banks_client.get_account(comment_account).await?.unwrap();
+    let comment_state = try_from_slice_unchecked::<ReviewCommentState>(&comment_state.data)?;
+
+    assert_eq!(comment_state.commenter, payer.pubkey());
+    assert_eq!(comment_state.comment, "First again!");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transfer_and_accept_review_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let new_owner = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+            transfer_review_ix(&program_id, &payer.pubkey(), &movie_review_account, &new_owner.pubkey())?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
+    );
+
+    let add_movie_review_tx_result = banks_client.process_transaction(add_movie_review_tx).await;
+
+    assert!(add_movie_review_tx_result.is_ok());
+
+    let movie_review_account_state =
+        banks_client.get_account(movie_review_account).await?.unwrap();
+    let movie_review_account_state =
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
+
+    assert_eq!(movie_review_account_state.owner, payer.pubkey());
+    assert_eq!(movie_review_account_state.pending_owner, new_owner.pubkey());
+
+    let accept_review_tx = Transaction::new_signed_with_payer(
+        &[accept_review_ix(&program_id, &new_owner.pubkey(), &movie_review_account)],
+        Some(&payer.pubkey()),
+        &[&payer, &new_owner],
+        recent_blockhash,
+    );
+
+    let accept_review_tx_result = banks_client.process_transaction(accept_review_tx).await;
+
+    assert!(accept_review_tx_result.is_ok());
+
+    let movie_review_account_state =
+        banks_client.get_account(movie_review_account).await?.unwrap();
+    let movie_review_account_state =
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
+
+    assert_eq!(movie_review_account_state.reviewer, payer.pubkey());
+    assert_eq!(movie_review_account_state.owner, new_owner.pubkey());
+    assert_eq!(movie_review_account_state.pending_owner, Pubkey::default());
+
+    let update_by_old_owner_tx = Transaction::new_signed_with_payer(
+        &[update_movie_review_ix(&program_id, &payer.pubkey(), &movie_review_account, uniform_ratings(1), "Mine?")?],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let update_by_old_owner_tx_result =
+        banks_client.process_transaction(update_by_old_owner_tx).await;
+
+    assert!(update_by_old_owner_tx_result.is_err());
+
+    let update_by_new_owner_tx = Transaction::new_signed_with_payer(
+        &[update_movie_review_ix(&program_id, &new_owner.pubkey(), &movie_review_account, uniform_ratings(4), "Mine.")?],
+        Some(&payer.pubkey()),
+        &[&payer, &new_owner],
+        recent_blockhash,
+    );
+
+    let update_by_new_owner_tx_result =
+        banks_client.process_transaction(update_by_new_owner_tx).await;
+
+    assert!(update_by_new_owner_tx_result.is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn accept_review_ix_with_unauthorized_signer_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let new_owner = Keypair::new();
+    let intruder = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(add_movie_review_tx).await?;
+
+    let accept_without_transfer_tx = Transaction::new_signed_with_payer(
+        &[accept_review_ix(&program_id, &new_owner.pubkey(), &movie_review_account)],
+        Some(&payer.pubkey()),
+        &[&payer, &new_owner],
+        recent_blockhash,
+    );
+
+    let accept_without_transfer_tx_result =
+        banks_client.process_transaction(accept_without_transfer_tx).await;
+
+    assert!(accept_without_transfer_tx_result.is_err());
+
+    let transfer_by_intruder_tx = Transaction::new_signed_with_payer(
+        &[transfer_review_ix(&program_id, &intruder.pubkey(), &movie_review_account, &intruder.pubkey())?],
+        Some(&payer.pubkey()),
+        &[&payer, &intruder],
+        recent_blockhash,
+    );
+
+    let transfer_by_intruder_tx_result =
+        banks_client.process_transaction(transfer_by_intruder_tx).await;
+
+    assert!(transfer_by_intruder_tx_result.is_err());
+
+    let transfer_review_tx = Transaction::new_signed_with_payer(
+        &[transfer_review_ix(&program_id, &payer.pubkey(), &movie_review_account, &new_owner.pubkey())?],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(transfer_review_tx).await?;
+
+    let accept_by_intruder_tx = Transaction::new_signed_with_payer(
+        &[accept_review_ix(&program_id, &intruder.pubkey(), &movie_review_account)],
+        Some(&payer.pubkey()),
+        &[&payer, &intruder],
+        recent_blockhash,
+    );
+
+    let accept_by_intruder_tx_result =
+        banks_client.process_transaction(accept_by_intruder_tx).await;
+
+    assert!(accept_by_intruder_tx_result.is_err());
+
+    let movie_review_account_state =
+        banks_client.get_account(movie_review_account).await?.unwrap();
+    let movie_review_account_state =
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
+
+    assert_eq!(movie_review_account_state.owner, payer.pubkey());
+    assert_eq!(movie_review_account_state.pending_owner, new_owner.pubkey());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_v1_accounts_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let reviewer = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &reviewer.pubkey(), &movie);
+    let comment_counter = get_comment_counter_address(&program_id, &movie_review_account);
+    let comment_account = get_comment_address(&program_id, &movie_review_account, 0);
+
+    let movie_v1 = borsh::to_vec(&MovieStateV1 {
+        discriminator: MovieState::DISCRIMINATOR.to_string(),
+        is_initialized: true,
+        imdb_id: MOVIE_IMDB_ID.to_string(),
+        title: MOVIE_TITLE.to_string(),
+    })?;
+
+    let mut movie_review_v1 = borsh::to_vec(&ReviewStateV1 {
+        discriminator: ReviewState::DISCRIMINATOR.to_string(),
+        is_initialized: true,
+        reviewer: reviewer.pubkey(),
+        movie,
+        rating: 5,
+        owner: reviewer.pubkey(),
+        pending_owner: Pubkey::default(),
+        title: MOVIE_TITLE.to_string(),
+        description: MOVIE_DESCRIPTION.to_string(),
+    })?;
+    movie_review_v1.resize(ReviewState::MAX_SPACE, 0);
+
+    let comment_counter_v1 = borsh::to_vec(&ReviewCommentCounterStateV1 {
+        discriminator: ReviewCommentCounterState::DISCRIMINATOR.to_string(),
+        is_initialized: true,
+        counter: 1,
+    })?;
+
+    let comment_v1 = borsh::to_vec(&ReviewCommentStateV1 {
+        discriminator: ReviewCommentState::DISCRIMINATOR.to_string(),
+        is_initialized: true,
+        review: movie_review_account,
+        commenter: reviewer.pubkey(),
+        count: 0,
+        comment: String::from("Totally agree!"),
+    })?;
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    for (address, data) in [
+        (movie, movie_v1.clone()),
+        (movie_review_account, movie_review_v1.clone()),
+        (comment_counter, comment_counter_v1.clone()),
+        (comment_account, comment_v1.clone()),
+    ] {
+        program_test.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: program_id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    program_test.add_account(
+        reviewer.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            owner: system_program_id(),
+            ..Account::default()
+        },
+    );
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let update_before_migration_tx = Transaction::new_signed_with_payer(
+        &[update_movie_review_ix(&program_id, &reviewer.pubkey(), &movie_review_account, uniform_ratings(3), "Not bad.")?],
+        Some(&payer.pubkey()),
+        &[&payer, &reviewer],
+        recent_blockhash,
+    );
+
+    let update_before_migration_tx_result =
+        banks_client.process_transaction(update_before_migration_tx).await;
+
+    assert!(update_before_migration_tx_result.is_err());
+
+    let migrate_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            migrate_account_ix(&program_id, &payer.pubkey(), &movie),
+            migrate_account_ix(&program_id, &payer.pubkey(), &movie_review_account),
+            migrate_account_ix(&program_id, &payer.pubkey(), &comment_counter),
+            migrate_account_ix(&program_id, &payer.pubkey(), &comment_account),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &admin],
+        recent_blockhash,
+    );
+
+    let migrate_tx_result = banks_client.process_transaction(migrate_tx).await;
+
+    assert!(migrate_tx_result.is_ok());
+
+    let movie_account_state = banks_client.get_account(movie).await?.unwrap();
+
+    assert_eq!(movie_account_state.data.len(), movie_v1.len() + 1);
+    assert_eq!(movie_account_state.data.len(), MovieState::space(MOVIE_IMDB_ID, MOVIE_TITLE));
+    assert!(movie_account_state.lamports >= Rent::default().minimum_balance(movie_account_state.data.len()));
+
+    let movie_account_state =
+        try_from_slice_unchecked::<MovieState>(&movie_account_state.data)?;
+
+    assert_eq!(movie_account_state.version, STATE_VERSION);
+    assert_eq!(movie_account_state.is_initialized, true);
+    assert_eq!(movie_account_state.imdb_id, MOVIE_IMDB_ID);
+    assert_eq!(movie_account_state.title, MOVIE_TITLE);
+
+    let movie_review_account_state =
+        banks_client.get_account(movie_review_account).await?.unwrap();
+
+    assert_eq!(movie_review_account_state.data.len(), ReviewState::MAX_SPACE);
+
+    let movie_review_account_state =
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
+
+    assert_eq!(movie_review_account_state.version, STATE_VERSION);
+    assert_eq!(movie_review_account_state.is_initialized, true);
+    assert_eq!(movie_review_account_state.reviewer, reviewer.pubkey());
+    assert_eq!(movie_review_account_state.movie, movie);
+    assert_eq!(movie_review_account_state.owner, reviewer.pubkey());
+    assert_eq!(movie_review_account_state.ratings, uniform_ratings(5));
+    assert!(movie_review_account_state.created_at > 0);
+    assert_eq!(movie_review_account_state.updated_at, movie_review_account_state.created_at);
+    assert_eq!(movie_review_account_state.title, MOVIE_TITLE);
+    assert_eq!(movie_review_account_state.description, MOVIE_DESCRIPTION);
+
+    let comment_counter_state =
+        banks_client.get_account(comment_counter).await?.unwrap();
+
+    assert_eq!(comment_counter_state.data.len(), ReviewCommentCounterState::SPACE);
+
+    let comment_counter_state =
+        try_from_slice_unchecked::<ReviewCommentCounterState>(&comment_counter_state.data)?;
+
+    assert_eq!(comment_counter_state.version, STATE_VERSION);
+    assert_eq!(comment_counter_state.counter, 1);
+
+    let comment_account_state =
+        banks_client.get_account(comment_account).await?.unwrap();
+
+    assert_eq!(comment_account_state.data.len(), comment_v1.len() + 1 + 8 + 8 + 8 + 8);
+
+    let comment_account_state =
+        try_from_slice_unchecked::<ReviewCommentState>(&comment_account_state.data)?;
+
+    assert_eq!(comment_account_state.version, STATE_VERSION);
+    assert_eq!(comment_account_state.review, movie_review_account);
+    assert_eq!(comment_account_state.commenter, reviewer.pubkey());
+    assert_eq!(comment_account_state.comment, "Totally agree!");
+    assert_eq!(comment_account_state.upvotes, 0);
+
+    let update_after_migration_tx = Transaction::new_signed_with_payer(
+        &[update_movie_review_ix(&program_id, &reviewer.pubkey(), &movie_review_account, uniform_ratings(4), "Better than I remembered.")?],
+        Some(&payer.pubkey()),
+        &[&payer, &reviewer],
+        recent_blockhash,
+    );
+
+    let update_after_migration_tx_result =
+        banks_client.process_transaction(update_after_migration_tx).await;
+
+    assert!(update_after_migration_tx_result.is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_unknown_version_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
+    let mut movie_data = borsh::to_vec(&MovieStateV1 {
+        discriminator: MovieState::DISCRIMINATOR.to_string(),
+        is_initialized: true,
+        imdb_id: MOVIE_IMDB_ID.to_string(),
+        title: MOVIE_TITLE.to_string(),
+    })?;
+
+    // the version byte follows the borsh-encoded discriminator
+    movie_data[4 + MovieState::DISCRIMINATOR.len()] = STATE_VERSION + 1;
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    program_test.add_account(
+        movie,
+        Account {
+            lamports: Rent::default().minimum_balance(movie_data.len()),
+            data: movie_data.clone(),
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let migrate_tx = Transaction::new_signed_with_payer(
+        &[migrate_account_ix(&program_id, &payer.pubkey(), &movie)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let migrate_tx_result = banks_client.process_transaction(migrate_tx).await;
+
+    assert_eq!(review_error(migrate_tx_result), Some(ReviewError::UnknownAccountVersion));
+    assert_eq!(banks_client.get_account(movie).await?.unwrap().data, movie_data);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stake_and_unstake_on_review_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let mut context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer, &movie);
+    let stake_account = get_stake_address(&program_id, &movie_review_account, &payer);
+    let escrow_ata = get_reward_token_address(&program_id, &get_escrow_authority_address(&program_id, &movie_review_account));
+    let user_ata = get_reward_token_address(&program_id, &payer);
+
+    let stake_amount = 4 * LAMPORTS_PER_SOL;
+
+    let stake_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer, MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer, &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+            stake_on_review_ix(&program_id, &payer, &movie_review_account, stake_amount)?,
+        ],
+        Some(&payer),
+        &[&context.payer, &admin],
+        context.last_blockhash,
+    );
+
+    let stake_tx_result = context.banks_client.process_transaction(stake_tx).await;
+
+    assert!(stake_tx_result.is_ok());
+
+    let escrow = context.banks_client.get_account(escrow_ata).await?.unwrap();
+    let escrow = spl_token::state::Account::unpack(&escrow.data)?;
+
+    assert_eq!(escrow.amount, stake_amount);
+    assert_eq!(escrow.owner, get_escrow_authority_address(&program_id, &movie_review_account));
+
+    let ata = context.banks_client.get_account(user_ata).await?.unwrap();
+    let ata = spl_token::state::Account::unpack(&ata.data)?;
+
+    assert_eq!(ata.amount, 10 * LAMPORTS_PER_SOL - stake_amount);
+
+    let stake_account_state = context.banks_client.get_account(stake_account).await?.unwrap();
+
+    assert_eq!(stake_account_state.data.len(), ReviewStakeState::SPACE);
+
+    let stake_account_state =
+        try_from_slice_unchecked::<ReviewStakeState>(&stake_account_state.data)?;
+
+    assert_eq!(stake_account_state.review, movie_review_account);
+    assert_eq!(stake_account_state.staker, payer);
+    assert_eq!(stake_account_state.amount, stake_amount);
+
+    let movie_review_account_state =
+        context.banks_client.get_account(movie_review_account).await?.unwrap();
+    let movie_review_account_state =
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
+
+    assert_eq!(movie_review_account_state.staked_amount, stake_amount);
+
+    let delete_while_staked_tx = Transaction::new_signed_with_payer(
+        &[delete_movie_review_ix(&program_id, &payer, &movie_review_account, &[])],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let delete_while_staked_tx_result =
+        context.banks_client.process_transaction(delete_while_staked_tx).await;
+
+    assert!(delete_while_staked_tx_result.is_err());
+
+    let unstake_during_cooldown_tx = Transaction::new_signed_with_payer(
+        &[unstake_from_review_ix(&program_id, &payer, &movie_review_account)],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let unstake_during_cooldown_tx_result =
+        context.banks_client.process_transaction(unstake_during_cooldown_tx).await;
+
+    assert!(unstake_during_cooldown_tx_result.is_err());
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+    clock.unix_timestamp += ReviewStakeState::UNSTAKE_COOLDOWN_SECONDS;
+    context.set_sysvar(&clock);
+
+    let recent_blockhash =
+        context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await?;
+
+    let unstake_tx = Transaction::new_signed_with_payer(
+        &[unstake_from_review_ix(&program_id, &payer, &movie_review_account)],
+        Some(&payer),
+        &[&context.payer],
+        recent_blockhash,
+    );
+
+    let unstake_tx_result = context.banks_client.process_transaction(unstake_tx).await;
+
+    assert!(unstake_tx_result.is_ok());
+
+    let ata = context.banks_client.get_account(user_ata).await?.unwrap();
+    let ata = spl_token::state::Account::unpack(&ata.data)?;
+
+    assert_eq!(ata.amount, 10 * LAMPORTS_PER_SOL);
+
+    let escrow = context.banks_client.get_account(escrow_ata).await?.unwrap();
+    let escrow = spl_token::state::Account::unpack(&escrow.data)?;
+
+    assert_eq!(escrow.amount, 0);
+
+    assert!(context.banks_client.get_account(stake_account).await?.is_none());
+
+    let movie_review_account_state =
+        context.banks_client.get_account(movie_review_account).await?.unwrap();
+    let movie_review_account_state =
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
+
+    assert_eq!(movie_review_account_state.staked_amount, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vote_on_comment_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let commenter = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer.pubkey(), &movie);
+    let comment_account = get_comment_address(&program_id, &movie_review_account, 0);
+    let commenter_profile = get_commenter_profile_address(&program_id, &commenter.pubkey());
+    let vote_receipt = get_vote_receipt_address(&program_id, &comment_account, &payer.pubkey());
+
+    let fund_commenter_ix = solana_system_interface::instruction::transfer(
+        &payer.pubkey(),
+        &commenter.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let add_comment_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            fund_commenter_ix,
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+            add_comment_ix(&program_id, &commenter.pubkey(), &movie_review_account, 0, "Agreed.")?,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &commenter, &admin],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(add_comment_tx).await?;
+
+    let commenter_profile_state = banks_client.get_account(commenter_profile).await?.unwrap();
+
+    assert_eq!(commenter_profile_state.data.len(), CommenterProfileState::SPACE);
+
+    let commenter_profile_state =
+        try_from_slice_unchecked::<CommenterProfileState>(&commenter_profile_state.data)?;
+
+    assert_eq!(commenter_profile_state.commenter, commenter.pubkey());
+    assert_eq!(commenter_profile_state.reputation, 0);
+
+    let self_vote_tx = Transaction::new_signed_with_payer(
+        &[
+            vote_on_comment_ix(
+                &program_id,
+                &commenter.pubkey(),
+                &comment_account,
+                &commenter.pubkey(),
+            ),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &commenter],
+        recent_blockhash,
+    );
+
+    let self_vote_tx_result = banks_client.process_transaction(self_vote_tx).await;
+
+    assert!(self_vote_tx_result.is_err());
+
+    let vote_tx = Transaction::new_signed_with_payer(
+        &[vote_on_comment_ix(&program_id, &payer.pubkey(), &comment_account, &commenter.pubkey())],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let vote_tx_result = banks_client.process_transaction(vote_tx).await;
+
+    assert!(vote_tx_result.is_ok());
+
+    let vote_receipt_state = banks_client.get_account(vote_receipt).await?.unwrap();
+    let vote_receipt_state =
+        try_from_slice_unchecked::<CommentVoteReceiptState>(&vote_receipt_state.data)?;
+
+    assert_eq!(vote_receipt_state.comment, comment_account);
+    assert_eq!(vote_receipt_state.voter, payer.pubkey());
+
+    let comment_account_state = banks_client.get_account(comment_account).await?.unwrap();
+    let comment_account_state =
+        try_from_slice_unchecked::<ReviewCommentState>(&comment_account_state.data)?;
+
+    assert_eq!(comment_account_state.upvotes, 1);
+
+    let commenter_profile_state = banks_client.get_account(commenter_profile).await?.unwrap();
+    let commenter_profile_state =
+        try_from_slice_unchecked::<CommenterProfileState>(&commenter_profile_state.data)?;
+
+    assert_eq!(commenter_profile_state.reputation, 1);
+
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await?;
+
+    let double_vote_tx = Transaction::new_signed_with_payer(
+        &[vote_on_comment_ix(&program_id, &payer.pubkey(), &comment_account, &commenter.pubkey())],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let double_vote_tx_result = banks_client.process_transaction(double_vote_tx).await;
+
+    assert!(double_vote_tx_result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_comment_ix_rate_limit_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let mut context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer, &movie);
+
+    let add_comment_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer, MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer, &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+            add_comment_ix(&program_id, &payer, &movie_review_account, 0, "First!")?,
+        ],
+        Some(&payer),
+        &[&context.payer, &admin],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(add_comment_tx).await?;
+
+    let add_comment_too_soon_tx = Transaction::new_signed_with_payer(
+        &[add_comment_ix(&program_id, &payer, &movie_review_account, 1, "Second!")?],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let add_comment_too_soon_tx_result =
+        context.banks_client.process_transaction(add_comment_too_soon_tx).await;
+
+    assert!(add_comment_too_soon_tx_result.is_err());
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+    clock.unix_timestamp += CommenterProfileState::BASE_COMMENT_COOLDOWN_SECONDS;
+    context.set_sysvar(&clock);
+
+    let recent_blockhash =
+        context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await?;
+
+    let add_comment_after_cooldown_tx = Transaction::new_signed_with_payer(
+        &[add_comment_ix(&program_id, &payer, &movie_review_account, 1, "Second!")?],
+        Some(&payer),
+        &[&context.payer],
+        recent_blockhash,
+    );
+
+    let add_comment_after_cooldown_tx_result =
+        context.banks_client.process_transaction(add_comment_after_cooldown_tx).await;
+
+    assert!(add_comment_after_cooldown_tx_result.is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reward_emission_halving_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let mut context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+    let another_reviewer = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer, &movie);
+
+    let fund_another_reviewer_ix = solana_system_interface::instruction::transfer(
+        &payer,
+        &another_reviewer.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            fund_another_reviewer_ix,
+            register_movie_ix(&program_id, &payer, MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer, &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer),
+        &[&context.payer, &admin],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(add_movie_review_tx).await?;
+
+    let ata = context.banks_client.get_account(get_reward_token_address(&program_id, &payer)).await?.unwrap();
+    let ata = spl_token::state::Account::unpack(&ata.data)?;
+
+    assert_eq!(ata.amount, REVIEW_REWARD);
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+    clock.unix_timestamp += ConfigState::DEFAULT_HALVING_PERIOD_SECONDS;
+    context.set_sysvar(&clock);
+
+    let recent_blockhash =
+        context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await?;
+
+    let add_movie_review_after_halving_tx = Transaction::new_signed_with_payer(
+        &[
+            add_movie_review_ix(&program_id, &another_reviewer.pubkey(), &movie, uniform_ratings(4), "Still great.")?,
+            add_comment_ix(&program_id, &another_reviewer.pubkey(), &movie_review_account, 0, "Agreed.")?,
+        ],
+        Some(&payer),
+        &[&context.payer, &another_reviewer],
+        recent_blockhash,
+    );
+
+    context.banks_client.process_transaction(add_movie_review_after_halving_tx).await?;
+
+    let ata = context.banks_client
+        .get_account(get_reward_token_address(&program_id, &another_reviewer.pubkey()))
+        .await?
+        .unwrap();
+    let ata = spl_token::state::Account::unpack(&ata.data)?;
+
+    assert_eq!(ata.amount, REVIEW_REWARD / 2 + COMMENT_REWARD / 2);
+
+    let config_state = context.banks_client.get_account(get_config_address(&program_id)).await?.unwrap();
+    let config_state = try_from_slice_unchecked::<ConfigState>(&config_state.data)?;
+
+    assert_eq!(config_state.reviews_rewarded, 2);
+    assert_eq!(config_state.halvings(clock.unix_timestamp), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn refund_comment_deposit_ix_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let mut context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+    let commenter = Keypair::new();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer, &movie);
+    let comment_account = get_comment_address(&program_id, &movie_review_account, 0);
+    let comment = "Agreed.";
+
+    let fund_commenter_ix = solana_system_interface::instruction::transfer(
+        &payer,
+        &commenter.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let add_comment_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            fund_commenter_ix,
+            register_movie_ix(&program_id, &payer, MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer, &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+            add_comment_ix(&program_id, &commenter.pubkey(), &movie_review_account, 0, comment)?,
+        ],
+        Some(&payer),
+        &[&context.payer, &commenter, &admin],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(add_comment_tx).await?;
+
+    let comment_rent = Rent::default().minimum_balance(ReviewCommentState::space(comment));
+
+    let comment_account_state = context.banks_client.get_account(comment_account).await?.unwrap();
+
+    assert_eq!(comment_account_state.lamports, comment_rent + ConfigState::DEFAULT_COMMENT_DEPOSIT);
+
+    let comment_account_state =
+        try_from_slice_unchecked::<ReviewCommentState>(&comment_account_state.data)?;
+
+    assert_eq!(comment_account_state.deposit, ConfigState::DEFAULT_COMMENT_DEPOSIT);
+
+    let refund_too_early_tx = Transaction::new_signed_with_payer(
+        &[refund_comment_deposit_ix(&program_id, &commenter.pubkey(), &comment_account)],
+        Some(&payer),
+        &[&context.payer, &commenter],
+        context.last_blockhash,
+    );
+
+    let refund_too_early_tx_result =
+        context.banks_client.process_transaction(refund_too_early_tx).await;
+
+    assert!(refund_too_early_tx_result.is_err());
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+    clock.unix_timestamp += ConfigState::DEFAULT_COMMENT_DISPUTE_WINDOW_SECONDS;
+    context.set_sysvar(&clock);
+
+    let commenter_balance = context.banks_client.get_balance(commenter.pubkey()).await?;
+
+    let recent_blockhash =
+        context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await?;
+
+    let refund_tx = Transaction::new_signed_with_payer(
+        &[refund_comment_deposit_ix(&program_id, &commenter.pubkey(), &comment_account)],
+        Some(&payer),
+        &[&context.payer, &commenter],
+        recent_blockhash,
+    );
+
+    let refund_tx_result = context.banks_client.process_transaction(refund_tx).await;
+
+    assert!(refund_tx_result.is_ok());
+
+    assert_eq!(
+        context.banks_client.get_balance(commenter.pubkey()).await?,
+        commenter_balance + ConfigState::DEFAULT_COMMENT_DEPOSIT,
+    );
+    assert_eq!(context.banks_client.get_balance(comment_account).await?, comment_rent);
+
+    let recent_blockhash =
+        context.banks_client.get_new_latest_blockhash(&recent_blockhash).await?;
+
+    let refund_again_tx = Transaction::new_signed_with_payer(
+        &[refund_comment_deposit_ix(&program_id, &commenter.pubkey(), &comment_account)],
+        Some(&payer),
+        &[&context.payer, &commenter],
+        recent_blockhash,
+    );
+
+    let refund_again_tx_result = context.banks_client.process_transaction(refund_again_tx).await;
+
+    assert!(refund_again_tx_result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn update_movie_review_ix_after_edit_window_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "program",
+        program_id,
+        processor!(process_instruction)
+    );
+
+    let admin = add_upgrade_authority(&mut program_test, &program_id);
+
+    let mut context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review_account = get_review_address(&program_id, &payer, &movie);
+
+    let add_movie_review_tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_token_mint_ix(&program_id, &admin.pubkey()),
+            register_movie_ix(&program_id, &payer, MOVIE_TITLE, MOVIE_IMDB_ID)?,
+            add_movie_review_ix(&program_id, &payer, &movie, uniform_ratings(5), MOVIE_DESCRIPTION)?,
+        ],
+        Some(&payer),
+        &[&context.payer, &admin],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(add_movie_review_tx).await?;
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await?;
+
+    let movie_review_account_state =
+        context.banks_client.get_account(movie_review_account).await?.unwrap();
+    let movie_review_account_state =
+        try_from_slice_unchecked::<ReviewState>(&movie_review_account_state.data)?;
+
+    assert_eq!(movie_review_account_state.created_at, clock.unix_timestamp);
+
+    let mut clock = clock;
+    clock.unix_timestamp += ConfigState::DEFAULT_REVIEW_EDIT_WINDOW_SECONDS + 1;
+    context.set_sysvar(&clock);
+
+    let recent_blockhash =
+        context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await?;
+
+    let update_movie_review_tx = Transaction::new_signed_with_payer(
+        &[update_movie_review_ix(&program_id, &payer, &movie_review_account, uniform_ratings(1), "Aged badly.")?],
+        Some(&payer),
+        &[&context.payer],
+        recent_blockhash,
+    );
+
+    let update_movie_review_tx_result =
+        context.banks_client.process_transaction(update_movie_review_tx).await;
+
+    assert_eq!(review_error(update_movie_review_tx_result), Some(ReviewError::EditWindowExpired));
+
+    Ok(())
+}
+
+// processor! doesn't deploy through the upgradeable loader, so the ProgramData
+// account naming the upgrade authority InitializeMint checks is added by hand.
+// The authority gets enough SOL to pay for the config and the mint.
+fn add_upgrade_authority(program_test: &mut ProgramTest, program_id: &Pubkey) -> Keypair {
+    let upgrade_authority = Keypair::new();
+
+    let mut data = vec![0; UpgradeableLoaderState::size_of_programdata_metadata()];
+    data[..4].copy_from_slice(&3u32.to_le_bytes());
+    data[12] = 1;
+    data[13..].copy_from_slice(upgrade_authority.pubkey().as_ref());
+
+    program_test.add_account(
+        get_program_data_address(program_id),
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: solana_sdk_ids::bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+
+    program_test.add_account(
+        upgrade_authority.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            owner: system_program_id(),
+            ..Account::default()
+        },
+    );
+
+    upgrade_authority
+}
+
+fn review_error(result: Result<(), BanksClientError>) -> Option<ReviewError> {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, error) => review_error_from_instruction_error(&error),
+        _ => None,
+    }
+}
+
+// The checks an RPC node runs for getProgramAccounts.
+fn filter_allows(filter: &RpcFilterType, data: &[u8]) -> bool {
+    match filter {
+        RpcFilterType::DataSize(size) => data.len() as u64 == *size,
+        RpcFilterType::Memcmp(compare) => compare.bytes_match(data),
+        RpcFilterType::TokenAccountState => false,
+    }
+}
+
+fn uniform_ratings(rating: u8) -> CategoryRatings {
+    CategoryRatings {
+        plot: rating,
+        acting: rating,
+        visuals: rating,
+        soundtrack: rating,
+    }
+}
@@ -0,0 +1,338 @@
+use litesvm::{types::TransactionResult, LiteSVM};
+
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use movie_review_client::{
+    decode::decode,
+    errors::review_error_from_instruction_error,
+    instructions::{
+        initialize_token_mint_ix, register_movie_ix, add_movie_review_ix, update_movie_review_ix,
+        add_comment_ix, vote_on_comment_ix,
+    },
+    pda::{
+        get_movie_address, get_review_address, get_comment_address, get_comment_counter_address,
+        get_commenter_profile_address, get_reward_token_address,
+    },
+};
+use program::error::ReviewError;
+use program::state::{
+    CategoryRatings, MovieState, ReviewState, ReviewCommentState, ReviewCommentCounterState, CommenterProfileState,
+};
+
+// LiteSVM runs the compiled program, so `cargo build-sbf` has to have been run
+// before these tests.
+const PROGRAM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/deploy/program.so");
+
+const MOVIE_TITLE: &str = "Interstellar";
+const MOVIE_IMDB_ID: &str = "tt0816692";
+const MOVIE_DESCRIPTION: &str = "Space, time and Hans Zimmer.";
+
+#[test]
+fn review_and_comment_flow_test() {
+    let (mut svm, program_id, payer) = setup();
+
+    let commenter = Keypair::new();
+    svm.airdrop(&commenter.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review = get_review_address(&program_id, &payer.pubkey(), &movie);
+    let comment = get_comment_address(&program_id, &movie_review, 0);
+
+    let ratings = CategoryRatings { plot: 5, acting: 4, visuals: 5, soundtrack: 5 };
+
+    send(
+        &mut svm,
+        &[
+            initialize_token_mint_ix(&program_id, &payer.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID).unwrap(),
+            add_movie_review_ix(&program_id, &payer.pubkey(), &movie, ratings, MOVIE_DESCRIPTION).unwrap(),
+            add_comment_ix(&program_id, &commenter.pubkey(), &movie_review, 0, "Agreed.").unwrap(),
+            vote_on_comment_ix(&program_id, &payer.pubkey(), &comment, &commenter.pubkey()),
+            update_movie_review_ix(&program_id, &payer.pubkey(), &movie_review, ratings, "Even better.").unwrap(),
+        ],
+        &payer,
+        &[&commenter],
+    ).unwrap();
+
+    let movie_state = decode::<MovieState>(&svm.get_account(&movie).unwrap().data).unwrap();
+
+    assert_eq!(movie_state.title, MOVIE_TITLE);
+
+    let movie_review_state = decode::<ReviewState>(&svm.get_account(&movie_review).unwrap().data).unwrap();
+
+    assert_eq!(movie_review_state.reviewer, payer.pubkey());
+    assert_eq!(movie_review_state.rating, 5);
+    assert_eq!(movie_review_state.ratings, ratings);
+    assert_eq!(movie_review_state.description, "Even better.");
+
+    let counter_state = decode::<ReviewCommentCounterState>(
+        &svm.get_account(&get_comment_counter_address(&program_id, &movie_review)).unwrap().data,
+    ).unwrap();
+
+    assert_eq!(counter_state.counter, 1);
+
+    let comment_state = decode::<ReviewCommentState>(&svm.get_account(&comment).unwrap().data).unwrap();
+
+    assert_eq!(comment_state.commenter, commenter.pubkey());
+    assert_eq!(comment_state.upvotes, 1);
+
+    let commenter_profile_state = decode::<CommenterProfileState>(
+        &svm.get_account(&get_commenter_profile_address(&program_id, &commenter.pubkey())).unwrap().data,
+    ).unwrap();
+
+    assert_eq!(commenter_profile_state.reputation, 1);
+
+    let reviewer_ata = svm.get_account(&get_reward_token_address(&program_id, &payer.pubkey())).unwrap();
+    let reviewer_ata = spl_token::state::Account::unpack(&reviewer_ata.data).unwrap();
+
+    assert_eq!(reviewer_ata.amount, 10 * LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn add_movie_review_with_wrong_review_pda_test() {
+    let (mut svm, program_id, payer) = setup_with_movie();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
+    let mut add_movie_review_ix =
+        add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION).unwrap();
+    add_movie_review_ix.accounts[2] = AccountMeta::new(Pubkey::new_unique(), false);
+
+    let result = send(&mut svm, &[add_movie_review_ix], &payer, &[]);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn add_comment_with_wrong_comment_pda_test() {
+    let (mut svm, program_id, payer) = setup_with_movie();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review = get_review_address(&program_id, &payer.pubkey(), &movie);
+
+    send(
+        &mut svm,
+        &[add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION).unwrap()],
+        &payer,
+        &[],
+    ).unwrap();
+
+    let result = send(
+        &mut svm,
+        &[add_comment_ix(&program_id, &payer.pubkey(), &movie_review, 1, "Skipping ahead.").unwrap()],
+        &payer,
+        &[],
+    );
+
+    assert!(result.is_err());
+    assert!(svm.get_account(&get_comment_address(&program_id, &movie_review, 1)).is_none());
+}
+
+#[test]
+fn add_movie_review_with_wrong_token_program_test() {
+    let (mut svm, program_id, payer) = setup_with_movie();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
+    let mut add_movie_review_ix =
+        add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION).unwrap();
+    add_movie_review_ix.accounts[10] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+
+    let result = send(&mut svm, &[add_movie_review_ix], &payer, &[]);
+
+    assert_eq!(review_error(result), Some(ReviewError::IncorrectAccountError));
+}
+
+#[test]
+fn add_movie_review_with_invalid_rating_test() {
+    let (mut svm, program_id, payer) = setup_with_movie();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
+    for ratings in [
+        uniform_ratings(0),
+        uniform_ratings(6),
+        CategoryRatings { plot: 5, acting: 5, visuals: 5, soundtrack: 0 },
+    ] {
+        let result = send(
+            &mut svm,
+            &[add_movie_review_ix(&program_id, &payer.pubkey(), &movie, ratings, MOVIE_DESCRIPTION).unwrap()],
+            &payer,
+            &[],
+        );
+
+        assert_eq!(review_error(result), Some(ReviewError::InvalidRating));
+    }
+}
+
+#[test]
+fn register_movie_with_oversized_payload_test() {
+    let (mut svm, program_id, payer) = setup();
+
+    let long_title = "x".repeat(MovieState::MAX_SPACE);
+
+    let result = send(
+        &mut svm,
+        &[register_movie_ix(&program_id, &payer.pubkey(), &long_title, MOVIE_IMDB_ID).unwrap()],
+        &payer,
+        &[],
+    );
+
+    assert_eq!(review_error(result), Some(ReviewError::InvalidDataLength));
+}
+
+#[test]
+fn add_movie_review_with_oversized_description_test() {
+    let (mut svm, program_id, payer) = setup();
+
+    let long_title = "x".repeat(400);
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+
+    send(
+        &mut svm,
+        &[
+            initialize_token_mint_ix(&program_id, &payer.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), &long_title, MOVIE_IMDB_ID).unwrap(),
+        ],
+        &payer,
+        &[],
+    ).unwrap();
+
+    let longest_description = "x".repeat(ReviewState::MAX_SPACE - ReviewState::space(&long_title, ""));
+
+    let result = send(
+        &mut svm,
+        &[
+            add_movie_review_ix(
+                &program_id,
+                &payer.pubkey(),
+                &movie,
+                uniform_ratings(5),
+                &(longest_description + "x"),
+            ).unwrap(),
+        ],
+        &payer,
+        &[],
+    );
+
+    assert_eq!(review_error(result), Some(ReviewError::InvalidDataLength));
+}
+
+#[test]
+fn update_movie_review_by_non_owner_test() {
+    let (mut svm, program_id, payer) = setup_with_movie();
+
+    let intruder = Keypair::new();
+    svm.airdrop(&intruder.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    let movie = get_movie_address(&program_id, MOVIE_IMDB_ID);
+    let movie_review = get_review_address(&program_id, &payer.pubkey(), &movie);
+
+    send(
+        &mut svm,
+        &[add_movie_review_ix(&program_id, &payer.pubkey(), &movie, uniform_ratings(5), MOVIE_DESCRIPTION).unwrap()],
+        &payer,
+        &[],
+    ).unwrap();
+
+    let result = send(
+        &mut svm,
+        &[update_movie_review_ix(&program_id, &intruder.pubkey(), &movie_review, uniform_ratings(1), "Mine.").unwrap()],
+        &intruder,
+        &[],
+    );
+
+    assert_eq!(review_error(result), Some(ReviewError::NotReviewOwner));
+}
+
+fn setup() -> (LiteSVM, Pubkey, Keypair) {
+    let program_id = Pubkey::new_unique();
+    let payer = Keypair::new();
+
+    let mut svm = LiteSVM::new();
+
+    svm.add_program_from_file(program_id, PROGRAM_PATH).unwrap();
+    svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    // the program isn't deployed through the upgradeable loader, so the
+    // ProgramData account InitializeMint checks is made up, naming the payer
+    svm.set_account(get_program_data_address(&program_id), program_data_account(&payer.pubkey())).unwrap();
+
+    (svm, program_id, payer)
+}
+
+fn setup_with_movie() -> (LiteSVM, Pubkey, Keypair) {
+    let (mut svm, program_id, payer) = setup();
+
+    send(
+        &mut svm,
+        &[
+            initialize_token_mint_ix(&program_id, &payer.pubkey()),
+            register_movie_ix(&program_id, &payer.pubkey(), MOVIE_TITLE, MOVIE_IMDB_ID).unwrap(),
+        ],
+        &payer,
+        &[],
+    ).unwrap();
+
+    (svm, program_id, payer)
+}
+
+fn program_data_account(upgrade_authority: &Pubkey) -> Account {
+    let mut data = vec![0; UpgradeableLoaderState::size_of_programdata_metadata()];
+    data[..4].copy_from_slice(&3u32.to_le_bytes());
+    data[12] = 1;
+    data[13..].copy_from_slice(upgrade_authority.as_ref());
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: solana_sdk_ids::bpf_loader_upgradeable::id(),
+        ..Account::default()
+    }
+}
+
+// Expires the blockhash after every transaction so retries of an identical
+// instruction aren't rejected as already processed.
+#[allow(clippy::result_large_err)]
+fn send(svm: &mut LiteSVM, instructions: &[Instruction], payer: &Keypair, signers: &[&Keypair]) -> TransactionResult {
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &all_signers,
+        svm.latest_blockhash(),
+    );
+
+    let result = svm.send_transaction(tx);
+    svm.expire_blockhash();
+
+    result
+}
+
+fn review_error(result: TransactionResult) -> Option<ReviewError> {
+    match result.unwrap_err().err {
+        TransactionError::InstructionError(_, error) => review_error_from_instruction_error(&error),
+        _ => None,
+    }
+}
+
+fn uniform_ratings(rating: u8) -> CategoryRatings {
+    CategoryRatings {
+        plot: rating,
+        acting: rating,
+        visuals: rating,
+        soundtrack: rating,
+    }
+}
@@ -0,0 +1,305 @@
+use proptest::prelude::*;
+
+use solana_sdk::pubkey::Pubkey;
+
+use program::state::{
+    STATE_VERSION,
+    CategoryRatings,
+    ConfigState, MovieState, ReviewState, ReviewStakeState, ReviewCommentCounterState, ReviewCommentState,
+    ReviewCommentPageState, CommenterProfileState, CommentVoteReceiptState, ReviewRewardReceiptState,
+};
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn category_ratings() -> impl Strategy<Value = CategoryRatings> {
+    (1u8..=5, 1u8..=5, 1u8..=5, 1u8..=5).prop_map(|(plot, acting, visuals, soundtrack)| {
+        CategoryRatings { plot, acting, visuals, soundtrack }
+    })
+}
+
+fn review_state(
+    reviewer: Pubkey,
+    movie: Pubkey,
+    ratings: CategoryRatings,
+    owner: Pubkey,
+    title: String,
+    description: String,
+) -> ReviewState {
+    ReviewState {
+        discriminator: ReviewState::DISCRIMINATOR.to_string(),
+        version: STATE_VERSION,
+        is_initialized: true,
+        reviewer,
+        movie,
+        rating: ratings.overall(),
+        ratings,
+        owner,
+        pending_owner: Pubkey::default(),
+        staked_amount: 0,
+        created_at: 0,
+        updated_at: 0,
+        title,
+        description,
+    }
+}
+
+proptest! {
+    #[test]
+    fn movie_space_matches_serialized_len(
+        imdb_id in ".{0,32}",
+        title in ".{0,200}",
+    ) {
+        let movie = MovieState {
+            discriminator: MovieState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            imdb_id: imdb_id.clone(),
+            title: title.clone(),
+        };
+
+        prop_assert_eq!(MovieState::space(&imdb_id, &title), borsh::to_vec(&movie).unwrap().len());
+    }
+
+    #[test]
+    fn review_space_matches_serialized_len(
+        reviewer in pubkey(),
+        movie in pubkey(),
+        ratings in category_ratings(),
+        title in ".{0,100}",
+        description in ".{0,400}",
+    ) {
+        let review = review_state(reviewer, movie, ratings, reviewer, title.clone(), description.clone());
+
+        prop_assert_eq!(ReviewState::space(&title, &description), borsh::to_vec(&review).unwrap().len());
+    }
+
+    #[test]
+    fn overall_rating_is_within_category_range(ratings in category_ratings()) {
+        let categories = [ratings.plot, ratings.acting, ratings.visuals, ratings.soundtrack];
+
+        prop_assert!(ratings.is_valid());
+        prop_assert!(ratings.overall() >= *categories.iter().min().unwrap());
+        prop_assert!(ratings.overall() <= *categories.iter().max().unwrap());
+    }
+
+    #[test]
+    fn review_offsets_match_serialized_layout(
+        reviewer in pubkey(),
+        movie in pubkey(),
+        ratings in category_ratings(),
+        owner in pubkey(),
+        title in ".{0,100}",
+        description in ".{0,400}",
+    ) {
+        let data = borsh::to_vec(&review_state(reviewer, movie, ratings, owner, title, description)).unwrap();
+
+        prop_assert_eq!(&data[ReviewState::REVIEWER_OFFSET..][..32], reviewer.as_ref());
+        prop_assert_eq!(&data[ReviewState::MOVIE_OFFSET..][..32], movie.as_ref());
+        prop_assert_eq!(data[ReviewState::RATING_OFFSET], ratings.overall());
+        prop_assert_eq!(
+            &data[ReviewState::RATINGS_OFFSET..][..CategoryRatings::SPACE],
+            &[ratings.plot, ratings.acting, ratings.visuals, ratings.soundtrack],
+        );
+        prop_assert_eq!(&data[ReviewState::OWNER_OFFSET..][..32], owner.as_ref());
+        prop_assert_eq!(&data[ReviewState::PENDING_OWNER_OFFSET..][..32], &[0; 32]);
+        prop_assert_eq!(&data[ReviewState::STAKED_AMOUNT_OFFSET..][..8], &0u64.to_le_bytes());
+        prop_assert_eq!(&data[ReviewState::CREATED_AT_OFFSET..][..8], &0i64.to_le_bytes());
+        prop_assert_eq!(&data[ReviewState::UPDATED_AT_OFFSET..][..8], &0i64.to_le_bytes());
+    }
+
+    #[test]
+    fn review_max_space_fits_longest_accepted_description(
+        title in "[a-zA-Z0-9 ]{0,200}",
+    ) {
+        let longest_description_len = ReviewState::MAX_SPACE - ReviewState::space(&title, "");
+        let longest_description = "x".repeat(longest_description_len);
+
+        prop_assert_eq!(ReviewState::space(&title, &longest_description), ReviewState::MAX_SPACE);
+
+        let review = review_state(
+            Pubkey::default(), Pubkey::default(), CategoryRatings::default(), Pubkey::default(), title.clone(), longest_description.clone(),
+        );
+        let mut data = vec![0; ReviewState::MAX_SPACE];
+
+        prop_assert!(borsh::to_writer(&mut data[..], &review).is_ok());
+
+        let too_long_description = longest_description + "x";
+
+        prop_assert!(ReviewState::space(&title, &too_long_description) > ReviewState::MAX_SPACE);
+    }
+
+    #[test]
+    fn movie_max_space_fits_longest_accepted_title(
+        imdb_id in "tt[0-9]{7,8}",
+    ) {
+        let longest_title = "x".repeat(MovieState::MAX_SPACE - MovieState::space(&imdb_id, ""));
+
+        prop_assert_eq!(MovieState::space(&imdb_id, &longest_title), MovieState::MAX_SPACE);
+        prop_assert!(MovieState::space(&imdb_id, &(longest_title + "x")) > MovieState::MAX_SPACE);
+    }
+
+    #[test]
+    fn comment_space_matches_serialized_len(
+        review in pubkey(),
+        commenter in pubkey(),
+        count in any::<u64>(),
+        upvotes in any::<u64>(),
+        deposit in any::<u64>(),
+        created_at in any::<i64>(),
+        updated_at in any::<i64>(),
+        comment in ".{0,400}",
+    ) {
+        let comment_state = ReviewCommentState {
+            discriminator: ReviewCommentState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            review,
+            commenter,
+            count,
+            upvotes,
+            deposit,
+            created_at,
+            updated_at,
+            comment: comment.clone(),
+        };
+        let data = borsh::to_vec(&comment_state).unwrap();
+
+        prop_assert_eq!(ReviewCommentState::space(&comment), data.len());
+        prop_assert_eq!(&data[ReviewCommentState::REVIEW_OFFSET..][..32], review.as_ref());
+        prop_assert_eq!(&data[ReviewCommentState::COMMENTER_OFFSET..][..32], commenter.as_ref());
+    }
+
+    #[test]
+    fn stake_space_matches_serialized_len(
+        review in pubkey(),
+        staker in pubkey(),
+        amount in any::<u64>(),
+        last_staked_at in any::<i64>(),
+    ) {
+        let stake_state = ReviewStakeState {
+            discriminator: ReviewStakeState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            review,
+            staker,
+            amount,
+            last_staked_at,
+        };
+
+        prop_assert_eq!(ReviewStakeState::SPACE, borsh::to_vec(&stake_state).unwrap().len());
+    }
+
+    #[test]
+    fn full_comment_page_fits_space(
+        review in pubkey(),
+        page in any::<u64>(),
+        payer in pubkey(),
+        comments in prop::collection::vec(pubkey(), ReviewCommentPageState::COMMENTS_PER_PAGE),
+    ) {
+        let comment_page_state = ReviewCommentPageState {
+            discriminator: ReviewCommentPageState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            review,
+            page,
+            payer,
+            comments,
+        };
+
+        prop_assert_eq!(ReviewCommentPageState::SPACE, borsh::to_vec(&comment_page_state).unwrap().len());
+    }
+
+    #[test]
+    fn commenter_profile_space_matches_serialized_len(
+        commenter in pubkey(),
+        reputation in any::<u64>(),
+        last_comment_at in any::<i64>(),
+    ) {
+        let commenter_profile_state = CommenterProfileState {
+            discriminator: CommenterProfileState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            commenter,
+            reputation,
+            last_comment_at,
+        };
+
+        prop_assert_eq!(CommenterProfileState::SPACE, borsh::to_vec(&commenter_profile_state).unwrap().len());
+        prop_assert!((0..=CommenterProfileState::BASE_COMMENT_COOLDOWN_SECONDS)
+            .contains(&commenter_profile_state.comment_cooldown()));
+    }
+
+    #[test]
+    fn vote_receipt_space_matches_serialized_len(comment in pubkey(), voter in pubkey()) {
+        let vote_receipt_state = CommentVoteReceiptState {
+            discriminator: CommentVoteReceiptState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            comment,
+            voter,
+        };
+
+        prop_assert_eq!(CommentVoteReceiptState::SPACE, borsh::to_vec(&vote_receipt_state).unwrap().len());
+    }
+
+    #[test]
+    fn reward_receipt_space_matches_serialized_len(movie in pubkey(), reviewer in pubkey()) {
+        let reward_receipt_state = ReviewRewardReceiptState {
+            discriminator: ReviewRewardReceiptState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            movie,
+            reviewer,
+        };
+
+        prop_assert_eq!(ReviewRewardReceiptState::SPACE, borsh::to_vec(&reward_receipt_state).unwrap().len());
+    }
+
+    #[test]
+    fn config_space_matches_serialized_len(
+        admin in pubkey(),
+        emission_start_at in any::<i64>(),
+        halving_period_seconds in 1i64..,
+        reviews_per_halving in 1u64..,
+        reviews_rewarded in any::<u64>(),
+        comment_deposit in any::<u64>(),
+        comment_dispute_window_seconds in any::<i64>(),
+        review_edit_window_seconds in any::<i64>(),
+        now in any::<i64>(),
+    ) {
+        let config_state = ConfigState {
+            discriminator: ConfigState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            admin,
+            emission_start_at,
+            halving_period_seconds,
+            reviews_per_halving,
+            reviews_rewarded,
+            comment_deposit,
+            comment_dispute_window_seconds,
+            review_edit_window_seconds,
+        };
+
+        prop_assert_eq!(ConfigState::SPACE, borsh::to_vec(&config_state).unwrap().len());
+
+        let halvings = config_state.halvings(now);
+        let expected_reward = if halvings >= 64 { 0 } else { u64::MAX >> halvings };
+
+        prop_assert_eq!(config_state.reward(u64::MAX, now), expected_reward);
+    }
+
+    #[test]
+    fn counter_space_matches_serialized_len(counter in any::<u64>()) {
+        let counter_state = ReviewCommentCounterState {
+            discriminator: ReviewCommentCounterState::DISCRIMINATOR.to_string(),
+            version: STATE_VERSION,
+            is_initialized: true,
+            counter,
+        };
+
+        prop_assert_eq!(ReviewCommentCounterState::SPACE, borsh::to_vec(&counter_state).unwrap().len());
+    }
+}
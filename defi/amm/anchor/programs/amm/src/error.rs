@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Token mints must be passed in ascending order")]
+    UnsortedMints,
+    #[msg("Mint is not one of the pool's two mints")]
+    MintNotInPool,
+    #[msg("Funding amount must be greater than zero")]
+    ZeroLiquidityAmount,
+    #[msg("Fee must be below 10000 basis points (100%)")]
+    FeeTooHigh,
+    #[msg("Swap amount must be greater than zero")]
+    ZeroSwapAmount,
+    #[msg("Slippage tolerance exceeded: output amount is below the minimum specified")]
+    SlippageExceed,
+    #[msg("Initial liquidity must exceed the permanently locked minimum")]
+    InsufficientInitialLiquidity,
+}
@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, MintTo, Token, TokenAccount, TransferChecked},
+};
+
+use integer_sqrt::IntegerSquareRoot;
+
+use crate::{
+    error::AmmError,
+    state::{LiquidityPool, MINIMUM_LIQUIDITY},
+};
+
+#[derive(Accounts)]
+#[instruction(amount_a: u64, amount_b: u64, fee_bps: u16)]
+pub struct CreatePool<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + LiquidityPool::INIT_SPACE,
+        seeds = [b"pool", mint_a.key().as_ref(), mint_b.key().as_ref(), &fee_bps.to_le_bytes()],
+        bump,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    // the native program sorts the mints itself; here the caller does, which
+    // also rules out identical mints
+    #[account(constraint = mint_a.key() < mint_b.key() @ AmmError::UnsortedMints)]
+    pub mint_a: Box<Account<'info, Mint>>,
+    pub mint_b: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool,
+    )]
+    pub vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool,
+    )]
+    pub vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"lp_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = 9,
+        mint::authority = pool,
+    )]
+    pub mint_lp: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_lp,
+        associated_token::authority = user,
+    )]
+    pub user_ata_lp: Box<Account<'info, TokenAccount>>,
+
+    // holds the locked MINIMUM_LIQUIDITY
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = mint_lp,
+        associated_token::authority = pool,
+    )]
+    pub pool_ata_lp: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = user,
+    )]
+    pub user_ata_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = user,
+    )]
+    pub user_ata_b: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreatePool>, amount_a: u64, amount_b: u64, fee_bps: u16) -> Result<()> {
+    require!(amount_a > 0 && amount_b > 0, AmmError::ZeroLiquidityAmount);
+    // a 100% fee would leave nothing to swap
+    require!(fee_bps < 10_000, AmmError::FeeTooHigh);
+
+    // transfer amount_a from user_ata_a to vault_a
+    token::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata_a.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
+                to: ctx.accounts.vault_a.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount_a,
+        ctx.accounts.mint_a.decimals,
+    )?;
+
+    // transfer amount_b from user_ata_b to vault_b
+    token::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata_b.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
+                to: ctx.accounts.vault_b.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount_b,
+        ctx.accounts.mint_b.decimals,
+    )?;
+
+    let lp_amount = (amount_a as u128 * amount_b as u128).integer_sqrt() as u64;
+
+    require!(lp_amount > MINIMUM_LIQUIDITY, AmmError::InsufficientInitialLiquidity);
+
+    let mint_a = ctx.accounts.mint_a.key();
+    let mint_b = ctx.accounts.mint_b.key();
+    let fee_bytes = fee_bps.to_le_bytes();
+    let bump = [ctx.bumps.pool];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool", mint_a.as_ref(), mint_b.as_ref(), &fee_bytes, &bump]];
+
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint_lp.to_account_info(),
+                to: ctx.accounts.pool_ata_lp.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        MINIMUM_LIQUIDITY,
+    )?;
+
+    // mint the rest of the lp tokens to user_ata_lp
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint_lp.to_account_info(),
+                to: ctx.accounts.user_ata_lp.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        lp_amount - MINIMUM_LIQUIDITY,
+    )?;
+
+    ctx.accounts.pool.set_inner(LiquidityPool {
+        mint_a,
+        mint_b,
+        reserve_a: amount_a,
+        reserve_b: amount_b,
+        fee_bps,
+        bump: ctx.bumps.pool,
+    });
+
+    Ok(())
+}
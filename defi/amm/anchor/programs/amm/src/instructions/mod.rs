@@ -0,0 +1,12 @@
+pub mod create_pool;
+pub mod provide_liquidity;
+pub mod swap;
+pub mod withdraw_liquidity;
+
+// every module has its own `handler`, only the account structs are used via
+// these re-exports
+#[allow(ambiguous_glob_reexports)]
+pub use create_pool::*;
+pub use provide_liquidity::*;
+pub use swap::*;
+pub use withdraw_liquidity::*;
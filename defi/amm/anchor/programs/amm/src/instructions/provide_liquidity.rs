@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, TransferChecked};
+
+use crate::{error::AmmError, state::LiquidityPool};
+
+#[derive(Accounts)]
+pub struct ProvideLiquidity<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), &pool.fee_bps.to_le_bytes()],
+        bump = pool.bump,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub mint_a: Box<Account<'info, Mint>>,
+    pub mint_b: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool,
+    )]
+    pub vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool,
+    )]
+    pub vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"lp_mint", pool.key().as_ref()], bump)]
+    pub mint_lp: Box<Account<'info, Mint>>,
+
+    #[account(mut, token::mint = mint_lp)]
+    pub user_ata_lp: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint_a, token::authority = user)]
+    pub user_ata_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint_b, token::authority = user)]
+    pub user_ata_b: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(
+    ctx: Context<ProvideLiquidity>,
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+) -> Result<()> {
+    require!(amount_a_desired > 0 && amount_b_desired > 0, AmmError::ZeroLiquidityAmount);
+
+    let pool = &ctx.accounts.pool;
+
+    let reserve_a = pool.reserve_a as u128;
+    let reserve_b = pool.reserve_b as u128;
+    let amount_a_desired = amount_a_desired as u128;
+    let amount_b_desired = amount_b_desired as u128;
+
+    // deposit at the current ratio, capped by whichever side runs out first
+    let b_needed = amount_a_desired * reserve_b / reserve_a;
+
+    let (take_a, take_b) = if b_needed <= amount_b_desired {
+        (amount_a_desired, b_needed)
+    } else {
+        (amount_b_desired * reserve_a / reserve_b, amount_b_desired)
+    };
+
+    require!(
+        take_a >= amount_a_min as u128 && take_b >= amount_b_min as u128,
+        AmmError::SlippageExceed
+    );
+
+    let total_lp = ctx.accounts.mint_lp.supply as u128;
+    let lp_amount = core::cmp::min(take_a * total_lp / reserve_a, take_b * total_lp / reserve_b) as u64;
+
+    let take_a = u64::try_from(take_a).map_err(|_| ProgramError::ArithmeticOverflow)?;
+    let take_b = u64::try_from(take_b).map_err(|_| ProgramError::ArithmeticOverflow)?;
+
+    // transfer take_a from user_ata_a to vault_a
+    token::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata_a.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
+                to: ctx.accounts.vault_a.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        take_a,
+        ctx.accounts.mint_a.decimals,
+    )?;
+
+    // transfer take_b from user_ata_b to vault_b
+    token::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata_b.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
+                to: ctx.accounts.vault_b.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        take_b,
+        ctx.accounts.mint_b.decimals,
+    )?;
+
+    let fee_bytes = pool.fee_bps.to_le_bytes();
+    let bump = [pool.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), &fee_bytes, &bump]];
+
+    // mint lp tokens to user
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint_lp.to_account_info(),
+                to: ctx.accounts.user_ata_lp.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        lp_amount,
+    )?;
+
+    let pool = &mut ctx.accounts.pool;
+
+    pool.reserve_a = pool.reserve_a.checked_add(take_a).ok_or(ProgramError::ArithmeticOverflow)?;
+    pool.reserve_b = pool.reserve_b.checked_add(take_b).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+
+use crate::{error::AmmError, state::LiquidityPool};
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), &pool.fee_bps.to_le_bytes()],
+        bump = pool.bump,
+        constraint = (mint_in.key() == pool.mint_a && mint_out.key() == pool.mint_b)
+            || (mint_in.key() == pool.mint_b && mint_out.key() == pool.mint_a) @ AmmError::MintNotInPool,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub mint_in: Box<Account<'info, Mint>>,
+    pub mint_out: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_in,
+        associated_token::authority = pool,
+    )]
+    pub vault_in: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_out,
+        associated_token::authority = pool,
+    )]
+    pub vault_out: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint_in, token::authority = user)]
+    pub user_ata_in: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint_out)]
+    pub user_ata_out: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
+    require!(amount_in > 0, AmmError::ZeroSwapAmount);
+
+    let pool = &ctx.accounts.pool;
+    let a_to_b = ctx.accounts.mint_in.key() == pool.mint_a;
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (pool.reserve_a as u128, pool.reserve_b as u128)
+    } else {
+        (pool.reserve_b as u128, pool.reserve_a as u128)
+    };
+
+    let amount_in_post_fee = amount_in as u128 * (10_000 - pool.fee_bps as u128);
+    let amount_out =
+        (reserve_out * amount_in_post_fee / (reserve_in * 10_000 + amount_in_post_fee)) as u64;
+
+    require!(amount_out >= min_out, AmmError::SlippageExceed);
+
+    // transfer amount_in of mint_in from user_ata_in to vault_in
+    token::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata_in.to_account_info(),
+                mint: ctx.accounts.mint_in.to_account_info(),
+                to: ctx.accounts.vault_in.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount_in,
+        ctx.accounts.mint_in.decimals,
+    )?;
+
+    let fee_bytes = pool.fee_bps.to_le_bytes();
+    let bump = [pool.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), &fee_bytes, &bump]];
+
+    // transfer amount_out of mint_out from vault_out to user_ata_out
+    token::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_out.to_account_info(),
+                mint: ctx.accounts.mint_out.to_account_info(),
+                to: ctx.accounts.user_ata_out.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount_out,
+        ctx.accounts.mint_out.decimals,
+    )?;
+
+    let pool = &mut ctx.accounts.pool;
+
+    if a_to_b {
+        pool.reserve_a = pool.reserve_a.checked_add(amount_in).ok_or(ProgramError::ArithmeticOverflow)?;
+        pool.reserve_b = pool.reserve_b.checked_sub(amount_out).ok_or(ProgramError::ArithmeticOverflow)?;
+    } else {
+        pool.reserve_b = pool.reserve_b.checked_add(amount_in).ok_or(ProgramError::ArithmeticOverflow)?;
+        pool.reserve_a = pool.reserve_a.checked_sub(amount_out).ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, TransferChecked};
+
+use crate::{error::AmmError, state::LiquidityPool};
+
+#[derive(Accounts)]
+pub struct WithdrawLiquidity<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), &pool.fee_bps.to_le_bytes()],
+        bump = pool.bump,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub mint_a: Box<Account<'info, Mint>>,
+    pub mint_b: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool,
+    )]
+    pub vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool,
+    )]
+    pub vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"lp_mint", pool.key().as_ref()], bump)]
+    pub mint_lp: Box<Account<'info, Mint>>,
+
+    #[account(mut, token::mint = mint_lp, token::authority = user)]
+    pub user_ata_lp: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint_a)]
+    pub user_ata_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint_b)]
+    pub user_ata_b: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(
+    ctx: Context<WithdrawLiquidity>,
+    amount_lp_in: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+) -> Result<()> {
+    require!(amount_lp_in > 0, AmmError::ZeroLiquidityAmount);
+
+    let pool = &ctx.accounts.pool;
+
+    let total_lp = ctx.accounts.mint_lp.supply as u128;
+
+    let a_out = (amount_lp_in as u128 * pool.reserve_a as u128 / total_lp) as u64;
+    let b_out = (amount_lp_in as u128 * pool.reserve_b as u128 / total_lp) as u64;
+
+    require!(a_out >= amount_a_min && b_out >= amount_b_min, AmmError::SlippageExceed);
+
+    // burn lp tokens from user_ata_lp
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint_lp.to_account_info(),
+                from: ctx.accounts.user_ata_lp.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount_lp_in,
+    )?;
+
+    let fee_bytes = pool.fee_bps.to_le_bytes();
+    let bump = [pool.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), &fee_bytes, &bump]];
+
+    // transfer a_out from vault_a to user_ata_a
+    token::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_a.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
+                to: ctx.accounts.user_ata_a.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        a_out,
+        ctx.accounts.mint_a.decimals,
+    )?;
+
+    // transfer b_out from vault_b to user_ata_b
+    token::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_b.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
+                to: ctx.accounts.user_ata_b.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        b_out,
+        ctx.accounts.mint_b.decimals,
+    )?;
+
+    let pool = &mut ctx.accounts.pool;
+
+    pool.reserve_a = pool.reserve_a.checked_sub(a_out).ok_or(ProgramError::ArithmeticOverflow)?;
+    pool.reserve_b = pool.reserve_b.checked_sub(b_out).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
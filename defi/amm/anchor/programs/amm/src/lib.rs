@@ -0,0 +1,48 @@
+// Anchor port of the native AMM's four core instructions, kept to the
+// constant-product curve so the account validation can be compared side by
+// side with defi/amm/native/program. Protocol fees, alternative curves,
+// Token-2022 and the later instructions are left to the native version.
+// the code generated by #[program] still calls AccountInfo::realloc
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+
+pub mod error;
+pub mod instructions;
+pub mod state;
+
+use instructions::*;
+
+declare_id!("9ezEwFMARZCMdqSW5yNHDBcCZ5JATqP4byobucHYhebQ");
+
+#[program]
+pub mod amm {
+    use super::*;
+
+    pub fn create_pool(ctx: Context<CreatePool>, amount_a: u64, amount_b: u64, fee_bps: u16) -> Result<()> {
+        instructions::create_pool::handler(ctx, amount_a, amount_b, fee_bps)
+    }
+
+    pub fn provide_liquidity(
+        ctx: Context<ProvideLiquidity>,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+    ) -> Result<()> {
+        instructions::provide_liquidity::handler(ctx, amount_a_desired, amount_b_desired, amount_a_min, amount_b_min)
+    }
+
+    pub fn withdraw_liquidity(
+        ctx: Context<WithdrawLiquidity>,
+        amount_lp_in: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+    ) -> Result<()> {
+        instructions::withdraw_liquidity::handler(ctx, amount_lp_in, amount_a_min, amount_b_min)
+    }
+
+    pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
+        instructions::swap::handler(ctx, amount_in, min_out)
+    }
+}
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+// LP tokens minted to the pool's own LP account at creation and never
+// redeemable, so the first depositor can't inflate the share price.
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityPool {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
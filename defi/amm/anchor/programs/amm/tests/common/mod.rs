@@ -0,0 +1,322 @@
+#![allow(dead_code)]
+
+use anyhow::Result;
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{entrypoint::ProgramResult, instruction::Instruction},
+    AccountDeserialize,
+    InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token::spl_token::{
+        id as token_program_id,
+        instruction::{initialize_mint2, mint_to},
+        state::{Account as TokenAccount, Mint},
+    },
+};
+
+use solana_program_test::*;
+
+use solana_sdk::{
+    instruction::InstructionError,
+    program_pack::Pack,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::instruction::create_account;
+
+use amm::state::LiquidityPool;
+
+pub const DECIMALS: u8 = 6;
+pub const USER_FUNDS: u64 = 10_000_000_000;
+pub const INITIAL_LIQUIDITY: u64 = 1_000_000_000;
+
+// `amm::entry` ties the account slice to the AccountInfo lifetime, which
+// processor! can't express, so hand it a leaked copy.
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+
+    amm::entry(program_id, accounts, data)
+}
+
+pub async fn start() -> ProgramTestContext {
+    ProgramTest::new(
+        "amm",
+        amm::ID,
+        processor!(process_instruction),
+    ).start_with_context().await
+}
+
+// Fetches a fresh blockhash for every transaction so that repeating an
+// identical instruction isn't rejected as already processed.
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await?;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+// Anchor reports both its own constraint errors and `AmmError`s (offset by
+// ERROR_CODE_OFFSET) as custom errors.
+pub fn custom_error(result: Result<(), BanksClientError>) -> Option<u32> {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+pub async fn create_mint(context: &mut ProgramTestContext) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[
+            create_account(
+                &payer,
+                &mint.pubkey(),
+                Rent::default().minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &token_program_id(),
+            ),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer, None, DECIMALS)?,
+        ],
+        &[&mint],
+    ).await?;
+
+    Ok(mint.pubkey())
+}
+
+pub async fn create_ata(context: &mut ProgramTestContext, owner: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[create_associated_token_account_idempotent(&payer, owner, mint, &token_program_id())],
+        &[],
+    ).await?;
+
+    Ok(get_associated_token_address(owner, mint))
+}
+
+// Only works for mints created by `create_mint`, whose authority is the payer.
+pub async fn create_funded_ata(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+    let ata = create_ata(context, owner, mint).await?;
+
+    send(
+        context,
+        &[mint_to(&token_program_id(), mint, &ata, &payer, &[], amount)?],
+        &[],
+    ).await?;
+
+    Ok(ata)
+}
+
+// Two mints in the ascending order create_pool expects.
+pub async fn create_mint_pair(context: &mut ProgramTestContext) -> Result<(Pubkey, Pubkey)> {
+    let mint_x = create_mint(context).await?;
+    let mint_y = create_mint(context).await?;
+
+    Ok(if mint_x < mint_y { (mint_x, mint_y) } else { (mint_y, mint_x) })
+}
+
+// Creates two mints, funds the payer with USER_FUNDS of each and creates a
+// pool seeded with INITIAL_LIQUIDITY on both sides.
+// Returns (mint_a, mint_b, pool).
+pub async fn setup_pool(context: &mut ProgramTestContext, fee_bps: u16) -> Result<(Pubkey, Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b) = create_mint_pair(context).await?;
+
+    create_funded_ata(context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(context, &payer, &mint_b, USER_FUNDS).await?;
+
+    send(
+        context,
+        &[create_pool_ix(&payer, &mint_a, &mint_b, INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, fee_bps)],
+        &[],
+    ).await?;
+
+    Ok((mint_a, mint_b, pool_address(&mint_a, &mint_b, fee_bps)))
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> Result<u64> {
+    let account = context.banks_client.get_account(*address).await?.unwrap();
+
+    Ok(TokenAccount::unpack(&account.data)?.amount)
+}
+
+pub async fn pool_state(context: &mut ProgramTestContext, pool: &Pubkey) -> Result<LiquidityPool> {
+    let account = context.banks_client.get_account(*pool).await?.unwrap();
+
+    Ok(LiquidityPool::try_deserialize(&mut &account.data[..])?)
+}
+
+// Same constant-product formula the program uses for swaps.
+pub fn quote(reserve_in: u64, reserve_out: u64, amount_in: u64, fee_bps: u16) -> u64 {
+    let amount_in_post_fee = amount_in as u128 * (10_000 - fee_bps as u128);
+
+    (reserve_out as u128 * amount_in_post_fee / (reserve_in as u128 * 10_000 + amount_in_post_fee)) as u64
+}
+
+pub fn pool_address(mint_a: &Pubkey, mint_b: &Pubkey, fee_bps: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"pool", mint_a.as_ref(), mint_b.as_ref(), &fee_bps.to_le_bytes()],
+        &amm::ID,
+    ).0
+}
+
+pub fn lp_mint_address(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"lp_mint", pool.as_ref()], &amm::ID).0
+}
+
+pub fn create_pool_ix(
+    user: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    fee_bps: u16,
+) -> Instruction {
+    let pool = pool_address(mint_a, mint_b, fee_bps);
+    let mint_lp = lp_mint_address(&pool);
+
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::CreatePool {
+            user: *user,
+            pool,
+            mint_a: *mint_a,
+            mint_b: *mint_b,
+            vault_a: get_associated_token_address(&pool, mint_a),
+            vault_b: get_associated_token_address(&pool, mint_b),
+            mint_lp,
+            user_ata_lp: get_associated_token_address(user, &mint_lp),
+            pool_ata_lp: get_associated_token_address(&pool, &mint_lp),
+            user_ata_a: get_associated_token_address(user, mint_a),
+            user_ata_b: get_associated_token_address(user, mint_b),
+            token_program: token_program_id(),
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        }.to_account_metas(None),
+        data: amm::instruction::CreatePool { amount_a, amount_b, fee_bps }.data(),
+    }
+}
+
+pub fn provide_liquidity_ix(
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amounts_desired: (u64, u64),
+    amounts_min: (u64, u64),
+) -> Instruction {
+    let mint_lp = lp_mint_address(pool);
+
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::ProvideLiquidity {
+            user: *user,
+            pool: *pool,
+            mint_a: *mint_a,
+            mint_b: *mint_b,
+            vault_a: get_associated_token_address(pool, mint_a),
+            vault_b: get_associated_token_address(pool, mint_b),
+            mint_lp,
+            user_ata_lp: get_associated_token_address(user, &mint_lp),
+            user_ata_a: get_associated_token_address(user, mint_a),
+            user_ata_b: get_associated_token_address(user, mint_b),
+            token_program: token_program_id(),
+        }.to_account_metas(None),
+        data: amm::instruction::ProvideLiquidity {
+            amount_a_desired: amounts_desired.0,
+            amount_b_desired: amounts_desired.1,
+            amount_a_min: amounts_min.0,
+            amount_b_min: amounts_min.1,
+        }.data(),
+    }
+}
+
+pub fn withdraw_liquidity_ix(
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_lp_in: u64,
+    amounts_min: (u64, u64),
+) -> Instruction {
+    let mint_lp = lp_mint_address(pool);
+
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::WithdrawLiquidity {
+            user: *user,
+            pool: *pool,
+            mint_a: *mint_a,
+            mint_b: *mint_b,
+            vault_a: get_associated_token_address(pool, mint_a),
+            vault_b: get_associated_token_address(pool, mint_b),
+            mint_lp,
+            user_ata_lp: get_associated_token_address(user, &mint_lp),
+            user_ata_a: get_associated_token_address(user, mint_a),
+            user_ata_b: get_associated_token_address(user, mint_b),
+            token_program: token_program_id(),
+        }.to_account_metas(None),
+        data: amm::instruction::WithdrawLiquidity {
+            amount_lp_in,
+            amount_a_min: amounts_min.0,
+            amount_b_min: amounts_min.1,
+        }.data(),
+    }
+}
+
+pub fn swap_ix(
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::Swap {
+            user: *user,
+            pool: *pool,
+            mint_in: *mint_in,
+            mint_out: *mint_out,
+            vault_in: get_associated_token_address(pool, mint_in),
+            vault_out: get_associated_token_address(pool, mint_out),
+            user_ata_in: get_associated_token_address(user, mint_in),
+            user_ata_out: get_associated_token_address(user, mint_out),
+            token_program: token_program_id(),
+        }.to_account_metas(None),
+        data: amm::instruction::Swap { amount_in, min_out }.data(),
+    }
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use anyhow::Result;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_spl::associated_token::get_associated_token_address;
+
+use solana_sdk::signature::Signer;
+
+use amm::error::AmmError;
+use amm::state::MINIMUM_LIQUIDITY;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn create_pool_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.mint_a, mint_a);
+    assert_eq!(pool_data.mint_b, mint_b);
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY);
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY);
+    assert_eq!(pool_data.fee_bps, FEE_BPS);
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_a)).await?, INITIAL_LIQUIDITY);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_b)).await?, INITIAL_LIQUIDITY);
+
+    let mint_lp = lp_mint_address(&pool);
+
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&pool, &mint_lp)).await?,
+        MINIMUM_LIQUIDITY,
+    );
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&payer, &mint_lp)).await?,
+        INITIAL_LIQUIDITY - MINIMUM_LIQUIDITY,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_below_minimum_liquidity_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b) = create_mint_pair(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(&mut context, &payer, &mint_b, USER_FUNDS).await?;
+
+    let result = send(
+        &mut context,
+        &[create_pool_ix(&payer, &mint_a, &mint_b, MINIMUM_LIQUIDITY, MINIMUM_LIQUIDITY, FEE_BPS)],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + AmmError::InsufficientInitialLiquidity as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_fee_too_high_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b) = create_mint_pair(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(&mut context, &payer, &mint_b, USER_FUNDS).await?;
+
+    let result = send(
+        &mut context,
+        &[create_pool_ix(&payer, &mint_a, &mint_b, INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, 10_000)],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + AmmError::FeeTooHigh as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_unsorted_mints_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b) = create_mint_pair(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(&mut context, &payer, &mint_b, USER_FUNDS).await?;
+
+    let result = send(
+        &mut context,
+        &[create_pool_ix(&payer, &mint_b, &mint_a, INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, FEE_BPS)],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + AmmError::UnsortedMints as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_twice_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, _pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let result = send(
+        &mut context,
+        &[create_pool_ix(&payer, &mint_a, &mint_b, INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, FEE_BPS)],
+        &[],
+    ).await;
+
+    // `init` fails inside the system program's create_account, which reports
+    // SystemError::AccountAlreadyInUse (0)
+    assert_eq!(custom_error(result), Some(0));
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use anyhow::Result;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_spl::associated_token::get_associated_token_address;
+
+use solana_sdk::signature::{Keypair, Signer};
+
+use amm::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn provide_liquidity_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let user_ata_lp = get_associated_token_address(&payer, &lp_mint_address(&pool));
+    let lp_before = token_balance(&mut context, &user_ata_lp).await?;
+
+    // twice as much b as the pool ratio needs, so only half of it is taken
+    send(
+        &mut context,
+        &[provide_liquidity_ix(&payer, &pool, &mint_a, &mint_b, (INITIAL_LIQUIDITY / 2, INITIAL_LIQUIDITY), (0, 0))],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY * 3 / 2);
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY * 3 / 2);
+    assert_eq!(token_balance(&mut context, &user_ata_lp).await? - lp_before, INITIAL_LIQUIDITY / 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn provide_liquidity_slippage_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let result = send(
+        &mut context,
+        &[
+            provide_liquidity_ix(
+                &payer,
+                &pool,
+                &mint_a,
+                &mint_b,
+                (INITIAL_LIQUIDITY / 2, INITIAL_LIQUIDITY),
+                (0, INITIAL_LIQUIDITY),
+            ),
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + AmmError::SlippageExceed as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_liquidity_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let user_ata_a = get_associated_token_address(&payer, &mint_a);
+    let user_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_a_before = token_balance(&mut context, &user_ata_a).await?;
+    let balance_b_before = token_balance(&mut context, &user_ata_b).await?;
+
+    // half of the total LP supply, including the locked minimum
+    let amount_lp_in = INITIAL_LIQUIDITY / 2;
+
+    send(
+        &mut context,
+        &[withdraw_liquidity_ix(&payer, &pool, &mint_a, &mint_b, amount_lp_in, (0, 0))],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &user_ata_a).await? - balance_a_before, INITIAL_LIQUIDITY / 2);
+    assert_eq!(token_balance(&mut context, &user_ata_b).await? - balance_b_before, INITIAL_LIQUIDITY / 2);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY / 2);
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY / 2);
+
+    Ok(())
+}
+
+// The LP account has to belong to the signer, or anyone could burn someone
+// else's LP and collect the proceeds.
+#[tokio::test]
+async fn withdraw_liquidity_foreign_lp_account_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let attacker = Keypair::new();
+    create_ata(&mut context, &attacker.pubkey(), &mint_a).await?;
+    create_ata(&mut context, &attacker.pubkey(), &mint_b).await?;
+
+    let mut instruction = withdraw_liquidity_ix(&attacker.pubkey(), &pool, &mint_a, &mint_b, 1_000, (0, 0));
+    instruction.accounts[7].pubkey = get_associated_token_address(&payer, &lp_mint_address(&pool));
+
+    let result = send(&mut context, &[instruction], &[&attacker]).await;
+
+    assert_eq!(custom_error(result), Some(anchor_lang::error::ErrorCode::ConstraintTokenOwner as u32));
+
+    Ok(())
+}
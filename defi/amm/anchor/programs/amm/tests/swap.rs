@@ -0,0 +1,159 @@
+mod common;
+
+use anyhow::Result;
+
+use anchor_lang::error::{ErrorCode, ERROR_CODE_OFFSET};
+use anchor_spl::associated_token::get_associated_token_address;
+
+use solana_sdk::signature::{Keypair, Signer};
+
+use amm::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Swap account indices, see swap_ix.
+const VAULT_IN: usize = 4;
+const VAULT_OUT: usize = 5;
+
+#[tokio::test]
+async fn swap_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let user_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_before = token_balance(&mut context, &user_ata_b).await?;
+
+    let amount_in = 10_000_000;
+    let expected_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    send(
+        &mut context,
+        &[swap_ix(&payer, &pool, &mint_a, &mint_b, amount_in, expected_out)],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &user_ata_b).await? - balance_before, expected_out);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + amount_in);
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY - expected_out);
+
+    // and back the other way
+    let amount_in = expected_out;
+    let expected_out = quote(pool_data.reserve_b, pool_data.reserve_a, amount_in, FEE_BPS);
+
+    send(
+        &mut context,
+        &[swap_ix(&payer, &pool, &mint_b, &mint_a, amount_in, expected_out)],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY);
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + 10_000_000 - expected_out);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_slippage_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let amount_in = 10_000_000;
+    let expected_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&payer, &pool, &mint_a, &mint_b, amount_in, expected_out + 1)],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + AmmError::SlippageExceed as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_zero_amount_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let result = send(&mut context, &[swap_ix(&payer, &pool, &mint_a, &mint_b, 0, 0)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + AmmError::ZeroSwapAmount as u32));
+
+    Ok(())
+}
+
+// The native program needs VaultInMismatch/VaultOutMismatch checks for
+// these; here the associated_token constraints cover them.
+#[tokio::test]
+async fn swap_attacker_vault_in_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let attacker = Keypair::new();
+    let attacker_ata_a = create_ata(&mut context, &attacker.pubkey(), &mint_a).await?;
+
+    let mut instruction = swap_ix(&payer, &pool, &mint_a, &mint_b, 1_000_000, 0);
+    instruction.accounts[VAULT_IN].pubkey = attacker_ata_a;
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ErrorCode::ConstraintTokenOwner as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_crossed_vaults_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let mut instruction = swap_ix(&payer, &pool, &mint_a, &mint_b, 1_000_000, 0);
+    instruction.accounts.swap(VAULT_IN, VAULT_OUT);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ErrorCode::ConstraintAssociated as u32));
+
+    Ok(())
+}
+
+// An attacker-minted token passed as mint_in against a real pool.
+#[tokio::test]
+async fn swap_foreign_mint_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, FEE_BPS).await?;
+
+    let fake_mint = create_mint(&mut context).await?;
+    create_funded_ata(&mut context, &payer, &fake_mint, USER_FUNDS).await?;
+
+    // the pool has no vault for the fake mint, so point at a real one to get
+    // past deserialization
+    let mut instruction = swap_ix(&payer, &pool, &fake_mint, &mint_b, 1_000_000, 0);
+    instruction.accounts[VAULT_IN].pubkey = get_associated_token_address(&pool, &mint_a);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + AmmError::MintNotInPool as u32));
+
+    Ok(())
+}
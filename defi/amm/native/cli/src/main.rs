@@ -0,0 +1,451 @@
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Parser, Subcommand};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::Mint,
+};
+
+use program::{
+    client::{
+        create_pool_ix_with_token_programs,
+        initial_deposit_ix_with_token_programs,
+        initialize_pool_ix_with_token_programs,
+        lp_mint_address,
+        pool_address,
+        provide_liquidity_ix_with_token_programs,
+        registry_address,
+        swap_ix_with_token_programs,
+        withdraw_liquidity_ix_with_token_programs,
+    },
+    curve::swap_output,
+    state::{CurveType, LiquidityPool, PoolRegistry},
+};
+
+// Command-line client for the native AMM program. Amounts are in base units
+// of the mint; pools are addressed by their two mints and fee tier, the same
+// way the program derives them.
+#[derive(Parser)]
+#[command(name = "amm", version)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, short = 'u', env = "AMM_RPC_URL", default_value = "http://localhost:8899")]
+    url: String,
+
+    /// Keypair that signs and pays for transactions [default: ~/.config/solana/id.json]
+    #[arg(long, short = 'k', env = "AMM_KEYPAIR")]
+    keypair: Option<String>,
+
+    /// Address of the deployed AMM program
+    #[arg(long, env = "AMM_PROGRAM_ID")]
+    program_id: Pubkey,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a pool and seed it with both tokens
+    CreatePool {
+        #[command(flatten)]
+        pool: PoolArgs,
+
+        #[arg(long)]
+        amount_a: u64,
+
+        #[arg(long)]
+        amount_b: u64,
+
+        /// Share of the swap fee sent to the treasury, in basis points
+        #[arg(long, default_value_t = 0)]
+        protocol_fee_bps: u16,
+    },
+    /// Create an empty pool, to be seeded later with `initial-deposit`
+    InitializePool {
+        #[command(flatten)]
+        pool: PoolArgs,
+
+        /// Share of the swap fee sent to the treasury, in basis points
+        #[arg(long, default_value_t = 0)]
+        protocol_fee_bps: u16,
+    },
+    /// Seed a pool made by `initialize-pool`; the amounts set its starting price
+    InitialDeposit {
+        #[command(flatten)]
+        pool: PoolArgs,
+
+        #[arg(long)]
+        amount_a: u64,
+
+        #[arg(long)]
+        amount_b: u64,
+    },
+    /// Deposit both tokens at the pool's current ratio
+    AddLiquidity {
+        #[command(flatten)]
+        pool: PoolArgs,
+
+        #[arg(long)]
+        amount_a: u64,
+
+        #[arg(long)]
+        amount_b: u64,
+
+        #[arg(long, default_value_t = 0)]
+        min_a: u64,
+
+        #[arg(long, default_value_t = 0)]
+        min_b: u64,
+    },
+    /// Burn LP tokens for a share of both reserves
+    RemoveLiquidity {
+        #[command(flatten)]
+        pool: PoolArgs,
+
+        #[arg(long)]
+        amount_lp: u64,
+
+        #[arg(long, default_value_t = 0)]
+        min_a: u64,
+
+        #[arg(long, default_value_t = 0)]
+        min_b: u64,
+    },
+    /// Swap `amount-in` of `mint-in` for the pool's other token
+    Swap {
+        #[arg(long)]
+        mint_in: Pubkey,
+
+        #[arg(long)]
+        mint_out: Pubkey,
+
+        #[arg(long, default_value_t = 30)]
+        fee_bps: u16,
+
+        #[arg(long)]
+        amount_in: u64,
+
+        /// Minimum output; quoted from the pool with `slippage-bps` if omitted
+        #[arg(long)]
+        min_out: Option<u64>,
+
+        #[arg(long, default_value_t = 50)]
+        slippage_bps: u16,
+    },
+    /// Print a pool's state
+    ShowPool {
+        #[command(flatten)]
+        pool: PoolArgs,
+    },
+    /// List every pool the program has created
+    ListPools,
+}
+
+#[derive(Args)]
+struct PoolArgs {
+    #[arg(long)]
+    mint_a: Pubkey,
+
+    #[arg(long)]
+    mint_b: Pubkey,
+
+    /// Swap fee in basis points, part of the pool address
+    #[arg(long, default_value_t = 30)]
+    fee_bps: u16,
+}
+
+impl PoolArgs {
+    fn pool(&self, program_id: &Pubkey) -> Pubkey {
+        pool_address(program_id, &self.mint_a, &self.mint_b, self.fee_bps)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let client = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+    let program_id = cli.program_id;
+
+    match cli.command {
+        Command::CreatePool { pool, amount_a, amount_b, protocol_fee_bps } => {
+            let payer = load_keypair(cli.keypair.as_deref())?;
+            let token_programs = (mint_owner(&client, &pool.mint_a).await?, mint_owner(&client, &pool.mint_b).await?);
+
+            send(
+                &client,
+                &payer,
+                &[
+                    create_pool_ix_with_token_programs(
+                        &program_id,
+                        &payer.pubkey(),
+                        &pool.mint_a,
+                        &pool.mint_b,
+                        amount_a,
+                        amount_b,
+                        pool.fee_bps,
+                        protocol_fee_bps,
+                        CurveType::ConstantProduct,
+                        (&token_programs.0, &token_programs.1),
+                    )?,
+                ],
+            ).await?;
+
+            println!("pool: {}", pool.pool(&program_id));
+        },
+        Command::InitializePool { pool, protocol_fee_bps } => {
+            let payer = load_keypair(cli.keypair.as_deref())?;
+            let token_programs = (mint_owner(&client, &pool.mint_a).await?, mint_owner(&client, &pool.mint_b).await?);
+
+            send(
+                &client,
+                &payer,
+                &[
+                    initialize_pool_ix_with_token_programs(
+                        &program_id,
+                        &payer.pubkey(),
+                        &pool.mint_a,
+                        &pool.mint_b,
+                        pool.fee_bps,
+                        protocol_fee_bps,
+                        CurveType::ConstantProduct,
+                        (&token_programs.0, &token_programs.1),
+                    )?,
+                ],
+            ).await?;
+
+            println!("pool: {}", pool.pool(&program_id));
+        },
+        Command::InitialDeposit { pool, amount_a, amount_b } => {
+            let payer = load_keypair(cli.keypair.as_deref())?;
+            let token_programs = (mint_owner(&client, &pool.mint_a).await?, mint_owner(&client, &pool.mint_b).await?);
+
+            send(
+                &client,
+                &payer,
+                &[
+                    initial_deposit_ix_with_token_programs(
+                        &program_id,
+                        &payer.pubkey(),
+                        &pool.pool(&program_id),
+                        &pool.mint_a,
+                        &pool.mint_b,
+                        amount_a,
+                        amount_b,
+                        (&token_programs.0, &token_programs.1),
+                    )?,
+                ],
+            ).await?;
+        },
+        Command::AddLiquidity { pool, amount_a, amount_b, min_a, min_b } => {
+            let payer = load_keypair(cli.keypair.as_deref())?;
+            let pool_key = pool.pool(&program_id);
+            let token_programs = (mint_owner(&client, &pool.mint_a).await?, mint_owner(&client, &pool.mint_b).await?);
+
+            // the LP account isn't created by ProvideLiquidity itself
+            let mint_lp = lp_mint_address(&program_id, &pool_key);
+
+            send(
+                &client,
+                &payer,
+                &[
+                    create_associated_token_account_idempotent(
+                        &payer.pubkey(),
+                        &payer.pubkey(),
+                        &mint_lp,
+                        &spl_token::id(),
+                    ),
+                    provide_liquidity_ix_with_token_programs(
+                        &program_id,
+                        &payer.pubkey(),
+                        &pool_key,
+                        &pool.mint_a,
+                        &pool.mint_b,
+                        (amount_a, amount_b),
+                        (min_a, min_b),
+                        (&token_programs.0, &token_programs.1),
+                    )?,
+                ],
+            ).await?;
+        },
+        Command::RemoveLiquidity { pool, amount_lp, min_a, min_b } => {
+            let payer = load_keypair(cli.keypair.as_deref())?;
+            let token_programs = (mint_owner(&client, &pool.mint_a).await?, mint_owner(&client, &pool.mint_b).await?);
+
+            send(
+                &client,
+                &payer,
+                &[
+                    withdraw_liquidity_ix_with_token_programs(
+                        &program_id,
+                        &payer.pubkey(),
+                        &pool.pool(&program_id),
+                        &pool.mint_a,
+                        &pool.mint_b,
+                        amount_lp,
+                        (min_a, min_b),
+                        (&token_programs.0, &token_programs.1),
+                    )?,
+                ],
+            ).await?;
+        },
+        Command::Swap { mint_in, mint_out, fee_bps, amount_in, min_out, slippage_bps } => {
+            let payer = load_keypair(cli.keypair.as_deref())?;
+            let pool_key = pool_address(&program_id, &mint_in, &mint_out, fee_bps);
+            let token_program_in = mint_owner(&client, &mint_in).await?;
+            let token_program_out = mint_owner(&client, &mint_out).await?;
+
+            let pool = fetch_pool(&client, &pool_key).await?;
+
+            let min_out = match min_out {
+                Some(min_out) => min_out,
+                None => {
+                    let a_to_b = mint_in == pool.mint_a;
+                    let (reserve_in, reserve_out) = if a_to_b {
+                        (pool.reserve_a, pool.reserve_b)
+                    } else {
+                        (pool.reserve_b, pool.reserve_a)
+                    };
+
+                    let amount_out = swap_output(pool.curve, reserve_in, reserve_out, amount_in, pool.fee_bps, a_to_b)?;
+
+                    println!("quoted output: {}", amount_out);
+
+                    (amount_out as u128 * (10_000 - slippage_bps.min(10_000) as u128) / 10_000) as u64
+                },
+            };
+
+            let mut swap_ix = swap_ix_with_token_programs(
+                &program_id,
+                &payer.pubkey(),
+                &pool_key,
+                &mint_in,
+                &mint_out,
+                amount_in,
+                min_out,
+                (&token_program_in, &token_program_out),
+            )?;
+
+            // oracle-bounded pools need their price account after the fixed accounts
+            if pool.oracle != Pubkey::default() {
+                swap_ix.accounts.push(AccountMeta::new_readonly(pool.oracle, false));
+            }
+
+            send(
+                &client,
+                &payer,
+                &[
+                    create_associated_token_account_idempotent(
+                        &payer.pubkey(),
+                        &payer.pubkey(),
+                        &mint_out,
+                        &token_program_out,
+                    ),
+                    swap_ix,
+                ],
+            ).await?;
+        },
+        Command::ShowPool { pool } => {
+            let pool_key = pool.pool(&program_id);
+            let pool_data = fetch_pool(&client, &pool_key).await?;
+
+            let mint_lp = lp_mint_address(&program_id, &pool_key);
+            let lp_supply = client.get_token_supply(&mint_lp).await?;
+
+            println!("pool:             {}", pool_key);
+            println!("mint a:           {} ({} decimals)", pool_data.mint_a, pool_data.decimals_a);
+            println!("mint b:           {} ({} decimals)", pool_data.mint_b, pool_data.decimals_b);
+            println!("reserve a:        {}", pool_data.reserve_a);
+            println!("reserve b:        {}", pool_data.reserve_b);
+            println!("lp fees a:        {} (lifetime {})", pool_data.fees_a, pool_data.fees_total_a);
+            println!("lp fees b:        {} (lifetime {})", pool_data.fees_b, pool_data.fees_total_b);
+            println!("curve:            {:?}", pool_data.curve);
+            println!("fee:              {} bps", pool_data.fee_bps);
+            println!("protocol fee:     {} bps of the fee", pool_data.protocol_fee_bps);
+            if pool_data.fee_change_ts != 0 {
+                println!(
+                    "pending fee:      {} bps of the fee, from unix time {}",
+                    pool_data.pending_protocol_fee_bps,
+                    pool_data.fee_change_ts,
+                );
+            }
+            println!("referral fee:     {} bps of the fee", pool_data.referral_fee_bps);
+            if pool_data.oracle != Pubkey::default() {
+                println!("oracle:           {} (max deviation {} bps)", pool_data.oracle, pool_data.max_oracle_deviation_bps);
+            }
+            println!("admin:            {}", pool_data.admin);
+            if pool_data.pending_admin != Pubkey::default() {
+                println!("pending admin:    {}", pool_data.pending_admin);
+            }
+            println!("paused:           {}", pool_data.paused);
+            println!("lp mint:          {}", mint_lp);
+            println!("lp supply:        {}", lp_supply.amount);
+        },
+        Command::ListPools => {
+            let account = client.get_account(&registry_address(&program_id)).await
+                .context("fetching pool registry")?;
+
+            for entry in PoolRegistry::load(&account.data)?.pools {
+                println!("{}  {} / {}  {} bps", entry.pool, entry.mint_a, entry.mint_b, entry.fee_bps);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn load_keypair(path: Option<&str>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path.to_string(),
+        None => format!("{}/.config/solana/id.json", std::env::var("HOME")?),
+    };
+
+    read_keypair_file(&path).map_err(|e| anyhow!("reading keypair {path}: {e}"))
+}
+
+// SPL Token or Token-2022, whichever owns the mint.
+async fn mint_owner(client: &RpcClient, mint: &Pubkey) -> Result<Pubkey> {
+    let account = client.get_account(mint).await
+        .with_context(|| format!("fetching mint {mint}"))?;
+
+    StateWithExtensions::<Mint>::unpack(&account.data)
+        .with_context(|| format!("{mint} is not a mint"))?;
+
+    Ok(account.owner)
+}
+
+async fn fetch_pool(client: &RpcClient, pool: &Pubkey) -> Result<LiquidityPool> {
+    let account = client.get_account(pool).await
+        .with_context(|| format!("fetching pool {pool}"))?;
+
+    Ok(LiquidityPool::load(&account.data)?)
+}
+
+async fn send(client: &RpcClient, payer: &Keypair, instructions: &[Instruction]) -> Result<()> {
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+
+    let tx_signature =
+        client.send_and_confirm_transaction_with_spinner(&tx).await?;
+
+    println!("tx signature: {}", tx_signature);
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use solana_program::{
+    entrypoint,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    account_info::AccountInfo,
+};
+
+use crate::processor;
+
+entrypoint!(process_instruction);
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {    
+    processor::process_instruction(program_id, accounts, instruction_data)?;
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+use thiserror::Error;
+
+use solana_program::program_error::ProgramError;
+
+#[derive(Error, Debug)]
+pub enum ClmmError {
+    #[error("Token mints must be different and passed in ascending order")]
+    InvalidMintOrder,
+    #[error("Pool address does not match PDA derived from token mints and tick spacing")]
+    PoolAddressMismatch,
+    #[error("Vault address does not match ATA derived from mint and pool address")]
+    VaultAddressMismatch,
+    #[error("Mint address does not match pool data")]
+    MintAddressMismatch,
+    #[error("Tick array address does not match PDA derived from the pool and start tick")]
+    TickArrayAddressMismatch,
+    #[error("Position address does not match PDA derived from the pool, owner and tick range")]
+    PositionAddressMismatch,
+    #[error("Tick spacing must be greater than zero")]
+    InvalidTickSpacing,
+    #[error("Fee must be below 10000 basis points (100%)")]
+    FeeTooHigh,
+    #[error("Sqrt price is outside the supported range")]
+    InvalidSqrtPrice,
+    #[error("Tick range must be ordered, within bounds and aligned to the tick spacing")]
+    InvalidTickRange,
+    #[error("Tick array start must be a multiple of the ticks covered by one array")]
+    InvalidTickArrayStart,
+    #[error("Tick array does not cover the required tick")]
+    TickArrayMismatch,
+    #[error("Swap needs another tick array to continue")]
+    MissingTickArray,
+    #[error("Liquidity amount must be greater than zero")]
+    ZeroLiquidity,
+    #[error("Position does not hold enough liquidity")]
+    InsufficientLiquidity,
+    #[error("Swap amount must be greater than zero")]
+    ZeroSwapAmount,
+    #[error("Slippage tolerance exceeded: token amounts are outside the limits specified")]
+    SlippageExceed,
+    #[error("Signer is not the position owner")]
+    NotPositionOwner,
+}
+
+impl From<ClmmError> for ProgramError {
+    fn from(error: ClmmError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
@@ -0,0 +1,143 @@
+use solana_program::program_error::ProgramError;
+
+use borsh::BorshDeserialize;
+
+pub enum ClmmInstruction {
+    InitializePool {
+        tick_spacing: u16,
+        fee_bps: u16,
+        sqrt_price: u128,
+    },
+    InitializeTickArray {
+        start_tick: i32,
+    },
+    OpenPosition {
+        tick_lower: i32,
+        tick_upper: i32,
+    },
+    IncreaseLiquidity {
+        liquidity: u128,
+        amount_a_max: u64,
+        amount_b_max: u64,
+    },
+    DecreaseLiquidity {
+        liquidity: u128,
+        amount_a_min: u64,
+        amount_b_min: u64,
+    },
+    Swap {
+        amount_in: u64,
+        min_out: u64,
+        a_to_b: bool,
+    },
+}
+
+impl ClmmInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&discriminator, rest) = input.split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(
+            match discriminator {
+                0 => {
+                    let payload = InitializePoolPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::InitializePool {
+                        tick_spacing: payload.tick_spacing,
+                        fee_bps: payload.fee_bps,
+                        sqrt_price: payload.sqrt_price,
+                    }
+                },
+                1 => {
+                    let payload = InitializeTickArrayPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::InitializeTickArray {
+                        start_tick: payload.start_tick,
+                    }
+                },
+                2 => {
+                    let payload = OpenPositionPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::OpenPosition {
+                        tick_lower: payload.tick_lower,
+                        tick_upper: payload.tick_upper,
+                    }
+                },
+                3 => {
+                    let payload = IncreaseLiquidityPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::IncreaseLiquidity {
+                        liquidity: payload.liquidity,
+                        amount_a_max: payload.amount_a_max,
+                        amount_b_max: payload.amount_b_max,
+                    }
+                },
+                4 => {
+                    let payload = DecreaseLiquidityPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::DecreaseLiquidity {
+                        liquidity: payload.liquidity,
+                        amount_a_min: payload.amount_a_min,
+                        amount_b_min: payload.amount_b_min,
+                    }
+                },
+                5 => {
+                    let payload = SwapPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::Swap {
+                        amount_in: payload.amount_in,
+                        min_out: payload.min_out,
+                        a_to_b: payload.a_to_b,
+                    }
+                },
+
+                _ => return Err(ProgramError::InvalidInstructionData)
+            }
+        )
+    }
+}
+
+#[derive(BorshDeserialize)]
+struct InitializePoolPayload {
+    tick_spacing: u16,
+    fee_bps: u16,
+    sqrt_price: u128,
+}
+
+#[derive(BorshDeserialize)]
+struct InitializeTickArrayPayload {
+    start_tick: i32,
+}
+
+#[derive(BorshDeserialize)]
+struct OpenPositionPayload {
+    tick_lower: i32,
+    tick_upper: i32,
+}
+
+#[derive(BorshDeserialize)]
+struct IncreaseLiquidityPayload {
+    liquidity: u128,
+    amount_a_max: u64,
+    amount_b_max: u64,
+}
+
+#[derive(BorshDeserialize)]
+struct DecreaseLiquidityPayload {
+    liquidity: u128,
+    amount_a_min: u64,
+    amount_b_min: u64,
+}
+
+#[derive(BorshDeserialize)]
+struct SwapPayload {
+    amount_in: u64,
+    min_out: u64,
+    a_to_b: bool,
+}
@@ -0,0 +1,7 @@
+pub mod entrypoint;
+pub mod processor;
+pub mod instruction;
+pub mod state;
+pub mod error;
+pub mod math;
+pub mod tick_math;
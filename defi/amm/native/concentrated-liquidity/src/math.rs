@@ -0,0 +1,154 @@
+// Liquidity and swap math over Q64.64 sqrt prices. Products of a liquidity
+// amount and a price don't fit in a u128, so intermediates go through U256.
+
+#![allow(clippy::manual_div_ceil, clippy::assign_op_pattern)]
+
+use uint::construct_uint;
+
+construct_uint! {
+    pub struct U256(4);
+}
+
+// Token A needed to move `liquidity` between two prices:
+// L * (sqrt_upper - sqrt_lower) / (sqrt_upper * sqrt_lower)
+pub fn amount_a_delta(
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u128> {
+    let numerator = U256::from(liquidity)
+        .checked_mul(U256::from(sqrt_price_upper.checked_sub(sqrt_price_lower)?))?
+        .checked_mul(U256::one() << 64)?;
+    let denominator = U256::from(sqrt_price_upper).checked_mul(U256::from(sqrt_price_lower))?;
+
+    div(numerator, denominator, round_up)
+}
+
+// Token B needed to move `liquidity` between two prices:
+// L * (sqrt_upper - sqrt_lower)
+pub fn amount_b_delta(
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u128> {
+    let numerator = U256::from(liquidity)
+        .checked_mul(U256::from(sqrt_price_upper.checked_sub(sqrt_price_lower)?))?;
+
+    div(numerator, U256::one() << 64, round_up)
+}
+
+// Price after `amount_in` is added to the pool. Selling token A lowers the
+// price, selling token B raises it; both round in the pool's favor.
+pub fn next_sqrt_price_from_input(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount_in: u64,
+    a_to_b: bool,
+) -> Option<u128> {
+    if amount_in == 0 {
+        return Some(sqrt_price);
+    }
+
+    if a_to_b {
+        // L * sqrt_p / (L + amount * sqrt_p)
+        let liquidity_shifted = U256::from(liquidity) << 64;
+
+        let numerator = liquidity_shifted.checked_mul(U256::from(sqrt_price))?;
+        let denominator = liquidity_shifted
+            .checked_add(U256::from(amount_in).checked_mul(U256::from(sqrt_price))?)?;
+
+        div(numerator, denominator, true)
+    } else {
+        // sqrt_p + amount / L
+        let delta = div(U256::from(amount_in) << 64, U256::from(liquidity), false)?;
+
+        sqrt_price.checked_add(delta)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapStep {
+    pub sqrt_price_next: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+// One step of an exact-input swap inside a range of constant liquidity: moves
+// the price towards `sqrt_price_target` until either the target is reached or
+// `amount_remaining` runs out.
+pub fn compute_swap_step(
+    sqrt_price: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_bps: u16,
+    a_to_b: bool,
+) -> Option<SwapStep> {
+    let amount_remaining_less_fee =
+        (amount_remaining as u128 * (10_000 - fee_bps as u128) / 10_000) as u64;
+
+    let amount_to_target = if a_to_b {
+        amount_a_delta(sqrt_price_target, sqrt_price, liquidity, true)?
+    } else {
+        amount_b_delta(sqrt_price, sqrt_price_target, liquidity, true)?
+    };
+
+    let sqrt_price_next = if amount_remaining_less_fee as u128 >= amount_to_target {
+        sqrt_price_target
+    } else {
+        next_sqrt_price_from_input(sqrt_price, liquidity, amount_remaining_less_fee, a_to_b)?
+    };
+
+    let (amount_in, amount_out) = if a_to_b {
+        (
+            amount_a_delta(sqrt_price_next, sqrt_price, liquidity, true)?,
+            amount_b_delta(sqrt_price_next, sqrt_price, liquidity, false)?,
+        )
+    } else {
+        (
+            amount_b_delta(sqrt_price, sqrt_price_next, liquidity, true)?,
+            amount_a_delta(sqrt_price, sqrt_price_next, liquidity, false)?,
+        )
+    };
+
+    let amount_in = u64::try_from(amount_in).ok()?;
+    let amount_out = u64::try_from(amount_out).ok()?;
+
+    // when the target isn't reached the whole remainder is spent, so anything
+    // not swapped is the fee
+    let fee_amount = if sqrt_price_next == sqrt_price_target {
+        let fee = (amount_in as u128 * fee_bps as u128).div_ceil(10_000 - fee_bps as u128);
+
+        u64::try_from(fee).ok()?
+    } else {
+        amount_remaining.checked_sub(amount_in)?
+    };
+
+    Some(SwapStep { sqrt_price_next, amount_in, amount_out, fee_amount })
+}
+
+// (a * b) >> 64, for applying a Q64.64 fee growth to a liquidity amount.
+pub fn mul_shr_64(a: u128, b: u128) -> Option<u128> {
+    let product = U256::from(a).checked_mul(U256::from(b))?;
+
+    (product >> 64).try_into().ok()
+}
+
+fn div(numerator: U256, denominator: U256, round_up: bool) -> Option<u128> {
+    if denominator.is_zero() {
+        return None;
+    }
+
+    let (quotient, remainder) = numerator.div_mod(denominator);
+
+    let quotient = if round_up && !remainder.is_zero() {
+        quotient.checked_add(U256::one())?
+    } else {
+        quotient
+    };
+
+    quotient.try_into().ok()
+}
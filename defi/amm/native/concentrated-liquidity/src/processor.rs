@@ -0,0 +1,1093 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program::{invoke, invoke_signed},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+    borsh1::try_from_slice_unchecked,
+};
+
+use solana_system_interface::{
+    program::id as system_program_id,
+    instruction::create_account,
+};
+
+use spl_associated_token_account::{
+    id as associated_token_program_id,
+    get_associated_token_address,
+    instruction::create_associated_token_account,
+};
+use spl_token::{
+    id as token_program_id,
+    instruction::transfer_checked,
+    state::Mint,
+};
+
+use crate::{
+    instruction::ClmmInstruction,
+    state::{Pool, Tick, TickArray, Position, TICK_ARRAY_SIZE},
+    error::ClmmError,
+    math::{amount_a_delta, amount_b_delta, compute_swap_step, mul_shr_64},
+    tick_math::{sqrt_price_at_tick, tick_at_sqrt_price, MIN_TICK, MAX_TICK, MIN_SQRT_PRICE, MAX_SQRT_PRICE},
+};
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8]
+) -> ProgramResult {
+    let instruction = ClmmInstruction::unpack(instruction_data)?;
+
+    match instruction {
+        ClmmInstruction::InitializePool { tick_spacing, fee_bps, sqrt_price } => {
+            process_initialize_pool(program_id, accounts, tick_spacing, fee_bps, sqrt_price)
+        },
+        ClmmInstruction::InitializeTickArray { start_tick } => {
+            process_initialize_tick_array(program_id, accounts, start_tick)
+        },
+        ClmmInstruction::OpenPosition { tick_lower, tick_upper } => {
+            process_open_position(program_id, accounts, tick_lower, tick_upper)
+        },
+        ClmmInstruction::IncreaseLiquidity { liquidity, amount_a_max, amount_b_max } => {
+            process_increase_liquidity(program_id, accounts, liquidity, amount_a_max, amount_b_max)
+        },
+        ClmmInstruction::DecreaseLiquidity { liquidity, amount_a_min, amount_b_min } => {
+            process_decrease_liquidity(program_id, accounts, liquidity, amount_a_min, amount_b_min)
+        },
+        ClmmInstruction::Swap { amount_in, min_out, a_to_b } => {
+            process_swap(program_id, accounts, amount_in, min_out, a_to_b)
+        },
+    }
+}
+
+pub fn process_initialize_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    tick_spacing: u16,
+    fee_bps: u16,
+    sqrt_price: u128,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // a single canonical order keeps one pool per pair and tick spacing
+    if mint_a.key >= mint_b.key {
+        return Err(ClmmError::InvalidMintOrder.into());
+    }
+
+    let (pool_pda, pool_bump) = Pubkey::find_program_address(
+        &[b"pool", mint_a.key.as_ref(), mint_b.key.as_ref(), &tick_spacing.to_le_bytes()],
+        program_id,
+    );
+
+    if *pool.key != pool_pda {
+        return Err(ClmmError::PoolAddressMismatch.into());
+    }
+
+    if *vault_a.key != get_associated_token_address(pool.key, mint_a.key) {
+        return Err(ClmmError::VaultAddressMismatch.into());
+    }
+
+    if *vault_b.key != get_associated_token_address(pool.key, mint_b.key) {
+        return Err(ClmmError::VaultAddressMismatch.into());
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *associated_token_program.key != associated_token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if tick_spacing == 0 {
+        return Err(ClmmError::InvalidTickSpacing.into());
+    }
+
+    if fee_bps >= 10_000 {
+        return Err(ClmmError::FeeTooHigh.into());
+    }
+
+    if !(MIN_SQRT_PRICE..MAX_SQRT_PRICE).contains(&sqrt_price) {
+        return Err(ClmmError::InvalidSqrtPrice.into());
+    }
+
+    let tick_current = tick_at_sqrt_price(sqrt_price)
+        .ok_or(ClmmError::InvalidSqrtPrice)?;
+
+    // create pool account
+    let pool_rent = Rent::get()?.minimum_balance(Pool::SPACE);
+
+    invoke_signed(
+        &create_account(
+            payer.key,
+            pool.key,
+            pool_rent,
+            Pool::SPACE as u64,
+            program_id,
+        ),
+        &[payer.clone(), pool.clone()],
+        &[
+            &[b"pool", mint_a.key.as_ref(), mint_b.key.as_ref(), &tick_spacing.to_le_bytes(), &[pool_bump]],
+        ],
+    )?;
+
+    // create vault_a ( pool's ata for mint_a )
+    invoke(
+        &create_associated_token_account(
+            payer.key,
+            pool.key,
+            mint_a.key,
+            token_program.key,
+        ),
+        &[payer.clone(), vault_a.clone(), pool.clone(), mint_a.clone()],
+    )?;
+
+    // create vault_b ( pool's ata for mint_b )
+    invoke(
+        &create_associated_token_account(
+            payer.key,
+            pool.key,
+            mint_b.key,
+            token_program.key,
+        ),
+        &[payer.clone(), vault_b.clone(), pool.clone(), mint_b.clone()],
+    )?;
+
+    let pool_data = Pool {
+        mint_a: *mint_a.key,
+        mint_b: *mint_b.key,
+        tick_spacing,
+        fee_bps,
+        sqrt_price,
+        tick_current,
+        liquidity: 0,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        bump: pool_bump,
+    };
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_initialize_tick_array(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    start_tick: i32,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let tick_array = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let pool_data =
+        try_from_slice_unchecked::<Pool>(&pool.data.borrow())?;
+
+    let expected_pool = Pubkey::create_program_address(
+        &[
+            b"pool",
+            pool_data.mint_a.as_ref(),
+            pool_data.mint_b.as_ref(),
+            &pool_data.tick_spacing.to_le_bytes(),
+            &[pool_data.bump],
+        ],
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(ClmmError::PoolAddressMismatch.into());
+    }
+
+    if start_tick != TickArray::start_tick_for(start_tick, pool_data.tick_spacing)
+        || start_tick < TickArray::start_tick_for(MIN_TICK, pool_data.tick_spacing)
+        || start_tick > MAX_TICK
+    {
+        return Err(ClmmError::InvalidTickArrayStart.into());
+    }
+
+    let (tick_array_pda, tick_array_bump) = Pubkey::find_program_address(
+        &[b"tick_array", pool.key.as_ref(), &start_tick.to_le_bytes()],
+        program_id,
+    );
+
+    if *tick_array.key != tick_array_pda {
+        return Err(ClmmError::TickArrayAddressMismatch.into());
+    }
+
+    // create tick array account
+    let tick_array_rent = Rent::get()?.minimum_balance(TickArray::SPACE);
+
+    invoke_signed(
+        &create_account(
+            payer.key,
+            tick_array.key,
+            tick_array_rent,
+            TickArray::SPACE as u64,
+            program_id,
+        ),
+        &[payer.clone(), tick_array.clone()],
+        &[
+            &[b"tick_array", pool.key.as_ref(), &start_tick.to_le_bytes(), &[tick_array_bump]],
+        ],
+    )?;
+
+    let tick_array_data = TickArray {
+        pool: *pool.key,
+        start_tick,
+        ticks: [Tick::default(); TICK_ARRAY_SIZE],
+        bump: tick_array_bump,
+    };
+
+    tick_array_data.serialize(&mut &mut tick_array.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_open_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    tick_lower: i32,
+    tick_upper: i32,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let position = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let pool_data =
+        try_from_slice_unchecked::<Pool>(&pool.data.borrow())?;
+
+    let expected_pool = Pubkey::create_program_address(
+        &[
+            b"pool",
+            pool_data.mint_a.as_ref(),
+            pool_data.mint_b.as_ref(),
+            &pool_data.tick_spacing.to_le_bytes(),
+            &[pool_data.bump],
+        ],
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(ClmmError::PoolAddressMismatch.into());
+    }
+
+    let spacing = pool_data.tick_spacing as i32;
+
+    if tick_lower >= tick_upper
+        || tick_lower < MIN_TICK
+        || tick_upper > MAX_TICK
+        || tick_lower % spacing != 0
+        || tick_upper % spacing != 0
+    {
+        return Err(ClmmError::InvalidTickRange.into());
+    }
+
+    let (position_pda, position_bump) = Pubkey::find_program_address(
+        &[
+            b"position",
+            pool.key.as_ref(),
+            owner.key.as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if *position.key != position_pda {
+        return Err(ClmmError::PositionAddressMismatch.into());
+    }
+
+    // create position account
+    let position_rent = Rent::get()?.minimum_balance(Position::SPACE);
+
+    invoke_signed(
+        &create_account(
+            owner.key,
+            position.key,
+            position_rent,
+            Position::SPACE as u64,
+            program_id,
+        ),
+        &[owner.clone(), position.clone()],
+        &[
+            &[
+                b"position",
+                pool.key.as_ref(),
+                owner.key.as_ref(),
+                &tick_lower.to_le_bytes(),
+                &tick_upper.to_le_bytes(),
+                &[position_bump],
+            ],
+        ],
+    )?;
+
+    let position_data = Position {
+        pool: *pool.key,
+        owner: *owner.key,
+        tick_lower,
+        tick_upper,
+        liquidity: 0,
+        fee_growth_inside_a: 0,
+        fee_growth_inside_b: 0,
+        tokens_owed_a: 0,
+        tokens_owed_b: 0,
+        bump: position_bump,
+    };
+
+    position_data.serialize(&mut &mut position.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_increase_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    liquidity: u128,
+    amount_a_max: u64,
+    amount_b_max: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let position = next_account_info(accounts_iter)?;
+    let tick_array_lower = next_account_info(accounts_iter)?;
+    let tick_array_upper = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let owner_ata_a = next_account_info(accounts_iter)?;
+    let owner_ata_b = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if liquidity == 0 {
+        return Err(ClmmError::ZeroLiquidity.into());
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut pool_data =
+        try_from_slice_unchecked::<Pool>(&pool.data.borrow())?;
+
+    let expected_pool = Pubkey::create_program_address(
+        &[
+            b"pool",
+            pool_data.mint_a.as_ref(),
+            pool_data.mint_b.as_ref(),
+            &pool_data.tick_spacing.to_le_bytes(),
+            &[pool_data.bump],
+        ],
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(ClmmError::PoolAddressMismatch.into());
+    }
+
+    if *mint_a.key != pool_data.mint_a || *mint_b.key != pool_data.mint_b {
+        return Err(ClmmError::MintAddressMismatch.into());
+    }
+
+    if *vault_a.key != get_associated_token_address(pool.key, mint_a.key) {
+        return Err(ClmmError::VaultAddressMismatch.into());
+    }
+
+    if *vault_b.key != get_associated_token_address(pool.key, mint_b.key) {
+        return Err(ClmmError::VaultAddressMismatch.into());
+    }
+
+    let mut position_data = load_position(program_id, pool.key, owner.key, position)?;
+
+    let liquidity_delta = i128::try_from(liquidity)
+        .map_err(|_| ProgramError::ArithmeticOverflow)?;
+
+    let (amount_a, amount_b) = modify_liquidity(
+        program_id,
+        pool.key,
+        &mut pool_data,
+        &mut position_data,
+        tick_array_lower,
+        tick_array_upper,
+        liquidity_delta,
+    )?;
+
+    if amount_a > amount_a_max || amount_b > amount_b_max {
+        return Err(ClmmError::SlippageExceed.into());
+    }
+
+    // transfer amount_a from owner_ata_a to vault_a
+    if amount_a > 0 {
+        let mint_a_decimals = Mint::unpack(&mint_a.data.borrow())?.decimals;
+
+        invoke(
+            &transfer_checked(
+                token_program.key,
+                owner_ata_a.key,
+                mint_a.key,
+                vault_a.key,
+                owner.key,
+                &[],
+                amount_a,
+                mint_a_decimals,
+            )?,
+            &[owner_ata_a.clone(), mint_a.clone(), vault_a.clone(), owner.clone()],
+        )?;
+    }
+
+    // transfer amount_b from owner_ata_b to vault_b
+    if amount_b > 0 {
+        let mint_b_decimals = Mint::unpack(&mint_b.data.borrow())?.decimals;
+
+        invoke(
+            &transfer_checked(
+                token_program.key,
+                owner_ata_b.key,
+                mint_b.key,
+                vault_b.key,
+                owner.key,
+                &[],
+                amount_b,
+                mint_b_decimals,
+            )?,
+            &[owner_ata_b.clone(), mint_b.clone(), vault_b.clone(), owner.clone()],
+        )?;
+    }
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+    position_data.serialize(&mut &mut position.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Removes `liquidity` from the position and pays it out together with every
+// fee the position has earned, so a zero `liquidity` just collects fees.
+pub fn process_decrease_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    liquidity: u128,
+    amount_a_min: u64,
+    amount_b_min: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let position = next_account_info(accounts_iter)?;
+    let tick_array_lower = next_account_info(accounts_iter)?;
+    let tick_array_upper = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let owner_ata_a = next_account_info(accounts_iter)?;
+    let owner_ata_b = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut pool_data =
+        try_from_slice_unchecked::<Pool>(&pool.data.borrow())?;
+
+    let expected_pool = Pubkey::create_program_address(
+        &[
+            b"pool",
+            pool_data.mint_a.as_ref(),
+            pool_data.mint_b.as_ref(),
+            &pool_data.tick_spacing.to_le_bytes(),
+            &[pool_data.bump],
+        ],
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(ClmmError::PoolAddressMismatch.into());
+    }
+
+    if *mint_a.key != pool_data.mint_a || *mint_b.key != pool_data.mint_b {
+        return Err(ClmmError::MintAddressMismatch.into());
+    }
+
+    if *vault_a.key != get_associated_token_address(pool.key, mint_a.key) {
+        return Err(ClmmError::VaultAddressMismatch.into());
+    }
+
+    if *vault_b.key != get_associated_token_address(pool.key, mint_b.key) {
+        return Err(ClmmError::VaultAddressMismatch.into());
+    }
+
+    let mut position_data = load_position(program_id, pool.key, owner.key, position)?;
+
+    if liquidity > position_data.liquidity {
+        return Err(ClmmError::InsufficientLiquidity.into());
+    }
+
+    let liquidity_delta = i128::try_from(liquidity)
+        .map_err(|_| ProgramError::ArithmeticOverflow)?;
+
+    let (amount_a, amount_b) = modify_liquidity(
+        program_id,
+        pool.key,
+        &mut pool_data,
+        &mut position_data,
+        tick_array_lower,
+        tick_array_upper,
+        -liquidity_delta,
+    )?;
+
+    if amount_a < amount_a_min || amount_b < amount_b_min {
+        return Err(ClmmError::SlippageExceed.into());
+    }
+
+    let payout_a = amount_a.checked_add(position_data.tokens_owed_a)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let payout_b = amount_b.checked_add(position_data.tokens_owed_b)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    position_data.tokens_owed_a = 0;
+    position_data.tokens_owed_b = 0;
+
+    // transfer payout_a from vault_a to owner_ata_a
+    if payout_a > 0 {
+        let mint_a_decimals = Mint::unpack(&mint_a.data.borrow())?.decimals;
+
+        invoke_signed(
+            &transfer_checked(
+                token_program.key,
+                vault_a.key,
+                mint_a.key,
+                owner_ata_a.key,
+                pool.key,
+                &[],
+                payout_a,
+                mint_a_decimals,
+            )?,
+            &[vault_a.clone(), mint_a.clone(), owner_ata_a.clone(), pool.clone()],
+            &[
+                &[
+                    b"pool",
+                    pool_data.mint_a.as_ref(),
+                    pool_data.mint_b.as_ref(),
+                    &pool_data.tick_spacing.to_le_bytes(),
+                    &[pool_data.bump],
+                ],
+            ],
+        )?;
+    }
+
+    // transfer payout_b from vault_b to owner_ata_b
+    if payout_b > 0 {
+        let mint_b_decimals = Mint::unpack(&mint_b.data.borrow())?.decimals;
+
+        invoke_signed(
+            &transfer_checked(
+                token_program.key,
+                vault_b.key,
+                mint_b.key,
+                owner_ata_b.key,
+                pool.key,
+                &[],
+                payout_b,
+                mint_b_decimals,
+            )?,
+            &[vault_b.clone(), mint_b.clone(), owner_ata_b.clone(), pool.clone()],
+            &[
+                &[
+                    b"pool",
+                    pool_data.mint_a.as_ref(),
+                    pool_data.mint_b.as_ref(),
+                    &pool_data.tick_spacing.to_le_bytes(),
+                    &[pool_data.bump],
+                ],
+            ],
+        )?;
+    }
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+    position_data.serialize(&mut &mut position.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Exact-input swap. Tick arrays the price may move through follow the fixed
+// accounts, ordered in the swap direction.
+pub fn process_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_out: u64,
+    a_to_b: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let user_ata_a = next_account_info(accounts_iter)?;
+    let user_ata_b = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let tick_array_accounts: Vec<&AccountInfo> = accounts_iter.collect();
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount_in == 0 {
+        return Err(ClmmError::ZeroSwapAmount.into());
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut pool_data =
+        try_from_slice_unchecked::<Pool>(&pool.data.borrow())?;
+
+    let expected_pool = Pubkey::create_program_address(
+        &[
+            b"pool",
+            pool_data.mint_a.as_ref(),
+            pool_data.mint_b.as_ref(),
+            &pool_data.tick_spacing.to_le_bytes(),
+            &[pool_data.bump],
+        ],
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(ClmmError::PoolAddressMismatch.into());
+    }
+
+    if *mint_a.key != pool_data.mint_a || *mint_b.key != pool_data.mint_b {
+        return Err(ClmmError::MintAddressMismatch.into());
+    }
+
+    if *vault_a.key != get_associated_token_address(pool.key, mint_a.key) {
+        return Err(ClmmError::VaultAddressMismatch.into());
+    }
+
+    if *vault_b.key != get_associated_token_address(pool.key, mint_b.key) {
+        return Err(ClmmError::VaultAddressMismatch.into());
+    }
+
+    let mut tick_arrays = Vec::with_capacity(tick_array_accounts.len());
+
+    for tick_array in &tick_array_accounts {
+        tick_arrays.push(load_tick_array(program_id, pool.key, tick_array)?);
+    }
+
+    // strictly ordered arrays also rule out passing the same array twice
+    let ordered = tick_arrays.windows(2).all(|pair| {
+        if a_to_b {
+            pair[0].start_tick > pair[1].start_tick
+        } else {
+            pair[0].start_tick < pair[1].start_tick
+        }
+    });
+
+    if !ordered {
+        return Err(ClmmError::TickArrayMismatch.into());
+    }
+
+    let tick_spacing = pool_data.tick_spacing;
+    let sqrt_price_limit = if a_to_b { MIN_SQRT_PRICE } else { MAX_SQRT_PRICE };
+
+    let mut amount_remaining = amount_in;
+    let mut amount_out: u64 = 0;
+    let mut array_index = 0;
+
+    // walk the price from one initialized tick to the next, swapping against
+    // the liquidity active in between, until the input is used up
+    while amount_remaining > 0 && pool_data.sqrt_price != sqrt_price_limit {
+        let search_tick = if a_to_b { pool_data.tick_current } else { pool_data.tick_current + 1 };
+        let start_tick = TickArray::start_tick_for(search_tick, tick_spacing);
+
+        while tick_arrays.get(array_index).ok_or(ClmmError::MissingTickArray)?.start_tick != start_tick {
+            array_index += 1;
+        }
+
+        let tick_array = &mut tick_arrays[array_index];
+
+        let (next_tick, initialized) = tick_array
+            .next_initialized_tick(pool_data.tick_current, tick_spacing, a_to_b)
+            .ok_or(ClmmError::TickArrayMismatch)?;
+        let next_tick = next_tick.clamp(MIN_TICK, MAX_TICK);
+
+        let sqrt_price_target = sqrt_price_at_tick(next_tick)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let step = compute_swap_step(
+            pool_data.sqrt_price,
+            sqrt_price_target,
+            pool_data.liquidity,
+            amount_remaining,
+            pool_data.fee_bps,
+            a_to_b,
+        ).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        amount_remaining -= step.amount_in + step.fee_amount;
+        amount_out = amount_out.checked_add(step.amount_out)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // fees accrue per unit of liquidity in range, in the input token
+        if let Some(fee_growth) = ((step.fee_amount as u128) << 64).checked_div(pool_data.liquidity) {
+            if a_to_b {
+                pool_data.fee_growth_global_a = pool_data.fee_growth_global_a.wrapping_add(fee_growth);
+            } else {
+                pool_data.fee_growth_global_b = pool_data.fee_growth_global_b.wrapping_add(fee_growth);
+            }
+        }
+
+        let sqrt_price_start = pool_data.sqrt_price;
+        pool_data.sqrt_price = step.sqrt_price_next;
+
+        if step.sqrt_price_next == sqrt_price_target {
+            if initialized {
+                let tick = tick_array.tick_mut(next_tick, tick_spacing)
+                    .ok_or(ClmmError::TickArrayMismatch)?;
+
+                tick.fee_growth_outside_a = pool_data.fee_growth_global_a.wrapping_sub(tick.fee_growth_outside_a);
+                tick.fee_growth_outside_b = pool_data.fee_growth_global_b.wrapping_sub(tick.fee_growth_outside_b);
+
+                let liquidity_net = if a_to_b { -tick.liquidity_net } else { tick.liquidity_net };
+
+                pool_data.liquidity = pool_data.liquidity.checked_add_signed(liquidity_net)
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+            }
+
+            // stopping on the edge of an array without crossing leaves the
+            // tick for the next array to cross
+            pool_data.tick_current = if a_to_b || !initialized { next_tick - 1 } else { next_tick };
+        } else if step.sqrt_price_next != sqrt_price_start {
+            pool_data.tick_current = tick_at_sqrt_price(step.sqrt_price_next)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+    }
+
+    if amount_out < min_out {
+        return Err(ClmmError::SlippageExceed.into());
+    }
+
+    let amount_in_used = amount_in - amount_remaining;
+
+    let (mint_in, mint_out, vault_in, vault_out, user_ata_in, user_ata_out) = if a_to_b {
+        (mint_a, mint_b, vault_a, vault_b, user_ata_a, user_ata_b)
+    } else {
+        (mint_b, mint_a, vault_b, vault_a, user_ata_b, user_ata_a)
+    };
+
+    // transfer amount_in_used of mint_in from user_ata_in to vault_in
+    let mint_in_decimals = Mint::unpack(&mint_in.data.borrow())?.decimals;
+
+    invoke(
+        &transfer_checked(
+            token_program.key,
+            user_ata_in.key,
+            mint_in.key,
+            vault_in.key,
+            user.key,
+            &[],
+            amount_in_used,
+            mint_in_decimals,
+        )?,
+        &[user_ata_in.clone(), mint_in.clone(), vault_in.clone(), user.clone()],
+    )?;
+
+    // transfer amount_out of mint_out from vault_out to user_ata_out
+    let mint_out_decimals = Mint::unpack(&mint_out.data.borrow())?.decimals;
+
+    invoke_signed(
+        &transfer_checked(
+            token_program.key,
+            vault_out.key,
+            mint_out.key,
+            user_ata_out.key,
+            pool.key,
+            &[],
+            amount_out,
+            mint_out_decimals,
+        )?,
+        &[vault_out.clone(), mint_out.clone(), user_ata_out.clone(), pool.clone()],
+        &[
+            &[
+                b"pool",
+                pool_data.mint_a.as_ref(),
+                pool_data.mint_b.as_ref(),
+                &pool_data.tick_spacing.to_le_bytes(),
+                &[pool_data.bump],
+            ],
+        ],
+    )?;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    for (tick_array, tick_array_data) in tick_array_accounts.iter().zip(&tick_arrays) {
+        tick_array_data.serialize(&mut &mut tick_array.data.borrow_mut()[..])?;
+    }
+
+    Ok(())
+}
+
+fn load_tick_array(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    tick_array: &AccountInfo,
+) -> Result<TickArray, ProgramError> {
+    let tick_array_data =
+        try_from_slice_unchecked::<TickArray>(&tick_array.data.borrow())?;
+
+    let expected_tick_array = Pubkey::create_program_address(
+        &[b"tick_array", pool.as_ref(), &tick_array_data.start_tick.to_le_bytes(), &[tick_array_data.bump]],
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_tick_array != *tick_array.key || tick_array_data.pool != *pool {
+        return Err(ClmmError::TickArrayAddressMismatch.into());
+    }
+
+    Ok(tick_array_data)
+}
+
+fn load_position(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    owner: &Pubkey,
+    position: &AccountInfo,
+) -> Result<Position, ProgramError> {
+    let position_data =
+        try_from_slice_unchecked::<Position>(&position.data.borrow())?;
+
+    let expected_position = Pubkey::create_program_address(
+        &[
+            b"position",
+            position_data.pool.as_ref(),
+            position_data.owner.as_ref(),
+            &position_data.tick_lower.to_le_bytes(),
+            &position_data.tick_upper.to_le_bytes(),
+            &[position_data.bump],
+        ],
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_position != *position.key || position_data.pool != *pool {
+        return Err(ClmmError::PositionAddressMismatch.into());
+    }
+
+    if position_data.owner != *owner {
+        return Err(ClmmError::NotPositionOwner.into());
+    }
+
+    Ok(position_data)
+}
+
+// Applies `liquidity_delta` to the position, both of its ticks and, if the
+// range is active, the pool. Returns the token amounts the change is worth,
+// rounded up when adding and down when removing.
+fn modify_liquidity(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pool_data: &mut Pool,
+    position_data: &mut Position,
+    tick_array_lower: &AccountInfo,
+    tick_array_upper: &AccountInfo,
+    liquidity_delta: i128,
+) -> Result<(u64, u64), ProgramError> {
+    let tick_spacing = pool_data.tick_spacing;
+
+    let mut lower_array = load_tick_array(program_id, pool, tick_array_lower)?;
+    let mut upper_array = if tick_array_upper.key == tick_array_lower.key {
+        None
+    } else {
+        Some(load_tick_array(program_id, pool, tick_array_upper)?)
+    };
+
+    {
+        let (tick_lower, tick_upper) = match upper_array.as_mut() {
+            Some(upper_array) => (
+                lower_array.tick_mut(position_data.tick_lower, tick_spacing)
+                    .ok_or(ClmmError::TickArrayMismatch)?,
+                upper_array.tick_mut(position_data.tick_upper, tick_spacing)
+                    .ok_or(ClmmError::TickArrayMismatch)?,
+            ),
+            None => lower_array
+                .tick_pair_mut(position_data.tick_lower, position_data.tick_upper, tick_spacing)
+                .ok_or(ClmmError::TickArrayMismatch)?,
+        };
+
+        update_tick(tick_lower, position_data.tick_lower, pool_data, liquidity_delta, false)?;
+        update_tick(tick_upper, position_data.tick_upper, pool_data, liquidity_delta, true)?;
+
+        // settle fees earned at the old liquidity before changing it
+        let (fee_growth_inside_a, fee_growth_inside_b) = fee_growth_inside(pool_data, position_data, tick_lower, tick_upper);
+
+        let owed_a = mul_shr_64(
+            position_data.liquidity,
+            fee_growth_inside_a.wrapping_sub(position_data.fee_growth_inside_a),
+        ).and_then(|owed| u64::try_from(owed).ok())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let owed_b = mul_shr_64(
+            position_data.liquidity,
+            fee_growth_inside_b.wrapping_sub(position_data.fee_growth_inside_b),
+        ).and_then(|owed| u64::try_from(owed).ok())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        position_data.tokens_owed_a = position_data.tokens_owed_a.checked_add(owed_a)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        position_data.tokens_owed_b = position_data.tokens_owed_b.checked_add(owed_b)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        position_data.fee_growth_inside_a = fee_growth_inside_a;
+        position_data.fee_growth_inside_b = fee_growth_inside_b;
+    }
+
+    position_data.liquidity = position_data.liquidity.checked_add_signed(liquidity_delta)
+        .ok_or(ClmmError::InsufficientLiquidity)?;
+
+    let sqrt_price_lower = sqrt_price_at_tick(position_data.tick_lower)
+        .ok_or(ClmmError::InvalidTickRange)?;
+    let sqrt_price_upper = sqrt_price_at_tick(position_data.tick_upper)
+        .ok_or(ClmmError::InvalidTickRange)?;
+
+    let liquidity = liquidity_delta.unsigned_abs();
+    let round_up = liquidity_delta > 0;
+
+    // below the range the position is all token A, above it all token B
+    let (amount_a, amount_b) = if pool_data.tick_current < position_data.tick_lower {
+        (amount_a_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up), Some(0))
+    } else if pool_data.tick_current < position_data.tick_upper {
+        pool_data.liquidity = pool_data.liquidity.checked_add_signed(liquidity_delta)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        (
+            amount_a_delta(pool_data.sqrt_price, sqrt_price_upper, liquidity, round_up),
+            amount_b_delta(sqrt_price_lower, pool_data.sqrt_price, liquidity, round_up),
+        )
+    } else {
+        (Some(0), amount_b_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up))
+    };
+
+    let amount_a = amount_a.and_then(|amount| u64::try_from(amount).ok())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let amount_b = amount_b.and_then(|amount| u64::try_from(amount).ok())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    lower_array.serialize(&mut &mut tick_array_lower.data.borrow_mut()[..])?;
+
+    if let Some(upper_array) = upper_array {
+        upper_array.serialize(&mut &mut tick_array_upper.data.borrow_mut()[..])?;
+    }
+
+    Ok((amount_a, amount_b))
+}
+
+fn update_tick(
+    tick: &mut Tick,
+    tick_index: i32,
+    pool_data: &Pool,
+    liquidity_delta: i128,
+    upper: bool,
+) -> ProgramResult {
+    // by convention all growth so far happened below a newly used tick
+    if !tick.is_initialized() && tick_index <= pool_data.tick_current {
+        tick.fee_growth_outside_a = pool_data.fee_growth_global_a;
+        tick.fee_growth_outside_b = pool_data.fee_growth_global_b;
+    }
+
+    tick.liquidity_gross = tick.liquidity_gross.checked_add_signed(liquidity_delta)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // the price crossing the lower tick upwards adds liquidity, the upper removes it
+    tick.liquidity_net = if upper {
+        tick.liquidity_net.checked_sub(liquidity_delta)
+    } else {
+        tick.liquidity_net.checked_add(liquidity_delta)
+    }.ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if !tick.is_initialized() {
+        *tick = Tick::default();
+    }
+
+    Ok(())
+}
+
+fn fee_growth_inside(pool_data: &Pool, position_data: &Position, tick_lower: &Tick, tick_upper: &Tick) -> (u128, u128) {
+    let (below_a, below_b) = if pool_data.tick_current >= position_data.tick_lower {
+        (tick_lower.fee_growth_outside_a, tick_lower.fee_growth_outside_b)
+    } else {
+        (
+            pool_data.fee_growth_global_a.wrapping_sub(tick_lower.fee_growth_outside_a),
+            pool_data.fee_growth_global_b.wrapping_sub(tick_lower.fee_growth_outside_b),
+        )
+    };
+
+    let (above_a, above_b) = if pool_data.tick_current < position_data.tick_upper {
+        (tick_upper.fee_growth_outside_a, tick_upper.fee_growth_outside_b)
+    } else {
+        (
+            pool_data.fee_growth_global_a.wrapping_sub(tick_upper.fee_growth_outside_a),
+            pool_data.fee_growth_global_b.wrapping_sub(tick_upper.fee_growth_outside_b),
+        )
+    };
+
+    (
+        pool_data.fee_growth_global_a.wrapping_sub(below_a).wrapping_sub(above_a),
+        pool_data.fee_growth_global_b.wrapping_sub(below_b).wrapping_sub(above_b),
+    )
+}
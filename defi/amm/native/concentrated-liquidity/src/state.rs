@@ -0,0 +1,178 @@
+use solana_program::pubkey::Pubkey;
+
+use borsh::{BorshSerialize, BorshDeserialize};
+
+pub const TICK_ARRAY_SIZE: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Pool {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub tick_spacing: u16,
+    pub fee_bps: u16,
+    pub sqrt_price: u128,
+    pub tick_current: i32,
+    pub liquidity: u128,
+    pub fee_growth_global_a: u128,
+    pub fee_growth_global_b: u128,
+    pub bump: u8,
+}
+
+// Fee growth outside a tick is measured relative to the current price: below
+// the tick while the price is above it and vice versa. Crossing the tick
+// flips it.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tick {
+    pub liquidity_net: i128,
+    pub liquidity_gross: u128,
+    pub fee_growth_outside_a: u128,
+    pub fee_growth_outside_b: u128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct TickArray {
+    pub pool: Pubkey,
+    pub start_tick: i32,
+    pub ticks: [Tick; TICK_ARRAY_SIZE],
+    pub bump: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    pub fee_growth_inside_a: u128,
+    pub fee_growth_inside_b: u128,
+    pub tokens_owed_a: u64,
+    pub tokens_owed_b: u64,
+    pub bump: u8,
+}
+
+impl Pool {
+    pub const SPACE: usize =
+        32       // mint_a pubkey
+        + 32     // mint_b pubkey
+        + 2      // tick_spacing
+        + 2      // fee_bps
+        + 16     // sqrt_price
+        + 4      // tick_current
+        + 16     // liquidity
+        + 16     // fee_growth_global_a
+        + 16     // fee_growth_global_b
+        + 1;     // bump
+}
+
+impl Tick {
+    pub const SPACE: usize =
+        16       // liquidity_net
+        + 16     // liquidity_gross
+        + 16     // fee_growth_outside_a
+        + 16;    // fee_growth_outside_b
+
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross > 0
+    }
+}
+
+impl TickArray {
+    pub const SPACE: usize =
+        32       // pool pubkey
+        + 4      // start_tick
+        + Tick::SPACE * TICK_ARRAY_SIZE
+        + 1;     // bump
+
+    // Start tick of the array holding `tick`.
+    pub fn start_tick_for(tick: i32, tick_spacing: u16) -> i32 {
+        let ticks_in_array = tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+
+        tick.div_euclid(ticks_in_array) * ticks_in_array
+    }
+
+    pub fn tick_mut(&mut self, tick: i32, tick_spacing: u16) -> Option<&mut Tick> {
+        let offset = self.offset(tick, tick_spacing)?;
+
+        self.ticks.get_mut(offset)
+    }
+
+    // Both ends of a position whose ticks live in this array.
+    pub fn tick_pair_mut(&mut self, tick_lower: i32, tick_upper: i32, tick_spacing: u16) -> Option<(&mut Tick, &mut Tick)> {
+        let lower = self.offset(tick_lower, tick_spacing)?;
+        let upper = self.offset(tick_upper, tick_spacing)?;
+
+        if lower >= upper {
+            return None;
+        }
+
+        let (low, high) = self.ticks.split_at_mut(upper);
+
+        Some((&mut low[lower], &mut high[0]))
+    }
+
+    // Next initialized tick the price reaches when moving from `tick_current`
+    // in the swap direction: at or below it when selling token A, above it
+    // when selling token B. Falls back to the edge of the array, reported as
+    // uninitialized, when there is none. None if the search doesn't start in
+    // this array.
+    pub fn next_initialized_tick(&self, tick_current: i32, tick_spacing: u16, a_to_b: bool) -> Option<(i32, bool)> {
+        let spacing = tick_spacing as i32;
+        let ticks_in_array = spacing * TICK_ARRAY_SIZE as i32;
+
+        if a_to_b {
+            let offset = (tick_current - self.start_tick).div_euclid(spacing);
+
+            if !(0..TICK_ARRAY_SIZE as i32).contains(&offset) {
+                return None;
+            }
+
+            let found = (0..=offset as usize).rev().find(|&i| self.ticks[i].is_initialized());
+
+            Some(match found {
+                Some(i) => (self.start_tick + i as i32 * spacing, true),
+                None => (self.start_tick, false),
+            })
+        } else {
+            if !(0..ticks_in_array).contains(&(tick_current + 1 - self.start_tick)) {
+                return None;
+            }
+
+            let offset = (tick_current - self.start_tick).div_euclid(spacing) + 1;
+
+            let found = (offset as usize..TICK_ARRAY_SIZE).find(|&i| self.ticks[i].is_initialized());
+
+            Some(match found {
+                Some(i) => (self.start_tick + i as i32 * spacing, true),
+                None => (self.start_tick + ticks_in_array, false),
+            })
+        }
+    }
+
+    fn offset(&self, tick: i32, tick_spacing: u16) -> Option<usize> {
+        let spacing = tick_spacing as i32;
+        let distance = tick - self.start_tick;
+
+        if distance < 0 || distance % spacing != 0 {
+            return None;
+        }
+
+        let offset = (distance / spacing) as usize;
+
+        (offset < TICK_ARRAY_SIZE).then_some(offset)
+    }
+}
+
+impl Position {
+    pub const SPACE: usize =
+        32       // pool pubkey
+        + 32     // owner pubkey
+        + 4      // tick_lower
+        + 4      // tick_upper
+        + 16     // liquidity
+        + 16     // fee_growth_inside_a
+        + 16     // fee_growth_inside_b
+        + 8      // tokens_owed_a
+        + 8      // tokens_owed_b
+        + 1;     // bump
+}
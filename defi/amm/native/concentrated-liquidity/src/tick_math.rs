@@ -0,0 +1,77 @@
+// Prices are stored as sqrt(price) in Q64.64 fixed point, where price is the
+// amount of token B per token A and tick t corresponds to price 1.0001^t.
+
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+
+pub const MIN_SQRT_PRICE: u128 = 4_295_048_016;
+pub const MAX_SQRT_PRICE: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+// floor(2^64 / 1.0001^(2^i / 2)), one entry per bit of |tick|
+const INVERSE_SQRT_RATIOS: [u128; 19] = [
+    0xfffcb933bd6fad37,
+    0xfff97272373d4132,
+    0xfff2e50f5f656932,
+    0xffe5caca7e10e4e6,
+    0xffcb9843d60f6159,
+    0xff973b41fa98c081,
+    0xff2ea16466c96a38,
+    0xfe5dee046a99a2a8,
+    0xfcbe86c7900a88ae,
+    0xf987a7253ac41317,
+    0xf3392b0822b70005,
+    0xe7159475a2c29b74,
+    0xd097f3bdfd2022b8,
+    0xa9f746462d870fdf,
+    0x70d869a156d2a1b8,
+    0x31be135f97d08fd9,
+    0x09aa508b5b7a84e1,
+    0x005d6af8dedb8119,
+    0x00002216e584f5fa,
+];
+
+// sqrt(1.0001^tick) in Q64.64. None if the tick is out of range.
+pub fn sqrt_price_at_tick(tick: i32) -> Option<u128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+
+    let abs_tick = tick.unsigned_abs();
+
+    // multiply together 1 / sqrt(1.0001)^(2^i) for every bit set in |tick|
+    let mut ratio: u128 = 1 << 64;
+
+    for (bit, inverse_sqrt_ratio) in INVERSE_SQRT_RATIOS.iter().enumerate() {
+        if abs_tick & (1 << bit) != 0 {
+            ratio = (ratio * inverse_sqrt_ratio) >> 64;
+        }
+    }
+
+    if tick > 0 {
+        ratio = u128::MAX / ratio;
+    }
+
+    Some(ratio)
+}
+
+// Largest tick whose sqrt price is at or below `sqrt_price`.
+pub fn tick_at_sqrt_price(sqrt_price: u128) -> Option<i32> {
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price) {
+        return None;
+    }
+
+    let mut low = MIN_TICK;
+    let mut high = MAX_TICK;
+
+    while low < high {
+        let middle = low + (high - low + 1) / 2;
+
+        if sqrt_price_at_tick(middle)? <= sqrt_price {
+            low = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+
+    Some(low)
+}
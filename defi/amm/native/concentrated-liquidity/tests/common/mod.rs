@@ -0,0 +1,430 @@
+#![allow(dead_code)]
+
+use anyhow::Result;
+use borsh::BorshSerialize;
+
+use solana_program_test::*;
+
+use solana_sdk::{
+    borsh1::try_from_slice_unchecked,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::{
+    instruction::create_account,
+    program::id as system_program_id,
+};
+use spl_associated_token_account::{
+    get_associated_token_address,
+    id as associated_token_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{
+    id as token_program_id,
+    instruction::{initialize_mint2, mint_to},
+    state::{Account as TokenAccount, Mint},
+};
+
+use concentrated_liquidity::processor::process_instruction;
+use concentrated_liquidity::state::{Pool, Position, TickArray};
+
+pub const DECIMALS: u8 = 6;
+pub const USER_FUNDS: u64 = 10_000_000_000;
+pub const TICK_SPACING: u16 = 10;
+pub const FEE_BPS: u16 = 30;
+
+pub async fn start(program_id: &Pubkey) -> ProgramTestContext {
+    ProgramTest::new(
+        "concentrated_liquidity",
+        *program_id,
+        processor!(process_instruction),
+    ).start_with_context().await
+}
+
+// Fetches a fresh blockhash for every transaction so that repeating an
+// identical instruction isn't rejected as already processed.
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await?;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub fn custom_error(result: Result<(), BanksClientError>) -> Option<u32> {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+pub async fn create_mint(context: &mut ProgramTestContext) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[
+            create_account(
+                &payer,
+                &mint.pubkey(),
+                Rent::default().minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &token_program_id(),
+            ),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer, None, DECIMALS)?,
+        ],
+        &[&mint],
+    ).await?;
+
+    Ok(mint.pubkey())
+}
+
+// Only works for mints created by `create_mint`, whose authority is the payer.
+pub async fn create_funded_ata(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+    let ata = get_associated_token_address(owner, mint);
+
+    send(
+        context,
+        &[
+            create_associated_token_account_idempotent(&payer, owner, mint, &token_program_id()),
+            mint_to(&token_program_id(), mint, &ata, &payer, &[], amount)?,
+        ],
+        &[],
+    ).await?;
+
+    Ok(ata)
+}
+
+// Creates two mints in pool order, funds the payer with USER_FUNDS of each,
+// and creates a pool at `sqrt_price` together with the tick arrays covering
+// `start_ticks`. Returns (mint_a, mint_b, pool).
+pub async fn setup_pool(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    sqrt_price: u128,
+    start_ticks: &[i32],
+) -> Result<(Pubkey, Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let mint_x = create_mint(context).await?;
+    let mint_y = create_mint(context).await?;
+    let (mint_a, mint_b) = if mint_x < mint_y { (mint_x, mint_y) } else { (mint_y, mint_x) };
+
+    create_funded_ata(context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(context, &payer, &mint_b, USER_FUNDS).await?;
+
+    send(
+        context,
+        &[initialize_pool_ix(program_id, &payer, &mint_a, &mint_b, TICK_SPACING, FEE_BPS, sqrt_price)?],
+        &[],
+    ).await?;
+
+    let pool = pool_address(program_id, &mint_a, &mint_b, TICK_SPACING);
+
+    for &start_tick in start_ticks {
+        send(
+            context,
+            &[initialize_tick_array_ix(program_id, &payer, &pool, start_tick)?],
+            &[],
+        ).await?;
+    }
+
+    Ok((mint_a, mint_b, pool))
+}
+
+// Opens a position for the payer and deposits `liquidity` into it.
+// Returns the position address.
+pub async fn open_funded_position(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    ticks: (i32, i32),
+    liquidity: u128,
+) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[
+            open_position_ix(program_id, &payer, pool, ticks)?,
+            increase_liquidity_ix(program_id, &payer, pool, mint_a, mint_b, ticks, liquidity, (u64::MAX, u64::MAX))?,
+        ],
+        &[],
+    ).await?;
+
+    Ok(position_address(program_id, pool, &payer, ticks))
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> Result<u64> {
+    let account = context.banks_client.get_account(*address).await?.unwrap();
+
+    Ok(TokenAccount::unpack(&account.data)?.amount)
+}
+
+pub async fn pool_state(context: &mut ProgramTestContext, pool: &Pubkey) -> Result<Pool> {
+    let account = context.banks_client.get_account(*pool).await?.unwrap();
+
+    Ok(try_from_slice_unchecked::<Pool>(&account.data)?)
+}
+
+pub async fn position_state(context: &mut ProgramTestContext, position: &Pubkey) -> Result<Position> {
+    let account = context.banks_client.get_account(*position).await?.unwrap();
+
+    Ok(try_from_slice_unchecked::<Position>(&account.data)?)
+}
+
+pub fn pool_address(program_id: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey, tick_spacing: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"pool", mint_a.as_ref(), mint_b.as_ref(), &tick_spacing.to_le_bytes()],
+        program_id,
+    ).0
+}
+
+pub fn tick_array_address(program_id: &Pubkey, pool: &Pubkey, start_tick: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array", pool.as_ref(), &start_tick.to_le_bytes()],
+        program_id,
+    ).0
+}
+
+pub fn position_address(program_id: &Pubkey, pool: &Pubkey, owner: &Pubkey, ticks: (i32, i32)) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"position", pool.as_ref(), owner.as_ref(), &ticks.0.to_le_bytes(), &ticks.1.to_le_bytes()],
+        program_id,
+    ).0
+}
+
+pub fn initialize_pool_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    tick_spacing: u16,
+    fee_bps: u16,
+    sqrt_price: u128,
+) -> Result<Instruction> {
+    let pool = pool_address(program_id, mint_a, mint_b, tick_spacing);
+
+    let mut data = vec![0];
+    InitializePoolPayload { tick_spacing, fee_bps, sqrt_price }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(pool, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(get_associated_token_address(&pool, mint_a), false),
+            AccountMeta::new(get_associated_token_address(&pool, mint_b), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn initialize_tick_array_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    pool: &Pubkey,
+    start_tick: i32,
+) -> Result<Instruction> {
+    let mut data = vec![1];
+    InitializeTickArrayPayload { start_tick }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new(tick_array_address(program_id, pool, start_tick), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn open_position_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    ticks: (i32, i32),
+) -> Result<Instruction> {
+    let mut data = vec![2];
+    OpenPositionPayload { tick_lower: ticks.0, tick_upper: ticks.1 }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new(position_address(program_id, pool, owner, ticks), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn increase_liquidity_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    ticks: (i32, i32),
+    liquidity: u128,
+    amounts_max: (u64, u64),
+) -> Result<Instruction> {
+    let mut data = vec![3];
+    LiquidityPayload { liquidity, amount_a: amounts_max.0, amount_b: amounts_max.1 }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        position_accounts(program_id, owner, pool, mint_a, mint_b, ticks),
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn decrease_liquidity_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    ticks: (i32, i32),
+    liquidity: u128,
+    amounts_min: (u64, u64),
+) -> Result<Instruction> {
+    let mut data = vec![4];
+    LiquidityPayload { liquidity, amount_a: amounts_min.0, amount_b: amounts_min.1 }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        position_accounts(program_id, owner, pool, mint_a, mint_b, ticks),
+    ))
+}
+
+fn position_accounts(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    ticks: (i32, i32),
+) -> Vec<AccountMeta> {
+    let start_lower = TickArray::start_tick_for(ticks.0, TICK_SPACING);
+    let start_upper = TickArray::start_tick_for(ticks.1, TICK_SPACING);
+
+    vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(position_address(program_id, pool, owner, ticks), false),
+        AccountMeta::new(tick_array_address(program_id, pool, start_lower), false),
+        AccountMeta::new(tick_array_address(program_id, pool, start_upper), false),
+        AccountMeta::new_readonly(*mint_a, false),
+        AccountMeta::new_readonly(*mint_b, false),
+        AccountMeta::new(get_associated_token_address(pool, mint_a), false),
+        AccountMeta::new(get_associated_token_address(pool, mint_b), false),
+        AccountMeta::new(get_associated_token_address(owner, mint_a), false),
+        AccountMeta::new(get_associated_token_address(owner, mint_b), false),
+        AccountMeta::new_readonly(token_program_id(), false),
+    ]
+}
+
+// `start_ticks` lists the tick arrays the swap may cross, in swap direction.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+    a_to_b: bool,
+    start_ticks: &[i32],
+) -> Result<Instruction> {
+    let mut data = vec![5];
+    SwapPayload { amount_in, min_out, a_to_b }.serialize(&mut data)?;
+
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new_readonly(*mint_a, false),
+        AccountMeta::new_readonly(*mint_b, false),
+        AccountMeta::new(get_associated_token_address(pool, mint_a), false),
+        AccountMeta::new(get_associated_token_address(pool, mint_b), false),
+        AccountMeta::new(get_associated_token_address(user, mint_a), false),
+        AccountMeta::new(get_associated_token_address(user, mint_b), false),
+        AccountMeta::new_readonly(token_program_id(), false),
+    ];
+
+    for &start_tick in start_ticks {
+        accounts.push(AccountMeta::new(tick_array_address(program_id, pool, start_tick), false));
+    }
+
+    Ok(Instruction::new_with_bytes(*program_id, &data, accounts))
+}
+
+#[derive(BorshSerialize)]
+struct InitializePoolPayload {
+    tick_spacing: u16,
+    fee_bps: u16,
+    sqrt_price: u128,
+}
+
+#[derive(BorshSerialize)]
+struct InitializeTickArrayPayload {
+    start_tick: i32,
+}
+
+#[derive(BorshSerialize)]
+struct OpenPositionPayload {
+    tick_lower: i32,
+    tick_upper: i32,
+}
+
+// Shared by increase (max amounts) and decrease (min amounts).
+#[derive(BorshSerialize)]
+struct LiquidityPayload {
+    liquidity: u128,
+    amount_a: u64,
+    amount_b: u64,
+}
+
+#[derive(BorshSerialize)]
+struct SwapPayload {
+    amount_in: u64,
+    min_out: u64,
+    a_to_b: bool,
+}
@@ -0,0 +1,250 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use concentrated_liquidity::error::ClmmError;
+use concentrated_liquidity::math::{amount_a_delta, amount_b_delta};
+use concentrated_liquidity::tick_math::sqrt_price_at_tick;
+
+use common::*;
+
+// price 1, tick 0
+const SQRT_PRICE_ONE: u128 = 1 << 64;
+const LIQUIDITY: u128 = 1_000_000_000;
+
+#[tokio::test]
+async fn position_in_range_deposits_both_tokens_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, SQRT_PRICE_ONE, &[-320, 0]).await?;
+
+    let ticks = (-100, 100);
+
+    let position = open_funded_position(&mut context, &program_id, &pool, &mint_a, &mint_b, ticks, LIQUIDITY).await?;
+
+    let expected_a = amount_a_delta(SQRT_PRICE_ONE, sqrt_price_at_tick(ticks.1).unwrap(), LIQUIDITY, true).unwrap();
+    let expected_b = amount_b_delta(sqrt_price_at_tick(ticks.0).unwrap(), SQRT_PRICE_ONE, LIQUIDITY, true).unwrap();
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_a)).await? as u128, expected_a);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_b)).await? as u128, expected_b);
+
+    assert_eq!(pool_state(&mut context, &pool).await?.liquidity, LIQUIDITY);
+    assert_eq!(position_state(&mut context, &position).await?.liquidity, LIQUIDITY);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn position_above_price_deposits_only_token_a_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, SQRT_PRICE_ONE, &[0]).await?;
+
+    open_funded_position(&mut context, &program_id, &pool, &mint_a, &mint_b, (100, 200), LIQUIDITY).await?;
+
+    assert!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_a)).await? > 0);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_b)).await?, 0);
+
+    // the range isn't active yet
+    assert_eq!(pool_state(&mut context, &pool).await?.liquidity, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_crosses_initialized_ticks_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) =
+        setup_pool(&mut context, &program_id, SQRT_PRICE_ONE, &[-320, 0, 320, 640]).await?;
+
+    open_funded_position(&mut context, &program_id, &pool, &mint_a, &mint_b, (-100, 100), LIQUIDITY).await?;
+    open_funded_position(&mut context, &program_id, &pool, &mint_a, &mint_b, (50, 700), LIQUIDITY / 2).await?;
+
+    // buying token A pushes the price up through tick 50, where the second
+    // range joins, and tick 100, where the first one leaves
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 1, false, &[0, 320, 640])?],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert!(pool_data.tick_current > 100 && pool_data.tick_current < 700);
+    assert_eq!(pool_data.liquidity, LIQUIDITY / 2);
+    assert!(pool_data.fee_growth_global_b > 0);
+
+    // selling it back brings the price under tick 50 again
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_b_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 1, true, &[0, -320])?],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert!(pool_data.tick_current < 0);
+    assert_eq!(pool_data.liquidity, LIQUIDITY);
+    assert!(token_balance(&mut context, &payer_ata_b).await? > balance_b_before);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_needs_tick_arrays_along_the_way_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, SQRT_PRICE_ONE, &[0, 320]).await?;
+
+    open_funded_position(&mut context, &program_id, &pool, &mint_a, &mint_b, (0, 600), LIQUIDITY).await?;
+
+    // enough input to leave the first array, which is the only one passed
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 20_000_000, 0, false, &[0])?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(ClmmError::MissingTickArray as u32));
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 20_000_000, 0, false, &[0, 320])?],
+        &[],
+    ).await?;
+
+    assert!(pool_state(&mut context, &pool).await?.tick_current >= 320);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn decrease_liquidity_pays_out_fees_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, SQRT_PRICE_ONE, &[-320, 0]).await?;
+
+    let ticks = (-100, 100);
+
+    open_funded_position(&mut context, &program_id, &pool, &mint_a, &mint_b, ticks, LIQUIDITY).await?;
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let amount_in = 1_000_000;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0, true, &[0, -320])?],
+        &[],
+    ).await?;
+
+    // zero liquidity only collects what the position has earned so far
+    let balance_a_before = token_balance(&mut context, &payer_ata_a).await?;
+
+    send(
+        &mut context,
+        &[decrease_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ticks, 0, (0, 0))?],
+        &[],
+    ).await?;
+
+    let fees_a = token_balance(&mut context, &payer_ata_a).await? - balance_a_before;
+    let expected_fees = amount_in * FEE_BPS as u64 / 10_000;
+
+    // the only position earns the whole fee, minus rounding
+    assert!(fees_a.abs_diff(expected_fees) <= 1);
+
+    // withdrawing everything leaves nothing but rounding dust in the vaults
+    send(
+        &mut context,
+        &[decrease_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ticks, LIQUIDITY, (0, 0))?],
+        &[],
+    ).await?;
+
+    assert!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_a)).await? <= 2);
+    assert!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_b)).await? <= 2);
+    assert_eq!(pool_state(&mut context, &pool).await?.liquidity, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn decrease_liquidity_checks_position_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, SQRT_PRICE_ONE, &[-320, 0]).await?;
+
+    let ticks = (-100, 100);
+
+    let position = open_funded_position(&mut context, &program_id, &pool, &mint_a, &mint_b, ticks, LIQUIDITY).await?;
+
+    let result = send(
+        &mut context,
+        &[decrease_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ticks, LIQUIDITY + 1, (0, 0))?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(ClmmError::InsufficientLiquidity as u32));
+
+    let intruder = Keypair::new();
+    create_funded_ata(&mut context, &intruder.pubkey(), &mint_a, 0).await?;
+    create_funded_ata(&mut context, &intruder.pubkey(), &mint_b, 0).await?;
+
+    let mut instruction = decrease_liquidity_ix(
+        &program_id,
+        &intruder.pubkey(),
+        &pool,
+        &mint_a,
+        &mint_b,
+        ticks,
+        LIQUIDITY,
+        (0, 0),
+    )?;
+    instruction.accounts[2] = AccountMeta::new(position, false);
+
+    let result = send(&mut context, &[instruction], &[&intruder]).await;
+
+    assert_eq!(custom_error(result), Some(ClmmError::NotPositionOwner as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn open_position_with_invalid_range_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, SQRT_PRICE_ONE, &[]).await?;
+
+    for ticks in [(100, 100), (100, -100), (-105, 100)] {
+        let result = send(
+            &mut context,
+            &[open_position_ix(&program_id, &payer, &pool, ticks)?],
+            &[],
+        ).await;
+
+        assert_eq!(custom_error(result), Some(ClmmError::InvalidTickRange as u32));
+    }
+
+    Ok(())
+}
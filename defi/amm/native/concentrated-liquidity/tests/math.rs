@@ -0,0 +1,143 @@
+use proptest::prelude::*;
+
+use concentrated_liquidity::math::{
+    amount_a_delta, amount_b_delta, next_sqrt_price_from_input, compute_swap_step,
+};
+use concentrated_liquidity::tick_math::{
+    sqrt_price_at_tick, tick_at_sqrt_price, MIN_TICK, MAX_TICK, MIN_SQRT_PRICE, MAX_SQRT_PRICE,
+};
+
+const FEE_BPS: u16 = 30;
+
+#[test]
+fn sqrt_price_at_known_ticks() {
+    assert_eq!(sqrt_price_at_tick(0), Some(1 << 64));
+    assert_eq!(sqrt_price_at_tick(MIN_TICK), Some(MIN_SQRT_PRICE));
+    assert_eq!(sqrt_price_at_tick(MAX_TICK), Some(MAX_SQRT_PRICE));
+    assert_eq!(sqrt_price_at_tick(MIN_TICK - 1), None);
+    assert_eq!(sqrt_price_at_tick(MAX_TICK + 1), None);
+
+    // 1.0001^(6932 / 2) is roughly sqrt(2)
+    let sqrt_two = sqrt_price_at_tick(6_932).unwrap() as f64 / 2f64.powi(64);
+
+    assert!((sqrt_two - 2f64.sqrt()).abs() < 1e-4);
+}
+
+#[test]
+fn amount_deltas_for_unit_range() {
+    let sqrt_price_lower = sqrt_price_at_tick(0).unwrap();
+    let sqrt_price_upper = sqrt_price_lower * 2;
+
+    // between prices 1 and 4 one unit of liquidity holds 1/2 of A or 1 of B
+    assert_eq!(amount_a_delta(sqrt_price_lower, sqrt_price_upper, 1_000_000, false), Some(500_000));
+    assert_eq!(amount_b_delta(sqrt_price_lower, sqrt_price_upper, 1_000_000, false), Some(1_000_000));
+    assert_eq!(amount_a_delta(sqrt_price_upper, sqrt_price_lower, 1_000_000, false), None);
+}
+
+proptest! {
+    #[test]
+    fn sqrt_price_increases_with_tick(tick in MIN_TICK..MAX_TICK) {
+        prop_assert!(sqrt_price_at_tick(tick).unwrap() < sqrt_price_at_tick(tick + 1).unwrap());
+    }
+
+    #[test]
+    fn tick_at_sqrt_price_inverts_sqrt_price_at_tick(tick in MIN_TICK..MAX_TICK) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        let sqrt_price_next = sqrt_price_at_tick(tick + 1).unwrap();
+
+        prop_assert_eq!(tick_at_sqrt_price(sqrt_price), Some(tick));
+        prop_assert_eq!(tick_at_sqrt_price(sqrt_price_next - 1), Some(tick));
+    }
+
+    #[test]
+    fn rounding_up_never_pays_less(
+        tick_lower in -100_000i32..100_000,
+        width in 1i32..10_000,
+        liquidity in 1u128..1_000_000_000_000_000,
+    ) {
+        let sqrt_price_lower = sqrt_price_at_tick(tick_lower).unwrap();
+        let sqrt_price_upper = sqrt_price_at_tick(tick_lower + width).unwrap();
+
+        let a_down = amount_a_delta(sqrt_price_lower, sqrt_price_upper, liquidity, false).unwrap();
+        let a_up = amount_a_delta(sqrt_price_lower, sqrt_price_upper, liquidity, true).unwrap();
+        let b_down = amount_b_delta(sqrt_price_lower, sqrt_price_upper, liquidity, false).unwrap();
+        let b_up = amount_b_delta(sqrt_price_lower, sqrt_price_upper, liquidity, true).unwrap();
+
+        prop_assert!(a_up - a_down <= 1);
+        prop_assert!(b_up - b_down <= 1);
+    }
+
+    #[test]
+    fn input_moves_price_against_the_seller(
+        tick in -100_000i32..100_000,
+        liquidity in 1_000u128..1_000_000_000_000_000,
+        amount_in in 1u64..1_000_000_000_000,
+        a_to_b in any::<bool>(),
+    ) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+
+        let next = next_sqrt_price_from_input(sqrt_price, liquidity, amount_in, a_to_b).unwrap();
+
+        if a_to_b {
+            prop_assert!(next <= sqrt_price);
+
+            // the price only moves as far as the input can actually pay for
+            prop_assert!(amount_a_delta(next, sqrt_price, liquidity, true).unwrap() <= amount_in as u128);
+        } else {
+            prop_assert!(next >= sqrt_price);
+            prop_assert!(amount_b_delta(sqrt_price, next, liquidity, true).unwrap() <= amount_in as u128);
+        }
+    }
+
+    #[test]
+    fn swap_step_stays_within_budget_and_target(
+        tick in -100_000i32..100_000,
+        distance in 1i32..5_000,
+        liquidity in 0u128..1_000_000_000_000_000,
+        amount_remaining in 1u64..1_000_000_000_000,
+        a_to_b in any::<bool>(),
+    ) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        let target_tick = if a_to_b { tick - distance } else { tick + distance };
+        let sqrt_price_target = sqrt_price_at_tick(target_tick).unwrap();
+
+        let step = compute_swap_step(sqrt_price, sqrt_price_target, liquidity, amount_remaining, FEE_BPS, a_to_b)
+            .unwrap();
+
+        prop_assert!(step.amount_in + step.fee_amount <= amount_remaining);
+
+        if a_to_b {
+            prop_assert!(step.sqrt_price_next >= sqrt_price_target && step.sqrt_price_next <= sqrt_price);
+        } else {
+            prop_assert!(step.sqrt_price_next <= sqrt_price_target && step.sqrt_price_next >= sqrt_price);
+        }
+
+        if step.sqrt_price_next != sqrt_price_target {
+            prop_assert_eq!(step.amount_in + step.fee_amount, amount_remaining);
+        }
+    }
+
+    #[test]
+    fn swap_step_round_trip_does_not_profit(
+        tick in -100_000i32..100_000,
+        liquidity in 1_000_000u128..1_000_000_000_000_000,
+        amount_in in 1u64..1_000_000_000,
+        a_to_b in any::<bool>(),
+    ) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        let (limit_there, limit_back) = if a_to_b {
+            (MIN_SQRT_PRICE, MAX_SQRT_PRICE)
+        } else {
+            (MAX_SQRT_PRICE, MIN_SQRT_PRICE)
+        };
+
+        let there = compute_swap_step(sqrt_price, limit_there, liquidity, amount_in, 0, a_to_b).unwrap();
+
+        prop_assume!(there.amount_out > 0);
+
+        let back = compute_swap_step(there.sqrt_price_next, limit_back, liquidity, there.amount_out, 0, !a_to_b)
+            .unwrap();
+
+        prop_assert!(back.amount_out <= amount_in);
+    }
+}
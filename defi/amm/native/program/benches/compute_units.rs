@@ -0,0 +1,292 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use mollusk_svm::{program::keyed_account_for_system_program, Mollusk};
+use mollusk_svm_programs_token::{associated_token, token};
+
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+use program::{
+    client::*,
+    state::CurveType,
+};
+
+// Checked-in CU numbers, one `<instruction> <units>` pair per line. Run with
+// `UPDATE_CU_BASELINE=1` to rewrite it after an intentional change.
+const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/compute_units.baseline");
+const REGRESSION_THRESHOLD_PERCENT: u64 = 5;
+
+const FEE_BPS: u16 = 30;
+const PROTOCOL_FEE_BPS: u16 = 2_000;
+const USER_FUNDS: u64 = 10_000_000_000;
+const INITIAL_LIQUIDITY: u64 = 1_000_000_000;
+const DONATION: u64 = 1_000_000;
+
+// FlashLoan and FlashRepay read the Instructions sysvar of the surrounding
+// transaction, SwapNative wraps SOL through the system program and
+// ExecuteFeeChange needs FEE_CHANGE_DELAY to pass, none of which fit a
+// one-instruction-at-a-time run, so they aren't measured here.
+fn main() {
+    let program_id = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let referrer = Pubkey::new_unique();
+
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let mint_c = Pubkey::new_unique();
+
+    // Mollusk loads `program.so` from `target/deploy`, so `cargo build-sbf`
+    // has to have been run first.
+    let mut mollusk = Mollusk::new(&program_id, "program");
+    token::add_program(&mut mollusk);
+    associated_token::add_program(&mut mollusk);
+
+    let mut accounts = HashMap::from([
+        keyed_account_for_system_program(),
+        token::keyed_account(),
+        associated_token::keyed_account(),
+        (payer, Account::new(100 * LAMPORTS_PER_SOL, 0, &Pubkey::default())),
+        (mint_a, mint_account(&payer)),
+        (mint_b, mint_account(&payer)),
+        (mint_c, mint_account(&payer)),
+        (get_associated_token_address(&payer, &mint_a), token_account(&mint_a, &payer, USER_FUNDS)),
+        (get_associated_token_address(&payer, &mint_b), token_account(&mint_b, &payer, USER_FUNDS)),
+        (get_associated_token_address(&payer, &mint_c), token_account(&mint_c, &payer, USER_FUNDS)),
+        (get_associated_token_address(&referrer, &mint_a), token_account(&mint_a, &referrer, 0)),
+        (get_program_data_address(&program_id), program_data_account(&payer)),
+    ]);
+
+    let pool = pool_address(&program_id, &mint_a, &mint_b, FEE_BPS);
+    let stable_pool = pool_address(&program_id, &mint_b, &mint_c, FEE_BPS);
+
+    let instructions = [
+        (
+            "create_pool",
+            create_pool_ix(
+                &program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                FEE_BPS,
+                PROTOCOL_FEE_BPS,
+                CurveType::ConstantProduct,
+            ).unwrap(),
+        ),
+        (
+            "create_pool_stable",
+            create_pool_ix(
+                &program_id,
+                &payer,
+                &mint_b,
+                &mint_c,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                FEE_BPS,
+                0,
+                CurveType::Stable { amp: 100 },
+            ).unwrap(),
+        ),
+        (
+            "provide_liquidity",
+            provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (100_000_000, 100_000_000), (0, 0)).unwrap(),
+        ),
+        ("swap", swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 0).unwrap()),
+        (
+            "swap_with_price_impact",
+            swap_with_price_impact_ix(&program_id, &payer, &pool, &mint_b, &mint_a, 10_000_000, 0, 500).unwrap(),
+        ),
+        ("set_referral_fee", set_referral_fee_ix(&program_id, &payer, &pool, 1_000)),
+        (
+            "swap_with_referrer",
+            swap_with_referrer_ix(
+                &program_id,
+                &payer,
+                &pool,
+                &mint_a,
+                &mint_b,
+                10_000_000,
+                0,
+                &get_associated_token_address(&referrer, &mint_a),
+            ).unwrap(),
+        ),
+        ("swap_stable", swap_ix(&program_id, &payer, &stable_pool, &mint_b, &mint_c, 10_000_000, 0).unwrap()),
+        (
+            "swap_route",
+            swap_route_ix(
+                &program_id,
+                &payer,
+                &[(pool, mint_a, mint_b), (stable_pool, mint_b, mint_c)],
+                10_000_000,
+                0,
+            ).unwrap(),
+        ),
+        ("zap_in", zap_in_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 0).unwrap()),
+        ("quote", quote_ix(&program_id, &pool, 10_000_000, true).unwrap()),
+        (
+            "withdraw_single_sided",
+            withdraw_single_sided_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, &mint_b, 0).unwrap(),
+        ),
+        (
+            "withdraw_liquidity",
+            withdraw_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, (0, 0)).unwrap(),
+        ),
+        ("collect_protocol_fees", collect_protocol_fees_ix(&program_id, &payer, &pool, &mint_a)),
+        ("skim", skim_ix(&program_id, &pool, &mint_a, &mint_b, &payer)),
+        ("sync", sync_ix(&program_id, &pool, &mint_a, &mint_b)),
+        ("pause", set_paused_ix(&program_id, &payer, &pool, true)),
+        ("unpause", set_paused_ix(&program_id, &payer, &pool, false)),
+        ("set_fee_tiers", set_fee_tiers_ix(&program_id, &payer, &[1, 5, 30, 100]).unwrap()),
+        ("propose_fee_change", propose_fee_change_ix(&program_id, &payer, &pool, 1_000).unwrap()),
+    ];
+
+    let mut measured = Vec::new();
+
+    for (name, instruction) in &instructions {
+        // give Skim and Sync an excess in the vault to act on
+        if matches!(*name, "skim" | "sync") {
+            donate(&mut accounts, &get_associated_token_address(&pool, &mint_a), DONATION);
+        }
+
+        let units = process(&mollusk, &mut accounts, instruction, name);
+        measured.push((name.to_string(), units));
+    }
+
+    let baseline_path = Path::new(BASELINE_PATH);
+
+    if std::env::var_os("UPDATE_CU_BASELINE").is_some() || !baseline_path.exists() {
+        let baseline: String = measured.iter().map(|(name, units)| format!("{name} {units}\n")).collect();
+        fs::write(baseline_path, baseline).unwrap();
+
+        println!("Wrote compute unit baseline to {BASELINE_PATH}");
+    }
+
+    let baseline = read_baseline(baseline_path);
+    let mut regressions = Vec::new();
+
+    // a negative change is a saving against the baseline
+    println!("{:<24} {:>10} {:>10} {:>8}", "instruction", "baseline", "measured", "change");
+
+    for (name, units) in &measured {
+        let Some(&baseline_units) = baseline.get(name) else {
+            regressions.push(format!("{name}: missing from {BASELINE_PATH}"));
+            continue;
+        };
+
+        let change = *units as i64 - baseline_units as i64;
+
+        println!("{name:<24} {baseline_units:>10} {units:>10} {change:>+8}");
+
+        if *units > baseline_units + baseline_units * REGRESSION_THRESHOLD_PERCENT / 100 {
+            regressions.push(format!(
+                "{name}: {units} CUs exceeds baseline {baseline_units} by more than {REGRESSION_THRESHOLD_PERCENT}%"
+            ));
+        }
+    }
+
+    if !regressions.is_empty() {
+        panic!("compute unit regressions:\n{}", regressions.join("\n"));
+    }
+}
+
+// Runs a single instruction against the shared account set and writes the
+// resulting accounts back, so each step sees the state left by the previous one.
+fn process(mollusk: &Mollusk, accounts: &mut HashMap<Pubkey, Account>, instruction: &Instruction, name: &str) -> u64 {
+    let instruction_accounts: Vec<(Pubkey, Account)> = instruction
+        .accounts
+        .iter()
+        .map(|meta| (meta.pubkey, accounts.get(&meta.pubkey).cloned().unwrap_or_default()))
+        .collect();
+
+    let result = mollusk.process_instruction(instruction, &instruction_accounts);
+
+    assert!(!result.program_result.is_err(), "{name} failed: {:?}", result.program_result);
+
+    accounts.extend(result.resulting_accounts);
+
+    result.compute_units_consumed
+}
+
+fn mint_account(authority: &Pubkey) -> Account {
+    let mut data = vec![0; Mint::LEN];
+
+    Mint {
+        mint_authority: COption::Some(*authority),
+        decimals: 6,
+        is_initialized: true,
+        ..Mint::default()
+    }.pack_into_slice(&mut data);
+
+    Account {
+        lamports: Rent::default().minimum_balance(Mint::LEN),
+        data,
+        owner: spl_token::id(),
+        ..Account::default()
+    }
+}
+
+// The config can only be created by the upgrade authority this names.
+fn program_data_account(upgrade_authority: &Pubkey) -> Account {
+    let mut data = vec![0; UpgradeableLoaderState::size_of_programdata_metadata()];
+    data[..4].copy_from_slice(&3u32.to_le_bytes());
+    data[12] = 1;
+    data[13..].copy_from_slice(upgrade_authority.as_ref());
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: solana_sdk_ids::bpf_loader_upgradeable::id(),
+        ..Account::default()
+    }
+}
+
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; TokenAccount::LEN];
+
+    TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    }.pack_into_slice(&mut data);
+
+    Account {
+        lamports: Rent::default().minimum_balance(TokenAccount::LEN),
+        data,
+        owner: spl_token::id(),
+        ..Account::default()
+    }
+}
+
+// Credits `amount` straight to a token account, as a transfer from outside
+// the pool would.
+fn donate(accounts: &mut HashMap<Pubkey, Account>, address: &Pubkey, amount: u64) {
+    let account = accounts.get_mut(address).unwrap();
+
+    let mut token_account = TokenAccount::unpack(&account.data).unwrap();
+    token_account.amount += amount;
+    token_account.pack_into_slice(&mut account.data);
+}
+
+fn read_baseline(path: &Path) -> HashMap<String, u64> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter_map(|line| {
+            let (name, units) = line.split_once(' ')?;
+            Some((name.to_string(), units.trim().parse().ok()?))
+        })
+        .collect()
+}
@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Result};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Signer, Keypair, keypair},
+    transaction::Transaction,
+};
+use solana_system_interface::instruction::create_account;
+use spl_associated_token_account::{
+    get_associated_token_address,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{
+    id as token_program_id,
+    instruction::{initialize_mint2, mint_to},
+    state::{Account as TokenAccount, Mint},
+};
+
+use program::{
+    client::{
+        create_pool_ix,
+        lp_mint_address,
+        pool_address,
+        provide_liquidity_ix,
+        swap_ix,
+        withdraw_liquidity_ix,
+    },
+    state::{CurveType, LiquidityPool},
+};
+
+const FEE_BPS: u16 = 30;
+const DECIMALS: u8 = 6;
+const ONE_TOKEN: u64 = 10u64.pow(DECIMALS as u32);
+
+// Walks a pool through its whole lifecycle: create, provide, swap and
+// withdraw, printing the pool state after each step. Run against a local
+// validator with the program deployed.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let program_id = keypair::read_keypair_file("target/deploy/program-keypair.json")
+        .map_err(|e| anyhow!("{e}"))?.pubkey();
+
+    let client = RpcClient::new_with_commitment(
+        "http://localhost:8899".to_string(), 
+        CommitmentConfig::confirmed(),
+    );
+
+    let payer = Keypair::new();
+
+    let airdrop_signature = client.request_airdrop(
+        &payer.pubkey(), 
+        10 * LAMPORTS_PER_SOL,
+    ).await?;
+    client.poll_for_signature(&airdrop_signature).await?;
+
+    // two mints with 1_000_000 whole tokens each for the payer
+    let mint_a = create_funded_mint(&client, &payer, 1_000_000 * ONE_TOKEN).await?;
+    let mint_b = create_funded_mint(&client, &payer, 1_000_000 * ONE_TOKEN).await?;
+
+    let pool = pool_address(&program_id, &mint_a, &mint_b, FEE_BPS);
+    let payer_ata_lp = get_associated_token_address(&payer.pubkey(), &lp_mint_address(&program_id, &pool));
+
+    println!("pool: {}", pool);
+
+    // 1:2 pool
+    send(
+        &client,
+        &payer,
+        &[
+            create_pool_ix(
+                &program_id,
+                &payer.pubkey(),
+                &mint_a,
+                &mint_b,
+                10_000 * ONE_TOKEN,
+                20_000 * ONE_TOKEN,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+    ).await?;
+
+    print_pool(&client, &pool, "after create").await?;
+
+    send(
+        &client,
+        &payer,
+        &[
+            provide_liquidity_ix(
+                &program_id,
+                &payer.pubkey(),
+                &pool,
+                &mint_a,
+                &mint_b,
+                (1_000 * ONE_TOKEN, 2_000 * ONE_TOKEN),
+                (990 * ONE_TOKEN, 1_980 * ONE_TOKEN),
+            )?,
+        ],
+    ).await?;
+
+    print_pool(&client, &pool, "after provide").await?;
+
+    // 100 A for B, accepting anything above 190 B
+    send(
+        &client,
+        &payer,
+        &[swap_ix(&program_id, &payer.pubkey(), &pool, &mint_a, &mint_b, 100 * ONE_TOKEN, 190 * ONE_TOKEN)?],
+    ).await?;
+
+    print_pool(&client, &pool, "after swap").await?;
+
+    // withdraw everything the payer holds
+    let lp_balance = token_balance(&client, &payer_ata_lp).await?;
+
+    send(
+        &client,
+        &payer,
+        &[withdraw_liquidity_ix(&program_id, &payer.pubkey(), &pool, &mint_a, &mint_b, lp_balance, (0, 0))?],
+    ).await?;
+
+    print_pool(&client, &pool, "after withdraw").await?;
+
+    println!("payer A: {}", token_balance(&client, &get_associated_token_address(&payer.pubkey(), &mint_a)).await?);
+    println!("payer B: {}", token_balance(&client, &get_associated_token_address(&payer.pubkey(), &mint_b)).await?);
+
+    Ok(())
+}
+
+// Creates a mint with the payer as authority and mints `amount` to the payer's ATA.
+async fn create_funded_mint(client: &RpcClient, payer: &Keypair, amount: u64) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer_ata = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let mint_rent = client.get_minimum_balance_for_rent_exemption(Mint::LEN).await?;
+
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(&payer.pubkey(), &mint.pubkey(), mint_rent, Mint::LEN as u64, &token_program_id()),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer.pubkey(), None, DECIMALS)?,
+            create_associated_token_account_idempotent(
+                &payer.pubkey(), 
+                &payer.pubkey(), 
+                &mint.pubkey(), 
+                &token_program_id(),
+            ),
+            mint_to(&token_program_id(), &mint.pubkey(), &payer_ata, &payer.pubkey(), &[], amount)?,
+        ],
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        recent_blockhash,
+    );
+
+    client.send_and_confirm_transaction_with_spinner(&tx).await?;
+
+    Ok(mint.pubkey())
+}
+
+async fn print_pool(client: &RpcClient, pool: &Pubkey, label: &str) -> Result<()> {
+    let account = client.get_account(pool).await?;
+    let pool_data = LiquidityPool::load(&account.data)?;
+
+    println!("{label}:");
+    println!("  reserve a:    {}", pool_data.reserve_a);
+    println!("  reserve b:    {}", pool_data.reserve_b);
+    println!("  fee:          {} bps", pool_data.fee_bps);
+    println!("  curve:        {:?}", pool_data.curve);
+    println!("  paused:       {}", pool_data.paused);
+
+    Ok(())
+}
+
+async fn send(client: &RpcClient, payer: &Keypair, instructions: &[Instruction]) -> Result<()> {
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()), 
+        &[payer], 
+        recent_blockhash,
+    );
+
+    let tx_signature = 
+        client.send_and_confirm_transaction_with_spinner(&tx).await?;
+
+    println!("tx signature: {}", tx_signature);
+
+    Ok(())
+}
+
+async fn token_balance(client: &RpcClient, address: &Pubkey) -> Result<u64> {
+    let account = client.get_account(address).await?;
+
+    Ok(TokenAccount::unpack(&account.data)?.amount)
+}
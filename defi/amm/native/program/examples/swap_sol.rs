@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Result};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Signer, Keypair, keypair},
+    transaction::Transaction,
+};
+use solana_system_interface::instruction::{create_account, transfer};
+use spl_associated_token_account::{
+    get_associated_token_address,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{
+    id as token_program_id,
+    instruction::{initialize_mint2, mint_to, sync_native},
+    native_mint,
+    state::{Account as TokenAccount, Mint},
+};
+
+use program::{
+    client::{create_pool_ix, pool_address, swap_native_ix},
+    state::CurveType,
+};
+
+const FEE_BPS: u16 = 30;
+
+// Creates a SOL/token pool and swaps SOL for the token with SwapNative,
+// without wrapping the SOL first. Run against a local validator with the
+// program deployed.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let program_id = keypair::read_keypair_file("target/deploy/program-keypair.json")
+        .map_err(|e| anyhow!("{e}"))?.pubkey();
+
+    let client = RpcClient::new_with_commitment(
+        "http://localhost:8899".to_string(), 
+        CommitmentConfig::confirmed(),
+    );
+
+    let payer = Keypair::new();
+
+    let airdrop_signature = client.request_airdrop(
+        &payer.pubkey(), 
+        10 * LAMPORTS_PER_SOL,
+    ).await?;
+    client.poll_for_signature(&airdrop_signature).await?;
+
+    // a token to trade against SOL, with 1_000 whole tokens for the payer
+    let mint = Keypair::new();
+    let payer_ata = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let mint_rent = client.get_minimum_balance_for_rent_exemption(Mint::LEN).await?;
+
+    send(
+        &client,
+        &payer,
+        &[
+            create_account(&payer.pubkey(), &mint.pubkey(), mint_rent, Mint::LEN as u64, &token_program_id()),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer.pubkey(), None, 9)?,
+            create_associated_token_account_idempotent(
+                &payer.pubkey(), 
+                &payer.pubkey(), 
+                &mint.pubkey(), 
+                &token_program_id(),
+            ),
+            mint_to(&token_program_id(), &mint.pubkey(), &payer_ata, &payer.pubkey(), &[], 1_000 * LAMPORTS_PER_SOL)?,
+        ],
+        &[&mint],
+    ).await?;
+
+    // seeding the pool still takes wSOL, so wrap 1 SOL by hand
+    let wsol_ata = get_associated_token_address(&payer.pubkey(), &native_mint::id());
+    let seed_amount = LAMPORTS_PER_SOL;
+
+    send(
+        &client,
+        &payer,
+        &[
+            create_associated_token_account_idempotent(
+                &payer.pubkey(), 
+                &payer.pubkey(), 
+                &native_mint::id(), 
+                &token_program_id(),
+            ),
+            transfer(&payer.pubkey(), &wsol_ata, seed_amount),
+            sync_native(&token_program_id(), &wsol_ata)?,
+            create_pool_ix(
+                &program_id,
+                &payer.pubkey(),
+                &native_mint::id(),
+                &mint.pubkey(),
+                seed_amount,
+                100 * seed_amount,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    let pool = pool_address(&program_id, &native_mint::id(), &mint.pubkey(), FEE_BPS);
+
+    println!("pool: {}", pool);
+
+    // swap 0.1 SOL straight from the payer's balance
+    let sol_before = client.get_balance(&payer.pubkey()).await?;
+    let tokens_before = token_balance(&client, &payer_ata).await?;
+
+    send(
+        &client,
+        &payer,
+        &[swap_native_ix(&program_id, &payer.pubkey(), &pool, &native_mint::id(), &mint.pubkey(), LAMPORTS_PER_SOL / 10, 1)?],
+        &[],
+    ).await?;
+
+    println!("SOL spent: {}", sol_before - client.get_balance(&payer.pubkey()).await?);
+    println!("tokens received: {}", token_balance(&client, &payer_ata).await? - tokens_before);
+
+    Ok(())
+}
+
+async fn send(client: &RpcClient, payer: &Keypair, instructions: &[Instruction], signers: &[&Keypair]) -> Result<()> {
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()), 
+        &all_signers, 
+        recent_blockhash,
+    );
+
+    let tx_signature = 
+        client.send_and_confirm_transaction_with_spinner(&tx).await?;
+
+    println!("tx signature: {}", tx_signature);
+
+    Ok(())
+}
+
+async fn token_balance(client: &RpcClient, address: &Pubkey) -> Result<u64> {
+    let account = client.get_account(address).await?;
+
+    Ok(TokenAccount::unpack(&account.data)?.amount)
+}
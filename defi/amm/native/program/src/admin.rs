@@ -0,0 +1,75 @@
+// Pool and config admins can be a plain keypair or an SPL Token multisig
+// account. A multisig admin can't sign itself; instead at least M of its
+// signers sign and follow the instruction's own accounts, the same way
+// spl-token accepts multisig owners. Squads vaults and SPL Governance
+// treasuries are PDAs that sign through CPI, so they work as plain signers.
+// Instructions where the admin also pays for new accounts still need it to
+// sign itself.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_sdk_ids::bpf_loader_upgradeable;
+use spl_token_2022::state::Multisig;
+
+use crate::error::AmmError;
+
+// `admin` must already be known to be the expected admin.
+pub fn check_admin_signature(admin: &AccountInfo, signers: &[AccountInfo]) -> ProgramResult {
+    if admin.is_signer {
+        return Ok(());
+    }
+
+    if *admin.owner != spl_token::id() && *admin.owner != spl_token_2022::id() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let multisig = Multisig::unpack(&admin.data.borrow())
+        .map_err(|_| ProgramError::MissingRequiredSignature)?;
+
+    // each of the multisig's signers counts once, however often it's passed
+    let mut matched = [false; spl_token_2022::instruction::MAX_SIGNERS];
+    let mut num_signers = 0u8;
+
+    for signer in signers.iter().filter(|signer| signer.is_signer) {
+        for (position, key) in multisig.signers[..multisig.n as usize].iter().enumerate() {
+            if key == signer.key && !matched[position] {
+                matched[position] = true;
+                num_signers += 1;
+            }
+        }
+    }
+
+    if num_signers < multisig.m {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    Ok(())
+}
+
+// Reads the upgrade authority out of the program's ProgramData account, None
+// once the program has been made immutable.
+pub fn upgrade_authority(program_id: &Pubkey, program_data: &AccountInfo) -> Result<Option<Pubkey>, ProgramError> {
+    if *program_data.key != get_program_data_address(program_id) || *program_data.owner != bpf_loader_upgradeable::id() {
+        return Err(AmmError::ProgramDataMismatch.into());
+    }
+
+    let data = program_data.data.borrow();
+
+    // bincode layout: a u32 variant (3 for ProgramData), the u64 slot of the
+    // last deploy, then the Option<Pubkey>
+    if data.len() < UpgradeableLoaderState::size_of_programdata_metadata() || data[..4] != 3u32.to_le_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    match data[12] {
+        0 => Ok(None),
+        _ => Ok(Some(Pubkey::try_from(&data[13..45]).map_err(|_| ProgramError::InvalidAccountData)?)),
+    }
+}
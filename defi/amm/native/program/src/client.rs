@@ -0,0 +1,1287 @@
+// Instruction builders for clients and tests. Each one derives the pool's
+// PDAs and ATAs and lists accounts in the order the processor reads them.
+// The builders without a `token_programs` argument assume plain SPL Token
+// mints on both sides.
+
+use borsh::BorshSerialize;
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::instructions::id as instructions_sysvar_id,
+};
+use solana_loader_v3_interface::get_program_data_address;
+use solana_system_interface::program::id as system_program_id;
+
+use spl_associated_token_account::{
+    get_associated_token_address,
+    get_associated_token_address_with_program_id,
+    id as associated_token_program_id,
+};
+use spl_token::id as token_program_id;
+
+use crate::{
+    instruction::{
+        CreatePoolPayload,
+        ProvideLiquidityPayload,
+        WithdrawLiquidityPayload,
+        SwapPayload,
+        QuotePayload,
+        FlashLoanPayload,
+        ZapInPayload,
+        WithdrawSingleSidedPayload,
+        SetFeeTiersPayload,
+        ProposeFeeChangePayload,
+        CreateFarmPayload,
+        StakePayload,
+        SetOraclePayload,
+        PlaceLimitOrderPayload,
+        OpenDcaPayload,
+        SetPoolCreatorsPayload,
+        WithdrawPercentPayload,
+        NominateAdminPayload,
+        InitializePoolPayload,
+        InitialDepositPayload,
+    },
+    state::CurveType,
+};
+
+// One pool per pair and fee tier: the curve isn't in the seeds.
+pub fn pool_address(program_id: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey, fee_bps: u16) -> Pubkey {
+    let (mint_lo, mint_hi) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+
+    Pubkey::find_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &fee_bps.to_le_bytes()],
+        program_id,
+    ).0
+}
+
+pub fn lp_mint_address(program_id: &Pubkey, pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"lp_mint", pool.as_ref()], program_id).0
+}
+
+pub fn treasury_address(program_id: &Pubkey, pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury", pool.as_ref()], program_id).0
+}
+
+// Holds a PoolRegistry listing every pool, so fetching this one account
+// enumerates them without a getProgramAccounts scan.
+pub fn registry_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"registry"], program_id).0
+}
+
+pub fn config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], program_id).0
+}
+
+pub fn farm_address(program_id: &Pubkey, pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"farm", pool.as_ref()], program_id).0
+}
+
+pub fn limit_order_address(program_id: &Pubkey, pool: &Pubkey, owner: &Pubkey, order_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"order", pool.as_ref(), owner.as_ref(), &order_id.to_le_bytes()], program_id).0
+}
+
+pub fn dca_address(program_id: &Pubkey, pool: &Pubkey, owner: &Pubkey, dca_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"dca", pool.as_ref(), owner.as_ref(), &dca_id.to_le_bytes()], program_id).0
+}
+
+pub fn stake_position_address(program_id: &Pubkey, farm: &Pubkey, owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"stake", farm.as_ref(), owner.as_ref()], program_id).0
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_pool_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+    curve: CurveType,
+) -> Result<Instruction, ProgramError> {
+    create_pool_ix_with_token_programs(
+        program_id,
+        user,
+        mint_a,
+        mint_b,
+        amount_a,
+        amount_b,
+        fee_bps,
+        protocol_fee_bps,
+        curve,
+        (&token_program_id(), &token_program_id()),
+    )
+}
+
+// `token_programs` are the programs owning (mint_a, mint_b).
+#[allow(clippy::too_many_arguments)]
+pub fn create_pool_ix_with_token_programs(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+    curve: CurveType,
+    token_programs: (&Pubkey, &Pubkey),
+) -> Result<Instruction, ProgramError> {
+    let (token_program_a, token_program_b) = token_programs;
+    let pool = pool_address(program_id, mint_a, mint_b, fee_bps);
+    let mint_lp = lp_mint_address(program_id, &pool);
+    let treasury = treasury_address(program_id, &pool);
+
+    let mut data = vec![0];
+    CreatePoolPayload { amount_a, amount_b, fee_bps, protocol_fee_bps, curve }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(pool, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&pool, mint_a, token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&pool, mint_b, token_program_b), false),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(get_associated_token_address(user, &mint_lp), false),
+            AccountMeta::new(get_associated_token_address(&pool, &mint_lp), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(user, mint_a, token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(user, mint_b, token_program_b), false),
+            AccountMeta::new_readonly(treasury, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&treasury, mint_a, token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&treasury, mint_b, token_program_b), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(*token_program_a, false),
+            AccountMeta::new_readonly(*token_program_b, false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new(registry_address(program_id), false),
+            AccountMeta::new_readonly(config_address(program_id), false),
+        ],
+    ))
+}
+
+pub fn initialize_pool_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+    curve: CurveType,
+) -> Result<Instruction, ProgramError> {
+    initialize_pool_ix_with_token_programs(
+        program_id,
+        user,
+        mint_a,
+        mint_b,
+        fee_bps,
+        protocol_fee_bps,
+        curve,
+        (&token_program_id(), &token_program_id()),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_pool_ix_with_token_programs(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+    curve: CurveType,
+    token_programs: (&Pubkey, &Pubkey),
+) -> Result<Instruction, ProgramError> {
+    let (token_program_a, token_program_b) = token_programs;
+    let pool = pool_address(program_id, mint_a, mint_b, fee_bps);
+    let mint_lp = lp_mint_address(program_id, &pool);
+    let treasury = treasury_address(program_id, &pool);
+
+    let mut data = vec![35];
+    InitializePoolPayload { fee_bps, protocol_fee_bps, curve }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(pool, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&pool, mint_a, token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&pool, mint_b, token_program_b), false),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(get_associated_token_address(&pool, &mint_lp), false),
+            AccountMeta::new_readonly(treasury, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&treasury, mint_a, token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&treasury, mint_b, token_program_b), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(*token_program_a, false),
+            AccountMeta::new_readonly(*token_program_b, false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new(registry_address(program_id), false),
+            AccountMeta::new_readonly(config_address(program_id), false),
+        ],
+    ))
+}
+
+pub fn provide_liquidity_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amounts_desired: (u64, u64),
+    amounts_min: (u64, u64),
+) -> Result<Instruction, ProgramError> {
+    provide_liquidity_ix_with_token_programs(
+        program_id,
+        user,
+        pool,
+        mint_a,
+        mint_b,
+        amounts_desired,
+        amounts_min,
+        (&token_program_id(), &token_program_id()),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity_ix_with_token_programs(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amounts_desired: (u64, u64),
+    amounts_min: (u64, u64),
+    token_programs: (&Pubkey, &Pubkey),
+) -> Result<Instruction, ProgramError> {
+    let mut data = vec![1];
+    ProvideLiquidityPayload {
+        amount_a_desired: amounts_desired.0,
+        amount_b_desired: amounts_desired.1,
+        amount_a_min: amounts_min.0,
+        amount_b_min: amounts_min.1,
+    }.serialize(&mut data)?;
+
+    // lets the program create the user's LP ATA on a first deposit, and
+    // re-seed the pool if its LP supply has gone to zero
+    let mut accounts = liquidity_accounts(program_id, user, pool, mint_a, mint_b, token_programs);
+    accounts.extend([
+        AccountMeta::new_readonly(system_program_id(), false),
+        AccountMeta::new_readonly(associated_token_program_id(), false),
+        AccountMeta::new(get_associated_token_address(pool, &lp_mint_address(program_id, pool)), false),
+    ]);
+
+    Ok(Instruction::new_with_bytes(*program_id, &data, accounts))
+}
+
+pub fn initial_deposit_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+) -> Result<Instruction, ProgramError> {
+    initial_deposit_ix_with_token_programs(
+        program_id,
+        user,
+        pool,
+        mint_a,
+        mint_b,
+        amount_a,
+        amount_b,
+        (&token_program_id(), &token_program_id()),
+    )
+}
+
+// Same accounts as provide_liquidity_ix.
+#[allow(clippy::too_many_arguments)]
+pub fn initial_deposit_ix_with_token_programs(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    token_programs: (&Pubkey, &Pubkey),
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = provide_liquidity_ix_with_token_programs(
+        program_id,
+        user,
+        pool,
+        mint_a,
+        mint_b,
+        (amount_a, amount_b),
+        (0, 0),
+        token_programs,
+    )?;
+
+    let mut data = vec![36];
+    InitialDepositPayload { amount_a, amount_b }.serialize(&mut data)?;
+    instruction.data = data;
+
+    Ok(instruction)
+}
+
+pub fn withdraw_liquidity_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_lp_in: u64,
+    amounts_min: (u64, u64),
+) -> Result<Instruction, ProgramError> {
+    withdraw_liquidity_ix_with_token_programs(
+        program_id,
+        user,
+        pool,
+        mint_a,
+        mint_b,
+        amount_lp_in,
+        amounts_min,
+        (&token_program_id(), &token_program_id()),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_liquidity_ix_with_token_programs(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_lp_in: u64,
+    amounts_min: (u64, u64),
+    token_programs: (&Pubkey, &Pubkey),
+) -> Result<Instruction, ProgramError> {
+    let mut data = vec![2];
+    WithdrawLiquidityPayload {
+        amount_lp_in,
+        amount_a_min: amounts_min.0,
+        amount_b_min: amounts_min.1,
+    }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        liquidity_accounts(program_id, user, pool, mint_a, mint_b, token_programs),
+    ))
+}
+
+// Burns `bps` of the user's LP balance at the time the transaction runs.
+pub fn withdraw_percent_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    bps: u16,
+    amounts_min: (u64, u64),
+) -> Result<Instruction, ProgramError> {
+    withdraw_percent_ix_with_token_programs(
+        program_id,
+        user,
+        pool,
+        mint_a,
+        mint_b,
+        bps,
+        amounts_min,
+        (&token_program_id(), &token_program_id()),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_percent_ix_with_token_programs(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    bps: u16,
+    amounts_min: (u64, u64),
+    token_programs: (&Pubkey, &Pubkey),
+) -> Result<Instruction, ProgramError> {
+    let mut data = vec![32];
+    WithdrawPercentPayload {
+        bps,
+        amount_a_min: amounts_min.0,
+        amount_b_min: amounts_min.1,
+    }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        liquidity_accounts(program_id, user, pool, mint_a, mint_b, token_programs),
+    ))
+}
+
+// WithdrawLiquidity accounts plus the treasury account for the side being
+// swapped away and the associated token program.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_single_sided_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    amount_lp_in: u64,
+    want_mint: &Pubkey,
+    min_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let token_programs = (&token_program_id(), &token_program_id());
+    let swapped_mint = if want_mint == mint_a { mint_b } else { mint_a };
+
+    let mut data = vec![14];
+    WithdrawSingleSidedPayload { amount_lp_in, want_mint: *want_mint, min_out }.serialize(&mut data)?;
+
+    let mut accounts = liquidity_accounts(program_id, user, pool, mint_a, mint_b, token_programs);
+    accounts.extend([
+        AccountMeta::new(get_associated_token_address(&treasury_address(program_id, pool), swapped_mint), false),
+        AccountMeta::new_readonly(associated_token_program_id(), false),
+    ]);
+
+    Ok(Instruction::new_with_bytes(*program_id, &data, accounts))
+}
+
+fn liquidity_accounts(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    token_programs: (&Pubkey, &Pubkey),
+) -> Vec<AccountMeta> {
+    let (token_program_a, token_program_b) = token_programs;
+    let mint_lp = lp_mint_address(program_id, pool);
+
+    vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new_readonly(*mint_a, false),
+        AccountMeta::new_readonly(*mint_b, false),
+        AccountMeta::new(get_associated_token_address_with_program_id(pool, mint_a, token_program_a), false),
+        AccountMeta::new(get_associated_token_address_with_program_id(pool, mint_b, token_program_b), false),
+        AccountMeta::new(mint_lp, false),
+        AccountMeta::new(get_associated_token_address(user, &mint_lp), false),
+        AccountMeta::new(get_associated_token_address_with_program_id(user, mint_a, token_program_a), false),
+        AccountMeta::new(get_associated_token_address_with_program_id(user, mint_b, token_program_b), false),
+        AccountMeta::new_readonly(token_program_id(), false),
+        AccountMeta::new_readonly(*token_program_a, false),
+        AccountMeta::new_readonly(*token_program_b, false),
+    ]
+}
+
+pub fn swap_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+) -> Result<Instruction, ProgramError> {
+    swap_ix_with_token_programs(
+        program_id,
+        user,
+        pool,
+        mint_in,
+        mint_out,
+        amount_in,
+        min_out,
+        (&token_program_id(), &token_program_id()),
+    )
+}
+
+// `token_programs` are the programs owning (mint_in, mint_out).
+#[allow(clippy::too_many_arguments)]
+pub fn swap_ix_with_token_programs(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+    token_programs: (&Pubkey, &Pubkey),
+) -> Result<Instruction, ProgramError> {
+    let (token_program_in, token_program_out) = token_programs;
+    let treasury = treasury_address(program_id, pool);
+
+    let mut data = vec![3];
+    SwapPayload { amount_in, min_out }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new_readonly(*mint_out, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(pool, mint_in, token_program_in), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(pool, mint_out, token_program_out), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(user, mint_in, token_program_in), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(user, mint_out, token_program_out), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&treasury, mint_in, token_program_in), false),
+            AccountMeta::new_readonly(*token_program_in, false),
+            AccountMeta::new_readonly(*token_program_out, false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+        ],
+    ))
+}
+
+// Swap `owner`'s tokens with `delegate` signing under an SPL Token approval
+// on the owner's input ATA. The output goes to the owner's ATA.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_as_delegate_ix(
+    program_id: &Pubkey,
+    delegate: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = swap_ix(program_id, owner, pool, mint_in, mint_out, amount_in, min_out)?;
+
+    instruction.accounts[0] = AccountMeta::new(*delegate, true);
+
+    Ok(instruction)
+}
+
+// Swap with the optional max_price_impact_bps appended to the payload.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_with_price_impact_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+    max_price_impact_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = swap_ix(program_id, user, pool, mint_in, mint_out, amount_in, min_out)?;
+
+    Some(max_price_impact_bps).serialize(&mut instruction.data)?;
+
+    Ok(instruction)
+}
+
+// Swap through a pool with an oracle, whose price account has to follow
+// the fixed accounts. Push a referrer after it if needed.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_with_oracle_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+    oracle: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = swap_ix(program_id, user, pool, mint_in, mint_out, amount_in, min_out)?;
+
+    instruction.accounts.push(AccountMeta::new_readonly(*oracle, false));
+
+    Ok(instruction)
+}
+
+// Swap with the optional referrer account appended.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_with_referrer_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+    referrer_ata: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = swap_ix(program_id, user, pool, mint_in, mint_out, amount_in, min_out)?;
+
+    instruction.accounts.push(AccountMeta::new(*referrer_ata, false));
+
+    Ok(instruction)
+}
+
+// Same accounts as Swap plus SPL Token (for the wSOL ATA) and the system program.
+pub fn swap_native_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = swap_ix(program_id, user, pool, mint_in, mint_out, amount_in, min_out)?;
+
+    instruction.data[0] = 12;
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(token_program_id(), false),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ]);
+
+    Ok(instruction)
+}
+
+// Same accounts as Swap plus the LP mint, the user's LP account and SPL Token.
+pub fn zap_in_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    min_lp_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let mint_lp = lp_mint_address(program_id, pool);
+    let mut instruction = swap_ix(program_id, user, pool, mint_in, mint_out, 0, 0)?;
+
+    instruction.data = vec![13];
+    ZapInPayload { amount_in, min_lp_out }.serialize(&mut instruction.data)?;
+    instruction.accounts.extend([
+        AccountMeta::new(mint_lp, false),
+        AccountMeta::new(get_associated_token_address(user, &mint_lp), false),
+        AccountMeta::new_readonly(token_program_id(), false),
+    ]);
+
+    Ok(instruction)
+}
+
+pub fn collect_protocol_fees_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    pool: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    let treasury = treasury_address(program_id, pool);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &[4],
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(treasury, false),
+            AccountMeta::new(get_associated_token_address(&treasury, mint), false),
+            AccountMeta::new(get_associated_token_address(admin, mint), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+    )
+}
+
+pub fn sync_ix(program_id: &Pubkey, pool: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[6],
+        vec![
+            AccountMeta::new(*pool, false),
+            AccountMeta::new_readonly(get_associated_token_address(pool, mint_a), false),
+            AccountMeta::new_readonly(get_associated_token_address(pool, mint_b), false),
+        ],
+    )
+}
+
+pub fn skim_ix(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    to: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[7],
+        vec![
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(get_associated_token_address(pool, mint_a), false),
+            AccountMeta::new(get_associated_token_address(pool, mint_b), false),
+            AccountMeta::new(get_associated_token_address(to, mint_a), false),
+            AccountMeta::new(get_associated_token_address(to, mint_b), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+    )
+}
+
+pub fn set_paused_ix(program_id: &Pubkey, admin: &Pubkey, pool: &Pubkey, paused: bool) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[5, paused as u8],
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*pool, false),
+        ],
+    )
+}
+
+pub fn set_referral_fee_ix(program_id: &Pubkey, admin: &Pubkey, pool: &Pubkey, referral_fee_bps: u16) -> Instruction {
+    let mut data = vec![15];
+    data.extend_from_slice(&referral_fee_bps.to_le_bytes());
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*pool, false),
+        ],
+    )
+}
+
+pub fn propose_fee_change_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    pool: &Pubkey,
+    protocol_fee_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    let mut data = vec![17];
+    ProposeFeeChangePayload { protocol_fee_bps }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*pool, false),
+        ],
+    ))
+}
+
+pub fn execute_fee_change_ix(program_id: &Pubkey, admin: &Pubkey, pool: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[18],
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*pool, false),
+        ],
+    )
+}
+
+// `None` removes the pool's oracle bound.
+pub fn set_oracle_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    pool: &Pubkey,
+    oracle: Option<&Pubkey>,
+    max_deviation_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    let mut data = vec![23];
+    SetOraclePayload { max_deviation_bps }.serialize(&mut data)?;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new(*pool, false),
+    ];
+
+    if let Some(oracle) = oracle {
+        accounts.push(AccountMeta::new_readonly(*oracle, false));
+    }
+
+    Ok(Instruction::new_with_bytes(*program_id, &data, accounts))
+}
+
+// Adapts an admin instruction built for `multisig` as the admin to an SPL
+// Token multisig: the multisig account itself doesn't sign, and `signers`
+// (at least M of its signers) are appended.
+pub fn with_multisig_signers(mut instruction: Instruction, multisig: &Pubkey, signers: &[&Pubkey]) -> Instruction {
+    for account in instruction.accounts.iter_mut().filter(|account| account.pubkey == *multisig) {
+        account.is_signer = false;
+    }
+
+    instruction.accounts.extend(signers.iter().map(|signer| AccountMeta::new_readonly(**signer, true)));
+
+    instruction
+}
+
+// The first call creates the config and makes `admin` its admin; only the
+// program's upgrade authority can make it.
+pub fn set_fee_tiers_ix(program_id: &Pubkey, admin: &Pubkey, fee_tiers: &[u16]) -> Result<Instruction, ProgramError> {
+    let mut data = vec![16];
+    SetFeeTiersPayload { fee_tiers: fee_tiers.to_vec() }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(config_address(program_id), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(get_program_data_address(program_id), false),
+        ],
+    ))
+}
+
+pub fn set_pool_creators_ix(program_id: &Pubkey, admin: &Pubkey, pool_creators: &[Pubkey]) -> Result<Instruction, ProgramError> {
+    let mut data = vec![31];
+    SetPoolCreatorsPayload { pool_creators: pool_creators.to_vec() }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(config_address(program_id), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(get_program_data_address(program_id), false),
+        ],
+    ))
+}
+
+// `target` is a pool or config_address(program_id). Nominating
+// Pubkey::default() cancels a pending nomination.
+pub fn nominate_admin_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    target: &Pubkey,
+    new_admin: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut data = vec![33];
+    NominateAdminPayload { new_admin: *new_admin }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*target, false),
+        ],
+    ))
+}
+
+pub fn accept_admin_ix(program_id: &Pubkey, new_admin: &Pubkey, target: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[34],
+        vec![
+            AccountMeta::new_readonly(*new_admin, true),
+            AccountMeta::new(*target, false),
+        ],
+    )
+}
+
+pub fn quote_ix(program_id: &Pubkey, pool: &Pubkey, amount_in: u64, a_to_b: bool) -> Result<Instruction, ProgramError> {
+    let mut data = vec![8];
+    QuotePayload { amount_in, a_to_b }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![AccountMeta::new_readonly(*pool, false)],
+    ))
+}
+
+// Each hop is (pool, mint_in, mint_out); intermediate tokens pass through
+// the user's ATAs, so those have to exist.
+pub fn swap_route_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    hops: &[(Pubkey, Pubkey, Pubkey)],
+    amount_in: u64,
+    min_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut data = vec![9];
+    SwapPayload { amount_in, min_out }.serialize(&mut data)?;
+
+    let mut accounts = vec![AccountMeta::new_readonly(*user, true)];
+
+    for (pool, mint_in, mint_out) in hops {
+        let treasury = treasury_address(program_id, pool);
+
+        accounts.extend([
+            AccountMeta::new(*pool, false),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new_readonly(*mint_out, false),
+            AccountMeta::new(get_associated_token_address(pool, mint_in), false),
+            AccountMeta::new(get_associated_token_address(pool, mint_out), false),
+            AccountMeta::new(get_associated_token_address(user, mint_in), false),
+            AccountMeta::new(get_associated_token_address(user, mint_out), false),
+            AccountMeta::new(get_associated_token_address(&treasury, mint_in), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ]);
+    }
+
+    Ok(Instruction::new_with_bytes(*program_id, &data, accounts))
+}
+
+pub fn flash_loan_ix(
+    program_id: &Pubkey,
+    borrower: &Pubkey,
+    pool: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut data = vec![10];
+    FlashLoanPayload { amount }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*borrower, true),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_associated_token_address(pool, mint), false),
+            AccountMeta::new(get_associated_token_address(borrower, mint), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(instructions_sysvar_id(), false),
+        ],
+    ))
+}
+
+pub fn flash_repay_ix(program_id: &Pubkey, borrower: &Pubkey, pool: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[11],
+        vec![
+            AccountMeta::new_readonly(*borrower, true),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_associated_token_address(pool, mint), false),
+            AccountMeta::new(get_associated_token_address(borrower, mint), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+    )
+}
+
+// The reward vault is created empty; fund it with a plain token transfer to
+// get_associated_token_address_with_program_id(farm, reward_mint, reward_token_program).
+pub fn create_farm_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    pool: &Pubkey,
+    reward_mint: &Pubkey,
+    reward_token_program: &Pubkey,
+    reward_per_second: u64,
+) -> Result<Instruction, ProgramError> {
+    let farm = farm_address(program_id, pool);
+    let mint_lp = lp_mint_address(program_id, pool);
+
+    let mut data = vec![19];
+    CreateFarmPayload { reward_per_second }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new(farm, false),
+            AccountMeta::new_readonly(mint_lp, false),
+            AccountMeta::new_readonly(*reward_mint, false),
+            AccountMeta::new(get_associated_token_address(&farm, &mint_lp), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&farm, reward_mint, reward_token_program), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn stake_lp_ix(program_id: &Pubkey, user: &Pubkey, pool: &Pubkey, amount: u64) -> Result<Instruction, ProgramError> {
+    let farm = farm_address(program_id, pool);
+    let mint_lp = lp_mint_address(program_id, pool);
+
+    let mut data = vec![20];
+    StakePayload { amount }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(farm, false),
+            AccountMeta::new(stake_position_address(program_id, &farm, user), false),
+            AccountMeta::new_readonly(mint_lp, false),
+            AccountMeta::new(get_associated_token_address(user, &mint_lp), false),
+            AccountMeta::new(get_associated_token_address(&farm, &mint_lp), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn unstake_lp_ix(program_id: &Pubkey, user: &Pubkey, pool: &Pubkey, amount: u64) -> Result<Instruction, ProgramError> {
+    let farm = farm_address(program_id, pool);
+    let mint_lp = lp_mint_address(program_id, pool);
+
+    let mut data = vec![21];
+    StakePayload { amount }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(farm, false),
+            AccountMeta::new(stake_position_address(program_id, &farm, user), false),
+            AccountMeta::new_readonly(mint_lp, false),
+            AccountMeta::new(get_associated_token_address(user, &mint_lp), false),
+            AccountMeta::new(get_associated_token_address(&farm, &mint_lp), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+    ))
+}
+
+// Rewards go to the user's ATA for reward_mint, which has to exist.
+pub fn harvest_ix(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &Pubkey,
+    reward_mint: &Pubkey,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let farm = farm_address(program_id, pool);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &[22],
+        vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(farm, false),
+            AccountMeta::new(stake_position_address(program_id, &farm, user), false),
+            AccountMeta::new_readonly(*reward_mint, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&farm, reward_mint, reward_token_program), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(user, reward_mint, reward_token_program), false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+        ],
+    )
+}
+
+// The builders below are for SPL Token mints. `order_id` tells apart
+// orders of the same owner on the same pool.
+#[allow(clippy::too_many_arguments)]
+pub fn place_limit_order_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    order_id: u64,
+    amount_in: u64,
+    min_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let order = limit_order_address(program_id, pool, owner, order_id);
+
+    let mut data = vec![24];
+    PlaceLimitOrderPayload { order_id, amount_in, min_out }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new(order, false),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new_readonly(*mint_out, false),
+            AccountMeta::new(get_associated_token_address(owner, mint_in), false),
+            AccountMeta::new(get_associated_token_address(&order, mint_in), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+// The owner's ATA for mint_out has to exist. For a pool with an oracle,
+// push its price account after these.
+#[allow(clippy::too_many_arguments)]
+pub fn fill_limit_order_ix(
+    program_id: &Pubkey,
+    cranker: &Pubkey,
+    pool: &Pubkey,
+    owner: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    order_id: u64,
+) -> Instruction {
+    let order = limit_order_address(program_id, pool, owner, order_id);
+    let treasury = treasury_address(program_id, pool);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &[25],
+        vec![
+            AccountMeta::new(*cranker, true),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(order, false),
+            AccountMeta::new(*owner, false),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new_readonly(*mint_out, false),
+            AccountMeta::new(get_associated_token_address(pool, mint_in), false),
+            AccountMeta::new(get_associated_token_address(pool, mint_out), false),
+            AccountMeta::new(get_associated_token_address(&order, mint_in), false),
+            AccountMeta::new(get_associated_token_address(owner, mint_out), false),
+            AccountMeta::new(get_associated_token_address(&treasury, mint_in), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(*program_id, false),
+        ],
+    )
+}
+
+pub fn cancel_limit_order_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    order_id: u64,
+) -> Instruction {
+    let order = limit_order_address(program_id, pool, owner, order_id);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &[26],
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new(order, false),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new(get_associated_token_address(&order, mint_in), false),
+            AccountMeta::new(get_associated_token_address(owner, mint_in), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+    )
+}
+
+// Sells amount_total of mint_in, amount_per_swap at a time, one swap every
+// `interval` seconds. SPL Token mints, like the limit order builders.
+#[allow(clippy::too_many_arguments)]
+pub fn open_dca_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    dca_id: u64,
+    amount_total: u64,
+    amount_per_swap: u64,
+    min_out_per_swap: u64,
+    interval: i64,
+) -> Result<Instruction, ProgramError> {
+    let dca = dca_address(program_id, pool, owner, dca_id);
+
+    let mut data = vec![27];
+    OpenDcaPayload { dca_id, amount_total, amount_per_swap, min_out_per_swap, interval }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new(dca, false),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new_readonly(*mint_out, false),
+            AccountMeta::new(get_associated_token_address(owner, mint_in), false),
+            AccountMeta::new(get_associated_token_address(&dca, mint_in), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+// The owner's ATA for mint_out has to exist. For a pool with an oracle,
+// push its price account after these.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_dca_ix(
+    program_id: &Pubkey,
+    cranker: &Pubkey,
+    pool: &Pubkey,
+    owner: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    dca_id: u64,
+) -> Instruction {
+    let dca = dca_address(program_id, pool, owner, dca_id);
+    let treasury = treasury_address(program_id, pool);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &[28],
+        vec![
+            AccountMeta::new(*cranker, true),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(dca, false),
+            AccountMeta::new(*owner, false),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new_readonly(*mint_out, false),
+            AccountMeta::new(get_associated_token_address(pool, mint_in), false),
+            AccountMeta::new(get_associated_token_address(pool, mint_out), false),
+            AccountMeta::new(get_associated_token_address(&dca, mint_in), false),
+            AccountMeta::new(get_associated_token_address(owner, mint_out), false),
+            AccountMeta::new(get_associated_token_address(&treasury, mint_in), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(*program_id, false),
+        ],
+    )
+}
+
+pub fn close_dca_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    mint_in: &Pubkey,
+    dca_id: u64,
+) -> Instruction {
+    let dca = dca_address(program_id, pool, owner, dca_id);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &[29],
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*pool, false),
+            AccountMeta::new(dca, false),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new(get_associated_token_address(&dca, mint_in), false),
+            AccountMeta::new(get_associated_token_address(owner, mint_in), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+    )
+}
+
+pub fn migrate_pool_ix(program_id: &Pubkey, payer: &Pubkey, pool: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[30],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+        ],
+    )
+}
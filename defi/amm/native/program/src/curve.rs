@@ -0,0 +1,260 @@
+use solana_program::program_error::ProgramError;
+
+use crate::{
+    state::CurveType,
+    error::AmmError,
+    math::{ONE, U256, ln, exp, pow, mul_div_floor, sqrt},
+};
+
+pub const MAX_AMP: u64 = 1_000_000;
+pub const MIN_WEIGHT_BPS: u16 = 100;
+
+// Newton's method converges in a handful of rounds for sane reserves; running
+// out of iterations is treated like an overflow.
+const MAX_ITERATIONS: usize = 255;
+
+// Amount of the other token paid out for `amount_in`, after the swap fee.
+// `a_to_b` tells weighted pools which weight belongs to which side.
+pub fn swap_output(
+    curve: CurveType,
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+    a_to_b: bool,
+) -> Result<u64, ProgramError> {
+    // every curve but constant sum divides by reserve_in, and none can pay
+    // out of an empty reserve_out
+    if reserve_out == 0 || (reserve_in == 0 && curve != CurveType::ConstantSum) {
+        return Err(AmmError::EmptyPool.into());
+    }
+
+    let amount_in_post_fee = (amount_in as u128)
+        .checked_mul(10_000 - fee_bps as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    match curve {
+        CurveType::ConstantProduct => {
+            constant_product_output(reserve_in, reserve_out, amount_in_post_fee)
+                .ok_or(ProgramError::ArithmeticOverflow)
+        },
+        CurveType::Stable { amp } => {
+            stable_output(amp, reserve_in, reserve_out, amount_in_post_fee)
+                .ok_or(ProgramError::ArithmeticOverflow)
+        },
+        CurveType::ConstantSum => {
+            // 1:1 pricing never runs out on its own, so the reserve has to be checked
+            let amount_out = amount_in_post_fee / 10_000;
+
+            if amount_out >= reserve_out as u128 {
+                return Err(AmmError::ReserveExhausted.into());
+            }
+
+            Ok(amount_out as u64)
+        },
+        CurveType::Weighted { weight_a_bps, weight_b_bps } => {
+            let (weight_in, weight_out) = if a_to_b {
+                (weight_a_bps, weight_b_bps)
+            } else {
+                (weight_b_bps, weight_a_bps)
+            };
+
+            weighted_output(weight_in, weight_out, reserve_in, reserve_out, amount_in_post_fee)
+                .ok_or(ProgramError::ArithmeticOverflow)
+        },
+    }
+}
+
+// `amount_in_post_fee` is scaled by 10_000, as in swap_output. The output
+// rounds down, in the pool's favor.
+fn constant_product_output(reserve_in: u64, reserve_out: u64, amount_in_post_fee: u128) -> Option<u64> {
+    let denominator = (reserve_in as u128)
+        .checked_mul(10_000)?
+        .checked_add(amount_in_post_fee)?;
+
+    u64::try_from(mul_div_floor(reserve_out as u128, amount_in_post_fee, denominator)?).ok()
+}
+
+// None means the math overflowed or the invariant didn't converge.
+fn stable_output(amp: u64, reserve_in: u64, reserve_out: u64, amount_in_post_fee: u128) -> Option<u64> {
+    let d = compute_d(amp, reserve_in, reserve_out)?;
+
+    let new_reserve_in = (reserve_in as u128).checked_add(amount_in_post_fee / 10_000)?;
+    let new_reserve_out = compute_y(amp, new_reserve_in, d)?;
+
+    // the extra unit rounds the output down in the pool's favor
+    let amount_out = (reserve_out as u128)
+        .saturating_sub(new_reserve_out)
+        .saturating_sub(1);
+
+    u64::try_from(amount_out).ok()
+}
+
+// Balancer's out-given-in: out = reserve_out * (1 - (reserve_in / (reserve_in + in)) ^ (w_in / w_out))
+fn weighted_output(
+    weight_in: u16,
+    weight_out: u16,
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in_post_fee: u128,
+) -> Option<u64> {
+    let new_reserve_in = (reserve_in as u128).checked_add(amount_in_post_fee / 10_000)?;
+
+    let ratio = (reserve_in as u128).checked_mul(ONE)?.checked_div(new_reserve_in)?;
+    let exponent = (weight_in as u128) * ONE / (weight_out as u128);
+
+    let remaining = pow(ratio, exponent)?.min(ONE);
+
+    // the extra unit covers the approximation error in pow, in the pool's favor
+    let amount_out = (reserve_out as u128)
+        .checked_mul(ONE - remaining)?
+        / ONE;
+
+    u64::try_from(amount_out.saturating_sub(1)).ok()
+}
+
+// Marginal price of the input in units of output, before fees, as a
+// (numerator, denominator) pair. For stable pools this is -dy/dx of the
+// invariant: (16A xy + D^3 / x) / (16A xy + D^3 / y) with x = reserve_in.
+pub fn spot_price(curve: CurveType, reserve_in: u64, reserve_out: u64, a_to_b: bool) -> Option<(U256, U256)> {
+    match curve {
+        CurveType::ConstantProduct => Some((U256::from(reserve_out), U256::from(reserve_in))),
+        CurveType::Stable { amp } => {
+            let d = U256::from(compute_d(amp, reserve_in, reserve_out)?);
+            let d_cubed = d.checked_mul(d)?.checked_mul(d)?;
+            let amp_xy = U256::from(16 * amp as u128) * U256::from(reserve_in) * U256::from(reserve_out);
+
+            Some((
+                amp_xy.checked_add(d_cubed / U256::from(reserve_in.max(1)))?,
+                amp_xy.checked_add(d_cubed / U256::from(reserve_out.max(1)))?,
+            ))
+        },
+        CurveType::ConstantSum => Some((U256::one(), U256::one())),
+        CurveType::Weighted { weight_a_bps, weight_b_bps } => {
+            let (weight_in, weight_out) = if a_to_b {
+                (weight_a_bps, weight_b_bps)
+            } else {
+                (weight_b_bps, weight_a_bps)
+            };
+
+            Some((
+                U256::from(reserve_out) * U256::from(weight_in),
+                U256::from(reserve_in) * U256::from(weight_out),
+            ))
+        },
+    }
+}
+
+// How far the execution price amount_out / amount_in falls short of the
+// spot price, in bps. Includes the swap fee.
+pub fn price_impact_bps(
+    curve: CurveType,
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    amount_out: u64,
+    a_to_b: bool,
+) -> Option<u64> {
+    let (spot_num, spot_den) = spot_price(curve, reserve_in, reserve_out, a_to_b)?;
+
+    let execution = U256::from(amount_out)
+        .checked_mul(spot_den)?
+        .checked_mul(U256::from(10_000u64))?;
+    let spot = U256::from(amount_in).checked_mul(spot_num)?;
+
+    if spot.is_zero() {
+        return None;
+    }
+
+    let ratio_bps = execution / spot;
+
+    Some(10_000u64.saturating_sub(ratio_bps.min(U256::from(10_000u64)).as_u64()))
+}
+
+// LP supply minted when a pool is created: sqrt(a * b) for constant product,
+// the invariant D for stable pools, a + b for constant sum and the weighted
+// geometric mean a^w_a * b^w_b for weighted pools.
+pub fn initial_lp_amount(curve: CurveType, amount_a: u64, amount_b: u64) -> Option<u64> {
+    match curve {
+        CurveType::ConstantProduct => {
+            u64::try_from(sqrt(amount_a as u128 * amount_b as u128)).ok()
+        },
+        CurveType::Stable { amp } => u64::try_from(compute_d(amp, amount_a, amount_b)?).ok(),
+        CurveType::ConstantSum => amount_a.checked_add(amount_b),
+        CurveType::Weighted { weight_a_bps, weight_b_bps } => {
+            let ln_a = ln((amount_a as u128).checked_mul(ONE)?)?;
+            let ln_b = ln((amount_b as u128).checked_mul(ONE)?)?;
+
+            let ln_mean = (ln_a * weight_a_bps as i128 + ln_b * weight_b_bps as i128) / 10_000;
+
+            u64::try_from(exp(ln_mean)? / ONE).ok()
+        },
+    }
+}
+
+// Stable-swap invariant for two coins:
+// 4A(x + y) + D = 4AD + D^3 / (4xy)
+pub fn compute_d(amp: u64, reserve_a: u64, reserve_b: u64) -> Option<u128> {
+    let x = reserve_a as u128;
+    let y = reserve_b as u128;
+    let sum = x + y;
+
+    if sum == 0 {
+        return Some(0);
+    }
+
+    let ann = (amp as u128).checked_mul(4)?;
+    let mut d = sum;
+
+    for _ in 0..MAX_ITERATIONS {
+        // d_p = D^3 / (4xy)
+        let d_p = d.checked_mul(d)?
+            .checked_div(x.checked_mul(2)?)?
+            .checked_mul(d)?
+            .checked_div(y.checked_mul(2)?)?;
+
+        let d_prev = d;
+
+        let numerator = ann.checked_mul(sum)?
+            .checked_add(d_p.checked_mul(2)?)?
+            .checked_mul(d)?;
+        let denominator = ann.checked_sub(1)?.checked_mul(d)?
+            .checked_add(d_p.checked_mul(3)?)?;
+
+        d = numerator.checked_div(denominator)?;
+
+        if d.abs_diff(d_prev) <= 1 {
+            return Some(d);
+        }
+    }
+
+    None
+}
+
+// Solves the invariant for the other reserve given one reserve and D:
+// y^2 + (x + D / 4A - D) y = D^3 / (16Ax)
+pub fn compute_y(amp: u64, reserve_x: u128, d: u128) -> Option<u128> {
+    let ann = (amp as u128).checked_mul(4)?;
+
+    let c = d.checked_mul(d)?
+        .checked_div(reserve_x.checked_mul(2)?)?
+        .checked_mul(d)?
+        .checked_div(ann.checked_mul(2)?)?;
+    let b = reserve_x.checked_add(d.checked_div(ann)?)?;
+
+    let mut y = d;
+
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+
+        y = y.checked_mul(y)?
+            .checked_add(c)?
+            .checked_div(y.checked_mul(2)?.checked_add(b)?.checked_sub(d)?)?;
+
+        if y.abs_diff(y_prev) <= 1 {
+            return Some(y);
+        }
+    }
+
+    None
+}
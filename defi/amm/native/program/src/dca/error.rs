@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+use solana_program::program_error::ProgramError;
+
+// DCA error codes start here, clear of the other subsystems'.
+pub const DCA_ERROR_OFFSET: u32 = 3_000;
+
+#[derive(Error, Debug)]
+pub enum DcaError {
+    #[error("DCA address does not match PDA derived from the pool, owner and DCA id")]
+    DcaAddressMismatch,
+    #[error("Escrow must be the DCA position's ATA for the input mint")]
+    EscrowMismatch,
+    #[error("DCA amounts must be greater than zero")]
+    ZeroDcaAmount,
+    #[error("DCA interval must be greater than zero")]
+    InvalidDcaInterval,
+    #[error("Account does not match the DCA position's owner, pool or mints")]
+    DcaAccountMismatch,
+    #[error("Next DCA swap is not due yet")]
+    DcaNotDue,
+}
+
+impl From<DcaError> for ProgramError {
+    fn from(error: DcaError) -> Self {
+        ProgramError::Custom(DCA_ERROR_OFFSET + error as u32)
+    }
+}
@@ -0,0 +1,8 @@
+// Dollar-cost averaging. The owner escrows tokens in a DcaPosition PDA that
+// sells `amount_per_swap` of them through the pool every `interval` seconds.
+// Execution is a permissionless crank gated by the Clock; each run pays the
+// cranker a lamport bounty the owner deposited up front.
+
+pub mod error;
+pub mod processor;
+pub mod state;
@@ -0,0 +1,350 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, clock::Clock, Sysvar},
+};
+
+use solana_system_interface::{
+    program::id as system_program_id,
+    instruction::create_account,
+};
+
+use spl_associated_token_account::{
+    id as associated_token_program_id,
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account,
+};
+use spl_token_2022::instruction::{transfer_checked, close_account};
+
+use crate::{
+    processor::{load_swap_pool, invoke_swap_signed, close_program_account},
+    token::{check_token_program, mint_decimals, token_amount},
+    dca::{
+        state::{DcaPosition, EXECUTION_BOUNTY_LAMPORTS},
+        error::DcaError,
+    },
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn process_open_dca(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    dca_id: u64,
+    amount_total: u64,
+    amount_per_swap: u64,
+    min_out_per_swap: u64,
+    interval: i64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let dca = next_account_info(accounts_iter)?;
+    let mint_in = next_account_info(accounts_iter)?;
+    let mint_out = next_account_info(accounts_iter)?;
+    let owner_ata_in = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount_total == 0 || amount_per_swap == 0 {
+        return Err(DcaError::ZeroDcaAmount.into());
+    }
+
+    if interval <= 0 {
+        return Err(DcaError::InvalidDcaInterval.into());
+    }
+
+    load_swap_pool(program_id, pool, mint_in, mint_out)?;
+
+    let (dca_pda, dca_bump) = Pubkey::find_program_address(
+        &[b"dca", pool.key.as_ref(), owner.key.as_ref(), &dca_id.to_le_bytes()], program_id);
+
+    if *dca.key != dca_pda {
+        return Err(DcaError::DcaAddressMismatch.into());
+    }
+
+    if !dca.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    check_token_program(token_program_in, mint_in)?;
+
+    if *escrow.key != get_associated_token_address_with_program_id(dca.key, mint_in.key, token_program_in.key) {
+        return Err(DcaError::EscrowMismatch.into());
+    }
+
+    if *associated_token_program.key != associated_token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // one bounty for every swap it takes to sell amount_total
+    let bounties = amount_total.div_ceil(amount_per_swap)
+        .checked_mul(EXECUTION_BOUNTY_LAMPORTS)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // create dca account, holding the bounties on top of its rent
+    invoke_signed(
+        &create_account(
+            owner.key,
+            dca.key,
+            Rent::get()?.minimum_balance(DcaPosition::SPACE) + bounties,
+            DcaPosition::SPACE as u64,
+            program_id,
+        ),
+        &[owner.clone(), dca.clone()],
+        &[&[b"dca", pool.key.as_ref(), owner.key.as_ref(), &dca_id.to_le_bytes(), &[dca_bump]]],
+    )?;
+
+    // create escrow ( dca's ata for mint_in )
+    invoke(
+        &create_associated_token_account(
+            owner.key,
+            dca.key,
+            mint_in.key,
+            token_program_in.key,
+        ),
+        &[owner.clone(), escrow.clone(), dca.clone(), mint_in.clone(), system_program.clone(), token_program_in.clone()],
+    )?;
+
+    // transfer amount_total of mint_in from owner_ata_in to escrow
+    invoke(
+        &transfer_checked(
+            token_program_in.key,
+            owner_ata_in.key,
+            mint_in.key,
+            escrow.key,
+            owner.key,
+            &[],
+            amount_total,
+            mint_decimals(mint_in)?,
+        )?,
+        &[owner_ata_in.clone(), mint_in.clone(), escrow.clone(), owner.clone()],
+    )?;
+
+    let dca_data = DcaPosition {
+        discriminator: DcaPosition::DISCRIMINATOR,
+        pool: *pool.key,
+        owner: *owner.key,
+        mint_in: *mint_in.key,
+        mint_out: *mint_out.key,
+        dca_id,
+        amount_per_swap,
+        min_out_per_swap,
+        interval,
+        // the first swap can run right away
+        next_execution_ts: Clock::get()?.unix_timestamp,
+        bump: dca_bump,
+    };
+
+    dca_data.serialize(&mut &mut dca.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Permissionless once the next swap is due. Any accounts after the fixed
+// ones (the pool's oracle, if it has one) are passed through to the Swap.
+pub fn process_execute_dca(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let cranker = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let dca = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+    let mint_in = next_account_info(accounts_iter)?;
+    let mint_out = next_account_info(accounts_iter)?;
+    let vault_in = next_account_info(accounts_iter)?;
+    let vault_out = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let owner_ata_out = next_account_info(accounts_iter)?;
+    let treasury_ata_in = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let token_program_out = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    let amm_program = next_account_info(accounts_iter)?;
+    let swap_extra_accounts = accounts_iter.as_slice();
+
+    if !cranker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut dca_data = load_dca(program_id, dca, pool, owner)?;
+
+    if *mint_in.key != dca_data.mint_in || *mint_out.key != dca_data.mint_out {
+        return Err(DcaError::DcaAccountMismatch.into());
+    }
+
+    check_token_program(token_program_in, mint_in)?;
+    check_token_program(token_program_out, mint_out)?;
+
+    if *escrow.key != get_associated_token_address_with_program_id(dca.key, mint_in.key, token_program_in.key) {
+        return Err(DcaError::EscrowMismatch.into());
+    }
+
+    // the output only ever goes to the owner
+    if *owner_ata_out.key != get_associated_token_address_with_program_id(owner.key, mint_out.key, token_program_out.key) {
+        return Err(DcaError::DcaAccountMismatch.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+
+    if now < dca_data.next_execution_ts {
+        return Err(DcaError::DcaNotDue.into());
+    }
+
+    let amount = dca_data.amount_per_swap.min(token_amount(escrow)?);
+
+    let dca_seeds: &[&[u8]] = &[
+        b"dca",
+        dca_data.pool.as_ref(),
+        dca_data.owner.as_ref(),
+        &dca_data.dca_id.to_le_bytes(),
+        &[dca_data.bump],
+    ];
+
+    // swap this interval's amount with the dca PDA as the user
+    let mut swap_accounts = vec![
+        dca.clone(), pool.clone(), mint_in.clone(), mint_out.clone(), vault_in.clone(), vault_out.clone(),
+        escrow.clone(), owner_ata_out.clone(), treasury_ata_in.clone(),
+        token_program_in.clone(), token_program_out.clone(), associated_token_program.clone(),
+    ];
+    swap_accounts.extend(swap_extra_accounts.iter().cloned());
+
+    invoke_swap_signed(program_id, amm_program, &swap_accounts, amount, dca_data.min_out_for(amount), dca_seeds)?;
+
+    // the last swap closes the position
+    if token_amount(escrow)? == 0 {
+        invoke_signed(
+            &close_account(
+                token_program_in.key,
+                escrow.key,
+                owner.key,
+                dca.key,
+                &[],
+            )?,
+            &[escrow.clone(), owner.clone(), dca.clone()],
+            &[dca_seeds],
+        )?;
+
+        return close_program_account(dca, owner, Some((cranker, EXECUTION_BOUNTY_LAMPORTS)));
+    }
+
+    // a missed interval isn't made up for with back-to-back swaps
+    dca_data.next_execution_ts = now.checked_add(dca_data.interval).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    dca_data.serialize(&mut &mut dca.data.borrow_mut()[..])?;
+
+    // pay the bounty, never dipping into the account's rent
+    let spare = dca.lamports().saturating_sub(Rent::get()?.minimum_balance(DcaPosition::SPACE));
+    let bounty = EXECUTION_BOUNTY_LAMPORTS.min(spare);
+
+    **dca.try_borrow_mut_lamports()? -= bounty;
+    **cranker.try_borrow_mut_lamports()? += bounty;
+
+    Ok(())
+}
+
+pub fn process_close_dca(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let dca = next_account_info(accounts_iter)?;
+    let mint_in = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let owner_ata_in = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let dca_data = load_dca(program_id, dca, pool, owner)?;
+
+    if *mint_in.key != dca_data.mint_in {
+        return Err(DcaError::DcaAccountMismatch.into());
+    }
+
+    check_token_program(token_program_in, mint_in)?;
+
+    if *escrow.key != get_associated_token_address_with_program_id(dca.key, mint_in.key, token_program_in.key) {
+        return Err(DcaError::EscrowMismatch.into());
+    }
+
+    let dca_seeds: &[&[u8]] = &[
+        b"dca",
+        dca_data.pool.as_ref(),
+        dca_data.owner.as_ref(),
+        &dca_data.dca_id.to_le_bytes(),
+        &[dca_data.bump],
+    ];
+
+    // transfer what's left in escrow back to owner_ata_in
+    invoke_signed(
+        &transfer_checked(
+            token_program_in.key,
+            escrow.key,
+            mint_in.key,
+            owner_ata_in.key,
+            dca.key,
+            &[],
+            token_amount(escrow)?,
+            mint_decimals(mint_in)?,
+        )?,
+        &[escrow.clone(), mint_in.clone(), owner_ata_in.clone(), dca.clone()],
+        &[dca_seeds],
+    )?;
+
+    invoke_signed(
+        &close_account(
+            token_program_in.key,
+            escrow.key,
+            owner.key,
+            dca.key,
+            &[],
+        )?,
+        &[escrow.clone(), owner.clone(), dca.clone()],
+        &[dca_seeds],
+    )?;
+
+    // unused bounties go back with the rent
+    close_program_account(dca, owner, None)
+}
+
+fn load_dca(
+    program_id: &Pubkey,
+    dca: &AccountInfo,
+    pool: &AccountInfo,
+    owner: &AccountInfo,
+) -> Result<DcaPosition, ProgramError> {
+    if dca.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let dca_data = DcaPosition::load(&dca.data.borrow())?;
+
+    if dca_data.pool != *pool.key || dca_data.owner != *owner.key {
+        return Err(DcaError::DcaAccountMismatch.into());
+    }
+
+    Ok(dca_data)
+}
@@ -0,0 +1,64 @@
+use solana_program::{
+    borsh1::try_from_slice_unchecked,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshSerialize, BorshDeserialize};
+use shank::ShankAccount;
+
+use crate::math::mul_div_floor;
+
+// Paid to the cranker for every swap it executes.
+pub const EXECUTION_BOUNTY_LAMPORTS: u64 = 50_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+pub struct DcaPosition {
+    pub discriminator: [u8; 8],
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub dca_id: u64,
+    pub amount_per_swap: u64,
+    // bound for a full-size swap, scaled down for a smaller last one
+    pub min_out_per_swap: u64,
+    pub interval: i64,
+    pub next_execution_ts: i64,
+    pub bump: u8,
+}
+
+impl DcaPosition {
+    pub const DISCRIMINATOR: [u8; 8] = *b"amm_dca_";
+
+    pub const SPACE: usize = 
+        8        // discriminator
+        + 32     // pool pubkey
+        + 32     // owner pubkey
+        + 32     // mint_in pubkey
+        + 32     // mint_out pubkey
+        + 8      // dca_id
+        + 8      // amount_per_swap
+        + 8      // min_out_per_swap
+        + 8      // interval
+        + 8      // next_execution_ts
+        + 1;     // bump
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let position = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if position.discriminator != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(position)
+    }
+
+    // Minimum output for a swap of `amount`, proportional to min_out_per_swap.
+    pub fn min_out_for(&self, amount: u64) -> u64 {
+        mul_div_floor(self.min_out_per_swap as u128, amount as u128, self.amount_per_swap.max(1) as u128)
+            .and_then(|min_out| u64::try_from(min_out).ok())
+            .unwrap_or(u64::MAX)
+    }
+}
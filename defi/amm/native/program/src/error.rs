@@ -22,6 +22,98 @@ pub enum AmmError {
     ZeroSwapAmount,
     #[error("Slippage tolerance exceeded: output amount is below the minimum specified")]
     SlippageExceed,
+    #[error("Treasury address does not match PDA derived from the pool")]
+    TreasuryAddressMismatch,
+    #[error("Signer is not the pool admin")]
+    NotPoolAdmin,
+    #[error("Pool is paused")]
+    PoolPaused,
+    #[error("Initial liquidity must exceed the permanently locked minimum")]
+    InsufficientInitialLiquidity,
+    #[error("Stable curve amplification must be between 1 and MAX_AMP")]
+    InvalidAmplification,
+    #[error("Swap would exhaust the output reserve")]
+    ReserveExhausted,
+    #[error("Pool weights must each be at least MIN_WEIGHT_BPS and add up to 10000")]
+    InvalidWeights,
+    #[error("Each hop of a route must take the previous hop's output mint as input")]
+    InvalidRoute,
+    #[error("Flash loan amount must be greater than zero")]
+    ZeroFlashLoanAmount,
+    #[error("Pool has an outstanding flash loan")]
+    FlashLoanActive,
+    #[error("Flash loan must be followed by a FlashRepay for the same pool and mint")]
+    FlashLoanNotRepaid,
+    #[error("Pool has no outstanding flash loan to repay")]
+    NoActiveFlashLoan,
+    #[error("Neither swap mint is the native SOL mint")]
+    NotNativeSwap,
+    #[error("Wrapped SOL account must be the user's ATA for the native mint")]
+    WrappedSolAccountMismatch,
+    #[error("Swap mints must be the pool's two mints")]
+    MintNotInPool,
+    #[error("Input vault must be the pool's ATA for the input mint")]
+    VaultInMismatch,
+    #[error("Output vault must be the pool's ATA for the output mint")]
+    VaultOutMismatch,
+    #[error("Swap would decrease the pool's constant-product invariant")]
+    InvariantViolation,
+    #[error("Execution price is further below the spot price than max_price_impact_bps allows")]
+    PriceImpactTooHigh,
+    #[error("Referrer account can't be one of the pool's own token accounts")]
+    InvalidReferrer,
+    #[error("Registry account must be the program's registry PDA")]
+    RegistryAddressMismatch,
+    #[error("Fee is not one of the allowed fee tiers")]
+    InvalidFeeTier,
+    #[error("Config account must be the program's config PDA")]
+    ConfigAddressMismatch,
+    #[error("Signer is not the protocol config admin")]
+    NotConfigAdmin,
+    #[error("Pool has no proposed fee change")]
+    NoPendingFeeChange,
+    #[error("Proposed fee change can't be executed until its delay has passed")]
+    FeeChangeTimelocked,
+    #[error("Oracle account missing or not the pool's oracle")]
+    OracleMismatch,
+    #[error("Oracle account is not a trading Pyth price")]
+    InvalidOracle,
+    #[error("Oracle price is too old")]
+    OracleStale,
+    #[error("Oracle confidence interval is too wide")]
+    OracleConfidenceTooWide,
+    #[error("Execution price deviates too far from the oracle price")]
+    OraclePriceDeviation,
+    #[error("Pool account uses an older layout; run MigratePool first")]
+    PoolNeedsMigration,
+    #[error("Pool account version is not one MigratePool can upgrade")]
+    UnknownPoolVersion,
+    #[error("Pool account is already at the current version")]
+    PoolAlreadyCurrent,
+    #[error("Signer is not on the config's pool creator allowlist")]
+    PoolCreatorNotAllowed,
+    #[error("Withdraw percentage must be between 1 and 10_000 bps")]
+    InvalidWithdrawPercent,
+    #[error("Signer neither owns the input token account nor is its delegate")]
+    NotTokenDelegate,
+    #[error("Delegated amount is less than the swap's amount in")]
+    DelegatedAmountTooLow,
+    #[error("A delegate's swap has to pay out to the input account's owner")]
+    DelegateOutputNotOwner,
+    #[error("Signer is not the nominated admin")]
+    NotPendingAdmin,
+    #[error("Pool has no liquidity to price this against")]
+    EmptyPool,
+    #[error("Pool doesn't hold enough liquidity for the amount requested")]
+    InsufficientLiquidity,
+    #[error("Pool has already been funded; use ProvideLiquidity")]
+    PoolAlreadyFunded,
+    #[error("A vault holds less than the pool's recorded reserves and fees")]
+    VaultBalanceMismatch,
+    #[error("Account is not this program's ProgramData account")]
+    ProgramDataMismatch,
+    #[error("Only the program's upgrade authority can create the config")]
+    NotUpgradeAuthority,
 }
 
 impl From<AmmError> for ProgramError {
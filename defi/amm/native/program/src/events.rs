@@ -0,0 +1,57 @@
+// Events logged with sol_log_data so indexers can follow pool activity
+// without replaying instructions. Each log is one Borsh-encoded AmmEvent,
+// whose leading variant byte tells the events apart.
+
+use borsh::{BorshSerialize, BorshDeserialize};
+
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum AmmEvent {
+    SwapExecuted(SwapExecuted),
+    LiquidityAdded(LiquidityAdded),
+    LiquidityRemoved(LiquidityRemoved),
+}
+
+// Amounts are what the pool received and paid out, after any Token-2022
+// transfer fees on the way in.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SwapExecuted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub protocol_fee: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_minted: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_burned: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+impl AmmEvent {
+    pub fn emit(&self) {
+        sol_log_data(&[&borsh::to_vec(self).unwrap()]);
+    }
+}
@@ -0,0 +1,32 @@
+use thiserror::Error;
+
+use solana_program::program_error::ProgramError;
+
+// Farm error codes start here, so they don't collide with AmmError's.
+pub const FARM_ERROR_OFFSET: u32 = 1_000;
+
+#[derive(Error, Debug)]
+pub enum FarmError {
+    #[error("Farm address does not match PDA derived from the pool")]
+    FarmAddressMismatch,
+    #[error("Stake position address does not match PDA derived from the farm and owner")]
+    PositionAddressMismatch,
+    #[error("Stake vault must be the farm's ATA for the LP mint")]
+    StakeVaultMismatch,
+    #[error("Reward vault must be the farm's ATA for the reward mint")]
+    RewardVaultMismatch,
+    #[error("Mint does not match the farm's")]
+    MintMismatch,
+    #[error("Stake amount must be greater than zero")]
+    ZeroStakeAmount,
+    #[error("Can't unstake more than the position holds")]
+    InsufficientStake,
+    #[error("Signer does not own the stake position")]
+    NotPositionOwner,
+}
+
+impl From<FarmError> for ProgramError {
+    fn from(error: FarmError) -> Self {
+        ProgramError::Custom(FARM_ERROR_OFFSET + error as u32)
+    }
+}
@@ -0,0 +1,9 @@
+// LP staking. A Farm streams `reward_per_second` of a reward mint to whoever
+// has the pool's LP tokens staked in it, split pro rata with the classic
+// accumulated-reward-per-share accounting: the farm tracks how much one
+// staked LP token has earned since it was created, and each position
+// remembers where that counter stood when its stake last changed.
+
+pub mod error;
+pub mod processor;
+pub mod state;
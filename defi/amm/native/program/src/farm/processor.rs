@@ -0,0 +1,456 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo}, 
+    entrypoint::ProgramResult, 
+    program_error::ProgramError,
+    program::{invoke, invoke_signed}, 
+    pubkey::Pubkey, 
+    sysvar::{rent::Rent, clock::Clock, Sysvar},
+};
+
+use solana_system_interface::{
+    program::id as system_program_id,
+    instruction::create_account,
+};
+
+use spl_associated_token_account::{
+    id as associated_token_program_id,
+    get_associated_token_address,
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account,
+};
+use spl_token::id as token_program_id;
+use spl_token_2022::instruction::transfer_checked;
+
+use crate::{
+    state::LiquidityPool,
+    error::AmmError,
+    token::{check_token_program, mint_decimals, token_amount},
+    farm::{
+        state::{Farm, StakePosition},
+        error::FarmError,
+    },
+};
+
+pub fn process_create_farm(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reward_per_second: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let farm = next_account_info(accounts_iter)?;
+    let mint_lp = next_account_info(accounts_iter)?;
+    let reward_mint = next_account_info(accounts_iter)?;
+    let stake_vault = next_account_info(accounts_iter)?;
+    let reward_vault = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let reward_token_program = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *admin.key != pool_data.admin {
+        return Err(AmmError::NotPoolAdmin.into());
+    }
+
+    let (farm_pda, farm_bump) = Pubkey::find_program_address(&[b"farm", pool.key.as_ref()], program_id);
+
+    if *farm.key != farm_pda {
+        return Err(FarmError::FarmAddressMismatch.into());
+    }
+
+    if !farm.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let (lp_mint_pda, _lp_mint_bump) = Pubkey::find_program_address(
+        &[b"lp_mint", pool.key.as_ref()], program_id);
+
+    if *mint_lp.key != lp_mint_pda {
+        return Err(AmmError::LpMintAddressMismatch.into());
+    }
+
+    // the LP mint is always a plain SPL Token mint
+    if *token_program.key != token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    check_token_program(reward_token_program, reward_mint)?;
+
+    if *stake_vault.key != get_associated_token_address(farm.key, mint_lp.key) {
+        return Err(FarmError::StakeVaultMismatch.into());
+    }
+
+    if *reward_vault.key != get_associated_token_address_with_program_id(farm.key, reward_mint.key, reward_token_program.key) {
+        return Err(FarmError::RewardVaultMismatch.into());
+    }
+
+    if *associated_token_program.key != associated_token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // create farm account
+    invoke_signed(
+        &create_account(
+            admin.key, 
+            farm.key, 
+            Rent::get()?.minimum_balance(Farm::SPACE), 
+            Farm::SPACE as u64, 
+            program_id,
+        ), 
+        &[admin.clone(), farm.clone()], 
+        &[&[b"farm", pool.key.as_ref(), &[farm_bump]]],
+    )?;
+
+    // create stake_vault ( farm's ata for mint_lp )
+    invoke(
+        &create_associated_token_account(
+            admin.key, 
+            farm.key, 
+            mint_lp.key, 
+            token_program.key,
+        ), 
+        &[admin.clone(), stake_vault.clone(), farm.clone(), mint_lp.clone(), system_program.clone(), token_program.clone()],
+    )?;
+
+    // create reward_vault ( farm's ata for reward_mint ), funded by plain transfers
+    invoke(
+        &create_associated_token_account(
+            admin.key, 
+            farm.key, 
+            reward_mint.key, 
+            reward_token_program.key,
+        ), 
+        &[admin.clone(), reward_vault.clone(), farm.clone(), reward_mint.clone(), system_program.clone(), reward_token_program.clone()],
+    )?;
+
+    let farm_data = Farm {
+        discriminator: Farm::DISCRIMINATOR,
+        pool: *pool.key,
+        lp_mint: *mint_lp.key,
+        reward_mint: *reward_mint.key,
+        reward_per_second,
+        acc_reward_per_share: 0,
+        last_update_ts: Clock::get()?.unix_timestamp,
+        total_staked: 0,
+        bump: farm_bump,
+    };
+
+    farm_data.serialize(&mut &mut farm.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_stake_lp(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let farm = next_account_info(accounts_iter)?;
+    let position = next_account_info(accounts_iter)?;
+    let mint_lp = next_account_info(accounts_iter)?;
+    let user_ata_lp = next_account_info(accounts_iter)?;
+    let stake_vault = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(FarmError::ZeroStakeAmount.into());
+    }
+
+    let mut farm_data = load_farm(program_id, farm)?;
+
+    if *mint_lp.key != farm_data.lp_mint {
+        return Err(FarmError::MintMismatch.into());
+    }
+
+    if *stake_vault.key != get_associated_token_address(farm.key, mint_lp.key) {
+        return Err(FarmError::StakeVaultMismatch.into());
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (position_pda, position_bump) = Pubkey::find_program_address(
+        &[b"stake", farm.key.as_ref(), user.key.as_ref()], program_id);
+
+    if *position.key != position_pda {
+        return Err(FarmError::PositionAddressMismatch.into());
+    }
+
+    // the first stake opens the position
+    let mut position_data = if position.data_is_empty() {
+        invoke_signed(
+            &create_account(
+                user.key, 
+                position.key, 
+                Rent::get()?.minimum_balance(StakePosition::SPACE), 
+                StakePosition::SPACE as u64, 
+                program_id,
+            ), 
+            &[user.clone(), position.clone()], 
+            &[&[b"stake", farm.key.as_ref(), user.key.as_ref(), &[position_bump]]],
+        )?;
+
+        StakePosition {
+            discriminator: StakePosition::DISCRIMINATOR,
+            farm: *farm.key,
+            owner: *user.key,
+            amount: 0,
+            reward_debt: 0,
+            pending_rewards: 0,
+            bump: position_bump,
+        }
+    } else {
+        StakePosition::load(&position.data.borrow())?
+    };
+
+    // transfer amount of mint_lp from user_ata_lp to stake_vault
+    invoke(
+        &transfer_checked(
+            token_program.key, 
+            user_ata_lp.key, 
+            mint_lp.key, 
+            stake_vault.key, 
+            user.key, 
+            &[], 
+            amount, 
+            mint_decimals(mint_lp)?,
+        )?, 
+        &[user_ata_lp.clone(), mint_lp.clone(), stake_vault.clone(), user.clone()],
+    )?;
+
+    farm_data.accrue(Clock::get()?.unix_timestamp)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let new_amount = position_data.amount.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    position_data.settle(farm_data.acc_reward_per_share, new_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    farm_data.total_staked = farm_data.total_staked.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    farm_data.serialize(&mut &mut farm.data.borrow_mut()[..])?;
+    position_data.serialize(&mut &mut position.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_unstake_lp(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let farm = next_account_info(accounts_iter)?;
+    let position = next_account_info(accounts_iter)?;
+    let mint_lp = next_account_info(accounts_iter)?;
+    let user_ata_lp = next_account_info(accounts_iter)?;
+    let stake_vault = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(FarmError::ZeroStakeAmount.into());
+    }
+
+    let mut farm_data = load_farm(program_id, farm)?;
+    let mut position_data = load_position(program_id, farm, position, user)?;
+
+    if *mint_lp.key != farm_data.lp_mint {
+        return Err(FarmError::MintMismatch.into());
+    }
+
+    if *stake_vault.key != get_associated_token_address(farm.key, mint_lp.key) {
+        return Err(FarmError::StakeVaultMismatch.into());
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let new_amount = position_data.amount.checked_sub(amount).ok_or(FarmError::InsufficientStake)?;
+
+    farm_data.accrue(Clock::get()?.unix_timestamp)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // rewards earned so far stay claimable through Harvest
+    position_data.settle(farm_data.acc_reward_per_share, new_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    farm_data.total_staked -= amount;
+
+    // transfer amount of mint_lp from stake_vault back to user_ata_lp
+    invoke_signed(
+        &transfer_checked(
+            token_program.key, 
+            stake_vault.key, 
+            mint_lp.key, 
+            user_ata_lp.key, 
+            farm.key, 
+            &[], 
+            amount, 
+            mint_decimals(mint_lp)?,
+        )?, 
+        &[stake_vault.clone(), mint_lp.clone(), user_ata_lp.clone(), farm.clone()],
+        &[&[b"farm", farm_data.pool.as_ref(), &[farm_data.bump]]],
+    )?;
+
+    farm_data.serialize(&mut &mut farm.data.borrow_mut()[..])?;
+    position_data.serialize(&mut &mut position.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_harvest(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let farm = next_account_info(accounts_iter)?;
+    let position = next_account_info(accounts_iter)?;
+    let reward_mint = next_account_info(accounts_iter)?;
+    let reward_vault = next_account_info(accounts_iter)?;
+    let user_ata_reward = next_account_info(accounts_iter)?;
+    let reward_token_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut farm_data = load_farm(program_id, farm)?;
+    let mut position_data = load_position(program_id, farm, position, user)?;
+
+    if *reward_mint.key != farm_data.reward_mint {
+        return Err(FarmError::MintMismatch.into());
+    }
+
+    check_token_program(reward_token_program, reward_mint)?;
+
+    if *reward_vault.key != get_associated_token_address_with_program_id(farm.key, reward_mint.key, reward_token_program.key) {
+        return Err(FarmError::RewardVaultMismatch.into());
+    }
+
+    farm_data.accrue(Clock::get()?.unix_timestamp)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    position_data.settle(farm_data.acc_reward_per_share, position_data.amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // an underfunded farm pays what it has; the rest stays owed until the
+    // vault is topped up
+    let payout = position_data.pending_rewards.min(token_amount(reward_vault)?);
+
+    if payout > 0 {
+        invoke_signed(
+            &transfer_checked(
+                reward_token_program.key, 
+                reward_vault.key, 
+                reward_mint.key, 
+                user_ata_reward.key, 
+                farm.key, 
+                &[], 
+                payout, 
+                mint_decimals(reward_mint)?,
+            )?, 
+            &[reward_vault.clone(), reward_mint.clone(), user_ata_reward.clone(), farm.clone()],
+            &[&[b"farm", farm_data.pool.as_ref(), &[farm_data.bump]]],
+        )?;
+    }
+
+    position_data.pending_rewards -= payout;
+
+    farm_data.serialize(&mut &mut farm.data.borrow_mut()[..])?;
+    position_data.serialize(&mut &mut position.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn load_farm(program_id: &Pubkey, farm: &AccountInfo) -> Result<Farm, ProgramError> {
+    if farm.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let farm_data = Farm::load(&farm.data.borrow())?;
+
+    let expected_farm = Pubkey::create_program_address(
+        &[b"farm", farm_data.pool.as_ref(), &[farm_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_farm != *farm.key {
+        return Err(FarmError::FarmAddressMismatch.into());
+    }
+
+    Ok(farm_data)
+}
+
+fn load_position(
+    program_id: &Pubkey,
+    farm: &AccountInfo,
+    position: &AccountInfo,
+    user: &AccountInfo,
+) -> Result<StakePosition, ProgramError> {
+    if position.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let position_data = StakePosition::load(&position.data.borrow())?;
+
+    if position_data.owner != *user.key {
+        return Err(FarmError::NotPositionOwner.into());
+    }
+
+    if position_data.farm != *farm.key {
+        return Err(FarmError::PositionAddressMismatch.into());
+    }
+
+    Ok(position_data)
+}
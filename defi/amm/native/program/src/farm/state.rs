@@ -0,0 +1,120 @@
+use solana_program::{
+    borsh1::try_from_slice_unchecked,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshSerialize, BorshDeserialize};
+use shank::ShankAccount;
+
+use crate::math::mul_div_floor;
+
+// acc_reward_per_share is scaled by this so small rewards spread over a
+// large stake don't round away to nothing.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+pub struct Farm {
+    pub discriminator: [u8; 8],
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_per_second: u64,
+    pub acc_reward_per_share: u128,
+    pub last_update_ts: i64,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+pub struct StakePosition {
+    pub discriminator: [u8; 8],
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl Farm {
+    pub const DISCRIMINATOR: [u8; 8] = *b"amm_farm";
+
+    pub const SPACE: usize = 
+        8        // discriminator
+        + 32     // pool pubkey
+        + 32     // lp_mint pubkey
+        + 32     // reward_mint pubkey
+        + 8      // reward_per_second
+        + 16     // acc_reward_per_share
+        + 8      // last_update_ts
+        + 8      // total_staked
+        + 1;     // bump
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let farm = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if farm.discriminator != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(farm)
+    }
+
+    // Credits the rewards emitted since the last update to every staked LP
+    // token. Must run before total_staked changes. Nothing accrues while
+    // nothing is staked, so those rewards stay in the vault.
+    pub fn accrue(&mut self, now: i64) -> Option<()> {
+        let elapsed = now.saturating_sub(self.last_update_ts);
+
+        if elapsed > 0 && self.total_staked > 0 {
+            let reward = (elapsed as u128).checked_mul(self.reward_per_second as u128)?;
+
+            self.acc_reward_per_share = self.acc_reward_per_share
+                .checked_add(mul_div_floor(reward, REWARD_PRECISION, self.total_staked as u128)?)?;
+        }
+
+        self.last_update_ts = self.last_update_ts.max(now);
+
+        Some(())
+    }
+}
+
+impl StakePosition {
+    pub const DISCRIMINATOR: [u8; 8] = *b"amm_stak";
+
+    pub const SPACE: usize = 
+        8        // discriminator
+        + 32     // farm pubkey
+        + 32     // owner pubkey
+        + 8      // amount
+        + 16     // reward_debt
+        + 8      // pending_rewards
+        + 1;     // bump
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let position = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if position.discriminator != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(position)
+    }
+
+    // Moves what the position earned since its stake last changed into
+    // pending_rewards, then changes the stake to `new_amount`. The farm has
+    // to be accrued first.
+    pub fn settle(&mut self, acc_reward_per_share: u128, new_amount: u64) -> Option<()> {
+        let accumulated = mul_div_floor(self.amount as u128, acc_reward_per_share, REWARD_PRECISION)?;
+        let earned = u64::try_from(accumulated.checked_sub(self.reward_debt)?).ok()?;
+
+        self.pending_rewards = self.pending_rewards.checked_add(earned)?;
+        self.amount = new_amount;
+        self.reward_debt = mul_div_floor(new_amount as u128, acc_reward_per_share, REWARD_PRECISION)?;
+
+        Some(())
+    }
+}
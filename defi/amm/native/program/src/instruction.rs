@@ -1,29 +1,448 @@
-use solana_program::program_error::ProgramError;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 
-use borsh::BorshDeserialize;
+use borsh::{BorshSerialize, BorshDeserialize};
+use shank::ShankInstruction;
 
+use crate::state::CurveType;
+
+// Shank derives the IDL from this enum: the variant index is the tag byte
+// and the fields are the Borsh payload, matching `unpack` below.
+#[derive(BorshSerialize, Debug, PartialEq, ShankInstruction)]
 pub enum AmmInstruction {
+    #[account(0, writable, signer, name = "user", desc = "Pool creator and fee payer")]
+    #[account(1, writable, name = "pool", desc = "Pool PDA")]
+    #[account(2, name = "mint_a")]
+    #[account(3, name = "mint_b")]
+    #[account(4, writable, name = "vault_a", desc = "Pool ATA for mint_a")]
+    #[account(5, writable, name = "vault_b", desc = "Pool ATA for mint_b")]
+    #[account(6, writable, name = "mint_lp", desc = "LP mint PDA")]
+    #[account(7, writable, name = "user_ata_lp")]
+    #[account(8, writable, name = "pool_ata_lp", desc = "Pool ATA holding the locked minimum liquidity")]
+    #[account(9, writable, name = "user_ata_a")]
+    #[account(10, writable, name = "user_ata_b")]
+    #[account(11, name = "treasury", desc = "Treasury PDA")]
+    #[account(12, writable, name = "treasury_ata_a")]
+    #[account(13, writable, name = "treasury_ata_b")]
+    #[account(14, name = "token_program", desc = "SPL Token, owner of the LP mint")]
+    #[account(15, name = "token_program_a")]
+    #[account(16, name = "token_program_b")]
+    #[account(17, name = "associated_token_program")]
+    #[account(18, name = "system_program")]
+    #[account(19, writable, name = "registry", desc = "Registry PDA listing every pool")]
+    #[account(20, name = "config", desc = "Config PDA holding the allowed fee tiers, may be uninitialized")]
     CreatePool {
         amount_a: u64,
         amount_b: u64,
         fee_bps: u16,
+        protocol_fee_bps: u16,
+        curve: CurveType,
     },
+    #[account(0, writable, signer, name = "user")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint_a")]
+    #[account(3, name = "mint_b")]
+    #[account(4, writable, name = "vault_a")]
+    #[account(5, writable, name = "vault_b")]
+    #[account(6, writable, name = "mint_lp")]
+    #[account(7, writable, name = "user_ata_lp")]
+    #[account(8, writable, name = "user_ata_a")]
+    #[account(9, writable, name = "user_ata_b")]
+    #[account(10, name = "token_program")]
+    #[account(11, name = "token_program_a")]
+    #[account(12, name = "token_program_b")]
+    #[account(13, optional, name = "system_program", desc = "With associated_token_program, creates user_ata_lp if needed")]
+    #[account(14, optional, name = "associated_token_program")]
+    #[account(15, optional, writable, name = "pool_ata_lp", desc = "Receives MINIMUM_LIQUIDITY when re-seeding a pool with no LP supply")]
     ProvideLiquidity {
         amount_a_desired: u64,
         amount_b_desired: u64,
         amount_a_min: u64,
         amount_b_min: u64,
     },
+    #[account(0, writable, signer, name = "user")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint_a")]
+    #[account(3, name = "mint_b")]
+    #[account(4, writable, name = "vault_a")]
+    #[account(5, writable, name = "vault_b")]
+    #[account(6, writable, name = "mint_lp")]
+    #[account(7, writable, name = "user_ata_lp")]
+    #[account(8, writable, name = "user_ata_a")]
+    #[account(9, writable, name = "user_ata_b")]
+    #[account(10, name = "token_program")]
+    #[account(11, name = "token_program_a")]
+    #[account(12, name = "token_program_b")]
     WithdrawLiquidity {
         amount_lp_in: u64,
         amount_a_min: u64,
         amount_b_min: u64,
     },
+    #[account(0, writable, signer, name = "user", desc = "Owner of user_ata_in, or its delegate")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint_in")]
+    #[account(3, name = "mint_out")]
+    #[account(4, writable, name = "vault_in")]
+    #[account(5, writable, name = "vault_out")]
+    #[account(6, writable, name = "user_ata_in")]
+    #[account(7, writable, name = "user_ata_out")]
+    #[account(8, writable, name = "treasury_ata_in")]
+    #[account(9, name = "token_program_in")]
+    #[account(10, name = "token_program_out")]
+    #[account(11, name = "associated_token_program")]
+    #[account(12, optional, name = "oracle", desc = "The pool's Pyth price account, required if it has one")]
+    #[account(13, optional, writable, name = "referrer_ata", desc = "Receives the referral share of the LP fee")]
     Swap {
         amount_in: u64,
         min_out: u64,
+        max_price_impact_bps: Option<u16>,
+    },
+    #[account(0, signer, name = "admin")]
+    #[account(1, name = "pool")]
+    #[account(2, name = "mint")]
+    #[account(3, name = "treasury")]
+    #[account(4, writable, name = "treasury_ata")]
+    #[account(5, writable, name = "admin_ata")]
+    #[account(6, name = "token_program")]
+    CollectProtocolFees,
+    #[account(0, signer, name = "admin")]
+    #[account(1, writable, name = "pool")]
+    SetPaused {
+        paused: bool,
+    },
+    #[account(0, writable, name = "pool")]
+    #[account(1, name = "vault_a")]
+    #[account(2, name = "vault_b")]
+    Sync,
+    #[account(0, name = "pool")]
+    #[account(1, name = "mint_a")]
+    #[account(2, name = "mint_b")]
+    #[account(3, writable, name = "vault_a")]
+    #[account(4, writable, name = "vault_b")]
+    #[account(5, writable, name = "to_ata_a")]
+    #[account(6, writable, name = "to_ata_b")]
+    #[account(7, name = "token_program_a")]
+    #[account(8, name = "token_program_b")]
+    Skim,
+    #[account(0, name = "pool")]
+    Quote {
+        amount_in: u64,
+        a_to_b: bool,
+    },
+    // followed by 10 accounts per hop: pool, mint_in, mint_out, vault_in,
+    // vault_out, user_ata_in, user_ata_out, treasury_ata_in and the two
+    // token programs
+    #[account(0, signer, name = "user")]
+    SwapRoute {
+        amount_in: u64,
+        min_out: u64,
+    },
+    #[account(0, signer, name = "borrower")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint")]
+    #[account(3, writable, name = "vault")]
+    #[account(4, writable, name = "borrower_ata")]
+    #[account(5, name = "token_program")]
+    #[account(6, name = "instructions", desc = "Instructions sysvar, used to find the FlashRepay")]
+    FlashLoan {
+        amount: u64,
+    },
+    #[account(0, signer, name = "borrower")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint")]
+    #[account(3, writable, name = "vault")]
+    #[account(4, writable, name = "borrower_ata")]
+    #[account(5, name = "token_program")]
+    FlashRepay,
+    #[account(0, writable, signer, name = "user")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint_in")]
+    #[account(3, name = "mint_out")]
+    #[account(4, writable, name = "vault_in")]
+    #[account(5, writable, name = "vault_out")]
+    #[account(6, writable, name = "user_ata_in", desc = "Temporary wSOL ATA when mint_in is the native mint")]
+    #[account(7, writable, name = "user_ata_out")]
+    #[account(8, writable, name = "treasury_ata_in")]
+    #[account(9, name = "token_program_in")]
+    #[account(10, name = "token_program_out")]
+    #[account(11, name = "associated_token_program")]
+    #[account(12, name = "token_program", desc = "SPL Token, owner of the native mint")]
+    #[account(13, name = "system_program")]
+    SwapNative {
+        amount_in: u64,
+        min_out: u64,
+    },
+    #[account(0, writable, signer, name = "user")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint_in")]
+    #[account(3, name = "mint_out")]
+    #[account(4, writable, name = "vault_in")]
+    #[account(5, writable, name = "vault_out")]
+    #[account(6, writable, name = "user_ata_in")]
+    #[account(7, writable, name = "user_ata_out")]
+    #[account(8, writable, name = "treasury_ata_in")]
+    #[account(9, name = "token_program_in")]
+    #[account(10, name = "token_program_out")]
+    #[account(11, name = "associated_token_program")]
+    #[account(12, writable, name = "mint_lp")]
+    #[account(13, writable, name = "user_ata_lp")]
+    #[account(14, name = "token_program")]
+    ZapIn {
+        amount_in: u64,
+        min_lp_out: u64,
+    },
+    #[account(0, writable, signer, name = "user")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint_a")]
+    #[account(3, name = "mint_b")]
+    #[account(4, writable, name = "vault_a")]
+    #[account(5, writable, name = "vault_b")]
+    #[account(6, writable, name = "mint_lp")]
+    #[account(7, writable, name = "user_ata_lp")]
+    #[account(8, writable, name = "user_ata_a")]
+    #[account(9, writable, name = "user_ata_b")]
+    #[account(10, name = "token_program")]
+    #[account(11, name = "token_program_a")]
+    #[account(12, name = "token_program_b")]
+    #[account(13, writable, name = "treasury_ata_in", desc = "Treasury ATA for the side swapped away")]
+    #[account(14, name = "associated_token_program")]
+    WithdrawSingleSided {
+        amount_lp_in: u64,
+        want_mint: Pubkey,
+        min_out: u64,
+    },
+    #[account(0, signer, name = "admin")]
+    #[account(1, writable, name = "pool")]
+    SetReferralFee {
+        referral_fee_bps: u16,
+    },
+    #[account(0, writable, signer, name = "admin", desc = "Config admin; only the upgrade authority can create the config")]
+    #[account(1, writable, name = "config", desc = "Config PDA")]
+    #[account(2, name = "system_program")]
+    #[account(3, name = "program_data", desc = "The program's ProgramData account, for its upgrade authority")]
+    SetFeeTiers {
+        fee_tiers: Vec<u16>,
+    },
+    #[account(0, signer, name = "admin")]
+    #[account(1, writable, name = "pool")]
+    ProposeFeeChange {
+        protocol_fee_bps: u16,
+    },
+    #[account(0, signer, name = "admin")]
+    #[account(1, writable, name = "pool")]
+    ExecuteFeeChange,
+    #[account(0, writable, signer, name = "admin", desc = "Pool admin and fee payer")]
+    #[account(1, name = "pool")]
+    #[account(2, writable, name = "farm", desc = "Farm PDA")]
+    #[account(3, name = "mint_lp", desc = "LP mint PDA")]
+    #[account(4, name = "reward_mint")]
+    #[account(5, writable, name = "stake_vault", desc = "Farm ATA holding staked LP tokens")]
+    #[account(6, writable, name = "reward_vault", desc = "Farm ATA for reward_mint, funded by plain transfers")]
+    #[account(7, name = "token_program", desc = "SPL Token program, for the LP mint")]
+    #[account(8, name = "reward_token_program", desc = "Token program owning reward_mint")]
+    #[account(9, name = "associated_token_program")]
+    #[account(10, name = "system_program")]
+    CreateFarm {
+        reward_per_second: u64,
+    },
+    #[account(0, writable, signer, name = "user")]
+    #[account(1, writable, name = "farm")]
+    #[account(2, writable, name = "position", desc = "Stake position PDA, created on the first stake")]
+    #[account(3, name = "mint_lp")]
+    #[account(4, writable, name = "user_ata_lp")]
+    #[account(5, writable, name = "stake_vault")]
+    #[account(6, name = "token_program")]
+    #[account(7, name = "system_program")]
+    StakeLp {
+        amount: u64,
+    },
+    #[account(0, signer, name = "user")]
+    #[account(1, writable, name = "farm")]
+    #[account(2, writable, name = "position")]
+    #[account(3, name = "mint_lp")]
+    #[account(4, writable, name = "user_ata_lp")]
+    #[account(5, writable, name = "stake_vault")]
+    #[account(6, name = "token_program")]
+    UnstakeLp {
+        amount: u64,
+    },
+    #[account(0, signer, name = "user")]
+    #[account(1, writable, name = "farm")]
+    #[account(2, writable, name = "position")]
+    #[account(3, name = "reward_mint")]
+    #[account(4, writable, name = "reward_vault")]
+    #[account(5, writable, name = "user_ata_reward")]
+    #[account(6, name = "reward_token_program")]
+    Harvest,
+    #[account(0, signer, name = "admin")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, optional, name = "oracle", desc = "Pyth price of mint_a in mint_b; omit to remove the bound")]
+    SetOracle {
+        max_deviation_bps: u16,
+    },
+    #[account(0, writable, signer, name = "owner", desc = "Order owner, pays rent and the fill bounty")]
+    #[account(1, name = "pool")]
+    #[account(2, writable, name = "order", desc = "Order PDA")]
+    #[account(3, name = "mint_in")]
+    #[account(4, name = "mint_out")]
+    #[account(5, writable, name = "owner_ata_in")]
+    #[account(6, writable, name = "escrow", desc = "Order ATA for mint_in")]
+    #[account(7, name = "token_program_in")]
+    #[account(8, name = "associated_token_program")]
+    #[account(9, name = "system_program")]
+    PlaceLimitOrder {
+        order_id: u64,
+        amount_in: u64,
+        min_out: u64,
+    },
+    #[account(0, writable, signer, name = "cranker", desc = "Anyone; receives the fill bounty")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, writable, name = "order")]
+    #[account(3, writable, name = "owner", desc = "Order owner, gets back the order's rent")]
+    #[account(4, name = "mint_in")]
+    #[account(5, name = "mint_out")]
+    #[account(6, writable, name = "vault_in")]
+    #[account(7, writable, name = "vault_out")]
+    #[account(8, writable, name = "escrow")]
+    #[account(9, writable, name = "owner_ata_out")]
+    #[account(10, writable, name = "treasury_ata_in")]
+    #[account(11, name = "token_program_in")]
+    #[account(12, name = "token_program_out")]
+    #[account(13, name = "associated_token_program")]
+    #[account(14, name = "amm_program", desc = "This program, for the Swap it makes")]
+    #[account(15, optional, name = "oracle", desc = "The pool's Pyth price account, required if it has one")]
+    FillLimitOrder,
+    #[account(0, writable, signer, name = "owner")]
+    #[account(1, name = "pool")]
+    #[account(2, writable, name = "order")]
+    #[account(3, name = "mint_in")]
+    #[account(4, writable, name = "escrow")]
+    #[account(5, writable, name = "owner_ata_in")]
+    #[account(6, name = "token_program_in")]
+    CancelLimitOrder,
+    #[account(0, writable, signer, name = "owner", desc = "Position owner, pays rent and the execution bounties")]
+    #[account(1, name = "pool")]
+    #[account(2, writable, name = "dca", desc = "DCA position PDA")]
+    #[account(3, name = "mint_in")]
+    #[account(4, name = "mint_out")]
+    #[account(5, writable, name = "owner_ata_in")]
+    #[account(6, writable, name = "escrow", desc = "DCA position ATA for mint_in")]
+    #[account(7, name = "token_program_in")]
+    #[account(8, name = "associated_token_program")]
+    #[account(9, name = "system_program")]
+    OpenDca {
+        dca_id: u64,
+        amount_total: u64,
+        amount_per_swap: u64,
+        min_out_per_swap: u64,
+        interval: i64,
+    },
+    #[account(0, writable, signer, name = "cranker", desc = "Anyone; receives the execution bounty")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, writable, name = "dca")]
+    #[account(3, writable, name = "owner", desc = "Position owner, gets back the rent once the escrow runs out")]
+    #[account(4, name = "mint_in")]
+    #[account(5, name = "mint_out")]
+    #[account(6, writable, name = "vault_in")]
+    #[account(7, writable, name = "vault_out")]
+    #[account(8, writable, name = "escrow")]
+    #[account(9, writable, name = "owner_ata_out")]
+    #[account(10, writable, name = "treasury_ata_in")]
+    #[account(11, name = "token_program_in")]
+    #[account(12, name = "token_program_out")]
+    #[account(13, name = "associated_token_program")]
+    #[account(14, name = "amm_program", desc = "This program, for the Swap it makes")]
+    #[account(15, optional, name = "oracle", desc = "The pool's Pyth price account, required if it has one")]
+    ExecuteDca,
+    #[account(0, writable, signer, name = "owner")]
+    #[account(1, name = "pool")]
+    #[account(2, writable, name = "dca")]
+    #[account(3, name = "mint_in")]
+    #[account(4, writable, name = "escrow")]
+    #[account(5, writable, name = "owner_ata_in")]
+    #[account(6, name = "token_program_in")]
+    CloseDca,
+    #[account(0, writable, signer, name = "payer", desc = "Covers the rent for the larger layout")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "system_program")]
+    #[account(3, name = "mint_a", desc = "Read once for the decimals the pool caches")]
+    #[account(4, name = "mint_b")]
+    MigratePool,
+    #[account(0, writable, signer, name = "admin", desc = "Config admin; only the upgrade authority can create the config")]
+    #[account(1, writable, name = "config", desc = "Config PDA")]
+    #[account(2, name = "system_program")]
+    #[account(3, name = "program_data", desc = "The program's ProgramData account, for its upgrade authority")]
+    SetPoolCreators {
+        pool_creators: Vec<Pubkey>,
+    },
+    #[account(0, writable, signer, name = "user")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint_a")]
+    #[account(3, name = "mint_b")]
+    #[account(4, writable, name = "vault_a")]
+    #[account(5, writable, name = "vault_b")]
+    #[account(6, writable, name = "mint_lp")]
+    #[account(7, writable, name = "user_ata_lp", desc = "bps of its balance is burned")]
+    #[account(8, writable, name = "user_ata_a")]
+    #[account(9, writable, name = "user_ata_b")]
+    #[account(10, name = "token_program")]
+    #[account(11, name = "token_program_a")]
+    #[account(12, name = "token_program_b")]
+    WithdrawPercent {
+        bps: u16,
+        amount_a_min: u64,
+        amount_b_min: u64,
+    },
+    #[account(0, signer, name = "admin")]
+    #[account(1, writable, name = "target", desc = "The pool, or the config PDA")]
+    NominateAdmin {
+        new_admin: Pubkey,
+    },
+    #[account(0, signer, name = "new_admin", desc = "The nominated admin")]
+    #[account(1, writable, name = "target", desc = "The pool, or the config PDA")]
+    AcceptAdmin,
+    #[account(0, writable, signer, name = "user", desc = "Pool creator and fee payer")]
+    #[account(1, writable, name = "pool", desc = "Pool PDA")]
+    #[account(2, name = "mint_a")]
+    #[account(3, name = "mint_b")]
+    #[account(4, writable, name = "vault_a", desc = "Pool ATA for mint_a")]
+    #[account(5, writable, name = "vault_b", desc = "Pool ATA for mint_b")]
+    #[account(6, writable, name = "mint_lp", desc = "LP mint PDA")]
+    #[account(7, writable, name = "pool_ata_lp", desc = "Pool ATA that will hold the locked minimum liquidity")]
+    #[account(8, name = "treasury", desc = "Treasury PDA")]
+    #[account(9, writable, name = "treasury_ata_a")]
+    #[account(10, writable, name = "treasury_ata_b")]
+    #[account(11, name = "token_program", desc = "SPL Token, owner of the LP mint")]
+    #[account(12, name = "token_program_a")]
+    #[account(13, name = "token_program_b")]
+    #[account(14, name = "associated_token_program")]
+    #[account(15, name = "system_program")]
+    #[account(16, writable, name = "registry", desc = "Registry PDA listing every pool")]
+    #[account(17, name = "config", desc = "Config PDA holding the allowed fee tiers, may be uninitialized")]
+    InitializePool {
+        fee_bps: u16,
+        protocol_fee_bps: u16,
+        curve: CurveType,
+    },
+    #[account(0, writable, signer, name = "user")]
+    #[account(1, writable, name = "pool")]
+    #[account(2, name = "mint_a")]
+    #[account(3, name = "mint_b")]
+    #[account(4, writable, name = "vault_a")]
+    #[account(5, writable, name = "vault_b")]
+    #[account(6, writable, name = "mint_lp")]
+    #[account(7, writable, name = "user_ata_lp")]
+    #[account(8, writable, name = "user_ata_a")]
+    #[account(9, writable, name = "user_ata_b")]
+    #[account(10, name = "token_program")]
+    #[account(11, name = "token_program_a")]
+    #[account(12, name = "token_program_b")]
+    #[account(13, name = "system_program")]
+    #[account(14, name = "associated_token_program")]
+    #[account(15, writable, name = "pool_ata_lp", desc = "Receives MINIMUM_LIQUIDITY")]
+    InitialDeposit {
+        amount_a: u64,
+        amount_b: u64,
     },
-
 }
 
 impl AmmInstruction {
@@ -41,6 +460,8 @@ impl AmmInstruction {
                         amount_a: payload.amount_a, 
                         amount_b: payload.amount_b,
                         fee_bps: payload.fee_bps,
+                        protocol_fee_bps: payload.protocol_fee_bps,
+                        curve: payload.curve,
                     }
                 },
                 1 => {
@@ -65,12 +486,220 @@ impl AmmInstruction {
                     }
                 },
                 3 => {
-                    let payload = SwapPayload::try_from_slice(rest)
+                    let mut rest = rest;
+                    let payload = SwapPayload::deserialize(&mut rest)
                         .map_err(|_| ProgramError::InvalidInstructionData)?;
 
+                    // the price-impact bound is a trailing Option<u16> that
+                    // older clients leave off entirely
+                    let max_price_impact_bps = if rest.is_empty() {
+                        None
+                    } else {
+                        Option::<u16>::try_from_slice(rest)
+                            .map_err(|_| ProgramError::InvalidInstructionData)?
+                    };
+
                     Self::Swap { 
                         amount_in: payload.amount_in,
                         min_out: payload.min_out, 
+                        max_price_impact_bps,
+                    }
+                },
+                4 => Self::CollectProtocolFees,
+                5 => {
+                    let payload = SetPausedPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetPaused { 
+                        paused: payload.paused,
+                    }
+                },
+                6 => Self::Sync,
+                7 => Self::Skim,
+                8 => {
+                    let payload = QuotePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::Quote { 
+                        amount_in: payload.amount_in,
+                        a_to_b: payload.a_to_b,
+                    }
+                },
+                9 => {
+                    let payload = SwapPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SwapRoute { 
+                        amount_in: payload.amount_in,
+                        min_out: payload.min_out, 
+                    }
+                },
+                10 => {
+                    let payload = FlashLoanPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::FlashLoan { 
+                        amount: payload.amount,
+                    }
+                },
+                11 => Self::FlashRepay,
+                12 => {
+                    let payload = SwapPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SwapNative { 
+                        amount_in: payload.amount_in,
+                        min_out: payload.min_out, 
+                    }
+                },
+                13 => {
+                    let payload = ZapInPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::ZapIn { 
+                        amount_in: payload.amount_in,
+                        min_lp_out: payload.min_lp_out, 
+                    }
+                },
+                14 => {
+                    let payload = WithdrawSingleSidedPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::WithdrawSingleSided { 
+                        amount_lp_in: payload.amount_lp_in,
+                        want_mint: payload.want_mint,
+                        min_out: payload.min_out, 
+                    }
+                },
+                15 => {
+                    let payload = SetReferralFeePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetReferralFee { 
+                        referral_fee_bps: payload.referral_fee_bps,
+                    }
+                },
+                16 => {
+                    let payload = SetFeeTiersPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetFeeTiers { 
+                        fee_tiers: payload.fee_tiers,
+                    }
+                },
+                17 => {
+                    let payload = ProposeFeeChangePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::ProposeFeeChange { 
+                        protocol_fee_bps: payload.protocol_fee_bps,
+                    }
+                },
+                18 => Self::ExecuteFeeChange,
+                19 => {
+                    let payload = CreateFarmPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::CreateFarm { 
+                        reward_per_second: payload.reward_per_second,
+                    }
+                },
+                20 => {
+                    let payload = StakePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::StakeLp { 
+                        amount: payload.amount,
+                    }
+                },
+                21 => {
+                    let payload = StakePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::UnstakeLp { 
+                        amount: payload.amount,
+                    }
+                },
+                22 => Self::Harvest,
+                23 => {
+                    let payload = SetOraclePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetOracle { 
+                        max_deviation_bps: payload.max_deviation_bps,
+                    }
+                },
+                24 => {
+                    let payload = PlaceLimitOrderPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::PlaceLimitOrder { 
+                        order_id: payload.order_id,
+                        amount_in: payload.amount_in,
+                        min_out: payload.min_out, 
+                    }
+                },
+                25 => Self::FillLimitOrder,
+                26 => Self::CancelLimitOrder,
+                27 => {
+                    let payload = OpenDcaPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::OpenDca { 
+                        dca_id: payload.dca_id,
+                        amount_total: payload.amount_total,
+                        amount_per_swap: payload.amount_per_swap,
+                        min_out_per_swap: payload.min_out_per_swap,
+                        interval: payload.interval,
+                    }
+                },
+                28 => Self::ExecuteDca,
+                29 => Self::CloseDca,
+                30 => Self::MigratePool,
+                31 => {
+                    let payload = SetPoolCreatorsPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetPoolCreators {
+                        pool_creators: payload.pool_creators,
+                    }
+                },
+                32 => {
+                    let payload = WithdrawPercentPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::WithdrawPercent {
+                        bps: payload.bps,
+                        amount_a_min: payload.amount_a_min,
+                        amount_b_min: payload.amount_b_min,
+                    }
+                },
+                33 => {
+                    let payload = NominateAdminPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::NominateAdmin {
+                        new_admin: payload.new_admin,
+                    }
+                },
+                34 => Self::AcceptAdmin,
+                35 => {
+                    let payload = InitializePoolPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::InitializePool {
+                        fee_bps: payload.fee_bps,
+                        protocol_fee_bps: payload.protocol_fee_bps,
+                        curve: payload.curve,
+                    }
+                },
+                36 => {
+                    let payload = InitialDepositPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::InitialDeposit {
+                        amount_a: payload.amount_a,
+                        amount_b: payload.amount_b,
                     }
                 },
 
@@ -80,30 +709,138 @@ impl AmmInstruction {
     }
 }
 
-#[derive(BorshDeserialize)]
-struct CreatePoolPayload {
-    amount_a: u64,
-    amount_b: u64,
-    fee_bps: u16,
+// Payloads are shared with the client builders so both sides stay in sync.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct CreatePoolPayload {
+    pub(crate) amount_a: u64,
+    pub(crate) amount_b: u64,
+    pub(crate) fee_bps: u16,
+    pub(crate) protocol_fee_bps: u16,
+    pub(crate) curve: CurveType,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct ProvideLiquidityPayload {
+    pub(crate) amount_a_desired: u64,
+    pub(crate) amount_b_desired: u64,
+    pub(crate) amount_a_min: u64,
+    pub(crate) amount_b_min: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct WithdrawLiquidityPayload {
+    pub(crate) amount_lp_in: u64,
+    pub(crate) amount_a_min: u64,
+    pub(crate) amount_b_min: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SwapPayload {
+    pub(crate) amount_in: u64,
+    pub(crate) min_out: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetPausedPayload {
+    pub(crate) paused: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct QuotePayload {
+    pub(crate) amount_in: u64,
+    pub(crate) a_to_b: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct FlashLoanPayload {
+    pub(crate) amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct ZapInPayload {
+    pub(crate) amount_in: u64,
+    pub(crate) min_lp_out: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct WithdrawSingleSidedPayload {
+    pub(crate) amount_lp_in: u64,
+    pub(crate) want_mint: Pubkey,
+    pub(crate) min_out: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetReferralFeePayload {
+    pub(crate) referral_fee_bps: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetFeeTiersPayload {
+    pub(crate) fee_tiers: Vec<u16>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct ProposeFeeChangePayload {
+    pub(crate) protocol_fee_bps: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct CreateFarmPayload {
+    pub(crate) reward_per_second: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct StakePayload {
+    pub(crate) amount: u64,
 }
 
-#[derive(BorshDeserialize)]
-struct ProvideLiquidityPayload {
-    amount_a_desired: u64,
-    amount_b_desired: u64,
-    amount_a_min: u64,
-    amount_b_min: u64,
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetOraclePayload {
+    pub(crate) max_deviation_bps: u16,
 }
 
-#[derive(BorshDeserialize)]
-struct WithdrawLiquidityPayload {
-    amount_lp_in: u64,
-    amount_a_min: u64,
-    amount_b_min: u64,
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct PlaceLimitOrderPayload {
+    pub(crate) order_id: u64,
+    pub(crate) amount_in: u64,
+    pub(crate) min_out: u64,
 }
 
-#[derive(BorshDeserialize)]
-struct SwapPayload {
-    amount_in: u64,
-    min_out: u64,
-}
\ No newline at end of file
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct OpenDcaPayload {
+    pub(crate) dca_id: u64,
+    pub(crate) amount_total: u64,
+    pub(crate) amount_per_swap: u64,
+    pub(crate) min_out_per_swap: u64,
+    pub(crate) interval: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetPoolCreatorsPayload {
+    pub(crate) pool_creators: Vec<Pubkey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct WithdrawPercentPayload {
+    pub(crate) bps: u16,
+    pub(crate) amount_a_min: u64,
+    pub(crate) amount_b_min: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct NominateAdminPayload {
+    pub(crate) new_admin: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct InitializePoolPayload {
+    pub(crate) fee_bps: u16,
+    pub(crate) protocol_fee_bps: u16,
+    pub(crate) curve: CurveType,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct InitialDepositPayload {
+    pub(crate) amount_a: u64,
+    pub(crate) amount_b: u64,
+}
@@ -2,4 +2,17 @@ pub mod entrypoint;
 pub mod processor;
 pub mod instruction;
 pub mod state;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod curve;
+pub mod math;
+pub mod oracle;
+pub mod pyth;
+pub mod token;
+pub mod admin;
+pub mod events;
+pub mod farm;
+pub mod limit_order;
+pub mod dca;
+
+#[cfg(feature = "client")]
+pub mod client;
\ No newline at end of file
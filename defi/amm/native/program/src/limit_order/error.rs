@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+use solana_program::program_error::ProgramError;
+
+// Limit order error codes start here, clear of AmmError's and FarmError's.
+pub const LIMIT_ORDER_ERROR_OFFSET: u32 = 2_000;
+
+#[derive(Error, Debug)]
+pub enum LimitOrderError {
+    #[error("Order address does not match PDA derived from the pool, owner and order id")]
+    OrderAddressMismatch,
+    #[error("Escrow must be the order's ATA for the input mint")]
+    EscrowMismatch,
+    #[error("Order amount and minimum output must be greater than zero")]
+    ZeroOrderAmount,
+    #[error("Account does not match the order's owner, pool or mints")]
+    OrderAccountMismatch,
+    #[error("Pool does not yet quote the order's minimum output")]
+    OrderPriceNotReached,
+}
+
+impl From<LimitOrderError> for ProgramError {
+    fn from(error: LimitOrderError) -> Self {
+        ProgramError::Custom(LIMIT_ORDER_ERROR_OFFSET + error as u32)
+    }
+}
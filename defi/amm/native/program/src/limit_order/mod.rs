@@ -0,0 +1,9 @@
+// Limit orders against a pool. The owner escrows `amount_in` of one side
+// in an order PDA together with a lamport bounty; once the pool quotes at
+// least `min_out` for it, anyone can crank FillLimitOrder, which swaps the
+// escrow through the pool into the owner's account and pays the bounty to
+// the cranker.
+
+pub mod error;
+pub mod processor;
+pub mod state;
@@ -0,0 +1,340 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use solana_system_interface::{
+    program::id as system_program_id,
+    instruction::create_account,
+};
+
+use spl_associated_token_account::{
+    id as associated_token_program_id,
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account,
+};
+use spl_token_2022::instruction::{transfer_checked, close_account};
+
+use crate::{
+    curve::swap_output,
+    math::mul_div_ceil,
+    processor::{load_swap_pool, invoke_swap_signed, close_program_account},
+    token::{check_token_program, mint_decimals, token_amount},
+    limit_order::{
+        state::{LimitOrder, FILL_BOUNTY_LAMPORTS},
+        error::LimitOrderError,
+    },
+};
+
+pub fn process_place_limit_order(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    order_id: u64,
+    amount_in: u64,
+    min_out: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let order = next_account_info(accounts_iter)?;
+    let mint_in = next_account_info(accounts_iter)?;
+    let mint_out = next_account_info(accounts_iter)?;
+    let owner_ata_in = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount_in == 0 || min_out == 0 {
+        return Err(LimitOrderError::ZeroOrderAmount.into());
+    }
+
+    load_swap_pool(program_id, pool, mint_in, mint_out)?;
+
+    let (order_pda, order_bump) = Pubkey::find_program_address(
+        &[b"order", pool.key.as_ref(), owner.key.as_ref(), &order_id.to_le_bytes()], program_id);
+
+    if *order.key != order_pda {
+        return Err(LimitOrderError::OrderAddressMismatch.into());
+    }
+
+    if !order.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    check_token_program(token_program_in, mint_in)?;
+
+    if *escrow.key != get_associated_token_address_with_program_id(order.key, mint_in.key, token_program_in.key) {
+        return Err(LimitOrderError::EscrowMismatch.into());
+    }
+
+    if *associated_token_program.key != associated_token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // create order account, holding the fill bounty on top of its rent
+    invoke_signed(
+        &create_account(
+            owner.key,
+            order.key,
+            Rent::get()?.minimum_balance(LimitOrder::SPACE) + FILL_BOUNTY_LAMPORTS,
+            LimitOrder::SPACE as u64,
+            program_id,
+        ),
+        &[owner.clone(), order.clone()],
+        &[&[b"order", pool.key.as_ref(), owner.key.as_ref(), &order_id.to_le_bytes(), &[order_bump]]],
+    )?;
+
+    // create escrow ( order's ata for mint_in )
+    invoke(
+        &create_associated_token_account(
+            owner.key,
+            order.key,
+            mint_in.key,
+            token_program_in.key,
+        ),
+        &[owner.clone(), escrow.clone(), order.clone(), mint_in.clone(), system_program.clone(), token_program_in.clone()],
+    )?;
+
+    // transfer amount_in of mint_in from owner_ata_in to escrow
+    invoke(
+        &transfer_checked(
+            token_program_in.key,
+            owner_ata_in.key,
+            mint_in.key,
+            escrow.key,
+            owner.key,
+            &[],
+            amount_in,
+            mint_decimals(mint_in)?,
+        )?,
+        &[owner_ata_in.clone(), mint_in.clone(), escrow.clone(), owner.clone()],
+    )?;
+
+    let order_data = LimitOrder {
+        discriminator: LimitOrder::DISCRIMINATOR,
+        pool: *pool.key,
+        owner: *owner.key,
+        mint_in: *mint_in.key,
+        mint_out: *mint_out.key,
+        order_id,
+        // what reached the escrow after any transfer fee on mint_in
+        amount_in: token_amount(escrow)?,
+        min_out,
+        bump: order_bump,
+    };
+
+    order_data.serialize(&mut &mut order.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Permissionless. Any accounts after the fixed ones (the pool's oracle, if
+// it has one) are passed through to the Swap.
+pub fn process_fill_limit_order(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let cranker = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let order = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+    let mint_in = next_account_info(accounts_iter)?;
+    let mint_out = next_account_info(accounts_iter)?;
+    let vault_in = next_account_info(accounts_iter)?;
+    let vault_out = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let owner_ata_out = next_account_info(accounts_iter)?;
+    let treasury_ata_in = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let token_program_out = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    let amm_program = next_account_info(accounts_iter)?;
+    let swap_extra_accounts = accounts_iter.as_slice();
+
+    if !cranker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let order_data = load_order(program_id, order, pool, owner)?;
+
+    if *mint_in.key != order_data.mint_in || *mint_out.key != order_data.mint_out {
+        return Err(LimitOrderError::OrderAccountMismatch.into());
+    }
+
+    check_token_program(token_program_in, mint_in)?;
+    check_token_program(token_program_out, mint_out)?;
+
+    if *escrow.key != get_associated_token_address_with_program_id(order.key, mint_in.key, token_program_in.key) {
+        return Err(LimitOrderError::EscrowMismatch.into());
+    }
+
+    // the output only ever goes to the owner
+    if *owner_ata_out.key != get_associated_token_address_with_program_id(owner.key, mint_out.key, token_program_out.key) {
+        return Err(LimitOrderError::OrderAccountMismatch.into());
+    }
+
+    let pool_data = load_swap_pool(program_id, pool, mint_in, mint_out)?;
+
+    let a_to_b = *mint_in.key == pool_data.mint_a;
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (pool_data.reserve_a, pool_data.reserve_b)
+    } else {
+        (pool_data.reserve_b, pool_data.reserve_a)
+    };
+
+    // the whole escrow is swapped, so that tokens sent to it after the order
+    // was placed can't keep it from closing; min_out scales with it to hold
+    // the order's price
+    let amount_in = token_amount(escrow)?;
+    let min_out = mul_div_ceil(order_data.min_out as u128, amount_in as u128, order_data.amount_in as u128)
+        .and_then(|min_out| u64::try_from(min_out).ok())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // fail early with a clear error; Swap enforces min_out on what
+    // actually arrives either way
+    let quote = swap_output(pool_data.curve, reserve_in, reserve_out, amount_in, pool_data.fee_bps, a_to_b)?;
+
+    if quote < min_out {
+        return Err(LimitOrderError::OrderPriceNotReached.into());
+    }
+
+    let order_seeds: &[&[u8]] = &[
+        b"order",
+        order_data.pool.as_ref(),
+        order_data.owner.as_ref(),
+        &order_data.order_id.to_le_bytes(),
+        &[order_data.bump],
+    ];
+
+    // swap the escrow through the pool with the order PDA as the user
+    let mut swap_accounts = vec![
+        order.clone(), pool.clone(), mint_in.clone(), mint_out.clone(), vault_in.clone(), vault_out.clone(),
+        escrow.clone(), owner_ata_out.clone(), treasury_ata_in.clone(),
+        token_program_in.clone(), token_program_out.clone(), associated_token_program.clone(),
+    ];
+    swap_accounts.extend(swap_extra_accounts.iter().cloned());
+
+    invoke_swap_signed(program_id, amm_program, &swap_accounts, amount_in, min_out, order_seeds)?;
+
+    // the escrow is empty now, its rent goes back to the owner
+    invoke_signed(
+        &close_account(
+            token_program_in.key,
+            escrow.key,
+            owner.key,
+            order.key,
+            &[],
+        )?,
+        &[escrow.clone(), owner.clone(), order.clone()],
+        &[order_seeds],
+    )?;
+
+    close_program_account(order, owner, Some((cranker, FILL_BOUNTY_LAMPORTS)))
+}
+
+pub fn process_cancel_limit_order(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let order = next_account_info(accounts_iter)?;
+    let mint_in = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let owner_ata_in = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let order_data = load_order(program_id, order, pool, owner)?;
+
+    if *mint_in.key != order_data.mint_in {
+        return Err(LimitOrderError::OrderAccountMismatch.into());
+    }
+
+    check_token_program(token_program_in, mint_in)?;
+
+    if *escrow.key != get_associated_token_address_with_program_id(order.key, mint_in.key, token_program_in.key) {
+        return Err(LimitOrderError::EscrowMismatch.into());
+    }
+
+    let order_seeds: &[&[u8]] = &[
+        b"order",
+        order_data.pool.as_ref(),
+        order_data.owner.as_ref(),
+        &order_data.order_id.to_le_bytes(),
+        &[order_data.bump],
+    ];
+
+    // transfer the whole escrow back to owner_ata_in
+    invoke_signed(
+        &transfer_checked(
+            token_program_in.key,
+            escrow.key,
+            mint_in.key,
+            owner_ata_in.key,
+            order.key,
+            &[],
+            token_amount(escrow)?,
+            mint_decimals(mint_in)?,
+        )?,
+        &[escrow.clone(), mint_in.clone(), owner_ata_in.clone(), order.clone()],
+        &[order_seeds],
+    )?;
+
+    invoke_signed(
+        &close_account(
+            token_program_in.key,
+            escrow.key,
+            owner.key,
+            order.key,
+            &[],
+        )?,
+        &[escrow.clone(), owner.clone(), order.clone()],
+        &[order_seeds],
+    )?;
+
+    // a cancelled order keeps its bounty
+    close_program_account(order, owner, None)
+}
+
+fn load_order(
+    program_id: &Pubkey,
+    order: &AccountInfo,
+    pool: &AccountInfo,
+    owner: &AccountInfo,
+) -> Result<LimitOrder, ProgramError> {
+    if order.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let order_data = LimitOrder::load(&order.data.borrow())?;
+
+    if order_data.pool != *pool.key || order_data.owner != *owner.key {
+        return Err(LimitOrderError::OrderAccountMismatch.into());
+    }
+
+    Ok(order_data)
+}
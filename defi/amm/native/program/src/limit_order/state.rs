@@ -0,0 +1,51 @@
+use solana_program::{
+    borsh1::try_from_slice_unchecked,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshSerialize, BorshDeserialize};
+use shank::ShankAccount;
+
+// Paid to whoever fills an order, on top of the order account's rent,
+// which goes back to the owner.
+pub const FILL_BOUNTY_LAMPORTS: u64 = 100_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+pub struct LimitOrder {
+    pub discriminator: [u8; 8],
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub order_id: u64,
+    pub amount_in: u64,
+    pub min_out: u64,
+    pub bump: u8,
+}
+
+impl LimitOrder {
+    pub const DISCRIMINATOR: [u8; 8] = *b"amm_ordr";
+
+    pub const SPACE: usize = 
+        8        // discriminator
+        + 32     // pool pubkey
+        + 32     // owner pubkey
+        + 32     // mint_in pubkey
+        + 32     // mint_out pubkey
+        + 8      // order_id
+        + 8      // amount_in
+        + 8      // min_out
+        + 1;     // bump
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let order = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if order.discriminator != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(order)
+    }
+}
@@ -0,0 +1,221 @@
+#![allow(clippy::manual_div_ceil, clippy::assign_op_pattern)]
+
+use integer_sqrt::IntegerSquareRoot;
+use uint::construct_uint;
+
+construct_uint! {
+    pub struct U256(4);
+}
+
+// Fixed-point helpers with 18 decimals: ONE is 1.0.
+
+pub const ONE: u128 = 1_000_000_000_000_000_000;
+
+const LN_2: i128 = 693_147_180_559_945_309;
+
+// ln(x) for fixed-point x > 0.
+pub fn ln(x: u128) -> Option<i128> {
+    if x == 0 {
+        return None;
+    }
+
+    // x = m * 2^k with m in [1, 2)
+    let mut m = x;
+    let mut k: i128 = 0;
+
+    while m >= 2 * ONE {
+        m /= 2;
+        k += 1;
+    }
+
+    while m < ONE {
+        m *= 2;
+        k -= 1;
+    }
+
+    // ln(m) = 2 * atanh(z) = 2 * (z + z^3/3 + z^5/5 + ...) with z = (m - 1) / (m + 1) < 1/3
+    let z = ((m - ONE) * ONE / (m + ONE)) as i128;
+    let z_squared = z * z / ONE as i128;
+
+    let mut term = z;
+    let mut sum = 0;
+    let mut n = 1;
+
+    while term != 0 {
+        sum += term / n;
+        term = term * z_squared / ONE as i128;
+        n += 2;
+    }
+
+    Some(k * LN_2 + 2 * sum)
+}
+
+// e^x for fixed-point x. None if the result doesn't fit in a u128.
+pub fn exp(x: i128) -> Option<u128> {
+    // x = k * ln(2) + r with r in [0, ln(2))
+    let k = x.div_euclid(LN_2);
+    let r = x.rem_euclid(LN_2) as u128;
+
+    let mut term = ONE;
+    let mut sum = ONE;
+    let mut n = 1;
+
+    while term != 0 {
+        term = term * r / ONE / n;
+        sum += term;
+        n += 1;
+    }
+
+    if k >= 0 {
+        let k = u32::try_from(k).ok()?;
+
+        if k >= sum.leading_zeros() {
+            return None;
+        }
+
+        Some(sum << k)
+    } else {
+        Some(sum.checked_shr(u32::try_from(-k).ok()?).unwrap_or(0))
+    }
+}
+
+// base^exponent for fixed-point base > 0 and exponent.
+pub fn pow(base: u128, exponent: u128) -> Option<u128> {
+    let exponent = i128::try_from(exponent).ok()?;
+
+    exp(ln(base)?.checked_mul(exponent)? / ONE as i128)
+}
+
+// How much of `amount_in` to swap so that the rest and the swap output are in
+// the pool's post-swap ratio, i.e. can be deposited without leftovers. The LP
+// fee is kept out of the reserves, so the input reserve R only grows by
+// s (1-f); for a constant-product pool this solves
+// s^2 (1-f)^2 + s R (2-f) - a R = 0, computed with g = 10_000 - fee_bps as
+// s = (sqrt(b^2 + 4 * 10_000^2 g^2 R a) - b) / 2g^2, b = 10_000 R (10_000 + g).
+pub fn zap_swap_amount(reserve_in: u64, amount_in: u64, fee_bps: u16) -> Option<u64> {
+    let g = U256::from(10_000u64.checked_sub(fee_bps as u64)?);
+
+    if g.is_zero() {
+        return None;
+    }
+
+    let reserve_in = U256::from(reserve_in);
+    let b = reserve_in.checked_mul(U256::from(10_000u64) * (U256::from(10_000u64) + g))?;
+
+    let discriminant = b.checked_mul(b)?
+        .checked_add(U256::from(400_000_000u64) * g * g * reserve_in * U256::from(amount_in))?;
+
+    let swap_amount = (discriminant.integer_sqrt() - b) / (U256::from(2u64) * g * g);
+
+    // rounding can't push it past amount_in, but stay safe
+    Some(swap_amount.min(U256::from(amount_in)).as_u64())
+}
+
+// Rounding policy: whatever the pool pays out or credits rounds down, and
+// whatever a user owes it rounds up, so no sequence of operations can pull
+// value out of the pool through rounding.
+
+// a * b / c rounded down, with the product taken in U256. None if c is zero
+// or the result overflows a u128.
+pub fn mul_div_floor(a: u128, b: u128, c: u128) -> Option<u128> {
+    if c == 0 {
+        return None;
+    }
+
+    let quotient = U256::from(a) * U256::from(b) / U256::from(c);
+
+    u128::try_from(quotient).ok()
+}
+
+// a * b / c rounded up, otherwise like mul_div_floor.
+pub fn mul_div_ceil(a: u128, b: u128, c: u128) -> Option<u128> {
+    if c == 0 {
+        return None;
+    }
+
+    let product = U256::from(a) * U256::from(b);
+    let c = U256::from(c);
+
+    let quotient = if (product % c).is_zero() { product / c } else { product / c + 1 };
+
+    u128::try_from(quotient).ok()
+}
+
+// `bps` basis points of `amount`, rounded down. None if it overflows a u64,
+// which only happens for bps above 10_000.
+pub fn bps_floor(amount: u64, bps: u16) -> Option<u64> {
+    u64::try_from(mul_div_floor(amount as u128, bps as u128, 10_000)?).ok()
+}
+
+// `bps` basis points of `amount`, rounded up.
+pub fn bps_ceil(amount: u64, bps: u16) -> Option<u64> {
+    u64::try_from(mul_div_ceil(amount as u128, bps as u128, 10_000)?).ok()
+}
+
+// Largest integer whose square is at most x.
+pub fn sqrt(x: u128) -> u128 {
+    x.integer_sqrt()
+}
+
+// Q64.64 fixed point: a u128 whose low 64 bits are the fraction, as used by
+// the TWAP accumulators.
+pub const Q64: u128 = 1 << 64;
+
+// num / den as Q64.64, rounded down. None if den is zero.
+pub fn q64_ratio(num: u64, den: u64) -> Option<u128> {
+    ((num as u128) << 64).checked_div(den as u128)
+}
+
+// amount * x for Q64.64 x, rounded down. None if it overflows a u64.
+pub fn q64_mul_floor(x: u128, amount: u64) -> Option<u64> {
+    u64::try_from(mul_div_floor(x, amount as u128, Q64)?).ok()
+}
+
+// amount * x for Q64.64 x, rounded up. None if it overflows a u64.
+pub fn q64_mul_ceil(x: u128, amount: u64) -> Option<u64> {
+    u64::try_from(mul_div_ceil(x, amount as u128, Q64)?).ok()
+}
+
+// Portion of the desired amounts a deposit takes at the pool's current ratio,
+// capped by whichever side runs out first. The side matched to the other
+// rounds up, since it's owed to the pool.
+pub fn deposit_amounts(reserve_a: u64, reserve_b: u64, desired_a: u64, desired_b: u64) -> Option<(u64, u64)> {
+    let b_needed = mul_div_ceil(desired_a as u128, reserve_b as u128, reserve_a as u128)?;
+
+    let (take_a, take_b) = if b_needed <= desired_b as u128 {
+        (desired_a as u128, b_needed)
+    } else {
+        (mul_div_ceil(desired_b as u128, reserve_a as u128, reserve_b as u128)?, desired_b as u128)
+    };
+
+    Some((u64::try_from(take_a).ok()?, u64::try_from(take_b).ok()?))
+}
+
+// LP minted for amounts that reached the vaults: the smaller of the two
+// shares, rounded down.
+pub fn lp_for_deposit(reserve_a: u64, reserve_b: u64, total_lp: u64, amount_a: u64, amount_b: u64) -> Option<u64> {
+    let lp_from_a = mul_div_floor(amount_a as u128, total_lp as u128, reserve_a as u128)?;
+    let lp_from_b = mul_div_floor(amount_b as u128, total_lp as u128, reserve_b as u128)?;
+
+    u64::try_from(lp_from_a.min(lp_from_b)).ok()
+}
+
+// Share of each reserve paid out for burning `amount_lp`, rounded down.
+pub fn withdraw_amounts(reserve_a: u64, reserve_b: u64, total_lp: u64, amount_lp: u64) -> Option<(u64, u64)> {
+    let a_out = mul_div_floor(amount_lp as u128, reserve_a as u128, total_lp as u128)?;
+    let b_out = mul_div_floor(amount_lp as u128, reserve_b as u128, total_lp as u128)?;
+
+    Some((u64::try_from(a_out).ok()?, u64::try_from(b_out).ok()?))
+}
+
+// Splits what a swap's input vault received into (to the reserve, LP fee).
+// The curve priced what arrived less the swap fee, rounded down, so that
+// much goes to the reserve and the rest of the vault's share is the LPs' fee.
+// If the treasury or referrer cut leaves less than that in the vault, all of
+// it goes to the reserve.
+pub fn split_lp_fee(vault_received: u64, amount_in_received: u64, fee_bps: u16) -> Option<(u64, u64)> {
+    let priced = amount_in_received.checked_sub(bps_floor(amount_in_received, fee_bps)?)?;
+    let to_reserve = vault_received.min(priced);
+
+    Some((to_reserve, vault_received - to_reserve))
+}
@@ -0,0 +1,33 @@
+// Uniswap v2 style price accumulators. Each cumulative grows by the spot price
+// (Q64.64) times the seconds it was in effect, so the time-weighted average
+// price between two observations is their difference over the elapsed time.
+
+use crate::{math::q64_ratio, state::LiquidityPool};
+
+// Must run before the reserves change, so the elapsed time is credited to the
+// price that was actually in effect. Overflow is intended and cancels out in
+// `twap`.
+pub fn update_price_cumulatives(pool_data: &mut LiquidityPool, now: i64) {
+    let elapsed = now.saturating_sub(pool_data.last_update_ts);
+
+    // price of token A in token B and vice versa, None while either reserve is empty
+    let price_a = q64_ratio(pool_data.reserve_b, pool_data.reserve_a);
+    let price_b = q64_ratio(pool_data.reserve_a, pool_data.reserve_b);
+
+    if let (Some(price_a), Some(price_b), true) = (price_a, price_b, elapsed > 0) {
+        pool_data.price_cumulative_a = pool_data.price_cumulative_a
+            .wrapping_add(price_a.wrapping_mul(elapsed as u128));
+        pool_data.price_cumulative_b = pool_data.price_cumulative_b
+            .wrapping_add(price_b.wrapping_mul(elapsed as u128));
+    }
+
+    pool_data.last_update_ts = now;
+}
+
+// Average Q64.64 price between two observations of the same cumulative.
+// None if no time passed between them.
+pub fn twap(cumulative_start: u128, cumulative_end: u128, ts_start: i64, ts_end: i64) -> Option<u128> {
+    let elapsed = ts_end.checked_sub(ts_start).filter(|elapsed| *elapsed > 0)?;
+
+    Some(cumulative_end.wrapping_sub(cumulative_start) / elapsed as u128)
+}
@@ -2,39 +2,63 @@ use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo}, 
     entrypoint::ProgramResult, 
+    instruction::{AccountMeta, Instruction},
     program_error::ProgramError,
-    program::{invoke, invoke_signed}, 
+    program_option::COption,
+    program::{invoke, invoke_signed, set_return_data}, 
     program_pack::Pack, 
     pubkey::Pubkey, 
-    sysvar::{rent::Rent, Sysvar},
-    borsh1::try_from_slice_unchecked,
+    sysvar::{
+        rent::Rent, 
+        clock::Clock, 
+        instructions::{self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked}, 
+        Sysvar,
+    },
     msg,
 };
 
 use solana_system_interface::{
     program::id as system_program_id,
-    instruction::create_account,
+    instruction::{create_account, transfer, allocate, assign},
 };
 
 use spl_associated_token_account::{
     id as associated_token_program_id,
     get_associated_token_address,
+    get_associated_token_address_with_program_id,
     instruction::{create_associated_token_account, create_associated_token_account_idempotent},
 };
 use spl_token::{
     id as token_program_id,
-    instruction::{transfer_checked, initialize_mint2, mint_to, burn},
+    instruction::{initialize_mint2, mint_to, burn, sync_native, close_account},
+    native_mint,
     state::Mint,
 };
-
-use integer_sqrt::IntegerSquareRoot;
+use spl_token_2022::instruction::transfer_checked;
 
 use crate::{
-    instruction::AmmInstruction,
-    state::LiquidityPool,
+    instruction::{AmmInstruction, SwapPayload},
+    state::{LiquidityPool, LiquidityPoolV1, LiquidityPoolV2, LiquidityPoolV3, LiquidityPoolV4, CurveType, PoolRegistry, RegistryEntry, ProtocolConfig},
     error::AmmError,
+    curve::{swap_output, initial_lp_amount, price_impact_bps, MAX_AMP, MIN_WEIGHT_BPS},
+    oracle::update_price_cumulatives,
+    pyth::{PythPrice, deviation_bps},
+    events::{AmmEvent, SwapExecuted, LiquidityAdded, LiquidityRemoved},
+    math::{zap_swap_amount, deposit_amounts, lp_for_deposit, withdraw_amounts, split_lp_fee, bps_floor, bps_ceil},
+    token::{check_token_program, mint_decimals, token_amount, token_account_state, amount_before_transfer_fee},
+    admin::{check_admin_signature, upgrade_authority},
+    farm,
+    limit_order,
+    dca,
 };
 
+// LP tokens minted to the pool's own LP account at creation and never
+// redeemable, so the first depositor can't inflate the share price.
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+// How long a proposed protocol fee waits before ExecuteFeeChange can apply it.
+pub const FEE_CHANGE_DELAY: i64 = 2 * 24 * 60 * 60;
+
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -43,8 +67,8 @@ pub fn process_instruction(
     let instruction = AmmInstruction::unpack(instruction_data)?;
 
     match instruction {
-        AmmInstruction::CreatePool { amount_a, amount_b, fee_bps } => {
-            process_create_pool(program_id, accounts, amount_a, amount_b, fee_bps)
+        AmmInstruction::CreatePool { amount_a, amount_b, fee_bps, protocol_fee_bps, curve } => {
+            process_create_pool(program_id, accounts, amount_a, amount_b, fee_bps, protocol_fee_bps, curve)
         },
         AmmInstruction::ProvideLiquidity { amount_a_desired, amount_b_desired, amount_a_min, amount_b_min } => {
             process_provide_liquidity(program_id, accounts, amount_a_desired, amount_b_desired, amount_a_min, amount_b_min)
@@ -52,18 +76,122 @@ pub fn process_instruction(
         AmmInstruction::WithdrawLiquidity { amount_lp_in, amount_a_min, amount_b_min } => {
             process_withdraw_liquidity(program_id, accounts, amount_lp_in, amount_a_min, amount_b_min)
         },
-        AmmInstruction::Swap { amount_in, min_out } => {
-            process_swap(program_id, accounts, amount_in, min_out)
+        AmmInstruction::Swap { amount_in, min_out, max_price_impact_bps } => {
+            process_swap(program_id, accounts, amount_in, min_out, max_price_impact_bps)
+        },
+        AmmInstruction::CollectProtocolFees => {
+            process_collect_protocol_fees(program_id, accounts)
+        },
+        AmmInstruction::SetPaused { paused } => {
+            process_set_paused(program_id, accounts, paused)
+        },
+        AmmInstruction::Sync => {
+            process_sync(program_id, accounts)
+        },
+        AmmInstruction::Skim => {
+            process_skim(program_id, accounts)
+        },
+        AmmInstruction::Quote { amount_in, a_to_b } => {
+            process_quote(program_id, accounts, amount_in, a_to_b)
+        },
+        AmmInstruction::SwapRoute { amount_in, min_out } => {
+            process_swap_route(program_id, accounts, amount_in, min_out)
+        },
+        AmmInstruction::FlashLoan { amount } => {
+            process_flash_loan(program_id, accounts, amount)
+        },
+        AmmInstruction::FlashRepay => {
+            process_flash_repay(program_id, accounts)
+        },
+        AmmInstruction::SwapNative { amount_in, min_out } => {
+            process_swap_native(program_id, accounts, amount_in, min_out)
+        },
+        AmmInstruction::ZapIn { amount_in, min_lp_out } => {
+            process_zap_in(program_id, accounts, amount_in, min_lp_out)
+        },
+        AmmInstruction::WithdrawSingleSided { amount_lp_in, want_mint, min_out } => {
+            process_withdraw_single_sided(program_id, accounts, amount_lp_in, want_mint, min_out)
+        },
+        AmmInstruction::SetReferralFee { referral_fee_bps } => {
+            process_set_referral_fee(program_id, accounts, referral_fee_bps)
+        },
+        AmmInstruction::SetFeeTiers { fee_tiers } => {
+            process_set_fee_tiers(program_id, accounts, fee_tiers)
+        },
+        AmmInstruction::ProposeFeeChange { protocol_fee_bps } => {
+            process_propose_fee_change(program_id, accounts, protocol_fee_bps)
+        },
+        AmmInstruction::ExecuteFeeChange => {
+            process_execute_fee_change(program_id, accounts)
+        },
+        AmmInstruction::CreateFarm { reward_per_second } => {
+            farm::processor::process_create_farm(program_id, accounts, reward_per_second)
+        },
+        AmmInstruction::StakeLp { amount } => {
+            farm::processor::process_stake_lp(program_id, accounts, amount)
+        },
+        AmmInstruction::UnstakeLp { amount } => {
+            farm::processor::process_unstake_lp(program_id, accounts, amount)
+        },
+        AmmInstruction::Harvest => {
+            farm::processor::process_harvest(program_id, accounts)
+        },
+        AmmInstruction::SetOracle { max_deviation_bps } => {
+            process_set_oracle(program_id, accounts, max_deviation_bps)
+        },
+        AmmInstruction::PlaceLimitOrder { order_id, amount_in, min_out } => {
+            limit_order::processor::process_place_limit_order(program_id, accounts, order_id, amount_in, min_out)
+        },
+        AmmInstruction::FillLimitOrder => {
+            limit_order::processor::process_fill_limit_order(program_id, accounts)
+        },
+        AmmInstruction::CancelLimitOrder => {
+            limit_order::processor::process_cancel_limit_order(program_id, accounts)
+        },
+        AmmInstruction::OpenDca { dca_id, amount_total, amount_per_swap, min_out_per_swap, interval } => {
+            dca::processor::process_open_dca(
+                program_id, accounts, dca_id, amount_total, amount_per_swap, min_out_per_swap, interval,
+            )
+        },
+        AmmInstruction::ExecuteDca => {
+            dca::processor::process_execute_dca(program_id, accounts)
+        },
+        AmmInstruction::CloseDca => {
+            dca::processor::process_close_dca(program_id, accounts)
+        },
+        AmmInstruction::MigratePool => {
+            process_migrate_pool(program_id, accounts)
+        },
+        AmmInstruction::SetPoolCreators { pool_creators } => {
+            process_set_pool_creators(program_id, accounts, pool_creators)
+        },
+        AmmInstruction::WithdrawPercent { bps, amount_a_min, amount_b_min } => {
+            process_withdraw_percent(program_id, accounts, bps, amount_a_min, amount_b_min)
+        },
+        AmmInstruction::NominateAdmin { new_admin } => {
+            process_nominate_admin(program_id, accounts, new_admin)
+        },
+        AmmInstruction::AcceptAdmin => {
+            process_accept_admin(program_id, accounts)
+        },
+        AmmInstruction::InitializePool { fee_bps, protocol_fee_bps, curve } => {
+            process_initialize_pool(program_id, accounts, fee_bps, protocol_fee_bps, curve)
+        },
+        AmmInstruction::InitialDeposit { amount_a, amount_b } => {
+            process_initial_deposit(program_id, accounts, amount_a, amount_b)
         },
     }
 }
 
+// InitializePool followed by InitialDeposit, in one instruction.
 pub fn process_create_pool(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     amount_a: u64,
     amount_b: u64,
     fee_bps: u16,
+    protocol_fee_bps: u16,
+    curve: CurveType,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -75,11 +203,75 @@ pub fn process_create_pool(
     let vault_b = next_account_info(accounts_iter)?;
     let mint_lp = next_account_info(accounts_iter)?;
     let user_ata_lp = next_account_info(accounts_iter)?;
+    let pool_ata_lp = next_account_info(accounts_iter)?;
     let user_ata_a = next_account_info(accounts_iter)?;
     let user_ata_b = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let treasury_ata_a = next_account_info(accounts_iter)?;
+    let treasury_ata_b = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let registry = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+
+    process_initialize_pool(
+        program_id, 
+        &[
+            user.clone(), pool.clone(), mint_a.clone(), mint_b.clone(), vault_a.clone(), vault_b.clone(), 
+            mint_lp.clone(), pool_ata_lp.clone(), treasury.clone(), treasury_ata_a.clone(), treasury_ata_b.clone(), 
+            token_program.clone(), token_program_a.clone(), token_program_b.clone(), 
+            associated_token_program.clone(), system_program.clone(), registry.clone(), config.clone(),
+        ], 
+        fee_bps, 
+        protocol_fee_bps, 
+        curve,
+    )?;
+
+    process_initial_deposit(
+        program_id, 
+        &[
+            user.clone(), pool.clone(), mint_a.clone(), mint_b.clone(), vault_a.clone(), vault_b.clone(), 
+            mint_lp.clone(), user_ata_lp.clone(), user_ata_a.clone(), user_ata_b.clone(), 
+            token_program.clone(), token_program_a.clone(), token_program_b.clone(), 
+            system_program.clone(), associated_token_program.clone(), pool_ata_lp.clone(),
+        ], 
+        amount_a, 
+        amount_b,
+    )
+}
+
+// Creates the pool, its vaults and LP mint, with nothing in them yet;
+// InitialDeposit funds it and sets the starting price.
+pub fn process_initialize_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+    curve: CurveType,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let mint_lp = next_account_info(accounts_iter)?;
+    let pool_ata_lp = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let treasury_ata_a = next_account_info(accounts_iter)?;
+    let treasury_ata_b = next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
     let associated_token_program = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
+    let registry = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
 
     if !user.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
@@ -95,6 +287,8 @@ pub fn process_create_pool(
         (mint_b.key.clone(), mint_a.key.clone())
     };
 
+    // The curve isn't part of the seeds, so a pair has one pool per fee tier
+    // whatever its curve; creating another with a different curve fails below.
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
         &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &fee_bps.to_le_bytes()], 
         program_id,
@@ -104,11 +298,15 @@ pub fn process_create_pool(
         return Err(AmmError::PoolAddressMismatch.into());
     }
 
-    if *vault_a.key != get_associated_token_address(pool.key, mint_a.key) {
+    if !pool.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    if *vault_a.key != get_associated_token_address_with_program_id(pool.key, mint_a.key, token_program_a.key) {
         return Err(AmmError::VaultAddressMismatch.into());
     }
 
-    if *vault_b.key != get_associated_token_address(pool.key, mint_b.key) {
+    if *vault_b.key != get_associated_token_address_with_program_id(pool.key, mint_b.key, token_program_b.key) {
         return Err(AmmError::VaultAddressMismatch.into());
     }
 
@@ -119,10 +317,33 @@ pub fn process_create_pool(
         return Err(AmmError::LpMintAddressMismatch.into());
     }
 
+    if *pool_ata_lp.key != get_associated_token_address(pool.key, mint_lp.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    let (treasury_pda, _treasury_bump) = Pubkey::find_program_address(
+        &[b"treasury", pool.key.as_ref()], program_id);
+
+    if *treasury.key != treasury_pda {
+        return Err(AmmError::TreasuryAddressMismatch.into());
+    }
+
+    if *treasury_ata_a.key != get_associated_token_address_with_program_id(treasury.key, mint_a.key, token_program_a.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    if *treasury_ata_b.key != get_associated_token_address_with_program_id(treasury.key, mint_b.key, token_program_b.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    // the LP mint is always a plain SPL Token mint
     if *token_program.key != token_program_id() {
         return Err(ProgramError::IncorrectProgramId);
     }
 
+    check_token_program(token_program_a, mint_a)?;
+    check_token_program(token_program_b, mint_b)?;
+
     if *associated_token_program.key != associated_token_program_id() {
         return Err(ProgramError::IncorrectProgramId);
     }
@@ -131,14 +352,55 @@ pub fn process_create_pool(
         return Err(ProgramError::IncorrectProgramId);
     }
 
-    if amount_a == 0 || amount_b == 0 {
-        return Err(AmmError::ZeroLiquidityAmount.into());
+    let (registry_pda, registry_bump) = Pubkey::find_program_address(&[b"registry"], program_id);
+
+    if *registry.key != registry_pda {
+        return Err(AmmError::RegistryAddressMismatch.into());
+    }
+
+    let (config_pda, _config_bump) = Pubkey::find_program_address(&[b"config"], program_id);
+
+    if *config.key != config_pda {
+        return Err(AmmError::ConfigAddressMismatch.into());
+    }
+
+    let fee_tiers = if config.data_is_empty() {
+        ProtocolConfig::DEFAULT_FEE_TIERS.to_vec()
+    } else {
+        let config_data = ProtocolConfig::load(&config.data.borrow())?;
+
+        if !config_data.can_create_pool(user.key) {
+            return Err(AmmError::PoolCreatorNotAllowed.into());
+        }
+
+        config_data.fee_tiers
+    };
+
+    if !fee_tiers.contains(&fee_bps) {
+        return Err(AmmError::InvalidFeeTier.into());
     }
 
-    if fee_bps > 10_000 {
+    if protocol_fee_bps > 10_000 {
         return Err(AmmError::FeeTooHigh.into());
     }
 
+    match curve {
+        CurveType::Stable { amp } => {
+            if amp == 0 || amp > MAX_AMP {
+                return Err(AmmError::InvalidAmplification.into());
+            }
+        },
+        CurveType::Weighted { weight_a_bps, weight_b_bps } => {
+            if weight_a_bps < MIN_WEIGHT_BPS 
+                || weight_b_bps < MIN_WEIGHT_BPS 
+                || weight_a_bps as u32 + weight_b_bps as u32 != 10_000 
+            {
+                return Err(AmmError::InvalidWeights.into());
+            }
+        },
+        CurveType::ConstantProduct | CurveType::ConstantSum => {},
+    }
+
     // create pool account
     let rent = Rent::get()?;
 
@@ -170,9 +432,9 @@ pub fn process_create_pool(
             user.key, 
             pool.key, 
             mint_a.key, 
-            token_program.key,
+            token_program_a.key,
         ), 
-        &[user.clone(), vault_a.clone(), pool.clone(), mint_a.clone()],
+        &[user.clone(), vault_a.clone(), pool.clone(), mint_a.clone(), system_program.clone(), token_program_a.clone()],
     )?;
 
     // create vault_v ( pool's ata for mint_b )
@@ -181,43 +443,30 @@ pub fn process_create_pool(
             user.key, 
             pool.key, 
             mint_b.key, 
-            token_program.key,
+            token_program_b.key,
         ), 
-        &[user.clone(), vault_b.clone(), pool.clone(), mint_b.clone()],
+        &[user.clone(), vault_b.clone(), pool.clone(), mint_b.clone(), system_program.clone(), token_program_b.clone()],
     )?;
 
-    // transfer amount_a from user_ata_a to vault_a
-    let mint_a_data = Mint::unpack(&mint_a.data.borrow())?;
-
+    // create treasury_ata_a and treasury_ata_b to collect the protocol's share of swap fees
     invoke(
-        &transfer_checked(
-            token_program.key, 
-            user_ata_a.key, 
-            mint_a.key, 
-            vault_a.key, 
+        &create_associated_token_account(
             user.key, 
-            &[], 
-            amount_a, 
-            mint_a_data.decimals,
-        )?, 
-        &[user_ata_a.clone(), mint_a.clone(), vault_a.clone(), user.clone()], 
+            treasury.key, 
+            mint_a.key, 
+            token_program_a.key,
+        ), 
+        &[user.clone(), treasury_ata_a.clone(), treasury.clone(), mint_a.clone(), system_program.clone(), token_program_a.clone()],
     )?;
-    
-    // transfer amount_b from user ata to pool ata
-    let mint_b_data = Mint::unpack(&mint_b.data.borrow())?;
 
     invoke(
-        &transfer_checked(
-            token_program.key, 
-            user_ata_b.key, 
-            mint_b.key, 
-            vault_b.key, 
+        &create_associated_token_account(
             user.key, 
-            &[], 
-            amount_b, 
-            mint_b_data.decimals,
-        )?, 
-        &[user_ata_b.clone(), mint_b.clone(), vault_b.clone(), user.clone()], 
+            treasury.key, 
+            mint_b.key, 
+            token_program_b.key,
+        ), 
+        &[user.clone(), treasury_ata_b.clone(), treasury.clone(), mint_b.clone(), system_program.clone(), token_program_b.clone()],
     )?;
 
     // create mint_lp
@@ -248,54 +497,141 @@ pub fn process_create_pool(
         &[mint_lp.clone(), pool.clone()],
     )?;
 
-    // create user_ata_lp
+    // create pool_ata_lp, which holds the MINIMUM_LIQUIDITY locked by InitialDeposit
     invoke(
-        &create_associated_token_account_idempotent(
-            user.key, 
+        &create_associated_token_account(
             user.key, 
+            pool.key, 
             mint_lp.key, 
             token_program.key,
         ), 
-        &[user.clone(), user_ata_lp.clone(), mint_lp.clone()],
+        &[user.clone(), pool_ata_lp.clone(), pool.clone(), mint_lp.clone(), system_program.clone(), token_program.clone()],
     )?;
 
-    // mint lp tokens to user_ata_lp
-    let lp_amount = (amount_a as u128)
-        .checked_mul(amount_b as u128)
-        .ok_or(ProgramError::InvalidInstructionData)?
-        .integer_sqrt() as u64;
+    // write pool data
+    let pool_data = LiquidityPool {
+        discriminator: LiquidityPool::DISCRIMINATOR,
+        version: LiquidityPool::VERSION,
+        is_initialized: true,
+        mint_a: *mint_a.key,
+        mint_b: *mint_b.key,
+        decimals_a: mint_decimals(mint_a)?,
+        decimals_b: mint_decimals(mint_b)?,
+        reserve_a: 0,
+        reserve_b: 0,
+        fees_a: 0,
+        fees_b: 0,
+        fees_total_a: 0,
+        fees_total_b: 0,
+        fee_bps,
+        protocol_fee_bps,
+        referral_fee_bps: 0,
+        curve,
+        admin: *user.key,
+        pending_admin: Pubkey::default(),
+        paused: false,
+        price_cumulative_a: 0,
+        price_cumulative_b: 0,
+        last_update_ts: Clock::get()?.unix_timestamp,
+        flash_loan_amount: 0,
+        flash_loan_mint: Pubkey::default(),
+        pending_protocol_fee_bps: 0,
+        fee_change_ts: 0,
+        oracle: Pubkey::default(),
+        max_oracle_deviation_bps: 0,
+        bump: pool_bump,
+    };
 
-    invoke_signed(
-        &mint_to(
-            token_program.key, 
-            mint_lp.key, 
-            user_ata_lp.key, 
-            pool.key, 
-            &[], 
-            lp_amount,
-        )?, 
-        &[mint_lp.clone(), user_ata_lp.clone(), pool.clone()], 
-        &[
-            &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &fee_bps.to_le_bytes(), &[pool_bump]],
-        ]
-    )?;
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
 
-    // update pool data
-    let mut pool_data = 
-        try_from_slice_unchecked::<LiquidityPool>(&pool.data.borrow())?;
+    // append the pool to the registry, creating the registry with the first pool
+    let entry = RegistryEntry {
+        pool: *pool.key,
+        mint_a: *mint_a.key,
+        mint_b: *mint_b.key,
+        fee_bps,
+    };
+
+    let registry_data = if registry.data_is_empty() {
+        let space = PoolRegistry::space(1);
+
+        // lamports sent to the PDA beforehand would make create_account fail,
+        // so top up, allocate and assign instead
+        let additional_rent = rent.minimum_balance(space).saturating_sub(registry.lamports());
+
+        if additional_rent > 0 {
+            invoke(
+                &transfer(user.key, registry.key, additional_rent), 
+                &[user.clone(), registry.clone()],
+            )?;
+        }
+
+        invoke_signed(
+            &allocate(registry.key, space as u64), 
+            std::slice::from_ref(registry), 
+            &[&[b"registry", &[registry_bump]]],
+        )?;
+
+        invoke_signed(
+            &assign(registry.key, program_id), 
+            std::slice::from_ref(registry), 
+            &[&[b"registry", &[registry_bump]]],
+        )?;
+
+        PoolRegistry {
+            discriminator: PoolRegistry::DISCRIMINATOR,
+            bump: registry_bump,
+            pools: vec![entry],
+        }
+    } else {
+        let mut registry_data = PoolRegistry::load(&registry.data.borrow())?;
+        registry_data.pools.push(entry);
 
-    pool_data.mint_a = *mint_a.key;
-    pool_data.mint_b = *mint_b.key;
-    pool_data.reserve_a = amount_a;
-    pool_data.reserve_b = amount_b;
-    pool_data.fee_bps = fee_bps;
-    pool_data.bump = pool_bump;
+        let new_space = PoolRegistry::space(registry_data.pools.len());
+        let additional_rent = rent.minimum_balance(new_space).saturating_sub(registry.lamports());
 
-    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+        if additional_rent > 0 {
+            invoke(
+                &transfer(user.key, registry.key, additional_rent), 
+                &[user.clone(), registry.clone()],
+            )?;
+        }
+
+        registry.resize(new_space)?;
+
+        registry_data
+    };
+
+    registry_data.serialize(&mut &mut registry.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
+// Funds a pool that has no LP supply yet, at the ratio of the amounts given,
+// with MINIMUM_LIQUIDITY locked. Takes ProvideLiquidity's accounts, including
+// the optional ones.
+pub fn process_initial_deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_a: u64,
+    amount_b: u64,
+) -> ProgramResult {
+    if accounts.len() < 16 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let mint_lp = &accounts[6];
+
+    // ProvideLiquidity checks mint_lp is the pool's, and the pool can't mint
+    // LP without a deposit
+    if Mint::unpack(&mint_lp.data.borrow())?.supply != 0 {
+        return Err(AmmError::PoolAlreadyFunded.into());
+    }
+
+    // the whole of both amounts is taken, so there's no slippage to bound
+    process_provide_liquidity(program_id, accounts, amount_a, amount_b, amount_a, amount_b)
+}
+
 pub fn process_provide_liquidity(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -317,13 +653,21 @@ pub fn process_provide_liquidity(
     let user_ata_a = next_account_info(accounts_iter)?;
     let user_ata_b = next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    // optional: with both, user_ata_lp is created for a first-time LP. ZapIn
+    // and older clients leave them off.
+    let system_program = next_account_info(accounts_iter).ok();
+    let associated_token_program = next_account_info(accounts_iter).ok();
+    // only needed to re-seed a pool with no LP supply left
+    let pool_ata_lp = next_account_info(accounts_iter).ok();
 
     if !user.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
     let mut pool_data = 
-        try_from_slice_unchecked::<LiquidityPool>(&pool.data.borrow())?;
+        LiquidityPool::load(&pool.data.borrow())?;
     
     let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
         (pool_data.mint_a, pool_data.mint_b)
@@ -340,6 +684,14 @@ pub fn process_provide_liquidity(
         return Err(AmmError::PoolAddressMismatch.into());
     }
 
+    if pool_data.paused {
+        return Err(AmmError::PoolPaused.into());
+    }
+
+    if pool_data.flash_loan_amount > 0 {
+        return Err(AmmError::FlashLoanActive.into());
+    }
+
     if *mint_a.key != pool_data.mint_a {
         return Err(AmmError::MintAddressMismatch.into());
     }
@@ -348,11 +700,14 @@ pub fn process_provide_liquidity(
         return Err(AmmError::MintAddressMismatch.into());
     }
 
-    if *vault_a.key != get_associated_token_address(pool.key, mint_a.key) {
+    check_token_program(token_program_a, mint_a)?;
+    check_token_program(token_program_b, mint_b)?;
+
+    if *vault_a.key != get_associated_token_address_with_program_id(pool.key, mint_a.key, token_program_a.key) {
         return Err(AmmError::VaultAddressMismatch.into());
     }
 
-    if *vault_b.key != get_associated_token_address(pool.key, mint_b.key) {
+    if *vault_b.key != get_associated_token_address_with_program_id(pool.key, mint_b.key, token_program_b.key) {
         return Err(AmmError::VaultAddressMismatch.into());
     }
 
@@ -367,56 +722,63 @@ pub fn process_provide_liquidity(
         return Err(AmmError::ZeroLiquidityAmount.into());
     }
 
-    let reserve_a = pool_data.reserve_a as u128;
-    let reserve_b = pool_data.reserve_b as u128;
-    let amount_a_desired = amount_a_desired as u128;
-    let amount_b_desired = amount_b_desired as u128;
+    // create user_ata_lp if the user doesn't have one yet
+    if let (Some(system_program), Some(associated_token_program)) = (system_program, associated_token_program) {
+        if *system_program.key != system_program_id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if *associated_token_program.key != associated_token_program_id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        invoke(
+            &create_associated_token_account_idempotent(
+                user.key, 
+                user.key, 
+                mint_lp.key, 
+                token_program.key,
+            ), 
+            &[user.clone(), user_ata_lp.clone(), mint_lp.clone(), system_program.clone(), token_program.clone()],
+        )?;
+    }
 
-    let take_a;
-    let take_b;
-    
-    let b_needed = 
-        amount_a_desired.checked_mul(reserve_b).ok_or(ProgramError::ArithmeticOverflow)?
-        / reserve_a;
+    let total_lp = Mint::unpack(&mint_lp.data.borrow())?.supply;
 
-    if b_needed <= amount_b_desired {
-        take_a = amount_a_desired;
-        take_b = b_needed
+    // with no LP left there's no ratio to hold the deposit to, so the pool is
+    // re-seeded like CreatePool: at the deposit's own ratio, with
+    // MINIMUM_LIQUIDITY locked again
+    let (take_a, take_b) = if total_lp == 0 {
+        (amount_a_desired, amount_b_desired)
     } else {
-        take_b = amount_b_desired;
-        take_a = 
-            amount_b_desired.checked_mul(reserve_a).ok_or(ProgramError::ArithmeticOverflow)?
-            / reserve_b;
-    }
+        // LP outstanding against an empty reserve gives no ratio either, but
+        // re-seeding would dilute those holders
+        if pool_data.reserve_a == 0 || pool_data.reserve_b == 0 {
+            return Err(AmmError::EmptyPool.into());
+        }
+
+        deposit_amounts(pool_data.reserve_a, pool_data.reserve_b, amount_a_desired, amount_b_desired)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+    };
 
-    if take_a < amount_a_min as u128 || take_b < amount_b_min as u128{
+    if take_a < amount_a_min || take_b < amount_b_min {
         return Err(AmmError::SlippageExceed.into());
     }
 
-    // calculate lp tokens to mint
-    let total_lp = Mint::unpack(&mint_lp.data.borrow())?.supply as u128;
-
-    let lp_from_a = take_a * total_lp / reserve_a;
-    let lp_from_b = take_b * total_lp / reserve_b;
-    let lp_amount = core::cmp::min(lp_from_a, lp_from_b) as u64;
-
-    let take_a = u64::try_from(take_a).map_err(|_| ProgramError::ArithmeticOverflow)?;
-    let take_b = u64::try_from(take_b).map_err(|_| ProgramError::ArithmeticOverflow)?;
-
-    let mint_a_data = Mint::unpack(&mint_a.data.borrow())?;
-    let mint_b_data = Mint::unpack(&mint_b.data.borrow())?;
+    let vault_a_before = token_amount(vault_a)?;
+    let vault_b_before = token_amount(vault_b)?;
 
     // transfer take_a amount from user_ata_a to vault_a
     invoke(
         &transfer_checked(
-            token_program.key, 
+            token_program_a.key, 
             user_ata_a.key, 
             mint_a.key, 
             vault_a.key, 
             user.key, 
             &[], 
             take_a, 
-            mint_a_data.decimals,
+            pool_data.decimals_a,
         )?, 
         &[user_ata_a.clone(), mint_a.clone(), vault_a.clone(), user.clone()],
     )?;
@@ -424,18 +786,82 @@ pub fn process_provide_liquidity(
     // transfer take_b amount from user_ata_b to vault_b
     invoke(
         &transfer_checked(
-            token_program.key, 
+            token_program_b.key, 
             user_ata_b.key, 
             mint_b.key, 
             vault_b.key, 
             user.key, 
             &[], 
             take_b, 
-            mint_b_data.decimals,
+            pool_data.decimals_b,
         )?, 
         &[user_ata_b.clone(), mint_b.clone(), vault_b.clone(), user.clone()],
     )?;
 
+    // Token-2022 transfer fees come out of the amount sent, so credit only
+    // what actually reached the vaults
+    let received_a = token_amount(vault_a)? - vault_a_before;
+    let received_b = token_amount(vault_b)? - vault_b_before;
+
+    // calculate lp tokens to mint
+    let lp_amount = if total_lp == 0 {
+        // any dust left in the reserves goes to whoever re-seeds
+        let lp_amount = initial_lp_amount(
+            pool_data.curve, 
+            pool_data.reserve_a.checked_add(received_a).ok_or(ProgramError::ArithmeticOverflow)?, 
+            pool_data.reserve_b.checked_add(received_b).ok_or(ProgramError::ArithmeticOverflow)?,
+        ).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if lp_amount <= MINIMUM_LIQUIDITY {
+            return Err(AmmError::InsufficientInitialLiquidity.into());
+        }
+
+        let (Some(system_program), Some(pool_ata_lp)) = (system_program, pool_ata_lp) else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if *pool_ata_lp.key != get_associated_token_address(pool.key, mint_lp.key) {
+            return Err(AmmError::VaultAddressMismatch.into());
+        }
+
+        // pools from before MINIMUM_LIQUIDITY was locked have no pool_ata_lp
+        invoke(
+            &create_associated_token_account_idempotent(
+                user.key, 
+                pool.key, 
+                mint_lp.key, 
+                token_program.key,
+            ), 
+            &[user.clone(), pool_ata_lp.clone(), pool.clone(), mint_lp.clone(), system_program.clone(), token_program.clone()],
+        )?;
+
+        invoke_signed(
+            &mint_to(
+                token_program.key, 
+                mint_lp.key, 
+                pool_ata_lp.key, 
+                pool.key, 
+                &[], 
+                MINIMUM_LIQUIDITY,
+            )?, 
+            &[mint_lp.clone(), pool_ata_lp.clone(), pool.clone()], 
+            &[
+                &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]],
+            ]
+        )?;
+
+        lp_amount - MINIMUM_LIQUIDITY
+    } else {
+        // LP tokens are also a claim on the fees, so they're priced against both
+        lp_for_deposit(
+            pool_data.reserve_a.checked_add(pool_data.fees_a).ok_or(ProgramError::ArithmeticOverflow)?, 
+            pool_data.reserve_b.checked_add(pool_data.fees_b).ok_or(ProgramError::ArithmeticOverflow)?, 
+            total_lp, 
+            received_a, 
+            received_b,
+        ).ok_or(ProgramError::ArithmeticOverflow)?
+    };
+
     // mint lp tokens to user
     invoke_signed(
         &mint_to(
@@ -452,11 +878,25 @@ pub fn process_provide_liquidity(
         ]
     )?;
 
-    pool_data.reserve_a = pool_data.reserve_a.checked_add(take_a).ok_or(ProgramError::ArithmeticOverflow)?;
-    pool_data.reserve_b = pool_data.reserve_b.checked_add(take_b).ok_or(ProgramError::ArithmeticOverflow)?;
+    update_price_cumulatives(&mut pool_data, Clock::get()?.unix_timestamp);
+
+    pool_data.reserve_a = pool_data.reserve_a.checked_add(received_a).ok_or(ProgramError::ArithmeticOverflow)?;
+    pool_data.reserve_b = pool_data.reserve_b.checked_add(received_b).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    check_vault_balances(&pool_data, vault_a, vault_b)?;
 
     pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
 
+    AmmEvent::LiquidityAdded(LiquidityAdded {
+        pool: *pool.key,
+        user: *user.key,
+        amount_a: received_a,
+        amount_b: received_b,
+        lp_minted: lp_amount,
+        reserve_a: pool_data.reserve_a,
+        reserve_b: pool_data.reserve_b,
+    }).emit();
+
     Ok(())
 }
 
@@ -480,6 +920,8 @@ pub fn process_withdraw_liquidity(
     let user_ata_a = next_account_info(accounts_iter)?;
     let user_ata_b = next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
 
     if !user.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
@@ -490,7 +932,7 @@ pub fn process_withdraw_liquidity(
     }
 
     let mut pool_data
-        = try_from_slice_unchecked::<LiquidityPool>(&pool.data.borrow())?;
+        = LiquidityPool::load(&pool.data.borrow())?;
 
     let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
         (pool_data.mint_a, pool_data.mint_b)
@@ -510,6 +952,10 @@ pub fn process_withdraw_liquidity(
         return Err(AmmError::PoolAddressMismatch.into());
     }
 
+    if pool_data.flash_loan_amount > 0 {
+        return Err(AmmError::FlashLoanActive.into());
+    }
+
     if *mint_a.key != pool_data.mint_a {
         return Err(AmmError::MintAddressMismatch.into());
     }
@@ -517,12 +963,15 @@ pub fn process_withdraw_liquidity(
     if *mint_b.key != pool_data.mint_b {
         return Err(AmmError::MintAddressMismatch.into());
     }
+
+    check_token_program(token_program_a, mint_a)?;
+    check_token_program(token_program_b, mint_b)?;
     
-    if *vault_a.key != get_associated_token_address(pool.key, mint_a.key) {
+    if *vault_a.key != get_associated_token_address_with_program_id(pool.key, mint_a.key, token_program_a.key) {
         return Err(AmmError::VaultAddressMismatch.into());
     }
 
-    if *vault_b.key != get_associated_token_address(pool.key, mint_b.key) {
+    if *vault_b.key != get_associated_token_address_with_program_id(pool.key, mint_b.key, token_program_b.key) {
         return Err(AmmError::VaultAddressMismatch.into());
     }
 
@@ -537,24 +986,26 @@ pub fn process_withdraw_liquidity(
     let mint_lp_data = 
         Mint::unpack(&mint_lp.data.borrow())?;
 
-    let total_lp = mint_lp_data.supply as u128;
-    let amount_lp_in  = amount_lp_in as u128;
-    let reserve_a = pool_data.reserve_a as u128;
-    let reserve_b = pool_data.reserve_b as u128;
+    let total_lp = mint_lp_data.supply;
 
     if total_lp == 0 {
-        return Err(ProgramError::UninitializedAccount);
+        return Err(AmmError::EmptyPool.into());
     }
 
-    let a_out = amount_lp_in.checked_mul(reserve_a)
-        .ok_or(ProgramError::ArithmeticOverflow)? / total_lp;
-    let b_out = amount_lp_in.checked_mul(reserve_b)
-        .ok_or(ProgramError::ArithmeticOverflow)? / total_lp;
-
-    if a_out < amount_a_min as u128 || b_out < amount_b_min as u128 {
-        return Err(AmmError::SlippageExceed.into());
+    if amount_lp_in > total_lp {
+        return Err(AmmError::InsufficientLiquidity.into());
     }
 
+    let (reserve_a_out, reserve_b_out) = withdraw_amounts(pool_data.reserve_a, pool_data.reserve_b, total_lp, amount_lp_in)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // along with the same share of the fees earned so far
+    let (fees_a_out, fees_b_out) = withdraw_amounts(pool_data.fees_a, pool_data.fees_b, total_lp, amount_lp_in)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let a_out = reserve_a_out + fees_a_out;
+    let b_out = reserve_b_out + fees_b_out;
+
     // burn lp tokens from user_ata_lp
     invoke(
         &burn(
@@ -563,30 +1014,25 @@ pub fn process_withdraw_liquidity(
             mint_lp.key, 
             user.key, 
             &[], 
-            amount_lp_in as u64,
+            amount_lp_in,
         )?, 
         &[user_ata_lp.clone(), mint_lp.clone(), user.clone()],
     )?;
 
-    let a_out = a_out as u64;
-    let b_out = b_out as u64;
-
-    let mint_a_data = 
-        Mint::unpack(&mint_a.data.borrow())?;
-    let mint_b_data =
-        Mint::unpack(&mint_b.data.borrow())?;
+    let user_a_before = token_amount(user_ata_a)?;
+    let user_b_before = token_amount(user_ata_b)?;
 
     // transfer a_out from vault_a to user_ata_a
     invoke_signed(
         &transfer_checked(
-            token_program.key, 
+            token_program_a.key, 
             vault_a.key, 
             mint_a.key, 
             user_ata_a.key, 
             pool.key, 
             &[], 
             a_out, 
-            mint_a_data.decimals,
+            pool_data.decimals_a,
         )?, 
         &[vault_a.clone(), mint_a.clone(), user_ata_a.clone(), pool.clone()], 
         &[
@@ -597,14 +1043,14 @@ pub fn process_withdraw_liquidity(
     // transfer b_out from vault_b to user_ata_b
     invoke_signed(
         &transfer_checked(
-            token_program.key, 
+            token_program_b.key, 
             vault_b.key, 
             mint_b.key, 
             user_ata_b.key, 
             pool.key, 
             &[], 
             b_out, 
-            mint_b_data.decimals,
+            pool_data.decimals_b,
         )?, 
         &[vault_b.clone(), mint_b.clone(), user_ata_b.clone(), pool.clone()], 
         &[
@@ -612,13 +1058,37 @@ pub fn process_withdraw_liquidity(
         ],
     )?;
 
-    pool_data.reserve_a = pool_data.reserve_a.checked_sub(a_out)
+    // the minimums apply to what the user receives after any transfer fees
+    let received_a = token_amount(user_ata_a)? - user_a_before;
+    let received_b = token_amount(user_ata_b)? - user_b_before;
+
+    if received_a < amount_a_min || received_b < amount_b_min {
+        return Err(AmmError::SlippageExceed.into());
+    }
+
+    update_price_cumulatives(&mut pool_data, Clock::get()?.unix_timestamp);
+
+    pool_data.reserve_a = pool_data.reserve_a.checked_sub(reserve_a_out)
         .ok_or(ProgramError::ArithmeticOverflow)?;
-    pool_data.reserve_b = pool_data.reserve_b.checked_sub(b_out)
+    pool_data.reserve_b = pool_data.reserve_b.checked_sub(reserve_b_out)
         .ok_or(ProgramError::ArithmeticOverflow)?;
+    pool_data.fees_a -= fees_a_out;
+    pool_data.fees_b -= fees_b_out;
+
+    check_vault_balances(&pool_data, vault_a, vault_b)?;
 
     pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
 
+    AmmEvent::LiquidityRemoved(LiquidityRemoved {
+        pool: *pool.key,
+        user: *user.key,
+        amount_a: a_out,
+        amount_b: b_out,
+        lp_burned: amount_lp_in,
+        reserve_a: pool_data.reserve_a,
+        reserve_b: pool_data.reserve_b,
+    }).emit();
+
     Ok(())
 }
 
@@ -627,6 +1097,7 @@ pub fn process_swap(
     accounts: &[AccountInfo],
     amount_in: u64,
     min_out: u64,
+    max_price_impact_bps: Option<u16>,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -638,8 +1109,10 @@ pub fn process_swap(
     let vault_out = next_account_info(accounts_iter)?;
     let user_ata_in = next_account_info(accounts_iter)?;
     let user_ata_out = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
-    let associated_token_program = next_account_info(accounts_iter)?;
+    let treasury_ata_in = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let token_program_out = next_account_info(accounts_iter)?;
+    let _associated_token_program = next_account_info(accounts_iter)?;
 
     if !user.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
@@ -650,7 +1123,29 @@ pub fn process_swap(
     }
 
     let mut pool_data = 
-        try_from_slice_unchecked::<LiquidityPool>(&pool.data.borrow())?;
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    // a pool with an oracle takes its price account next
+    let oracle = if pool_data.oracle != Pubkey::default() {
+        let oracle = next_account_info(accounts_iter).map_err(|_| AmmError::OracleMismatch)?;
+
+        if *oracle.key != pool_data.oracle {
+            return Err(AmmError::OracleMismatch.into());
+        }
+
+        Some(oracle)
+    } else {
+        None
+    };
+    // optional: receives referral_fee_bps of the swap fee
+    let referrer_ata = next_account_info(accounts_iter).ok();
+
+    let a_to_b = *mint_in.key == pool_data.mint_a && *mint_out.key == pool_data.mint_b;
+    let b_to_a = *mint_in.key == pool_data.mint_b && *mint_out.key == pool_data.mint_a;
+
+    if !a_to_b && !b_to_a {
+        return Err(AmmError::MintNotInPool.into());
+    }
 
     let (mint_lo, mint_hi) = if mint_in.key < mint_out.key {
         (mint_in.key.clone(), mint_out.key.clone())
@@ -667,54 +1162,195 @@ pub fn process_swap(
         return Err(AmmError::PoolAddressMismatch.into());
     }
 
-    let reserve_in;
-    let reserve_out;
+    if pool_data.paused {
+        return Err(AmmError::PoolPaused.into());
+    }
 
-    if *mint_in.key == pool_data.mint_a {
-        reserve_in = pool_data.reserve_a as u128;
-        reserve_out = pool_data.reserve_b as u128;
+    if pool_data.flash_loan_amount > 0 {
+        return Err(AmmError::FlashLoanActive.into());
     }
-    else {
-        reserve_in = pool_data.reserve_b as u128;
-        reserve_out = pool_data.reserve_a as u128;
+
+    check_token_program(token_program_in, mint_in)?;
+    check_token_program(token_program_out, mint_out)?;
+
+    if *vault_in.key != get_associated_token_address_with_program_id(pool.key, mint_in.key, token_program_in.key) {
+        return Err(AmmError::VaultInMismatch.into());
+    }
+
+    if *vault_out.key != get_associated_token_address_with_program_id(pool.key, mint_out.key, token_program_out.key) {
+        return Err(AmmError::VaultOutMismatch.into());
     }
 
-    let fee_bps = pool_data.fee_bps as u128;
+    let (treasury, _treasury_bump) = Pubkey::find_program_address(
+        &[b"treasury", pool.key.as_ref()], program_id);
 
-    let amount_in_post_fee= 
-        (amount_in as u128) * (10_000 - fee_bps);
+    if *treasury_ata_in.key != get_associated_token_address_with_program_id(&treasury, mint_in.key, token_program_in.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (pool_data.reserve_a, pool_data.reserve_b)
+    } else {
+        (pool_data.reserve_b, pool_data.reserve_a)
+    };
 
-    let amount_out= 
-        ((reserve_out * amount_in_post_fee) / (reserve_in * 10_000 + amount_in_post_fee)) 
-        as u64;
+    // the protocol takes protocol_fee_bps of the swap fee, the rest stays in the pool for LPs
+    let swap_fee = bps_floor(amount_in, pool_data.fee_bps).ok_or(ProgramError::ArithmeticOverflow)?;
+    let protocol_fee = bps_floor(swap_fee, pool_data.protocol_fee_bps).ok_or(ProgramError::ArithmeticOverflow)?;
 
-    if amount_out < min_out {
-        return Err(AmmError::SlippageExceed.into());
+    // the referrer's slice also comes out of the LPs' share; without a
+    // referrer account it stays in the pool
+    let referral_fee = match referrer_ata {
+        Some(referrer_ata) => {
+            if referrer_ata.key == vault_in.key || referrer_ata.key == treasury_ata_in.key {
+                return Err(AmmError::InvalidReferrer.into());
+            }
+
+            bps_floor(swap_fee, pool_data.referral_fee_bps).ok_or(ProgramError::ArithmeticOverflow)?
+        },
+        None => 0,
+    };
+
+    // a bot or router swaps with an SPL delegate approval instead of the
+    // owner's signature, up to the approved amount and only back to the owner
+    let user_ata_in_state = token_account_state(user_ata_in)?;
+
+    if user_ata_in_state.owner != *user.key {
+        if user_ata_in_state.delegate != COption::Some(*user.key) {
+            return Err(AmmError::NotTokenDelegate.into());
+        }
+
+        if user_ata_in_state.delegated_amount < amount_in {
+            return Err(AmmError::DelegatedAmountTooLow.into());
+        }
+
+        if token_account_state(user_ata_out)?.owner != user_ata_in_state.owner {
+            return Err(AmmError::DelegateOutputNotOwner.into());
+        }
     }
 
-    let mint_in_decimals = Mint::unpack(&mint_in.data.borrow())?.decimals;
+    let amount_to_vault = amount_in - protocol_fee - referral_fee;
+
+    let mint_in_decimals = pool_data.decimals(mint_in.key);
+
+    let vault_in_before = token_amount(vault_in)?;
+    let treasury_in_before = token_amount(treasury_ata_in)?;
+    let referrer_in_before = match referrer_ata {
+        Some(referrer_ata) => token_amount(referrer_ata)?,
+        None => 0,
+    };
 
-    // transfer amount_in of mint_in from user_ata_in to vault_in
+    // transfer amount_to_vault of mint_in from user_ata_in to vault_in
     invoke(
         &transfer_checked(
-            token_program.key,
+            token_program_in.key,
             user_ata_in.key, 
             mint_in.key, 
             vault_in.key, 
             user.key, 
             &[], 
-            amount_in, 
+            amount_to_vault, 
             mint_in_decimals,
         )?, 
         &[user_ata_in.clone(), mint_in.clone(), vault_in.clone(), user.clone()], 
     )?;
 
-    let mint_out_decimals = Mint::unpack(&mint_out.data.borrow())?.decimals;
+    // transfer protocol_fee of mint_in from user_ata_in to treasury_ata_in
+    if protocol_fee > 0 {
+        invoke(
+            &transfer_checked(
+                token_program_in.key,
+                user_ata_in.key, 
+                mint_in.key, 
+                treasury_ata_in.key, 
+                user.key, 
+                &[], 
+                protocol_fee, 
+                mint_in_decimals,
+            )?, 
+            &[user_ata_in.clone(), mint_in.clone(), treasury_ata_in.clone(), user.clone()], 
+        )?;
+    }
+
+    // transfer referral_fee of mint_in from user_ata_in to referrer_ata
+    let referrer_received = match referrer_ata {
+        Some(referrer_ata) if referral_fee > 0 => {
+            invoke(
+                &transfer_checked(
+                    token_program_in.key,
+                    user_ata_in.key, 
+                    mint_in.key, 
+                    referrer_ata.key, 
+                    user.key, 
+                    &[], 
+                    referral_fee, 
+                    mint_in_decimals,
+                )?, 
+                &[user_ata_in.clone(), mint_in.clone(), referrer_ata.clone(), user.clone()], 
+            )?;
+
+            token_amount(referrer_ata)? - referrer_in_before
+        },
+        _ => 0,
+    };
+
+    // with a Token-2022 transfer fee on mint_in, less than amount_in arrives,
+    // and only what arrived is priced
+    let vault_received = token_amount(vault_in)? - vault_in_before;
+    let amount_in_received = vault_received 
+        + (token_amount(treasury_ata_in)? - treasury_in_before) 
+        + referrer_received;
+
+    let amount_out = swap_output(
+        pool_data.curve, 
+        reserve_in, 
+        reserve_out, 
+        amount_in_received, 
+        pool_data.fee_bps,
+        a_to_b,
+    )?;
+
+    // unlike min_out this bounds the price relative to the pool's spot
+    // price, so it holds however the pool moved before the swap landed
+    if let Some(max_price_impact_bps) = max_price_impact_bps {
+        let impact_bps = price_impact_bps(
+            pool_data.curve, 
+            reserve_in, 
+            reserve_out, 
+            amount_in_received, 
+            amount_out, 
+            a_to_b,
+        ).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if impact_bps > max_price_impact_bps as u64 {
+            return Err(AmmError::PriceImpactTooHigh.into());
+        }
+    }
+
+    let mint_out_decimals = pool_data.decimals(mint_out.key);
+
+    // bounds the execution price against the oracle, so a manipulated or
+    // stale pool can't be traded far off the market price
+    if let Some(oracle) = oracle {
+        let oracle_price = PythPrice::load(&oracle.data.borrow())?
+            .checked(Clock::get()?.unix_timestamp)?;
+
+        let deviation = if a_to_b {
+            deviation_bps(&oracle_price, mint_in_decimals, mint_out_decimals, amount_in_received, amount_out)?
+        } else {
+            deviation_bps(&oracle_price, mint_out_decimals, mint_in_decimals, amount_out, amount_in_received)?
+        };
+
+        if deviation > pool_data.max_oracle_deviation_bps as u64 {
+            return Err(AmmError::OraclePriceDeviation.into());
+        }
+    }
+    let user_out_before = token_amount(user_ata_out)?;
 
     // transfer amount_out of mint_out from vault_out to user_ata_out
     invoke_signed(
         &transfer_checked(
-            token_program.key, 
+            token_program_out.key, 
             vault_out.key, 
             mint_out.key, 
             user_ata_out.key, 
@@ -729,16 +1365,1851 @@ pub fn process_swap(
         ],
     )?;
 
-    if *mint_in.key == pool_data.mint_a {
-        pool_data.reserve_a += amount_in;
-        pool_data.reserve_b -= amount_out;
-    }
-    else {
-        pool_data.reserve_a -= amount_out;
-        pool_data.reserve_b += amount_in;
+    // slippage is checked against what reaches the user after any transfer fee on mint_out
+    if token_amount(user_ata_out)? - user_out_before < min_out {
+        return Err(AmmError::SlippageExceed.into());
     }
 
-    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+    update_price_cumulatives(&mut pool_data, Clock::get()?.unix_timestamp);
 
-    Ok(())
-}
\ No newline at end of file
+    let k_before = pool_data.reserve_a as u128 * pool_data.reserve_b as u128;
+
+    // the LP fee stays in the vault but out of the reserves
+    let (to_reserve, lp_fee) = split_lp_fee(vault_received, amount_in_received, pool_data.fee_bps)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    pool_data.add_lp_fee(a_to_b, lp_fee)?;
+
+    let new_reserve_in = reserve_in.checked_add(to_reserve).ok_or(ProgramError::ArithmeticOverflow)?;
+    let new_reserve_out = reserve_out.checked_sub(amount_out).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if a_to_b {
+        pool_data.reserve_a = new_reserve_in;
+        pool_data.reserve_b = new_reserve_out;
+    }
+    else {
+        pool_data.reserve_a = new_reserve_out;
+        pool_data.reserve_b = new_reserve_in;
+    }
+
+    // the output rounds down, so a constant-product swap can't shrink k;
+    // the other curves keep a different invariant
+    if pool_data.curve == CurveType::ConstantProduct 
+        && (pool_data.reserve_a as u128 * pool_data.reserve_b as u128) < k_before 
+    {
+        return Err(AmmError::InvariantViolation.into());
+    }
+
+    let (vault_a, vault_b) = if a_to_b {
+        (vault_in, vault_out)
+    } else {
+        (vault_out, vault_in)
+    };
+
+    check_vault_balances(&pool_data, vault_a, vault_b)?;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    AmmEvent::SwapExecuted(SwapExecuted {
+        pool: *pool.key,
+        user: *user.key,
+        mint_in: *mint_in.key,
+        mint_out: *mint_out.key,
+        amount_in: amount_in_received,
+        amount_out,
+        protocol_fee,
+        reserve_a: pool_data.reserve_a,
+        reserve_b: pool_data.reserve_b,
+    }).emit();
+
+    Ok(())
+}
+
+pub fn process_collect_protocol_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let treasury_ata = next_account_info(accounts_iter)?;
+    let admin_ata = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *admin.key != pool_data.admin {
+        return Err(AmmError::NotPoolAdmin.into());
+    }
+
+    check_admin_signature(admin, signers)?;
+
+    if *mint.key != pool_data.mint_a && *mint.key != pool_data.mint_b {
+        return Err(AmmError::MintAddressMismatch.into());
+    }
+
+    let (treasury_pda, treasury_bump) = Pubkey::find_program_address(
+        &[b"treasury", pool.key.as_ref()], program_id);
+
+    if *treasury.key != treasury_pda {
+        return Err(AmmError::TreasuryAddressMismatch.into());
+    }
+
+    check_token_program(token_program, mint)?;
+
+    if *treasury_ata.key != get_associated_token_address_with_program_id(treasury.key, mint.key, token_program.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    let amount = token_amount(treasury_ata)?;
+
+    if amount == 0 {
+        return Ok(());
+    }
+
+
+    // transfer the whole treasury_ata balance to admin_ata
+    invoke_signed(
+        &transfer_checked(
+            token_program.key, 
+            treasury_ata.key, 
+            mint.key, 
+            admin_ata.key, 
+            treasury.key, 
+            &[], 
+            amount, 
+            pool_data.decimals(mint.key),
+        )?, 
+        &[treasury_ata.clone(), mint.clone(), admin_ata.clone(), treasury.clone()], 
+        &[
+            &[b"treasury", pool.key.as_ref(), &[treasury_bump]]
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    paused: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *admin.key != pool_data.admin {
+        return Err(AmmError::NotPoolAdmin.into());
+    }
+
+    check_admin_signature(admin, signers)?;
+
+    pool_data.paused = paused;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Tokens sent straight to the vaults don't go through the pool, so Sync
+// folds whatever the vaults actually hold back into the reserves.
+pub fn process_sync(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let pool = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if pool_data.flash_loan_amount > 0 {
+        return Err(AmmError::FlashLoanActive.into());
+    }
+
+    // the vaults' owners are their token programs, SPL Token or Token-2022
+    if *vault_a.key != get_associated_token_address_with_program_id(pool.key, &pool_data.mint_a, vault_a.owner) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    if *vault_b.key != get_associated_token_address_with_program_id(pool.key, &pool_data.mint_b, vault_b.owner) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    update_price_cumulatives(&mut pool_data, Clock::get()?.unix_timestamp);
+
+    // what the vaults hold beyond the LP fees
+    pool_data.reserve_a = token_amount(vault_a)?.saturating_sub(pool_data.fees_a);
+    pool_data.reserve_b = token_amount(vault_b)?.saturating_sub(pool_data.fees_b);
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// The counterpart to Sync: instead of folding excess vault balances into the
+// reserves, Skim sends them to the caller's token accounts.
+pub fn process_skim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let pool = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let to_ata_a = next_account_info(accounts_iter)?;
+    let to_ata_b = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+
+    let pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if pool_data.flash_loan_amount > 0 {
+        return Err(AmmError::FlashLoanActive.into());
+    }
+
+    if *mint_a.key != pool_data.mint_a {
+        return Err(AmmError::MintAddressMismatch.into());
+    }
+
+    if *mint_b.key != pool_data.mint_b {
+        return Err(AmmError::MintAddressMismatch.into());
+    }
+
+    check_token_program(token_program_a, mint_a)?;
+    check_token_program(token_program_b, mint_b)?;
+
+    if *vault_a.key != get_associated_token_address_with_program_id(pool.key, mint_a.key, token_program_a.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    if *vault_b.key != get_associated_token_address_with_program_id(pool.key, mint_b.key, token_program_b.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    let excess_a = token_amount(vault_a)?.saturating_sub(pool_data.reserve_a).saturating_sub(pool_data.fees_a);
+    let excess_b = token_amount(vault_b)?.saturating_sub(pool_data.reserve_b).saturating_sub(pool_data.fees_b);
+
+    // transfer excess_a from vault_a to to_ata_a
+    if excess_a > 0 {
+        invoke_signed(
+            &transfer_checked(
+                token_program_a.key, 
+                vault_a.key, 
+                mint_a.key, 
+                to_ata_a.key, 
+                pool.key, 
+                &[], 
+                excess_a, 
+                pool_data.decimals_a,
+            )?, 
+            &[vault_a.clone(), mint_a.clone(), to_ata_a.clone(), pool.clone()], 
+            &[
+                &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]]
+            ],
+        )?;
+    }
+
+    // transfer excess_b from vault_b to to_ata_b
+    if excess_b > 0 {
+        invoke_signed(
+            &transfer_checked(
+                token_program_b.key, 
+                vault_b.key, 
+                mint_b.key, 
+                to_ata_b.key, 
+                pool.key, 
+                &[], 
+                excess_b, 
+                pool_data.decimals_b,
+            )?, 
+            &[vault_b.clone(), mint_b.clone(), to_ata_b.clone(), pool.clone()], 
+            &[
+                &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]]
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+// Read-only: writes the amount a Swap of `amount_in` would pay out right now
+// as return data (u64, little endian), so callers don't replicate the curve math.
+// Token-2022 transfer fees on either side aren't taken into account.
+pub fn process_quote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    a_to_b: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let pool = next_account_info(accounts_iter)?;
+
+    if amount_in == 0 {
+        return Err(AmmError::ZeroSwapAmount.into());
+    }
+
+    let pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if pool_data.flash_loan_amount > 0 {
+        return Err(AmmError::FlashLoanActive.into());
+    }
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (pool_data.reserve_a, pool_data.reserve_b)
+    } else {
+        (pool_data.reserve_b, pool_data.reserve_a)
+    };
+
+    let amount_out = swap_output(
+        pool_data.curve, 
+        reserve_in, 
+        reserve_out, 
+        amount_in, 
+        pool_data.fee_bps,
+        a_to_b,
+    )?;
+
+    set_return_data(&amount_out.to_le_bytes());
+
+    Ok(())
+}
+
+// Accounts of one hop in a SwapRoute, in order.
+const ROUTE_HOP_ACCOUNTS: usize = 10;
+
+// Swaps through several pools in one instruction, e.g. A -> B -> C. Every hop
+// pays out to the user's ATA for its output mint, which is the next hop's
+// input, and only the final output is checked against `min_out`.
+pub fn process_swap_route(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_out: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let hop_accounts = accounts_iter.as_slice();
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount_in == 0 {
+        return Err(AmmError::ZeroSwapAmount.into());
+    }
+
+    if hop_accounts.is_empty() {
+        return Err(AmmError::InvalidRoute.into());
+    }
+
+    let hops = hop_accounts.chunks_exact(ROUTE_HOP_ACCOUNTS);
+
+    if !hops.remainder().is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let mut amount = amount_in;
+    let mut previous_mint_out: Option<Pubkey> = None;
+
+    for hop in hops {
+        let mint_in = &hop[1];
+
+        if previous_mint_out.is_some_and(|mint| mint != *mint_in.key) {
+            return Err(AmmError::InvalidRoute.into());
+        }
+
+        amount = swap_route_hop(program_id, user, hop, amount)?;
+        previous_mint_out = Some(*hop[2].key);
+    }
+
+    if amount < min_out {
+        return Err(AmmError::SlippageExceed.into());
+    }
+
+    Ok(())
+}
+
+// One hop of a route: pool, mint_in, mint_out, vault_in, vault_out,
+// user_ata_in, user_ata_out, treasury_ata_in, token_program_in,
+// token_program_out. Returns the amount that reached user_ata_out.
+fn swap_route_hop<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    hop: &[AccountInfo<'a>],
+    amount_in: u64,
+) -> Result<u64, ProgramError> {
+    let [
+        pool, 
+        mint_in, 
+        mint_out, 
+        vault_in, 
+        vault_out, 
+        user_ata_in, 
+        user_ata_out, 
+        treasury_ata_in, 
+        token_program_in, 
+        token_program_out,
+    ] = hop else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if pool_data.paused {
+        return Err(AmmError::PoolPaused.into());
+    }
+
+    if pool_data.flash_loan_amount > 0 {
+        return Err(AmmError::FlashLoanActive.into());
+    }
+
+    // hops don't carry oracle accounts, so oracle-bounded pools can't be routed through
+    if pool_data.oracle != Pubkey::default() {
+        return Err(AmmError::OracleMismatch.into());
+    }
+
+    let a_to_b = *mint_in.key == pool_data.mint_a && *mint_out.key == pool_data.mint_b;
+    let b_to_a = *mint_in.key == pool_data.mint_b && *mint_out.key == pool_data.mint_a;
+
+    if !a_to_b && !b_to_a {
+        return Err(AmmError::MintAddressMismatch.into());
+    }
+
+    check_token_program(token_program_in, mint_in)?;
+    check_token_program(token_program_out, mint_out)?;
+
+    if *vault_in.key != get_associated_token_address_with_program_id(pool.key, mint_in.key, token_program_in.key) 
+        || *vault_out.key != get_associated_token_address_with_program_id(pool.key, mint_out.key, token_program_out.key) 
+    {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    // intermediate tokens pass through the user's own ATAs
+    if *user_ata_in.key != get_associated_token_address_with_program_id(user.key, mint_in.key, token_program_in.key) 
+        || *user_ata_out.key != get_associated_token_address_with_program_id(user.key, mint_out.key, token_program_out.key) 
+    {
+        return Err(AmmError::InvalidRoute.into());
+    }
+
+    let (treasury, _treasury_bump) = Pubkey::find_program_address(
+        &[b"treasury", pool.key.as_ref()], program_id);
+
+    if *treasury_ata_in.key != get_associated_token_address_with_program_id(&treasury, mint_in.key, token_program_in.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (pool_data.reserve_a, pool_data.reserve_b)
+    } else {
+        (pool_data.reserve_b, pool_data.reserve_a)
+    };
+
+    let swap_fee = bps_floor(amount_in, pool_data.fee_bps).ok_or(ProgramError::ArithmeticOverflow)?;
+    let protocol_fee = bps_floor(swap_fee, pool_data.protocol_fee_bps).ok_or(ProgramError::ArithmeticOverflow)?;
+    let amount_to_vault = amount_in - protocol_fee;
+
+    let mint_in_decimals = pool_data.decimals(mint_in.key);
+
+    let vault_in_before = token_amount(vault_in)?;
+    let treasury_in_before = token_amount(treasury_ata_in)?;
+
+    // transfer amount_to_vault of mint_in from user_ata_in to vault_in
+    invoke(
+        &transfer_checked(
+            token_program_in.key,
+            user_ata_in.key, 
+            mint_in.key, 
+            vault_in.key, 
+            user.key, 
+            &[], 
+            amount_to_vault, 
+            mint_in_decimals,
+        )?, 
+        &[user_ata_in.clone(), mint_in.clone(), vault_in.clone(), user.clone()], 
+    )?;
+
+    // transfer protocol_fee of mint_in from user_ata_in to treasury_ata_in
+    if protocol_fee > 0 {
+        invoke(
+            &transfer_checked(
+                token_program_in.key,
+                user_ata_in.key, 
+                mint_in.key, 
+                treasury_ata_in.key, 
+                user.key, 
+                &[], 
+                protocol_fee, 
+                mint_in_decimals,
+            )?, 
+            &[user_ata_in.clone(), mint_in.clone(), treasury_ata_in.clone(), user.clone()], 
+        )?;
+    }
+
+    let vault_received = token_amount(vault_in)? - vault_in_before;
+    let amount_in_received = vault_received + (token_amount(treasury_ata_in)? - treasury_in_before);
+
+    let amount_out = swap_output(
+        pool_data.curve, 
+        reserve_in, 
+        reserve_out, 
+        amount_in_received, 
+        pool_data.fee_bps,
+        a_to_b,
+    )?;
+
+    let mint_out_decimals = pool_data.decimals(mint_out.key);
+    let user_out_before = token_amount(user_ata_out)?;
+
+    // transfer amount_out of mint_out from vault_out to user_ata_out
+    invoke_signed(
+        &transfer_checked(
+            token_program_out.key, 
+            vault_out.key, 
+            mint_out.key, 
+            user_ata_out.key, 
+            pool.key, 
+            &[], 
+            amount_out, 
+            mint_out_decimals,
+        )?, 
+        &[vault_out.clone(), mint_out.clone(), user_ata_out.clone(), pool.clone()], 
+        &[
+            &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]]
+        ],
+    )?;
+
+    let amount_out_received = token_amount(user_ata_out)? - user_out_before;
+
+    update_price_cumulatives(&mut pool_data, Clock::get()?.unix_timestamp);
+
+    let (to_reserve, lp_fee) = split_lp_fee(vault_received, amount_in_received, pool_data.fee_bps)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    pool_data.add_lp_fee(a_to_b, lp_fee)?;
+
+    if a_to_b {
+        pool_data.reserve_a += to_reserve;
+        pool_data.reserve_b -= amount_out;
+    }
+    else {
+        pool_data.reserve_a -= amount_out;
+        pool_data.reserve_b += to_reserve;
+    }
+
+    let (vault_a, vault_b) = if a_to_b {
+        (vault_in, vault_out)
+    } else {
+        (vault_out, vault_in)
+    };
+
+    check_vault_balances(&pool_data, vault_a, vault_b)?;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    AmmEvent::SwapExecuted(SwapExecuted {
+        pool: *pool.key,
+        user: *user.key,
+        mint_in: *mint_in.key,
+        mint_out: *mint_out.key,
+        amount_in: amount_in_received,
+        amount_out,
+        protocol_fee,
+        reserve_a: pool_data.reserve_a,
+        reserve_b: pool_data.reserve_b,
+    }).emit();
+
+    Ok(amount_out_received)
+}
+
+// Lends `amount` of one pool token to the borrower. A FlashRepay for the same
+// pool and mint has to come later in the same transaction, which is checked
+// through the Instructions sysvar; until it runs, every other instruction
+// touching the pool's reserves is rejected.
+pub fn process_flash_loan(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let borrower = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let borrower_ata = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let instructions = next_account_info(accounts_iter)?;
+
+    if !borrower.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(AmmError::ZeroFlashLoanAmount.into());
+    }
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if pool_data.paused {
+        return Err(AmmError::PoolPaused.into());
+    }
+
+    if pool_data.flash_loan_amount > 0 {
+        return Err(AmmError::FlashLoanActive.into());
+    }
+
+    let reserve = if *mint.key == pool_data.mint_a {
+        pool_data.reserve_a
+    } else if *mint.key == pool_data.mint_b {
+        pool_data.reserve_b
+    } else {
+        return Err(AmmError::MintAddressMismatch.into());
+    };
+
+    check_token_program(token_program, mint)?;
+
+    if *vault.key != get_associated_token_address_with_program_id(pool.key, mint.key, token_program.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    if *instructions.key != instructions_sysvar::ID {
+        return Err(ProgramError::UnsupportedSysvar);
+    }
+
+    if amount > reserve {
+        return Err(AmmError::ReserveExhausted.into());
+    }
+
+    // look for the first later FlashLoan or FlashRepay on this pool; it has to
+    // be a repay of the same mint
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let mut repaid = false;
+
+    for index in current_index + 1.. {
+        let Ok(instruction) = load_instruction_at_checked(index, instructions) else {
+            break;
+        };
+
+        if instruction.program_id != *program_id 
+            || instruction.accounts.get(1).map(|meta| meta.pubkey) != Some(*pool.key) 
+        {
+            continue;
+        }
+
+        match AmmInstruction::unpack(&instruction.data) {
+            Ok(AmmInstruction::FlashRepay) => {
+                repaid = instruction.accounts.get(2).map(|meta| meta.pubkey) == Some(*mint.key);
+                break;
+            },
+            Ok(AmmInstruction::FlashLoan { .. }) => break,
+            _ => {},
+        }
+    }
+
+    if !repaid {
+        return Err(AmmError::FlashLoanNotRepaid.into());
+    }
+
+    // transfer amount of mint from vault to borrower_ata
+    invoke_signed(
+        &transfer_checked(
+            token_program.key, 
+            vault.key, 
+            mint.key, 
+            borrower_ata.key, 
+            pool.key, 
+            &[], 
+            amount, 
+            pool_data.decimals(mint.key),
+        )?, 
+        &[vault.clone(), mint.clone(), borrower_ata.clone(), pool.clone()], 
+        &[
+            &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]]
+        ],
+    )?;
+
+    pool_data.flash_loan_amount = amount;
+    pool_data.flash_loan_mint = *mint.key;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Pays back the outstanding flash loan plus the pool's fee_bps of it, rounded
+// up. The whole fee is set aside for LPs, in the vault but out of the reserves.
+// For Token-2022 mints with a transfer fee the borrower sends enough extra for
+// the vault to receive it all.
+pub fn process_flash_repay(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let borrower = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let borrower_ata = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !borrower.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if pool_data.flash_loan_amount == 0 {
+        return Err(AmmError::NoActiveFlashLoan.into());
+    }
+
+    if *mint.key != pool_data.flash_loan_mint {
+        return Err(AmmError::MintAddressMismatch.into());
+    }
+
+    check_token_program(token_program, mint)?;
+
+    if *vault.key != get_associated_token_address_with_program_id(pool.key, mint.key, token_program.key) {
+        return Err(AmmError::VaultAddressMismatch.into());
+    }
+
+    let fee = bps_ceil(pool_data.flash_loan_amount, pool_data.fee_bps).ok_or(ProgramError::ArithmeticOverflow)?;
+    let amount_due = pool_data.flash_loan_amount
+        .checked_add(fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let amount_sent = amount_before_transfer_fee(mint, Clock::get()?.epoch, amount_due)?;
+
+    let vault_before = token_amount(vault)?;
+
+    // transfer amount_sent of mint from borrower_ata to vault
+    invoke(
+        &transfer_checked(
+            token_program.key,
+            borrower_ata.key, 
+            mint.key, 
+            vault.key, 
+            borrower.key, 
+            &[], 
+            amount_sent, 
+            pool_data.decimals(mint.key),
+        )?, 
+        &[borrower_ata.clone(), mint.clone(), vault.clone(), borrower.clone()], 
+    )?;
+
+    let fee_received = (token_amount(vault)? - vault_before)
+        .checked_sub(pool_data.flash_loan_amount)
+        .ok_or(AmmError::FlashLoanNotRepaid)?;
+
+    update_price_cumulatives(&mut pool_data, Clock::get()?.unix_timestamp);
+
+    pool_data.add_lp_fee(*mint.key == pool_data.mint_a, fee_received)?;
+
+    pool_data.flash_loan_amount = 0;
+    pool_data.flash_loan_mint = Pubkey::default();
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Swap where one side is native SOL. The user's wSOL ATA is created if
+// needed, funded with amount_in lamports and synced when SOL goes in, and
+// closed back to the user once the regular Swap has run, so SOL comes out
+// unwrapped. Any wSOL already in that ATA is unwrapped along with it.
+pub fn process_swap_native(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_out: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let _pool = next_account_info(accounts_iter)?;
+    let mint_in = next_account_info(accounts_iter)?;
+    let mint_out = next_account_info(accounts_iter)?;
+    let _vault_in = next_account_info(accounts_iter)?;
+    let _vault_out = next_account_info(accounts_iter)?;
+    let user_ata_in = next_account_info(accounts_iter)?;
+    let user_ata_out = next_account_info(accounts_iter)?;
+    let _treasury_ata_in = next_account_info(accounts_iter)?;
+    let _token_program_in = next_account_info(accounts_iter)?;
+    let _token_program_out = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let native_in = *mint_in.key == native_mint::id();
+
+    let (native_mint_account, wsol_ata) = if native_in {
+        (mint_in, user_ata_in)
+    } else if *mint_out.key == native_mint::id() {
+        (mint_out, user_ata_out)
+    } else {
+        return Err(AmmError::NotNativeSwap.into());
+    };
+
+    if *wsol_ata.key != get_associated_token_address(user.key, &native_mint::id()) {
+        return Err(AmmError::WrappedSolAccountMismatch.into());
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *associated_token_program.key != associated_token_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // create wsol_ata if the user doesn't have one yet
+    invoke(
+        &create_associated_token_account_idempotent(
+            user.key, 
+            user.key, 
+            native_mint_account.key, 
+            token_program.key,
+        ), 
+        &[user.clone(), wsol_ata.clone(), native_mint_account.clone(), system_program.clone(), token_program.clone()],
+    )?;
+
+    if native_in {
+        // wrap amount_in lamports from user into wsol_ata
+        invoke(
+            &transfer(user.key, wsol_ata.key, amount_in), 
+            &[user.clone(), wsol_ata.clone()],
+        )?;
+
+        invoke(
+            &sync_native(token_program.key, wsol_ata.key)?, 
+            &[wsol_ata.clone(), token_program.clone()],
+        )?;
+    }
+
+    // the leading accounts are exactly what Swap expects
+    process_swap(program_id, &accounts[..12], amount_in, min_out, None)?;
+
+    // unwrap: closing wsol_ata sends all of its lamports to user
+    invoke(
+        &close_account(
+            token_program.key, 
+            wsol_ata.key, 
+            user.key, 
+            user.key, 
+            &[],
+        )?, 
+        &[wsol_ata.clone(), user.clone()],
+    )?;
+
+    Ok(())
+}
+
+// Single-sided deposit: swaps part of amount_in through the pool, then
+// provides the remainder together with the swap output. The split is exact
+// for constant product; other curves leave some dust with the user.
+pub fn process_zap_in(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_lp_out: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint_in = next_account_info(accounts_iter)?;
+    let mint_out = next_account_info(accounts_iter)?;
+    let vault_in = next_account_info(accounts_iter)?;
+    let vault_out = next_account_info(accounts_iter)?;
+    let user_ata_in = next_account_info(accounts_iter)?;
+    let user_ata_out = next_account_info(accounts_iter)?;
+    let _treasury_ata_in = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let token_program_out = next_account_info(accounts_iter)?;
+    let _associated_token_program = next_account_info(accounts_iter)?;
+    let mint_lp = next_account_info(accounts_iter)?;
+    let user_ata_lp = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if amount_in == 0 {
+        return Err(AmmError::ZeroSwapAmount.into());
+    }
+
+    let pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let a_to_b = *mint_in.key == pool_data.mint_a;
+
+    let reserve_in = if a_to_b { pool_data.reserve_a } else { pool_data.reserve_b };
+
+    if reserve_in == 0 {
+        return Err(AmmError::EmptyPool.into());
+    }
+
+    let swap_amount = zap_swap_amount(reserve_in, amount_in, pool_data.fee_bps)
+        .ok_or(AmmError::FeeTooHigh)?;
+
+    let out_before = token_amount(user_ata_out)?;
+
+    // the leading accounts are exactly what Swap expects, which also
+    // validates the pool, mints and vaults
+    process_swap(program_id, &accounts[..12], swap_amount, 0, None)?;
+
+    let amount_out_received = token_amount(user_ata_out)? - out_before;
+    let amount_in_left = amount_in - swap_amount;
+
+    let lp_before = token_amount(user_ata_lp)?;
+
+    let (provide_accounts, amount_a_desired, amount_b_desired) = if a_to_b {
+        (
+            [
+                user.clone(), pool.clone(), mint_in.clone(), mint_out.clone(), vault_in.clone(), vault_out.clone(), 
+                mint_lp.clone(), user_ata_lp.clone(), user_ata_in.clone(), user_ata_out.clone(), 
+                token_program.clone(), token_program_in.clone(), token_program_out.clone(),
+            ],
+            amount_in_left,
+            amount_out_received,
+        )
+    } else {
+        (
+            [
+                user.clone(), pool.clone(), mint_out.clone(), mint_in.clone(), vault_out.clone(), vault_in.clone(), 
+                mint_lp.clone(), user_ata_lp.clone(), user_ata_out.clone(), user_ata_in.clone(), 
+                token_program.clone(), token_program_out.clone(), token_program_in.clone(),
+            ],
+            amount_out_received,
+            amount_in_left,
+        )
+    };
+
+    process_provide_liquidity(program_id, &provide_accounts, amount_a_desired, amount_b_desired, 0, 0)?;
+
+    if token_amount(user_ata_lp)? - lp_before < min_lp_out {
+        return Err(AmmError::SlippageExceed.into());
+    }
+
+    Ok(())
+}
+
+// Burns `bps` of the LP the user holds, read on-chain so a balance that
+// changes between the client reading it and the transaction landing can't
+// leave LP behind or fail the burn. Takes WithdrawLiquidity's accounts.
+pub fn process_withdraw_percent(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bps: u16,
+    amount_a_min: u64,
+    amount_b_min: u64,
+) -> ProgramResult {
+    if bps == 0 || bps > 10_000 {
+        return Err(AmmError::InvalidWithdrawPercent.into());
+    }
+
+    let user_ata_lp = accounts.get(7).ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let amount_lp_in = bps_floor(token_amount(user_ata_lp)?, bps)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    process_withdraw_liquidity(program_id, accounts, amount_lp_in, amount_a_min, amount_b_min)
+}
+
+// Burns LP like WithdrawLiquidity, then swaps the unwanted side back
+// through the pool (paying the usual fees) so the user only receives
+// want_mint.
+pub fn process_withdraw_single_sided(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_lp_in: u64,
+    want_mint: Pubkey,
+    min_out: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let _mint_lp = next_account_info(accounts_iter)?;
+    let _user_ata_lp = next_account_info(accounts_iter)?;
+    let user_ata_a = next_account_info(accounts_iter)?;
+    let user_ata_b = next_account_info(accounts_iter)?;
+    let _token_program = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    let treasury_ata_in = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    let want_a = if want_mint == *mint_a.key {
+        true
+    } else if want_mint == *mint_b.key {
+        false
+    } else {
+        return Err(AmmError::MintAddressMismatch.into());
+    };
+
+    let (user_ata_in, user_ata_out) = if want_a { (user_ata_b, user_ata_a) } else { (user_ata_a, user_ata_b) };
+
+    let in_before = token_amount(user_ata_in)?;
+    let out_before = token_amount(user_ata_out)?;
+
+    process_withdraw_liquidity(program_id, &accounts[..13], amount_lp_in, 0, 0)?;
+
+    let amount_in = token_amount(user_ata_in)? - in_before;
+
+    if amount_in > 0 {
+        let swap_accounts = if want_a {
+            [
+                user.clone(), pool.clone(), mint_b.clone(), mint_a.clone(), vault_b.clone(), vault_a.clone(), 
+                user_ata_b.clone(), user_ata_a.clone(), treasury_ata_in.clone(), 
+                token_program_b.clone(), token_program_a.clone(), associated_token_program.clone(),
+            ]
+        } else {
+            [
+                user.clone(), pool.clone(), mint_a.clone(), mint_b.clone(), vault_a.clone(), vault_b.clone(), 
+                user_ata_a.clone(), user_ata_b.clone(), treasury_ata_in.clone(), 
+                token_program_a.clone(), token_program_b.clone(), associated_token_program.clone(),
+            ]
+        };
+
+        process_swap(program_id, &swap_accounts, amount_in, 0, None)?;
+    }
+
+    if token_amount(user_ata_out)? - out_before < min_out {
+        return Err(AmmError::SlippageExceed.into());
+    }
+
+    Ok(())
+}
+
+pub fn process_set_referral_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    referral_fee_bps: u16,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *admin.key != pool_data.admin {
+        return Err(AmmError::NotPoolAdmin.into());
+    }
+
+    check_admin_signature(admin, signers)?;
+
+    // protocol and referrer together can take at most the whole swap fee
+    if referral_fee_bps as u32 + pool_data.protocol_fee_bps as u32 > 10_000 {
+        return Err(AmmError::FeeTooHigh.into());
+    }
+
+    pool_data.referral_fee_bps = referral_fee_bps;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_set_fee_tiers(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_tiers: Vec<u16>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let program_data = next_account_info(accounts_iter)?;
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[b"config"], program_id);
+
+    if *config.key != config_pda {
+        return Err(AmmError::ConfigAddressMismatch.into());
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if fee_tiers.is_empty() || fee_tiers.len() > ProtocolConfig::MAX_FEE_TIERS {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // a 100% fee would leave nothing to swap
+    if fee_tiers.iter().any(|&fee_bps| fee_bps >= 10_000) {
+        return Err(AmmError::FeeTooHigh.into());
+    }
+
+    let mut config_data = load_or_create_config(program_id, admin, signers, config, config_bump, program_data)?;
+
+    config_data.fee_tiers = fee_tiers;
+
+    config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// An empty list opens pool creation to anyone again.
+pub fn process_set_pool_creators(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pool_creators: Vec<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let program_data = next_account_info(accounts_iter)?;
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[b"config"], program_id);
+
+    if *config.key != config_pda {
+        return Err(AmmError::ConfigAddressMismatch.into());
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if pool_creators.len() > ProtocolConfig::MAX_POOL_CREATORS {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut config_data = load_or_create_config(program_id, admin, signers, config, config_bump, program_data)?;
+
+    config_data.pool_creators = pool_creators;
+
+    config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Loads the config, checking `admin` is its admin, or creates it with the
+// defaults if it doesn't exist yet. Only the program's upgrade authority can
+// create it, becoming its admin, so nobody can claim the config between the
+// deploy and its setup; it pays for the account, so it has to sign itself.
+fn load_or_create_config<'a>(
+    program_id: &Pubkey,
+    admin: &AccountInfo<'a>,
+    signers: &[AccountInfo<'a>],
+    config: &AccountInfo<'a>,
+    config_bump: u8,
+    program_data: &AccountInfo<'a>,
+) -> Result<ProtocolConfig, ProgramError> {
+    if !config.data_is_empty() {
+        let config_data = ProtocolConfig::load(&config.data.borrow())?;
+
+        if *admin.key != config_data.admin {
+            return Err(AmmError::NotConfigAdmin.into());
+        }
+
+        check_admin_signature(admin, signers)?;
+
+        return Ok(config_data);
+    }
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if upgrade_authority(program_id, program_data)? != Some(*admin.key) {
+        return Err(AmmError::NotUpgradeAuthority.into());
+    }
+
+    invoke_signed(
+        &create_account(
+            admin.key, 
+            config.key, 
+            Rent::get()?.minimum_balance(ProtocolConfig::SPACE), 
+            ProtocolConfig::SPACE as u64, 
+            program_id,
+        ), 
+        &[admin.clone(), config.clone()], 
+        &[&[b"config", &[config_bump]]],
+    )?;
+
+    Ok(ProtocolConfig {
+        discriminator: ProtocolConfig::DISCRIMINATOR,
+        admin: *admin.key,
+        pending_admin: Pubkey::default(),
+        fee_tiers: ProtocolConfig::DEFAULT_FEE_TIERS.to_vec(),
+        pool_creators: Vec::new(),
+        bump: config_bump,
+    })
+}
+
+// Admins of pools and of the config change in two steps: the current admin
+// nominates a successor, which only takes over once it accepts, so a mistyped
+// key can't strand the role. Nominating again replaces the pending admin, and
+// nominating the default pubkey cancels it.
+pub fn process_nominate_admin(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_admin: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let target = next_account_info(accounts_iter)?;
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let (config_pda, _config_bump) = Pubkey::find_program_address(&[b"config"], program_id);
+
+    if *target.key == config_pda {
+        let mut config_data = ProtocolConfig::load(&target.data.borrow())?;
+
+        if *admin.key != config_data.admin {
+            return Err(AmmError::NotConfigAdmin.into());
+        }
+
+        check_admin_signature(admin, signers)?;
+
+        config_data.pending_admin = new_admin;
+
+        config_data.serialize(&mut &mut target.data.borrow_mut()[..])?;
+
+        return Ok(());
+    }
+
+    let mut pool_data = 
+        LiquidityPool::load(&target.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *target.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *admin.key != pool_data.admin {
+        return Err(AmmError::NotPoolAdmin.into());
+    }
+
+    check_admin_signature(admin, signers)?;
+
+    pool_data.pending_admin = new_admin;
+
+    pool_data.serialize(&mut &mut target.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// A multisig nominee accepts like a multisig admin acts, with its signers
+// following the fixed accounts.
+pub fn process_accept_admin(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let new_admin = next_account_info(accounts_iter)?;
+    let target = next_account_info(accounts_iter)?;
+    // signers of a multisig nominee
+    let signers = accounts_iter.as_slice();
+
+    let (config_pda, _config_bump) = Pubkey::find_program_address(&[b"config"], program_id);
+
+    if *target.key == config_pda {
+        let mut config_data = ProtocolConfig::load(&target.data.borrow())?;
+
+        if config_data.pending_admin == Pubkey::default() || *new_admin.key != config_data.pending_admin {
+            return Err(AmmError::NotPendingAdmin.into());
+        }
+
+        check_admin_signature(new_admin, signers)?;
+
+        config_data.admin = config_data.pending_admin;
+        config_data.pending_admin = Pubkey::default();
+
+        config_data.serialize(&mut &mut target.data.borrow_mut()[..])?;
+
+        return Ok(());
+    }
+
+    let mut pool_data = 
+        LiquidityPool::load(&target.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *target.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if pool_data.pending_admin == Pubkey::default() || *new_admin.key != pool_data.pending_admin {
+        return Err(AmmError::NotPendingAdmin.into());
+    }
+
+    check_admin_signature(new_admin, signers)?;
+
+    pool_data.admin = pool_data.pending_admin;
+    pool_data.pending_admin = Pubkey::default();
+
+    pool_data.serialize(&mut &mut target.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// The swap fee is part of the pool address and can't change, but the
+// protocol's share of it can, in two steps: propose now, execute once
+// FEE_CHANGE_DELAY has passed. LPs get that long to react before it applies.
+pub fn process_propose_fee_change(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    protocol_fee_bps: u16,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *admin.key != pool_data.admin {
+        return Err(AmmError::NotPoolAdmin.into());
+    }
+
+    check_admin_signature(admin, signers)?;
+
+    if protocol_fee_bps as u32 + pool_data.referral_fee_bps as u32 > 10_000 {
+        return Err(AmmError::FeeTooHigh.into());
+    }
+
+    // proposing again replaces the pending change and restarts the delay
+    pool_data.pending_protocol_fee_bps = protocol_fee_bps;
+    pool_data.fee_change_ts = Clock::get()?.unix_timestamp
+        .checked_add(FEE_CHANGE_DELAY)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_execute_fee_change(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *admin.key != pool_data.admin {
+        return Err(AmmError::NotPoolAdmin.into());
+    }
+
+    check_admin_signature(admin, signers)?;
+
+    if pool_data.fee_change_ts == 0 {
+        return Err(AmmError::NoPendingFeeChange.into());
+    }
+
+    if Clock::get()?.unix_timestamp < pool_data.fee_change_ts {
+        return Err(AmmError::FeeChangeTimelocked.into());
+    }
+
+    // the referral fee may have been raised since the proposal
+    if pool_data.pending_protocol_fee_bps as u32 + pool_data.referral_fee_bps as u32 > 10_000 {
+        return Err(AmmError::FeeTooHigh.into());
+    }
+
+    pool_data.protocol_fee_bps = pool_data.pending_protocol_fee_bps;
+    pool_data.pending_protocol_fee_bps = 0;
+    pool_data.fee_change_ts = 0;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Sets the Pyth price account that bounds swaps in this pool, or removes
+// the bound when no oracle account is passed. The oracle quotes mint_a in
+// units of mint_b.
+pub fn process_set_oracle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_deviation_bps: u16,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    // optional: omitted to remove the oracle. It never signs, which tells it
+    // apart from a multisig admin's signers after it.
+    let oracle = match accounts_iter.as_slice().first() {
+        Some(account) if !account.is_signer => next_account_info(accounts_iter).ok(),
+        _ => None,
+    };
+    // signers of a multisig admin
+    let signers = accounts_iter.as_slice();
+
+    let mut pool_data = 
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *admin.key != pool_data.admin {
+        return Err(AmmError::NotPoolAdmin.into());
+    }
+
+    check_admin_signature(admin, signers)?;
+
+    match oracle {
+        Some(oracle) => {
+            // only the layout is checked here; freshness is checked on every swap
+            PythPrice::load(&oracle.data.borrow())?;
+
+            pool_data.oracle = *oracle.key;
+            pool_data.max_oracle_deviation_bps = max_deviation_bps;
+        },
+        None => {
+            pool_data.oracle = Pubkey::default();
+            pool_data.max_oracle_deviation_bps = 0;
+        },
+    }
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Upgrades a pool account written with an older layout to the current one,
+// growing it as needed. Permissionless: the result holds the same state, and
+// the payer only covers the extra rent.
+pub fn process_migrate_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if pool.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *system_program.key != system_program_id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let pool_data = {
+        let data = pool.data.borrow();
+
+        if data.len() <= 8 || data[..8] != LiquidityPool::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        match data[8] {
+            1 => LiquidityPoolV1::load(&data)?.migrate().migrate().migrate(),
+            2 => LiquidityPoolV2::load(&data)?.migrate().migrate(),
+            3 => LiquidityPoolV3::load(&data)?.migrate(),
+            4 => LiquidityPoolV4::load(&data)?,
+            LiquidityPool::VERSION => return Err(AmmError::PoolAlreadyCurrent.into()),
+            _ => return Err(AmmError::UnknownPoolVersion.into()),
+        }
+    };
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]], 
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    if *mint_a.key != pool_data.mint_a || *mint_b.key != pool_data.mint_b {
+        return Err(AmmError::MintAddressMismatch.into());
+    }
+
+    let pool_data = pool_data.migrate(mint_decimals(mint_a)?, mint_decimals(mint_b)?);
+
+    // top up rent for the new size, then grow the account
+    let rent_shortfall = Rent::get()?.minimum_balance(LiquidityPool::SPACE).saturating_sub(pool.lamports());
+
+    if rent_shortfall > 0 {
+        invoke(
+            &transfer(payer.key, pool.key, rent_shortfall), 
+            &[payer.clone(), pool.clone()],
+        )?;
+    }
+
+    pool.resize(LiquidityPool::SPACE)?;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Checks the pool PDA and that it trades mint_in against mint_out.
+pub(crate) fn load_swap_pool(
+    program_id: &Pubkey,
+    pool: &AccountInfo,
+    mint_in: &AccountInfo,
+    mint_out: &AccountInfo,
+) -> Result<LiquidityPool, ProgramError> {
+    let pool_data =
+        LiquidityPool::load(&pool.data.borrow())?;
+
+    let a_to_b = *mint_in.key == pool_data.mint_a && *mint_out.key == pool_data.mint_b;
+    let b_to_a = *mint_in.key == pool_data.mint_b && *mint_out.key == pool_data.mint_a;
+
+    if !a_to_b && !b_to_a {
+        return Err(AmmError::MintNotInPool.into());
+    }
+
+    let (mint_lo, mint_hi) = if pool_data.mint_a < pool_data.mint_b {
+        (pool_data.mint_a, pool_data.mint_b)
+    } else {
+        (pool_data.mint_b, pool_data.mint_a)
+    };
+
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", mint_lo.as_ref(), mint_hi.as_ref(), &pool_data.fee_bps.to_le_bytes(), &[pool_data.bump]],
+        program_id,
+    ).map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if expected_pool != *pool.key {
+        return Err(AmmError::PoolAddressMismatch.into());
+    }
+
+    Ok(pool_data)
+}
+
+// Fails if either vault holds less than the pool has recorded for it, as a
+// transfer that skimmed a fee or went to the wrong account would leave it.
+pub(crate) fn check_vault_balances(
+    pool_data: &LiquidityPool,
+    vault_a: &AccountInfo,
+    vault_b: &AccountInfo,
+) -> ProgramResult {
+    let recorded_a = pool_data.reserve_a.checked_add(pool_data.fees_a).ok_or(ProgramError::ArithmeticOverflow)?;
+    let recorded_b = pool_data.reserve_b.checked_add(pool_data.fees_b).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if token_amount(vault_a)? < recorded_a || token_amount(vault_b)? < recorded_b {
+        return Err(AmmError::VaultBalanceMismatch.into());
+    }
+
+    Ok(())
+}
+
+// Runs this program's own Swap with a PDA as the user, so tokens the PDA
+// escrows can be traded without their owner signing. `swap_accounts` are
+// Swap's accounts in order, starting with the PDA; trailing ones (the
+// pool's oracle) are passed read-only.
+pub(crate) fn invoke_swap_signed<'a>(
+    program_id: &Pubkey,
+    amm_program: &AccountInfo<'a>,
+    swap_accounts: &[AccountInfo<'a>],
+    amount_in: u64,
+    min_out: u64,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    if *amm_program.key != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut data = vec![3];
+    SwapPayload { amount_in, min_out }.serialize(&mut data)?;
+
+    let metas = swap_accounts.iter().enumerate().map(|(index, account)| match index {
+        0 => AccountMeta::new(*account.key, true),
+        // pool, vaults, user token accounts and treasury
+        1 | 4..=8 => AccountMeta::new(*account.key, false),
+        _ => AccountMeta::new_readonly(*account.key, false),
+    }).collect();
+
+    let mut account_infos = swap_accounts.to_vec();
+    account_infos.push(amm_program.clone());
+
+    invoke_signed(
+        &Instruction::new_with_bytes(*program_id, &data, metas),
+        &account_infos,
+        &[signer_seeds],
+    )
+}
+
+// Closes a program-owned account: `bounty` of its lamports go to the
+// cranker, if any, and the rest to the owner.
+pub(crate) fn close_program_account(
+    account: &AccountInfo,
+    owner: &AccountInfo,
+    cranker: Option<(&AccountInfo, u64)>,
+) -> ProgramResult {
+    let lamports = account.lamports();
+    let bounty = cranker.map_or(0, |(_, bounty)| bounty.min(lamports));
+
+    if let Some((cranker, _)) = cranker {
+        **cranker.try_borrow_mut_lamports()? += bounty;
+    }
+    **owner.try_borrow_mut_lamports()? += lamports - bounty;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    account.resize(0)?;
+    account.assign(&system_program_id());
+
+    Ok(())
+}
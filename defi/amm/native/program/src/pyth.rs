@@ -0,0 +1,123 @@
+// Just enough of Pyth's v2 price account layout to read the aggregate price.
+// pyth-sdk-solana pins solana-program 1.x, so the fields are read by offset.
+
+use solana_program::program_error::ProgramError;
+
+use crate::{error::AmmError, math::U256};
+
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_VERSION: u32 = 2;
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+
+// Header and aggregate price, everything after is per-publisher data.
+pub const PYTH_PRICE_ACCOUNT_LEN: usize = 240;
+
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+
+// A price published longer ago than this, in seconds, is stale.
+pub const MAX_ORACLE_AGE: i64 = 60;
+
+// Prices whose confidence interval is wider than this share of the price
+// are too uncertain to bound a swap with.
+pub const MAX_ORACLE_CONFIDENCE_BPS: u64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PythPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub status: u32,
+}
+
+impl PythPrice {
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < PYTH_PRICE_ACCOUNT_LEN {
+            return Err(AmmError::InvalidOracle.into());
+        }
+
+        if read_u32(data, 0) != PYTH_MAGIC
+            || read_u32(data, 4) != PYTH_VERSION
+            || read_u32(data, 8) != PYTH_PRICE_ACCOUNT_TYPE
+        {
+            return Err(AmmError::InvalidOracle.into());
+        }
+
+        Ok(Self {
+            price: read_u64(data, AGG_PRICE_OFFSET) as i64,
+            conf: read_u64(data, AGG_CONF_OFFSET),
+            expo: read_u32(data, EXPO_OFFSET) as i32,
+            publish_time: read_u64(data, TIMESTAMP_OFFSET) as i64,
+            status: read_u32(data, AGG_STATUS_OFFSET),
+        })
+    }
+
+    // The price if it's trading, fresh at `now` and tight enough to rely on.
+    pub fn checked(&self, now: i64) -> Result<Self, ProgramError> {
+        if self.status != PYTH_STATUS_TRADING || self.price <= 0 {
+            return Err(AmmError::InvalidOracle.into());
+        }
+
+        if now.saturating_sub(self.publish_time) > MAX_ORACLE_AGE {
+            return Err(AmmError::OracleStale.into());
+        }
+
+        if self.conf as u128 * 10_000 > self.price as u128 * MAX_ORACLE_CONFIDENCE_BPS as u128 {
+            return Err(AmmError::OracleConfidenceTooWide.into());
+        }
+
+        Ok(*self)
+    }
+}
+
+// How far a trade of amount_a for amount_b (raw units, either direction)
+// is from the oracle's price of A in B, in bps. The oracle quotes whole
+// tokens, so its price is scaled by the difference in decimals. The swap
+// fee counts towards the deviation.
+pub fn deviation_bps(
+    oracle: &PythPrice,
+    decimals_a: u8,
+    decimals_b: u8,
+    amount_a: u64,
+    amount_b: u64,
+) -> Result<u64, ProgramError> {
+    let scale = oracle.expo as i64 + decimals_b as i64 - decimals_a as i64;
+
+    // 10^30 still leaves room for price * amount * 10_000 in a U256
+    if scale.abs() > 30 {
+        return Err(AmmError::InvalidOracle.into());
+    }
+
+    let power = U256::from(10u64).pow(U256::from(scale.unsigned_abs()));
+
+    let (oracle_num, oracle_den) = if scale >= 0 {
+        (U256::from(oracle.price as u64) * power, U256::one())
+    } else {
+        (U256::from(oracle.price as u64), power)
+    };
+
+    // amount_b / amount_a against oracle_num / oracle_den
+    let expected = oracle_num * U256::from(amount_a);
+    let actual = U256::from(amount_b) * oracle_den;
+
+    if expected.is_zero() {
+        return Ok(u64::MAX);
+    }
+
+    let difference = if actual > expected { actual - expected } else { expected - actual };
+
+    Ok((difference * U256::from(10_000u64) / expected).min(U256::from(u64::MAX)).as_u64())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
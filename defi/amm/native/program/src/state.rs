@@ -1,23 +1,550 @@
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    borsh1::try_from_slice_unchecked,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
 use borsh::{BorshSerialize, BorshDeserialize};
+use shank::ShankAccount;
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+use crate::error::AmmError;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveType {
+    ConstantProduct,
+    Stable {
+        amp: u64,
+    },
+    ConstantSum,
+    Weighted {
+        weight_a_bps: u16,
+        weight_b_bps: u16,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
 pub struct LiquidityPool {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
+    // cached at creation so transfers needn't read the mints
+    pub decimals_a: u8,
+    pub decimals_b: u8,
     pub reserve_a: u64,
     pub reserve_b: u64,
+    // LP fees held in the vaults on top of the reserves, owed to LPs pro rata
+    pub fees_a: u64,
+    pub fees_b: u64,
+    // every LP fee ever earned, for measuring fee APR off-chain
+    pub fees_total_a: u128,
+    pub fees_total_b: u128,
     pub fee_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub referral_fee_bps: u16,
+    pub curve: CurveType,
+    pub admin: Pubkey,
+    // nominated by NominateAdmin, becomes admin once it accepts
+    pub pending_admin: Pubkey,
+    pub paused: bool,
+    pub price_cumulative_a: u128,
+    pub price_cumulative_b: u128,
+    pub last_update_ts: i64,
+    pub flash_loan_amount: u64,
+    pub flash_loan_mint: Pubkey,
+    pub pending_protocol_fee_bps: u16,
+    pub fee_change_ts: i64,
+    pub oracle: Pubkey,
+    pub max_oracle_deviation_bps: u16,
     pub bump: u8,
 }
 
+impl CurveType {
+    pub const SPACE: usize = 
+        1        // variant
+        + 8;     // amp
+}
+
 impl LiquidityPool {
+    pub const DISCRIMINATOR: [u8; 8] = *b"amm_pool";
+
+    pub const VERSION: u8 = 5;
+
     pub const SPACE: usize = 
-        32       // mint_a pubkey
+        8        // discriminator
+        + 1      // version
+        + 1      // is_initialized
+        + 32     // mint_a pubkey
         + 32     // mint_b pubkey
+        + 1      // decimals_a
+        + 1      // decimals_b
         + 8      // reserve_a 
         + 8      // reserve_b 
+        + 8      // fees_a
+        + 8      // fees_b
+        + 16     // fees_total_a
+        + 16     // fees_total_b
         + 2      // fee_bps
+        + 2      // protocol_fee_bps
+        + 2      // referral_fee_bps
+        + CurveType::SPACE
+        + 32     // admin pubkey
+        + 32     // pending_admin pubkey
+        + 1      // paused
+        + 16     // price_cumulative_a
+        + 16     // price_cumulative_b
+        + 8      // last_update_ts
+        + 8      // flash_loan_amount
+        + 32     // flash_loan_mint pubkey
+        + 2      // pending_protocol_fee_bps
+        + 8      // fee_change_ts
+        + 32     // oracle pubkey
+        + 2      // max_oracle_deviation_bps
+        + 1;     // bump
+
+    // Deserializes a pool account, rejecting anything that isn't an
+    // initialized LiquidityPool of the current version.
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() <= 8 || data[..8] != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // older layouts have to go through MigratePool first
+        if data[8] != Self::VERSION {
+            return Err(AmmError::PoolNeedsMigration.into());
+        }
+
+        let pool = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !pool.is_initialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Ok(pool)
+    }
+
+    // Decimals of `mint`, which the caller has checked is one of the pool's.
+    pub fn decimals(&self, mint: &Pubkey) -> u8 {
+        if *mint == self.mint_a {
+            self.decimals_a
+        } else {
+            self.decimals_b
+        }
+    }
+
+    // Sets `fee` aside for LPs, in mint A if `in_a` and otherwise mint B.
+    pub fn add_lp_fee(&mut self, in_a: bool, fee: u64) -> Result<(), ProgramError> {
+        let (fees, fees_total) = if in_a {
+            (&mut self.fees_a, &mut self.fees_total_a)
+        } else {
+            (&mut self.fees_b, &mut self.fees_total_b)
+        };
+
+        *fees = fees.checked_add(fee).ok_or(ProgramError::ArithmeticOverflow)?;
+        *fees_total = fees_total.saturating_add(fee as u128);
+
+        Ok(())
+    }
+}
+
+// Layout of pools created before LiquidityPool carried a version. Its
+// is_initialized byte sits where `version` is now and is always 1 in a live
+// pool, so these read as version 1.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct LiquidityPoolV1 {
+    pub discriminator: [u8; 8],
+    pub is_initialized: bool,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub fee_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub referral_fee_bps: u16,
+    pub curve: CurveType,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub price_cumulative_a: u128,
+    pub price_cumulative_b: u128,
+    pub last_update_ts: i64,
+    pub flash_loan_amount: u64,
+    pub flash_loan_mint: Pubkey,
+    pub pending_protocol_fee_bps: u16,
+    pub fee_change_ts: i64,
+    pub oracle: Pubkey,
+    pub max_oracle_deviation_bps: u16,
+    pub bump: u8,
+}
+
+impl LiquidityPoolV1 {
+    pub const SPACE: usize = LiquidityPoolV2::SPACE - 1;
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let pool = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if pool.discriminator != LiquidityPool::DISCRIMINATOR || !pool.is_initialized {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(pool)
+    }
+
+    // Fields a later version adds get their defaults here.
+    pub fn migrate(self) -> LiquidityPoolV2 {
+        LiquidityPoolV2 {
+            discriminator: self.discriminator,
+            version: 2,
+            is_initialized: self.is_initialized,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            reserve_a: self.reserve_a,
+            reserve_b: self.reserve_b,
+            fee_bps: self.fee_bps,
+            protocol_fee_bps: self.protocol_fee_bps,
+            referral_fee_bps: self.referral_fee_bps,
+            curve: self.curve,
+            admin: self.admin,
+            paused: self.paused,
+            price_cumulative_a: self.price_cumulative_a,
+            price_cumulative_b: self.price_cumulative_b,
+            last_update_ts: self.last_update_ts,
+            flash_loan_amount: self.flash_loan_amount,
+            flash_loan_mint: self.flash_loan_mint,
+            pending_protocol_fee_bps: self.pending_protocol_fee_bps,
+            fee_change_ts: self.fee_change_ts,
+            oracle: self.oracle,
+            max_oracle_deviation_bps: self.max_oracle_deviation_bps,
+            bump: self.bump,
+        }
+    }
+}
+
+// Version 2 layout, from before pools could have a pending admin.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct LiquidityPoolV2 {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub fee_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub referral_fee_bps: u16,
+    pub curve: CurveType,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub price_cumulative_a: u128,
+    pub price_cumulative_b: u128,
+    pub last_update_ts: i64,
+    pub flash_loan_amount: u64,
+    pub flash_loan_mint: Pubkey,
+    pub pending_protocol_fee_bps: u16,
+    pub fee_change_ts: i64,
+    pub oracle: Pubkey,
+    pub max_oracle_deviation_bps: u16,
+    pub bump: u8,
+}
+
+impl LiquidityPoolV2 {
+    pub const SPACE: usize = LiquidityPoolV3::SPACE - 32;
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let pool = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if pool.discriminator != LiquidityPool::DISCRIMINATOR || pool.version != 2 || !pool.is_initialized {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(pool)
+    }
+
+    pub fn migrate(self) -> LiquidityPoolV3 {
+        LiquidityPoolV3 {
+            discriminator: self.discriminator,
+            version: 3,
+            is_initialized: self.is_initialized,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            reserve_a: self.reserve_a,
+            reserve_b: self.reserve_b,
+            fee_bps: self.fee_bps,
+            protocol_fee_bps: self.protocol_fee_bps,
+            referral_fee_bps: self.referral_fee_bps,
+            curve: self.curve,
+            admin: self.admin,
+            pending_admin: Pubkey::default(),
+            paused: self.paused,
+            price_cumulative_a: self.price_cumulative_a,
+            price_cumulative_b: self.price_cumulative_b,
+            last_update_ts: self.last_update_ts,
+            flash_loan_amount: self.flash_loan_amount,
+            flash_loan_mint: self.flash_loan_mint,
+            pending_protocol_fee_bps: self.pending_protocol_fee_bps,
+            fee_change_ts: self.fee_change_ts,
+            oracle: self.oracle,
+            max_oracle_deviation_bps: self.max_oracle_deviation_bps,
+            bump: self.bump,
+        }
+    }
+}
+
+// Version 3 layout, from before LP fees were kept out of the reserves.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct LiquidityPoolV3 {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub fee_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub referral_fee_bps: u16,
+    pub curve: CurveType,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub paused: bool,
+    pub price_cumulative_a: u128,
+    pub price_cumulative_b: u128,
+    pub last_update_ts: i64,
+    pub flash_loan_amount: u64,
+    pub flash_loan_mint: Pubkey,
+    pub pending_protocol_fee_bps: u16,
+    pub fee_change_ts: i64,
+    pub oracle: Pubkey,
+    pub max_oracle_deviation_bps: u16,
+    pub bump: u8,
+}
+
+impl LiquidityPoolV3 {
+    pub const SPACE: usize = LiquidityPoolV4::SPACE - 48;
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let pool = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if pool.discriminator != LiquidityPool::DISCRIMINATOR || pool.version != 3 || !pool.is_initialized {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(pool)
+    }
+
+    // Fees earned so far are already part of the reserves and stay there.
+    pub fn migrate(self) -> LiquidityPoolV4 {
+        LiquidityPoolV4 {
+            discriminator: self.discriminator,
+            version: 4,
+            is_initialized: self.is_initialized,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            reserve_a: self.reserve_a,
+            reserve_b: self.reserve_b,
+            fees_a: 0,
+            fees_b: 0,
+            fees_total_a: 0,
+            fees_total_b: 0,
+            fee_bps: self.fee_bps,
+            protocol_fee_bps: self.protocol_fee_bps,
+            referral_fee_bps: self.referral_fee_bps,
+            curve: self.curve,
+            admin: self.admin,
+            pending_admin: self.pending_admin,
+            paused: self.paused,
+            price_cumulative_a: self.price_cumulative_a,
+            price_cumulative_b: self.price_cumulative_b,
+            last_update_ts: self.last_update_ts,
+            flash_loan_amount: self.flash_loan_amount,
+            flash_loan_mint: self.flash_loan_mint,
+            pending_protocol_fee_bps: self.pending_protocol_fee_bps,
+            fee_change_ts: self.fee_change_ts,
+            oracle: self.oracle,
+            max_oracle_deviation_bps: self.max_oracle_deviation_bps,
+            bump: self.bump,
+        }
+    }
+}
+
+// Version 4 layout, from before mint decimals were cached in the pool.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct LiquidityPoolV4 {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub fees_a: u64,
+    pub fees_b: u64,
+    pub fees_total_a: u128,
+    pub fees_total_b: u128,
+    pub fee_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub referral_fee_bps: u16,
+    pub curve: CurveType,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub paused: bool,
+    pub price_cumulative_a: u128,
+    pub price_cumulative_b: u128,
+    pub last_update_ts: i64,
+    pub flash_loan_amount: u64,
+    pub flash_loan_mint: Pubkey,
+    pub pending_protocol_fee_bps: u16,
+    pub fee_change_ts: i64,
+    pub oracle: Pubkey,
+    pub max_oracle_deviation_bps: u16,
+    pub bump: u8,
+}
+
+impl LiquidityPoolV4 {
+    pub const SPACE: usize = LiquidityPool::SPACE - 2;
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let pool = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if pool.discriminator != LiquidityPool::DISCRIMINATOR || pool.version != 4 || !pool.is_initialized {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(pool)
+    }
+
+    pub fn migrate(self, decimals_a: u8, decimals_b: u8) -> LiquidityPool {
+        LiquidityPool {
+            discriminator: self.discriminator,
+            version: LiquidityPool::VERSION,
+            is_initialized: self.is_initialized,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            decimals_a,
+            decimals_b,
+            reserve_a: self.reserve_a,
+            reserve_b: self.reserve_b,
+            fees_a: self.fees_a,
+            fees_b: self.fees_b,
+            fees_total_a: self.fees_total_a,
+            fees_total_b: self.fees_total_b,
+            fee_bps: self.fee_bps,
+            protocol_fee_bps: self.protocol_fee_bps,
+            referral_fee_bps: self.referral_fee_bps,
+            curve: self.curve,
+            admin: self.admin,
+            pending_admin: self.pending_admin,
+            paused: self.paused,
+            price_cumulative_a: self.price_cumulative_a,
+            price_cumulative_b: self.price_cumulative_b,
+            last_update_ts: self.last_update_ts,
+            flash_loan_amount: self.flash_loan_amount,
+            flash_loan_mint: self.flash_loan_mint,
+            pending_protocol_fee_bps: self.pending_protocol_fee_bps,
+            fee_change_ts: self.fee_change_ts,
+            oracle: self.oracle,
+            max_oracle_deviation_bps: self.max_oracle_deviation_bps,
+            bump: self.bump,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegistryEntry {
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub fee_bps: u16,
+}
+
+// Every pool the program has created, in creation order. Grows by one entry
+// per CreatePool, so clients can list pools by reading a single account.
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+pub struct PoolRegistry {
+    pub discriminator: [u8; 8],
+    pub bump: u8,
+    pub pools: Vec<RegistryEntry>,
+}
+
+impl RegistryEntry {
+    pub const SPACE: usize = 
+        32       // pool pubkey
+        + 32     // mint_a pubkey
+        + 32     // mint_b pubkey
+        + 2;     // fee_bps
+}
+
+impl PoolRegistry {
+    pub const DISCRIMINATOR: [u8; 8] = *b"amm_regy";
+
+    pub fn space(pool_count: usize) -> usize {
+        8        // discriminator
+        + 1      // bump
+        + 4      // pools length
+        + pool_count * RegistryEntry::SPACE
+    }
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let registry = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if registry.discriminator != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(registry)
+    }
+}
+
+// Program-wide settings. Until SetFeeTiers or SetPoolCreators first creates
+// it, CreatePool falls back to DEFAULT_FEE_TIERS and anyone may create pools.
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+pub struct ProtocolConfig {
+    pub discriminator: [u8; 8],
+    pub admin: Pubkey,
+    // nominated by NominateAdmin, becomes admin once it accepts
+    pub pending_admin: Pubkey,
+    pub fee_tiers: Vec<u16>,
+    // empty leaves pool creation permissionless
+    pub pool_creators: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    pub const DISCRIMINATOR: [u8; 8] = *b"amm_conf";
+
+    pub const DEFAULT_FEE_TIERS: [u16; 4] = [1, 5, 30, 100];
+    pub const MAX_FEE_TIERS: usize = 8;
+    pub const MAX_POOL_CREATORS: usize = 16;
+
+    pub const SPACE: usize = 
+        8        // discriminator
+        + 32     // admin pubkey
+        + 32     // pending_admin pubkey
+        + 4      // fee_tiers length
+        + 2 * Self::MAX_FEE_TIERS
+        + 4      // pool_creators length
+        + 32 * Self::MAX_POOL_CREATORS
         + 1;     // bump
-}
\ No newline at end of file
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let config = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if config.discriminator != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(config)
+    }
+
+    pub fn can_create_pool(&self, creator: &Pubkey) -> bool {
+        self.pool_creators.is_empty() || self.pool_creators.contains(creator)
+    }
+}
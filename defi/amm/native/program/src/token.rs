@@ -0,0 +1,58 @@
+// Pool tokens can be owned by SPL Token or Token-2022. Token-2022 accounts may
+// carry extensions after the base layout, so everything here unpacks through
+// StateWithExtensions, which reads plain SPL Token accounts just as well.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+};
+
+use spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig,
+        BaseStateWithExtensions,
+        StateWithExtensions,
+    },
+    state::{Account as TokenAccount, Mint},
+};
+
+pub fn check_token_program(token_program: &AccountInfo, mint: &AccountInfo) -> ProgramResult {
+    if *token_program.key != spl_token::id() && *token_program.key != spl_token_2022::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if mint.owner != token_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    Ok(())
+}
+
+pub fn mint_decimals(mint: &AccountInfo) -> Result<u8, ProgramError> {
+    Ok(StateWithExtensions::<Mint>::unpack(&mint.data.borrow())?.base.decimals)
+}
+
+pub fn token_amount(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(StateWithExtensions::<TokenAccount>::unpack(&token_account.data.borrow())?.base.amount)
+}
+
+pub fn token_account_state(token_account: &AccountInfo) -> Result<TokenAccount, ProgramError> {
+    Ok(StateWithExtensions::<TokenAccount>::unpack(&token_account.data.borrow())?.base)
+}
+
+// How much has to be sent so that `amount` arrives after the mint's transfer
+// fee, if it has one.
+pub fn amount_before_transfer_fee(mint: &AccountInfo, epoch: u64, amount: u64) -> Result<u64, ProgramError> {
+    let mint_data = mint.data.borrow();
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+    let Ok(transfer_fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(amount);
+    };
+
+    let fee = transfer_fee_config.calculate_inverse_epoch_fee(epoch, amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    amount.checked_add(fee).ok_or(ProgramError::ArithmeticOverflow)
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::AmmError;
+use program::state::ProtocolConfig;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn pool_admin_transfer_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let new_admin = Keypair::new();
+
+    send(&mut context, &[nominate_admin_ix(&program_id, &payer, &pool, &new_admin.pubkey())?], &[]).await?;
+
+    // nothing changes until the nominee accepts
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.admin, payer);
+    assert_eq!(pool_data.pending_admin, new_admin.pubkey());
+
+    send(&mut context, &[accept_admin_ix(&program_id, &new_admin.pubkey(), &pool)], &[&new_admin]).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.admin, new_admin.pubkey());
+    assert_eq!(pool_data.pending_admin, Pubkey::default());
+
+    let result = send(&mut context, &[set_paused_ix(&program_id, &payer, &pool, true)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+
+    send(&mut context, &[set_paused_ix(&program_id, &new_admin.pubkey(), &pool, true)], &[&new_admin]).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn nominate_admin_not_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let attacker = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[nominate_admin_ix(&program_id, &attacker.pubkey(), &pool, &attacker.pubkey())?],
+        &[&attacker],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+
+    // with nobody nominated, nobody can accept
+    let result = send(&mut context, &[accept_admin_ix(&program_id, &attacker.pubkey(), &pool)], &[&attacker]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPendingAdmin as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn overwrite_nomination_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let mistyped = Keypair::new();
+    let intended = Keypair::new();
+
+    send(&mut context, &[nominate_admin_ix(&program_id, &payer, &pool, &mistyped.pubkey())?], &[]).await?;
+    send(&mut context, &[nominate_admin_ix(&program_id, &payer, &pool, &intended.pubkey())?], &[]).await?;
+
+    let result = send(&mut context, &[accept_admin_ix(&program_id, &mistyped.pubkey(), &pool)], &[&mistyped]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPendingAdmin as u32));
+
+    send(&mut context, &[accept_admin_ix(&program_id, &intended.pubkey(), &pool)], &[&intended]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.admin, intended.pubkey());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_nomination_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let nominee = Keypair::new();
+
+    send(&mut context, &[nominate_admin_ix(&program_id, &payer, &pool, &nominee.pubkey())?], &[]).await?;
+    send(&mut context, &[nominate_admin_ix(&program_id, &payer, &pool, &Pubkey::default())?], &[]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.pending_admin, Pubkey::default());
+
+    let result = send(&mut context, &[accept_admin_ix(&program_id, &nominee.pubkey(), &pool)], &[&nominee]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPendingAdmin as u32));
+    assert_eq!(pool_state(&mut context, &pool).await?.admin, payer);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn config_admin_transfer_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+    let config = config_address(&program_id);
+
+    send(&mut context, &[set_fee_tiers_ix(&program_id, &payer, &[1, 30])?], &[]).await?;
+
+    let new_admin = Keypair::new();
+
+    send(&mut context, &[nominate_admin_ix(&program_id, &payer, &config, &new_admin.pubkey())?], &[]).await?;
+    send(&mut context, &[accept_admin_ix(&program_id, &new_admin.pubkey(), &config)], &[&new_admin]).await?;
+
+    let account = context.banks_client.get_account(config).await?.unwrap();
+    let config_data = ProtocolConfig::load(&account.data)?;
+
+    assert_eq!(config_data.admin, new_admin.pubkey());
+    assert_eq!(config_data.pending_admin, Pubkey::default());
+    assert_eq!(config_data.fee_tiers, vec![1, 30]);
+
+    let result = send(&mut context, &[set_fee_tiers_ix(&program_id, &payer, &[5])?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotConfigAdmin as u32));
+
+    Ok(())
+}
@@ -0,0 +1,365 @@
+#![allow(dead_code)]
+
+use anyhow::Result;
+
+use solana_program_test::*;
+
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_sdk::{
+    account::Account,
+    borsh1::try_from_slice_unchecked,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::instruction::create_account;
+use spl_associated_token_account::{
+    get_associated_token_address,
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{
+    id as token_program_id,
+    instruction::{initialize_mint2, transfer},
+    state::Mint,
+};
+use spl_token_2022::{
+    extension::{
+        transfer_fee::instruction::initialize_transfer_fee_config,
+        ExtensionType,
+        StateWithExtensions,
+    },
+    id as token_2022_program_id,
+    instruction::mint_to,
+    state::{Account as TokenAccount, Mint as Token2022Mint},
+};
+
+use program::processor::process_instruction;
+use program::state::{LiquidityPool, CurveType};
+
+pub use program::client::*;
+
+pub const DECIMALS: u8 = 6;
+pub const USER_FUNDS: u64 = 10_000_000_000;
+pub const INITIAL_LIQUIDITY: u64 = 1_000_000_000;
+
+pub async fn start(program_id: &Pubkey) -> ProgramTestContext {
+    let mut context = ProgramTest::new(
+        "program",
+        *program_id,
+        processor!(process_instruction),
+    ).start_with_context().await;
+
+    // processor! doesn't deploy through the upgradeable loader, so the
+    // ProgramData account the config checks is made up here, with the payer
+    // as upgrade authority
+    let program_data = program_data_account(&context.payer.pubkey());
+    context.set_account(&get_program_data_address(program_id), &program_data.into());
+
+    context
+}
+
+// A ProgramData account as the upgradeable loader lays it out, minus the code.
+pub fn program_data_account(upgrade_authority: &Pubkey) -> Account {
+    let mut data = vec![0; UpgradeableLoaderState::size_of_programdata_metadata()];
+    data[..4].copy_from_slice(&3u32.to_le_bytes());
+    data[12] = 1;
+    data[13..].copy_from_slice(upgrade_authority.as_ref());
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: solana_sdk_ids::bpf_loader_upgradeable::id(),
+        ..Account::default()
+    }
+}
+
+// Fetches a fresh blockhash for every transaction so that repeating an
+// identical instruction isn't rejected as already processed.
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await?;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub fn custom_error(result: Result<(), BanksClientError>) -> Option<u32> {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+pub fn instruction_error(result: Result<(), BanksClientError>) -> Option<InstructionError> {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, error) => Some(error),
+        _ => None,
+    }
+}
+
+pub async fn create_mint(context: &mut ProgramTestContext) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[
+            create_account(
+                &payer,
+                &mint.pubkey(),
+                Rent::default().minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &token_program_id(),
+            ),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer, None, DECIMALS)?,
+        ],
+        &[&mint],
+    ).await?;
+
+    Ok(mint.pubkey())
+}
+
+// Token-2022 mint charging `transfer_fee_bps` of every transfer, up to `maximum_fee`.
+pub async fn create_transfer_fee_mint(
+    context: &mut ProgramTestContext,
+    transfer_fee_bps: u16,
+    maximum_fee: u64,
+) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+
+    let space = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[ExtensionType::TransferFeeConfig])?;
+
+    send(
+        context,
+        &[
+            create_account(
+                &payer,
+                &mint.pubkey(),
+                Rent::default().minimum_balance(space),
+                space as u64,
+                &token_2022_program_id(),
+            ),
+            initialize_transfer_fee_config(
+                &token_2022_program_id(),
+                &mint.pubkey(),
+                Some(&payer),
+                Some(&payer),
+                transfer_fee_bps,
+                maximum_fee,
+            )?,
+            spl_token_2022::instruction::initialize_mint2(
+                &token_2022_program_id(),
+                &mint.pubkey(),
+                &payer,
+                None,
+                DECIMALS,
+            )?,
+        ],
+        &[&mint],
+    ).await?;
+
+    Ok(mint.pubkey())
+}
+
+pub async fn create_ata(context: &mut ProgramTestContext, owner: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+    create_ata_with_token_program(context, owner, mint, &token_program_id()).await
+}
+
+pub async fn create_ata_with_token_program(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[create_associated_token_account_idempotent(&payer, owner, mint, token_program)],
+        &[],
+    ).await?;
+
+    Ok(get_associated_token_address_with_program_id(owner, mint, token_program))
+}
+
+// Only works for mints created by `create_mint`, whose authority is the payer.
+pub async fn create_funded_ata(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey> {
+    create_funded_ata_with_token_program(context, owner, mint, &token_program_id(), amount).await
+}
+
+// Same for `create_transfer_fee_mint` mints, with the Token-2022 program.
+pub async fn create_funded_ata_with_token_program(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+    let ata = create_ata_with_token_program(context, owner, mint, token_program).await?;
+
+    send(
+        context,
+        &[mint_to(token_program, mint, &ata, &payer, &[], amount)?],
+        &[],
+    ).await?;
+
+    Ok(ata)
+}
+
+// Creates two mints, funds the payer with USER_FUNDS of each and creates a
+// constant-product pool seeded with INITIAL_LIQUIDITY on both sides.
+// Returns (mint_a, mint_b, pool).
+pub async fn setup_pool(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+) -> Result<(Pubkey, Pubkey, Pubkey)> {
+    setup_pool_with_curve(context, program_id, fee_bps, protocol_fee_bps, CurveType::ConstantProduct).await
+}
+
+pub async fn setup_pool_with_curve(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+    curve: CurveType,
+) -> Result<(Pubkey, Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_mint(context).await?;
+    let mint_b = create_mint(context).await?;
+
+    create_funded_ata(context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(context, &payer, &mint_b, USER_FUNDS).await?;
+
+    send(
+        context,
+        &[
+            create_pool_ix(
+                program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                fee_bps,
+                protocol_fee_bps,
+                curve,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    Ok((mint_a, mint_b, pool_address(program_id, &mint_a, &mint_b, fee_bps)))
+}
+
+// Sends tokens from the payer straight to `to`, bypassing the pool.
+pub async fn donate(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    to: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[
+            transfer(
+                &token_program_id(),
+                &get_associated_token_address(&payer, mint),
+                to,
+                &payer,
+                &[],
+                amount,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+// Moves the clock forward by `seconds`, for anything that depends on elapsed time.
+pub async fn warp_forward(context: &mut ProgramTestContext, seconds: i64) -> Result<()> {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+    clock.unix_timestamp += seconds;
+
+    context.set_sysvar(&clock);
+
+    Ok(())
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> Result<u64> {
+    let account = context.banks_client.get_account(*address).await?.unwrap();
+
+    Ok(StateWithExtensions::<TokenAccount>::unpack(&account.data)?.base.amount)
+}
+
+pub async fn mint_supply(context: &mut ProgramTestContext, mint: &Pubkey) -> Result<u64> {
+    let account = context.banks_client.get_account(*mint).await?.unwrap();
+
+    Ok(StateWithExtensions::<Token2022Mint>::unpack(&account.data)?.base.supply)
+}
+
+pub async fn pool_state(context: &mut ProgramTestContext, pool: &Pubkey) -> Result<LiquidityPool> {
+    let account = context.banks_client.get_account(*pool).await?.unwrap();
+
+    Ok(try_from_slice_unchecked::<LiquidityPool>(&account.data)?)
+}
+
+// xorshift64, so a failing random sequence can be replayed from its seed.
+// The seed must be non-zero.
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // 1..=max, skewed towards small amounts with the odd one near the top
+    pub fn amount(&mut self, max: u64) -> u64 {
+        let max = max.max(1);
+
+        match self.next() % 4 {
+            0 => 1 + self.next() % max.min(1_000),
+            3 => 1 + self.next() % max,
+            _ => 1 + self.next() % (max / 100).max(1),
+        }
+    }
+}
+
+// Same constant-product formula the program uses for swaps.
+pub fn quote(reserve_in: u64, reserve_out: u64, amount_in: u64, fee_bps: u16) -> u64 {
+    let amount_in_post_fee = amount_in as u128 * (10_000 - fee_bps as u128);
+
+    (reserve_out as u128 * amount_in_post_fee / (reserve_in as u128 * 10_000 + amount_in_post_fee)) as u64
+}
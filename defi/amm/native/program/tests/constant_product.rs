@@ -0,0 +1,102 @@
+use proptest::prelude::*;
+
+use program::curve::{initial_lp_amount, swap_output};
+use program::math::{deposit_amounts, lp_for_deposit, withdraw_amounts};
+use program::state::CurveType;
+
+// Reserves a real pool can hold: at least enough for MINIMUM_LIQUIDITY.
+fn reserve() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        1_000u64..1_000_000,
+        1_000_000u64..1_000_000_000_000,
+        1_000_000_000_000u64..=u64::MAX,
+    ]
+}
+
+fn extreme() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        Just(0u64),
+        Just(1u64),
+        Just(u64::MAX - 1),
+        Just(u64::MAX),
+        any::<u64>(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn output_never_exceeds_reserve(
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+        amount_in in any::<u64>(),
+        fee_bps in 0u16..=10_000,
+    ) {
+        if let Ok(amount_out) = swap_output(CurveType::ConstantProduct, reserve_in, reserve_out, amount_in, fee_bps, true) {
+            prop_assert!(amount_out < reserve_out);
+        }
+    }
+
+    #[test]
+    fn k_never_decreases(
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+        amount_in in any::<u64>(),
+        fee_bps in 0u16..=10_000,
+    ) {
+        let Ok(amount_out) = swap_output(CurveType::ConstantProduct, reserve_in, reserve_out, amount_in, fee_bps, true) else {
+            return Ok(());
+        };
+        let Some(new_reserve_in) = reserve_in.checked_add(amount_in) else {
+            return Ok(());
+        };
+
+        let k_before = reserve_in as u128 * reserve_out as u128;
+        let k_after = new_reserve_in as u128 * (reserve_out - amount_out) as u128;
+
+        prop_assert!(k_after >= k_before, "{k_after} < {k_before}");
+    }
+
+    #[test]
+    fn provide_then_withdraw_never_returns_more(
+        reserve_a in reserve(),
+        reserve_b in reserve(),
+        desired_a in any::<u64>(),
+        desired_b in any::<u64>(),
+    ) {
+        let total_lp = initial_lp_amount(CurveType::ConstantProduct, reserve_a, reserve_b).unwrap();
+
+        let (take_a, take_b) = deposit_amounts(reserve_a, reserve_b, desired_a, desired_b).unwrap();
+        prop_assert!(take_a <= desired_a && take_b <= desired_b);
+
+        let lp = lp_for_deposit(reserve_a, reserve_b, total_lp, take_a, take_b).unwrap();
+
+        let (Some(new_reserve_a), Some(new_reserve_b), Some(new_total_lp)) = (
+            reserve_a.checked_add(take_a),
+            reserve_b.checked_add(take_b),
+            total_lp.checked_add(lp),
+        ) else {
+            return Ok(());
+        };
+
+        let (out_a, out_b) = withdraw_amounts(new_reserve_a, new_reserve_b, new_total_lp, lp).unwrap();
+
+        prop_assert!(out_a <= take_a, "{out_a} > {take_a}");
+        prop_assert!(out_b <= take_b, "{out_b} > {take_b}");
+    }
+
+    #[test]
+    fn no_panics_on_extreme_values(
+        reserve_a in extreme(),
+        reserve_b in extreme(),
+        amount_a in extreme(),
+        amount_b in extreme(),
+        total_lp in extreme(),
+        fee_bps in prop_oneof![Just(0u16), Just(10_000u16), 0u16..=10_000],
+    ) {
+        let _ = swap_output(CurveType::ConstantProduct, reserve_a, reserve_b, amount_a, fee_bps, true);
+        let _ = initial_lp_amount(CurveType::ConstantProduct, amount_a, amount_b);
+        let _ = deposit_amounts(reserve_a, reserve_b, amount_a, amount_b);
+        let _ = lp_for_deposit(reserve_a, reserve_b, total_lp, amount_a, amount_b);
+        let _ = withdraw_amounts(reserve_a, reserve_b, total_lp, amount_a);
+    }
+}
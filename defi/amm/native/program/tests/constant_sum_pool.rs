@@ -0,0 +1,92 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+use program::state::CurveType;
+
+use common::*;
+
+// zero isn't a default fee tier, so each test adds it first
+const FEE_BPS: u16 = 0;
+
+#[tokio::test]
+async fn constant_sum_pool_swaps_one_to_one_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    send(&mut context, &[set_fee_tiers_ix(&program_id, &payer, &[FEE_BPS, 30])?], &[]).await?;
+
+    let (mint_a, mint_b, pool) =
+        setup_pool_with_curve(&mut context, &program_id, FEE_BPS, 0, CurveType::ConstantSum).await?;
+
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 250_000_000, 250_000_000)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_b).await?, balance_before + 250_000_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn constant_sum_pool_drain_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    send(&mut context, &[set_fee_tiers_ix(&program_id, &payer, &[FEE_BPS, 30])?], &[]).await?;
+
+    let (mint_a, mint_b, pool) =
+        setup_pool_with_curve(&mut context, &program_id, FEE_BPS, 0, CurveType::ConstantSum).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, INITIAL_LIQUIDITY, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::ReserveExhausted as u32));
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, INITIAL_LIQUIDITY - 1, 0)?],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, 2 * INITIAL_LIQUIDITY - 1);
+    assert_eq!(pool_data.reserve_b, 1);
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::ReserveExhausted as u32));
+
+    // the drained side can still be refilled from the other direction
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_b, &mint_a, 1_000_000, 1_000_000)?],
+        &[],
+    ).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.reserve_b, 1_000_001);
+
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use proptest::prelude::*;
+
+use solana_program::program_error::ProgramError;
+
+use program::curve::{swap_output, initial_lp_amount, compute_d, compute_y, price_impact_bps};
+use program::error::AmmError;
+use program::math::U256;
+use program::state::CurveType;
+
+const FEE_BPS: u16 = 4;
+
+#[test]
+fn constant_product_handles_extreme_reserves() {
+    // reserve_out * amount_in_post_fee is far beyond u128 here
+    let reserve = u64::MAX / 2;
+
+    let amount_out = swap_output(CurveType::ConstantProduct, reserve, reserve, reserve, 0, true).unwrap();
+
+    assert_eq!(amount_out, reserve / 2);
+
+    let amount_out = swap_output(CurveType::ConstantProduct, 1, u64::MAX, u64::MAX, FEE_BPS, true).unwrap();
+
+    assert!(amount_out < u64::MAX);
+}
+
+#[test]
+fn constant_product_price_impact_of_known_trades() {
+    let reserve = 1_000_000_000;
+
+    // 1% of the pool with no fee executes at 1 / 1.01 of spot, 99.01 bps
+    // worse, and the impact rounds up
+    let amount_out = swap_output(CurveType::ConstantProduct, reserve, reserve, reserve / 100, 0, true).unwrap();
+
+    assert_eq!(price_impact_bps(CurveType::ConstantProduct, reserve, reserve, reserve / 100, amount_out, true), Some(100));
+
+    // the 30 bps fee counts towards the impact, on top of ~10 bps of price movement
+    let amount_out = swap_output(CurveType::ConstantProduct, reserve, reserve, reserve / 1_000, 30, true).unwrap();
+
+    assert_eq!(price_impact_bps(CurveType::ConstantProduct, reserve, reserve, reserve / 1_000, amount_out, true), Some(40));
+
+    // spot price follows the reserve ratio
+    assert_eq!(price_impact_bps(CurveType::ConstantProduct, reserve, 2 * reserve, 1_000, 2_000, true), Some(0));
+}
+
+#[test]
+fn empty_reserves_are_reported() {
+    let curves = [
+        CurveType::ConstantProduct,
+        CurveType::Stable { amp: 100 },
+        CurveType::ConstantSum,
+        CurveType::Weighted { weight_a_bps: 8_000, weight_b_bps: 2_000 },
+    ];
+
+    for curve in curves {
+        assert_eq!(swap_output(curve, 1_000, 0, 1, FEE_BPS, true), Err(AmmError::EmptyPool.into()));
+        assert_eq!(swap_output(curve, 0, 0, 1, FEE_BPS, true), Err(AmmError::EmptyPool.into()));
+    }
+
+    // constant sum prices 1:1 without looking at reserve_in
+    assert_eq!(swap_output(CurveType::ConstantSum, 0, 1_000, 100, 0, true), Ok(100));
+    assert_eq!(swap_output(CurveType::ConstantProduct, 0, 1_000, 100, 0, true), Err(AmmError::EmptyPool.into()));
+}
+
+proptest! {
+    #[test]
+    fn stable_beats_constant_product_near_peg(
+        reserve in 1_000_000_000u64..1_000_000_000_000,
+        imbalance_bps in 0u64..500,
+        trade_bps in 1u64..100,
+        amp in 10u64..1_000,
+    ) {
+        // the output side is the scarcer one, where constant product prices worst
+        let reserve_in = reserve + reserve * imbalance_bps / 10_000;
+        let reserve_out = reserve;
+        let amount_in = (reserve * trade_bps / 10_000).max(1);
+
+        let stable_out =
+            swap_output(CurveType::Stable { amp }, reserve_in, reserve_out, amount_in, FEE_BPS, true).unwrap();
+        let constant_product_out =
+            swap_output(CurveType::ConstantProduct, reserve_in, reserve_out, amount_in, FEE_BPS, true).unwrap();
+
+        prop_assert!(stable_out >= constant_product_out);
+    }
+
+    #[test]
+    fn stable_slippage_at_peg_is_below_fee_plus_one_bps(
+        reserve in 1_000_000_000u64..1_000_000_000_000,
+        trade_bps in 1u64..10,
+        amp in 100u64..1_000,
+    ) {
+        let amount_in = reserve * trade_bps / 10_000;
+
+        let stable_out = swap_output(CurveType::Stable { amp }, reserve, reserve, amount_in, FEE_BPS, true).unwrap();
+
+        prop_assert!(stable_out <= amount_in);
+        prop_assert!(stable_out as u128 * 10_000 >= amount_in as u128 * (10_000 - FEE_BPS as u128 - 1));
+    }
+
+    #[test]
+    fn stable_output_never_drains_reserve(
+        reserve_in in 1_000u64..1_000_000_000_000,
+        reserve_out in 1_000u64..1_000_000_000_000,
+        amount_in in 1u64..1_000_000_000_000_000,
+        amp in 1u64..1_000,
+    ) {
+        let result = swap_output(CurveType::Stable { amp }, reserve_in, reserve_out, amount_in, FEE_BPS, true);
+
+        if let Ok(amount_out) = result {
+            prop_assert!(amount_out < reserve_out);
+        }
+    }
+
+    #[test]
+    fn stable_invariant_does_not_decrease_after_swap(
+        reserve_in in 1_000_000u64..1_000_000_000_000,
+        reserve_out in 1_000_000u64..1_000_000_000_000,
+        trade_bps in 1u64..5_000,
+        amp in 1u64..1_000,
+    ) {
+        let amount_in = (reserve_in * trade_bps / 10_000).max(1);
+
+        let d_before = compute_d(amp, reserve_in, reserve_out).unwrap();
+        let amount_out =
+            swap_output(CurveType::Stable { amp }, reserve_in, reserve_out, amount_in, FEE_BPS, true).unwrap();
+        let d_after = compute_d(amp, reserve_in + amount_in, reserve_out - amount_out).unwrap();
+
+        prop_assert!(d_after >= d_before);
+    }
+
+    #[test]
+    fn compute_y_recovers_reserve(
+        reserve_a in 1_000_000u64..1_000_000_000_000,
+        reserve_b in 1_000_000u64..1_000_000_000_000,
+        amp in 1u64..1_000,
+    ) {
+        let d = compute_d(amp, reserve_a, reserve_b).unwrap();
+        let y = compute_y(amp, reserve_a as u128, d).unwrap();
+
+        prop_assert!(y.abs_diff(reserve_b as u128) <= 2);
+    }
+
+    #[test]
+    fn balanced_stable_lp_matches_constant_product(
+        amount in 1_000u64..1_000_000_000_000,
+        amp in 1u64..1_000,
+    ) {
+        let stable_lp = initial_lp_amount(CurveType::Stable { amp }, amount, amount).unwrap();
+        let constant_product_lp = initial_lp_amount(CurveType::ConstantProduct, amount, amount).unwrap();
+
+        prop_assert!(stable_lp.abs_diff(2 * constant_product_lp) <= 1);
+    }
+
+    #[test]
+    fn constant_sum_pays_one_to_one_until_exhausted(
+        reserve_in in 0u64..1_000_000_000_000,
+        reserve_out in 0u64..1_000_000_000_000,
+        amount_in in 0u64..2_000_000_000_000,
+    ) {
+        let amount_in_post_fee = (amount_in as u128 * (10_000 - FEE_BPS as u128) / 10_000) as u64;
+
+        match swap_output(CurveType::ConstantSum, reserve_in, reserve_out, amount_in, FEE_BPS, true) {
+            Ok(amount_out) => {
+                prop_assert_eq!(amount_out, amount_in_post_fee);
+                prop_assert!(amount_out < reserve_out);
+            },
+            Err(error) if reserve_out == 0 => {
+                prop_assert_eq!(error, ProgramError::from(AmmError::EmptyPool));
+            },
+            Err(error) => {
+                prop_assert_eq!(error, ProgramError::from(AmmError::ReserveExhausted));
+                prop_assert!(amount_in_post_fee >= reserve_out);
+            },
+        }
+    }
+
+    #[test]
+    fn weighted_with_equal_weights_matches_constant_product(
+        reserve_in in 1_000_000u64..1_000_000_000_000,
+        reserve_out in 1_000_000u64..1_000_000_000_000,
+        trade_bps in 1u64..5_000,
+    ) {
+        let amount_in = reserve_in * trade_bps / 10_000;
+        let weighted = CurveType::Weighted { weight_a_bps: 5_000, weight_b_bps: 5_000 };
+
+        let weighted_out = swap_output(weighted, reserve_in, reserve_out, amount_in, FEE_BPS, true).unwrap();
+        let constant_product_out =
+            swap_output(CurveType::ConstantProduct, reserve_in, reserve_out, amount_in, FEE_BPS, true).unwrap();
+
+        prop_assert!(weighted_out <= constant_product_out);
+        prop_assert!(constant_product_out - weighted_out <= 2 + constant_product_out / 1_000_000);
+    }
+
+    #[test]
+    fn weighted_spot_price_follows_weights(
+        reserve in 1_000_000_000u64..1_000_000_000_000,
+        weight_a_bps in 1_000u16..9_000,
+    ) {
+        let weighted = CurveType::Weighted { weight_a_bps, weight_b_bps: 10_000 - weight_a_bps };
+        let amount_in = reserve / 1_000_000;
+
+        // with equal reserves one A is worth w_a / w_b of B
+        let amount_out = swap_output(weighted, reserve, reserve, amount_in, 0, true).unwrap();
+        let expected_out = amount_in as u128 * weight_a_bps as u128 / (10_000 - weight_a_bps) as u128;
+
+        prop_assert!(amount_out as u128 <= expected_out);
+        prop_assert!(expected_out - amount_out as u128 <= expected_out / 1_000 + 1);
+    }
+
+    #[test]
+    fn weighted_output_never_drains_reserve(
+        reserve_in in 1_000u64..1_000_000_000_000,
+        reserve_out in 1_000u64..1_000_000_000_000,
+        amount_in in 1u64..1_000_000_000_000_000,
+        weight_a_bps in 100u16..9_900,
+        a_to_b in any::<bool>(),
+    ) {
+        let weighted = CurveType::Weighted { weight_a_bps, weight_b_bps: 10_000 - weight_a_bps };
+
+        if let Ok(amount_out) = swap_output(weighted, reserve_in, reserve_out, amount_in, FEE_BPS, a_to_b) {
+            prop_assert!(amount_out < reserve_out);
+        }
+    }
+
+    #[test]
+    fn balanced_weighted_lp_matches_constant_product(
+        amount in 1_000u64..1_000_000_000_000,
+        weight_a_bps in 100u16..9_900,
+    ) {
+        let weighted = CurveType::Weighted { weight_a_bps, weight_b_bps: 10_000 - weight_a_bps };
+
+        let weighted_lp = initial_lp_amount(weighted, amount, amount).unwrap();
+
+        prop_assert!(weighted_lp.abs_diff(amount) <= 1 + amount / 1_000_000_000);
+    }
+
+    #[test]
+    fn constant_product_k_does_not_decrease(
+        reserve_in in 1u64..u64::MAX / 2,
+        reserve_out in 1u64..u64::MAX,
+        amount_in in 1u64..u64::MAX / 2,
+        fee_bps in 0u16..10_000,
+    ) {
+        let amount_out = swap_output(CurveType::ConstantProduct, reserve_in, reserve_out, amount_in, fee_bps, true).unwrap();
+
+        prop_assert!(amount_out < reserve_out);
+
+        let k_before = U256::from(reserve_in) * U256::from(reserve_out);
+        let k_after = U256::from(reserve_in + amount_in) * U256::from(reserve_out - amount_out);
+
+        prop_assert!(k_after >= k_before);
+    }
+
+    #[test]
+    fn stable_price_impact_at_peg_is_tiny(
+        reserve in 1_000_000_000u64..1_000_000_000_000,
+        trade_bps in 1u64..10,
+        amp in 100u64..1_000,
+    ) {
+        let amount_in = reserve * trade_bps / 10_000;
+        let curve = CurveType::Stable { amp };
+
+        let amount_out = swap_output(curve, reserve, reserve, amount_in, 0, true).unwrap();
+
+        prop_assert!(price_impact_bps(curve, reserve, reserve, amount_in, amount_out, true).unwrap() <= 1);
+    }
+
+    #[test]
+    fn weighted_price_impact_of_small_trade_is_tiny(
+        reserve in 1_000_000_000_000u64..1_000_000_000_000_000,
+        weight_a_bps in 100u16..9_900,
+    ) {
+        let curve = CurveType::Weighted { weight_a_bps, weight_b_bps: 10_000 - weight_a_bps };
+        let amount_in = reserve / 1_000_000;
+
+        let amount_out = swap_output(curve, reserve, reserve, amount_in, 0, true).unwrap();
+
+        // one bp of price movement at the most extreme weights, one of rounding
+        prop_assert!(price_impact_bps(curve, reserve, reserve, amount_in, amount_out, true).unwrap() <= 2);
+    }
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+use program::dca::{
+    error::{DcaError, DCA_ERROR_OFFSET},
+    state::EXECUTION_BOUNTY_LAMPORTS,
+};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const DCA_ID: u64 = 1;
+const AMOUNT_TOTAL: u64 = 30_000_000;
+const AMOUNT_PER_SWAP: u64 = 10_000_000;
+const INTERVAL: i64 = 3_600;
+
+async fn setup_cranker(context: &mut ProgramTestContext) -> Result<Keypair> {
+    let payer = context.payer.pubkey();
+    let cranker = Keypair::new();
+
+    send(context, &[transfer(&payer, &cranker.pubkey(), 1_000_000_000)], &[]).await?;
+
+    Ok(cranker)
+}
+
+fn dca_error(error: DcaError) -> Option<u32> {
+    Some(DCA_ERROR_OFFSET + error as u32)
+}
+
+#[tokio::test]
+async fn executes_once_per_interval_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let cranker = setup_cranker(&mut context).await?;
+
+    send(
+        &mut context,
+        &[open_dca_ix(&program_id, &payer, &pool, &mint_a, &mint_b, DCA_ID, AMOUNT_TOTAL, AMOUNT_PER_SWAP, 0, INTERVAL)?],
+        &[],
+    ).await?;
+
+    let execute_ix = execute_dca_ix(&program_id, &cranker.pubkey(), &pool, &payer, &mint_a, &mint_b, DCA_ID);
+    let dca = dca_address(&program_id, &pool, &payer, DCA_ID);
+    let escrow = get_associated_token_address(&dca, &mint_a);
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+
+    let b_before = token_balance(&mut context, &payer_ata_b).await?;
+    let cranker_before = context.banks_client.get_balance(cranker.pubkey()).await?;
+
+    // the first swap is due right away
+    send(&mut context, std::slice::from_ref(&execute_ix), &[&cranker]).await?;
+
+    assert_eq!(token_balance(&mut context, &escrow).await?, AMOUNT_TOTAL - AMOUNT_PER_SWAP);
+    assert!(token_balance(&mut context, &payer_ata_b).await? > b_before);
+
+    let result = send(&mut context, std::slice::from_ref(&execute_ix), &[&cranker]).await;
+
+    assert_eq!(custom_error(result), dca_error(DcaError::DcaNotDue));
+
+    warp_forward(&mut context, INTERVAL - 1).await?;
+
+    let result = send(&mut context, std::slice::from_ref(&execute_ix), &[&cranker]).await;
+
+    assert_eq!(custom_error(result), dca_error(DcaError::DcaNotDue));
+
+    warp_forward(&mut context, 1).await?;
+    send(&mut context, std::slice::from_ref(&execute_ix), &[&cranker]).await?;
+
+    // the last swap empties the escrow and closes the position
+    warp_forward(&mut context, INTERVAL).await?;
+    send(&mut context, &[execute_ix], &[&cranker]).await?;
+
+    assert!(context.banks_client.get_account(dca).await?.is_none());
+    assert!(context.banks_client.get_account(escrow).await?.is_none());
+    assert_eq!(
+        context.banks_client.get_balance(cranker.pubkey()).await? - cranker_before,
+        3 * EXECUTION_BOUNTY_LAMPORTS,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_refunds_remaining_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let cranker = setup_cranker(&mut context).await?;
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let a_before = token_balance(&mut context, &payer_ata_a).await?;
+
+    send(
+        &mut context,
+        &[open_dca_ix(&program_id, &payer, &pool, &mint_a, &mint_b, DCA_ID, AMOUNT_TOTAL, AMOUNT_PER_SWAP, 0, INTERVAL)?],
+        &[],
+    ).await?;
+
+    send(
+        &mut context,
+        &[execute_dca_ix(&program_id, &cranker.pubkey(), &pool, &payer, &mint_a, &mint_b, DCA_ID)],
+        &[&cranker],
+    ).await?;
+
+    send(&mut context, &[close_dca_ix(&program_id, &payer, &pool, &mint_a, DCA_ID)], &[]).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, a_before - AMOUNT_PER_SWAP);
+    assert!(context.banks_client.get_account(dca_address(&program_id, &pool, &payer, DCA_ID)).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn min_out_bounds_each_swap_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let cranker = setup_cranker(&mut context).await?;
+
+    // asks for more than the pool gives at 1.0
+    send(
+        &mut context,
+        &[open_dca_ix(&program_id, &payer, &pool, &mint_a, &mint_b, DCA_ID, AMOUNT_TOTAL, AMOUNT_PER_SWAP, AMOUNT_PER_SWAP, INTERVAL)?],
+        &[],
+    ).await?;
+
+    let result = send(
+        &mut context,
+        &[execute_dca_ix(&program_id, &cranker.pubkey(), &pool, &payer, &mint_a, &mint_b, DCA_ID)],
+        &[&cranker],
+    ).await;
+
+    // the inner Swap's slippage check fails the whole execution
+    assert_eq!(custom_error(result), Some(AmmError::SlippageExceed as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn zero_interval_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[open_dca_ix(&program_id, &payer, &pool, &mint_a, &mint_b, DCA_ID, AMOUNT_TOTAL, AMOUNT_PER_SWAP, 0, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), dca_error(DcaError::InvalidDcaInterval));
+
+    Ok(())
+}
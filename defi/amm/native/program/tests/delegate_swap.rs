@@ -0,0 +1,147 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::AccountMeta,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{
+    id as token_program_id,
+    instruction::approve,
+    state::Account as TokenAccount,
+};
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Swap account indices, see swap_ix.
+const USER_ATA_OUT: usize = 7;
+
+async fn approve_delegate(
+    context: &mut ProgramTestContext,
+    source: &Pubkey,
+    delegate: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let owner = context.payer.pubkey();
+
+    send(context, &[approve(&token_program_id(), source, delegate, &owner, &[], amount)?], &[]).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delegate_swap_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let owner = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let owner_ata_a = get_associated_token_address(&owner, &mint_a);
+    let owner_ata_b = get_associated_token_address(&owner, &mint_b);
+    let balance_a_before = token_balance(&mut context, &owner_ata_a).await?;
+    let balance_b_before = token_balance(&mut context, &owner_ata_b).await?;
+
+    let delegate = Keypair::new();
+    let amount_in = 1_000_000;
+
+    approve_delegate(&mut context, &owner_ata_a, &delegate.pubkey(), amount_in * 2).await?;
+
+    let expected_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    send(
+        &mut context,
+        &[swap_as_delegate_ix(&program_id, &delegate.pubkey(), &owner, &pool, &mint_a, &mint_b, amount_in, expected_out)?],
+        &[&delegate],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &owner_ata_a).await?, balance_a_before - amount_in);
+    assert_eq!(token_balance(&mut context, &owner_ata_b).await?, balance_b_before + expected_out);
+
+    // the swap used up its share of the approval
+    let account = context.banks_client.get_account(owner_ata_a).await?.unwrap();
+
+    assert_eq!(TokenAccount::unpack(&account.data)?.delegated_amount, amount_in);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delegate_swap_over_approval_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let owner = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let delegate = Keypair::new();
+
+    approve_delegate(&mut context, &get_associated_token_address(&owner, &mint_a), &delegate.pubkey(), 999_999).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_as_delegate_ix(&program_id, &delegate.pubkey(), &owner, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[&delegate],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::DelegatedAmountTooLow as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delegate_swap_without_approval_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let owner = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    // approved, but for someone else
+    let delegate = Keypair::new();
+
+    approve_delegate(&mut context, &get_associated_token_address(&owner, &mint_a), &Pubkey::new_unique(), 1_000_000).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_as_delegate_ix(&program_id, &delegate.pubkey(), &owner, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[&delegate],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotTokenDelegate as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delegate_swap_to_delegate_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let owner = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let delegate = Keypair::new();
+    let delegate_ata_b = create_ata(&mut context, &delegate.pubkey(), &mint_b).await?;
+
+    approve_delegate(&mut context, &get_associated_token_address(&owner, &mint_a), &delegate.pubkey(), 1_000_000).await?;
+
+    // the approval covers spending the owner's tokens, not keeping the proceeds
+    let mut instruction = swap_as_delegate_ix(&program_id, &delegate.pubkey(), &owner, &pool, &mint_a, &mint_b, 1_000_000, 0)?;
+    instruction.accounts[USER_ATA_OUT] = AccountMeta::new(delegate_ata_b, false);
+
+    let result = send(&mut context, &[instruction], &[&delegate]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::DelegateOutputNotOwner as u32));
+
+    Ok(())
+}
@@ -0,0 +1,270 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::processor::MINIMUM_LIQUIDITY;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const PROTOCOL_FEE_BPS: u16 = 2_000;
+const OPERATIONS: usize = 40;
+
+// Reference model of a constant-product pool with one liquidity provider, who
+// is also the pool's creator. Written independently of the program's math so
+// that any rounding or accounting drift between the two shows up as a
+// mismatch.
+#[derive(Clone, Debug, PartialEq)]
+struct Model {
+    reserve_a: u64,
+    reserve_b: u64,
+    fees_a: u64,
+    fees_b: u64,
+    lp_supply: u64,
+    user_a: u64,
+    user_b: u64,
+    user_lp: u64,
+    treasury_a: u64,
+    treasury_b: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+    Provide { desired_a: u64, desired_b: u64 },
+    Withdraw { amount_lp: u64 },
+    Swap { a_to_b: bool, amount_in: u64 },
+}
+
+impl Model {
+    fn new() -> Self {
+        let lp_supply = isqrt(INITIAL_LIQUIDITY as u128 * INITIAL_LIQUIDITY as u128) as u64;
+
+        Self {
+            reserve_a: INITIAL_LIQUIDITY,
+            reserve_b: INITIAL_LIQUIDITY,
+            fees_a: 0,
+            fees_b: 0,
+            lp_supply,
+            user_a: USER_FUNDS - INITIAL_LIQUIDITY,
+            user_b: USER_FUNDS - INITIAL_LIQUIDITY,
+            user_lp: lp_supply - MINIMUM_LIQUIDITY,
+            treasury_a: 0,
+            treasury_b: 0,
+        }
+    }
+
+    // None means the program is expected to reject the operation and leave
+    // everything untouched.
+    fn apply(&self, operation: Operation) -> Option<Self> {
+        let mut next = self.clone();
+
+        match operation {
+            Operation::Provide { desired_a, desired_b } => {
+                let (reserve_a, reserve_b) = (self.reserve_a as u128, self.reserve_b as u128);
+                let supply = self.lp_supply as u128;
+
+                // what the user owes rounds up, what they get rounds down
+                let b_needed = (desired_a as u128 * reserve_b).div_ceil(reserve_a);
+                let (take_a, take_b) = if b_needed <= desired_b as u128 {
+                    (desired_a as u128, b_needed)
+                } else {
+                    ((desired_b as u128 * reserve_a).div_ceil(reserve_b), desired_b as u128)
+                };
+
+                if take_a > self.user_a as u128 || take_b > self.user_b as u128 {
+                    return None;
+                }
+
+                // LP is priced against the fees as well as the reserves
+                let (value_a, value_b) = (reserve_a + self.fees_a as u128, reserve_b + self.fees_b as u128);
+                let lp = (take_a * supply / value_a).min(take_b * supply / value_b) as u64;
+
+                next.user_a -= take_a as u64;
+                next.user_b -= take_b as u64;
+                next.reserve_a += take_a as u64;
+                next.reserve_b += take_b as u64;
+                next.lp_supply += lp;
+                next.user_lp += lp;
+            },
+            Operation::Withdraw { amount_lp } => {
+                if amount_lp > self.user_lp {
+                    return None;
+                }
+
+                let supply = self.lp_supply as u128;
+                let out_a = (amount_lp as u128 * self.reserve_a as u128 / supply) as u64;
+                let out_b = (amount_lp as u128 * self.reserve_b as u128 / supply) as u64;
+                let fees_out_a = (amount_lp as u128 * self.fees_a as u128 / supply) as u64;
+                let fees_out_b = (amount_lp as u128 * self.fees_b as u128 / supply) as u64;
+
+                next.user_lp -= amount_lp;
+                next.lp_supply -= amount_lp;
+                next.reserve_a -= out_a;
+                next.reserve_b -= out_b;
+                next.fees_a -= fees_out_a;
+                next.fees_b -= fees_out_b;
+                next.user_a += out_a + fees_out_a;
+                next.user_b += out_b + fees_out_b;
+            },
+            Operation::Swap { a_to_b, amount_in } => {
+                let (user_in, user_out, reserve_in, reserve_out, fees_in, treasury_in) = if a_to_b {
+                    (&mut next.user_a, &mut next.user_b, &mut next.reserve_a, &mut next.reserve_b, &mut next.fees_a, &mut next.treasury_a)
+                } else {
+                    (&mut next.user_b, &mut next.user_a, &mut next.reserve_b, &mut next.reserve_a, &mut next.fees_b, &mut next.treasury_b)
+                };
+
+                if amount_in > *user_in {
+                    return None;
+                }
+
+                let fee = (amount_in as u128 * FEE_BPS as u128 / 10_000) as u64;
+                let protocol_fee = (fee as u128 * PROTOCOL_FEE_BPS as u128 / 10_000) as u64;
+
+                let amount_in_post_fee = amount_in as u128 * (10_000 - FEE_BPS as u128);
+                let amount_out = (*reserve_out as u128 * amount_in_post_fee
+                    / (*reserve_in as u128 * 10_000 + amount_in_post_fee)) as u64;
+
+                *user_in -= amount_in;
+                *treasury_in += protocol_fee;
+                *reserve_in += amount_in - fee;
+                *fees_in += fee - protocol_fee;
+                *reserve_out -= amount_out;
+                *user_out += amount_out;
+            },
+        }
+
+        Some(next)
+    }
+}
+
+fn isqrt(n: u128) -> u128 {
+    let mut x = (n as f64).sqrt() as u128;
+
+    while x * x > n {
+        x -= 1;
+    }
+    while (x + 1) * (x + 1) <= n {
+        x += 1;
+    }
+
+    x
+}
+
+fn random_operation(rng: &mut Rng, model: &Model) -> Operation {
+    match rng.next() % 3 {
+        0 => Operation::Provide {
+            desired_a: rng.amount(model.user_a + model.user_a / 10),
+            desired_b: rng.amount(model.user_b + model.user_b / 10),
+        },
+        1 => Operation::Withdraw { amount_lp: rng.amount(model.user_lp + model.user_lp / 10) },
+        _ => {
+            let a_to_b = rng.next().is_multiple_of(2);
+            let balance = if a_to_b { model.user_a } else { model.user_b };
+
+            Operation::Swap { a_to_b, amount_in: rng.amount(balance + balance / 10) }
+        },
+    }
+}
+
+async fn observe(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    pool: &Pubkey,
+) -> Result<Model> {
+    let payer = context.payer.pubkey();
+    let pool_data = pool_state(context, pool).await?;
+
+    let mint_lp = lp_mint_address(program_id, pool);
+    let treasury = treasury_address(program_id, pool);
+
+    // the reserves and fees have to agree with what the vaults actually hold
+    assert_eq!(
+        token_balance(context, &get_associated_token_address(pool, mint_a)).await?,
+        pool_data.reserve_a + pool_data.fees_a,
+    );
+    assert_eq!(
+        token_balance(context, &get_associated_token_address(pool, mint_b)).await?,
+        pool_data.reserve_b + pool_data.fees_b,
+    );
+
+    Ok(Model {
+        reserve_a: pool_data.reserve_a,
+        reserve_b: pool_data.reserve_b,
+        fees_a: pool_data.fees_a,
+        fees_b: pool_data.fees_b,
+        lp_supply: mint_supply(context, &mint_lp).await?,
+        user_a: token_balance(context, &get_associated_token_address(&payer, mint_a)).await?,
+        user_b: token_balance(context, &get_associated_token_address(&payer, mint_b)).await?,
+        user_lp: token_balance(context, &get_associated_token_address(&payer, &mint_lp)).await?,
+        treasury_a: token_balance(context, &get_associated_token_address(&treasury, mint_a)).await?,
+        treasury_b: token_balance(context, &get_associated_token_address(&treasury, mint_b)).await?,
+    })
+}
+
+async fn replay(seed: u64) -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    let mut rng = Rng(seed);
+    let mut model = Model::new();
+
+    assert_eq!(observe(&mut context, &program_id, &mint_a, &mint_b, &pool).await?, model, "seed {seed}: after create");
+
+    for step in 0..OPERATIONS {
+        let operation = random_operation(&mut rng, &model);
+
+        let instruction = match operation {
+            Operation::Provide { desired_a, desired_b } => {
+                provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (desired_a, desired_b), (0, 0))?
+            },
+            Operation::Withdraw { amount_lp } => {
+                withdraw_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_lp, (0, 0))?
+            },
+            Operation::Swap { a_to_b: true, amount_in } => {
+                swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0)?
+            },
+            Operation::Swap { a_to_b: false, amount_in } => {
+                swap_ix(&program_id, &payer, &pool, &mint_b, &mint_a, amount_in, 0)?
+            },
+        };
+
+        let result = send(&mut context, &[instruction], &[]).await;
+
+        match model.apply(operation) {
+            Some(next) => {
+                assert!(result.is_ok(), "seed {seed} step {step}: {operation:?} failed: {result:?}");
+                model = next;
+            },
+            None => assert!(result.is_err(), "seed {seed} step {step}: {operation:?} should have failed"),
+        }
+
+        assert_eq!(
+            observe(&mut context, &program_id, &mint_a, &mint_b, &pool).await?,
+            model,
+            "seed {seed} step {step}: {operation:?}",
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn program_matches_reference_model_test() -> Result<()> {
+    for seed in [0x9e37_79b9_7f4a_7c15, 0xdead_beef_cafe_f00d, 0x0123_4567_89ab_cdef, 42] {
+        replay(seed).await?;
+    }
+
+    Ok(())
+}
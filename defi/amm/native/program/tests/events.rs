@@ -0,0 +1,282 @@
+mod common;
+
+use std::sync::Once;
+
+use anyhow::Result;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use borsh::BorshDeserialize;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+
+use program::events::{AmmEvent, SwapExecuted, LiquidityAdded, LiquidityRemoved};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Sends the instructions and decodes every AmmEvent from the "Program data:"
+// lines LogDataStubs leaves in the logs.
+async fn send_for_events(context: &mut ProgramTestContext, instructions: &[Instruction]) -> Result<Vec<AmmEvent>> {
+    let recent_blockhash = context.get_new_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        recent_blockhash,
+    );
+
+    let result = context.banks_client.process_transaction_with_metadata(tx).await?;
+    result.result?;
+
+    let mut events = Vec::new();
+
+    for log in result.metadata.unwrap().log_messages {
+        if let Some(data) = log.strip_prefix("Program log: Program data: ") {
+            let bytes = BASE64_STANDARD.decode(data)?;
+            events.push(AmmEvent::try_from_slice(&bytes)?);
+        }
+    }
+
+    Ok(events)
+}
+
+#[tokio::test]
+async fn swap_emits_event_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start_logging_events(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let amount_in = 10_000_000;
+    let expected_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    let events = send_for_events(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0)?],
+    ).await?;
+
+    assert_eq!(
+        events,
+        vec![AmmEvent::SwapExecuted(SwapExecuted {
+            pool,
+            user: payer,
+            mint_in: mint_a,
+            mint_out: mint_b,
+            amount_in,
+            amount_out: expected_out,
+            protocol_fee: 0,
+            // the LP fee is kept outside the reserve
+            reserve_a: INITIAL_LIQUIDITY + amount_in - amount_in * FEE_BPS as u64 / 10_000,
+            reserve_b: INITIAL_LIQUIDITY - expected_out,
+        })],
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn liquidity_emits_events_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start_logging_events(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let amount = INITIAL_LIQUIDITY / 10;
+
+    let events = send_for_events(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (amount, amount), (0, 0))?],
+    ).await?;
+
+    // LP supply equals the reserves, so a tenth of each mints a tenth of the supply
+    assert_eq!(
+        events,
+        vec![AmmEvent::LiquidityAdded(LiquidityAdded {
+            pool,
+            user: payer,
+            amount_a: amount,
+            amount_b: amount,
+            lp_minted: amount,
+            reserve_a: INITIAL_LIQUIDITY + amount,
+            reserve_b: INITIAL_LIQUIDITY + amount,
+        })],
+    );
+
+    let events = send_for_events(
+        &mut context,
+        &[withdraw_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount, (0, 0))?],
+    ).await?;
+
+    assert_eq!(
+        events,
+        vec![AmmEvent::LiquidityRemoved(LiquidityRemoved {
+            pool,
+            user: payer,
+            amount_a: amount,
+            amount_b: amount,
+            lp_burned: amount,
+            reserve_a: INITIAL_LIQUIDITY,
+            reserve_b: INITIAL_LIQUIDITY,
+        })],
+    );
+
+    Ok(())
+}
+
+// A route logs one swap per hop.
+#[tokio::test]
+async fn swap_route_emits_event_per_hop_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start_logging_events(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool_ab) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let mint_c = create_mint(&mut context).await?;
+    create_funded_ata(&mut context, &payer, &mint_c, USER_FUNDS).await?;
+
+    send(
+        &mut context,
+        &[
+            create_pool_ix(
+                &program_id,
+                &payer,
+                &mint_b,
+                &mint_c,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                FEE_BPS,
+                0,
+                program::state::CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    let pool_bc = pool_address(&program_id, &mint_b, &mint_c, FEE_BPS);
+
+    let events = send_for_events(
+        &mut context,
+        &[swap_route_ix(&program_id, &payer, &[(pool_ab, mint_a, mint_b), (pool_bc, mint_b, mint_c)], 1_000_000, 0)?],
+    ).await?;
+
+    assert_eq!(events.len(), 2);
+    assert!(matches!(&events[0], AmmEvent::SwapExecuted(event) if event.pool == pool_ab));
+    assert!(matches!(&events[1], AmmEvent::SwapExecuted(event) if event.pool == pool_bc));
+
+    Ok(())
+}
+
+async fn start_logging_events(program_id: &Pubkey) -> ProgramTestContext {
+    let context = start(program_id).await;
+
+    install_log_data_stubs();
+
+    context
+}
+
+// processor! runs the program natively, where sol_log_data falls through to a
+// stub that only prints to stdout. Wrapping the stubs ProgramTest installs lets
+// the data reach the transaction logs, as a "Program log: Program data: " line.
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_sysvar(&self, sysvar_id_addr: *const u8, var_addr: *mut u8, offset: u64, length: u64) -> u64 {
+        self.0.sol_get_sysvar(sysvar_id_addr, var_addr, offset, length)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    fn sol_get_epoch_stake(&self, vote_address: *const u8) -> u64 {
+        self.0.sol_get_epoch_stake(vote_address)
+    }
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memcpy(dst, src, n)
+    }
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memmove(dst, src, n)
+    }
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.0.sol_memcmp(s1, s2, n, result)
+    }
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.0.sol_memset(s, c, n)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| BASE64_STANDARD.encode(field)).collect();
+
+        self.0.sol_log(&format!("Program data: {}", fields.join(" ")));
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+// ProgramTest sets its stubs on the first start, and every test here installs
+// the wrapper before sending anything, so no transaction sees the swap.
+fn install_log_data_stubs() {
+    static ONCE: Once = Once::new();
+
+    ONCE.call_once(|| {
+        struct NoStubs;
+        impl SyscallStubs for NoStubs {}
+
+        let program_test_stubs = set_syscall_stubs(Box::new(NoStubs));
+        set_syscall_stubs(Box::new(LogDataStubs(program_test_stubs)));
+    });
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::id as token_program_id;
+
+use program::error::AmmError;
+use program::farm::{
+    error::{FarmError, FARM_ERROR_OFFSET},
+    state::{Farm, StakePosition},
+};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const REWARD_PER_SECOND: u64 = 1_000;
+const REWARD_FUNDS: u64 = 1_000_000_000;
+
+// Creates a pool and a farm on it with a funded reward vault.
+// Returns (pool, reward_mint).
+async fn setup_farm(context: &mut ProgramTestContext, program_id: &Pubkey) -> Result<(Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(context, program_id, FEE_BPS, 0).await?;
+
+    let reward_mint = create_mint(context).await?;
+    create_funded_ata(context, &payer, &reward_mint, REWARD_FUNDS).await?;
+
+    send(
+        context,
+        &[create_farm_ix(program_id, &payer, &pool, &reward_mint, &token_program_id(), REWARD_PER_SECOND)?],
+        &[],
+    ).await?;
+
+    let farm = farm_address(program_id, &pool);
+
+    donate(context, &reward_mint, &get_associated_token_address(&farm, &reward_mint), REWARD_FUNDS).await?;
+
+    Ok((pool, reward_mint))
+}
+
+// A second user holding `amount_lp` of the pool's LP tokens and some SOL for
+// the stake position's rent.
+async fn setup_staker(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    reward_mint: &Pubkey,
+    amount_lp: u64,
+) -> Result<Keypair> {
+    let payer = context.payer.pubkey();
+    let staker = Keypair::new();
+    let mint_lp = lp_mint_address(program_id, pool);
+
+    send(context, &[transfer(&payer, &staker.pubkey(), 100_000_000)], &[]).await?;
+
+    let staker_ata_lp = create_ata(context, &staker.pubkey(), &mint_lp).await?;
+    donate(context, &mint_lp, &staker_ata_lp, amount_lp).await?;
+
+    create_ata(context, &staker.pubkey(), reward_mint).await?;
+
+    Ok(staker)
+}
+
+async fn farm_state(context: &mut ProgramTestContext, farm: &Pubkey) -> Result<Farm> {
+    let account = context.banks_client.get_account(*farm).await?.unwrap();
+
+    Ok(Farm::load(&account.data)?)
+}
+
+async fn position_state(context: &mut ProgramTestContext, position: &Pubkey) -> Result<StakePosition> {
+    let account = context.banks_client.get_account(*position).await?.unwrap();
+
+    Ok(StakePosition::load(&account.data)?)
+}
+
+#[tokio::test]
+async fn harvest_pays_emission_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (pool, reward_mint) = setup_farm(&mut context, &program_id).await?;
+    let payer_ata_reward = get_associated_token_address(&payer, &reward_mint);
+
+    send(&mut context, &[stake_lp_ix(&program_id, &payer, &pool, 500_000_000)?], &[]).await?;
+
+    warp_forward(&mut context, 100).await?;
+
+    send(&mut context, &[harvest_ix(&program_id, &payer, &pool, &reward_mint, &token_program_id())], &[]).await?;
+
+    // the only staker gets the whole emission, less rounding
+    let harvested = token_balance(&mut context, &payer_ata_reward).await?;
+
+    assert!(harvested <= 100 * REWARD_PER_SECOND);
+    assert!(harvested >= 100 * REWARD_PER_SECOND - 1);
+
+    // harvesting again right away pays nothing new
+    send(&mut context, &[harvest_ix(&program_id, &payer, &pool, &reward_mint, &token_program_id())], &[]).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_reward).await?, harvested);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rewards_split_pro_rata_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (pool, reward_mint) = setup_farm(&mut context, &program_id).await?;
+    let staker = setup_staker(&mut context, &program_id, &pool, &reward_mint, 300_000_000).await?;
+
+    send(&mut context, &[stake_lp_ix(&program_id, &payer, &pool, 100_000_000)?], &[]).await?;
+
+    // the payer earns alone for the first 100 seconds
+    warp_forward(&mut context, 100).await?;
+
+    send(&mut context, &[stake_lp_ix(&program_id, &staker.pubkey(), &pool, 300_000_000)?], &[&staker]).await?;
+
+    // then holds a quarter of the stake for the next 100
+    warp_forward(&mut context, 100).await?;
+
+    send(
+        &mut context,
+        &[
+            harvest_ix(&program_id, &payer, &pool, &reward_mint, &token_program_id()),
+            harvest_ix(&program_id, &staker.pubkey(), &pool, &reward_mint, &token_program_id()),
+        ],
+        &[&staker],
+    ).await?;
+
+    let payer_rewards = token_balance(&mut context, &get_associated_token_address(&payer, &reward_mint)).await?;
+    let staker_rewards = token_balance(&mut context, &get_associated_token_address(&staker.pubkey(), &reward_mint)).await?;
+
+    assert!(payer_rewards.abs_diff(125 * REWARD_PER_SECOND) <= 2);
+    assert!(staker_rewards.abs_diff(75 * REWARD_PER_SECOND) <= 2);
+    assert!(payer_rewards + staker_rewards <= 200 * REWARD_PER_SECOND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unstake_returns_lp_and_keeps_rewards_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (pool, reward_mint) = setup_farm(&mut context, &program_id).await?;
+    let farm = farm_address(&program_id, &pool);
+    let position = stake_position_address(&program_id, &farm, &payer);
+    let payer_ata_lp = get_associated_token_address(&payer, &lp_mint_address(&program_id, &pool));
+
+    let lp_before = token_balance(&mut context, &payer_ata_lp).await?;
+
+    send(&mut context, &[stake_lp_ix(&program_id, &payer, &pool, 400_000_000)?], &[]).await?;
+
+    warp_forward(&mut context, 50).await?;
+
+    send(&mut context, &[unstake_lp_ix(&program_id, &payer, &pool, 400_000_000)?], &[]).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_lp).await?, lp_before);
+    assert_eq!(farm_state(&mut context, &farm).await?.total_staked, 0);
+
+    let position_data = position_state(&mut context, &position).await?;
+
+    assert_eq!(position_data.amount, 0);
+    assert!(position_data.pending_rewards >= 50 * REWARD_PER_SECOND - 1);
+
+    // nothing accrues once nothing is staked
+    warp_forward(&mut context, 50).await?;
+
+    send(&mut context, &[harvest_ix(&program_id, &payer, &pool, &reward_mint, &token_program_id())], &[]).await?;
+
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&payer, &reward_mint)).await?,
+        position_data.pending_rewards,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unstake_more_than_staked_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (pool, _reward_mint) = setup_farm(&mut context, &program_id).await?;
+
+    send(&mut context, &[stake_lp_ix(&program_id, &payer, &pool, 1_000)?], &[]).await?;
+
+    let result = send(&mut context, &[unstake_lp_ix(&program_id, &payer, &pool, 1_001)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(FARM_ERROR_OFFSET + FarmError::InsufficientStake as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_farm_by_non_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let reward_mint = create_mint(&mut context).await?;
+
+    let stranger = Keypair::new();
+    send(&mut context, &[transfer(&payer, &stranger.pubkey(), 100_000_000)], &[]).await?;
+
+    let result = send(
+        &mut context,
+        &[create_farm_ix(&program_id, &stranger.pubkey(), &pool, &reward_mint, &token_program_id(), REWARD_PER_SECOND)?],
+        &[&stranger],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+
+    Ok(())
+}
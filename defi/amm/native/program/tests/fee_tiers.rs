@@ -0,0 +1,134 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::AmmError;
+use program::state::{CurveType, ProtocolConfig};
+
+use common::*;
+
+async fn create_pool_with_fee(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    fee_bps: u16,
+) -> Result<Result<(), BanksClientError>> {
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_mint(context).await?;
+    let mint_b = create_mint(context).await?;
+
+    create_funded_ata(context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(context, &payer, &mint_b, USER_FUNDS).await?;
+
+    Ok(send(
+        context,
+        &[
+            create_pool_ix(
+                program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                fee_bps,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await)
+}
+
+#[tokio::test]
+async fn default_fee_tiers_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    for fee_bps in ProtocolConfig::DEFAULT_FEE_TIERS {
+        create_pool_with_fee(&mut context, &program_id, fee_bps).await??;
+    }
+
+    for fee_bps in [0, 2, 25, 1_000, 10_000, 10_001] {
+        let result = create_pool_with_fee(&mut context, &program_id, fee_bps).await?;
+
+        assert_eq!(custom_error(result), Some(AmmError::InvalidFeeTier as u32), "fee_bps {fee_bps}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_fee_tiers_replaces_defaults_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    send(&mut context, &[set_fee_tiers_ix(&program_id, &payer, &[25, 250])?], &[]).await?;
+
+    let account = context.banks_client.get_account(config_address(&program_id)).await?.unwrap();
+    let config = ProtocolConfig::load(&account.data)?;
+
+    assert_eq!(config.admin, payer);
+    assert_eq!(config.fee_tiers, vec![25, 250]);
+
+    create_pool_with_fee(&mut context, &program_id, 25).await??;
+    create_pool_with_fee(&mut context, &program_id, 250).await??;
+
+    let result = create_pool_with_fee(&mut context, &program_id, 30).await?;
+
+    assert_eq!(custom_error(result), Some(AmmError::InvalidFeeTier as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_fee_tiers_validation_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let result = send(&mut context, &[set_fee_tiers_ix(&program_id, &payer, &[30, 10_000])?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FeeTooHigh as u32));
+
+    send(&mut context, &[set_fee_tiers_ix(&program_id, &payer, &[30])?], &[]).await?;
+
+    // only the admin who created the config can change it afterwards
+    let stranger = Keypair::new();
+
+    let result = send(&mut context, &[set_fee_tiers_ix(&program_id, &stranger.pubkey(), &[1])?], &[&stranger]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotConfigAdmin as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_upgrade_authority_creates_config_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    // nobody but the upgrade authority can claim the config before it's set up
+    let stranger = Keypair::new();
+
+    let result = send(&mut context, &[set_fee_tiers_ix(&program_id, &stranger.pubkey(), &[1])?], &[&stranger]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotUpgradeAuthority as u32));
+
+    // nor by passing some other account off as the ProgramData
+    let mut instruction = set_fee_tiers_ix(&program_id, &stranger.pubkey(), &[1])?;
+    instruction.accounts[3].pubkey = Pubkey::new_unique();
+
+    let result = send(&mut context, &[instruction], &[&stranger]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::ProgramDataMismatch as u32));
+    assert!(context.banks_client.get_account(config_address(&program_id)).await?.is_none());
+
+    Ok(())
+}
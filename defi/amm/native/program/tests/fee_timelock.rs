@@ -0,0 +1,113 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::AmmError;
+use program::processor::FEE_CHANGE_DELAY;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const PROTOCOL_FEE_BPS: u16 = 1_000;
+
+#[tokio::test]
+async fn fee_change_applies_after_delay_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    send(&mut context, &[propose_fee_change_ix(&program_id, &payer, &pool, 2_500)?], &[]).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.protocol_fee_bps, PROTOCOL_FEE_BPS);
+    assert_eq!(pool_data.pending_protocol_fee_bps, 2_500);
+
+    // one second short of the delay
+    warp_forward(&mut context, FEE_CHANGE_DELAY - 1).await?;
+
+    let result = send(&mut context, &[execute_fee_change_ix(&program_id, &payer, &pool)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FeeChangeTimelocked as u32));
+
+    warp_forward(&mut context, 1).await?;
+
+    send(&mut context, &[execute_fee_change_ix(&program_id, &payer, &pool)], &[]).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.protocol_fee_bps, 2_500);
+    assert_eq!(pool_data.fee_change_ts, 0);
+
+    // nothing left to execute
+    let result = send(&mut context, &[execute_fee_change_ix(&program_id, &payer, &pool)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NoPendingFeeChange as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn new_proposal_restarts_delay_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    send(&mut context, &[propose_fee_change_ix(&program_id, &payer, &pool, 2_500)?], &[]).await?;
+
+    warp_forward(&mut context, FEE_CHANGE_DELAY / 2).await?;
+
+    send(&mut context, &[propose_fee_change_ix(&program_id, &payer, &pool, 500)?], &[]).await?;
+
+    warp_forward(&mut context, FEE_CHANGE_DELAY / 2).await?;
+
+    let result = send(&mut context, &[execute_fee_change_ix(&program_id, &payer, &pool)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FeeChangeTimelocked as u32));
+
+    warp_forward(&mut context, FEE_CHANGE_DELAY / 2).await?;
+
+    send(&mut context, &[execute_fee_change_ix(&program_id, &payer, &pool)], &[]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.protocol_fee_bps, 500);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fee_change_by_non_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    let stranger = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[propose_fee_change_ix(&program_id, &stranger.pubkey(), &pool, 10_000)?],
+        &[&stranger],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+
+    send(&mut context, &[propose_fee_change_ix(&program_id, &payer, &pool, 2_500)?], &[]).await?;
+
+    warp_forward(&mut context, FEE_CHANGE_DELAY).await?;
+
+    let result = send(&mut context, &[execute_fee_change_ix(&program_id, &stranger.pubkey(), &pool)], &[&stranger]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+
+    Ok(())
+}
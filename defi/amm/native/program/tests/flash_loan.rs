@@ -0,0 +1,230 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account as TokenAccount;
+
+use program::error::AmmError;
+use program::processor::process_instruction;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Example borrower. A real one would trade the borrowed tokens, e.g. arbitrage
+// them against another pool; this one only checks that they arrived, i.e. that
+// the token account holds at least the u64 passed as instruction data.
+fn process_borrower(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let token_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let expected_balance = u64::from_le_bytes(
+        instruction_data.try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let balance = TokenAccount::unpack(&token_account.data.borrow())?.amount;
+
+    if balance < expected_balance {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    msg!("Borrower holds {} tokens", balance);
+
+    Ok(())
+}
+
+async fn start_with_borrower(program_id: &Pubkey, borrower_program_id: &Pubkey) -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "program",
+        *program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program("borrower", *borrower_program_id, processor!(process_borrower));
+
+    program_test.start_with_context().await
+}
+
+fn borrower_ix(borrower_program_id: &Pubkey, token_account: &Pubkey, expected_balance: u64) -> Instruction {
+    Instruction::new_with_bytes(
+        *borrower_program_id,
+        &expected_balance.to_le_bytes(),
+        vec![AccountMeta::new_readonly(*token_account, false)],
+    )
+}
+
+#[tokio::test]
+async fn flash_loan_repaid_in_same_transaction_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let borrower_program_id = Pubkey::new_unique();
+    let mut context = start_with_borrower(&program_id, &borrower_program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let vault_a = get_associated_token_address(&pool, &mint_a);
+    let balance_before = token_balance(&mut context, &payer_ata_a).await?;
+
+    let amount = 100_000_000;
+    let fee = (amount * FEE_BPS as u64).div_ceil(10_000);
+
+    send(
+        &mut context,
+        &[
+            flash_loan_ix(&program_id, &payer, &pool, &mint_a, amount)?,
+            borrower_ix(&borrower_program_id, &payer_ata_a, balance_before + amount),
+            flash_repay_ix(&program_id, &payer, &pool, &mint_a),
+        ],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, balance_before - fee);
+    assert_eq!(token_balance(&mut context, &vault_a).await?, INITIAL_LIQUIDITY + fee);
+
+    // the fee goes to LPs
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY);
+    assert_eq!(pool_data.fees_a, fee);
+    assert_eq!(pool_data.fees_total_a, fee as u128);
+    assert_eq!(pool_data.flash_loan_amount, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flash_loan_without_repay_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let borrower_program_id = Pubkey::new_unique();
+    let mut context = start_with_borrower(&program_id, &borrower_program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let amount = 100_000_000;
+
+    let result = send(
+        &mut context,
+        &[
+            flash_loan_ix(&program_id, &payer, &pool, &mint_a, amount)?,
+            borrower_ix(&borrower_program_id, &payer_ata_a, amount),
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FlashLoanNotRepaid as u32));
+
+    // repaying in the other token doesn't count
+    let result = send(
+        &mut context,
+        &[
+            flash_loan_ix(&program_id, &payer, &pool, &mint_a, amount)?,
+            flash_repay_ix(&program_id, &payer, &pool, &mint_b),
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FlashLoanNotRepaid as u32));
+
+    // neither does a second loan taken before the repay
+    let result = send(
+        &mut context,
+        &[
+            flash_loan_ix(&program_id, &payer, &pool, &mint_a, amount)?,
+            flash_loan_ix(&program_id, &payer, &pool, &mint_b, amount)?,
+            flash_repay_ix(&program_id, &payer, &pool, &mint_b),
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FlashLoanNotRepaid as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pool_is_locked_during_flash_loan_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let amount = 100_000_000;
+
+    let result = send(
+        &mut context,
+        &[
+            flash_loan_ix(&program_id, &payer, &pool, &mint_a, amount)?,
+            swap_ix(&program_id, &payer, &pool, &mint_b, &mint_a, 1_000_000, 0)?,
+            flash_repay_ix(&program_id, &payer, &pool, &mint_a),
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FlashLoanActive as u32));
+
+    // syncing mid-loan would write the lent amount off the reserves
+    let result = send(
+        &mut context,
+        &[
+            flash_loan_ix(&program_id, &payer, &pool, &mint_a, amount)?,
+            sync_ix(&program_id, &pool, &mint_a, &mint_b),
+            flash_repay_ix(&program_id, &payer, &pool, &mint_a),
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FlashLoanActive as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flash_loan_limits_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[
+            flash_loan_ix(&program_id, &payer, &pool, &mint_a, INITIAL_LIQUIDITY + 1)?,
+            flash_repay_ix(&program_id, &payer, &pool, &mint_a),
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::ReserveExhausted as u32));
+
+    let result = send(
+        &mut context,
+        &[flash_repay_ix(&program_id, &payer, &pool, &mint_a)],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NoActiveFlashLoan as u32));
+
+    Ok(())
+}
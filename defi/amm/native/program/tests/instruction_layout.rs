@@ -0,0 +1,94 @@
+use borsh::to_vec;
+
+use solana_program::pubkey::Pubkey;
+
+use program::instruction::AmmInstruction;
+use program::state::CurveType;
+
+// The Shank IDL describes each instruction as its variant index followed by
+// the Borsh-encoded fields, so that encoding has to round-trip through the
+// hand-rolled unpack.
+fn assert_round_trips(instruction: AmmInstruction) {
+    let data = to_vec(&instruction).unwrap();
+
+    assert_eq!(AmmInstruction::unpack(&data).unwrap(), instruction);
+}
+
+#[test]
+fn idl_layout_matches_unpack() {
+    assert_round_trips(AmmInstruction::CreatePool {
+        amount_a: 1,
+        amount_b: 2,
+        fee_bps: 30,
+        protocol_fee_bps: 5,
+        curve: CurveType::Weighted { weight_a_bps: 8_000, weight_b_bps: 2_000 },
+    });
+    assert_round_trips(AmmInstruction::ProvideLiquidity {
+        amount_a_desired: 1,
+        amount_b_desired: 2,
+        amount_a_min: 3,
+        amount_b_min: 4,
+    });
+    assert_round_trips(AmmInstruction::WithdrawLiquidity { amount_lp_in: 1, amount_a_min: 2, amount_b_min: 3 });
+    assert_round_trips(AmmInstruction::Swap { amount_in: 1, min_out: 2, max_price_impact_bps: None });
+    assert_round_trips(AmmInstruction::Swap { amount_in: 1, min_out: 2, max_price_impact_bps: Some(150) });
+    assert_round_trips(AmmInstruction::CollectProtocolFees);
+    assert_round_trips(AmmInstruction::SetPaused { paused: true });
+    assert_round_trips(AmmInstruction::Sync);
+    assert_round_trips(AmmInstruction::Skim);
+    assert_round_trips(AmmInstruction::Quote { amount_in: 1, a_to_b: false });
+    assert_round_trips(AmmInstruction::SwapRoute { amount_in: 1, min_out: 2 });
+    assert_round_trips(AmmInstruction::FlashLoan { amount: 1 });
+    assert_round_trips(AmmInstruction::FlashRepay);
+    assert_round_trips(AmmInstruction::SwapNative { amount_in: 1, min_out: 2 });
+    assert_round_trips(AmmInstruction::ZapIn { amount_in: 1, min_lp_out: 2 });
+    assert_round_trips(AmmInstruction::WithdrawSingleSided {
+        amount_lp_in: 1,
+        want_mint: Pubkey::new_unique(),
+        min_out: 2,
+    });
+    assert_round_trips(AmmInstruction::SetReferralFee { referral_fee_bps: 10 });
+    assert_round_trips(AmmInstruction::SetFeeTiers { fee_tiers: vec![1, 5, 30, 100] });
+    assert_round_trips(AmmInstruction::ProposeFeeChange { protocol_fee_bps: 1_000 });
+    assert_round_trips(AmmInstruction::ExecuteFeeChange);
+    assert_round_trips(AmmInstruction::CreateFarm { reward_per_second: 1_000 });
+    assert_round_trips(AmmInstruction::StakeLp { amount: 1 });
+    assert_round_trips(AmmInstruction::UnstakeLp { amount: 2 });
+    assert_round_trips(AmmInstruction::Harvest);
+    assert_round_trips(AmmInstruction::SetOracle { max_deviation_bps: 100 });
+    assert_round_trips(AmmInstruction::PlaceLimitOrder { order_id: 7, amount_in: 1, min_out: 2 });
+    assert_round_trips(AmmInstruction::FillLimitOrder);
+    assert_round_trips(AmmInstruction::CancelLimitOrder);
+    assert_round_trips(AmmInstruction::OpenDca {
+        dca_id: 1,
+        amount_total: 2,
+        amount_per_swap: 3,
+        min_out_per_swap: 4,
+        interval: 3_600,
+    });
+    assert_round_trips(AmmInstruction::ExecuteDca);
+    assert_round_trips(AmmInstruction::CloseDca);
+    assert_round_trips(AmmInstruction::MigratePool);
+    assert_round_trips(AmmInstruction::SetPoolCreators { pool_creators: vec![Pubkey::new_unique()] });
+    assert_round_trips(AmmInstruction::WithdrawPercent { bps: 5_000, amount_a_min: 1, amount_b_min: 2 });
+    assert_round_trips(AmmInstruction::NominateAdmin { new_admin: Pubkey::new_unique() });
+    assert_round_trips(AmmInstruction::AcceptAdmin);
+    assert_round_trips(AmmInstruction::InitializePool {
+        fee_bps: 30,
+        protocol_fee_bps: 5,
+        curve: CurveType::Stable { amp: 100 },
+    });
+    assert_round_trips(AmmInstruction::InitialDeposit { amount_a: 1, amount_b: 2 });
+}
+
+#[test]
+fn swap_without_price_impact_bound_is_still_accepted() {
+    // clients predating the bound send only amount_in and min_out
+    let mut data = vec![3];
+    data.extend_from_slice(&to_vec(&(1u64, 2u64)).unwrap());
+
+    assert_eq!(
+        AmmInstruction::unpack(&data).unwrap(),
+        AmmInstruction::Swap { amount_in: 1, min_out: 2, max_price_impact_bps: None },
+    );
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::curve::swap_output;
+use program::math::bps_floor;
+use program::state::CurveType;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+async fn setup_pool_with_reserves(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    reserves: (u64, u64),
+    funds: (u64, u64),
+) -> Result<(Pubkey, Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_mint(context).await?;
+    let mint_b = create_mint(context).await?;
+
+    create_funded_ata(context, &payer, &mint_a, funds.0).await?;
+    create_funded_ata(context, &payer, &mint_b, funds.1).await?;
+
+    send(
+        context,
+        &[
+            create_pool_ix(
+                program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                reserves.0,
+                reserves.1,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    Ok((mint_a, mint_b, pool_address(program_id, &mint_a, &mint_b, FEE_BPS)))
+}
+
+fn k(reserve_a: u64, reserve_b: u64) -> u128 {
+    reserve_a as u128 * reserve_b as u128
+}
+
+// reserve_out * amount_in used to overflow u128 at these sizes
+#[tokio::test]
+async fn swap_with_huge_reserves_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let reserve = u64::MAX / 4;
+    let amount_in = u64::MAX / 4;
+
+    let (mint_a, mint_b, pool) =
+        setup_pool_with_reserves(&mut context, &program_id, (reserve, reserve), (reserve + amount_in, reserve)).await?;
+
+    let expected_out = swap_output(CurveType::ConstantProduct, reserve, reserve, amount_in, FEE_BPS, true)?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, expected_out)?],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+    let lp_fee = bps_floor(amount_in, FEE_BPS).unwrap();
+
+    assert_eq!(pool_data.reserve_a, reserve + amount_in - lp_fee);
+    assert_eq!(pool_data.reserve_b, reserve - expected_out);
+    assert_eq!(pool_data.fees_a, lp_fee);
+    assert!(k(pool_data.reserve_a, pool_data.reserve_b) >= k(reserve, reserve));
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &mint_b)).await?, expected_out);
+
+    Ok(())
+}
+
+// Lopsided pool where one side is tiny: outputs round down to almost
+// nothing, and k must still not shrink.
+#[tokio::test]
+async fn swap_with_lopsided_reserves_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let reserve_a = u64::MAX / 2;
+    let reserve_b = 10_000;
+
+    let (mint_a, mint_b, pool) =
+        setup_pool_with_reserves(&mut context, &program_id, (reserve_a, reserve_b), (u64::MAX, reserve_b + 1_000)).await?;
+
+    send(
+        &mut context,
+        &[
+            swap_ix(&program_id, &payer, &pool, &mint_b, &mint_a, 1, 0)?,
+            swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, u64::MAX / 4, 0)?,
+        ],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert!(k(pool_data.reserve_a, pool_data.reserve_b) >= k(reserve_a, reserve_b));
+    assert!(pool_data.reserve_b > 0);
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::get_associated_token_address;
+
+use program::limit_order::{
+    error::{LimitOrderError, LIMIT_ORDER_ERROR_OFFSET},
+    state::FILL_BOUNTY_LAMPORTS,
+};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const ORDER_ID: u64 = 1;
+const ORDER_AMOUNT: u64 = 10_000_000;
+
+// A funded keypair to crank fills with, so its lamports only move by the bounty.
+async fn setup_cranker(context: &mut ProgramTestContext) -> Result<Keypair> {
+    let payer = context.payer.pubkey();
+    let cranker = Keypair::new();
+
+    send(context, &[transfer(&payer, &cranker.pubkey(), 1_000_000_000)], &[]).await?;
+
+    Ok(cranker)
+}
+
+fn limit_order_error(error: LimitOrderError) -> Option<u32> {
+    Some(LIMIT_ORDER_ERROR_OFFSET + error as u32)
+}
+
+#[tokio::test]
+async fn fill_after_price_crosses_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let cranker = setup_cranker(&mut context).await?;
+
+    // sell A for B at 1.0 or better, above the pool's price after fees
+    send(
+        &mut context,
+        &[place_limit_order_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ORDER_ID, ORDER_AMOUNT, ORDER_AMOUNT)?],
+        &[],
+    ).await?;
+
+    let fill_ix = fill_limit_order_ix(&program_id, &cranker.pubkey(), &pool, &payer, &mint_a, &mint_b, ORDER_ID);
+
+    let result = send(&mut context, std::slice::from_ref(&fill_ix), &[&cranker]).await;
+
+    assert_eq!(custom_error(result), limit_order_error(LimitOrderError::OrderPriceNotReached));
+
+    // buying A pushes its price up past the order's
+    send(&mut context, &[swap_ix(&program_id, &payer, &pool, &mint_b, &mint_a, 100_000_000, 0)?], &[]).await?;
+
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let b_before = token_balance(&mut context, &payer_ata_b).await?;
+    let cranker_before = context.banks_client.get_balance(cranker.pubkey()).await?;
+
+    send(&mut context, &[fill_ix], &[&cranker]).await?;
+
+    assert!(token_balance(&mut context, &payer_ata_b).await? - b_before >= ORDER_AMOUNT);
+    assert_eq!(context.banks_client.get_balance(cranker.pubkey()).await? - cranker_before, FILL_BOUNTY_LAMPORTS);
+
+    // order and escrow are closed
+    let order = limit_order_address(&program_id, &pool, &payer, ORDER_ID);
+
+    assert!(context.banks_client.get_account(order).await?.is_none());
+    assert!(context.banks_client.get_account(get_associated_token_address(&order, &mint_a)).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fill_with_donated_escrow_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let cranker = setup_cranker(&mut context).await?;
+
+    // an order that fills at any price
+    send(
+        &mut context,
+        &[place_limit_order_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ORDER_ID, ORDER_AMOUNT, 1)?],
+        &[],
+    ).await?;
+
+    // tokens sent to the escrow after placing don't keep it from closing,
+    // they're swapped along with the order
+    let order = limit_order_address(&program_id, &pool, &payer, ORDER_ID);
+    let escrow = get_associated_token_address(&order, &mint_a);
+
+    donate(&mut context, &mint_a, &escrow, ORDER_AMOUNT / 2).await?;
+
+    let vault_a = get_associated_token_address(&pool, &mint_a);
+    let vault_a_before = token_balance(&mut context, &vault_a).await?;
+
+    send(
+        &mut context,
+        &[fill_limit_order_ix(&program_id, &cranker.pubkey(), &pool, &payer, &mint_a, &mint_b, ORDER_ID)],
+        &[&cranker],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &vault_a).await? - vault_a_before, ORDER_AMOUNT + ORDER_AMOUNT / 2);
+    assert!(context.banks_client.get_account(order).await?.is_none());
+    assert!(context.banks_client.get_account(escrow).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_returns_escrow_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let a_before = token_balance(&mut context, &payer_ata_a).await?;
+
+    send(
+        &mut context,
+        &[place_limit_order_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ORDER_ID, ORDER_AMOUNT, ORDER_AMOUNT)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, a_before - ORDER_AMOUNT);
+
+    send(&mut context, &[cancel_limit_order_ix(&program_id, &payer, &pool, &mint_a, ORDER_ID)], &[]).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, a_before);
+    assert!(context.banks_client.get_account(limit_order_address(&program_id, &pool, &payer, ORDER_ID)).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fill_pays_only_the_owner_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let cranker = setup_cranker(&mut context).await?;
+    create_ata(&mut context, &cranker.pubkey(), &mint_b).await?;
+
+    // an order that fills at any price
+    send(
+        &mut context,
+        &[place_limit_order_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ORDER_ID, ORDER_AMOUNT, 1)?],
+        &[],
+    ).await?;
+
+    // the cranker points the output at its own account
+    let mut fill_ix = fill_limit_order_ix(&program_id, &cranker.pubkey(), &pool, &payer, &mint_a, &mint_b, ORDER_ID);
+    fill_ix.accounts[9].pubkey = get_associated_token_address(&cranker.pubkey(), &mint_b);
+
+    let result = send(&mut context, &[fill_ix], &[&cranker]).await;
+
+    assert_eq!(custom_error(result), limit_order_error(LimitOrderError::OrderAccountMismatch));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_by_stranger_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    send(
+        &mut context,
+        &[place_limit_order_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ORDER_ID, ORDER_AMOUNT, ORDER_AMOUNT)?],
+        &[],
+    ).await?;
+
+    let stranger = Keypair::new();
+    create_ata(&mut context, &stranger.pubkey(), &mint_a).await?;
+
+    let mut cancel_ix = cancel_limit_order_ix(&program_id, &stranger.pubkey(), &pool, &mint_a, ORDER_ID);
+    // the payer's order, under the stranger's signature
+    cancel_ix.accounts[2].pubkey = limit_order_address(&program_id, &pool, &payer, ORDER_ID);
+
+    let result = send(&mut context, &[cancel_ix], &[&stranger]).await;
+
+    assert_eq!(custom_error(result), limit_order_error(LimitOrderError::OrderAccountMismatch));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn zero_amount_order_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[place_limit_order_ix(&program_id, &payer, &pool, &mint_a, &mint_b, ORDER_ID, 0, ORDER_AMOUNT)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), limit_order_error(LimitOrderError::ZeroOrderAmount));
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::math::withdraw_amounts;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn withdraw_pays_out_lp_fees_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let amount_in = 10_000_000;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0)?],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.fees_a, 30_000);
+    assert_eq!(pool_data.fees_b, 0);
+    assert_eq!(pool_data.fees_total_a, 30_000);
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + amount_in - 30_000);
+
+    let mint_lp = lp_mint_address(&program_id, &pool);
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let amount_lp = token_balance(&mut context, &get_associated_token_address(&payer, &mint_lp)).await?;
+    let total_lp = mint_supply(&mut context, &mint_lp).await?;
+    let balance_a_before = token_balance(&mut context, &payer_ata_a).await?;
+
+    send(
+        &mut context,
+        &[withdraw_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_lp, (0, 0))?],
+        &[],
+    ).await?;
+
+    let (reserve_a_out, _) = withdraw_amounts(pool_data.reserve_a, pool_data.reserve_b, total_lp, amount_lp).unwrap();
+    let (fees_a_out, _) = withdraw_amounts(pool_data.fees_a, pool_data.fees_b, total_lp, amount_lp).unwrap();
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, balance_a_before + reserve_a_out + fees_a_out);
+
+    // the locked MINIMUM_LIQUIDITY keeps its share; the lifetime total stays
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.fees_a, 30_000 - fees_a_out);
+    assert_eq!(pool_data.fees_total_a, 30_000);
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&pool, &mint_a)).await?,
+        pool_data.reserve_a + pool_data.fees_a,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deposit_is_priced_against_fees_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    send(
+        &mut context,
+        &[
+            swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 0)?,
+            swap_ix(&program_id, &payer, &pool, &mint_b, &mint_a, 10_000_000, 0)?,
+        ],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+    let mint_lp = lp_mint_address(&program_id, &pool);
+    let total_lp = mint_supply(&mut context, &mint_lp).await?;
+
+    // deposit at the reserves' ratio
+    let amount_a = pool_data.reserve_a / 10;
+    let amount_b = pool_data.reserve_b / 10;
+
+    send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (amount_a, amount_b), (0, 0))?],
+        &[],
+    ).await?;
+
+    // a newcomer gets no share of fees earned before it joined
+    let minted = mint_supply(&mut context, &mint_lp).await? - total_lp;
+    let lp_from_reserves = (amount_a as u128 * total_lp as u128 / pool_data.reserve_a as u128) as u64;
+
+    assert!(minted < lp_from_reserves);
+    assert!(minted as u128 * (pool_data.reserve_a + pool_data.fees_a) as u128 <= amount_a as u128 * total_lp as u128);
+
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use proptest::prelude::*;
+
+use program::math::{
+    ONE,
+    Q64,
+    ln,
+    exp,
+    pow,
+    zap_swap_amount,
+    split_lp_fee,
+    mul_div_floor,
+    mul_div_ceil,
+    bps_floor,
+    bps_ceil,
+    sqrt,
+    q64_ratio,
+    q64_mul_floor,
+    q64_mul_ceil,
+};
+
+fn assert_close(actual: u128, expected: u128, relative: u128) {
+    let tolerance = expected / relative + 1;
+
+    assert!(
+        actual.abs_diff(expected) <= tolerance,
+        "{actual} is not within {tolerance} of {expected}",
+    );
+}
+
+#[test]
+fn ln_of_known_values() {
+    assert_eq!(ln(ONE), Some(0));
+    assert_eq!(ln(0), None);
+
+    assert_close(ln(2 * ONE).unwrap() as u128, 693_147_180_559_945_309, 1_000_000_000_000);
+    assert_close((-ln(ONE / 2).unwrap()) as u128, 693_147_180_559_945_309, 1_000_000_000_000);
+    assert_close(ln(10 * ONE).unwrap() as u128, 2_302_585_092_994_045_684, 1_000_000_000_000);
+}
+
+#[test]
+fn exp_of_known_values() {
+    assert_eq!(exp(0), Some(ONE));
+
+    assert_close(exp(ONE as i128).unwrap(), 2_718_281_828_459_045_235, 1_000_000_000_000);
+    assert_close(exp(-(ONE as i128)).unwrap(), 367_879_441_171_442_321, 1_000_000_000_000);
+    assert_eq!(exp(100 * ONE as i128), None);
+}
+
+#[test]
+fn pow_of_known_values() {
+    assert_close(pow(4 * ONE, ONE / 2).unwrap(), 2 * ONE, 1_000_000_000_000);
+    assert_close(pow(2 * ONE, 10 * ONE).unwrap(), 1_024 * ONE, 1_000_000_000_000);
+    assert_close(pow(ONE / 2, 3 * ONE).unwrap(), ONE / 8, 1_000_000_000_000);
+}
+
+#[test]
+fn mul_div_of_known_values() {
+    assert_eq!(mul_div_floor(7, 3, 2), Some(10));
+    assert_eq!(mul_div_ceil(7, 3, 2), Some(11));
+    assert_eq!(mul_div_floor(6, 3, 2), Some(9));
+    assert_eq!(mul_div_ceil(6, 3, 2), Some(9));
+    assert_eq!(mul_div_floor(1, 1, 3), Some(0));
+    assert_eq!(mul_div_ceil(1, 1, 3), Some(1));
+
+    // the product may exceed u128 as long as the quotient doesn't
+    assert_eq!(mul_div_floor(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+    assert_eq!(mul_div_ceil(u128::MAX, 3, 4), Some(u128::MAX - u128::MAX / 4));
+    assert_eq!(mul_div_floor(u128::MAX, 2, 1), None);
+
+    assert_eq!(mul_div_floor(1, 1, 0), None);
+    assert_eq!(mul_div_ceil(1, 1, 0), None);
+}
+
+// Every small input against the exact rational result.
+#[test]
+fn mul_div_exhaustive_small_values() {
+    for a in 0..40u128 {
+        for b in 0..40u128 {
+            for c in 1..40u128 {
+                let floor = mul_div_floor(a, b, c).unwrap();
+                let ceil = mul_div_ceil(a, b, c).unwrap();
+
+                assert!(floor * c <= a * b && a * b < (floor + 1) * c, "floor({a} * {b} / {c}) = {floor}");
+                assert!(ceil * c >= a * b && (ceil == 0 || a * b > (ceil - 1) * c), "ceil({a} * {b} / {c}) = {ceil}");
+            }
+        }
+    }
+}
+
+#[test]
+fn bps_of_known_values() {
+    assert_eq!(bps_floor(10_000, 30), Some(30));
+    assert_eq!(bps_floor(9_999, 30), Some(29));
+    assert_eq!(bps_ceil(9_999, 30), Some(30));
+    assert_eq!(bps_floor(1, 9_999), Some(0));
+    assert_eq!(bps_ceil(1, 1), Some(1));
+    assert_eq!(bps_floor(u64::MAX, 10_000), Some(u64::MAX));
+    assert_eq!(bps_floor(u64::MAX, 10_001), None);
+}
+
+#[test]
+fn sqrt_of_known_values() {
+    assert_eq!(sqrt(0), 0);
+    assert_eq!(sqrt(1), 1);
+    assert_eq!(sqrt(15), 3);
+    assert_eq!(sqrt(16), 4);
+    assert_eq!(sqrt(u64::MAX as u128 * u64::MAX as u128), u64::MAX as u128);
+    assert_eq!(sqrt(u128::MAX), u64::MAX as u128);
+
+    for x in 0..10_000u128 {
+        let root = sqrt(x);
+
+        assert!(root * root <= x && (root + 1) * (root + 1) > x, "sqrt({x}) = {root}");
+    }
+}
+
+#[test]
+fn q64_of_known_values() {
+    assert_eq!(q64_ratio(1, 1), Some(Q64));
+    assert_eq!(q64_ratio(1, 2), Some(Q64 / 2));
+    assert_eq!(q64_ratio(3, 2), Some(Q64 + Q64 / 2));
+    assert_eq!(q64_ratio(u64::MAX, 1), Some((u64::MAX as u128) << 64));
+    assert_eq!(q64_ratio(1, 0), None);
+
+    assert_eq!(q64_mul_floor(Q64 / 2, 3), Some(1));
+    assert_eq!(q64_mul_ceil(Q64 / 2, 3), Some(2));
+    assert_eq!(q64_mul_floor(Q64, u64::MAX), Some(u64::MAX));
+    assert_eq!(q64_mul_floor(2 * Q64, u64::MAX), None);
+
+    // 1/3 isn't exact in binary, so a third of 3 rounds to either side
+    let third = q64_ratio(1, 3).unwrap();
+
+    assert_eq!(q64_mul_floor(third, 3), Some(0));
+    assert_eq!(q64_mul_ceil(third, 3), Some(1));
+}
+
+#[test]
+fn zap_swap_amount_of_known_values() {
+    // no fee: sqrt(R^2 + R a) - R
+    assert_eq!(zap_swap_amount(1_000, 3_000, 0), Some(1_000));
+    assert_eq!(zap_swap_amount(1_000_000, 0, 30), Some(0));
+
+    // tiny deposits into a deep pool split almost exactly in half
+    assert_eq!(zap_swap_amount(1_000_000_000_000, 1_000_000, 0), Some(499_999));
+    assert_eq!(zap_swap_amount(1_000_000, 1_000, 10_000), None);
+    assert_eq!(zap_swap_amount(1_000_000, 1_000, 10_001), None);
+}
+
+#[test]
+fn split_lp_fee_of_known_values() {
+    // 30 bps of 10_000_000, none of it taken by the treasury
+    assert_eq!(split_lp_fee(10_000_000, 10_000_000, 30), Some((9_970_000, 30_000)));
+    // 6_000 of the fee went to the treasury
+    assert_eq!(split_lp_fee(9_994_000, 10_000_000, 30), Some((9_970_000, 24_000)));
+    // the whole fee went elsewhere
+    assert_eq!(split_lp_fee(9_970_000, 10_000_000, 30), Some((9_970_000, 0)));
+    assert_eq!(split_lp_fee(1_000, 1_000, 0), Some((1_000, 0)));
+}
+
+// Output of a constant-product swap, matching the AMM's rounding.
+fn swap_out(reserve_in: u64, reserve_out: u64, amount_in: u64, fee_bps: u16) -> u64 {
+    let amount_in_post_fee = amount_in as u128 * (10_000 - fee_bps as u128);
+
+    (reserve_out as u128 * amount_in_post_fee / (reserve_in as u128 * 10_000 + amount_in_post_fee)) as u64
+}
+
+proptest! {
+    #[test]
+    fn mul_div_ceil_is_floor_plus_remainder(a in any::<u64>(), b in any::<u64>(), c in 1u64..) {
+        let floor = mul_div_floor(a as u128, b as u128, c as u128).unwrap();
+        let ceil = mul_div_ceil(a as u128, b as u128, c as u128).unwrap();
+        let exact = (a as u128 * b as u128).is_multiple_of(c as u128);
+
+        prop_assert_eq!(ceil, if exact { floor } else { floor + 1 });
+    }
+
+    #[test]
+    fn bps_floor_and_ceil_differ_by_remainder(amount in any::<u64>(), bps in 0u16..=10_000) {
+        let floor = bps_floor(amount, bps).unwrap();
+        let ceil = bps_ceil(amount, bps).unwrap();
+
+        prop_assert!(floor <= amount);
+        prop_assert!(ceil - floor <= 1);
+        prop_assert_eq!(ceil == floor, (amount as u128 * bps as u128).is_multiple_of(10_000));
+    }
+
+    #[test]
+    fn sqrt_is_floor_root(x in any::<u128>()) {
+        let root = sqrt(x);
+
+        prop_assert!(root * root <= x);
+        prop_assert!((root + 1).checked_mul(root + 1).is_none_or(|next| next > x));
+    }
+
+    #[test]
+    fn q64_ratio_round_trips_within_a_unit(num in any::<u64>(), den in 1u64..) {
+        let ratio = q64_ratio(num, den).unwrap();
+
+        // num / den * den, rounded both ways, brackets num
+        if let Some(floor) = q64_mul_floor(ratio, den) {
+            prop_assert!(floor <= num && num - floor <= 1, "{floor} vs {num}");
+        }
+
+        if let Some(ceil) = q64_mul_ceil(ratio, den) {
+            prop_assert!(ceil <= num, "{ceil} > {num}");
+        }
+    }
+
+    #[test]
+    fn exp_inverts_ln(x in 1_000_000u128..1_000_000_000_000_000_000_000_000_000) {
+        let roundtrip = exp(ln(x).unwrap()).unwrap();
+
+        prop_assert!(roundtrip.abs_diff(x) <= x / 1_000_000_000_000 + 1);
+    }
+
+    #[test]
+    fn pow_with_unit_exponent_is_identity(x in 1_000_000u128..1_000_000_000_000_000_000_000_000) {
+        let power = pow(x, ONE).unwrap();
+
+        prop_assert!(power.abs_diff(x) <= x / 1_000_000_000_000 + 1);
+    }
+
+    #[test]
+    fn pow_of_ratio_below_one_stays_below_one(
+        ratio in 1u128..ONE,
+        exponent in 1u128..100 * ONE,
+    ) {
+        if let Some(power) = pow(ratio, exponent) {
+            prop_assert!(power <= ONE);
+        }
+    }
+
+    #[test]
+    fn zap_split_matches_post_swap_ratio(
+        reserve_in in 1_000_000_000u64..1_000_000_000_000_000,
+        reserve_out_ratio in 1u64..100,
+        amount_in_bps in 1u64..10_000,
+        fee_bps in 0u16..1_000,
+    ) {
+        let reserve_out = reserve_in / 10 * reserve_out_ratio;
+        let amount_in = reserve_in / 10_000 * amount_in_bps;
+        let swap_amount = zap_swap_amount(reserve_in, amount_in, fee_bps).unwrap();
+
+        prop_assert!(swap_amount <= amount_in);
+
+        let out = swap_out(reserve_in, reserve_out, swap_amount, fee_bps);
+
+        // the LP fee stays out of the reserve
+        let new_reserve_in = reserve_in + swap_amount - bps_floor(swap_amount, fee_bps).unwrap();
+
+        // (amount_in - swap_amount) / out == new_reserve_in / (reserve_out - out)
+        let left = (amount_in - swap_amount) as u128 * (reserve_out - out) as u128;
+        let right = out as u128 * new_reserve_in as u128;
+
+        prop_assert!(left.abs_diff(right) <= right / 1_000 + 1, "{left} vs {right}");
+    }
+}
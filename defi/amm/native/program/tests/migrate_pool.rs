@@ -0,0 +1,245 @@
+mod common;
+
+use anyhow::Result;
+
+use borsh::to_vec;
+
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+use program::error::AmmError;
+use program::state::{LiquidityPool, LiquidityPoolV1, LiquidityPoolV2, LiquidityPoolV3, LiquidityPoolV4};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// The pool as it was laid out before the version byte.
+fn v1_layout(pool_data: &LiquidityPool) -> LiquidityPoolV1 {
+    LiquidityPoolV1 {
+        discriminator: pool_data.discriminator,
+        is_initialized: pool_data.is_initialized,
+        mint_a: pool_data.mint_a,
+        mint_b: pool_data.mint_b,
+        reserve_a: pool_data.reserve_a,
+        reserve_b: pool_data.reserve_b,
+        fee_bps: pool_data.fee_bps,
+        protocol_fee_bps: pool_data.protocol_fee_bps,
+        referral_fee_bps: pool_data.referral_fee_bps,
+        curve: pool_data.curve,
+        admin: pool_data.admin,
+        paused: pool_data.paused,
+        price_cumulative_a: pool_data.price_cumulative_a,
+        price_cumulative_b: pool_data.price_cumulative_b,
+        last_update_ts: pool_data.last_update_ts,
+        flash_loan_amount: pool_data.flash_loan_amount,
+        flash_loan_mint: pool_data.flash_loan_mint,
+        pending_protocol_fee_bps: pool_data.pending_protocol_fee_bps,
+        fee_change_ts: pool_data.fee_change_ts,
+        oracle: pool_data.oracle,
+        max_oracle_deviation_bps: pool_data.max_oracle_deviation_bps,
+        bump: pool_data.bump,
+    }
+}
+
+// Rewrites a freshly created pool with the layout that predates the version
+// byte, funded for that size only, as pools deployed before it look.
+async fn inject_v1_pool(
+    context: &mut solana_program_test::ProgramTestContext,
+    pool: &Pubkey,
+) -> Result<LiquidityPool> {
+    let pool_data = pool_state(context, pool).await?;
+
+    let mut data = to_vec(&v1_layout(&pool_data))?;
+    data.resize(LiquidityPoolV1::SPACE, 0);
+
+    let account: Account = context.banks_client.get_account(*pool).await?.unwrap();
+    let rent = context.banks_client.get_rent().await?;
+
+    context.set_account(
+        pool,
+        &AccountSharedData::from(Account {
+            lamports: rent.minimum_balance(LiquidityPoolV1::SPACE),
+            data,
+            ..account
+        }),
+    );
+
+    Ok(pool_data)
+}
+
+#[tokio::test]
+async fn migrate_v1_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let before = inject_v1_pool(&mut context, &pool).await?;
+
+    // the current layout can't be read until the pool is migrated
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::PoolNeedsMigration as u32));
+
+    send(&mut context, &[migrate_pool_ix(&program_id, &payer, &pool, &mint_a, &mint_b)], &[]).await?;
+
+    let account = context.banks_client.get_account(pool).await?.unwrap();
+    let rent = context.banks_client.get_rent().await?;
+
+    assert_eq!(account.data.len(), LiquidityPool::SPACE);
+    assert!(rent.is_exempt(account.lamports, LiquidityPool::SPACE));
+    assert_eq!(to_vec(&pool_state(&mut context, &pool).await?)?, to_vec(&before)?);
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_v2_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let before = pool_state(&mut context, &pool).await?;
+
+    // a version 2 pool is a version 1 pool that went through MigratePool once
+    let v2 = v1_layout(&before).migrate();
+
+    let mut data = to_vec(&v2)?;
+    data.resize(LiquidityPoolV2::SPACE, 0);
+
+    let account: Account = context.banks_client.get_account(pool).await?.unwrap();
+    let rent = context.banks_client.get_rent().await?;
+
+    context.set_account(
+        &pool,
+        &AccountSharedData::from(Account {
+            lamports: rent.minimum_balance(LiquidityPoolV2::SPACE),
+            data,
+            ..account
+        }),
+    );
+
+    send(&mut context, &[migrate_pool_ix(&program_id, &payer, &pool, &mint_a, &mint_b)], &[]).await?;
+
+    let account = context.banks_client.get_account(pool).await?.unwrap();
+
+    assert_eq!(account.data.len(), LiquidityPool::SPACE);
+    assert_eq!(to_vec(&pool_state(&mut context, &pool).await?)?, to_vec(&before)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_v3_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let before = pool_state(&mut context, &pool).await?;
+
+    // a version 3 pool, from before LP fees were kept out of the reserves
+    let v3 = v1_layout(&before).migrate().migrate();
+
+    let mut data = to_vec(&v3)?;
+    data.resize(LiquidityPoolV3::SPACE, 0);
+
+    let account: Account = context.banks_client.get_account(pool).await?.unwrap();
+    let rent = context.banks_client.get_rent().await?;
+
+    context.set_account(
+        &pool,
+        &AccountSharedData::from(Account {
+            lamports: rent.minimum_balance(LiquidityPoolV3::SPACE),
+            data,
+            ..account
+        }),
+    );
+
+    send(&mut context, &[migrate_pool_ix(&program_id, &payer, &pool, &mint_a, &mint_b)], &[]).await?;
+
+    let account = context.banks_client.get_account(pool).await?.unwrap();
+
+    assert_eq!(account.data.len(), LiquidityPool::SPACE);
+    assert_eq!(to_vec(&pool_state(&mut context, &pool).await?)?, to_vec(&before)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_v4_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let before = pool_state(&mut context, &pool).await?;
+
+    // a version 4 pool, from before the mint decimals were cached
+    let v4 = v1_layout(&before).migrate().migrate().migrate();
+
+    let mut data = to_vec(&v4)?;
+    data.resize(LiquidityPoolV4::SPACE, 0);
+
+    let account: Account = context.banks_client.get_account(pool).await?.unwrap();
+    let rent = context.banks_client.get_rent().await?;
+
+    context.set_account(
+        &pool,
+        &AccountSharedData::from(Account {
+            lamports: rent.minimum_balance(LiquidityPoolV4::SPACE),
+            data,
+            ..account
+        }),
+    );
+
+    // the decimals have to come from the pool's own mints
+    let result = send(&mut context, &[migrate_pool_ix(&program_id, &payer, &pool, &mint_b, &mint_a)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::MintAddressMismatch as u32));
+
+    send(&mut context, &[migrate_pool_ix(&program_id, &payer, &pool, &mint_a, &mint_b)], &[]).await?;
+
+    let account = context.banks_client.get_account(pool).await?.unwrap();
+
+    assert_eq!(account.data.len(), LiquidityPool::SPACE);
+    assert_eq!(to_vec(&pool_state(&mut context, &pool).await?)?, to_vec(&before)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_current_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.version, LiquidityPool::VERSION);
+
+    let result = send(&mut context, &[migrate_pool_ix(&program_id, &payer, &pool, &mint_a, &mint_b)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::PoolAlreadyCurrent as u32));
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+use program::processor::MINIMUM_LIQUIDITY;
+use program::state::CurveType;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn create_pool_locks_minimum_liquidity_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let mint_lp = lp_mint_address(&program_id, &pool);
+
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&pool, &mint_lp)).await?,
+        MINIMUM_LIQUIDITY,
+    );
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&payer, &mint_lp)).await?,
+        INITIAL_LIQUIDITY - MINIMUM_LIQUIDITY,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_below_minimum_liquidity_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_mint(&mut context).await?;
+    let mint_b = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(&mut context, &payer, &mint_b, USER_FUNDS).await?;
+
+    let result = send(
+        &mut context,
+        &[
+            create_pool_ix(
+                &program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                MINIMUM_LIQUIDITY,
+                MINIMUM_LIQUIDITY,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::InsufficientInitialLiquidity as u32));
+
+    Ok(())
+}
+
+// The first depositor seeds a tiny pool, then donates a large amount and syncs
+// to inflate the value of each LP token so later deposits round down to nothing.
+// With MINIMUM_LIQUIDITY locked, most of the donation accrues to the locked
+// share and the victim still gets a fair amount of LP.
+#[tokio::test]
+async fn first_depositor_inflation_attack_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let attacker = context.payer.pubkey();
+
+    let mint_a = create_mint(&mut context).await?;
+    let mint_b = create_mint(&mut context).await?;
+
+    let attacker_ata_a = create_funded_ata(&mut context, &attacker, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(&mut context, &attacker, &mint_b, USER_FUNDS).await?;
+
+    let seed = MINIMUM_LIQUIDITY + 1;
+
+    send(
+        &mut context,
+        &[
+            create_pool_ix(
+                &program_id,
+                &attacker,
+                &mint_a,
+                &mint_b,
+                seed,
+                seed,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    let pool = pool_address(&program_id, &mint_a, &mint_b, FEE_BPS);
+    let mint_lp = lp_mint_address(&program_id, &pool);
+    let attacker_ata_lp = get_associated_token_address(&attacker, &mint_lp);
+
+    assert_eq!(token_balance(&mut context, &attacker_ata_lp).await?, 1);
+
+    let donation = 1_000_000_000;
+
+    donate(&mut context, &mint_a, &get_associated_token_address(&pool, &mint_a), donation).await?;
+    donate(&mut context, &mint_b, &get_associated_token_address(&pool, &mint_b), donation).await?;
+    send(&mut context, &[sync_ix(&program_id, &pool, &mint_a, &mint_b)], &[]).await?;
+
+    let victim = Keypair::new();
+    let deposit = 100_000_000;
+
+    let victim_ata_a = create_funded_ata(&mut context, &victim.pubkey(), &mint_a, deposit).await?;
+    create_funded_ata(&mut context, &victim.pubkey(), &mint_b, deposit).await?;
+    let victim_ata_lp = create_ata(&mut context, &victim.pubkey(), &mint_lp).await?;
+
+    send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &victim.pubkey(), &pool, &mint_a, &mint_b, (deposit, deposit), (0, 0))?],
+        &[&victim],
+    ).await?;
+
+    let victim_lp = token_balance(&mut context, &victim_ata_lp).await?;
+
+    assert!(victim_lp > 0);
+
+    let attacker_balance_before = token_balance(&mut context, &attacker_ata_a).await?;
+
+    send(
+        &mut context,
+        &[withdraw_liquidity_ix(&program_id, &attacker, &pool, &mint_a, &mint_b, 1, (0, 0))?],
+        &[],
+    ).await?;
+
+    let attacker_recovered = token_balance(&mut context, &attacker_ata_a).await? - attacker_balance_before;
+
+    assert!(attacker_recovered < donation / 1_000);
+
+    send(
+        &mut context,
+        &[withdraw_liquidity_ix(&program_id, &victim.pubkey(), &pool, &mint_a, &mint_b, victim_lp, (0, 0))?],
+        &[&victim],
+    ).await?;
+
+    assert!(token_balance(&mut context, &victim_ata_a).await? >= deposit * 99 / 100);
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    clock::Clock,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::AmmError;
+use program::pyth::{
+    PythPrice,
+    PYTH_MAGIC,
+    PYTH_VERSION,
+    PYTH_PRICE_ACCOUNT_TYPE,
+    PYTH_STATUS_TRADING,
+    PYTH_PRICE_ACCOUNT_LEN,
+    MAX_ORACLE_AGE,
+    deviation_bps,
+};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const MAX_DEVIATION_BPS: u16 = 100;
+
+// 1.0 with Pyth's usual 8 decimal places
+const PRICE_ONE: i64 = 100_000_000;
+const EXPO: i32 = -8;
+
+// A Pyth v2 price account holding just the header and aggregate price.
+fn pyth_price_data(price: i64, conf: u64, expo: i32, publish_time: i64) -> Vec<u8> {
+    let mut data = vec![0; PYTH_PRICE_ACCOUNT_LEN];
+
+    data[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+    data[4..8].copy_from_slice(&PYTH_VERSION.to_le_bytes());
+    data[8..12].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+    data[12..16].copy_from_slice(&(PYTH_PRICE_ACCOUNT_LEN as u32).to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+
+    data
+}
+
+// Writes a mocked price account at `oracle`, published `age` seconds ago.
+async fn set_oracle_price(
+    context: &mut ProgramTestContext,
+    oracle: &Pubkey,
+    price: i64,
+    conf: u64,
+    age: i64,
+) -> Result<()> {
+    let now = context.banks_client.get_sysvar::<Clock>().await?.unix_timestamp;
+
+    context.set_account(
+        oracle,
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000_000,
+            data: pyth_price_data(price, conf, EXPO, now - age),
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        }),
+    );
+
+    Ok(())
+}
+
+// Creates a pool priced at 1.0 with an oracle also at 1.0.
+// Returns (mint_a, mint_b, pool, oracle).
+async fn setup_oracle_pool(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+) -> Result<(Pubkey, Pubkey, Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(context, program_id, FEE_BPS, 0).await?;
+
+    let oracle = Pubkey::new_unique();
+    set_oracle_price(context, &oracle, PRICE_ONE, 0, 0).await?;
+
+    send(context, &[set_oracle_ix(program_id, &payer, &pool, Some(&oracle), MAX_DEVIATION_BPS)?], &[]).await?;
+
+    Ok((mint_a, mint_b, pool, oracle))
+}
+
+#[test]
+fn deviation_scales_by_decimals() {
+    let oracle = PythPrice { price: 150 * PRICE_ONE, conf: 0, expo: EXPO, publish_time: 0, status: PYTH_STATUS_TRADING };
+
+    // 1 A (9 decimals) for 150 B (6 decimals) is exactly the oracle price
+    assert_eq!(deviation_bps(&oracle, 9, 6, 1_000_000_000, 150_000_000).unwrap(), 0);
+
+    // 1% short in either direction
+    assert_eq!(deviation_bps(&oracle, 9, 6, 1_000_000_000, 148_500_000).unwrap(), 100);
+    assert_eq!(deviation_bps(&oracle, 9, 6, 1_000_000_000, 151_500_000).unwrap(), 100);
+
+    // nothing out is as far off as it gets
+    assert_eq!(deviation_bps(&oracle, 9, 6, 0, 150_000_000).unwrap(), u64::MAX);
+}
+
+#[test]
+fn price_checks() {
+    let price = PythPrice::load(&pyth_price_data(PRICE_ONE, PRICE_ONE as u64 / 100, EXPO, 1_000)).unwrap();
+
+    assert_eq!(price.price, PRICE_ONE);
+    assert_eq!(price.expo, EXPO);
+    assert!(price.checked(1_000 + MAX_ORACLE_AGE).is_ok());
+    assert!(price.checked(1_000 + MAX_ORACLE_AGE + 1).is_err());
+
+    let wide = PythPrice::load(&pyth_price_data(PRICE_ONE, PRICE_ONE as u64 / 10, EXPO, 1_000)).unwrap();
+
+    assert!(wide.checked(1_000).is_err());
+
+    let mut not_pyth = pyth_price_data(PRICE_ONE, 0, EXPO, 1_000);
+    not_pyth[0] = 0;
+
+    assert!(PythPrice::load(&not_pyth).is_err());
+}
+
+#[tokio::test]
+async fn swap_within_oracle_bound_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool, oracle) = setup_oracle_pool(&mut context, &program_id).await?;
+
+    // 0.1% of the reserves costs about 40 bps with the fee
+    send(
+        &mut context,
+        &[swap_with_oracle_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0, &oracle)?],
+        &[],
+    ).await?;
+
+    send(
+        &mut context,
+        &[swap_with_oracle_ix(&program_id, &payer, &pool, &mint_b, &mint_a, 1_000_000, 0, &oracle)?],
+        &[],
+    ).await?;
+
+    // 5% of the reserves moves the price too far
+    let result = send(
+        &mut context,
+        &[swap_with_oracle_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 50_000_000, 0, &oracle)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::OraclePriceDeviation as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_against_moved_oracle_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool, oracle) = setup_oracle_pool(&mut context, &program_id).await?;
+
+    // the market moved 10% and the pool hasn't followed
+    set_oracle_price(&mut context, &oracle, PRICE_ONE * 11 / 10, 0, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_with_oracle_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0, &oracle)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::OraclePriceDeviation as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stale_or_uncertain_oracle_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool, oracle) = setup_oracle_pool(&mut context, &program_id).await?;
+
+    set_oracle_price(&mut context, &oracle, PRICE_ONE, 0, MAX_ORACLE_AGE + 1).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_with_oracle_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0, &oracle)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::OracleStale as u32));
+
+    // a 3% confidence interval
+    set_oracle_price(&mut context, &oracle, PRICE_ONE, PRICE_ONE as u64 * 3 / 100, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_with_oracle_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0, &oracle)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::OracleConfidenceTooWide as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_without_oracle_account_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool, _oracle) = setup_oracle_pool(&mut context, &program_id).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::OracleMismatch as u32));
+
+    // some other price account
+    let other = Pubkey::new_unique();
+    set_oracle_price(&mut context, &other, PRICE_ONE, 0, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_with_oracle_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0, &other)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::OracleMismatch as u32));
+
+    // removing the oracle lifts the bound
+    send(&mut context, &[set_oracle_ix(&program_id, &payer, &pool, None, 0)?], &[]).await?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 50_000_000, 0)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_oracle_by_non_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let oracle = Pubkey::new_unique();
+    set_oracle_price(&mut context, &oracle, PRICE_ONE, 0, 0).await?;
+
+    let stranger = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[set_oracle_ix(&program_id, &stranger.pubkey(), &pool, Some(&oracle), MAX_DEVIATION_BPS)?],
+        &[&stranger],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn set_paused_blocks_swaps_and_deposits_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    send(&mut context, &[set_paused_ix(&program_id, &payer, &pool, true)], &[]).await?;
+
+    assert!(pool_state(&mut context, &pool).await?.paused);
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::PoolPaused as u32));
+
+    let result = send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (1_000_000, 1_000_000), (0, 0))?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::PoolPaused as u32));
+
+    // withdrawals stay open so LPs can always exit
+    send(
+        &mut context,
+        &[withdraw_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, (0, 0))?],
+        &[],
+    ).await?;
+
+    send(&mut context, &[set_paused_ix(&program_id, &payer, &pool, false)], &[]).await?;
+
+    assert!(!pool_state(&mut context, &pool).await?.paused);
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_paused_by_non_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let intruder = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[set_paused_ix(&program_id, &intruder.pubkey(), &pool, true)],
+        &[&intruder],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+    assert!(!pool_state(&mut context, &pool).await?.paused);
+
+    send(&mut context, &[set_paused_ix(&program_id, &payer, &pool, true)], &[]).await?;
+
+    let result = send(
+        &mut context,
+        &[set_paused_ix(&program_id, &intruder.pubkey(), &pool, false)],
+        &[&intruder],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+    assert!(pool_state(&mut context, &pool).await?.paused);
+
+    Ok(())
+}
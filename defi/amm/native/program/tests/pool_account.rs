@@ -0,0 +1,125 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+use program::state::{LiquidityPool, CurveType};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Overwrites the pool account's data in place, keeping owner and lamports.
+async fn patch_pool(
+    context: &mut solana_program_test::ProgramTestContext,
+    pool: &Pubkey,
+    patch: impl FnOnce(&mut Vec<u8>),
+) -> Result<()> {
+    let mut account: Account = context.banks_client.get_account(*pool).await?.unwrap();
+
+    patch(&mut account.data);
+    context.set_account(pool, &AccountSharedData::from(account));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_sets_header_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.discriminator, LiquidityPool::DISCRIMINATOR);
+    assert_eq!(pool_data.version, LiquidityPool::VERSION);
+    assert!(pool_data.is_initialized);
+    assert_eq!((pool_data.decimals_a, pool_data.decimals_b), (DECIMALS, DECIMALS));
+
+    let account = context.banks_client.get_account(pool).await?.unwrap();
+
+    assert_eq!(account.data.len(), LiquidityPool::SPACE);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_twice_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, _pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[
+            create_pool_ix(
+                &program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::AccountAlreadyInitialized));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_rejects_wrong_discriminator_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    patch_pool(&mut context, &pool, |data| data[0] ^= 0xff).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::InvalidAccountData));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn provide_rejects_uninitialized_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    // is_initialized follows the 8-byte discriminator and the version
+    patch_pool(&mut context, &pool, |data| data[9] = 0).await?;
+
+    let result = send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (1_000_000, 1_000_000), (0, 0))?],
+        &[],
+    ).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::UninitializedAccount));
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::AmmError;
+use program::state::{CurveType, ProtocolConfig};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Creates a fresh pair of mints and tries to create a pool for them as the payer.
+async fn create_pool_as_payer(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+) -> Result<Result<(), BanksClientError>> {
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_mint(context).await?;
+    let mint_b = create_mint(context).await?;
+
+    create_funded_ata(context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(context, &payer, &mint_b, USER_FUNDS).await?;
+
+    Ok(send(
+        context,
+        &[
+            create_pool_ix(
+                program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await)
+}
+
+#[tokio::test]
+async fn empty_allowlist_is_permissionless_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    send(&mut context, &[set_pool_creators_ix(&program_id, &payer, &[])?], &[]).await?;
+
+    let account = context.banks_client.get_account(config_address(&program_id)).await?.unwrap();
+    let config = ProtocolConfig::load(&account.data)?;
+
+    // creating the config this way keeps the default fee tiers
+    assert_eq!(config.admin, payer);
+    assert_eq!(config.fee_tiers, ProtocolConfig::DEFAULT_FEE_TIERS.to_vec());
+    assert!(config.pool_creators.is_empty());
+
+    create_pool_as_payer(&mut context, &program_id).await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn allowlist_restricts_pool_creation_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let creator = Pubkey::new_unique();
+
+    send(&mut context, &[set_pool_creators_ix(&program_id, &payer, &[creator])?], &[]).await?;
+
+    let result = create_pool_as_payer(&mut context, &program_id).await?;
+
+    assert_eq!(custom_error(result), Some(AmmError::PoolCreatorNotAllowed as u32));
+
+    send(&mut context, &[set_pool_creators_ix(&program_id, &payer, &[creator, payer])?], &[]).await?;
+
+    create_pool_as_payer(&mut context, &program_id).await??;
+
+    // the fee tiers are left alone
+    send(&mut context, &[set_fee_tiers_ix(&program_id, &payer, &[FEE_BPS])?], &[]).await?;
+
+    let account = context.banks_client.get_account(config_address(&program_id)).await?.unwrap();
+
+    assert_eq!(ProtocolConfig::load(&account.data)?.pool_creators, vec![creator, payer]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_pool_creators_validation_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let too_many: Vec<Pubkey> = (0..=ProtocolConfig::MAX_POOL_CREATORS).map(|_| Pubkey::new_unique()).collect();
+
+    let result = send(&mut context, &[set_pool_creators_ix(&program_id, &payer, &too_many)?], &[]).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::InvalidInstructionData));
+
+    send(&mut context, &[set_pool_creators_ix(&program_id, &payer, &[payer])?], &[]).await?;
+
+    // only the config admin can change the list
+    let stranger = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[set_pool_creators_ix(&program_id, &stranger.pubkey(), &[stranger.pubkey()])?],
+        &[&stranger],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotConfigAdmin as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stranger_cannot_create_allowlist_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    // a stranger can't create the config to put itself on the allowlist
+    let stranger = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[set_pool_creators_ix(&program_id, &stranger.pubkey(), &[stranger.pubkey()])?],
+        &[&stranger],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotUpgradeAuthority as u32));
+    assert!(context.banks_client.get_account(config_address(&program_id)).await?.is_none());
+
+    // so pool creation stays open
+    create_pool_as_payer(&mut context, &program_id).await??;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// 1% of the pool: 30 bps of fee plus ~99 bps of price movement.
+#[tokio::test]
+async fn swap_within_price_impact_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    let amount_in = INITIAL_LIQUIDITY / 100;
+    let expected_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    send(
+        &mut context,
+        &[swap_with_price_impact_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0, 150)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_b).await?, balance_before + expected_out);
+
+    Ok(())
+}
+
+// min_out of zero accepts anything, the price-impact bound still applies.
+#[tokio::test]
+async fn swap_exceeding_price_impact_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_with_price_impact_ix(&program_id, &payer, &pool, &mint_a, &mint_b, INITIAL_LIQUIDITY / 10, 0, 150)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::PriceImpactTooHigh as u32));
+
+    // without the bound the same swap goes through
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, INITIAL_LIQUIDITY / 10, 0)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+// The bound is relative to the spot price at execution, so it still passes
+// after another swap has moved the pool, where a stale min_out would fail.
+#[tokio::test]
+async fn price_impact_is_relative_to_current_spot_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let amount_in = INITIAL_LIQUIDITY / 100;
+    let stale_min_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, INITIAL_LIQUIDITY / 10, 0)?],
+        &[],
+    ).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, stale_min_out)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::SlippageExceed as u32));
+
+    send(
+        &mut context,
+        &[swap_with_price_impact_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0, 150)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const PROTOCOL_FEE_BPS: u16 = 2_000;
+
+#[tokio::test]
+async fn swap_routes_protocol_fee_to_treasury_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    let treasury = treasury_address(&program_id, &pool);
+    let treasury_ata_a = get_associated_token_address(&treasury, &mint_a);
+    let treasury_ata_b = get_associated_token_address(&treasury, &mint_b);
+
+    assert_eq!(token_balance(&mut context, &treasury_ata_a).await?, 0);
+    assert_eq!(token_balance(&mut context, &treasury_ata_b).await?, 0);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.protocol_fee_bps, PROTOCOL_FEE_BPS);
+    assert_eq!(pool_data.admin, payer);
+
+    let amount_in = 10_000_000;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0)?],
+        &[],
+    ).await?;
+
+    // 30 bps of 10_000_000 is 30_000, of which 20% goes to the protocol
+    assert_eq!(token_balance(&mut context, &treasury_ata_a).await?, 6_000);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    // the other 24_000 is the LPs', kept next to the reserve
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + amount_in - 30_000);
+    assert_eq!(pool_data.fees_a, 24_000);
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&pool, &mint_a)).await?,
+        pool_data.reserve_a + pool_data.fees_a,
+    );
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_b, &mint_a, amount_in, 0)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &treasury_ata_a).await?, 6_000);
+    assert_eq!(token_balance(&mut context, &treasury_ata_b).await?, 6_000);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&pool, &mint_b)).await?,
+        pool_data.reserve_b + pool_data.fees_b,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_without_protocol_fee_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let amount_in = 10_000_000;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0)?],
+        &[],
+    ).await?;
+
+    let treasury = treasury_address(&program_id, &pool);
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&treasury, &mint_a)).await?, 0);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + amount_in - 30_000);
+    assert_eq!(pool_data.fees_a, 30_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn collect_protocol_fees_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 0)?],
+        &[],
+    ).await?;
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let payer_balance_before = token_balance(&mut context, &payer_ata_a).await?;
+
+    send(
+        &mut context,
+        &[collect_protocol_fees_ix(&program_id, &payer, &pool, &mint_a)],
+        &[],
+    ).await?;
+
+    let treasury = treasury_address(&program_id, &pool);
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&treasury, &mint_a)).await?, 0);
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, payer_balance_before + 6_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn collect_protocol_fees_by_non_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 0)?],
+        &[],
+    ).await?;
+
+    let intruder = Keypair::new();
+    create_funded_ata(&mut context, &intruder.pubkey(), &mint_a, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[collect_protocol_fees_ix(&program_id, &intruder.pubkey(), &pool, &mint_a)],
+        &[&intruder],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+
+    Ok(())
+}
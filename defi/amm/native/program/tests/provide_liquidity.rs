@@ -0,0 +1,230 @@
+mod common;
+
+use anyhow::Result;
+
+use borsh::to_vec;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    instruction::{AccountMeta, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, Mint};
+
+use program::error::AmmError;
+use program::processor::MINIMUM_LIQUIDITY;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// ProvideLiquidity account indices, see provide_liquidity_ix.
+const SYSTEM_PROGRAM: usize = 13;
+
+async fn patch_account(
+    context: &mut ProgramTestContext,
+    address: &Pubkey,
+    patch: impl FnOnce(&mut Vec<u8>),
+) -> Result<()> {
+    let mut account: Account = context.banks_client.get_account(*address).await?.unwrap();
+
+    patch(&mut account.data);
+    context.set_account(address, &AccountSharedData::from(account));
+
+    Ok(())
+}
+
+// Leaves the pool with no LP supply and empty reserves, as a pool from
+// before MINIMUM_LIQUIDITY was locked ends up once every LP has withdrawn.
+async fn drain_pool(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+) -> Result<()> {
+    let payer = context.payer.pubkey();
+    let mint_lp = lp_mint_address(program_id, pool);
+
+    for token_account in [
+        get_associated_token_address(&payer, &mint_lp),
+        get_associated_token_address(pool, &mint_lp),
+        get_associated_token_address(pool, mint_a),
+        get_associated_token_address(pool, mint_b),
+    ] {
+        patch_account(context, &token_account, |data| {
+            let mut state = TokenAccount::unpack(data).unwrap();
+            state.amount = 0;
+            TokenAccount::pack(state, data).unwrap();
+        }).await?;
+    }
+
+    patch_account(context, &mint_lp, |data| {
+        let mut state = Mint::unpack(data).unwrap();
+        state.supply = 0;
+        Mint::pack(state, data).unwrap();
+    }).await?;
+
+    let mut pool_data = pool_state(context, pool).await?;
+    pool_data.reserve_a = 0;
+    pool_data.reserve_b = 0;
+
+    patch_account(context, pool, |data| {
+        let encoded = to_vec(&pool_data).unwrap();
+        data[..encoded.len()].copy_from_slice(&encoded);
+    }).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn first_deposit_creates_lp_ata_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    // the LP pays the ATA's rent itself
+    let lp = Keypair::new();
+    let deposit = 100_000_000;
+
+    send(&mut context, &[transfer(&payer, &lp.pubkey(), 1_000_000_000)], &[]).await?;
+    create_funded_ata(&mut context, &lp.pubkey(), &mint_a, deposit).await?;
+    create_funded_ata(&mut context, &lp.pubkey(), &mint_b, deposit).await?;
+
+    let lp_ata_lp = get_associated_token_address(&lp.pubkey(), &lp_mint_address(&program_id, &pool));
+
+    assert!(context.banks_client.get_account(lp_ata_lp).await?.is_none());
+
+    send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &lp.pubkey(), &pool, &mint_a, &mint_b, (deposit, deposit), (0, 0))?],
+        &[&lp],
+    ).await?;
+
+    assert!(token_balance(&mut context, &lp_ata_lp).await? > 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn provide_liquidity_wrong_system_program_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let mut instruction = provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (1_000_000, 1_000_000), (0, 0))?;
+    instruction.accounts[SYSTEM_PROGRAM] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::IncorrectProgramId));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn refill_drained_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    drain_pool(&mut context, &program_id, &pool, &mint_a, &mint_b).await?;
+
+    // the pool takes the new ratio as is, rather than dividing by zero
+    let (amount_a, amount_b) = (1_000_000, 4_000_000);
+
+    send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (amount_a, amount_b), (amount_a, amount_b))?],
+        &[],
+    ).await?;
+
+    let mint_lp = lp_mint_address(&program_id, &pool);
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!((pool_data.reserve_a, pool_data.reserve_b), (amount_a, amount_b));
+    assert_eq!(mint_supply(&mut context, &mint_lp).await?, 2_000_000);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_lp)).await?, MINIMUM_LIQUIDITY);
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&payer, &mint_lp)).await?,
+        2_000_000 - MINIMUM_LIQUIDITY,
+    );
+
+    // and later deposits follow it
+    send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (1_000_000, 1_000_000), (0, 0))?],
+        &[],
+    ).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!((pool_data.reserve_a, pool_data.reserve_b), (1_250_000, 5_000_000));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn refill_drained_pool_too_small_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    drain_pool(&mut context, &program_id, &pool, &mint_a, &mint_b).await?;
+
+    let result = send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (1_000, 1_000), (0, 0))?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::InsufficientInitialLiquidity as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn drained_pool_reports_empty_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    drain_pool(&mut context, &program_id, &pool, &mint_a, &mint_b).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::EmptyPool as u32));
+
+    let result = send(&mut context, &[quote_ix(&program_id, &pool, 1_000_000, true)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::EmptyPool as u32));
+
+    let result = send(
+        &mut context,
+        &[withdraw_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1, (0, 0))?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::EmptyPool as u32));
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// How a client reads a quote: simulate the instruction and decode the
+// program's return data, no transaction is ever landed.
+async fn simulate_quote(context: &mut ProgramTestContext, instruction: Instruction) -> Result<u64> {
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let simulation = context.banks_client.simulate_transaction(tx).await?;
+
+    simulation.result.unwrap()?;
+
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+
+    Ok(u64::from_le_bytes(return_data.data.as_slice().try_into()?))
+}
+
+#[tokio::test]
+async fn quote_matches_swap_output_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let amount_in = 10_000_000;
+
+    let quoted_a_to_b = simulate_quote(&mut context, quote_ix(&program_id, &pool, amount_in, true)?).await?;
+    let quoted_b_to_a = simulate_quote(&mut context, quote_ix(&program_id, &pool, amount_in, false)?).await?;
+
+    assert_eq!(quoted_a_to_b, quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS));
+    assert_eq!(quoted_b_to_a, quoted_a_to_b);
+
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, quoted_a_to_b)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_b).await?, balance_before + quoted_a_to_b);
+
+    // the next quote reflects the moved reserves
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(
+        simulate_quote(&mut context, quote_ix(&program_id, &pool, amount_in, false)?).await?,
+        quote(pool_data.reserve_b, pool_data.reserve_a, amount_in, FEE_BPS),
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quote_zero_amount_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(&mut context, &[quote_ix(&program_id, &pool, 0, true)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::ZeroSwapAmount as u32));
+
+    Ok(())
+}
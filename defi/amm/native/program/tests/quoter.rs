@@ -0,0 +1,100 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use amm_quoter::{quote_swap, state::PoolState};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const PROTOCOL_FEE_BPS: u16 = 2_000;
+
+async fn decode_pool(context: &mut ProgramTestContext, pool: &Pubkey) -> Result<PoolState> {
+    let account = context.banks_client.get_account(*pool).await?.unwrap();
+
+    Ok(PoolState::decode(&account.data)?)
+}
+
+#[tokio::test]
+async fn quoter_decodes_pool_account_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    let decoded = decode_pool(&mut context, &pool).await?;
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(decoded.mint_a, mint_a.to_bytes());
+    assert_eq!(decoded.mint_b, mint_b.to_bytes());
+    assert_eq!(decoded.reserve_a, pool_data.reserve_a);
+    assert_eq!(decoded.reserve_b, pool_data.reserve_b);
+    assert_eq!(decoded.fee_bps, FEE_BPS);
+    assert_eq!(decoded.protocol_fee_bps, PROTOCOL_FEE_BPS);
+    assert_eq!(decoded.admin, payer.to_bytes());
+    assert_eq!(decoded.bump, pool_data.bump);
+
+    Ok(())
+}
+
+// Quotes each swap off-chain, then runs it with min_out set to exactly the
+// quoted amount, so any rounding difference fails the swap.
+#[tokio::test]
+async fn quoter_matches_onchain_swaps_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    send(&mut context, &[set_referral_fee_ix(&program_id, &payer, &pool, 3_000)], &[]).await?;
+
+    let referrer = Keypair::new();
+    let referrer_ata_a = create_ata(&mut context, &referrer.pubkey(), &mint_a).await?;
+    let referrer_ata_b = create_ata(&mut context, &referrer.pubkey(), &mint_b).await?;
+
+    let swaps = [
+        (1_234_567, true, false),
+        (98_765_431, false, false),
+        (7, true, true),
+        (333_333_333, false, true),
+        (1, false, false),
+        (50_000_001, true, true),
+    ];
+
+    for (amount_in, a_to_b, with_referrer) in swaps {
+        let (mint_in, mint_out) = if a_to_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let referrer_ata = if a_to_b { referrer_ata_a } else { referrer_ata_b };
+
+        let quote = quote_swap(&decode_pool(&mut context, &pool).await?, amount_in, a_to_b, with_referrer)?;
+
+        let payer_ata_out = get_associated_token_address(&payer, &mint_out);
+        let balance_before = token_balance(&mut context, &payer_ata_out).await?;
+        let referrer_before = token_balance(&mut context, &referrer_ata).await?;
+
+        let instruction = if with_referrer {
+            swap_with_referrer_ix(&program_id, &payer, &pool, &mint_in, &mint_out, amount_in, quote.amount_out, &referrer_ata)?
+        } else {
+            swap_ix(&program_id, &payer, &pool, &mint_in, &mint_out, amount_in, quote.amount_out)?
+        };
+
+        send(&mut context, &[instruction], &[]).await?;
+
+        assert_eq!(token_balance(&mut context, &payer_ata_out).await?, balance_before + quote.amount_out);
+        assert_eq!(token_balance(&mut context, &referrer_ata).await?, referrer_before + quote.referral_fee);
+
+        let pool_data = pool_state(&mut context, &pool).await?;
+
+        assert_eq!((pool_data.reserve_a, pool_data.reserve_b), (quote.reserve_a, quote.reserve_b));
+    }
+
+    Ok(())
+}
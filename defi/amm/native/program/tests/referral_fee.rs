@@ -0,0 +1,139 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const PROTOCOL_FEE_BPS: u16 = 2_000;
+const REFERRAL_FEE_BPS: u16 = 5_000;
+
+#[tokio::test]
+async fn swap_with_referrer_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    send(&mut context, &[set_referral_fee_ix(&program_id, &payer, &pool, REFERRAL_FEE_BPS)], &[]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.referral_fee_bps, REFERRAL_FEE_BPS);
+
+    let referrer = Keypair::new();
+    let referrer_ata_a = create_ata(&mut context, &referrer.pubkey(), &mint_a).await?;
+
+    let amount_in = 10_000_000;
+    let expected_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    send(
+        &mut context,
+        &[swap_with_referrer_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, expected_out, &referrer_ata_a)?],
+        &[],
+    ).await?;
+
+    // 30 bps of 10_000_000 is 30_000: 20% to the protocol, 50% to the referrer
+    assert_eq!(token_balance(&mut context, &referrer_ata_a).await?, 15_000);
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&treasury_address(&program_id, &pool), &mint_a)).await?,
+        6_000,
+    );
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    // and the remaining 9_000 to the LPs
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + amount_in - 30_000);
+    assert_eq!(pool_data.fees_a, 9_000);
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&pool, &mint_a)).await?,
+        pool_data.reserve_a + pool_data.fees_a,
+    );
+
+    Ok(())
+}
+
+// Without a referrer account the referral slice stays with the LPs.
+#[tokio::test]
+async fn swap_without_referrer_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    send(&mut context, &[set_referral_fee_ix(&program_id, &payer, &pool, REFERRAL_FEE_BPS)], &[]).await?;
+
+    let amount_in = 10_000_000;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, 0)?],
+        &[],
+    ).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.fees_a, 30_000 - 6_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn referrer_cannot_be_pool_vault_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    send(&mut context, &[set_referral_fee_ix(&program_id, &payer, &pool, REFERRAL_FEE_BPS)], &[]).await?;
+
+    let vault_a = get_associated_token_address(&pool, &mint_a);
+
+    let result = send(
+        &mut context,
+        &[swap_with_referrer_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 0, &vault_a)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::InvalidReferrer as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_referral_fee_checks_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, PROTOCOL_FEE_BPS).await?;
+
+    let stranger = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[set_referral_fee_ix(&program_id, &stranger.pubkey(), &pool, REFERRAL_FEE_BPS)],
+        &[&stranger],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotPoolAdmin as u32));
+
+    let payer = context.payer.pubkey();
+
+    // together with the protocol's 20% this would exceed the whole fee
+    let result = send(
+        &mut context,
+        &[set_referral_fee_ix(&program_id, &payer, &pool, 10_000 - PROTOCOL_FEE_BPS + 1)],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::FeeTooHigh as u32));
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use solana_system_interface::instruction::transfer;
+
+use program::error::AmmError;
+use program::state::{CurveType, PoolRegistry, RegistryEntry};
+
+use common::*;
+
+async fn registry_state(context: &mut ProgramTestContext, program_id: &Pubkey) -> Result<PoolRegistry> {
+    let account = context.banks_client.get_account(registry_address(program_id)).await?.unwrap();
+
+    let registry = PoolRegistry::load(&account.data)?;
+
+    assert_eq!(account.owner, *program_id);
+    assert_eq!(account.data.len(), PoolRegistry::space(registry.pools.len()));
+
+    Ok(registry)
+}
+
+#[tokio::test]
+async fn create_pool_appends_to_registry_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, 30, 0).await?;
+
+    let registry = registry_state(&mut context, &program_id).await?;
+
+    assert_eq!(registry.discriminator, PoolRegistry::DISCRIMINATOR);
+    assert_eq!(registry.pools, vec![RegistryEntry { pool, mint_a, mint_b, fee_bps: 30 }]);
+
+    let (mint_c, mint_d, second_pool) = setup_pool(&mut context, &program_id, 5, 0).await?;
+
+    let registry = registry_state(&mut context, &program_id).await?;
+
+    assert_eq!(
+        registry.pools,
+        vec![
+            RegistryEntry { pool, mint_a, mint_b, fee_bps: 30 },
+            RegistryEntry { pool: second_pool, mint_a: mint_c, mint_b: mint_d, fee_bps: 5 },
+        ],
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn prefunded_registry_does_not_block_pool_creation_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    // anyone can send lamports to the registry address before the first pool exists
+    send(&mut context, &[transfer(&payer, &registry_address(&program_id), 1_000_000)], &[]).await?;
+
+    let (_mint_a, _mint_b, pool) = setup_pool(&mut context, &program_id, 30, 0).await?;
+
+    let registry = registry_state(&mut context, &program_id).await?;
+
+    assert_eq!(registry.pools.len(), 1);
+    assert_eq!(registry.pools[0].pool, pool);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_with_wrong_registry_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_mint(&mut context).await?;
+    let mint_b = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(&mut context, &payer, &mint_b, USER_FUNDS).await?;
+
+    let mut instruction = create_pool_ix(
+        &program_id,
+        &payer,
+        &mint_a,
+        &mint_b,
+        INITIAL_LIQUIDITY,
+        INITIAL_LIQUIDITY,
+        30,
+        0,
+        CurveType::ConstantProduct,
+    )?;
+    // the registry sits just before the protocol config
+    instruction.accounts[19].pubkey = Pubkey::new_unique();
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::RegistryAddressMismatch as u32));
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use proptest::prelude::*;
+
+use program::curve::{initial_lp_amount, swap_output};
+use program::math::{deposit_amounts, lp_for_deposit, withdraw_amounts};
+use program::state::CurveType;
+
+// Every division in the pool's math rounds against the user. These check
+// that repeating dust-sized operations, where rounding is most of the amount,
+// never comes out ahead.
+
+fn reserve() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        1_000u64..1_000_000,
+        1_000_000u64..1_000_000_000_000_000,
+    ]
+}
+
+fn dust() -> impl Strategy<Value = u64> {
+    1u64..1_000
+}
+
+#[test]
+fn deposit_rounds_owed_side_up() {
+    // 1 A is worth 2/3 B, so the deposit has to bring a whole B
+    assert_eq!(deposit_amounts(3, 2, 1, 10), Some((1, 1)));
+
+    // and from the other side, 1 B is worth 1.5 A
+    assert_eq!(deposit_amounts(3, 2, 10, 1), Some((2, 1)));
+
+    // exact ratios take exactly
+    assert_eq!(deposit_amounts(1_000, 2_000, 10, 20), Some((10, 20)));
+}
+
+#[test]
+fn dust_deposit_mints_nothing_it_cant_back() {
+    // 1 unit of each into a pool where 1 LP is worth 10 of each
+    assert_eq!(lp_for_deposit(10_000, 10_000, 1_000, 1, 1), Some(0));
+
+    // burning 1 LP of 3 pays out the floor of a third
+    assert_eq!(withdraw_amounts(10, 10, 3, 1), Some((3, 3)));
+}
+
+proptest! {
+    #[test]
+    fn dust_deposit_withdraw_round_trip(
+        reserve_a in reserve(),
+        reserve_b in reserve(),
+        desired_a in dust(),
+        desired_b in dust(),
+        rounds in 1usize..20,
+    ) {
+        let mut reserve_a = reserve_a;
+        let mut reserve_b = reserve_b;
+        let mut total_lp = initial_lp_amount(CurveType::ConstantProduct, reserve_a, reserve_b).unwrap();
+
+        let (mut paid_a, mut paid_b, mut got_a, mut got_b) = (0u64, 0u64, 0u64, 0u64);
+
+        for _ in 0..rounds {
+            let (take_a, take_b) = deposit_amounts(reserve_a, reserve_b, desired_a, desired_b).unwrap();
+            let lp = lp_for_deposit(reserve_a, reserve_b, total_lp, take_a, take_b).unwrap();
+
+            reserve_a += take_a;
+            reserve_b += take_b;
+            total_lp += lp;
+
+            let (out_a, out_b) = withdraw_amounts(reserve_a, reserve_b, total_lp, lp).unwrap();
+
+            reserve_a -= out_a;
+            reserve_b -= out_b;
+            total_lp -= lp;
+
+            paid_a += take_a;
+            paid_b += take_b;
+            got_a += out_a;
+            got_b += out_b;
+        }
+
+        prop_assert!(got_a <= paid_a, "{got_a} > {paid_a}");
+        prop_assert!(got_b <= paid_b, "{got_b} > {paid_b}");
+    }
+
+    #[test]
+    fn dust_swap_round_trip(
+        reserve_a in reserve(),
+        reserve_b in reserve(),
+        amount_in in dust(),
+        fee_bps in 0u16..100,
+        rounds in 1usize..20,
+    ) {
+        let mut reserve_a = reserve_a;
+        let mut reserve_b = reserve_b;
+        let mut balance_a = amount_in;
+
+        // swap A to B and straight back, over and over
+        for _ in 0..rounds {
+            let out_b = swap_output(CurveType::ConstantProduct, reserve_a, reserve_b, balance_a, fee_bps, true).unwrap();
+
+            reserve_a += balance_a;
+            reserve_b -= out_b;
+
+            let out_a = swap_output(CurveType::ConstantProduct, reserve_b, reserve_a, out_b, fee_bps, false).unwrap();
+
+            reserve_b += out_b;
+            reserve_a -= out_a;
+
+            balance_a = out_a;
+        }
+
+        prop_assert!(balance_a <= amount_in, "{balance_a} > {amount_in}");
+    }
+}
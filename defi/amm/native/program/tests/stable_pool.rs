@@ -0,0 +1,117 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::curve::swap_output;
+use program::error::AmmError;
+use program::processor::MINIMUM_LIQUIDITY;
+use program::state::CurveType;
+
+use common::*;
+
+const FEE_BPS: u16 = 5;
+const STABLE: CurveType = CurveType::Stable { amp: 100 };
+
+#[tokio::test]
+async fn stable_pool_swap_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool_with_curve(&mut context, &program_id, FEE_BPS, 0, STABLE).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.curve, STABLE);
+
+    // balanced stable pools start with D = a + b LP tokens
+    let mint_lp = lp_mint_address(&program_id, &pool);
+
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&payer, &mint_lp)).await?,
+        2 * INITIAL_LIQUIDITY - MINIMUM_LIQUIDITY,
+    );
+
+    let amount_in = 10_000_000;
+    let expected_out = swap_output(STABLE, INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS, true).unwrap();
+
+    assert!(expected_out > quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS));
+
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, expected_out)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_b).await?, balance_before + expected_out);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    // the LP fee is kept outside the reserve
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + amount_in - amount_in * FEE_BPS as u64 / 10_000);
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY - expected_out);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_stable_pool_with_invalid_amp_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let result = setup_pool_with_curve(&mut context, &program_id, FEE_BPS, 0, CurveType::Stable { amp: 0 }).await;
+
+    let error = result.unwrap_err().downcast::<solana_program_test::BanksClientError>()?;
+
+    assert_eq!(custom_error(Err(error)), Some(AmmError::InvalidAmplification as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stable_pool_on_taken_pair_and_fee_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    // same mints and fee tier, so the same address as the constant product pool
+    let result = send(
+        &mut context,
+        &[
+            create_pool_ix(
+                &program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                FEE_BPS,
+                0,
+                STABLE,
+            )?,
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::AccountAlreadyInitialized));
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.curve, CurveType::ConstantProduct);
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY);
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY);
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{instruction::sync_native, native_mint};
+
+use program::error::AmmError;
+use program::state::CurveType;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// SOL/token pool seeded with INITIAL_LIQUIDITY on both sides. The payer's
+// wSOL ATA is left empty. Returns (mint, pool).
+async fn setup_native_pool(context: &mut ProgramTestContext, program_id: &Pubkey) -> Result<(Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let mint = create_mint(context).await?;
+    create_funded_ata(context, &payer, &mint, USER_FUNDS).await?;
+
+    let wsol_ata = create_ata(context, &payer, &native_mint::id()).await?;
+
+    send(
+        context,
+        &[
+            transfer(&payer, &wsol_ata, INITIAL_LIQUIDITY),
+            sync_native(&spl_token::id(), &wsol_ata)?,
+            create_pool_ix(
+                program_id,
+                &payer,
+                &native_mint::id(),
+                &mint,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    Ok((mint, pool_address(program_id, &native_mint::id(), &mint, FEE_BPS)))
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> Result<u64> {
+    Ok(context.banks_client.get_balance(*address).await?)
+}
+
+#[tokio::test]
+async fn swap_sol_for_token_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint, pool) = setup_native_pool(&mut context, &program_id).await?;
+
+    let payer_ata = get_associated_token_address(&payer, &mint);
+    let wsol_ata = get_associated_token_address(&payer, &native_mint::id());
+    let balance_before = token_balance(&mut context, &payer_ata).await?;
+
+    let amount_in = 10_000_000;
+    let expected_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    send(
+        &mut context,
+        &[swap_native_ix(&program_id, &payer, &pool, &native_mint::id(), &mint, amount_in, expected_out)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata).await?, balance_before + expected_out);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+    let reserve_sol = if pool_data.mint_a == native_mint::id() { pool_data.reserve_a } else { pool_data.reserve_b };
+
+    assert_eq!(reserve_sol, INITIAL_LIQUIDITY + amount_in - amount_in * FEE_BPS as u64 / 10_000);
+
+    // the temporary wSOL account is gone
+    assert!(context.banks_client.get_account(wsol_ata).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_token_for_sol_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint, pool) = setup_native_pool(&mut context, &program_id).await?;
+
+    let wsol_ata = get_associated_token_address(&payer, &native_mint::id());
+
+    // closing the existing, empty wSOL ATA also refunds its rent
+    let wsol_ata_rent = lamports(&mut context, &wsol_ata).await?;
+    let lamports_before = lamports(&mut context, &payer).await?;
+
+    let amount_in = 10_000_000;
+    let expected_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+
+    send(
+        &mut context,
+        &[swap_native_ix(&program_id, &payer, &pool, &mint, &native_mint::id(), amount_in, expected_out)?],
+        &[],
+    ).await?;
+
+    let gained = lamports(&mut context, &payer).await? - lamports_before;
+
+    // everything paid out arrives as plain SOL, less the transaction fee
+    assert!(gained <= expected_out + wsol_ata_rent);
+    assert!(gained + 10_000 >= expected_out + wsol_ata_rent);
+    assert!(context.banks_client.get_account(wsol_ata).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_native_needs_sol_side_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_native_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::NotNativeSwap as u32));
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+use program::state::CurveType;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Creates mints A, B and C, funds the payer with each, and creates the
+// A/B and B/C pools. Returns ([mint_a, mint_b, mint_c], pool_ab, pool_bc).
+async fn setup_route(context: &mut ProgramTestContext, program_id: &Pubkey) -> Result<([Pubkey; 3], Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let mut mints = [Pubkey::default(); 3];
+
+    for mint in &mut mints {
+        *mint = create_mint(context).await?;
+        create_funded_ata(context, &payer, mint, USER_FUNDS).await?;
+    }
+
+    let [mint_a, mint_b, mint_c] = mints;
+
+    for (mint_x, mint_y) in [(mint_a, mint_b), (mint_b, mint_c)] {
+        send(
+            context,
+            &[
+                create_pool_ix(
+                    program_id,
+                    &payer,
+                    &mint_x,
+                    &mint_y,
+                    INITIAL_LIQUIDITY,
+                    INITIAL_LIQUIDITY,
+                    FEE_BPS,
+                    0,
+                    CurveType::ConstantProduct,
+                )?,
+            ],
+            &[],
+        ).await?;
+    }
+
+    Ok((
+        mints,
+        pool_address(program_id, &mint_a, &mint_b, FEE_BPS),
+        pool_address(program_id, &mint_b, &mint_c, FEE_BPS),
+    ))
+}
+
+#[tokio::test]
+async fn swap_route_through_two_pools_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let ([mint_a, mint_b, mint_c], pool_ab, pool_bc) = setup_route(&mut context, &program_id).await?;
+
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let payer_ata_c = get_associated_token_address(&payer, &mint_c);
+    let balance_b_before = token_balance(&mut context, &payer_ata_b).await?;
+    let balance_c_before = token_balance(&mut context, &payer_ata_c).await?;
+
+    let amount_in = 10_000_000;
+    let amount_b = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+    let amount_c = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_b, FEE_BPS);
+
+    send(
+        &mut context,
+        &[
+            swap_route_ix(
+                &program_id,
+                &payer,
+                &[(pool_ab, mint_a, mint_b), (pool_bc, mint_b, mint_c)],
+                amount_in,
+                amount_c,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    // token B only passes through
+    assert_eq!(token_balance(&mut context, &payer_ata_b).await?, balance_b_before);
+    assert_eq!(token_balance(&mut context, &payer_ata_c).await?, balance_c_before + amount_c);
+
+    let pool_ab_data = pool_state(&mut context, &pool_ab).await?;
+    let pool_bc_data = pool_state(&mut context, &pool_bc).await?;
+
+    let (reserve_a, reserve_b) = if pool_ab_data.mint_a == mint_a {
+        (pool_ab_data.reserve_a, pool_ab_data.reserve_b)
+    } else {
+        (pool_ab_data.reserve_b, pool_ab_data.reserve_a)
+    };
+
+    // each hop keeps its LP fee outside the reserves
+    let lp_fee = |amount: u64| amount * FEE_BPS as u64 / 10_000;
+
+    assert_eq!(reserve_a, INITIAL_LIQUIDITY + amount_in - lp_fee(amount_in));
+    assert_eq!(reserve_b, INITIAL_LIQUIDITY - amount_b);
+    assert_eq!(
+        pool_bc_data.reserve_a + pool_bc_data.reserve_b,
+        2 * INITIAL_LIQUIDITY + amount_b - lp_fee(amount_b) - amount_c,
+    );
+    assert_eq!(pool_bc_data.fees_a + pool_bc_data.fees_b, lp_fee(amount_b));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_route_slippage_applies_to_final_output_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let ([mint_a, mint_b, mint_c], pool_ab, pool_bc) = setup_route(&mut context, &program_id).await?;
+
+    let amount_in = 10_000_000;
+    let amount_b = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS);
+    let amount_c = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_b, FEE_BPS);
+
+    let result = send(
+        &mut context,
+        &[
+            swap_route_ix(
+                &program_id,
+                &payer,
+                &[(pool_ab, mint_a, mint_b), (pool_bc, mint_b, mint_c)],
+                amount_in,
+                amount_c + 1,
+            )?,
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::SlippageExceed as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_route_with_broken_chain_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let ([mint_a, mint_b, mint_c], pool_ab, pool_bc) = setup_route(&mut context, &program_id).await?;
+
+    // the second hop starts from C although the first one ends in B
+    let result = send(
+        &mut context,
+        &[
+            swap_route_ix(
+                &program_id,
+                &payer,
+                &[(pool_ab, mint_a, mint_b), (pool_bc, mint_c, mint_b)],
+                10_000_000,
+                0,
+            )?,
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::InvalidRoute as u32));
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Swap account indices, see swap_ix.
+const MINT_IN: usize = 2;
+const VAULT_IN: usize = 4;
+const VAULT_OUT: usize = 5;
+
+// Without the vault check the input would land in the attacker's own
+// account while the pool still paid out of its real reserves.
+#[tokio::test]
+async fn swap_attacker_vault_in_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let attacker = Keypair::new();
+    let attacker_ata_a = create_ata(&mut context, &attacker.pubkey(), &mint_a).await?;
+
+    let mut instruction = swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?;
+    instruction.accounts[VAULT_IN] = AccountMeta::new(attacker_ata_a, false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::VaultInMismatch as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_attacker_vault_out_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let attacker = Keypair::new();
+    let attacker_ata_b = create_funded_ata(&mut context, &attacker.pubkey(), &mint_b, INITIAL_LIQUIDITY).await?;
+
+    let mut instruction = swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?;
+    instruction.accounts[VAULT_OUT] = AccountMeta::new(attacker_ata_b, false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::VaultOutMismatch as u32));
+
+    Ok(())
+}
+
+// Vaults swapped around so each one is a pool ATA, just for the wrong mint.
+#[tokio::test]
+async fn swap_crossed_vaults_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let mut instruction = swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?;
+    instruction.accounts.swap(VAULT_IN, VAULT_OUT);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::VaultInMismatch as u32));
+
+    Ok(())
+}
+
+// An attacker-minted token passed as mint_in against a real pool.
+#[tokio::test]
+async fn swap_foreign_mint_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let fake_mint = create_mint(&mut context).await?;
+    create_funded_ata(&mut context, &payer, &fake_mint, USER_FUNDS).await?;
+
+    let mut instruction = swap_ix(&program_id, &payer, &pool, &fake_mint, &mint_b, 1_000_000, 0)?;
+    instruction.accounts[VAULT_IN] = swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 0, 0)?.accounts[VAULT_IN].clone();
+
+    let result = send(&mut context, &[instruction.clone()], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::MintNotInPool as u32));
+
+    // same mint on both sides
+    instruction.accounts[MINT_IN] = AccountMeta::new_readonly(mint_b, false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::MintNotInPool as u32));
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn sync_absorbs_donation_into_reserves_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let vault_a = get_associated_token_address(&pool, &mint_a);
+    let donation = 500_000_000;
+
+    donate(&mut context, &mint_a, &vault_a, donation).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY);
+    assert_eq!(token_balance(&mut context, &vault_a).await?, INITIAL_LIQUIDITY + donation);
+
+    let amount_in = 10_000_000;
+    let quote_before_sync = quote(pool_data.reserve_b, pool_data.reserve_a, amount_in, FEE_BPS);
+
+    send(&mut context, &[sync_ix(&program_id, &pool, &mint_a, &mint_b)], &[]).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + donation);
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY);
+
+    let quote_after_sync = quote(pool_data.reserve_b, pool_data.reserve_a, amount_in, FEE_BPS);
+
+    assert!(quote_after_sync > quote_before_sync);
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let balance_before = token_balance(&mut context, &payer_ata_a).await?;
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_b, &mint_a, amount_in, quote_after_sync)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, balance_before + quote_after_sync);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sync_with_foreign_vault_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let mut sync_ix = sync_ix(&program_id, &pool, &mint_a, &mint_b);
+    sync_ix.accounts[1].pubkey = get_associated_token_address(&payer, &mint_a);
+
+    let result = send(&mut context, &[sync_ix], &[]).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::VaultAddressMismatch as u32));
+
+    Ok(())
+}
@@ -0,0 +1,284 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::TransactionError,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use program::error::AmmError;
+use program::state::CurveType;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const TRANSFER_FEE_BPS: u16 = 100;
+
+// Same rounding as the Token-2022 transfer fee extension.
+fn transfer_fee(amount: u64) -> u64 {
+    (amount * TRANSFER_FEE_BPS as u64).div_ceil(10_000)
+}
+
+// Pool of a Token-2022 mint with a 1% transfer fee (A) and a plain SPL Token
+// mint (B), both seeded with INITIAL_LIQUIDITY. Returns (mint_a, mint_b, pool).
+async fn setup_transfer_fee_pool(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+) -> Result<(Pubkey, Pubkey, Pubkey)> {
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_transfer_fee_mint(context, TRANSFER_FEE_BPS, u64::MAX).await?;
+    let mint_b = create_mint(context).await?;
+
+    create_funded_ata_with_token_program(context, &payer, &mint_a, &spl_token_2022::id(), USER_FUNDS).await?;
+    create_funded_ata(context, &payer, &mint_b, USER_FUNDS).await?;
+
+    send(
+        context,
+        &[
+            create_pool_ix_with_token_programs(
+                program_id,
+                &payer,
+                &mint_a,
+                &mint_b,
+                INITIAL_LIQUIDITY,
+                INITIAL_LIQUIDITY,
+                FEE_BPS,
+                0,
+                CurveType::ConstantProduct,
+                (&spl_token_2022::id(), &spl_token::id()),
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    Ok((mint_a, mint_b, pool_address(program_id, &mint_a, &mint_b, FEE_BPS)))
+}
+
+async fn assert_reserves_match_vaults(
+    context: &mut ProgramTestContext,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+) -> Result<()> {
+    let vault_a = get_associated_token_address_with_program_id(pool, mint_a, &spl_token_2022::id());
+    let vault_b = get_associated_token_address_with_program_id(pool, mint_b, &spl_token::id());
+
+    let pool_data = pool_state(context, pool).await?;
+
+    assert_eq!(pool_data.reserve_a + pool_data.fees_a, token_balance(context, &vault_a).await?);
+    assert_eq!(pool_data.reserve_b + pool_data.fees_b, token_balance(context, &vault_b).await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_credits_amount_after_transfer_fee_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (mint_a, mint_b, pool) = setup_transfer_fee_pool(&mut context, &program_id).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY - transfer_fee(INITIAL_LIQUIDITY));
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY);
+
+    assert_reserves_match_vaults(&mut context, &pool, &mint_a, &mint_b).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_prices_amount_after_transfer_fee_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_transfer_fee_pool(&mut context, &program_id).await?;
+
+    let payer_ata_a = get_associated_token_address_with_program_id(&payer, &mint_a, &spl_token_2022::id());
+    let payer_ata_b = get_associated_token_address_with_program_id(&payer, &mint_b, &spl_token::id());
+
+    // A -> B: the pool only prices what is left of amount_in after the fee
+    let pool_data = pool_state(&mut context, &pool).await?;
+    let amount_in = 10_000_000;
+    let expected_out = quote(
+        pool_data.reserve_a,
+        pool_data.reserve_b,
+        amount_in - transfer_fee(amount_in),
+        FEE_BPS,
+    );
+
+    let balance_b_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    send(
+        &mut context,
+        &[
+            swap_ix_with_token_programs(
+                &program_id,
+                &payer,
+                &pool,
+                &mint_a,
+                &mint_b,
+                amount_in,
+                expected_out,
+                (&spl_token_2022::id(), &spl_token::id()),
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_b).await?, balance_b_before + expected_out);
+    assert_reserves_match_vaults(&mut context, &pool, &mint_a, &mint_b).await?;
+
+    // B -> A: the fee comes out of what the pool pays
+    let pool_data = pool_state(&mut context, &pool).await?;
+    let expected_out = quote(pool_data.reserve_b, pool_data.reserve_a, amount_in, FEE_BPS);
+    let expected_received = expected_out - transfer_fee(expected_out);
+
+    let balance_a_before = token_balance(&mut context, &payer_ata_a).await?;
+
+    send(
+        &mut context,
+        &[
+            swap_ix_with_token_programs(
+                &program_id,
+                &payer,
+                &pool,
+                &mint_b,
+                &mint_a,
+                amount_in,
+                expected_received,
+                (&spl_token::id(), &spl_token_2022::id()),
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, balance_a_before + expected_received);
+    assert_reserves_match_vaults(&mut context, &pool, &mint_a, &mint_b).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_slippage_applies_after_transfer_fee_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_transfer_fee_pool(&mut context, &program_id).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+    let amount_in = 10_000_000;
+    let amount_out = quote(pool_data.reserve_b, pool_data.reserve_a, amount_in, FEE_BPS);
+
+    // the pool sends amount_out, but the user only gets it minus the fee
+    let result = send(
+        &mut context,
+        &[
+            swap_ix_with_token_programs(
+                &program_id,
+                &payer,
+                &pool,
+                &mint_b,
+                &mint_a,
+                amount_in,
+                amount_out,
+                (&spl_token::id(), &spl_token_2022::id()),
+            )?,
+        ],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::SlippageExceed as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn liquidity_with_transfer_fee_mint_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_transfer_fee_pool(&mut context, &program_id).await?;
+
+    let token_programs = (&spl_token_2022::id(), &spl_token::id());
+
+    send(
+        &mut context,
+        &[
+            provide_liquidity_ix_with_token_programs(
+                &program_id,
+                &payer,
+                &pool,
+                &mint_a,
+                &mint_b,
+                (100_000_000, 100_000_000),
+                (0, 0),
+                token_programs,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    assert_reserves_match_vaults(&mut context, &pool, &mint_a, &mint_b).await?;
+
+    let user_ata_lp = get_associated_token_address_with_program_id(
+        &payer,
+        &lp_mint_address(&program_id, &pool),
+        &spl_token::id(),
+    );
+    let lp_balance = token_balance(&mut context, &user_ata_lp).await?;
+
+    send(
+        &mut context,
+        &[
+            withdraw_liquidity_ix_with_token_programs(
+                &program_id,
+                &payer,
+                &pool,
+                &mint_a,
+                &mint_b,
+                lp_balance / 2,
+                (0, 0),
+                token_programs,
+            )?,
+        ],
+        &[],
+    ).await?;
+
+    assert_reserves_match_vaults(&mut context, &pool, &mint_a, &mint_b).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn token_program_must_own_mint_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_transfer_fee_pool(&mut context, &program_id).await?;
+
+    // SPL Token passed for the Token-2022 side
+    let result: Result<(), BanksClientError> = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId),
+    );
+
+    Ok(())
+}
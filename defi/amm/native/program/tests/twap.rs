@@ -0,0 +1,79 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+use program::oracle::twap;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn price_cumulatives_track_time_weighted_price_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let created = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(created.price_cumulative_a, 0);
+    assert_eq!(created.price_cumulative_b, 0);
+
+    // equal reserves price both tokens at exactly 1.0
+    warp_forward(&mut context, 100).await?;
+
+    send(&mut context, &[sync_ix(&program_id, &pool, &mint_a, &mint_b)], &[]).await?;
+
+    let synced = pool_state(&mut context, &pool).await?;
+    let elapsed = (synced.last_update_ts - created.last_update_ts) as u128;
+
+    assert!(elapsed >= 100);
+    assert_eq!(synced.price_cumulative_a, (1 << 64) * elapsed);
+    assert_eq!(synced.price_cumulative_b, (1 << 64) * elapsed);
+
+    // selling token A makes it cheaper for the rest of the window
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, INITIAL_LIQUIDITY / 10, 0)?],
+        &[],
+    ).await?;
+
+    let swapped = pool_state(&mut context, &pool).await?;
+    let price_a = ((swapped.reserve_b as u128) << 64) / swapped.reserve_a as u128;
+
+    warp_forward(&mut context, 300).await?;
+
+    send(&mut context, &[sync_ix(&program_id, &pool, &mint_a, &mint_b)], &[]).await?;
+
+    let observed = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(
+        twap(swapped.price_cumulative_a, observed.price_cumulative_a, swapped.last_update_ts, observed.last_update_ts),
+        Some(price_a),
+    );
+
+    // over the whole window the average sits between the two spot prices
+    let average_a = twap(
+        created.price_cumulative_a,
+        observed.price_cumulative_a,
+        created.last_update_ts,
+        observed.last_update_ts,
+    ).unwrap();
+
+    assert!(average_a > price_a && average_a < 1 << 64);
+
+    Ok(())
+}
+
+#[test]
+fn twap_survives_accumulator_overflow() {
+    assert_eq!(twap(u128::MAX - 9, 20, 0, 10), Some(3));
+    assert_eq!(twap(0, 100, 10, 10), None);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+use program::processor::MINIMUM_LIQUIDITY;
+use program::state::CurveType;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Initializes a constant-product pool from an account holding neither token.
+// Returns (mint_a, mint_b, pool, creator).
+async fn initialize_pool(context: &mut ProgramTestContext, program_id: &Pubkey) -> Result<(Pubkey, Pubkey, Pubkey, Keypair)> {
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_mint(context).await?;
+    let mint_b = create_mint(context).await?;
+
+    create_funded_ata(context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(context, &payer, &mint_b, USER_FUNDS).await?;
+
+    let creator = Keypair::new();
+
+    send(context, &[transfer(&payer, &creator.pubkey(), 1_000_000_000)], &[]).await?;
+    send(
+        context,
+        &[initialize_pool_ix(program_id, &creator.pubkey(), &mint_a, &mint_b, FEE_BPS, 0, CurveType::ConstantProduct)?],
+        &[&creator],
+    ).await?;
+
+    Ok((mint_a, mint_b, pool_address(program_id, &mint_a, &mint_b, FEE_BPS), creator))
+}
+
+#[tokio::test]
+async fn initialize_then_deposit_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool, creator) = initialize_pool(&mut context, &program_id).await?;
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.admin, creator.pubkey());
+    assert_eq!((pool_data.reserve_a, pool_data.reserve_b), (0, 0));
+
+    // nothing to trade against until it's funded
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::EmptyPool as u32));
+
+    let (amount_a, amount_b) = (INITIAL_LIQUIDITY, 4 * INITIAL_LIQUIDITY);
+
+    send(
+        &mut context,
+        &[initial_deposit_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_a, amount_b)?],
+        &[],
+    ).await?;
+
+    let mint_lp = lp_mint_address(&program_id, &pool);
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!((pool_data.reserve_a, pool_data.reserve_b), (amount_a, amount_b));
+    assert_eq!(mint_supply(&mut context, &mint_lp).await?, 2 * INITIAL_LIQUIDITY);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mint_lp)).await?, MINIMUM_LIQUIDITY);
+    assert_eq!(
+        token_balance(&mut context, &get_associated_token_address(&payer, &mint_lp)).await?,
+        2 * INITIAL_LIQUIDITY - MINIMUM_LIQUIDITY,
+    );
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn initial_deposit_into_funded_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool, _creator) = initialize_pool(&mut context, &program_id).await?;
+
+    send(
+        &mut context,
+        &[initial_deposit_ix(&program_id, &payer, &pool, &mint_a, &mint_b, INITIAL_LIQUIDITY, INITIAL_LIQUIDITY)?],
+        &[],
+    ).await?;
+
+    // a second one would take its amounts whatever the pool's price
+    let result = send(
+        &mut context,
+        &[initial_deposit_ix(&program_id, &payer, &pool, &mint_a, &mint_b, INITIAL_LIQUIDITY, 1)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::PoolAlreadyFunded as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn initial_deposit_too_small_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool, _creator) = initialize_pool(&mut context, &program_id).await?;
+
+    let result = send(
+        &mut context,
+        &[initial_deposit_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000, 1_000)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::InsufficientInitialLiquidity as u32));
+
+    Ok(())
+}
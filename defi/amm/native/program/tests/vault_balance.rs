@@ -0,0 +1,104 @@
+mod common;
+
+use anyhow::Result;
+
+use borsh::to_vec;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Records `missing` more of mint A than vault_a holds, as a transfer into the
+// vault that never landed would.
+async fn overstate_reserve_a(context: &mut ProgramTestContext, pool: &Pubkey, missing: u64) -> Result<()> {
+    let mut pool_data = pool_state(context, pool).await?;
+    pool_data.reserve_a += missing;
+
+    let mut account: Account = context.banks_client.get_account(*pool).await?.unwrap();
+    let encoded = to_vec(&pool_data)?;
+
+    account.data[..encoded.len()].copy_from_slice(&encoded);
+    context.set_account(pool, &AccountSharedData::from(account));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_vault_balance_mismatch_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    overstate_reserve_a(&mut context, &pool, 1_000).await?;
+
+    let result = send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::VaultBalanceMismatch as u32));
+
+    // Sync brings the record back in line with the vault
+    send(&mut context, &[sync_ix(&program_id, &pool, &mint_a, &mint_b)], &[]).await?;
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 2_000_000, 0)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn provide_liquidity_vault_balance_mismatch_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    overstate_reserve_a(&mut context, &pool, 1_000).await?;
+
+    let result = send(
+        &mut context,
+        &[provide_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, (1_000_000, 1_000_000), (0, 0))?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::VaultBalanceMismatch as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_liquidity_vault_balance_mismatch_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    overstate_reserve_a(&mut context, &pool, 1_000).await?;
+
+    let result = send(
+        &mut context,
+        &[withdraw_liquidity_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000_000, (0, 0))?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::VaultBalanceMismatch as u32));
+
+    Ok(())
+}
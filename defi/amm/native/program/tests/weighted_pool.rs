@@ -0,0 +1,97 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::curve::swap_output;
+use program::error::AmmError;
+use program::state::CurveType;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn weighted_pool_prices_by_weight_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let curve = CurveType::Weighted { weight_a_bps: 8_000, weight_b_bps: 2_000 };
+
+    let (mint_a, mint_b, pool) = setup_pool_with_curve(&mut context, &program_id, FEE_BPS, 0, curve).await?;
+
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    let amount_in = 1_000_000;
+    let expected_out = swap_output(curve, INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, amount_in, FEE_BPS, true).unwrap();
+
+    send(
+        &mut context,
+        &[swap_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_in, expected_out)?],
+        &[],
+    ).await?;
+
+    let amount_out = token_balance(&mut context, &payer_ata_b).await? - balance_before;
+
+    assert_eq!(amount_out, expected_out);
+
+    // with equal reserves an 80/20 pool values one A at roughly four B
+    assert!(amount_out > 3 * amount_in && amount_out < 4 * amount_in);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.curve, curve);
+    // the LP fee is kept outside the reserve
+    assert_eq!(pool_data.reserve_a, INITIAL_LIQUIDITY + amount_in - amount_in * FEE_BPS as u64 / 10_000);
+    assert_eq!(pool_data.reserve_b, INITIAL_LIQUIDITY - amount_out);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_with_invalid_weights_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mint_a = create_mint(&mut context).await?;
+    let mint_b = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &mint_a, USER_FUNDS).await?;
+    create_funded_ata(&mut context, &payer, &mint_b, USER_FUNDS).await?;
+
+    for curve in [
+        CurveType::Weighted { weight_a_bps: 5_000, weight_b_bps: 4_000 },
+        CurveType::Weighted { weight_a_bps: 10_000, weight_b_bps: 0 },
+        CurveType::Weighted { weight_a_bps: 9_950, weight_b_bps: 50 },
+    ] {
+        let result = send(
+            &mut context,
+            &[
+                create_pool_ix(
+                    &program_id,
+                    &payer,
+                    &mint_a,
+                    &mint_b,
+                    INITIAL_LIQUIDITY,
+                    INITIAL_LIQUIDITY,
+                    FEE_BPS,
+                    0,
+                    curve,
+                )?,
+            ],
+            &[],
+        ).await;
+
+        assert_eq!(custom_error(result), Some(AmmError::InvalidWeights as u32));
+    }
+
+    Ok(())
+}
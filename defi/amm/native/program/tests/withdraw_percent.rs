@@ -0,0 +1,93 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn withdraw_percent_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let mint_lp = lp_mint_address(&program_id, &pool);
+    let payer_ata_lp = get_associated_token_address(&payer, &mint_lp);
+    let lp_before = token_balance(&mut context, &payer_ata_lp).await?;
+    let supply_before = mint_supply(&mut context, &mint_lp).await?;
+
+    send(
+        &mut context,
+        &[withdraw_percent_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 5_000, (0, 0))?],
+        &[],
+    ).await?;
+
+    let burned = lp_before / 2;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_lp).await?, lp_before - burned);
+    assert_eq!(mint_supply(&mut context, &mint_lp).await?, supply_before - burned);
+
+    // all of what's left, however much that is by now
+    send(
+        &mut context,
+        &[withdraw_percent_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 10_000, (0, 0))?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_lp).await?, 0);
+    assert_eq!(mint_supply(&mut context, &mint_lp).await?, supply_before - lp_before);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_percent_out_of_range_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    for bps in [0, 10_001] {
+        let result = send(
+            &mut context,
+            &[withdraw_percent_ix(&program_id, &payer, &pool, &mint_a, &mint_b, bps, (0, 0))?],
+            &[],
+        ).await;
+
+        assert_eq!(custom_error(result), Some(AmmError::InvalidWithdrawPercent as u32));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_percent_slippage_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    // a tenth of the LP can't pay out half of reserve A
+    let result = send(
+        &mut context,
+        &[withdraw_percent_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000, (INITIAL_LIQUIDITY / 2, 0))?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::SlippageExceed as u32));
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::AmmError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn withdraw_single_sided_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_a_before = token_balance(&mut context, &payer_ata_a).await?;
+    let balance_b_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    // a tenth of the supply withdraws a tenth of each reserve, then the B
+    // side is swapped into what's left of the pool
+    let amount_lp_in = INITIAL_LIQUIDITY / 10;
+    let withdrawn = INITIAL_LIQUIDITY / 10;
+    let remaining = INITIAL_LIQUIDITY - withdrawn;
+    let expected_out = withdrawn + quote(remaining, remaining, withdrawn, FEE_BPS);
+
+    send(
+        &mut context,
+        &[withdraw_single_sided_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_lp_in, &mint_a, expected_out)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, balance_a_before + expected_out);
+    assert_eq!(token_balance(&mut context, &payer_ata_b).await?, balance_b_before);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_single_sided_b_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let payer_ata_a = get_associated_token_address(&payer, &mint_a);
+    let payer_ata_b = get_associated_token_address(&payer, &mint_b);
+    let balance_a_before = token_balance(&mut context, &payer_ata_a).await?;
+    let balance_b_before = token_balance(&mut context, &payer_ata_b).await?;
+
+    let amount_lp_in = INITIAL_LIQUIDITY / 10;
+
+    send(
+        &mut context,
+        &[withdraw_single_sided_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_lp_in, &mint_b, 0)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_a).await?, balance_a_before);
+    assert!(token_balance(&mut context, &payer_ata_b).await? > balance_b_before + INITIAL_LIQUIDITY / 10);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_single_sided_slippage_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let amount_lp_in = INITIAL_LIQUIDITY / 10;
+    let withdrawn = INITIAL_LIQUIDITY / 10;
+    let remaining = INITIAL_LIQUIDITY - withdrawn;
+    let expected_out = withdrawn + quote(remaining, remaining, withdrawn, FEE_BPS);
+
+    let result = send(
+        &mut context,
+        &[withdraw_single_sided_ix(&program_id, &payer, &pool, &mint_a, &mint_b, amount_lp_in, &mint_a, expected_out + 1)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::SlippageExceed as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_single_sided_unknown_mint_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[withdraw_single_sided_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 1_000, &Pubkey::new_unique(), 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::MintAddressMismatch as u32));
+
+    Ok(())
+}
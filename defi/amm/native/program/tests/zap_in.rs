@@ -0,0 +1,132 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::AmmError;
+use program::math::zap_swap_amount;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn zap_in_a_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let mint_lp = lp_mint_address(&program_id, &pool);
+
+    let user = Keypair::new();
+    let amount_in = 100_000_000;
+
+    let user_ata_a = create_funded_ata(&mut context, &user.pubkey(), &mint_a, amount_in).await?;
+    let user_ata_b = create_ata(&mut context, &user.pubkey(), &mint_b).await?;
+    let user_ata_lp = create_ata(&mut context, &user.pubkey(), &mint_lp).await?;
+
+    let swap_amount = zap_swap_amount(INITIAL_LIQUIDITY, amount_in, FEE_BPS).unwrap();
+    let swap_out = quote(INITIAL_LIQUIDITY, INITIAL_LIQUIDITY, swap_amount, FEE_BPS);
+
+    send(
+        &mut context,
+        &[zap_in_ix(&program_id, &user.pubkey(), &pool, &mint_a, &mint_b, amount_in, 0)?],
+        &[&user],
+    ).await?;
+
+    let lp = token_balance(&mut context, &user_ata_lp).await?;
+
+    // the LP share is priced off the post-swap reserves
+    let expected_lp = swap_out as u128 * INITIAL_LIQUIDITY as u128 / (INITIAL_LIQUIDITY - swap_out) as u128;
+
+    assert!(lp > 0);
+    assert!(lp as u128 <= expected_lp);
+    assert!(lp as u128 >= expected_lp * 999 / 1_000);
+
+    // only rounding dust is left behind
+    assert!(token_balance(&mut context, &user_ata_a).await? <= amount_in / 10_000);
+    assert!(token_balance(&mut context, &user_ata_b).await? <= amount_in / 10_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn zap_in_b_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let mint_lp = lp_mint_address(&program_id, &pool);
+
+    let user = Keypair::new();
+    let amount_in = 100_000_000;
+
+    let user_ata_a = create_ata(&mut context, &user.pubkey(), &mint_a).await?;
+    let user_ata_b = create_funded_ata(&mut context, &user.pubkey(), &mint_b, amount_in).await?;
+    let user_ata_lp = create_ata(&mut context, &user.pubkey(), &mint_lp).await?;
+
+    send(
+        &mut context,
+        &[zap_in_ix(&program_id, &user.pubkey(), &pool, &mint_b, &mint_a, amount_in, 0)?],
+        &[&user],
+    ).await?;
+
+    assert!(token_balance(&mut context, &user_ata_lp).await? > 0);
+    assert!(token_balance(&mut context, &user_ata_a).await? <= amount_in / 10_000);
+    assert!(token_balance(&mut context, &user_ata_b).await? <= amount_in / 10_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn zap_in_slippage_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+    let mint_lp = lp_mint_address(&program_id, &pool);
+
+    let user = Keypair::new();
+    let amount_in = 100_000_000;
+
+    create_funded_ata(&mut context, &user.pubkey(), &mint_a, amount_in).await?;
+    create_ata(&mut context, &user.pubkey(), &mint_b).await?;
+    create_ata(&mut context, &user.pubkey(), &mint_lp).await?;
+
+    // LP supply equals the reserves, so even amount_in on both sides would
+    // only mint amount_in
+    let min_lp_out = amount_in;
+
+    let result = send(
+        &mut context,
+        &[zap_in_ix(&program_id, &user.pubkey(), &pool, &mint_a, &mint_b, amount_in, min_lp_out)?],
+        &[&user],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::SlippageExceed as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn zap_in_zero_amount_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let (mint_a, mint_b, pool) = setup_pool(&mut context, &program_id, FEE_BPS, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[zap_in_ix(&program_id, &payer, &pool, &mint_a, &mint_b, 0, 0)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(AmmError::ZeroSwapAmount as u32));
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteError {
+    #[error("Account data is not an initialized LiquidityPool")]
+    InvalidPoolAccount,
+    #[error("Only constant-product pools can be quoted")]
+    UnsupportedCurve,
+    #[error("Pool is paused")]
+    PoolPaused,
+    #[error("Pool has an outstanding flash loan")]
+    FlashLoanActive,
+    #[error("Swap amount must be greater than zero")]
+    ZeroAmount,
+    #[error("Quote overflowed")]
+    Overflow,
+}
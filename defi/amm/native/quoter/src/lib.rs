@@ -0,0 +1,118 @@
+// Off-chain quotes for the native AMM. Decodes a LiquidityPool account and
+// reproduces the program's fee split and constant-product output, rounding
+// included, so a client can compute min_out without simulating a swap.
+//
+// Only plain SPL Token mints are covered: with a Token-2022 transfer fee the
+// pool prices what actually arrives, which depends on the mint's fee config.
+
+#![allow(clippy::manual_div_ceil, clippy::assign_op_pattern)]
+
+pub mod state;
+pub mod error;
+
+use uint::construct_uint;
+
+use crate::{
+    state::{PoolState, CurveType},
+    error::QuoteError,
+};
+
+construct_uint! {
+    pub struct U256(4);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quote {
+    pub amount_out: u64,
+    // part of amount_in sent to the treasury
+    pub protocol_fee: u64,
+    // part of amount_in sent to the referrer, zero without one
+    pub referral_fee: u64,
+    // part of amount_in kept for the LPs, outside the reserves
+    pub lp_fee: u64,
+    // reserves after the swap, as the program will record them
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+// Quotes a Swap of `amount_in`, A to B when `a_to_b`. Set `with_referrer`
+// when the swap will pass a referrer account.
+pub fn quote_swap(pool: &PoolState, amount_in: u64, a_to_b: bool, with_referrer: bool) -> Result<Quote, QuoteError> {
+    if pool.curve != CurveType::ConstantProduct {
+        return Err(QuoteError::UnsupportedCurve);
+    }
+
+    if pool.paused {
+        return Err(QuoteError::PoolPaused);
+    }
+
+    if pool.flash_loan_amount > 0 {
+        return Err(QuoteError::FlashLoanActive);
+    }
+
+    if amount_in == 0 {
+        return Err(QuoteError::ZeroAmount);
+    }
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (pool.reserve_a, pool.reserve_b)
+    } else {
+        (pool.reserve_b, pool.reserve_a)
+    };
+
+    let protocol_fee = fee_share(amount_in, pool.fee_bps, pool.protocol_fee_bps);
+    let referral_fee = if with_referrer {
+        fee_share(amount_in, pool.fee_bps, pool.referral_fee_bps)
+    } else {
+        0
+    };
+
+    let amount_out = constant_product_output(reserve_in, reserve_out, amount_in, pool.fee_bps)
+        .ok_or(QuoteError::Overflow)?;
+
+    // the reserve takes what the curve priced, the rest of the vault's share
+    // is the LP fee
+    let vault_received = amount_in - protocol_fee - referral_fee;
+    let priced = amount_in - ((amount_in as u128) * (pool.fee_bps as u128) / 10_000) as u64;
+    let to_reserve = vault_received.min(priced);
+    let lp_fee = vault_received - to_reserve;
+
+    let new_reserve_in = reserve_in
+        .checked_add(to_reserve)
+        .ok_or(QuoteError::Overflow)?;
+    let new_reserve_out = reserve_out - amount_out;
+
+    let (reserve_a, reserve_b) = if a_to_b {
+        (new_reserve_in, new_reserve_out)
+    } else {
+        (new_reserve_out, new_reserve_in)
+    };
+
+    Ok(Quote { amount_out, protocol_fee, referral_fee, lp_fee, reserve_a, reserve_b })
+}
+
+// `amount_out` less `slippage_bps`, rounded down, for use as min_out.
+pub fn min_out(amount_out: u64, slippage_bps: u16) -> u64 {
+    let slippage_bps = slippage_bps.min(10_000) as u128;
+
+    (amount_out as u128 * (10_000 - slippage_bps) / 10_000) as u64
+}
+
+// share_bps of the swap fee on amount_in, divided in the same order as the
+// program so the rounding matches.
+fn fee_share(amount_in: u64, fee_bps: u16, share_bps: u16) -> u64 {
+    ((amount_in as u128) * (fee_bps as u128) / 10_000 * (share_bps as u128) / 10_000) as u64
+}
+
+fn constant_product_output(reserve_in: u64, reserve_out: u64, amount_in: u64, fee_bps: u16) -> Option<u64> {
+    let amount_in_post_fee = U256::from(amount_in) * U256::from(10_000 - fee_bps.min(10_000) as u64);
+
+    let numerator = U256::from(reserve_out) * amount_in_post_fee;
+    let denominator = U256::from(reserve_in) * U256::from(10_000u64) + amount_in_post_fee;
+
+    if denominator.is_zero() {
+        return None;
+    }
+
+    u64::try_from(numerator / denominator).ok()
+}
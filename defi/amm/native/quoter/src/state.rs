@@ -0,0 +1,72 @@
+// Mirror of the program's LiquidityPool layout. Pubkeys are kept as raw
+// bytes so this crate doesn't need the Solana SDK.
+
+use borsh::{BorshSerialize, BorshDeserialize};
+
+use crate::error::QuoteError;
+
+pub const POOL_DISCRIMINATOR: [u8; 8] = *b"amm_pool";
+pub const POOL_VERSION: u8 = 5;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveType {
+    ConstantProduct,
+    Stable {
+        amp: u64,
+    },
+    ConstantSum,
+    Weighted {
+        weight_a_bps: u16,
+        weight_b_bps: u16,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PoolState {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub mint_a: [u8; 32],
+    pub mint_b: [u8; 32],
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub fees_a: u64,
+    pub fees_b: u64,
+    pub fees_total_a: u128,
+    pub fees_total_b: u128,
+    pub fee_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub referral_fee_bps: u16,
+    pub curve: CurveType,
+    pub admin: [u8; 32],
+    pub pending_admin: [u8; 32],
+    pub paused: bool,
+    pub price_cumulative_a: u128,
+    pub price_cumulative_b: u128,
+    pub last_update_ts: i64,
+    pub flash_loan_amount: u64,
+    pub flash_loan_mint: [u8; 32],
+    pub pending_protocol_fee_bps: u16,
+    pub fee_change_ts: i64,
+    pub oracle: [u8; 32],
+    pub max_oracle_deviation_bps: u16,
+    pub bump: u8,
+}
+
+impl PoolState {
+    // Decodes pool account data. Shorter curve variants leave trailing
+    // padding, which is ignored. Pools still on an older layout have to be
+    // migrated on-chain first.
+    pub fn decode(data: &[u8]) -> Result<Self, QuoteError> {
+        let pool = Self::deserialize(&mut &data[..])
+            .map_err(|_| QuoteError::InvalidPoolAccount)?;
+
+        if pool.discriminator != POOL_DISCRIMINATOR || pool.version != POOL_VERSION || !pool.is_initialized {
+            return Err(QuoteError::InvalidPoolAccount);
+        }
+
+        Ok(pool)
+    }
+}
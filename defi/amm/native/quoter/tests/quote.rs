@@ -0,0 +1,137 @@
+use proptest::prelude::*;
+
+use amm_quoter::{
+    quote_swap,
+    min_out,
+    error::QuoteError,
+    state::{PoolState, CurveType, POOL_DISCRIMINATOR, POOL_VERSION},
+};
+
+fn pool(reserve_a: u64, reserve_b: u64, fee_bps: u16, protocol_fee_bps: u16) -> PoolState {
+    PoolState {
+        discriminator: POOL_DISCRIMINATOR,
+        version: POOL_VERSION,
+        is_initialized: true,
+        mint_a: [1; 32],
+        mint_b: [2; 32],
+        decimals_a: 6,
+        decimals_b: 6,
+        reserve_a,
+        reserve_b,
+        fees_a: 0,
+        fees_b: 0,
+        fees_total_a: 0,
+        fees_total_b: 0,
+        fee_bps,
+        protocol_fee_bps,
+        referral_fee_bps: 0,
+        curve: CurveType::ConstantProduct,
+        admin: [3; 32],
+        pending_admin: [0; 32],
+        paused: false,
+        price_cumulative_a: 0,
+        price_cumulative_b: 0,
+        last_update_ts: 0,
+        flash_loan_amount: 0,
+        flash_loan_mint: [0; 32],
+        pending_protocol_fee_bps: 0,
+        fee_change_ts: 0,
+        oracle: [0; 32],
+        max_oracle_deviation_bps: 0,
+        bump: 255,
+    }
+}
+
+#[test]
+fn quote_of_known_swap() {
+    let pool = pool(1_000_000_000, 1_000_000_000, 30, 2_000);
+
+    let quote = quote_swap(&pool, 10_000_000, true, false).unwrap();
+
+    // 1e9 * 1e7 * 0.997 / (1e9 + 1e7 * 0.997), rounded down
+    assert_eq!(quote.amount_out, 9_871_580);
+    // 20% of the 30_000 fee
+    assert_eq!(quote.protocol_fee, 6_000);
+    assert_eq!(quote.referral_fee, 0);
+    // the rest of the fee stays in the vault, outside the reserve
+    assert_eq!(quote.lp_fee, 24_000);
+    assert_eq!(quote.reserve_a, 1_000_000_000 + 10_000_000 - 30_000);
+    assert_eq!(quote.reserve_b, 1_000_000_000 - 9_871_580);
+}
+
+#[test]
+fn quote_with_referrer() {
+    let mut pool = pool(1_000_000_000, 1_000_000_000, 30, 2_000);
+    pool.referral_fee_bps = 5_000;
+
+    let with_referrer = quote_swap(&pool, 10_000_000, false, true).unwrap();
+    let without_referrer = quote_swap(&pool, 10_000_000, false, false).unwrap();
+
+    // the referrer only changes where the fee goes, not the output
+    assert_eq!(with_referrer.amount_out, without_referrer.amount_out);
+    assert_eq!(with_referrer.referral_fee, 15_000);
+    assert_eq!(with_referrer.lp_fee, without_referrer.lp_fee - 15_000);
+    assert_eq!(with_referrer.reserve_b, without_referrer.reserve_b);
+}
+
+#[test]
+fn quote_rejects_unquotable_pools() {
+    let mut paused = pool(1_000, 1_000, 30, 0);
+    paused.paused = true;
+
+    assert_eq!(quote_swap(&paused, 100, true, false), Err(QuoteError::PoolPaused));
+
+    let mut stable = pool(1_000, 1_000, 30, 0);
+    stable.curve = CurveType::Stable { amp: 100 };
+
+    assert_eq!(quote_swap(&stable, 100, true, false), Err(QuoteError::UnsupportedCurve));
+    assert_eq!(quote_swap(&pool(1_000, 1_000, 30, 0), 0, true, false), Err(QuoteError::ZeroAmount));
+}
+
+#[test]
+fn decode_pool_account() {
+    let pool = pool(1_000, 2_000, 30, 0);
+
+    // the account is sized for the largest curve variant
+    let mut data = borsh::to_vec(&pool).unwrap();
+    data.extend_from_slice(&[0; 8]);
+
+    assert_eq!(PoolState::decode(&data), Ok(pool));
+
+    // a pool that hasn't been migrated yet
+    let mut legacy = data.clone();
+    legacy[8] = 1;
+
+    assert_eq!(PoolState::decode(&legacy), Err(QuoteError::InvalidPoolAccount));
+
+    data[0] ^= 0xff;
+
+    assert_eq!(PoolState::decode(&data), Err(QuoteError::InvalidPoolAccount));
+    assert_eq!(PoolState::decode(&[]), Err(QuoteError::InvalidPoolAccount));
+}
+
+#[test]
+fn min_out_of_known_values() {
+    assert_eq!(min_out(10_000, 50), 9_950);
+    assert_eq!(min_out(999, 1), 998);
+    assert_eq!(min_out(10_000, 20_000), 0);
+}
+
+proptest! {
+    #[test]
+    fn quote_keeps_constant_product(
+        reserve_a in 1_000u64..u64::MAX / 4,
+        reserve_b in 1_000u64..u64::MAX / 4,
+        amount_in in 1u64..u64::MAX / 4,
+        fee_bps in 0u16..1_000,
+        protocol_fee_bps in 0u16..10_000,
+    ) {
+        let pool = pool(reserve_a, reserve_b, fee_bps, protocol_fee_bps);
+        let quote = quote_swap(&pool, amount_in, true, false).unwrap();
+
+        prop_assert!(quote.amount_out < reserve_b);
+        prop_assert!(
+            quote.reserve_a as u128 * quote.reserve_b as u128 >= reserve_a as u128 * reserve_b as u128
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum SwapProgramError {
+    #[msg("Swap amount must be greater than zero")]
+    InvalidSwapZeroAmount,
+    #[msg("Cannot swap an asset for itself")]
+    InvalidSwapMatchingAssets,
+    #[msg("The pool can't pay out anything for this amount")]
+    InvalidSwapNotEnoughLiquidity,
+    #[msg("Fee must not exceed 10000 basis points (100%)")]
+    InvalidFee,
+    #[msg("The pool already holds the maximum number of assets")]
+    PoolAssetLimitReached,
+}
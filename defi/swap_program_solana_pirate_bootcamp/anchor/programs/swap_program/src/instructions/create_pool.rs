@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::SwapProgramError, state::LiquidityPool};
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // one pool per program, like the native version
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LiquidityPool::INIT_SPACE,
+        seeds = [b"liquidity_pool"],
+        bump,
+    )]
+    pub pool: Account<'info, LiquidityPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreatePool>, fee_bps: u16) -> Result<()> {
+    require!(fee_bps <= 10_000, SwapProgramError::InvalidFee);
+
+    ctx.accounts.pool.set_inner(LiquidityPool {
+        admin: ctx.accounts.payer.key(),
+        fee_bps,
+        asset_count: 0,
+        bump: ctx.bumps.pool,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, TransferChecked},
+};
+
+use crate::{
+    error::SwapProgramError,
+    state::{LiquidityPool, PoolAsset, MAX_ASSETS},
+};
+
+#[derive(Accounts)]
+pub struct FundPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [b"liquidity_pool"], bump = pool.bump)]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub mint: Box<Account<'info, Mint>>,
+
+    // created the first time the pool is funded with `mint`
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PoolAsset::INIT_SPACE,
+        seeds = [b"pool_asset", pool.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub pool_asset: Box<Account<'info, PoolAsset>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = pool,
+    )]
+    pub pool_ata: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_ata: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<FundPool>, amount: u64) -> Result<()> {
+    // a PoolAsset init_if_needed just created is still zeroed
+    if ctx.accounts.pool_asset.mint == Pubkey::default() {
+        let pool = &mut ctx.accounts.pool;
+
+        require!(pool.asset_count < MAX_ASSETS, SwapProgramError::PoolAssetLimitReached);
+
+        pool.asset_count += 1;
+
+        ctx.accounts.pool_asset.set_inner(PoolAsset {
+            mint: ctx.accounts.mint.key(),
+            collected_fees: 0,
+            bump: ctx.bumps.pool_asset,
+        });
+    }
+
+    // transfer amount from payer_ata to pool_ata
+    token::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.payer_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.pool_ata.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    Ok(())
+}
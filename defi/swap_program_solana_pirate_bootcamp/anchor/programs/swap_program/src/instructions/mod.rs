@@ -0,0 +1,10 @@
+pub mod create_pool;
+pub mod fund_pool;
+pub mod swap;
+
+// every module has its own `handler`, only the account structs are used via
+// these re-exports
+#[allow(ambiguous_glob_reexports)]
+pub use create_pool::*;
+pub use fund_pool::*;
+pub use swap::*;
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+
+use crate::{
+    error::SwapProgramError,
+    state::{LiquidityPool, PoolAsset},
+};
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"liquidity_pool"], bump = pool.bump)]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(constraint = receive_mint.key() != pay_mint.key() @ SwapProgramError::InvalidSwapMatchingAssets)]
+    pub receive_mint: Box<Account<'info, Mint>>,
+    pub pay_mint: Box<Account<'info, Mint>>,
+
+    // both have to be in the pool
+    #[account(
+        mut,
+        seeds = [b"pool_asset", pool.key().as_ref(), receive_mint.key().as_ref()],
+        bump = receive_asset.bump,
+    )]
+    pub receive_asset: Box<Account<'info, PoolAsset>>,
+
+    #[account(
+        seeds = [b"pool_asset", pool.key().as_ref(), pay_mint.key().as_ref()],
+        bump = pay_asset.bump,
+    )]
+    pub pay_asset: Box<Account<'info, PoolAsset>>,
+
+    #[account(
+        mut,
+        associated_token::mint = receive_mint,
+        associated_token::authority = pool,
+    )]
+    pub pool_receive_ata: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = pool,
+    )]
+    pub pool_pay_ata: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = receive_mint, token::authority = payer)]
+    pub payer_receive_ata: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = pay_mint, token::authority = payer)]
+    pub payer_pay_ata: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<Swap>, amount_to_swap: u64) -> Result<()> {
+    require!(amount_to_swap > 0, SwapProgramError::InvalidSwapZeroAmount);
+
+    // r = R * p / (P + p), the fee comes off r and stays in the pool
+    let pool_receive_balance = ctx.accounts.pool_receive_ata.amount as u128;
+    let pool_pay_balance = ctx.accounts.pool_pay_ata.amount as u128;
+
+    let amount_to_receive =
+        (pool_receive_balance * amount_to_swap as u128 / (pool_pay_balance + amount_to_swap as u128)) as u64;
+    let fee = (amount_to_receive as u128 * ctx.accounts.pool.fee_bps as u128 / 10_000) as u64;
+    let amount_to_receive = amount_to_receive - fee;
+
+    require!(amount_to_receive > 0, SwapProgramError::InvalidSwapNotEnoughLiquidity);
+
+    // transfer amount_to_swap from payer_pay_ata to pool_pay_ata
+    token::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.payer_pay_ata.to_account_info(),
+                mint: ctx.accounts.pay_mint.to_account_info(),
+                to: ctx.accounts.pool_pay_ata.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
+            },
+        ),
+        amount_to_swap,
+        ctx.accounts.pay_mint.decimals,
+    )?;
+
+    let bump = [ctx.accounts.pool.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"liquidity_pool", &bump]];
+
+    // transfer amount_to_receive from pool_receive_ata to payer_receive_ata
+    token::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_receive_ata.to_account_info(),
+                mint: ctx.accounts.receive_mint.to_account_info(),
+                to: ctx.accounts.payer_receive_ata.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount_to_receive,
+        ctx.accounts.receive_mint.decimals,
+    )?;
+
+    let receive_asset = &mut ctx.accounts.receive_asset;
+
+    receive_asset.collected_fees = receive_asset.collected_fees
+        .checked_add(fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+// Anchor port of the native pirate swap's CreatePool, FundPool and Swap, so
+// the account validation can be compared side by side with
+// defi/swap_program_solana_pirate_bootcamp/native/program. Pausing, funding
+// receipts, weights, price and volume tracking and the admin instructions
+// are left to the native version.
+// the code generated by #[program] still calls AccountInfo::realloc
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+
+pub mod error;
+pub mod instructions;
+pub mod state;
+
+use instructions::*;
+
+declare_id!("CyKa6wC8ZHCgwJsgGnRqHhZ8Ti7qY9tBqt8WKyriQnAz");
+
+#[program]
+pub mod swap_program {
+    use super::*;
+
+    pub fn create_pool(ctx: Context<CreatePool>, fee_bps: u16) -> Result<()> {
+        instructions::create_pool::handler(ctx, fee_bps)
+    }
+
+    pub fn fund_pool(ctx: Context<FundPool>, amount: u64) -> Result<()> {
+        instructions::fund_pool::handler(ctx, amount)
+    }
+
+    pub fn swap(ctx: Context<Swap>, amount_to_swap: u64) -> Result<()> {
+        instructions::swap::handler(ctx, amount_to_swap)
+    }
+}
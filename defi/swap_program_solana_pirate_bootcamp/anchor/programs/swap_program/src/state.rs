@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_ASSETS: u8 = 10;
+
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityPool {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    // how many PoolAsset accounts the pool has
+    pub asset_count: u8,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PoolAsset {
+    pub mint: Pubkey,
+    // swap fees kept so far
+    pub collected_fees: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,248 @@
+#![allow(dead_code)]
+
+use anyhow::Result;
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{entrypoint::ProgramResult, instruction::Instruction},
+    AccountDeserialize,
+    InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token::spl_token::{
+        id as token_program_id,
+        instruction::{initialize_mint2, mint_to},
+        state::{Account as TokenAccount, Mint},
+    },
+};
+
+use solana_program_test::*;
+
+use solana_sdk::{
+    instruction::InstructionError,
+    program_pack::Pack,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::instruction::create_account;
+
+use swap_program::state::{LiquidityPool, PoolAsset};
+
+pub const DECIMALS: u8 = 6;
+pub const USER_FUNDS: u64 = 10_000_000_000;
+pub const INITIAL_FUNDING: u64 = 1_000_000_000;
+
+// `swap_program::entry` ties the account slice to the AccountInfo lifetime,
+// which processor! can't express, so hand it a leaked copy.
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+
+    swap_program::entry(program_id, accounts, data)
+}
+
+pub async fn start() -> ProgramTestContext {
+    ProgramTest::new(
+        "swap_program",
+        swap_program::ID,
+        processor!(process_instruction),
+    ).start_with_context().await
+}
+
+// Fetches a fresh blockhash for every transaction so that repeating an
+// identical instruction isn't rejected as already processed.
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await?;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+// Anchor reports both its own constraint errors and `SwapProgramError`s
+// (offset by ERROR_CODE_OFFSET) as custom errors.
+pub fn custom_error(result: Result<(), BanksClientError>) -> Option<u32> {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+pub async fn create_mint(context: &mut ProgramTestContext) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[
+            create_account(
+                &payer,
+                &mint.pubkey(),
+                Rent::default().minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &token_program_id(),
+            ),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer, None, DECIMALS)?,
+        ],
+        &[&mint],
+    ).await?;
+
+    Ok(mint.pubkey())
+}
+
+pub async fn create_ata(context: &mut ProgramTestContext, owner: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[create_associated_token_account_idempotent(&payer, owner, mint, &token_program_id())],
+        &[],
+    ).await?;
+
+    Ok(get_associated_token_address(owner, mint))
+}
+
+// Only works for mints created by `create_mint`, whose authority is the payer.
+pub async fn create_funded_ata(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+    let ata = create_ata(context, owner, mint).await?;
+
+    send(
+        context,
+        &[mint_to(&token_program_id(), mint, &ata, &payer, &[], amount)?],
+        &[],
+    ).await?;
+
+    Ok(ata)
+}
+
+// Creates a pool and `count` mints, funds the payer with USER_FUNDS of each
+// and puts INITIAL_FUNDING of each into the pool. Returns the mints.
+pub async fn setup_pool(context: &mut ProgramTestContext, count: usize, fee_bps: u16) -> Result<Vec<Pubkey>> {
+    let payer = context.payer.pubkey();
+
+    send(context, &[create_pool_ix(&payer, fee_bps)], &[]).await?;
+
+    let mut mints = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let mint = create_mint(context).await?;
+
+        create_funded_ata(context, &payer, &mint, USER_FUNDS).await?;
+        send(context, &[fund_pool_ix(&payer, &mint, INITIAL_FUNDING)], &[]).await?;
+
+        mints.push(mint);
+    }
+
+    Ok(mints)
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> Result<u64> {
+    let account = context.banks_client.get_account(*address).await?.unwrap();
+
+    Ok(TokenAccount::unpack(&account.data)?.amount)
+}
+
+pub async fn pool_state(context: &mut ProgramTestContext) -> Result<LiquidityPool> {
+    let account = context.banks_client.get_account(pool_address()).await?.unwrap();
+
+    Ok(LiquidityPool::try_deserialize(&mut &account.data[..])?)
+}
+
+pub async fn pool_asset_state(context: &mut ProgramTestContext, mint: &Pubkey) -> Result<PoolAsset> {
+    let account = context.banks_client.get_account(pool_asset_address(mint)).await?.unwrap();
+
+    Ok(PoolAsset::try_deserialize(&mut &account.data[..])?)
+}
+
+// Same formula the program uses for swaps, after the fee.
+pub fn quote(pool_receive_balance: u64, pool_pay_balance: u64, amount_to_swap: u64, fee_bps: u16) -> u64 {
+    let amount_to_receive =
+        (pool_receive_balance as u128 * amount_to_swap as u128 / (pool_pay_balance as u128 + amount_to_swap as u128)) as u64;
+
+    amount_to_receive - (amount_to_receive as u128 * fee_bps as u128 / 10_000) as u64
+}
+
+pub fn pool_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"liquidity_pool"], &swap_program::ID).0
+}
+
+pub fn pool_asset_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_asset", pool_address().as_ref(), mint.as_ref()], &swap_program::ID).0
+}
+
+pub fn create_pool_ix(payer: &Pubkey, fee_bps: u16) -> Instruction {
+    Instruction {
+        program_id: swap_program::ID,
+        accounts: swap_program::accounts::CreatePool {
+            payer: *payer,
+            pool: pool_address(),
+            system_program: anchor_lang::system_program::ID,
+        }.to_account_metas(None),
+        data: swap_program::instruction::CreatePool { fee_bps }.data(),
+    }
+}
+
+pub fn fund_pool_ix(payer: &Pubkey, mint: &Pubkey, amount: u64) -> Instruction {
+    let pool = pool_address();
+
+    Instruction {
+        program_id: swap_program::ID,
+        accounts: swap_program::accounts::FundPool {
+            payer: *payer,
+            pool,
+            mint: *mint,
+            pool_asset: pool_asset_address(mint),
+            pool_ata: get_associated_token_address(&pool, mint),
+            payer_ata: get_associated_token_address(payer, mint),
+            token_program: token_program_id(),
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        }.to_account_metas(None),
+        data: swap_program::instruction::FundPool { amount }.data(),
+    }
+}
+
+pub fn swap_ix(payer: &Pubkey, receive_mint: &Pubkey, pay_mint: &Pubkey, amount_to_swap: u64) -> Instruction {
+    let pool = pool_address();
+
+    Instruction {
+        program_id: swap_program::ID,
+        accounts: swap_program::accounts::Swap {
+            payer: *payer,
+            pool,
+            receive_mint: *receive_mint,
+            pay_mint: *pay_mint,
+            receive_asset: pool_asset_address(receive_mint),
+            pay_asset: pool_asset_address(pay_mint),
+            pool_receive_ata: get_associated_token_address(&pool, receive_mint),
+            pool_pay_ata: get_associated_token_address(&pool, pay_mint),
+            payer_receive_ata: get_associated_token_address(payer, receive_mint),
+            payer_pay_ata: get_associated_token_address(payer, pay_mint),
+            token_program: token_program_id(),
+        }.to_account_metas(None),
+        data: swap_program::instruction::Swap { amount_to_swap }.data(),
+    }
+}
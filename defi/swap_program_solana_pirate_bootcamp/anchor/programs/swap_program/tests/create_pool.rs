@@ -0,0 +1,44 @@
+mod common;
+
+use anyhow::Result;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+
+use solana_sdk::signature::Signer;
+
+use swap_program::error::SwapProgramError;
+
+use common::*;
+
+#[tokio::test]
+async fn create_pool_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    send(&mut context, &[create_pool_ix(&payer, 30)], &[]).await?;
+
+    let pool_data = pool_state(&mut context).await?;
+
+    assert_eq!(pool_data.admin, payer);
+    assert_eq!(pool_data.fee_bps, 30);
+    assert_eq!(pool_data.asset_count, 0);
+
+    // there's only the one pool
+    let result = send(&mut context, &[create_pool_ix(&payer, 0)], &[]).await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_invalid_fee_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let result = send(&mut context, &[create_pool_ix(&payer, 10_001)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + SwapProgramError::InvalidFee as u32));
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use anyhow::Result;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_spl::associated_token::get_associated_token_address;
+
+use solana_sdk::signature::Signer;
+
+use swap_program::error::SwapProgramError;
+use swap_program::state::MAX_ASSETS;
+
+use common::*;
+
+#[tokio::test]
+async fn fund_pool_multiple_mints_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, 3, 0).await?;
+    let pool = pool_address();
+
+    assert_eq!(pool_state(&mut context).await?.asset_count, 3);
+
+    for mint in &mints {
+        assert_eq!(pool_asset_state(&mut context, mint).await?.mint, *mint);
+        assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, mint)).await?, INITIAL_FUNDING);
+        assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, mint)).await?, USER_FUNDS - INITIAL_FUNDING);
+    }
+
+    // a listed mint is topped up, not listed again
+    send(&mut context, &[fund_pool_ix(&payer, &mints[0], 1_000)], &[]).await?;
+
+    assert_eq!(pool_state(&mut context).await?.asset_count, 3);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mints[0])).await?, INITIAL_FUNDING + 1_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pool_asset_limit_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, MAX_ASSETS as usize, 0).await?;
+
+    let one_too_many = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &one_too_many, USER_FUNDS).await?;
+
+    let result = send(&mut context, &[fund_pool_ix(&payer, &one_too_many, 1_000_000)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + SwapProgramError::PoolAssetLimitReached as u32));
+
+    // a full pool still takes more of what it already lists
+    send(&mut context, &[fund_pool_ix(&payer, &mints[0], 1_000_000)], &[]).await?;
+
+    Ok(())
+}
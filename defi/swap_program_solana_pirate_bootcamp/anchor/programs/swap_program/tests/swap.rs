@@ -0,0 +1,111 @@
+mod common;
+
+use anyhow::Result;
+
+use anchor_lang::error::{ErrorCode, ERROR_CODE_OFFSET};
+use anchor_spl::associated_token::get_associated_token_address;
+
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+use swap_program::error::SwapProgramError;
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// payer_receive_ata's index, see swap_ix
+const SWAP_PAYER_RECEIVE_ATA: usize = 8;
+
+#[tokio::test]
+async fn swap_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, 2, FEE_BPS).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pool = pool_address();
+    let amount_to_swap = 100_000_000;
+
+    send(&mut context, &[swap_ix(&payer, &gold, &rum, amount_to_swap)], &[]).await?;
+
+    let expected = quote(INITIAL_FUNDING, INITIAL_FUNDING, amount_to_swap, FEE_BPS);
+    let fee = quote(INITIAL_FUNDING, INITIAL_FUNDING, amount_to_swap, 0) - expected;
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &gold)).await?, USER_FUNDS - INITIAL_FUNDING + expected);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &rum)).await?, INITIAL_FUNDING + amount_to_swap);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING - expected);
+
+    assert_eq!(pool_asset_state(&mut context, &gold).await?.collected_fees, fee);
+    assert_eq!(pool_asset_state(&mut context, &rum).await?.collected_fees, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_zero_amount_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, 2, FEE_BPS).await?;
+
+    let result = send(&mut context, &[swap_ix(&payer, &mints[0], &mints[1], 0)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + SwapProgramError::InvalidSwapZeroAmount as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_matching_assets_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, 2, FEE_BPS).await?;
+
+    let result = send(&mut context, &[swap_ix(&payer, &mints[0], &mints[0], 1_000_000)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ERROR_CODE_OFFSET + SwapProgramError::InvalidSwapMatchingAssets as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_asset_not_in_pool_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, 1, FEE_BPS).await?;
+
+    let parrots = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &parrots, USER_FUNDS).await?;
+
+    // parrots has no PoolAsset
+    let result = send(&mut context, &[swap_ix(&payer, &mints[0], &parrots, 1_000_000)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ErrorCode::AccountNotInitialized as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_to_someone_elses_ata_test() -> Result<()> {
+    let mut context = start().await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, 2, FEE_BPS).await?;
+
+    // the pool pays out to the payer's own token account, like the native Swap
+    let other_ata = create_ata(&mut context, &Pubkey::new_unique(), &mints[0]).await?;
+
+    let mut instruction = swap_ix(&payer, &mints[0], &mints[1], 1_000_000);
+    instruction.accounts[SWAP_PAYER_RECEIVE_ATA].pubkey = other_ata;
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(ErrorCode::ConstraintTokenOwner as u32));
+    assert_eq!(token_balance(&mut context, &other_ata).await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use anyhow::{anyhow, Result};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Signer, Keypair, keypair},
+    transaction::Transaction,
+};
+use solana_system_interface::instruction::create_account;
+use spl_associated_token_account::{
+    get_associated_token_address,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{
+    id as token_program_id,
+    instruction::{initialize_mint2, mint_to},
+    state::{Account as TokenAccount, Mint},
+};
+
+use program::client::{create_pool_ix, fund_pool_ix, pool_address, swap_ix};
+
+const FEE_BPS: u16 = 30;
+const DECIMALS: u8 = 6;
+const ONE_TOKEN: u64 = 10u64.pow(DECIMALS as u32);
+
+// The bootcamp's pirate trading post: stocks the pool with gold, rum and
+// cannons, makes a few trades and prints what the pool holds after each
+// step. Run against a fresh local validator with the program deployed, the
+// pool is shared by every run and holds at most LiquidityPool::MAX_ASSETS.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let program_id = keypair::read_keypair_file("target/deploy/program-keypair.json")
+        .map_err(|e| anyhow!("{e}"))?.pubkey();
+
+    let client = RpcClient::new_with_commitment(
+        "http://localhost:8899".to_string(),
+        CommitmentConfig::confirmed(),
+    );
+
+    let payer = Keypair::new();
+
+    let airdrop_signature = client.request_airdrop(
+        &payer.pubkey(),
+        10 * LAMPORTS_PER_SOL,
+    ).await?;
+    client.poll_for_signature(&airdrop_signature).await?;
+
+    let gold = create_funded_mint(&client, &payer, 1_000_000 * ONE_TOKEN).await?;
+    let rum = create_funded_mint(&client, &payer, 1_000_000 * ONE_TOKEN).await?;
+    let cannons = create_funded_mint(&client, &payer, 1_000_000 * ONE_TOKEN).await?;
+
+    let assets = [("gold", gold), ("rum", rum), ("cannons", cannons)];
+
+    let pool = pool_address(&program_id);
+
+    println!("pool: {}", pool);
+
+    if client.get_account(&pool).await.is_err() {
+        send(&client, &payer, &[create_pool_ix(&program_id, &payer.pubkey(), FEE_BPS)?]).await?;
+    }
+
+    send(
+        &client,
+        &payer,
+        &[
+            fund_pool_ix(&program_id, &payer.pubkey(), &gold, 10_000 * ONE_TOKEN)?,
+            fund_pool_ix(&program_id, &payer.pubkey(), &rum, 4_000 * ONE_TOKEN)?,
+            fund_pool_ix(&program_id, &payer.pubkey(), &cannons, 500 * ONE_TOKEN)?,
+        ],
+    ).await?;
+
+    print_holdings(&client, &pool, &assets, "after funding").await?;
+
+    // 50 rum for gold
+    send(&client, &payer, &[swap_ix(&program_id, &payer.pubkey(), &gold, &rum, 50 * ONE_TOKEN, 0)?]).await?;
+
+    print_holdings(&client, &pool, &assets, "after rum for gold").await?;
+
+    // 1_000 gold for cannons
+    send(&client, &payer, &[swap_ix(&program_id, &payer.pubkey(), &cannons, &gold, 1_000 * ONE_TOKEN, 0)?]).await?;
+
+    print_holdings(&client, &pool, &assets, "after gold for cannons").await?;
+
+    // 10 cannons for rum
+    send(&client, &payer, &[swap_ix(&program_id, &payer.pubkey(), &rum, &cannons, 10 * ONE_TOKEN, 0)?]).await?;
+
+    print_holdings(&client, &pool, &assets, "after cannons for rum").await?;
+
+    Ok(())
+}
+
+// Creates a mint with the payer as authority and mints `amount` to the payer's ATA.
+async fn create_funded_mint(client: &RpcClient, payer: &Keypair, amount: u64) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer_ata = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let mint_rent = client.get_minimum_balance_for_rent_exemption(Mint::LEN).await?;
+
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(&payer.pubkey(), &mint.pubkey(), mint_rent, Mint::LEN as u64, &token_program_id()),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer.pubkey(), None, DECIMALS)?,
+            create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                &payer.pubkey(),
+                &mint.pubkey(),
+                &token_program_id(),
+            ),
+            mint_to(&token_program_id(), &mint.pubkey(), &payer_ata, &payer.pubkey(), &[], amount)?,
+        ],
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        recent_blockhash,
+    );
+
+    client.send_and_confirm_transaction_with_spinner(&tx).await?;
+
+    Ok(mint.pubkey())
+}
+
+async fn print_holdings(client: &RpcClient, pool: &Pubkey, assets: &[(&str, Pubkey)], label: &str) -> Result<()> {
+    println!("{label}:");
+
+    for (name, mint) in assets {
+        let pool_ata = client.get_account(&get_associated_token_address(pool, mint)).await?;
+        let amount = TokenAccount::unpack(&pool_ata.data)?.amount;
+
+        println!("  {:<8} {}", name, amount as f64 / ONE_TOKEN as f64);
+    }
+
+    Ok(())
+}
+
+async fn send(client: &RpcClient, payer: &Keypair, instructions: &[Instruction]) -> Result<()> {
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+
+    let tx_signature =
+        client.send_and_confirm_transaction_with_spinner(&tx).await?;
+
+    println!("tx signature: {}", tx_signature);
+
+    Ok(())
+}
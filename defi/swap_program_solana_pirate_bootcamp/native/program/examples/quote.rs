@@ -0,0 +1,147 @@
+use anyhow::{anyhow, Result};
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Signer, Keypair, keypair},
+    transaction::Transaction,
+};
+use solana_system_interface::instruction::create_account;
+use spl_associated_token_account::{
+    get_associated_token_address,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{
+    id as token_program_id,
+    instruction::{initialize_mint2, mint_to},
+    state::Mint,
+};
+
+use program::client::{create_pool_ix, fund_pool_ix, pool_address, quote_ix};
+
+const FEE_BPS: u16 = 30;
+const DECIMALS: u8 = 6;
+const ONE_TOKEN: u64 = 10u64.pow(DECIMALS as u32);
+
+// Funds the pool with two new assets and asks it what a swap between them
+// would pay, by simulating Quote and reading its return data. Nothing is
+// swapped. Run against a local validator with the program deployed.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let program_id = keypair::read_keypair_file("target/deploy/program-keypair.json")
+        .map_err(|e| anyhow!("{e}"))?.pubkey();
+
+    let client = RpcClient::new_with_commitment(
+        "http://localhost:8899".to_string(),
+        CommitmentConfig::confirmed(),
+    );
+
+    let payer = Keypair::new();
+
+    let airdrop_signature = client.request_airdrop(
+        &payer.pubkey(),
+        10 * LAMPORTS_PER_SOL,
+    ).await?;
+    client.poll_for_signature(&airdrop_signature).await?;
+
+    let gold = create_funded_mint(&client, &payer, 1_000_000 * ONE_TOKEN).await?;
+    let rum = create_funded_mint(&client, &payer, 1_000_000 * ONE_TOKEN).await?;
+
+    // there's only one pool per program, it may be there from an earlier run
+    if client.get_account(&pool_address(&program_id)).await.is_err() {
+        send(&client, &payer, &[create_pool_ix(&program_id, &payer.pubkey(), FEE_BPS)?]).await?;
+    }
+
+    // 1:2 gold to rum
+    send(
+        &client,
+        &payer,
+        &[
+            fund_pool_ix(&program_id, &payer.pubkey(), &gold, 10_000 * ONE_TOKEN)?,
+            fund_pool_ix(&program_id, &payer.pubkey(), &rum, 20_000 * ONE_TOKEN)?,
+        ],
+    ).await?;
+
+    let amount = 100 * ONE_TOKEN;
+    let quoted = simulate_quote(&client, &payer, quote_ix(&program_id, &rum, &gold, amount)?).await?;
+
+    println!("{} rum buys {} gold", amount, quoted);
+
+    Ok(())
+}
+
+async fn simulate_quote(client: &RpcClient, payer: &Keypair, instruction: Instruction) -> Result<u64> {
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+
+    let simulation = client.simulate_transaction(&tx).await?.value;
+
+    if let Some(err) = simulation.err {
+        return Err(anyhow!("quote failed: {err}"));
+    }
+
+    let return_data = simulation.return_data.ok_or(anyhow!("quote returned no data"))?;
+    let data = BASE64_STANDARD.decode(&return_data.data.0)?;
+
+    Ok(u64::from_le_bytes(data.as_slice().try_into()?))
+}
+
+async fn create_funded_mint(client: &RpcClient, payer: &Keypair, amount: u64) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer_ata = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let mint_rent = client.get_minimum_balance_for_rent_exemption(Mint::LEN).await?;
+
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(&payer.pubkey(), &mint.pubkey(), mint_rent, Mint::LEN as u64, &token_program_id()),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer.pubkey(), None, DECIMALS)?,
+            create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                &payer.pubkey(),
+                &mint.pubkey(),
+                &token_program_id(),
+            ),
+            mint_to(&token_program_id(), &mint.pubkey(), &payer_ata, &payer.pubkey(), &[], amount)?,
+        ],
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        recent_blockhash,
+    );
+
+    client.send_and_confirm_transaction_with_spinner(&tx).await?;
+
+    Ok(mint.pubkey())
+}
+
+async fn send(client: &RpcClient, payer: &Keypair, instructions: &[Instruction]) -> Result<()> {
+    let recent_blockhash = client.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+
+    let tx_signature =
+        client.send_and_confirm_transaction_with_spinner(&tx).await?;
+
+    println!("tx signature: {}", tx_signature);
+
+    Ok(())
+}
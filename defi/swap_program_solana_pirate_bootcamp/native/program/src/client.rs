@@ -0,0 +1,469 @@
+// Instruction builders for clients and tests. Each one derives the pool PDA
+// and ATAs and lists accounts in the order the processor reads them.
+
+use borsh::BorshSerialize;
+
+use solana_program::{
+    borsh1::try_from_slice_unchecked,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solana_system_interface::program::id as system_program_id;
+
+use spl_associated_token_account::{
+    get_associated_token_address,
+    id as associated_token_program_id,
+};
+use spl_token::id as token_program_id;
+
+use crate::{
+    instruction::{
+        CreatePoolPayload,
+        FundPayload,
+        SwapPayload,
+        SetPausedPayload,
+        RemoveAssetPayload,
+        WithdrawFundingPayload,
+        SetFeePayload,
+        QuotePayload,
+        SetWeightPayload,
+        SetPricingModePayload,
+        FundPoolBatchPayload,
+        RebalancePayload,
+        SetFunderAllowlistPayload,
+        FunderPayload,
+    },
+    state::{LiquidityPool, PoolAsset, PairPrice, AssetOracle, FunderAllowlist, ApprovedFunder, FundingReceipt, PricingMode, AssetList},
+};
+
+pub fn pool_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id).0
+}
+
+pub fn pool_asset_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[PoolAsset::SEED_PREFIX.as_bytes(), pool_address(program_id).as_ref(), mint.as_ref()],
+        program_id,
+    ).0
+}
+
+// Either order of the mints gives the same address.
+pub fn pair_price_address(program_id: &Pubkey, mint_x: &Pubkey, mint_y: &Pubkey) -> Pubkey {
+    let (mint_a, mint_b) = PairPrice::sort_mints(mint_x, mint_y);
+
+    Pubkey::find_program_address(
+        &[PairPrice::SEED_PREFIX.as_bytes(), pool_address(program_id).as_ref(), mint_a.as_ref(), mint_b.as_ref()],
+        program_id,
+    ).0
+}
+
+// Reads a PairPrice account's data, as fetched from an RPC node.
+pub fn decode_pair_price(data: &[u8]) -> Result<PairPrice, ProgramError> {
+    try_from_slice_unchecked::<PairPrice>(data).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+// Reads ListAssets' return data.
+pub fn decode_asset_list(data: &[u8]) -> Result<AssetList, ProgramError> {
+    try_from_slice_unchecked::<AssetList>(data).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+pub fn asset_oracle_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[AssetOracle::SEED_PREFIX.as_bytes(), pool_address(program_id).as_ref(), mint.as_ref()],
+        program_id,
+    ).0
+}
+
+pub fn funder_allowlist_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[FunderAllowlist::SEED_PREFIX.as_bytes(), pool_address(program_id).as_ref()],
+        program_id,
+    ).0
+}
+
+pub fn approved_funder_address(program_id: &Pubkey, funder: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[ApprovedFunder::SEED_PREFIX.as_bytes(), pool_address(program_id).as_ref(), funder.as_ref()],
+        program_id,
+    ).0
+}
+
+pub fn funding_receipt_address(program_id: &Pubkey, funder: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[FundingReceipt::SEED_PREFIX.as_bytes(), funder.as_ref(), mint.as_ref()],
+        program_id,
+    ).0
+}
+
+pub fn create_pool_ix(program_id: &Pubkey, payer: &Pubkey, fee_bps: u16) -> Result<Instruction, ProgramError> {
+    let mut data = vec![0];
+    CreatePoolPayload { fee_bps }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(pool_address(program_id), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn fund_pool_ix(program_id: &Pubkey, payer: &Pubkey, mint: &Pubkey, amount: u64) -> Result<Instruction, ProgramError> {
+    let pool = pool_address(program_id);
+
+    let mut data = vec![1];
+    FundPayload { amount }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(pool, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_associated_token_address(&pool, mint), false),
+            AccountMeta::new(get_associated_token_address(payer, mint), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new(funding_receipt_address(program_id, payer, mint), false),
+            AccountMeta::new(pool_asset_address(program_id, mint), false),
+            AccountMeta::new_readonly(funder_allowlist_address(program_id), false),
+            AccountMeta::new_readonly(approved_funder_address(program_id, payer), false),
+        ],
+    ))
+}
+
+// FundPool for every (mint, amount) in `funding`, in one instruction.
+pub fn fund_pool_batch_ix(program_id: &Pubkey, payer: &Pubkey, funding: &[(Pubkey, u64)]) -> Result<Instruction, ProgramError> {
+    let pool = pool_address(program_id);
+
+    let mut data = vec![12];
+    FundPoolBatchPayload { amounts: funding.iter().map(|(_, amount)| *amount).collect() }.serialize(&mut data)?;
+
+    let mut accounts = vec![
+        AccountMeta::new(pool, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program_id(), false),
+        AccountMeta::new_readonly(token_program_id(), false),
+        AccountMeta::new_readonly(associated_token_program_id(), false),
+        AccountMeta::new_readonly(funder_allowlist_address(program_id), false),
+        AccountMeta::new_readonly(approved_funder_address(program_id, payer), false),
+    ];
+
+    for (mint, _) in funding {
+        accounts.extend([
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_associated_token_address(&pool, mint), false),
+            AccountMeta::new(get_associated_token_address(payer, mint), false),
+            AccountMeta::new(funding_receipt_address(program_id, payer, mint), false),
+            AccountMeta::new(pool_asset_address(program_id, mint), false),
+        ]);
+    }
+
+    Ok(Instruction::new_with_bytes(*program_id, &data, accounts))
+}
+
+// Pays `amount_to_swap` of `pay_mint` for whatever the pool gives of
+// `receive_mint`, failing if that's less than `min_receive`, which 0 turns
+// off. The payer's ATA for `receive_mint` has to exist already.
+pub fn swap_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    receive_mint: &Pubkey,
+    pay_mint: &Pubkey,
+    amount_to_swap: u64,
+    min_receive: u64,
+) -> Result<Instruction, ProgramError> {
+    let pool = pool_address(program_id);
+
+    let mut data = vec![2];
+    SwapPayload { amount_to_swap, min_receive }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new_readonly(*receive_mint, false),
+            AccountMeta::new(get_associated_token_address(&pool, receive_mint), false),
+            AccountMeta::new(get_associated_token_address(payer, receive_mint), false),
+            AccountMeta::new_readonly(*pay_mint, false),
+            AccountMeta::new(get_associated_token_address(&pool, pay_mint), false),
+            AccountMeta::new(get_associated_token_address(payer, pay_mint), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new(pool_asset_address(program_id, receive_mint), false),
+            AccountMeta::new(pool_asset_address(program_id, pay_mint), false),
+            AccountMeta::new(pair_price_address(program_id, receive_mint, pay_mint), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn set_paused_ix(program_id: &Pubkey, admin: &Pubkey, paused: bool) -> Result<Instruction, ProgramError> {
+    let mut data = vec![3];
+    SetPausedPayload { paused }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(pool_address(program_id), false),
+        ],
+    ))
+}
+
+pub fn remove_asset_ix(program_id: &Pubkey, admin: &Pubkey, mint: &Pubkey) -> Result<Instruction, ProgramError> {
+    let pool = pool_address(program_id);
+
+    let mut data = vec![4];
+    RemoveAssetPayload { mint: *mint }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(pool, false),
+            AccountMeta::new(get_associated_token_address(&pool, mint), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new(pool_asset_address(program_id, mint), false),
+        ],
+    ))
+}
+
+pub fn withdraw_funding_ix(program_id: &Pubkey, funder: &Pubkey, mint: &Pubkey, amount: u64) -> Result<Instruction, ProgramError> {
+    let pool = pool_address(program_id);
+
+    let mut data = vec![5];
+    WithdrawFundingPayload { mint: *mint, amount }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*funder, true),
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_associated_token_address(&pool, mint), false),
+            AccountMeta::new(get_associated_token_address(funder, mint), false),
+            AccountMeta::new(funding_receipt_address(program_id, funder, mint), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+    ))
+}
+
+pub fn set_fee_ix(program_id: &Pubkey, admin: &Pubkey, fee_bps: u16) -> Result<Instruction, ProgramError> {
+    let mut data = vec![6];
+    SetFeePayload { fee_bps }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(pool_address(program_id), false),
+        ],
+    ))
+}
+
+// Simulate this and read the amount out of the return data.
+pub fn quote_ix(program_id: &Pubkey, pay_mint: &Pubkey, receive_mint: &Pubkey, amount: u64) -> Result<Instruction, ProgramError> {
+    let pool = pool_address(program_id);
+
+    let mut data = vec![7];
+    QuotePayload { pay_mint: *pay_mint, receive_mint: *receive_mint, amount }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new_readonly(get_associated_token_address(&pool, receive_mint), false),
+            AccountMeta::new_readonly(get_associated_token_address(&pool, pay_mint), false),
+            AccountMeta::new_readonly(pool_asset_address(program_id, receive_mint), false),
+            AccountMeta::new_readonly(pool_asset_address(program_id, pay_mint), false),
+        ],
+    ))
+}
+
+// `assets` is the pool's asset list from before the migration, in order,
+// and empty for a pool that already has PoolAsset accounts.
+pub fn migrate_pool_ix(program_id: &Pubkey, admin: &Pubkey, assets: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*admin, true),
+        AccountMeta::new(pool_address(program_id), false),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ];
+
+    accounts.extend(assets.iter().map(|mint| AccountMeta::new(pool_asset_address(program_id, mint), false)));
+
+    Instruction::new_with_bytes(*program_id, &[8], accounts)
+}
+
+pub fn set_weight_ix(program_id: &Pubkey, admin: &Pubkey, mint: &Pubkey, weight: u16) -> Result<Instruction, ProgramError> {
+    let mut data = vec![9];
+    SetWeightPayload { mint: *mint, weight }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(pool_address(program_id), false),
+            AccountMeta::new(pool_asset_address(program_id, mint), false),
+        ],
+    ))
+}
+
+pub fn set_pricing_mode_ix(program_id: &Pubkey, admin: &Pubkey, mode: PricingMode) -> Result<Instruction, ProgramError> {
+    let mut data = vec![10];
+    SetPricingModePayload { mode }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(pool_address(program_id), false),
+        ],
+    ))
+}
+
+pub fn set_asset_oracle_ix(program_id: &Pubkey, admin: &Pubkey, mint: &Pubkey, price_feed: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[11],
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(pool_address(program_id), false),
+            AccountMeta::new_readonly(pool_asset_address(program_id, mint), false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(asset_oracle_address(program_id, mint), false),
+            AccountMeta::new_readonly(*price_feed, false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    )
+}
+
+// What Swap and Quote take after their other accounts when the pool is
+// oracle-priced: each asset's AssetOracle and the price account it names.
+pub fn oracle_accounts(
+    program_id: &Pubkey,
+    (receive_mint, receive_price_feed): (&Pubkey, &Pubkey),
+    (pay_mint, pay_price_feed): (&Pubkey, &Pubkey),
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(asset_oracle_address(program_id, receive_mint), false),
+        AccountMeta::new_readonly(*receive_price_feed, false),
+        AccountMeta::new_readonly(asset_oracle_address(program_id, pay_mint), false),
+        AccountMeta::new_readonly(*pay_price_feed, false),
+    ]
+}
+
+// Trades `amount` of the pool's `from_mint` to the admin's ATA for
+// `to_mint` from the admin's ATA. Both ATAs have to exist already, and an
+// oracle-priced pool takes oracle_accounts for (from_mint, to_mint) after.
+pub fn rebalance_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    from_mint: &Pubkey,
+    to_mint: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let pool = pool_address(program_id);
+
+    let mut data = vec![13];
+    RebalancePayload { from_mint: *from_mint, to_mint: *to_mint, amount }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new_readonly(*from_mint, false),
+            AccountMeta::new(get_associated_token_address(&pool, from_mint), false),
+            AccountMeta::new(get_associated_token_address(admin, from_mint), false),
+            AccountMeta::new_readonly(*to_mint, false),
+            AccountMeta::new(get_associated_token_address(&pool, to_mint), false),
+            AccountMeta::new(get_associated_token_address(admin, to_mint), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(pool_asset_address(program_id, from_mint), false),
+            AccountMeta::new_readonly(pool_asset_address(program_id, to_mint), false),
+        ],
+    ))
+}
+
+// With the allowlist enabled, only funders added with add_funder_ix can fund
+// the pool.
+pub fn set_funder_allowlist_ix(program_id: &Pubkey, admin: &Pubkey, enabled: bool) -> Result<Instruction, ProgramError> {
+    let mut data = vec![14];
+    SetFunderAllowlistPayload { enabled }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(pool_address(program_id), false),
+            AccountMeta::new(funder_allowlist_address(program_id), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn add_funder_ix(program_id: &Pubkey, admin: &Pubkey, funder: &Pubkey) -> Result<Instruction, ProgramError> {
+    let mut data = vec![15];
+    FunderPayload { funder: *funder }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(pool_address(program_id), false),
+            AccountMeta::new(approved_funder_address(program_id, funder), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+    ))
+}
+
+pub fn remove_funder_ix(program_id: &Pubkey, admin: &Pubkey, funder: &Pubkey) -> Result<Instruction, ProgramError> {
+    let mut data = vec![16];
+    FunderPayload { funder: *funder }.serialize(&mut data)?;
+
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(pool_address(program_id), false),
+            AccountMeta::new(approved_funder_address(program_id, funder), false),
+        ],
+    ))
+}
+
+// Simulate this and read the pool's balance of each of `mints` out of the
+// return data with decode_asset_list. The mints can be any of the pool's,
+// up to AssetList::MAX_PAGE_ASSETS of them.
+pub fn list_assets_ix(program_id: &Pubkey, mints: &[Pubkey]) -> Instruction {
+    let pool = pool_address(program_id);
+
+    let mut accounts = vec![AccountMeta::new_readonly(pool, false)];
+
+    for mint in mints {
+        accounts.extend([
+            AccountMeta::new_readonly(pool_asset_address(program_id, mint), false),
+            AccountMeta::new_readonly(get_associated_token_address(&pool, mint), false),
+        ]);
+    }
+
+    Instruction::new_with_bytes(*program_id, &[17], accounts)
+}
@@ -10,6 +10,66 @@ pub enum SwapProgramError {
     // error 1
     #[error("")]
     InvalidSwapMatchingAssets,
+    // error 2
+    #[error("")]
+    InvalidSwapNotEnoughLiquidity,
+    // error 3
+    #[error("")]
+    PoolPaused,
+    // error 4
+    #[error("")]
+    NotPoolAdmin,
+    // error 5
+    #[error("")]
+    AssetNotInPool,
+    // error 6
+    #[error("")]
+    PoolAssetNotEmpty,
+    // error 7
+    #[error("")]
+    WithdrawExceedsReceipt,
+    // error 8
+    #[error("")]
+    InvalidFee,
+    // error 9
+    #[error("")]
+    PoolAssetLimitReached,
+    // error 10
+    #[error("")]
+    InvalidMintOwner,
+    // error 11
+    #[error("")]
+    InvalidTokenProgram,
+    // error 12
+    #[error("")]
+    PoolNeedsMigration,
+    // error 13
+    #[error("")]
+    PoolAlreadyCurrent,
+    // error 14
+    #[error("")]
+    InvalidWeight,
+    // error 15
+    #[error("")]
+    InvalidOracle,
+    // error 16
+    #[error("")]
+    OracleStale,
+    // error 17
+    #[error("")]
+    OracleConfidenceTooWide,
+    // error 18
+    #[error("")]
+    OracleMismatch,
+    // error 19
+    #[error("")]
+    OracleNotSet,
+    // error 20
+    #[error("")]
+    SlippageExceeded,
+    // error 21
+    #[error("")]
+    FunderNotApproved,
 }
 
 impl From<SwapProgramError> for ProgramError {
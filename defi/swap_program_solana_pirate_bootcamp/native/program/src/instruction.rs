@@ -1,15 +1,68 @@
-use solana_program::program_error::ProgramError;
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
-use borsh::BorshDeserialize;
+use borsh::{BorshSerialize, BorshDeserialize};
+
+use crate::state::PricingMode;
 
 pub enum SwapInstruction {
-    CreatePool,
+    CreatePool {
+        fee_bps: u16,
+    },
     FundPool {
         amount: u64,
     },
     Swap {
         amount_to_swap: u64,
-    }
+        min_receive: u64,
+    },
+    SetPaused {
+        paused: bool,
+    },
+    RemoveAsset {
+        mint: Pubkey,
+    },
+    WithdrawFunding {
+        mint: Pubkey,
+        amount: u64,
+    },
+    SetFee {
+        fee_bps: u16,
+    },
+    Quote {
+        pay_mint: Pubkey,
+        receive_mint: Pubkey,
+        amount: u64,
+    },
+    MigratePool,
+    SetWeight {
+        mint: Pubkey,
+        weight: u16,
+    },
+    SetPricingMode {
+        mode: PricingMode,
+    },
+    SetAssetOracle,
+    FundPoolBatch {
+        amounts: Vec<u64>,
+    },
+    Rebalance {
+        from_mint: Pubkey,
+        to_mint: Pubkey,
+        amount: u64,
+    },
+    SetFunderAllowlist {
+        enabled: bool,
+    },
+    AddFunder {
+        funder: Pubkey,
+    },
+    RemoveFunder {
+        funder: Pubkey,
+    },
+    ListAssets,
 }
 
 impl SwapInstruction {
@@ -20,7 +73,12 @@ impl SwapInstruction {
         Ok(
             match discriminator {
                 0 => {
-                    Self::CreatePool
+                    let payload = CreatePoolPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::CreatePool { 
+                        fee_bps: payload.fee_bps 
+                    }
                 },
                 1 => {
                     let payload = FundPayload::try_from_slice(rest)
@@ -35,9 +93,115 @@ impl SwapInstruction {
                         .map_err(|_| ProgramError::InvalidInstructionData)?;
 
                     Self::Swap { 
-                        amount_to_swap: payload.amount_to_swap 
+                        amount_to_swap: payload.amount_to_swap, 
+                        min_receive: payload.min_receive,
+                    }
+                },
+                3 => {
+                    let payload = SetPausedPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetPaused { 
+                        paused: payload.paused 
+                    }
+                },
+                4 => {
+                    let payload = RemoveAssetPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::RemoveAsset { 
+                        mint: payload.mint 
+                    }
+                },
+                5 => {
+                    let payload = WithdrawFundingPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::WithdrawFunding { 
+                        mint: payload.mint, 
+                        amount: payload.amount,
+                    }
+                },
+                6 => {
+                    let payload = SetFeePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetFee { 
+                        fee_bps: payload.fee_bps 
+                    }
+                },
+                7 => {
+                    let payload = QuotePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::Quote { 
+                        pay_mint: payload.pay_mint, 
+                        receive_mint: payload.receive_mint, 
+                        amount: payload.amount,
+                    }
+                },
+                8 => Self::MigratePool,
+                9 => {
+                    let payload = SetWeightPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetWeight { 
+                        mint: payload.mint, 
+                        weight: payload.weight,
+                    }
+                },
+                10 => {
+                    let payload = SetPricingModePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetPricingMode { 
+                        mode: payload.mode 
+                    }
+                },
+                11 => Self::SetAssetOracle,
+                12 => {
+                    let payload = FundPoolBatchPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::FundPoolBatch { 
+                        amounts: payload.amounts 
+                    }
+                },
+                13 => {
+                    let payload = RebalancePayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::Rebalance { 
+                        from_mint: payload.from_mint, 
+                        to_mint: payload.to_mint, 
+                        amount: payload.amount,
+                    }
+                },
+                14 => {
+                    let payload = SetFunderAllowlistPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::SetFunderAllowlist { 
+                        enabled: payload.enabled 
+                    }
+                },
+                15 => {
+                    let payload = FunderPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::AddFunder { 
+                        funder: payload.funder 
                     }
                 },
+                16 => {
+                    let payload = FunderPayload::try_from_slice(rest)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                    Self::RemoveFunder { 
+                        funder: payload.funder 
+                    }
+                },
+                17 => Self::ListAssets,
 
                 _ => return Err(ProgramError::InvalidInstructionData)
             }
@@ -45,12 +209,79 @@ impl SwapInstruction {
     } 
 }
 
-#[derive(BorshDeserialize)]
-struct FundPayload {
-    amount: u64,
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct CreatePoolPayload {
+    pub(crate) fee_bps: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct FundPayload {
+    pub(crate) amount: u64,
 }
 
-#[derive(BorshDeserialize)]
-struct SwapPayload {
-    amount_to_swap: u64,
-}
\ No newline at end of file
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SwapPayload {
+    pub(crate) amount_to_swap: u64,
+    pub(crate) min_receive: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetPausedPayload {
+    pub(crate) paused: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct RemoveAssetPayload {
+    pub(crate) mint: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct WithdrawFundingPayload {
+    pub(crate) mint: Pubkey,
+    pub(crate) amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetFeePayload {
+    pub(crate) fee_bps: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct QuotePayload {
+    pub(crate) pay_mint: Pubkey,
+    pub(crate) receive_mint: Pubkey,
+    pub(crate) amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetWeightPayload {
+    pub(crate) mint: Pubkey,
+    pub(crate) weight: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetPricingModePayload {
+    pub(crate) mode: PricingMode,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct FundPoolBatchPayload {
+    pub(crate) amounts: Vec<u64>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct RebalancePayload {
+    pub(crate) from_mint: Pubkey,
+    pub(crate) to_mint: Pubkey,
+    pub(crate) amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct SetFunderAllowlistPayload {
+    pub(crate) enabled: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct FunderPayload {
+    pub(crate) funder: Pubkey,
+}
@@ -2,4 +2,9 @@ pub mod entrypoint;
 pub mod processor;
 pub mod instruction;
 pub mod state;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod math;
+pub mod pyth;
+
+#[cfg(feature = "client")]
+pub mod client;
\ No newline at end of file
@@ -0,0 +1,170 @@
+// How much of the receive asset `pay_amount` of the pay asset buys:
+// r = R * p / (P + p), where R and P are the pool's balances of the receive
+// and pay assets. Both sides are in their own base units, so the mints'
+// decimals cancel out of the ratio.
+pub fn swap_receive_amount(pool_receive_balance: u64, pool_pay_balance: u64, pay_amount: u64) -> Option<u64> {
+    let numerator = (pool_receive_balance as u128).checked_mul(pay_amount as u128)?;
+    let denominator = (pool_pay_balance as u128).checked_add(pay_amount as u128)?;
+
+    if denominator == 0 {
+        return None;
+    }
+
+    // never more than R, so it fits back in a u64
+    Some((numerator / denominator) as u64)
+}
+
+// swap_receive_amount for assets of different weights, keeping Balancer's
+// invariant R^Wr * P^Wp constant: r = R * (1 - (P / (P + p))^(Wp / Wr)).
+// A heavier asset buys more and costs more. Equal weights cancel out to the
+// unweighted formula. The power is estimated, then rounded up by its error
+// bound, so r never comes out above the exact value.
+pub fn weighted_swap_receive_amount(
+    pool_receive_balance: u64,
+    pool_pay_balance: u64,
+    pay_amount: u64,
+    receive_weight: u16,
+    pay_weight: u16,
+) -> Option<u64> {
+    if receive_weight == pay_weight {
+        return swap_receive_amount(pool_receive_balance, pool_pay_balance, pay_amount);
+    }
+
+    let (_, power) = pow_ratio(
+        pool_pay_balance as u128,
+        pool_pay_balance as u128 + pay_amount as u128,
+        pay_weight,
+        receive_weight,
+    )?;
+
+    // never more than R, so it fits back in a u64
+    Some(((pool_receive_balance as u128 * (ONE - power)) >> 64) as u64)
+}
+
+// The inverse of weighted_swap_receive_amount: how much of the pay asset
+// takes `receive_amount` of the receive asset out of the pool,
+// p = P * ((R / (R - r))^(Wr / Wp) - 1), rounded up along with the power.
+// None if that's all of R or more.
+pub fn weighted_swap_pay_amount(
+    pool_receive_balance: u64,
+    pool_pay_balance: u64,
+    receive_amount: u64,
+    receive_weight: u16,
+    pay_weight: u16,
+) -> Option<u64> {
+    let remaining = pool_receive_balance.checked_sub(receive_amount).filter(|remaining| *remaining > 0)?;
+
+    if receive_weight == pay_weight {
+        let numerator = (receive_amount as u128).checked_mul(pool_pay_balance as u128)?;
+
+        return u64::try_from(numerator.div_ceil(remaining as u128)).ok();
+    }
+
+    // (R - r) / R to the power, the reciprocal of the one above, rounded down
+    let (power, _) = pow_ratio(remaining as u128, pool_receive_balance as u128, receive_weight, pay_weight)?;
+
+    if power == 0 {
+        return None;
+    }
+
+    u64::try_from((pool_pay_balance as u128 * (ONE - power)).div_ceil(power)).ok()
+}
+
+// Fixed point with 64 fractional bits, for the weighted powers.
+const ONE: u128 = 1 << 64;
+
+// ln(2) in the same fixed point, rounded down.
+const LN_2: u128 = 0xB172_17F7_D1CF_79AB;
+
+// pow_ratio's estimate is within 2^-37 of the exact power, relative to it,
+// and a few units; the bounds it returns allow 2^-32 and 64 units.
+const POW_RELATIVE_ERROR_SHIFT: u32 = 32;
+const POW_ABSOLUTE_ERROR: u128 = 64;
+
+// Lower and upper bounds on (low / high)^(numerator / denominator) in fixed
+// point, for 0 < low <= high, by way of 2^(-log2(high / low) * exponent).
+fn pow_ratio(low: u128, high: u128, numerator: u16, denominator: u16) -> Option<(u128, u128)> {
+    if low == 0 || denominator == 0 {
+        return None;
+    }
+
+    let exponent = log2(high).saturating_sub(log2(low)) * numerator as u128 / denominator as u128;
+
+    let estimate = exp2_neg(exponent);
+    let error = (estimate >> POW_RELATIVE_ERROR_SHIFT) + POW_ABSOLUTE_ERROR;
+
+    Some((estimate.saturating_sub(error), estimate.saturating_add(error).min(ONE)))
+}
+
+// log2(n) in fixed point for n >= 1. The integer part is the top bit; each
+// fractional bit is set if squaring the mantissa takes it past 2.
+fn log2(n: u128) -> u128 {
+    let integer = 127 - n.leading_zeros();
+
+    // in [1, 2) with 63 fractional bits, so that its square fits
+    let mut mantissa = if integer > 63 { n >> (integer - 63) } else { n << (63 - integer) };
+    let mut log = (integer as u128) << 64;
+
+    for bit in (0..64).rev() {
+        mantissa = (mantissa * mantissa) >> 63;
+
+        if mantissa >= 1 << 64 {
+            mantissa >>= 1;
+            log |= 1 << bit;
+        }
+    }
+
+    log
+}
+
+// 2^-y for y >= 0 in fixed point. The integer part of y is a shift, the
+// fractional part f is e^(-f ln 2) as a Taylor series, summed until its
+// terms run out of bits.
+fn exp2_neg(y: u128) -> u128 {
+    let shift = y >> 64;
+
+    if shift >= 64 {
+        return 0;
+    }
+
+    let t = ((y & (ONE - 1)) * LN_2) >> 64;
+
+    let mut sum = ONE;
+    let mut term = ONE;
+    let mut k = 1;
+    let mut negative = true;
+
+    while term > 0 {
+        term = ((term * t) >> 64) / k;
+
+        // t < 1, so the terms shrink and the sum stays between 1/2 and 1
+        if negative {
+            sum -= term;
+        } else {
+            sum += term;
+        }
+
+        k += 1;
+        negative = !negative;
+    }
+
+    sum >> shift
+}
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// `fee_bps` of `amount`, rounded down.
+pub fn fee_amount(amount: u64, fee_bps: u16) -> Option<u64> {
+    let fee = (amount as u128).checked_mul(fee_bps as u128)? / BPS_DENOMINATOR as u128;
+
+    u64::try_from(fee).ok()
+}
+
+// Fixed-point scale of a stored price.
+pub const PRICE_SCALE: u128 = 1_000_000_000;
+
+// Base units of B per base unit of A when `amount_a` traded for `amount_b`,
+// scaled by PRICE_SCALE and rounded down.
+pub fn executed_price(amount_a: u64, amount_b: u64) -> Option<u128> {
+    (amount_b as u128).checked_mul(PRICE_SCALE)?.checked_div(amount_a as u128)
+}
@@ -4,30 +4,57 @@ use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult,
     program_error::ProgramError,
-    sysvar::{Sysvar, rent::Rent},
-    program::{invoke, invoke_signed},
+    sysvar::{Sysvar, clock::Clock, rent::Rent},
+    program::{invoke, invoke_signed, set_return_data},
     borsh1::try_from_slice_unchecked,
     program_pack::Pack,
 };
-use solana_system_interface::instruction::{
-    create_account,
-    transfer,
+use solana_system_interface::{
+    instruction::{create_account, transfer},
+    program::id as system_program_id,
 };
 use spl_associated_token_account::{
     get_associated_token_address,
     instruction::create_associated_token_account_idempotent,
 };
 use spl_token::{
-    instruction::transfer_checked,
-    state::Mint,
+    id as token_program_id,
+    instruction::{close_account, transfer_checked},
+    state::{Account as TokenAccount, Mint},
 };
 
 use crate::{
     instruction::SwapInstruction,
-    state::LiquidityPool,
+    state::{
+        LiquidityPool,
+        LiquidityPoolV1,
+        LiquidityPoolV2,
+        PricingMode,
+        PoolAsset,
+        PairPrice,
+        AssetOracle,
+        FunderAllowlist,
+        ApprovedFunder,
+        FundingReceipt,
+        AssetList,
+        AssetBalance,
+    },
     error::SwapProgramError,
+    math::{
+        weighted_swap_receive_amount,
+        weighted_swap_pay_amount,
+        fee_amount,
+        executed_price,
+        BPS_DENOMINATOR,
+    },
+    pyth::{PythPrice, oracle_receive_amount, oracle_pay_amount},
 };
 
+// Accounts per mint in FundPoolBatch, see process_fund_pool_batch.
+pub const FUND_POOL_BATCH_ASSET_ACCOUNTS: usize = 5;
+
+// Accounts per asset in ListAssets, see process_list_assets.
+pub const LIST_ASSETS_ASSET_ACCOUNTS: usize = 2;
 
 pub fn process_instruction(
     program_id: &Pubkey,
@@ -37,21 +64,67 @@ pub fn process_instruction(
     let instruction = SwapInstruction::unpack(instruction_data)?;
 
     match instruction {
-        SwapInstruction::CreatePool => {
-            process_create_pool(program_id, accounts)
+        SwapInstruction::CreatePool { fee_bps } => {
+            process_create_pool(program_id, accounts, fee_bps)
         },
         SwapInstruction::FundPool { amount } => {
             process_fund_pool(program_id, accounts, amount)
         },
-        SwapInstruction::Swap { amount_to_swap } => {
-            process_swap(program_id, accounts, amount_to_swap)
-        }
+        SwapInstruction::Swap { amount_to_swap, min_receive } => {
+            process_swap(program_id, accounts, amount_to_swap, min_receive)
+        },
+        SwapInstruction::SetPaused { paused } => {
+            process_set_paused(program_id, accounts, paused)
+        },
+        SwapInstruction::RemoveAsset { mint } => {
+            process_remove_asset(program_id, accounts, mint)
+        },
+        SwapInstruction::WithdrawFunding { mint, amount } => {
+            process_withdraw_funding(program_id, accounts, mint, amount)
+        },
+        SwapInstruction::SetFee { fee_bps } => {
+            process_set_fee(program_id, accounts, fee_bps)
+        },
+        SwapInstruction::Quote { pay_mint, receive_mint, amount } => {
+            process_quote(program_id, accounts, pay_mint, receive_mint, amount)
+        },
+        SwapInstruction::MigratePool => {
+            process_migrate_pool(program_id, accounts)
+        },
+        SwapInstruction::SetWeight { mint, weight } => {
+            process_set_weight(program_id, accounts, mint, weight)
+        },
+        SwapInstruction::SetPricingMode { mode } => {
+            process_set_pricing_mode(program_id, accounts, mode)
+        },
+        SwapInstruction::SetAssetOracle => {
+            process_set_asset_oracle(program_id, accounts)
+        },
+        SwapInstruction::FundPoolBatch { amounts } => {
+            process_fund_pool_batch(program_id, accounts, amounts)
+        },
+        SwapInstruction::Rebalance { from_mint, to_mint, amount } => {
+            process_rebalance(program_id, accounts, from_mint, to_mint, amount)
+        },
+        SwapInstruction::SetFunderAllowlist { enabled } => {
+            process_set_funder_allowlist(program_id, accounts, enabled)
+        },
+        SwapInstruction::AddFunder { funder } => {
+            process_add_funder(program_id, accounts, funder)
+        },
+        SwapInstruction::RemoveFunder { funder } => {
+            process_remove_funder(program_id, accounts, funder)
+        },
+        SwapInstruction::ListAssets => {
+            process_list_assets(program_id, accounts)
+        },
     }
 }
 
 pub fn process_create_pool(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    fee_bps: u16,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -59,6 +132,10 @@ pub fn process_create_pool(
     let payer = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
 
+    if fee_bps as u64 > BPS_DENOMINATOR {
+        return Err(SwapProgramError::InvalidFee.into());
+    }
+
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
         &[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
 
@@ -84,13 +161,15 @@ pub fn process_create_pool(
         ]
     )?;
 
-    let mut pool_data = 
-        try_from_slice_unchecked::<LiquidityPool>(&pool.data.borrow())?;
-
-    pool_data.assets = vec![];
-    pool_data.bump = pool_bump;
-
-    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+    LiquidityPool {
+        version: LiquidityPool::VERSION,
+        admin: *payer.key,
+        paused: false,
+        fee_bps,
+        asset_count: 0,
+        pricing: PricingMode::Balances,
+        bump: pool_bump,
+    }.serialize(&mut &mut pool.data.borrow_mut()[..])?;
 
     Ok(())
 }
@@ -110,14 +189,103 @@ pub fn process_fund_pool(
     let system_program = next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
     let associated_token_program = next_account_info(accounts_iter)?;
+    let receipt = next_account_info(accounts_iter)?;
+    let pool_asset = next_account_info(accounts_iter)?;
+    let allowlist = next_account_info(accounts_iter)?;
+    let approved_funder = next_account_info(accounts_iter)?;
 
-    let (pool_pda, pool_bump) = Pubkey::find_program_address
-        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+    let mut pool_data = load_pool(program_id, pool)?;
 
-    if *pool.key != pool_pda {
-        return Err(ProgramError::InvalidSeeds);
+    if *token_program.key != token_program_id() {
+        return Err(SwapProgramError::InvalidTokenProgram.into());
+    }
+
+    if pool_data.paused {
+        return Err(SwapProgramError::PoolPaused.into());
+    }
+
+    check_funder(program_id, pool, payer, allowlist, approved_funder)?;
+
+    fund_asset(
+        program_id, 
+        (pool, &mut pool_data), 
+        (payer, system_program, token_program), 
+        [mint, pool_ata, payer_ata, receipt, pool_asset], 
+        amount,
+    )?;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// FundPool for several mints at once, each amount going with the mint in the
+// same place among the remaining accounts, which come in groups of
+// FUND_POOL_BATCH_ASSET_ACCOUNTS: mint, pool ATA, payer ATA, funding receipt
+// and PoolAsset.
+pub fn process_fund_pool_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amounts: Vec<u64>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let pool = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let _associated_token_program = next_account_info(accounts_iter)?;
+    let allowlist = next_account_info(accounts_iter)?;
+    let approved_funder = next_account_info(accounts_iter)?;
+
+    let asset_accounts = accounts_iter.as_slice();
+
+    if amounts.is_empty() || asset_accounts.len() != amounts.len() * FUND_POOL_BATCH_ASSET_ACCOUNTS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut pool_data = load_pool(program_id, pool)?;
+
+    if *token_program.key != token_program_id() {
+        return Err(SwapProgramError::InvalidTokenProgram.into());
+    }
+
+    if pool_data.paused {
+        return Err(SwapProgramError::PoolPaused.into());
+    }
+
+    check_funder(program_id, pool, payer, allowlist, approved_funder)?;
+
+    for (accounts, amount) in asset_accounts.chunks(FUND_POOL_BATCH_ASSET_ACCOUNTS).zip(amounts) {
+        let [mint, pool_ata, payer_ata, receipt, pool_asset] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        fund_asset(
+            program_id, 
+            (pool, &mut pool_data), 
+            (payer, system_program, token_program), 
+            [mint, pool_ata, payer_ata, receipt, pool_asset], 
+            amount,
+        )?;
     }
 
+    // the new asset count is written once for the whole batch
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Moves `amount` of `mint` from the payer into the pool, creating the pool's
+// ATA, the mint's PoolAsset and the payer's FundingReceipt on the first
+// funding. A new asset is counted in `pool_data`, which the caller writes.
+fn fund_asset<'a>(
+    program_id: &Pubkey,
+    (pool, pool_data): (&AccountInfo<'a>, &mut LiquidityPool),
+    (payer, system_program, token_program): (&AccountInfo<'a>, &AccountInfo<'a>, &AccountInfo<'a>),
+    [mint, pool_ata, payer_ata, receipt, pool_asset]: [&AccountInfo<'a>; FUND_POOL_BATCH_ASSET_ACCOUNTS],
+    amount: u64,
+) -> ProgramResult {
     if *pool_ata.key != get_associated_token_address(pool.key, mint.key) {
         return Err(ProgramError::InvalidSeeds);
     }
@@ -126,6 +294,24 @@ pub fn process_fund_pool(
         return Err(ProgramError::InvalidSeeds);
     }
 
+    let (receipt_pda, receipt_bump) = Pubkey::find_program_address(
+        &[FundingReceipt::SEED_PREFIX.as_bytes(), payer.key.as_ref(), mint.key.as_ref()], program_id);
+
+    if *receipt.key != receipt_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (pool_asset_pda, pool_asset_bump) = Pubkey::find_program_address(
+        &[PoolAsset::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint.key.as_ref()], program_id);
+
+    if *pool_asset.key != pool_asset_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *mint.owner != token_program_id() {
+        return Err(SwapProgramError::InvalidMintOwner.into());
+    }
+
     invoke(
         &create_associated_token_account_idempotent(
             payer.key, 
@@ -133,32 +319,42 @@ pub fn process_fund_pool(
             mint.key, 
             token_program.key,
         ), 
-        &[payer.clone(), pool.clone(), mint.clone(), token_program.clone()], 
+        &[payer.clone(), pool_ata.clone(), pool.clone(), mint.clone(), system_program.clone(), token_program.clone()], 
     )?;
 
-    let mut pool_data = try_from_slice_unchecked::<LiquidityPool>(&pool.data.borrow())?;
-
-    if !pool_data.assets.contains(mint.key) {
-        let rent = Rent::get()?;
-
-        let new_account_size = pool.data_len() + 32;
+    // first funding of this mint
+    if pool_asset.data_is_empty() {
+        if pool_data.asset_count as usize >= LiquidityPool::MAX_ASSETS {
+            return Err(SwapProgramError::PoolAssetLimitReached.into());
+        }
 
-        let lamports_required = rent.minimum_balance(new_account_size);
-        let additional_rent_to_fund = lamports_required - pool.lamports();
+        let pool_asset_rent = Rent::get()?.minimum_balance(PoolAsset::SPACE);
 
-        invoke(
-            &transfer(
+        invoke_signed(
+            &create_account(
                 payer.key, 
-                pool.key, 
-                additional_rent_to_fund,
+                pool_asset.key, 
+                pool_asset_rent, 
+                PoolAsset::SPACE as u64, 
+                program_id,
             ), 
-            &[payer.clone(), pool.clone()],
+            &[payer.clone(), pool_asset.clone(), system_program.clone()],
+            &[
+                &[PoolAsset::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint.key.as_ref(), &[pool_asset_bump]]
+            ]
         )?;
 
-        pool.resize(new_account_size)?;
+        PoolAsset {
+            mint: *mint.key,
+            collected_fees: 0,
+            pay_volume: 0,
+            receive_volume: 0,
+            weight: PoolAsset::DEFAULT_WEIGHT,
+            bump: pool_asset_bump,
+        }.serialize(&mut &mut pool_asset.data.borrow_mut()[..])?;
 
-        pool_data.assets.push(*mint.key);
-    } 
+        pool_data.asset_count += 1;
+    }
 
     let mint_data = Mint::unpack(&mint.data.borrow())?;
 
@@ -176,13 +372,48 @@ pub fn process_fund_pool(
         &[token_program.clone(), payer_ata.clone(), mint.clone(), pool_ata.clone(), payer.clone()],
     )?;
 
+    // first contribution of this mint from this funder
+    if receipt.data_is_empty() {
+        let receipt_rent = Rent::get()?.minimum_balance(FundingReceipt::SPACE);
+
+        invoke_signed(
+            &create_account(
+                payer.key, 
+                receipt.key, 
+                receipt_rent, 
+                FundingReceipt::SPACE as u64, 
+                program_id,
+            ), 
+            &[payer.clone(), receipt.clone(), system_program.clone()],
+            &[
+                &[FundingReceipt::SEED_PREFIX.as_bytes(), payer.key.as_ref(), mint.key.as_ref(), &[receipt_bump]]
+            ]
+        )?;
+
+        FundingReceipt {
+            funder: *payer.key,
+            mint: *mint.key,
+            amount: 0,
+            bump: receipt_bump,
+        }.serialize(&mut &mut receipt.data.borrow_mut()[..])?;
+    }
+
+    let mut receipt_data = try_from_slice_unchecked::<FundingReceipt>(&receipt.data.borrow())?;
+
+    receipt_data.amount = receipt_data.amount
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    receipt_data.serialize(&mut &mut receipt.data.borrow_mut()[..])?;
+
     Ok(())
 }
 
 pub fn process_swap(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    amount_to_swap: u64
+    amount_to_swap: u64,
+    min_receive: u64,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -196,9 +427,12 @@ pub fn process_swap(
     let payer = next_account_info(accounts_iter)?;
     let token_program = next_account_info(accounts_iter)?;
     let associated_token_program = next_account_info(accounts_iter)?;
+    let receive_asset = next_account_info(accounts_iter)?;
+    let pay_asset = next_account_info(accounts_iter)?;
+    let pair_price = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
 
-    let (pool_pda, pool_bump) = Pubkey::find_program_address
-        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+    let pool_data = load_pool(program_id, pool)?;
 
     if *pool_receive_ata.key != get_associated_token_address(pool.key, receive_mint.key) {
         return Err(ProgramError::InvalidSeeds);
@@ -216,6 +450,23 @@ pub fn process_swap(
         return Err(ProgramError::InvalidSeeds);
     }
 
+    if *token_program.key != token_program_id() {
+        return Err(SwapProgramError::InvalidTokenProgram.into());
+    }
+
+    if *receive_mint.owner != token_program_id() || *pay_mint.owner != token_program_id() {
+        return Err(SwapProgramError::InvalidMintOwner.into());
+    }
+
+    let (mint_a, mint_b) = PairPrice::sort_mints(receive_mint.key, pay_mint.key);
+
+    let (pair_price_pda, pair_price_bump) = Pubkey::find_program_address(
+        &[PairPrice::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint_a.as_ref(), mint_b.as_ref()], program_id);
+
+    if *pair_price.key != pair_price_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
     if amount_to_swap == 0 {
         return Err(SwapProgramError::InvalidSwapZeroAmount.into());
     }
@@ -224,6 +475,1179 @@ pub fn process_swap(
         return Err(SwapProgramError::InvalidSwapMatchingAssets.into());
     }
 
+    if pool_data.paused {
+        return Err(SwapProgramError::PoolPaused.into());
+    }
+
+    let mut receive_asset_data = load_pool_asset(program_id, pool, receive_asset, receive_mint.key)?;
+    let mut pay_asset_data = load_pool_asset(program_id, pool, pay_asset, pay_mint.key)?;
+
+    let oracle_prices = load_oracle_prices(
+        program_id, 
+        pool, 
+        &pool_data, 
+        accounts_iter, 
+        receive_mint.key, 
+        pay_mint.key,
+    )?;
+
+    let (amount_to_receive, fee) = swap_output(
+        &pool_data, 
+        (&receive_asset_data, pool_receive_ata), 
+        (&pay_asset_data, pool_pay_ata), 
+        oracle_prices,
+        amount_to_swap,
+    )?;
+
+    // the pool moved, or the oracle did, since the payer priced the swap
+    if amount_to_receive < min_receive {
+        return Err(SwapProgramError::SlippageExceeded.into());
+    }
+
+    let pay_mint_data = Mint::unpack(&pay_mint.data.borrow())?;
+    let receive_mint_data = Mint::unpack(&receive_mint.data.borrow())?;
+
+    // transfer amount_to_swap from payer_pay_ata to pool_pay_ata
+    invoke(
+        &transfer_checked(
+            token_program.key, 
+            payer_pay_ata.key, 
+            pay_mint.key, 
+            pool_pay_ata.key, 
+            payer.key, 
+            &[], 
+            amount_to_swap, 
+            pay_mint_data.decimals,
+        )?, 
+        &[token_program.clone(), payer_pay_ata.clone(), pay_mint.clone(), pool_pay_ata.clone(), payer.clone()],
+    )?;
+
+    // transfer amount_to_receive from pool_receive_ata to payer_receive_ata
+    invoke_signed(
+        &transfer_checked(
+            token_program.key, 
+            pool_receive_ata.key, 
+            receive_mint.key, 
+            payer_receive_ata.key, 
+            pool.key, 
+            &[], 
+            amount_to_receive, 
+            receive_mint_data.decimals,
+        )?, 
+        &[token_program.clone(), pool_receive_ata.clone(), receive_mint.clone(), payer_receive_ata.clone(), pool.clone()],
+        &[
+            &[LiquidityPool::SEED_PREFIX.as_bytes(), &[pool_data.bump]]
+        ]
+    )?;
+
+    receive_asset_data.collected_fees = receive_asset_data.collected_fees
+        .checked_add(fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    receive_asset_data.receive_volume = receive_asset_data.receive_volume
+        .checked_add(amount_to_receive as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    pay_asset_data.pay_volume = pay_asset_data.pay_volume
+        .checked_add(amount_to_swap as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    receive_asset_data.serialize(&mut &mut receive_asset.data.borrow_mut()[..])?;
+    pay_asset_data.serialize(&mut &mut pay_asset.data.borrow_mut()[..])?;
+
+    // first swap between these two assets
+    if pair_price.data_is_empty() {
+        let pair_price_rent = Rent::get()?.minimum_balance(PairPrice::SPACE);
+
+        invoke_signed(
+            &create_account(
+                payer.key, 
+                pair_price.key, 
+                pair_price_rent, 
+                PairPrice::SPACE as u64, 
+                program_id,
+            ), 
+            &[payer.clone(), pair_price.clone(), system_program.clone()],
+            &[
+                &[PairPrice::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint_a.as_ref(), mint_b.as_ref(), &[pair_price_bump]]
+            ]
+        )?;
+    }
+
+    let (amount_a, amount_b) = if pay_mint.key == mint_a {
+        (amount_to_swap, amount_to_receive)
+    } else {
+        (amount_to_receive, amount_to_swap)
+    };
+
+    PairPrice {
+        mint_a: *mint_a,
+        mint_b: *mint_b,
+        price: executed_price(amount_a, amount_b).ok_or(ProgramError::ArithmeticOverflow)?,
+        slot: Clock::get()?.slot,
+        bump: pair_price_bump,
+    }.serialize(&mut &mut pair_price.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    paused: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+
+    let (pool_pda, _pool_bump) = Pubkey::find_program_address
+        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+
+    if *pool.key != pool_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut pool_data = LiquidityPool::load(&pool.data.borrow())?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    pool_data.paused = paused;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Drops an asset the pool no longer holds any of: closes the pool's ATA and
+// PoolAsset for it, returning both rents to the admin.
+pub fn process_remove_asset(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let pool_ata = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let pool_asset = next_account_info(accounts_iter)?;
+
+    let (pool_pda, pool_bump) = Pubkey::find_program_address
+        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+
+    if *pool.key != pool_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *pool_ata.key != get_associated_token_address(pool.key, &mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(SwapProgramError::InvalidTokenProgram.into());
+    }
+
+    let mut pool_data = LiquidityPool::load(&pool.data.borrow())?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    load_pool_asset(program_id, pool, pool_asset, &mint)?;
+
+    if TokenAccount::unpack(&pool_ata.data.borrow())?.amount != 0 {
+        return Err(SwapProgramError::PoolAssetNotEmpty.into());
+    }
+
+    invoke_signed(
+        &close_account(
+            token_program.key, 
+            pool_ata.key, 
+            admin.key, 
+            pool.key, 
+            &[],
+        )?, 
+        &[token_program.clone(), pool_ata.clone(), admin.clone(), pool.clone()],
+        &[
+            &[LiquidityPool::SEED_PREFIX.as_bytes(), &[pool_bump]]
+        ]
+    )?;
+
+    // the program owns the PoolAsset, so it can be closed directly
+    **admin.try_borrow_mut_lamports()? += pool_asset.lamports();
+    **pool_asset.try_borrow_mut_lamports()? = 0;
+
+    pool_asset.resize(0)?;
+    pool_asset.assign(&system_program_id());
+
+    pool_data.asset_count -= 1;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+// Pays a funder back up to what their receipt says they've put in of `mint`.
+pub fn process_withdraw_funding(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let funder = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let pool_ata = next_account_info(accounts_iter)?;
+    let funder_ata = next_account_info(accounts_iter)?;
+    let receipt = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !funder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (pool_pda, pool_bump) = Pubkey::find_program_address
+        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+
+    if *pool.key != pool_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *mint_account.key != mint {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if *pool_ata.key != get_associated_token_address(pool.key, &mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *funder_ata.key != get_associated_token_address(funder.key, &mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(SwapProgramError::InvalidTokenProgram.into());
+    }
+
+    let (receipt_pda, _receipt_bump) = Pubkey::find_program_address(
+        &[FundingReceipt::SEED_PREFIX.as_bytes(), funder.key.as_ref(), mint.as_ref()], program_id);
+
+    if *receipt.key != receipt_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut receipt_data = try_from_slice_unchecked::<FundingReceipt>(&receipt.data.borrow())?;
+
+    if amount > receipt_data.amount {
+        return Err(SwapProgramError::WithdrawExceedsReceipt.into());
+    }
+
+    receipt_data.amount -= amount;
+
+    let mint_data = Mint::unpack(&mint_account.data.borrow())?;
+
+    invoke_signed(
+        &transfer_checked(
+            token_program.key, 
+            pool_ata.key, 
+            mint_account.key, 
+            funder_ata.key, 
+            pool.key, 
+            &[], 
+            amount, 
+            mint_data.decimals,
+        )?, 
+        &[token_program.clone(), pool_ata.clone(), mint_account.clone(), funder_ata.clone(), pool.clone()],
+        &[
+            &[LiquidityPool::SEED_PREFIX.as_bytes(), &[pool_bump]]
+        ]
+    )?;
+
+    receipt_data.serialize(&mut &mut receipt.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_set_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_bps: u16,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+
+    let (pool_pda, _pool_bump) = Pubkey::find_program_address
+        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+
+    if *pool.key != pool_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut pool_data = LiquidityPool::load(&pool.data.borrow())?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    if fee_bps as u64 > BPS_DENOMINATOR {
+        return Err(SwapProgramError::InvalidFee.into());
+    }
+
+    pool_data.fee_bps = fee_bps;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_set_weight(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+    weight: u16,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let pool_asset = next_account_info(accounts_iter)?;
+
+    let (pool_pda, _pool_bump) = Pubkey::find_program_address
+        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+
+    if *pool.key != pool_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let pool_data = LiquidityPool::load(&pool.data.borrow())?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    if weight == 0 {
+        return Err(SwapProgramError::InvalidWeight.into());
+    }
+
+    let mut pool_asset_data = load_pool_asset(program_id, pool, pool_asset, &mint)?;
+
+    pool_asset_data.weight = weight;
+
+    pool_asset_data.serialize(&mut &mut pool_asset.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Switches the pool between pricing swaps off its balances and off its
+// assets' oracles. Every asset needs an AssetOracle before oracle-priced
+// swaps go through.
+pub fn process_set_pricing_mode(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: PricingMode,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+
+    let mut pool_data = load_pool(program_id, pool)?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    pool_data.pricing = mode;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Sets the Pyth price account an asset of the pool is priced by in oracle
+// mode, creating its AssetOracle the first time.
+pub fn process_set_asset_oracle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let pool_asset = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let asset_oracle = next_account_info(accounts_iter)?;
+    let price_feed = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let pool_data = load_pool(program_id, pool)?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    load_pool_asset(program_id, pool, pool_asset, mint.key)?;
+
+    if *mint.owner != token_program_id() {
+        return Err(SwapProgramError::InvalidMintOwner.into());
+    }
+
+    let mint_data = Mint::unpack(&mint.data.borrow())?;
+
+    // only the layout, the price itself is checked when it's swapped at
+    PythPrice::load(&price_feed.data.borrow())?;
+
+    let (asset_oracle_pda, asset_oracle_bump) = Pubkey::find_program_address(
+        &[AssetOracle::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint.key.as_ref()], program_id);
+
+    if *asset_oracle.key != asset_oracle_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if asset_oracle.data_is_empty() {
+        let asset_oracle_rent = Rent::get()?.minimum_balance(AssetOracle::SPACE);
+
+        invoke_signed(
+            &create_account(
+                admin.key, 
+                asset_oracle.key, 
+                asset_oracle_rent, 
+                AssetOracle::SPACE as u64, 
+                program_id,
+            ), 
+            &[admin.clone(), asset_oracle.clone(), system_program.clone()],
+            &[
+                &[AssetOracle::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint.key.as_ref(), &[asset_oracle_bump]]
+            ]
+        )?;
+    }
+
+    AssetOracle {
+        mint: *mint.key,
+        price_feed: *price_feed.key,
+        decimals: mint_data.decimals,
+        bump: asset_oracle_bump,
+    }.serialize(&mut &mut asset_oracle.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Shifts `amount` of the pool's inventory out of `from_mint` and into
+// `to_mint` through the admin's treasury, its ATAs of the two mints: the
+// pool signs `amount` of from_mint over to it and takes back what that costs
+// in to_mint at the pool's price, with no fee. Nothing a swap tracks, like
+// fees, volume or the pair's price, is touched, and it goes through while
+// the pool is paused.
+pub fn process_rebalance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    from_mint: Pubkey,
+    to_mint: Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let from_mint_account = next_account_info(accounts_iter)?;
+    let pool_from_ata = next_account_info(accounts_iter)?;
+    let treasury_from_ata = next_account_info(accounts_iter)?;
+    let to_mint_account = next_account_info(accounts_iter)?;
+    let pool_to_ata = next_account_info(accounts_iter)?;
+    let treasury_to_ata = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let from_asset = next_account_info(accounts_iter)?;
+    let to_asset = next_account_info(accounts_iter)?;
+
+    let pool_data = load_pool(program_id, pool)?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    if *from_mint_account.key != from_mint || *to_mint_account.key != to_mint {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if *pool_from_ata.key != get_associated_token_address(pool.key, &from_mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *treasury_from_ata.key != get_associated_token_address(admin.key, &from_mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *pool_to_ata.key != get_associated_token_address(pool.key, &to_mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *treasury_to_ata.key != get_associated_token_address(admin.key, &to_mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *token_program.key != token_program_id() {
+        return Err(SwapProgramError::InvalidTokenProgram.into());
+    }
+
+    if *from_mint_account.owner != token_program_id() || *to_mint_account.owner != token_program_id() {
+        return Err(SwapProgramError::InvalidMintOwner.into());
+    }
+
+    if amount == 0 {
+        return Err(SwapProgramError::InvalidSwapZeroAmount.into());
+    }
+
+    if from_mint == to_mint {
+        return Err(SwapProgramError::InvalidSwapMatchingAssets.into());
+    }
+
+    let from_asset_data = load_pool_asset(program_id, pool, from_asset, &from_mint)?;
+    let to_asset_data = load_pool_asset(program_id, pool, to_asset, &to_mint)?;
+
+    // the treasury takes from_mint out, so it's on the receiving side
+    let oracle_prices = load_oracle_prices(
+        program_id, 
+        pool, 
+        &pool_data, 
+        accounts_iter, 
+        &from_mint, 
+        &to_mint,
+    )?;
+
+    let amount_to_pay = swap_input(
+        (&from_asset_data, pool_from_ata), 
+        (&to_asset_data, pool_to_ata), 
+        oracle_prices,
+        amount,
+    )?;
+
+    let from_mint_data = Mint::unpack(&from_mint_account.data.borrow())?;
+    let to_mint_data = Mint::unpack(&to_mint_account.data.borrow())?;
+
+    invoke(
+        &transfer_checked(
+            token_program.key, 
+            treasury_to_ata.key, 
+            to_mint_account.key, 
+            pool_to_ata.key, 
+            admin.key, 
+            &[], 
+            amount_to_pay, 
+            to_mint_data.decimals,
+        )?, 
+        &[token_program.clone(), treasury_to_ata.clone(), to_mint_account.clone(), pool_to_ata.clone(), admin.clone()],
+    )?;
+
+    invoke_signed(
+        &transfer_checked(
+            token_program.key, 
+            pool_from_ata.key, 
+            from_mint_account.key, 
+            treasury_from_ata.key, 
+            pool.key, 
+            &[], 
+            amount, 
+            from_mint_data.decimals,
+        )?, 
+        &[token_program.clone(), pool_from_ata.clone(), from_mint_account.clone(), treasury_from_ata.clone(), pool.clone()],
+        &[
+            &[LiquidityPool::SEED_PREFIX.as_bytes(), &[pool_data.bump]]
+        ]
+    )?;
+
+    Ok(())
+}
+
+// Turns the funder allowlist on or off, creating the FunderAllowlist the
+// first time. With it on, only funders added with AddFunder can FundPool.
+pub fn process_set_funder_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let allowlist = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let pool_data = load_pool(program_id, pool)?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    let (allowlist_pda, allowlist_bump) = Pubkey::find_program_address(
+        &[FunderAllowlist::SEED_PREFIX.as_bytes(), pool.key.as_ref()], program_id);
+
+    if *allowlist.key != allowlist_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if allowlist.data_is_empty() {
+        let allowlist_rent = Rent::get()?.minimum_balance(FunderAllowlist::SPACE);
+
+        invoke_signed(
+            &create_account(
+                admin.key, 
+                allowlist.key, 
+                allowlist_rent, 
+                FunderAllowlist::SPACE as u64, 
+                program_id,
+            ), 
+            &[admin.clone(), allowlist.clone(), system_program.clone()],
+            &[
+                &[FunderAllowlist::SEED_PREFIX.as_bytes(), pool.key.as_ref(), &[allowlist_bump]]
+            ]
+        )?;
+    }
+
+    FunderAllowlist {
+        enabled,
+        bump: allowlist_bump,
+    }.serialize(&mut &mut allowlist.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Lets `funder` fund the pool while the allowlist is on. Adding a funder
+// twice changes nothing.
+pub fn process_add_funder(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    funder: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let approved_funder = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let pool_data = load_pool(program_id, pool)?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    let (approved_funder_pda, approved_funder_bump) = Pubkey::find_program_address(
+        &[ApprovedFunder::SEED_PREFIX.as_bytes(), pool.key.as_ref(), funder.as_ref()], program_id);
+
+    if *approved_funder.key != approved_funder_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if !approved_funder.data_is_empty() {
+        return Ok(());
+    }
+
+    let approved_funder_rent = Rent::get()?.minimum_balance(ApprovedFunder::SPACE);
+
+    invoke_signed(
+        &create_account(
+            admin.key, 
+            approved_funder.key, 
+            approved_funder_rent, 
+            ApprovedFunder::SPACE as u64, 
+            program_id,
+        ), 
+        &[admin.clone(), approved_funder.clone(), system_program.clone()],
+        &[
+            &[ApprovedFunder::SEED_PREFIX.as_bytes(), pool.key.as_ref(), funder.as_ref(), &[approved_funder_bump]]
+        ]
+    )?;
+
+    ApprovedFunder {
+        funder,
+        bump: approved_funder_bump,
+    }.serialize(&mut &mut approved_funder.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Takes `funder` off the allowlist, closing its ApprovedFunder. What it has
+// already funded stays in the pool and can still be withdrawn.
+pub fn process_remove_funder(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    funder: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let approved_funder = next_account_info(accounts_iter)?;
+
+    let pool_data = load_pool(program_id, pool)?;
+
+    check_pool_admin(&pool_data, admin)?;
+
+    let (approved_funder_pda, _approved_funder_bump) = Pubkey::find_program_address(
+        &[ApprovedFunder::SEED_PREFIX.as_bytes(), pool.key.as_ref(), funder.as_ref()], program_id);
+
+    if *approved_funder.key != approved_funder_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if approved_funder.owner != program_id || approved_funder.data_is_empty() {
+        return Err(SwapProgramError::FunderNotApproved.into());
+    }
+
+    // the program owns the ApprovedFunder, so it can be closed directly
+    **admin.try_borrow_mut_lamports()? += approved_funder.lamports();
+    **approved_funder.try_borrow_mut_lamports()? = 0;
+
+    approved_funder.resize(0)?;
+    approved_funder.assign(&system_program_id());
+
+    Ok(())
+}
+
+// Prices a swap without making it and returns the amount the payer would
+// receive, as a little-endian u64, through the return data.
+pub fn process_quote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pay_mint: Pubkey,
+    receive_mint: Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let pool = next_account_info(accounts_iter)?;
+    let pool_receive_ata = next_account_info(accounts_iter)?;
+    let pool_pay_ata = next_account_info(accounts_iter)?;
+    let receive_asset = next_account_info(accounts_iter)?;
+    let pay_asset = next_account_info(accounts_iter)?;
+
+    let (pool_pda, _pool_bump) = Pubkey::find_program_address
+        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+
+    if *pool.key != pool_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *pool_receive_ata.key != get_associated_token_address(pool.key, &receive_mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *pool_pay_ata.key != get_associated_token_address(pool.key, &pay_mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if amount == 0 {
+        return Err(SwapProgramError::InvalidSwapZeroAmount.into());
+    }
+
+    if receive_mint == pay_mint {
+        return Err(SwapProgramError::InvalidSwapMatchingAssets.into());
+    }
+
+    let pool_data = LiquidityPool::load(&pool.data.borrow())?;
+
+    if pool_data.paused {
+        return Err(SwapProgramError::PoolPaused.into());
+    }
+
+    let receive_asset_data = load_pool_asset(program_id, pool, receive_asset, &receive_mint)?;
+    let pay_asset_data = load_pool_asset(program_id, pool, pay_asset, &pay_mint)?;
+
+    let oracle_prices = load_oracle_prices(
+        program_id, 
+        pool, 
+        &pool_data, 
+        accounts_iter, 
+        &receive_mint, 
+        &pay_mint,
+    )?;
+
+    let (amount_to_receive, _fee) = swap_output(
+        &pool_data, 
+        (&receive_asset_data, pool_receive_ata), 
+        (&pay_asset_data, pool_pay_ata), 
+        oracle_prices,
+        amount,
+    )?;
+
+    set_return_data(&amount_to_receive.to_le_bytes());
+
+    Ok(())
+}
+
+// Returns the mint and pool balance of each asset passed in as an AssetList,
+// the remaining accounts coming in groups of LIST_ASSETS_ASSET_ACCOUNTS:
+// PoolAsset and pool ATA. Up to AssetList::MAX_PAGE_ASSETS at a time, so a
+// client pages through a bigger pool by passing a few assets per call.
+pub fn process_list_assets(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let pool = next_account_info(accounts_iter)?;
+
+    let pool_data = load_pool(program_id, pool)?;
+
+    let asset_accounts = accounts_iter.as_slice();
+
+    if !asset_accounts.len().is_multiple_of(LIST_ASSETS_ASSET_ACCOUNTS)
+        || asset_accounts.len() / LIST_ASSETS_ASSET_ACCOUNTS > AssetList::MAX_PAGE_ASSETS
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut assets = Vec::with_capacity(asset_accounts.len() / LIST_ASSETS_ASSET_ACCOUNTS);
+
+    for accounts in asset_accounts.chunks(LIST_ASSETS_ASSET_ACCOUNTS) {
+        let [pool_asset, pool_ata] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if *pool_ata.owner != token_program_id() {
+            return Err(SwapProgramError::InvalidTokenProgram.into());
+        }
+
+        let pool_ata_data = TokenAccount::unpack(&pool_ata.data.borrow())?;
+
+        if *pool_ata.key != get_associated_token_address(pool.key, &pool_ata_data.mint) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // a token account the pool holds but hasn't listed isn't an asset
+        load_pool_asset(program_id, pool, pool_asset, &pool_ata_data.mint)?;
+
+        assets.push(AssetBalance {
+            mint: pool_ata_data.mint,
+            balance: pool_ata_data.amount,
+        });
+    }
+
+    let mut data = vec![];
+    AssetList {
+        asset_count: pool_data.asset_count,
+        assets,
+    }.serialize(&mut data)?;
+
+    set_return_data(&data);
+
+    Ok(())
+}
+
+// Moves a LiquidityPoolV1 pool onto PoolAsset accounts, one per asset and
+// passed in the pool's asset order, and a LiquidityPoolV1 or V2 pool onto
+// the current layout, settling the difference in rent with the admin.
+pub fn process_migrate_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let (pool_pda, _pool_bump) = Pubkey::find_program_address
+        (&[LiquidityPool::SEED_PREFIX.as_bytes()], program_id);
+
+    if *pool.key != pool_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if LiquidityPool::load(&pool.data.borrow()).is_ok() {
+        return Err(SwapProgramError::PoolAlreadyCurrent.into());
+    }
+
+    // only pools from before PoolAsset have assets to move
+    let pool_v1 = match LiquidityPoolV2::load(&pool.data.borrow()) {
+        Ok(_) => None,
+        Err(_) => Some(LiquidityPoolV1::load(&pool.data.borrow())?),
+    };
+
+    let pool_data = match &pool_v1 {
+        Some(pool_v1) => pool_v1.migrate(),
+        None => LiquidityPoolV2::load(&pool.data.borrow())?.migrate(),
+    };
+
+    check_pool_admin(&pool_data, admin)?;
+
+    let pool_asset_rent = Rent::get()?.minimum_balance(PoolAsset::SPACE);
+
+    let (assets, collected_fees) = pool_v1
+        .map(|pool_v1| (pool_v1.assets, pool_v1.collected_fees))
+        .unwrap_or_default();
+
+    for (mint, collected_fees) in assets.iter().zip(collected_fees) {
+        let pool_asset = next_account_info(accounts_iter)?;
+
+        let (pool_asset_pda, pool_asset_bump) = Pubkey::find_program_address(
+            &[PoolAsset::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint.as_ref()], program_id);
+
+        if *pool_asset.key != pool_asset_pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        invoke_signed(
+            &create_account(
+                admin.key, 
+                pool_asset.key, 
+                pool_asset_rent, 
+                PoolAsset::SPACE as u64, 
+                program_id,
+            ), 
+            &[admin.clone(), pool_asset.clone(), system_program.clone()],
+            &[
+                &[PoolAsset::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint.as_ref(), &[pool_asset_bump]]
+            ]
+        )?;
+
+        PoolAsset {
+            mint: *mint,
+            collected_fees,
+            pay_volume: 0,
+            receive_volume: 0,
+            weight: PoolAsset::DEFAULT_WEIGHT,
+            bump: pool_asset_bump,
+        }.serialize(&mut &mut pool_asset.data.borrow_mut()[..])?;
+    }
+
+    pool.resize(LiquidityPool::SPACE)?;
+
+    pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+    let pool_rent = Rent::get()?.minimum_balance(LiquidityPool::SPACE);
+
+    if pool.lamports() > pool_rent {
+        // the pool owns itself, so the freed rent can be moved directly
+        let excess_rent = pool.lamports() - pool_rent;
+
+        **pool.try_borrow_mut_lamports()? -= excess_rent;
+        **admin.try_borrow_mut_lamports()? += excess_rent;
+    } else if pool.lamports() < pool_rent {
+        invoke(
+            &transfer(admin.key, pool.key, pool_rent - pool.lamports()), 
+            &[admin.clone(), pool.clone(), system_program.clone()],
+        )?;
+    }
+
+    Ok(())
+}
+
+// What the pool pays out for `amount_to_swap` at the ATAs' current
+// balances and the assets' weights, or at the oracle prices of an
+// oracle-priced pool, and the fee held back from it, which stays in
+// pool_receive_ata.
+fn swap_output(
+    pool_data: &LiquidityPool,
+    (receive_asset_data, pool_receive_ata): (&PoolAsset, &AccountInfo),
+    (pay_asset_data, pool_pay_ata): (&PoolAsset, &AccountInfo),
+    oracle_prices: Option<(OraclePrice, OraclePrice)>,
+    amount_to_swap: u64,
+) -> Result<(u64, u64), ProgramError> {
+    let pool_receive_balance = TokenAccount::unpack(&pool_receive_ata.data.borrow())?.amount;
+    let pool_pay_balance = TokenAccount::unpack(&pool_pay_ata.data.borrow())?.amount;
+
+    let amount_to_receive = match oracle_prices {
+        // the balances only cap what can be paid out
+        Some(((receive_price, receive_decimals), (pay_price, pay_decimals))) => {
+            let amount_to_receive = oracle_receive_amount(
+                amount_to_swap, 
+                (&pay_price, pay_decimals), 
+                (&receive_price, receive_decimals),
+            ).ok_or(ProgramError::ArithmeticOverflow)?;
+
+            if amount_to_receive > pool_receive_balance {
+                return Err(SwapProgramError::InvalidSwapNotEnoughLiquidity.into());
+            }
+
+            amount_to_receive
+        },
+        None => weighted_swap_receive_amount(
+            pool_receive_balance, 
+            pool_pay_balance, 
+            amount_to_swap, 
+            receive_asset_data.weight, 
+            pay_asset_data.weight,
+        ).ok_or(ProgramError::ArithmeticOverflow)?,
+    };
+
+    let fee = fee_amount(amount_to_receive, pool_data.fee_bps)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if amount_to_receive - fee == 0 {
+        return Err(SwapProgramError::InvalidSwapNotEnoughLiquidity.into());
+    }
+
+    Ok((amount_to_receive - fee, fee))
+}
+
+// The pool, checked against the address its stored bump derives, which
+// costs a single hash where find_program_address may take many.
+fn load_pool(program_id: &Pubkey, pool: &AccountInfo) -> Result<LiquidityPool, ProgramError> {
+    let pool_data = LiquidityPool::load(&pool.data.borrow())?;
+
+    let pool_pda = Pubkey::create_program_address(
+        &[LiquidityPool::SEED_PREFIX.as_bytes(), &[pool_data.bump]], program_id)
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+
+    if *pool.key != pool_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(pool_data)
+}
+
+// An asset's checked oracle price and its mint's decimals.
+type OraclePrice = (PythPrice, u8);
+
+// The other way round from swap_output: what has to be paid in to take
+// `amount_to_receive` out, rounded up and without a fee.
+fn swap_input(
+    (receive_asset_data, pool_receive_ata): (&PoolAsset, &AccountInfo),
+    (pay_asset_data, pool_pay_ata): (&PoolAsset, &AccountInfo),
+    oracle_prices: Option<(OraclePrice, OraclePrice)>,
+    amount_to_receive: u64,
+) -> Result<u64, ProgramError> {
+    let pool_receive_balance = TokenAccount::unpack(&pool_receive_ata.data.borrow())?.amount;
+    let pool_pay_balance = TokenAccount::unpack(&pool_pay_ata.data.borrow())?.amount;
+
+    // the pool keeps some of every asset
+    if amount_to_receive >= pool_receive_balance {
+        return Err(SwapProgramError::InvalidSwapNotEnoughLiquidity.into());
+    }
+
+    match oracle_prices {
+        Some(((receive_price, receive_decimals), (pay_price, pay_decimals))) => oracle_pay_amount(
+            amount_to_receive, 
+            (&pay_price, pay_decimals), 
+            (&receive_price, receive_decimals),
+        ),
+        None => weighted_swap_pay_amount(
+            pool_receive_balance, 
+            pool_pay_balance, 
+            amount_to_receive, 
+            receive_asset_data.weight, 
+            pay_asset_data.weight,
+        ),
+    }.ok_or(ProgramError::ArithmeticOverflow)
+}
+
+// The receive and pay assets' prices if the pool is oracle-priced, from the
+// AssetOracle and price account pairs, receive asset first, that follow the
+// instruction's other accounts.
+fn load_oracle_prices(
+    program_id: &Pubkey,
+    pool: &AccountInfo,
+    pool_data: &LiquidityPool,
+    accounts_iter: &mut std::slice::Iter<AccountInfo>,
+    receive_mint: &Pubkey,
+    pay_mint: &Pubkey,
+) -> Result<Option<(OraclePrice, OraclePrice)>, ProgramError> {
+    if pool_data.pricing != PricingMode::Oracle {
+        return Ok(None);
+    }
+
+    let receive_oracle = next_account_info(accounts_iter)?;
+    let receive_price_feed = next_account_info(accounts_iter)?;
+    let pay_oracle = next_account_info(accounts_iter)?;
+    let pay_price_feed = next_account_info(accounts_iter)?;
+
+    let now = Clock::get()?.unix_timestamp;
+
+    Ok(Some((
+        load_oracle_price(program_id, pool, (receive_oracle, receive_price_feed), receive_mint, now)?,
+        load_oracle_price(program_id, pool, (pay_oracle, pay_price_feed), pay_mint, now)?,
+    )))
+}
+
+fn load_oracle_price(
+    program_id: &Pubkey,
+    pool: &AccountInfo,
+    (asset_oracle, price_feed): (&AccountInfo, &AccountInfo),
+    mint: &Pubkey,
+    now: i64,
+) -> Result<OraclePrice, ProgramError> {
+    let (asset_oracle_pda, _asset_oracle_bump) = Pubkey::find_program_address(
+        &[AssetOracle::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint.as_ref()], program_id);
+
+    if *asset_oracle.key != asset_oracle_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if asset_oracle.owner != program_id || asset_oracle.data_is_empty() {
+        return Err(SwapProgramError::OracleNotSet.into());
+    }
+
+    let asset_oracle_data = try_from_slice_unchecked::<AssetOracle>(&asset_oracle.data.borrow())?;
+
+    if *price_feed.key != asset_oracle_data.price_feed {
+        return Err(SwapProgramError::OracleMismatch.into());
+    }
+
+    let price = PythPrice::load(&price_feed.data.borrow())?.checked(now)?;
+
+    Ok((price, asset_oracle_data.decimals))
+}
+
+// The PoolAsset for `mint`, which only exists while the mint is in the pool.
+fn load_pool_asset(
+    program_id: &Pubkey,
+    pool: &AccountInfo,
+    pool_asset: &AccountInfo,
+    mint: &Pubkey,
+) -> Result<PoolAsset, ProgramError> {
+    let (pool_asset_pda, _pool_asset_bump) = Pubkey::find_program_address(
+        &[PoolAsset::SEED_PREFIX.as_bytes(), pool.key.as_ref(), mint.as_ref()], program_id);
+
+    if *pool_asset.key != pool_asset_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if pool_asset.owner != program_id || pool_asset.data_is_empty() {
+        return Err(SwapProgramError::AssetNotInPool.into());
+    }
+
+    Ok(try_from_slice_unchecked::<PoolAsset>(&pool_asset.data.borrow())?)
+}
+
+// FundPool's payer has to be approved while the pool's allowlist is on.
+fn check_funder(
+    program_id: &Pubkey,
+    pool: &AccountInfo,
+    payer: &AccountInfo,
+    allowlist: &AccountInfo,
+    approved_funder: &AccountInfo,
+) -> ProgramResult {
+    let (allowlist_pda, _allowlist_bump) = Pubkey::find_program_address(
+        &[FunderAllowlist::SEED_PREFIX.as_bytes(), pool.key.as_ref()], program_id);
+
+    if *allowlist.key != allowlist_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // never turned on
+    if allowlist.owner != program_id || allowlist.data_is_empty() {
+        return Ok(());
+    }
+
+    if !try_from_slice_unchecked::<FunderAllowlist>(&allowlist.data.borrow())?.enabled {
+        return Ok(());
+    }
+
+    let (approved_funder_pda, _approved_funder_bump) = Pubkey::find_program_address(
+        &[ApprovedFunder::SEED_PREFIX.as_bytes(), pool.key.as_ref(), payer.key.as_ref()], program_id);
+
+    if *approved_funder.key != approved_funder_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if approved_funder.owner != program_id || approved_funder.data_is_empty() {
+        return Err(SwapProgramError::FunderNotApproved.into());
+    }
+
+    Ok(())
+}
+
+// Privileged instructions need the pool's admin, the account that created
+// it, to sign.
+fn check_pool_admin(pool_data: &LiquidityPool, admin: &AccountInfo) -> ProgramResult {
+    if *admin.key != pool_data.admin {
+        return Err(SwapProgramError::NotPoolAdmin.into());
+    }
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    Ok(())
+}
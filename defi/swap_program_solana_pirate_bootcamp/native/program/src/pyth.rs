@@ -0,0 +1,135 @@
+// Just enough of Pyth's v2 price account layout to read the aggregate price.
+// pyth-sdk-solana pins solana-program 1.x, so the fields are read by offset.
+
+use solana_program::program_error::ProgramError;
+
+use crate::error::SwapProgramError;
+
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_VERSION: u32 = 2;
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+
+// Header and aggregate price, everything after is per-publisher data.
+pub const PYTH_PRICE_ACCOUNT_LEN: usize = 240;
+
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+
+// A price published longer ago than this, in seconds, is stale.
+pub const MAX_ORACLE_AGE: i64 = 60;
+
+// Prices whose confidence interval is wider than this share of the price
+// are too uncertain to swap at.
+pub const MAX_ORACLE_CONFIDENCE_BPS: u64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PythPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub status: u32,
+}
+
+impl PythPrice {
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < PYTH_PRICE_ACCOUNT_LEN {
+            return Err(SwapProgramError::InvalidOracle.into());
+        }
+
+        if read_u32(data, 0) != PYTH_MAGIC
+            || read_u32(data, 4) != PYTH_VERSION
+            || read_u32(data, 8) != PYTH_PRICE_ACCOUNT_TYPE
+        {
+            return Err(SwapProgramError::InvalidOracle.into());
+        }
+
+        Ok(Self {
+            price: read_u64(data, AGG_PRICE_OFFSET) as i64,
+            conf: read_u64(data, AGG_CONF_OFFSET),
+            expo: read_u32(data, EXPO_OFFSET) as i32,
+            publish_time: read_u64(data, TIMESTAMP_OFFSET) as i64,
+            status: read_u32(data, AGG_STATUS_OFFSET),
+        })
+    }
+
+    // The price if it's trading, fresh at `now` and tight enough to rely on.
+    pub fn checked(&self, now: i64) -> Result<Self, ProgramError> {
+        if self.status != PYTH_STATUS_TRADING || self.price <= 0 {
+            return Err(SwapProgramError::InvalidOracle.into());
+        }
+
+        if now.saturating_sub(self.publish_time) > MAX_ORACLE_AGE {
+            return Err(SwapProgramError::OracleStale.into());
+        }
+
+        if self.conf as u128 * 10_000 > self.price as u128 * MAX_ORACLE_CONFIDENCE_BPS as u128 {
+            return Err(SwapProgramError::OracleConfidenceTooWide.into());
+        }
+
+        Ok(*self)
+    }
+}
+
+// What `pay_amount` base units of one asset are worth in base units of
+// another, rounded down, given each one's oracle price of a whole token in
+// the same currency and its mint's decimals:
+// r = p * Pp * 10^(ep + dr) / (Pr * 10^(er + dp))
+pub fn oracle_receive_amount(
+    pay_amount: u64,
+    pay: (&PythPrice, u8),
+    receive: (&PythPrice, u8),
+) -> Option<u64> {
+    convert_at_oracle_prices(pay_amount, pay, receive, false)
+}
+
+// The inverse of oracle_receive_amount: how much of the pay asset is worth
+// `receive_amount` of the receive asset, rounded up.
+pub fn oracle_pay_amount(
+    receive_amount: u64,
+    pay: (&PythPrice, u8),
+    receive: (&PythPrice, u8),
+) -> Option<u64> {
+    convert_at_oracle_prices(receive_amount, receive, pay, true)
+}
+
+fn convert_at_oracle_prices(
+    amount: u64,
+    (from_price, from_decimals): (&PythPrice, u8),
+    (to_price, to_decimals): (&PythPrice, u8),
+    round_up: bool,
+) -> Option<u64> {
+    if from_price.price <= 0 || to_price.price <= 0 {
+        return None;
+    }
+
+    let scale = from_price.expo as i64 + to_decimals as i64
+        - to_price.expo as i64 - from_decimals as i64;
+
+    let power = 10u128.checked_pow(u32::try_from(scale.unsigned_abs()).ok()?)?;
+
+    let mut numerator = (amount as u128).checked_mul(from_price.price as u128)?;
+    let mut denominator = to_price.price as u128;
+
+    if scale >= 0 {
+        numerator = numerator.checked_mul(power)?;
+    } else {
+        denominator = denominator.checked_mul(power)?;
+    }
+
+    let converted = if round_up { numerator.div_ceil(denominator) } else { numerator / denominator };
+
+    u64::try_from(converted).ok()
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
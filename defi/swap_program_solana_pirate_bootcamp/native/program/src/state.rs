@@ -1,17 +1,326 @@
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    borsh1::try_from_slice_unchecked,
+    program::MAX_RETURN_DATA,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
 use borsh::{BorshSerialize, BorshDeserialize};
 
+use crate::{
+    error::SwapProgramError,
+    math::PRICE_SCALE,
+};
+
+// Where swap prices come from.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PricingMode {
+    // the pool's balances, see math::weighted_swap_receive_amount
+    Balances,
+    // each asset's Pyth price, see AssetOracle
+    Oracle,
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct LiquidityPool {
-    pub assets: Vec<Pubkey>,
+    pub version: u8,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub fee_bps: u16,
+    // how many PoolAsset accounts the pool has
+    pub asset_count: u8,
+    pub pricing: PricingMode,
     pub bump: u8,
 }
 
 impl LiquidityPool {
     pub const SEED_PREFIX: &'static str = "liquidity_pool";
 
+    // pools that kept their assets inline, see LiquidityPoolV1, count as 1
+    // and ones without a pricing mode, see LiquidityPoolV2, as 2
+    pub const VERSION: u8 = 3;
+
+    pub const MAX_ASSETS: usize = 10;
+
+    pub const SPACE: usize = 
+        1    // version
+        + 32 // admin pubkey
+        + 1  // paused
+        + 2  // fee_bps
+        + 1  // asset_count
+        + 1  // pricing
+        + 1; // 1 byte bump
+
+    // A pool of the current layout. One on an older one has to go through
+    // MigratePool first.
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::SPACE || data[0] != Self::VERSION {
+            return Err(SwapProgramError::PoolNeedsMigration.into());
+        }
+
+        Ok(try_from_slice_unchecked::<Self>(data)?)
+    }
+}
+
+// One asset of the pool, so that adding one doesn't mean growing the pool
+// account and checking for one is a single PDA derivation.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PoolAsset {
+    pub mint: Pubkey,
+    // swap fees kept so far
+    pub collected_fees: u64,
+    // everything swappers have ever paid in and taken out of this asset
+    pub pay_volume: u128,
+    pub receive_volume: u128,
+    // relative to the other assets', see math::weighted_swap_receive_amount
+    pub weight: u16,
+    pub bump: u8,
+}
+
+impl PoolAsset {
+    pub const SEED_PREFIX: &'static str = "pool_asset";
+
+    // what an asset starts with, so that weights can go below it
+    pub const DEFAULT_WEIGHT: u16 = 100;
+
+    pub const SPACE: usize = 
+        32   // mint pubkey
+        + 8  // collected fees
+        + 16 // pay volume
+        + 16 // receive volume
+        + 2  // weight
+        + 1; // 1 byte bump
+}
+
+// The last swap between two assets, for clients to show without going
+// through the logs. `mint_a` sorts before `mint_b`, and `price` is in
+// mint_b per mint_a, see math::executed_price.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PairPrice {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub price: u128,
+    pub slot: u64,
+    pub bump: u8,
+}
+
+impl PairPrice {
+    pub const SEED_PREFIX: &'static str = "pair_price";
+
+    pub const SPACE: usize = 
+        32   // mint_a pubkey
+        + 32 // mint_b pubkey
+        + 16 // price
+        + 8  // slot
+        + 1; // 1 byte bump
+
+    // Either order of a pair gives the same PairPrice.
+    pub fn sort_mints<'a>(mint_x: &'a Pubkey, mint_y: &'a Pubkey) -> (&'a Pubkey, &'a Pubkey) {
+        if mint_x < mint_y { (mint_x, mint_y) } else { (mint_y, mint_x) }
+    }
+
+    // How much of the other mint one base unit of `base` went for, in base
+    // units. None if `base` isn't one of the pair.
+    pub fn price_of(&self, base: &Pubkey) -> Option<f64> {
+        let price = self.price as f64 / PRICE_SCALE as f64;
+
+        if *base == self.mint_a {
+            Some(price)
+        } else if *base == self.mint_b && self.price != 0 {
+            Some(1.0 / price)
+        } else {
+            None
+        }
+    }
+}
+
+// The Pyth price account an asset is priced by while its pool is in
+// PricingMode::Oracle, and the mint's decimals to scale that price by.
+// Every asset of a pool has to be priced in the same currency.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct AssetOracle {
+    pub mint: Pubkey,
+    pub price_feed: Pubkey,
+    pub decimals: u8,
+    pub bump: u8,
+}
+
+impl AssetOracle {
+    pub const SEED_PREFIX: &'static str = "asset_oracle";
+
+    pub const SPACE: usize = 
+        32   // mint pubkey
+        + 32 // price feed pubkey
+        + 1  // decimals
+        + 1; // 1 byte bump
+}
+
+// Whether only funders with an ApprovedFunder can FundPool. A pool that
+// never had one made is open to anyone, as is one with it disabled.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FunderAllowlist {
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+impl FunderAllowlist {
+    pub const SEED_PREFIX: &'static str = "funder_allowlist";
+
+    pub const SPACE: usize = 
+        1    // enabled
+        + 1; // 1 byte bump
+}
+
+// A funder the admin has let into an allowlisted pool.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ApprovedFunder {
+    pub funder: Pubkey,
+    pub bump: u8,
+}
+
+impl ApprovedFunder {
+    pub const SEED_PREFIX: &'static str = "approved_funder";
+
+    pub const SPACE: usize = 
+        32   // funder pubkey
+        + 1; // 1 byte bump
+}
+
+// Layout of pools from before PricingMode, which only priced off their
+// balances. Only MigratePool reads it.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LiquidityPoolV2 {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub fee_bps: u16,
+    pub asset_count: u8,
+    pub bump: u8,
+}
+
+impl LiquidityPoolV2 {
+    pub const VERSION: u8 = 2;
+
+    pub const SPACE: usize = 
+        1    // version
+        + 32 // admin pubkey
+        + 1  // paused
+        + 2  // fee_bps
+        + 1  // asset_count
+        + 1; // 1 byte bump
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::SPACE || data[0] != Self::VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(try_from_slice_unchecked::<Self>(data)?)
+    }
+
+    pub fn migrate(&self) -> LiquidityPool {
+        LiquidityPool {
+            version: LiquidityPool::VERSION,
+            admin: self.admin,
+            paused: self.paused,
+            fee_bps: self.fee_bps,
+            asset_count: self.asset_count,
+            pricing: PricingMode::Balances,
+            bump: self.bump,
+        }
+    }
+}
+
+// Layout of pools from before PoolAsset, which grew by ASSET_SPACE for
+// every asset. Only MigratePool reads it.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LiquidityPoolV1 {
+    pub assets: Vec<Pubkey>,
+    pub collected_fees: Vec<u64>,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+impl LiquidityPoolV1 {
     pub const SPACE: usize = 
         4    // empty vector
+        + 4  // empty vector
+        + 32 // admin pubkey
+        + 1  // paused
+        + 2  // fee_bps
+        + 1; // 1 byte bump
+
+    pub const ASSET_SPACE: usize = 
+        32   // mint pubkey
+        + 8; // collected fee
+
+    // The account is sized exactly for its assets, which tells it apart from
+    // the fixed-size current layout.
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let pool = try_from_slice_unchecked::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if pool.collected_fees.len() != pool.assets.len()
+            || data.len() != Self::SPACE + pool.assets.len() * Self::ASSET_SPACE {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(pool)
+    }
+
+    pub fn migrate(&self) -> LiquidityPool {
+        LiquidityPool {
+            version: LiquidityPool::VERSION,
+            admin: self.admin,
+            paused: self.paused,
+            fee_bps: self.fee_bps,
+            asset_count: self.assets.len() as u8,
+            pricing: PricingMode::Balances,
+            bump: self.bump,
+        }
+    }
+}
+
+// What one funder has put into the pool of one mint, across every FundPool.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FundingReceipt {
+    pub funder: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl FundingReceipt {
+    pub const SEED_PREFIX: &'static str = "funding_receipt";
+
+    pub const SPACE: usize = 
+        32   // funder pubkey
+        + 32 // mint pubkey
+        + 8  // amount
         + 1; // 1 byte bump
-}
\ No newline at end of file
+}
+
+// Not an account, what ListAssets returns: the assets it was passed, and
+// the pool's asset_count to tell whether that was all of them.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub struct AssetList {
+    pub asset_count: u8,
+    pub assets: Vec<AssetBalance>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct AssetBalance {
+    pub mint: Pubkey,
+    // what the pool's ATA holds
+    pub balance: u64,
+}
+
+impl AssetList {
+    // How many assets fit in return data at once, more than
+    // LiquidityPool::MAX_ASSETS. A bigger list has to be asked for in pages.
+    pub const MAX_PAGE_ASSETS: usize = (MAX_RETURN_DATA
+        - 1        // asset_count
+        - 4)       // assets length
+        / (32 + 8); // mint pubkey and balance
+}
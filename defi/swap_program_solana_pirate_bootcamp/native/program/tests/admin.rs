@@ -0,0 +1,78 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+
+use program::error::SwapProgramError;
+
+use common::*;
+
+// Clears the admin's signer flag, so the instruction names the right admin
+// without its signature.
+fn unsigned(mut instruction: Instruction) -> Instruction {
+    instruction.accounts[0].is_signer = false;
+    instruction
+}
+
+#[tokio::test]
+async fn creator_is_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    // created by someone other than the fee payer
+    let creator = Keypair::new();
+
+    send(&mut context, &[transfer(&payer, &creator.pubkey(), 1_000_000_000)], &[]).await?;
+    send(&mut context, &[create_pool_ix(&program_id, &creator.pubkey(), 0)?], &[&creator]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool_address(&program_id)).await?.admin, creator.pubkey());
+
+    let result = send(&mut context, &[set_paused_ix(&program_id, &payer, true)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+
+    send(&mut context, &[set_paused_ix(&program_id, &creator.pubkey(), true)?], &[&creator]).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn admin_signature_required_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    // an admin apart from the fee payer, whose signature can be left off
+    let creator = Keypair::new();
+
+    send(&mut context, &[transfer(&payer, &creator.pubkey(), 1_000_000_000)], &[]).await?;
+    send(&mut context, &[create_pool_ix(&program_id, &creator.pubkey(), 0)?], &[&creator]).await?;
+
+    let mint = create_mint(&mut context).await?;
+
+    for instruction in [
+        set_paused_ix(&program_id, &creator.pubkey(), true)?,
+        set_fee_ix(&program_id, &creator.pubkey(), 100)?,
+        remove_asset_ix(&program_id, &creator.pubkey(), &mint)?,
+        rebalance_ix(&program_id, &creator.pubkey(), &mint, &Pubkey::new_unique(), 1)?,
+        set_funder_allowlist_ix(&program_id, &creator.pubkey(), true)?,
+    ] {
+        let result = send(&mut context, &[unsigned(instruction)], &[]).await;
+
+        assert_eq!(instruction_error(result), Some(InstructionError::MissingRequiredSignature));
+    }
+
+    let pool_data = pool_state(&mut context, &pool_address(&program_id)).await?;
+
+    assert!(!pool_data.paused);
+    assert_eq!(pool_data.fee_bps, 0);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+use program::error::SwapProgramError;
+use program::state::LiquidityPool;
+
+use common::*;
+
+#[tokio::test]
+async fn pool_asset_limit_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, LiquidityPool::MAX_ASSETS).await?;
+    let pool = pool_address(&program_id);
+
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count as usize, LiquidityPool::MAX_ASSETS);
+
+    let one_too_many = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &one_too_many, USER_FUNDS).await?;
+
+    let result = send(&mut context, &[fund_pool_ix(&program_id, &payer, &one_too_many, 1_000_000)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::PoolAssetLimitReached as u32));
+
+    // a full pool still takes more of what it already lists
+    send(&mut context, &[fund_pool_ix(&program_id, &payer, &mints[0], 1_000_000)?], &[]).await?;
+
+    // and makes room once an asset is removed
+    send(&mut context, &[withdraw_funding_ix(&program_id, &payer, &mints[1], INITIAL_FUNDING)?], &[]).await?;
+    send(&mut context, &[remove_asset_ix(&program_id, &payer, &mints[1])?], &[]).await?;
+    send(&mut context, &[fund_pool_ix(&program_id, &payer, &one_too_many, 1_000_000)?], &[]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count as usize, LiquidityPool::MAX_ASSETS);
+
+    Ok(())
+}
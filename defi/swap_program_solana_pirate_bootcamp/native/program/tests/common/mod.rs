@@ -0,0 +1,180 @@
+#![allow(dead_code)]
+
+use anyhow::Result;
+
+use solana_program_test::*;
+
+use solana_sdk::{
+    borsh1::try_from_slice_unchecked,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::instruction::create_account;
+use spl_associated_token_account::{
+    get_associated_token_address,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{
+    id as token_program_id,
+    instruction::{initialize_mint2, mint_to},
+    state::{Account as TokenAccount, Mint},
+};
+
+use program::processor::process_instruction;
+use program::state::{LiquidityPool, PoolAsset};
+
+pub use program::client::*;
+
+pub const DECIMALS: u8 = 6;
+pub const USER_FUNDS: u64 = 10_000_000_000;
+pub const INITIAL_FUNDING: u64 = 1_000_000_000;
+
+pub async fn start(program_id: &Pubkey) -> ProgramTestContext {
+    ProgramTest::new(
+        "program",
+        *program_id,
+        processor!(process_instruction),
+    ).start_with_context().await
+}
+
+// Fetches a fresh blockhash for every transaction so that repeating an
+// identical instruction isn't rejected as already processed.
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await?;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub fn custom_error(result: Result<(), BanksClientError>) -> Option<u32> {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+pub fn instruction_error(result: Result<(), BanksClientError>) -> Option<InstructionError> {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, error) => Some(error),
+        _ => None,
+    }
+}
+
+pub async fn create_mint(context: &mut ProgramTestContext) -> Result<Pubkey> {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[
+            create_account(
+                &payer,
+                &mint.pubkey(),
+                Rent::default().minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &token_program_id(),
+            ),
+            initialize_mint2(&token_program_id(), &mint.pubkey(), &payer, None, DECIMALS)?,
+        ],
+        &[&mint],
+    ).await?;
+
+    Ok(mint.pubkey())
+}
+
+pub async fn create_ata(context: &mut ProgramTestContext, owner: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+
+    send(
+        context,
+        &[create_associated_token_account_idempotent(&payer, owner, mint, &token_program_id())],
+        &[],
+    ).await?;
+
+    Ok(get_associated_token_address(owner, mint))
+}
+
+// Only works for mints created by `create_mint`, whose authority is the payer.
+pub async fn create_funded_ata(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey> {
+    let payer = context.payer.pubkey();
+    let ata = create_ata(context, owner, mint).await?;
+
+    send(
+        context,
+        &[mint_to(&token_program_id(), mint, &ata, &payer, &[], amount)?],
+        &[],
+    ).await?;
+
+    Ok(ata)
+}
+
+// Creates a fee-free pool and `count` mints, funds the payer with
+// USER_FUNDS of each and puts INITIAL_FUNDING of each into the pool.
+// Returns the mints.
+pub async fn setup_pool(context: &mut ProgramTestContext, program_id: &Pubkey, count: usize) -> Result<Vec<Pubkey>> {
+    setup_pool_with_fee(context, program_id, count, 0).await
+}
+
+pub async fn setup_pool_with_fee(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    count: usize,
+    fee_bps: u16,
+) -> Result<Vec<Pubkey>> {
+    let payer = context.payer.pubkey();
+
+    send(context, &[create_pool_ix(program_id, &payer, fee_bps)?], &[]).await?;
+
+    let mut mints = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let mint = create_mint(context).await?;
+
+        create_funded_ata(context, &payer, &mint, USER_FUNDS).await?;
+        send(context, &[fund_pool_ix(program_id, &payer, &mint, INITIAL_FUNDING)?], &[]).await?;
+
+        mints.push(mint);
+    }
+
+    Ok(mints)
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> Result<u64> {
+    let account = context.banks_client.get_account(*address).await?.unwrap();
+
+    Ok(TokenAccount::unpack(&account.data)?.amount)
+}
+
+pub async fn pool_state(context: &mut ProgramTestContext, pool: &Pubkey) -> Result<LiquidityPool> {
+    let account = context.banks_client.get_account(*pool).await?.unwrap();
+
+    Ok(try_from_slice_unchecked::<LiquidityPool>(&account.data)?)
+}
+
+pub async fn pool_asset_state(context: &mut ProgramTestContext, program_id: &Pubkey, mint: &Pubkey) -> Result<PoolAsset> {
+    let account = context.banks_client.get_account(pool_asset_address(program_id, mint)).await?.unwrap();
+
+    Ok(try_from_slice_unchecked::<PoolAsset>(&account.data)?)
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    borsh1::try_from_slice_unchecked,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::state::{FundingReceipt, LiquidityPool, PoolAsset};
+
+use common::*;
+
+#[tokio::test]
+async fn create_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let pool = pool_address(&program_id);
+
+    send(&mut context, &[create_pool_ix(&program_id, &payer, 30)?], &[]).await?;
+
+    let account = context.banks_client.get_account(pool).await?.unwrap();
+
+    assert_eq!(account.owner, program_id);
+    assert_eq!(account.data.len(), LiquidityPool::SPACE);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.version, LiquidityPool::VERSION);
+    assert_eq!(pool_data.admin, payer);
+    assert!(!pool_data.paused);
+    assert_eq!(pool_data.fee_bps, 30);
+    assert_eq!(pool_data.asset_count, 0);
+
+    // there's only the one pool
+    let result = send(&mut context, &[create_pool_ix(&program_id, &payer, 0)?], &[]).await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fund_pool_multiple_mints_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 3).await?;
+    let pool = pool_address(&program_id);
+
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count, 3);
+
+    for mint in &mints {
+        let pool_asset = pool_asset_state(&mut context, &program_id, mint).await?;
+
+        assert_eq!(pool_asset.mint, *mint);
+        assert_eq!(pool_asset.weight, PoolAsset::DEFAULT_WEIGHT);
+        assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, mint)).await?, INITIAL_FUNDING);
+        assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, mint)).await?, USER_FUNDS - INITIAL_FUNDING);
+    }
+
+    // a listed mint is topped up, not listed again, and the pool never grows
+    send(&mut context, &[fund_pool_ix(&program_id, &payer, &mints[0], 1_000)?], &[]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count, 3);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &mints[0])).await?, INITIAL_FUNDING + 1_000);
+    assert_eq!(context.banks_client.get_account(pool).await?.unwrap().data.len(), LiquidityPool::SPACE);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fund_pool_batch_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    send(&mut context, &[create_pool_ix(&program_id, &payer, 30)?], &[]).await?;
+
+    let mut funding = vec![];
+
+    for amount in [INITIAL_FUNDING, 2 * INITIAL_FUNDING, 3 * INITIAL_FUNDING] {
+        let mint = create_mint(&mut context).await?;
+
+        create_funded_ata(&mut context, &payer, &mint, USER_FUNDS).await?;
+
+        funding.push((mint, amount));
+    }
+
+    send(&mut context, &[fund_pool_batch_ix(&program_id, &payer, &funding)?], &[]).await?;
+
+    let pool = pool_address(&program_id);
+
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count, 3);
+
+    for (mint, amount) in &funding {
+        assert_eq!(pool_asset_state(&mut context, &program_id, mint).await?.mint, *mint);
+        assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, mint)).await?, *amount);
+
+        let receipt = context.banks_client
+            .get_account(funding_receipt_address(&program_id, &payer, mint)).await?
+            .unwrap();
+
+        assert_eq!(try_from_slice_unchecked::<FundingReceipt>(&receipt.data)?.amount, *amount);
+    }
+
+    // topping up listed mints and listing the same one twice are both fine
+    let (gold, rum) = (funding[0].0, funding[1].0);
+
+    send(&mut context, &[fund_pool_batch_ix(&program_id, &payer, &[(gold, 1_000), (rum, 1_000), (gold, 1_000)])?], &[]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count, 3);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING + 2_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fund_pool_batch_amounts_mismatch_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+
+    // two mints' accounts, one amount
+    let mut instruction = fund_pool_batch_ix(&program_id, &payer, &[(mints[0], 1_000), (mints[1], 1_000)])?;
+    instruction.data = fund_pool_batch_ix(&program_id, &payer, &[(mints[0], 1_000)])?.data;
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::InvalidArgument));
+
+    let result = send(&mut context, &[fund_pool_batch_ix(&program_id, &payer, &[])?], &[]).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::InvalidArgument));
+
+    Ok(())
+}
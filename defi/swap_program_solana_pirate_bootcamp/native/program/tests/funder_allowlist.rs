@@ -0,0 +1,144 @@
+mod common;
+
+use anyhow::Result;
+
+use borsh::BorshDeserialize;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::state::FunderAllowlist;
+
+use common::*;
+
+// A funder apart from the admin, with lamports for its funding receipts and
+// USER_FUNDS of `mint`.
+async fn create_funder(context: &mut ProgramTestContext, mint: &Pubkey) -> Result<Keypair> {
+    let payer = context.payer.pubkey();
+    let funder = Keypair::new();
+
+    send(context, &[transfer(&payer, &funder.pubkey(), 1_000_000_000)], &[]).await?;
+    create_funded_ata(context, &funder.pubkey(), mint, USER_FUNDS).await?;
+
+    Ok(funder)
+}
+
+#[tokio::test]
+async fn funder_allowlist_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+    let gold = mints[0];
+
+    let funder = create_funder(&mut context, &gold).await?;
+
+    // anyone can fund until the allowlist is turned on
+    send(&mut context, &[fund_pool_ix(&program_id, &funder.pubkey(), &gold, 1_000)?], &[&funder]).await?;
+
+    send(&mut context, &[set_funder_allowlist_ix(&program_id, &payer, true)?], &[]).await?;
+
+    let account = context.banks_client.get_account(funder_allowlist_address(&program_id)).await?.unwrap();
+
+    assert!(FunderAllowlist::try_from_slice(&account.data)?.enabled);
+
+    let result = send(&mut context, &[fund_pool_ix(&program_id, &funder.pubkey(), &gold, 1_000)?], &[&funder]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::FunderNotApproved as u32));
+
+    // approving twice is the same as once
+    send(&mut context, &[add_funder_ix(&program_id, &payer, &funder.pubkey())?], &[]).await?;
+    send(&mut context, &[add_funder_ix(&program_id, &payer, &funder.pubkey())?], &[]).await?;
+
+    send(&mut context, &[fund_pool_ix(&program_id, &funder.pubkey(), &gold, 1_000)?], &[&funder]).await?;
+
+    let pool = pool_address(&program_id);
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING + 2_000);
+
+    // the admin isn't approved either, until it adds itself
+    let result = send(&mut context, &[fund_pool_ix(&program_id, &payer, &gold, 1_000)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::FunderNotApproved as u32));
+
+    send(&mut context, &[remove_funder_ix(&program_id, &payer, &funder.pubkey())?], &[]).await?;
+
+    assert!(context.banks_client.get_account(approved_funder_address(&program_id, &funder.pubkey())).await?.is_none());
+
+    let result = send(&mut context, &[fund_pool_ix(&program_id, &funder.pubkey(), &gold, 1_000)?], &[&funder]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::FunderNotApproved as u32));
+
+    // removing a funder who isn't there
+    let result = send(&mut context, &[remove_funder_ix(&program_id, &payer, &funder.pubkey())?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::FunderNotApproved as u32));
+
+    // turned off, funding is open again
+    send(&mut context, &[set_funder_allowlist_ix(&program_id, &payer, false)?], &[]).await?;
+    send(&mut context, &[fund_pool_ix(&program_id, &funder.pubkey(), &gold, 1_000)?], &[&funder]).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn funder_allowlist_batch_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+    let gold = mints[0];
+
+    let funder = create_funder(&mut context, &gold).await?;
+
+    send(&mut context, &[set_funder_allowlist_ix(&program_id, &payer, true)?], &[]).await?;
+
+    let result = send(
+        &mut context,
+        &[fund_pool_batch_ix(&program_id, &funder.pubkey(), &[(gold, 1_000)])?],
+        &[&funder],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::FunderNotApproved as u32));
+
+    send(&mut context, &[add_funder_ix(&program_id, &payer, &funder.pubkey())?], &[]).await?;
+    send(
+        &mut context,
+        &[fund_pool_batch_ix(&program_id, &funder.pubkey(), &[(gold, 1_000)])?],
+        &[&funder],
+    ).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn funder_allowlist_admin_only_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+
+    let attacker = create_funder(&mut context, &mints[0]).await?;
+
+    for instruction in [
+        set_funder_allowlist_ix(&program_id, &attacker.pubkey(), true)?,
+        add_funder_ix(&program_id, &attacker.pubkey(), &attacker.pubkey())?,
+        remove_funder_ix(&program_id, &attacker.pubkey(), &attacker.pubkey())?,
+    ] {
+        let result = send(&mut context, &[instruction], &[&attacker]).await;
+
+        assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+    }
+
+    assert!(context.banks_client.get_account(funder_allowlist_address(&program_id)).await?.is_none());
+
+    Ok(())
+}
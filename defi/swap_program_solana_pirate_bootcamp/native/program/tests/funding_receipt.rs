@@ -0,0 +1,80 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    borsh1::try_from_slice_unchecked,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+
+use program::state::FundingReceipt;
+
+use common::*;
+
+async fn receipt_state(context: &mut ProgramTestContext, receipt: &Pubkey) -> Result<FundingReceipt> {
+    let account = context.banks_client.get_account(*receipt).await?.unwrap();
+
+    Ok(try_from_slice_unchecked::<FundingReceipt>(&account.data)?)
+}
+
+#[tokio::test]
+async fn funding_receipt_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let receipt = funding_receipt_address(&program_id, &payer, &mints[0]);
+
+    let receipt_data = receipt_state(&mut context, &receipt).await?;
+
+    assert_eq!(receipt_data.funder, payer);
+    assert_eq!(receipt_data.mint, mints[0]);
+    assert_eq!(receipt_data.amount, INITIAL_FUNDING);
+
+    // later contributions add up
+    send(&mut context, &[fund_pool_ix(&program_id, &payer, &mints[0], 250_000)?], &[]).await?;
+
+    assert_eq!(receipt_state(&mut context, &receipt).await?.amount, INITIAL_FUNDING + 250_000);
+
+    // without touching the other mint's
+    let other = funding_receipt_address(&program_id, &payer, &mints[1]);
+
+    assert_eq!(receipt_state(&mut context, &other).await?.amount, INITIAL_FUNDING);
+
+    let account = context.banks_client.get_account(receipt).await?.unwrap();
+
+    assert_eq!(account.owner, program_id);
+    assert_eq!(account.data.len(), FundingReceipt::SPACE);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn funding_receipt_per_funder_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+
+    let funder = Keypair::new();
+
+    send(&mut context, &[transfer(&payer, &funder.pubkey(), 1_000_000_000)], &[]).await?;
+    create_funded_ata(&mut context, &funder.pubkey(), &mints[0], 500_000).await?;
+    send(&mut context, &[fund_pool_ix(&program_id, &funder.pubkey(), &mints[0], 500_000)?], &[&funder]).await?;
+
+    let receipt_data = receipt_state(&mut context, &funding_receipt_address(&program_id, &funder.pubkey(), &mints[0])).await?;
+
+    assert_eq!(receipt_data.funder, funder.pubkey());
+    assert_eq!(receipt_data.amount, 500_000);
+
+    let payer_receipt = funding_receipt_address(&program_id, &payer, &mints[0]);
+
+    assert_eq!(receipt_state(&mut context, &payer_receipt).await?.amount, INITIAL_FUNDING);
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use borsh::to_vec;
+
+use solana_program::program_error::ProgramError;
+
+use program::instruction::SwapInstruction;
+
+#[test]
+fn unpack_swap() {
+    let mut data = vec![2];
+    data.extend(to_vec(&(1_000u64, 990u64)).unwrap());
+
+    assert!(matches!(
+        SwapInstruction::unpack(&data),
+        Ok(SwapInstruction::Swap { amount_to_swap: 1_000, min_receive: 990 }),
+    ));
+
+    // a swap without a minimum, as sent before min_receive, is rejected
+    // rather than read as having none
+    assert!(matches!(
+        SwapInstruction::unpack(&data[..9]),
+        Err(ProgramError::InvalidInstructionData),
+    ));
+}
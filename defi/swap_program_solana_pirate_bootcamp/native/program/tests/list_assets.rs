@@ -0,0 +1,109 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::state::{AssetBalance, AssetList};
+
+use common::*;
+
+// Simulates the instruction and decodes the AssetList the program returns.
+async fn simulate_list_assets(context: &mut ProgramTestContext, instruction: Instruction) -> Result<AssetList> {
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let simulation = context.banks_client.simulate_transaction(tx).await?;
+
+    simulation.result.unwrap()?;
+
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+
+    Ok(decode_asset_list(&return_data.data)?)
+}
+
+#[tokio::test]
+async fn list_assets_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 3).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, 100_000_000, 0)?], &[]).await?;
+
+    let list = simulate_list_assets(&mut context, list_assets_ix(&program_id, &mints)).await?;
+
+    let pool = pool_address(&program_id);
+    let mut expected = vec![];
+
+    for mint in &mints {
+        let balance = token_balance(&mut context, &get_associated_token_address(&pool, mint)).await?;
+
+        expected.push(AssetBalance { mint: *mint, balance });
+    }
+
+    assert_eq!(list, AssetList { asset_count: 3, assets: expected.clone() });
+    assert_eq!(list.assets[1].balance, INITIAL_FUNDING + 100_000_000);
+
+    // a page of the pool, whose asset_count says there's more
+    let list = simulate_list_assets(&mut context, list_assets_ix(&program_id, &[rum])).await?;
+
+    assert_eq!(list, AssetList { asset_count: 3, assets: vec![expected[1]] });
+
+    // and of none, for just the count
+    let list = simulate_list_assets(&mut context, list_assets_ix(&program_id, &[])).await?;
+
+    assert_eq!(list, AssetList { asset_count: 3, assets: vec![] });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_assets_invalid_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let pool = pool_address(&program_id);
+
+    // a token account of the pool's that was never funded through FundPool
+    let stray = create_mint(&mut context).await?;
+
+    create_ata(&mut context, &pool, &stray).await?;
+
+    let result = send(&mut context, &[list_assets_ix(&program_id, &[mints[0], stray])], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::AssetNotInPool as u32));
+
+    // an asset's PoolAsset without its ATA
+    let mut instruction = list_assets_ix(&program_id, &mints);
+    instruction.accounts.pop();
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::InvalidArgument));
+
+    // one asset's PoolAsset with another's ATA
+    let mut instruction = list_assets_ix(&program_id, &mints);
+    instruction.accounts.swap(1, 3);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::InvalidSeeds));
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use proptest::prelude::*;
+
+use program::math::{
+    swap_receive_amount,
+    weighted_swap_receive_amount,
+    weighted_swap_pay_amount,
+    fee_amount,
+    executed_price,
+    PRICE_SCALE,
+};
+
+#[test]
+fn swap_receive_amount_of_known_values() {
+    // r = R * p / (P + p)
+    assert_eq!(swap_receive_amount(1_000, 1_000, 1_000), Some(500));
+    assert_eq!(swap_receive_amount(1_000, 3_000, 1_000), Some(250));
+    assert_eq!(swap_receive_amount(1_000, 1_000, 0), Some(0));
+    assert_eq!(swap_receive_amount(0, 1_000, 1_000), Some(0));
+
+    // rounds down, in the pool's favour
+    assert_eq!(swap_receive_amount(1_000, 2_000, 1), Some(0));
+    assert_eq!(swap_receive_amount(10, 3, 1), Some(2));
+}
+
+#[test]
+fn swap_receive_amount_never_drains_the_pool() {
+    for pay_amount in [1, 1_000, u64::MAX / 2, u64::MAX] {
+        let received = swap_receive_amount(u64::MAX, 1, pay_amount).unwrap();
+
+        assert!(received < u64::MAX);
+    }
+
+    assert_eq!(swap_receive_amount(0, 0, 0), None);
+}
+
+#[test]
+fn weighted_swap_receive_amount_of_known_values() {
+    // r = R * (1 - (P / (P + p))^(Wp / Wr)), rounded down: 875 exactly
+    assert_eq!(weighted_swap_receive_amount(1_000, 1_000, 1_000, 1, 3), Some(874));
+    // 206.3
+    assert_eq!(weighted_swap_receive_amount(1_000, 1_000, 1_000, 3, 1), Some(206));
+    assert_eq!(weighted_swap_receive_amount(1_000, 1_000, 0, 3, 1), Some(0));
+
+    // only the ratio of the weights matters
+    assert_eq!(weighted_swap_receive_amount(1_000, 3_000, 500, 200, 400), weighted_swap_receive_amount(1_000, 3_000, 500, 1, 2));
+
+    // and equal ones leave the unweighted formula
+    for weight in [1, 100, u16::MAX] {
+        assert_eq!(weighted_swap_receive_amount(1_000, 3_000, 1_000, weight, weight), swap_receive_amount(1_000, 3_000, 1_000));
+    }
+}
+
+#[test]
+fn weighted_swap_receive_amount_never_drains_the_pool() {
+    for pay_amount in [1, 1_000, u32::MAX as u64, u64::MAX] {
+        for (receive_weight, pay_weight) in [(1, u16::MAX), (u16::MAX, 1)] {
+            let received = weighted_swap_receive_amount(u64::MAX, 1, pay_amount, receive_weight, pay_weight).unwrap();
+
+            assert!(received < u64::MAX);
+        }
+    }
+
+    // nothing to price the pay asset against
+    assert_eq!(weighted_swap_receive_amount(1_000, 0, 1_000, 1, 3), None);
+}
+
+#[test]
+fn weighted_swap_pay_amount_of_known_values() {
+    // p = P * ((R / (R - r))^(Wr / Wp) - 1), rounded up
+    assert_eq!(weighted_swap_pay_amount(1_000, 1_000, 500, 1, 1), Some(1_000));
+    // 1_000 exactly
+    assert_eq!(weighted_swap_pay_amount(1_000, 1_000, 875, 1, 3), Some(1_001));
+    // 1_370.4
+    assert_eq!(weighted_swap_pay_amount(1_000, 1_000, 250, 3, 1), Some(1_371));
+
+    // rounds up, in the pool's favour
+    assert_eq!(weighted_swap_pay_amount(1_000, 1_000, 1, 1, 1), Some(2));
+
+    // never all of the receive asset
+    assert_eq!(weighted_swap_pay_amount(1_000, 1_000, 1_000, 1, 1), None);
+    assert_eq!(weighted_swap_pay_amount(1_000, 1_000, 1_001, 1, 3), None);
+}
+
+fn balance() -> impl Strategy<Value = u64> {
+    1..=1u64 << 40
+}
+
+fn weight() -> impl Strategy<Value = u16> {
+    1..=u16::MAX
+}
+
+proptest! {
+    // Wp / Wr = 2 is exact in integers: r = R * ((P + p)^2 - P^2) / (P + p)^2
+    #[test]
+    fn weighted_swap_receive_amount_is_at_most_exact(
+        receive_balance in 1..=u32::MAX as u64,
+        pay_balance in 1..=u32::MAX as u64,
+        pay_amount in 0..=u32::MAX as u64,
+    ) {
+        let after = (pay_balance + pay_amount) as u128;
+        let exact = receive_balance as u128 * (after * after - pay_balance as u128 * pay_balance as u128) / (after * after);
+
+        let received = weighted_swap_receive_amount(receive_balance, pay_balance, pay_amount, 1, 2).unwrap() as u128;
+
+        prop_assert!(received <= exact);
+        prop_assert!(exact - received <= (receive_balance as u128 >> 30) + 1);
+    }
+
+    // Wr / Wp = 2: p = P * (R^2 - (R - r)^2) / (R - r)^2, rounded up
+    #[test]
+    fn weighted_swap_pay_amount_is_at_least_exact(
+        receive_balance in 2..=u32::MAX as u64,
+        pay_balance in 1..=u32::MAX as u64,
+        // short of draining the pool, where the power is too small for the fixed point
+        receive_fraction in 0.0..0.99998f64,
+    ) {
+        let receive_amount = (receive_fraction * (receive_balance - 1) as f64) as u64;
+
+        let remaining = (receive_balance - receive_amount) as u128;
+        let exact = (pay_balance as u128 * (receive_balance as u128 * receive_balance as u128 - remaining * remaining))
+            .div_ceil(remaining * remaining);
+
+        let paid = weighted_swap_pay_amount(receive_balance, pay_balance, receive_amount, 2, 1).unwrap() as u128;
+
+        prop_assert!(paid >= exact);
+        prop_assert!(paid - exact <= ((pay_balance as u128 + exact) >> 24) + 1);
+    }
+
+    #[test]
+    fn splitting_a_swap_never_receives_more(
+        receive_balance in balance(),
+        pay_balance in balance(),
+        first_pay_amount in balance(),
+        second_pay_amount in balance(),
+        receive_weight in weight(),
+        pay_weight in weight(),
+    ) {
+        let whole = weighted_swap_receive_amount(
+            receive_balance, pay_balance, first_pay_amount + second_pay_amount, receive_weight, pay_weight,
+        ).unwrap();
+
+        let first = weighted_swap_receive_amount(
+            receive_balance, pay_balance, first_pay_amount, receive_weight, pay_weight,
+        ).unwrap();
+        let second = weighted_swap_receive_amount(
+            receive_balance - first, pay_balance + first_pay_amount, second_pay_amount, receive_weight, pay_weight,
+        ).unwrap();
+
+        prop_assert!(first + second <= whole);
+    }
+
+    #[test]
+    fn swapping_there_and_back_never_profits(
+        receive_balance in balance(),
+        pay_balance in balance(),
+        pay_amount in balance(),
+        receive_weight in weight(),
+        pay_weight in weight(),
+    ) {
+        let received = weighted_swap_receive_amount(receive_balance, pay_balance, pay_amount, receive_weight, pay_weight).unwrap();
+
+        let returned = weighted_swap_receive_amount(
+            pay_balance + pay_amount, receive_balance - received, received, pay_weight, receive_weight,
+        ).unwrap();
+
+        prop_assert!(returned <= pay_amount);
+    }
+}
+
+#[test]
+fn fee_amount_of_known_values() {
+    assert_eq!(fee_amount(1_000_000, 30), Some(3_000));
+    assert_eq!(fee_amount(1_000_000, 0), Some(0));
+    assert_eq!(fee_amount(1_000_000, 10_000), Some(1_000_000));
+
+    // rounds down, in the swapper's favour
+    assert_eq!(fee_amount(333, 30), Some(0));
+    assert_eq!(fee_amount(u64::MAX, 10_000), Some(u64::MAX));
+}
+
+#[test]
+fn executed_price_of_known_values() {
+    assert_eq!(executed_price(1_000, 1_000), Some(PRICE_SCALE));
+    assert_eq!(executed_price(1_000, 2_000), Some(2 * PRICE_SCALE));
+    assert_eq!(executed_price(3, 1), Some(333_333_333));
+
+    // u64 amounts can't overflow the scaled u128
+    assert_eq!(executed_price(1, u64::MAX), Some(u64::MAX as u128 * PRICE_SCALE));
+    assert_eq!(executed_price(0, 1_000), None);
+}
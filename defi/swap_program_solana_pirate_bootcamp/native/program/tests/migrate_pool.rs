@@ -0,0 +1,163 @@
+mod common;
+
+use anyhow::Result;
+
+use borsh::to_vec;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::SwapProgramError;
+use program::state::{LiquidityPool, LiquidityPoolV1, LiquidityPoolV2, PricingMode};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Rewrites the pool as it was stored before PoolAsset, with the assets and
+// their fees inline, and drops their PoolAsset accounts.
+async fn downgrade_pool(context: &mut ProgramTestContext, program_id: &Pubkey, mints: &[Pubkey]) -> Result<Vec<u64>> {
+    let pool = pool_address(program_id);
+    let pool_data = pool_state(context, &pool).await?;
+
+    let mut collected_fees = vec![];
+
+    for mint in mints {
+        collected_fees.push(pool_asset_state(context, program_id, mint).await?.collected_fees);
+        context.set_account(&pool_asset_address(program_id, mint), &AccountSharedData::default());
+    }
+
+    let pool_v1 = LiquidityPoolV1 {
+        assets: mints.to_vec(),
+        collected_fees: collected_fees.clone(),
+        admin: pool_data.admin,
+        paused: pool_data.paused,
+        fee_bps: pool_data.fee_bps,
+        bump: pool_data.bump,
+    };
+
+    let mut account: Account = context.banks_client.get_account(pool).await?.unwrap();
+
+    account.data = to_vec(&pool_v1)?;
+    account.lamports = context.banks_client.get_rent().await?.minimum_balance(account.data.len());
+    context.set_account(&pool, &AccountSharedData::from(account));
+
+    Ok(collected_fees)
+}
+
+#[tokio::test]
+async fn migrate_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool_with_fee(&mut context, &program_id, 2, FEE_BPS).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pool = pool_address(&program_id);
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, 100_000_000, 0)?], &[]).await?;
+
+    let collected_fees = downgrade_pool(&mut context, &program_id, &mints).await?;
+
+    assert!(collected_fees[0] > 0);
+
+    // nothing runs on the old layout
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, 1_000_000, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::PoolNeedsMigration as u32));
+
+    let attacker = Keypair::new();
+
+    let result = send(&mut context, &[migrate_pool_ix(&program_id, &attacker.pubkey(), &mints)], &[&attacker]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+
+    let pool_lamports_before = context.banks_client.get_balance(pool).await?;
+
+    send(&mut context, &[migrate_pool_ix(&program_id, &payer, &mints)], &[]).await?;
+
+    let account = context.banks_client.get_account(pool).await?.unwrap();
+
+    assert_eq!(account.data.len(), LiquidityPool::SPACE);
+    assert!(account.lamports < pool_lamports_before);
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.version, LiquidityPool::VERSION);
+    assert_eq!(pool_data.admin, payer);
+    assert_eq!(pool_data.fee_bps, FEE_BPS);
+    assert_eq!(pool_data.asset_count, 2);
+
+    for (mint, fees) in mints.iter().zip(&collected_fees) {
+        let pool_asset = pool_asset_state(&mut context, &program_id, mint).await?;
+
+        assert_eq!(pool_asset.mint, *mint);
+        assert_eq!(pool_asset.collected_fees, *fees);
+    }
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, 1_000_000, 0)?], &[]).await?;
+
+    let result = send(&mut context, &[migrate_pool_ix(&program_id, &payer, &mints)], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::PoolAlreadyCurrent as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_v2_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool_with_fee(&mut context, &program_id, 2, FEE_BPS).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pool = pool_address(&program_id);
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    // as stored before the pricing mode, with its assets already on PoolAsset
+    let pool_v2 = LiquidityPoolV2 {
+        version: LiquidityPoolV2::VERSION,
+        admin: pool_data.admin,
+        paused: pool_data.paused,
+        fee_bps: pool_data.fee_bps,
+        asset_count: pool_data.asset_count,
+        bump: pool_data.bump,
+    };
+
+    let rent = context.banks_client.get_rent().await?;
+    let mut account: Account = context.banks_client.get_account(pool).await?.unwrap();
+
+    account.data = to_vec(&pool_v2)?;
+    account.lamports = rent.minimum_balance(LiquidityPoolV2::SPACE);
+    context.set_account(&pool, &AccountSharedData::from(account));
+
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, 1_000_000, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::PoolNeedsMigration as u32));
+
+    // growing the pool by the pricing mode is paid for by the admin
+    send(&mut context, &[migrate_pool_ix(&program_id, &payer, &[])], &[]).await?;
+
+    let account = context.banks_client.get_account(pool).await?.unwrap();
+
+    assert_eq!(account.data.len(), LiquidityPool::SPACE);
+    assert_eq!(account.lamports, rent.minimum_balance(LiquidityPool::SPACE));
+
+    let pool_data = pool_state(&mut context, &pool).await?;
+
+    assert_eq!(pool_data.version, LiquidityPool::VERSION);
+    assert_eq!(pool_data.fee_bps, FEE_BPS);
+    assert_eq!(pool_data.asset_count, 2);
+    assert_eq!(pool_data.pricing, PricingMode::Balances);
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, 1_000_000, 0)?], &[]).await?;
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+mod common;
+
+use anyhow::Result;
+
+use borsh::BorshDeserialize;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::pyth::{
+    PYTH_MAGIC,
+    PYTH_VERSION,
+    PYTH_PRICE_ACCOUNT_TYPE,
+    PYTH_STATUS_TRADING,
+    PYTH_PRICE_ACCOUNT_LEN,
+    MAX_ORACLE_AGE,
+};
+use program::state::{AssetOracle, PricingMode};
+
+use common::*;
+
+// 1.0 with Pyth's usual 8 decimal places
+const PRICE_ONE: i64 = 100_000_000;
+const EXPO: i32 = -8;
+
+// A Pyth v2 price account holding just the header and aggregate price.
+fn pyth_price_data(price: i64, conf: u64, publish_time: i64) -> Vec<u8> {
+    let mut data = vec![0; PYTH_PRICE_ACCOUNT_LEN];
+
+    data[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+    data[4..8].copy_from_slice(&PYTH_VERSION.to_le_bytes());
+    data[8..12].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+    data[12..16].copy_from_slice(&(PYTH_PRICE_ACCOUNT_LEN as u32).to_le_bytes());
+    data[20..24].copy_from_slice(&EXPO.to_le_bytes());
+    data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+
+    data
+}
+
+// Writes a mocked price account at `price_feed`, published `age` seconds ago.
+async fn set_price(
+    context: &mut ProgramTestContext,
+    price_feed: &Pubkey,
+    price: i64,
+    conf: u64,
+    age: i64,
+) -> Result<()> {
+    let now = context.banks_client.get_sysvar::<Clock>().await?.unix_timestamp;
+
+    context.set_account(
+        price_feed,
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000_000,
+            data: pyth_price_data(price, conf, now - age),
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        }),
+    );
+
+    Ok(())
+}
+
+// A two-asset pool priced off oracles, gold at 2.0 and rum at 1.0 though
+// the pool holds as much of one as of the other.
+// Returns ((gold, gold_feed), (rum, rum_feed)).
+async fn setup_oracle_pool(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+) -> Result<((Pubkey, Pubkey), (Pubkey, Pubkey))> {
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(context, program_id, 2).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+    let (gold_feed, rum_feed) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    set_price(context, &gold_feed, 2 * PRICE_ONE, 0, 0).await?;
+    set_price(context, &rum_feed, PRICE_ONE, 0, 0).await?;
+
+    send(
+        context,
+        &[
+            set_asset_oracle_ix(program_id, &payer, &gold, &gold_feed),
+            set_asset_oracle_ix(program_id, &payer, &rum, &rum_feed),
+            set_pricing_mode_ix(program_id, &payer, PricingMode::Oracle)?,
+        ],
+        &[],
+    ).await?;
+
+    Ok(((gold, gold_feed), (rum, rum_feed)))
+}
+
+fn oracle_swap_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    (receive_mint, receive_price_feed): (&Pubkey, &Pubkey),
+    (pay_mint, pay_price_feed): (&Pubkey, &Pubkey),
+    amount_to_swap: u64,
+) -> Result<Instruction> {
+    let mut instruction = swap_ix(program_id, payer, receive_mint, pay_mint, amount_to_swap, 0)?;
+
+    instruction.accounts.extend(oracle_accounts(
+        program_id,
+        (receive_mint, receive_price_feed),
+        (pay_mint, pay_price_feed),
+    ));
+
+    Ok(instruction)
+}
+
+#[tokio::test]
+async fn oracle_swap_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let ((gold, gold_feed), (rum, rum_feed)) = setup_oracle_pool(&mut context, &program_id).await?;
+
+    let pool = pool_address(&program_id);
+
+    assert_eq!(pool_state(&mut context, &pool).await?.pricing, PricingMode::Oracle);
+
+    let account = context.banks_client.get_account(asset_oracle_address(&program_id, &gold)).await?.unwrap();
+    let asset_oracle = AssetOracle::try_from_slice(&account.data)?;
+
+    assert_eq!(asset_oracle.mint, gold);
+    assert_eq!(asset_oracle.price_feed, gold_feed);
+    assert_eq!(asset_oracle.decimals, DECIMALS);
+
+    // 100 rum at 1.0 buys 50 gold at 2.0, where the balances would give ~90
+    let amount_to_swap = 100_000_000;
+
+    send(
+        &mut context,
+        &[oracle_swap_ix(&program_id, &payer, (&gold, &gold_feed), (&rum, &rum_feed), amount_to_swap)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING - 50_000_000);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &rum)).await?, INITIAL_FUNDING + amount_to_swap);
+
+    // the price doesn't move with the balances, only with the oracle
+    set_price(&mut context, &gold_feed, 4 * PRICE_ONE, 0, 0).await?;
+
+    send(
+        &mut context,
+        &[oracle_swap_ix(&program_id, &payer, (&gold, &gold_feed), (&rum, &rum_feed), amount_to_swap)?],
+        &[],
+    ).await?;
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING - 75_000_000);
+
+    // more than the pool holds
+    let result = send(
+        &mut context,
+        &[oracle_swap_ix(&program_id, &payer, (&rum, &rum_feed), (&gold, &gold_feed), INITIAL_FUNDING)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidSwapNotEnoughLiquidity as u32));
+
+    // and back to the balances
+    send(&mut context, &[set_pricing_mode_ix(&program_id, &payer, PricingMode::Balances)?], &[]).await?;
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, 0)?], &[]).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn oracle_swap_bad_price_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let ((gold, gold_feed), (rum, rum_feed)) = setup_oracle_pool(&mut context, &program_id).await?;
+
+    for (conf, age, error) in [
+        (0, MAX_ORACLE_AGE + 10, SwapProgramError::OracleStale),
+        (PRICE_ONE as u64 / 10, 0, SwapProgramError::OracleConfidenceTooWide),
+    ] {
+        set_price(&mut context, &rum_feed, PRICE_ONE, conf, age).await?;
+
+        let result = send(
+            &mut context,
+            &[oracle_swap_ix(&program_id, &payer, (&gold, &gold_feed), (&rum, &rum_feed), 1_000_000)?],
+            &[],
+        ).await;
+
+        assert_eq!(custom_error(result), Some(error as u32));
+    }
+
+    set_price(&mut context, &rum_feed, PRICE_ONE, 0, 0).await?;
+
+    // a price account that isn't the one the asset was set up with
+    let other_feed = Pubkey::new_unique();
+
+    set_price(&mut context, &other_feed, PRICE_ONE, 0, 0).await?;
+
+    let result = send(
+        &mut context,
+        &[oracle_swap_ix(&program_id, &payer, (&gold, &gold_feed), (&rum, &other_feed), 1_000_000)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::OracleMismatch as u32));
+
+    // without the oracle accounts at all
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, 1_000_000, 0)?], &[]).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::NotEnoughAccountKeys));
+
+    send(
+        &mut context,
+        &[oracle_swap_ix(&program_id, &payer, (&gold, &gold_feed), (&rum, &rum_feed), 1_000_000)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn oracle_swap_asset_without_oracle_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let ((gold, gold_feed), (rum, rum_feed)) = setup_oracle_pool(&mut context, &program_id).await?;
+
+    // funded after the pool went over to oracle prices, and never given one
+    let cannons = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &cannons, USER_FUNDS).await?;
+    send(&mut context, &[fund_pool_ix(&program_id, &payer, &cannons, INITIAL_FUNDING)?], &[]).await?;
+
+    let result = send(
+        &mut context,
+        &[oracle_swap_ix(&program_id, &payer, (&gold, &gold_feed), (&cannons, &gold_feed), 1_000_000)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::OracleNotSet as u32));
+
+    // rum still trades
+    send(
+        &mut context,
+        &[oracle_swap_ix(&program_id, &payer, (&gold, &gold_feed), (&rum, &rum_feed), 1_000_000)?],
+        &[],
+    ).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn oracle_admin_only_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+    let price_feed = Pubkey::new_unique();
+
+    set_price(&mut context, &price_feed, PRICE_ONE, 0, 0).await?;
+
+    let attacker = Keypair::new();
+
+    for instruction in [
+        set_pricing_mode_ix(&program_id, &attacker.pubkey(), PricingMode::Oracle)?,
+        set_asset_oracle_ix(&program_id, &attacker.pubkey(), &mints[0], &price_feed),
+    ] {
+        let result = send(&mut context, &[instruction], &[&attacker]).await;
+
+        assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+    }
+
+    assert_eq!(pool_state(&mut context, &pool_address(&program_id)).await?.pricing, PricingMode::Balances);
+
+    // only a Pyth price account will do
+    let result = send(
+        &mut context,
+        &[set_asset_oracle_ix(&program_id, &payer, &mints[0], &mints[0])],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidOracle as u32));
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+use program::math::{swap_receive_amount, executed_price};
+use program::state::PairPrice;
+
+use common::*;
+
+// What a swap paying `paid` of `pay_mint` for `received` should record.
+fn recorded_price(mint_a: &Pubkey, pay_mint: &Pubkey, paid: u64, received: u64) -> Option<u128> {
+    if mint_a == pay_mint {
+        executed_price(paid, received)
+    } else {
+        executed_price(received, paid)
+    }
+}
+
+#[tokio::test]
+async fn pair_price_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pair_price = pair_price_address(&program_id, &gold, &rum);
+
+    assert_eq!(pair_price, pair_price_address(&program_id, &rum, &gold));
+    assert!(context.banks_client.get_account(pair_price).await?.is_none());
+
+    let amount_to_swap = 100_000_000;
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, 0)?], &[]).await?;
+
+    let received = swap_receive_amount(INITIAL_FUNDING, INITIAL_FUNDING, amount_to_swap).unwrap();
+
+    let account = context.banks_client.get_account(pair_price).await?.unwrap();
+    let first = decode_pair_price(&account.data)?;
+
+    let (mint_a, mint_b) = PairPrice::sort_mints(&gold, &rum);
+
+    assert_eq!((&first.mint_a, &first.mint_b), (mint_a, mint_b));
+    assert_eq!(first.price_of(&rum), Some(received as f64 / amount_to_swap as f64));
+
+    assert_eq!(Some(first.price), recorded_price(mint_a, &rum, amount_to_swap, received));
+
+    // the other direction overwrites it
+    context.warp_to_slot(first.slot + 100)?;
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &rum, &gold, received, 0)?], &[]).await?;
+
+    let account = context.banks_client.get_account(pair_price).await?.unwrap();
+    let second = decode_pair_price(&account.data)?;
+
+    let received_back = swap_receive_amount(INITIAL_FUNDING + amount_to_swap, INITIAL_FUNDING - received, received).unwrap();
+
+    assert!(second.slot >= first.slot + 100);
+    assert_eq!(Some(second.price), recorded_price(mint_a, &gold, received, received_back));
+
+    Ok(())
+}
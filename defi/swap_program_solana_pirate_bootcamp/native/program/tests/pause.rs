@@ -0,0 +1,59 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use program::error::SwapProgramError;
+
+use common::*;
+
+#[tokio::test]
+async fn pause_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let pool = pool_address(&program_id);
+
+    // whoever creates the pool administers it
+    assert_eq!(pool_state(&mut context, &pool).await?.admin, payer);
+
+    send(&mut context, &[set_paused_ix(&program_id, &payer, true)?], &[]).await?;
+
+    assert!(pool_state(&mut context, &pool).await?.paused);
+
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &mints[0], &mints[1], 1_000_000, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::PoolPaused as u32));
+
+    let result = send(&mut context, &[fund_pool_ix(&program_id, &payer, &mints[0], 1_000_000)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::PoolPaused as u32));
+
+    send(&mut context, &[set_paused_ix(&program_id, &payer, false)?], &[]).await?;
+    send(&mut context, &[swap_ix(&program_id, &payer, &mints[0], &mints[1], 1_000_000, 0)?], &[]).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pause_not_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    setup_pool(&mut context, &program_id, 2).await?;
+
+    let attacker = Keypair::new();
+
+    let result = send(&mut context, &[set_paused_ix(&program_id, &attacker.pubkey(), true)?], &[&attacker]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+    assert!(!pool_state(&mut context, &pool_address(&program_id)).await?.paused);
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use solana_program::program_error::ProgramError;
+
+use program::error::SwapProgramError;
+use program::pyth::{
+    PythPrice,
+    PYTH_MAGIC,
+    PYTH_VERSION,
+    PYTH_PRICE_ACCOUNT_TYPE,
+    PYTH_STATUS_TRADING,
+    PYTH_PRICE_ACCOUNT_LEN,
+    MAX_ORACLE_AGE,
+    oracle_receive_amount,
+    oracle_pay_amount,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+fn price(price: i64, expo: i32) -> PythPrice {
+    PythPrice {
+        price,
+        conf: 0,
+        expo,
+        publish_time: NOW,
+        status: PYTH_STATUS_TRADING,
+    }
+}
+
+#[test]
+fn load_reads_the_aggregate_price() {
+    let mut data = vec![0; PYTH_PRICE_ACCOUNT_LEN];
+
+    data[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+    data[4..8].copy_from_slice(&PYTH_VERSION.to_le_bytes());
+    data[8..12].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+    data[20..24].copy_from_slice(&(-8i32).to_le_bytes());
+    data[96..104].copy_from_slice(&NOW.to_le_bytes());
+    data[208..216].copy_from_slice(&150_000_000i64.to_le_bytes());
+    data[216..224].copy_from_slice(&1_000u64.to_le_bytes());
+    data[224..228].copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+
+    assert_eq!(
+        PythPrice::load(&data).unwrap(),
+        PythPrice { price: 150_000_000, conf: 1_000, expo: -8, publish_time: NOW, status: PYTH_STATUS_TRADING },
+    );
+
+    // anything else, like a product account, is rejected
+    data[8..12].copy_from_slice(&2u32.to_le_bytes());
+
+    assert_eq!(PythPrice::load(&data), Err(SwapProgramError::InvalidOracle.into()));
+    assert_eq!(PythPrice::load(&data[..100]), Err(SwapProgramError::InvalidOracle.into()));
+}
+
+#[test]
+fn checked_rejects_stale_and_uncertain_prices() {
+    let fresh = price(100_000_000, -8);
+
+    assert!(fresh.checked(NOW + MAX_ORACLE_AGE).is_ok());
+    assert_eq!(fresh.checked(NOW + MAX_ORACLE_AGE + 1), Err(ProgramError::from(SwapProgramError::OracleStale)));
+
+    // 2% of the price is the widest interval allowed
+    let wide = PythPrice { conf: 2_000_001, ..fresh };
+
+    assert!(PythPrice { conf: 2_000_000, ..fresh }.checked(NOW).is_ok());
+    assert_eq!(wide.checked(NOW), Err(ProgramError::from(SwapProgramError::OracleConfidenceTooWide)));
+
+    let halted = PythPrice { status: 0, ..fresh };
+
+    assert_eq!(halted.checked(NOW), Err(ProgramError::from(SwapProgramError::InvalidOracle)));
+    assert_eq!(price(0, -8).checked(NOW), Err(ProgramError::from(SwapProgramError::InvalidOracle)));
+}
+
+#[test]
+fn oracle_receive_amount_of_known_values() {
+    let (two, one) = (price(200_000_000, -8), price(100_000_000, -8));
+
+    // 100 tokens at 1.00 buy 50 tokens at 2.00, whatever the pool holds
+    assert_eq!(oracle_receive_amount(100_000_000, (&one, 6), (&two, 6)), Some(50_000_000));
+    assert_eq!(oracle_receive_amount(50_000_000, (&two, 6), (&one, 6)), Some(100_000_000));
+
+    // decimals and exponents scale it
+    assert_eq!(oracle_receive_amount(100_000_000, (&one, 6), (&two, 9)), Some(50_000_000_000));
+    assert_eq!(oracle_receive_amount(100_000_000, (&one, 9), (&two, 6)), Some(50_000));
+    assert_eq!(oracle_receive_amount(100_000_000, (&price(1, 0), 6), (&two, 6)), Some(50_000_000));
+
+    // rounds down, in the pool's favour
+    assert_eq!(oracle_receive_amount(1, (&one, 6), (&two, 6)), Some(0));
+    assert_eq!(oracle_receive_amount(3, (&one, 6), (&two, 6)), Some(1));
+}
+
+#[test]
+fn oracle_pay_amount_of_known_values() {
+    let (two, one) = (price(200_000_000, -8), price(100_000_000, -8));
+
+    // 50 tokens at 2.00 cost 100 tokens at 1.00
+    assert_eq!(oracle_pay_amount(50_000_000, (&one, 6), (&two, 6)), Some(100_000_000));
+    assert_eq!(oracle_pay_amount(100_000_000, (&two, 6), (&one, 6)), Some(50_000_000));
+    assert_eq!(oracle_pay_amount(50_000_000_000, (&one, 6), (&two, 9)), Some(100_000_000));
+
+    // rounds up, in the pool's favour
+    assert_eq!(oracle_pay_amount(1, (&two, 6), (&one, 6)), Some(1));
+    assert_eq!(oracle_pay_amount(3, (&two, 6), (&one, 6)), Some(2));
+}
+
+#[test]
+fn oracle_receive_amount_overflow() {
+    let (cheap, dear) = (price(1, -8), price(i64::MAX, -8));
+
+    assert_eq!(oracle_receive_amount(u64::MAX, (&dear, 6), (&cheap, 6)), None);
+    assert_eq!(oracle_receive_amount(1, (&cheap, 0), (&cheap, 255)), None);
+    assert_eq!(oracle_receive_amount(1, (&price(0, -8), 6), (&cheap, 6)), None);
+    assert_eq!(oracle_receive_amount(u64::MAX, (&cheap, 6), (&dear, 6)), Some(2));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::math::{swap_receive_amount, fee_amount};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+// Simulates the instruction and decodes the u64 the program returns.
+async fn simulate_quote(context: &mut ProgramTestContext, instruction: Instruction) -> Result<u64> {
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let simulation = context.banks_client.simulate_transaction(tx).await?;
+
+    simulation.result.unwrap()?;
+
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+
+    Ok(u64::from_le_bytes(return_data.data.as_slice().try_into()?))
+}
+
+#[tokio::test]
+async fn quote_matches_swap_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool_with_fee(&mut context, &program_id, 2, FEE_BPS).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let amount = 100_000_000;
+
+    let quoted = simulate_quote(&mut context, quote_ix(&program_id, &rum, &gold, amount)?).await?;
+
+    let gross = swap_receive_amount(INITIAL_FUNDING, INITIAL_FUNDING, amount).unwrap();
+
+    assert_eq!(quoted, gross - fee_amount(gross, FEE_BPS).unwrap());
+
+    // quoting moves nothing
+    let payer_ata_gold = get_associated_token_address(&payer, &gold);
+
+    assert_eq!(token_balance(&mut context, &payer_ata_gold).await?, USER_FUNDS - INITIAL_FUNDING);
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount, 0)?], &[]).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata_gold).await?, USER_FUNDS - INITIAL_FUNDING + quoted);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quote_invalid_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let result = send(&mut context, &[quote_ix(&program_id, &rum, &gold, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidSwapZeroAmount as u32));
+
+    let result = send(&mut context, &[quote_ix(&program_id, &gold, &gold, 1_000)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidSwapMatchingAssets as u32));
+
+    send(&mut context, &[set_paused_ix(&program_id, &payer, true)?], &[]).await?;
+
+    let result = send(&mut context, &[quote_ix(&program_id, &rum, &gold, 1_000)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::PoolPaused as u32));
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::math::weighted_swap_pay_amount;
+use program::state::PoolAsset;
+
+use common::*;
+
+#[tokio::test]
+async fn rebalance_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool_with_fee(&mut context, &program_id, 2, 30).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pool = pool_address(&program_id);
+
+    // the admin rebalances even with trading stopped
+    send(&mut context, &[set_paused_ix(&program_id, &payer, true)?], &[]).await?;
+
+    let amount = 100_000_000;
+
+    send(&mut context, &[rebalance_ix(&program_id, &payer, &gold, &rum, amount)?], &[]).await?;
+
+    // priced like a swap for exactly `amount`, with no fee
+    let paid = weighted_swap_pay_amount(
+        INITIAL_FUNDING,
+        INITIAL_FUNDING,
+        amount,
+        PoolAsset::DEFAULT_WEIGHT,
+        PoolAsset::DEFAULT_WEIGHT,
+    ).unwrap();
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING - amount);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &rum)).await?, INITIAL_FUNDING + paid);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &gold)).await?, USER_FUNDS - INITIAL_FUNDING + amount);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &rum)).await?, USER_FUNDS - INITIAL_FUNDING - paid);
+
+    // and kept apart from the swap bookkeeping
+    for mint in &mints {
+        let pool_asset = pool_asset_state(&mut context, &program_id, mint).await?;
+
+        assert_eq!(pool_asset.collected_fees, 0);
+        assert_eq!((pool_asset.pay_volume, pool_asset.receive_volume), (0, 0));
+    }
+
+    // the pool always keeps some of an asset
+    let result = send(&mut context, &[rebalance_ix(&program_id, &payer, &gold, &rum, INITIAL_FUNDING - amount)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidSwapNotEnoughLiquidity as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rebalance_not_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pool = pool_address(&program_id);
+
+    // someone with rum of their own, trying to take gold out at the pool's price
+    let attacker = Keypair::new();
+
+    create_funded_ata(&mut context, &attacker.pubkey(), &rum, USER_FUNDS).await?;
+    create_ata(&mut context, &attacker.pubkey(), &gold).await?;
+
+    let result = send(
+        &mut context,
+        &[rebalance_ix(&program_id, &attacker.pubkey(), &gold, &rum, 1_000_000)?],
+        &[&attacker],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING);
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use common::*;
+
+#[tokio::test]
+async fn remove_asset_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let pool = pool_address(&program_id);
+
+    // listed, but never stocked
+    let cannons = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &cannons, USER_FUNDS).await?;
+    send(&mut context, &[fund_pool_ix(&program_id, &payer, &cannons, 0)?], &[]).await?;
+
+    let pool_ata = get_associated_token_address(&pool, &cannons);
+    let pool_asset = pool_asset_address(&program_id, &cannons);
+
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count, 3);
+
+    send(&mut context, &[remove_asset_ix(&program_id, &payer, &cannons)?], &[]).await?;
+
+    assert!(context.banks_client.get_account(pool_ata).await?.is_none());
+    assert!(context.banks_client.get_account(pool_asset).await?.is_none());
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count, 2);
+
+    // the rest are untouched
+    for mint in &mints {
+        assert_eq!(pool_asset_state(&mut context, &program_id, mint).await?.mint, *mint);
+    }
+
+    // and it can be listed again
+    send(&mut context, &[fund_pool_ix(&program_id, &payer, &cannons, 1_000_000)?], &[]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.asset_count, 3);
+    assert_eq!(pool_asset_state(&mut context, &program_id, &cannons).await?.mint, cannons);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn remove_stocked_asset_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+
+    let result = send(&mut context, &[remove_asset_ix(&program_id, &payer, &mints[0])?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::PoolAssetNotEmpty as u32));
+    assert_eq!(pool_state(&mut context, &pool_address(&program_id)).await?.asset_count, 2);
+    assert_eq!(pool_asset_state(&mut context, &program_id, &mints[0]).await?.mint, mints[0]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn remove_unknown_asset_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    setup_pool(&mut context, &program_id, 2).await?;
+
+    let result = send(&mut context, &[remove_asset_ix(&program_id, &payer, &Pubkey::new_unique())?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::AssetNotInPool as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn remove_asset_not_admin_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+
+    let attacker = Keypair::new();
+
+    let result = send(&mut context, &[remove_asset_ix(&program_id, &attacker.pubkey(), &mints[0])?], &[&attacker]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use borsh::to_vec;
+
+use solana_program::{program::MAX_RETURN_DATA, pubkey::Pubkey};
+
+use program::math::PRICE_SCALE;
+use program::state::{
+    ApprovedFunder,
+    AssetBalance,
+    AssetList,
+    AssetOracle,
+    FunderAllowlist,
+    FundingReceipt,
+    LiquidityPool,
+    LiquidityPoolV1,
+    LiquidityPoolV2,
+    PairPrice,
+    PoolAsset,
+    PricingMode,
+};
+
+#[test]
+fn pool_space_matches_layout() {
+    let pool = LiquidityPool {
+        version: LiquidityPool::VERSION,
+        admin: Pubkey::new_unique(),
+        paused: false,
+        fee_bps: 30,
+        asset_count: 0,
+        pricing: PricingMode::Oracle,
+        bump: 255,
+    };
+
+    let data = to_vec(&pool).unwrap();
+
+    assert_eq!(data.len(), LiquidityPool::SPACE);
+    assert!(LiquidityPool::load(&data).is_ok());
+}
+
+#[test]
+fn pool_asset_space_matches_layout() {
+    let pool_asset = PoolAsset {
+        mint: Pubkey::new_unique(),
+        collected_fees: u64::MAX,
+        pay_volume: u128::MAX,
+        receive_volume: u128::MAX,
+        weight: u16::MAX,
+        bump: 255,
+    };
+
+    assert_eq!(to_vec(&pool_asset).unwrap().len(), PoolAsset::SPACE);
+}
+
+#[test]
+fn pair_price_space_matches_layout() {
+    let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mint_a, mint_b) = PairPrice::sort_mints(&mint_y, &mint_x);
+
+    assert!(mint_a < mint_b);
+    assert_eq!(PairPrice::sort_mints(&mint_x, &mint_y), (mint_a, mint_b));
+
+    let pair_price = PairPrice {
+        mint_a: *mint_a,
+        mint_b: *mint_b,
+        price: 4 * PRICE_SCALE,
+        slot: u64::MAX,
+        bump: 255,
+    };
+
+    assert_eq!(to_vec(&pair_price).unwrap().len(), PairPrice::SPACE);
+
+    assert_eq!(pair_price.price_of(mint_a), Some(4.0));
+    assert_eq!(pair_price.price_of(mint_b), Some(0.25));
+    assert_eq!(pair_price.price_of(&Pubkey::new_unique()), None);
+}
+
+#[test]
+fn v1_pool_needs_migration() {
+    let mut pool = LiquidityPoolV1 {
+        assets: vec![],
+        collected_fees: vec![],
+        admin: Pubkey::new_unique(),
+        paused: true,
+        fee_bps: 30,
+        bump: 255,
+    };
+
+    assert_eq!(to_vec(&pool).unwrap().len(), LiquidityPoolV1::SPACE);
+
+    pool.assets.push(Pubkey::new_unique());
+    pool.collected_fees.push(u64::MAX);
+
+    let data = to_vec(&pool).unwrap();
+
+    assert_eq!(data.len(), LiquidityPoolV1::SPACE + LiquidityPoolV1::ASSET_SPACE);
+    assert!(LiquidityPool::load(&data).is_err());
+
+    let migrated = LiquidityPoolV1::load(&data).unwrap().migrate();
+
+    assert_eq!(migrated.version, LiquidityPool::VERSION);
+    assert_eq!(migrated.admin, pool.admin);
+    assert!(migrated.paused);
+    assert_eq!(migrated.asset_count, 1);
+    assert_eq!(migrated.pricing, PricingMode::Balances);
+
+    // and a current pool isn't mistaken for one
+    assert!(LiquidityPoolV1::load(&to_vec(&migrated).unwrap()).is_err());
+}
+
+#[test]
+fn v2_pool_needs_migration() {
+    let pool = LiquidityPoolV2 {
+        version: LiquidityPoolV2::VERSION,
+        admin: Pubkey::new_unique(),
+        paused: false,
+        fee_bps: 30,
+        asset_count: 3,
+        bump: 255,
+    };
+
+    let data = to_vec(&pool).unwrap();
+
+    assert_eq!(data.len(), LiquidityPoolV2::SPACE);
+    assert!(LiquidityPool::load(&data).is_err());
+    assert!(LiquidityPoolV1::load(&data).is_err());
+
+    let migrated = LiquidityPoolV2::load(&data).unwrap().migrate();
+
+    assert_eq!(migrated.version, LiquidityPool::VERSION);
+    assert_eq!(migrated.admin, pool.admin);
+    assert_eq!(migrated.asset_count, 3);
+    assert_eq!(migrated.pricing, PricingMode::Balances);
+
+    assert!(LiquidityPoolV2::load(&to_vec(&migrated).unwrap()).is_err());
+}
+
+#[test]
+fn asset_oracle_space_matches_layout() {
+    let asset_oracle = AssetOracle {
+        mint: Pubkey::new_unique(),
+        price_feed: Pubkey::new_unique(),
+        decimals: 9,
+        bump: 255,
+    };
+
+    assert_eq!(to_vec(&asset_oracle).unwrap().len(), AssetOracle::SPACE);
+}
+
+#[test]
+fn funding_receipt_space_matches_layout() {
+    let receipt = FundingReceipt {
+        funder: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        amount: u64::MAX,
+        bump: 255,
+    };
+
+    assert_eq!(to_vec(&receipt).unwrap().len(), FundingReceipt::SPACE);
+}
+
+#[test]
+fn funder_allowlist_space_matches_layout() {
+    let allowlist = FunderAllowlist { enabled: true, bump: 255 };
+
+    assert_eq!(to_vec(&allowlist).unwrap().len(), FunderAllowlist::SPACE);
+
+    let approved_funder = ApprovedFunder { funder: Pubkey::new_unique(), bump: 255 };
+
+    assert_eq!(to_vec(&approved_funder).unwrap().len(), ApprovedFunder::SPACE);
+}
+
+#[test]
+fn asset_list_page_fits_return_data() {
+    let asset = AssetBalance { mint: Pubkey::new_unique(), balance: u64::MAX };
+
+    let page = AssetList { asset_count: u8::MAX, assets: vec![asset; AssetList::MAX_PAGE_ASSETS] };
+
+    assert!(to_vec(&page).unwrap().len() <= MAX_RETURN_DATA);
+    const { assert!(AssetList::MAX_PAGE_ASSETS >= LiquidityPool::MAX_ASSETS) };
+
+    let page = AssetList { asset_count: u8::MAX, assets: vec![asset; AssetList::MAX_PAGE_ASSETS + 1] };
+
+    assert!(to_vec(&page).unwrap().len() > MAX_RETURN_DATA);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::math::swap_receive_amount;
+
+use common::*;
+
+#[tokio::test]
+async fn swap_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pool = pool_address(&program_id);
+    let amount_to_swap = 100_000_000;
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, 0)?], &[]).await?;
+
+    // 1_000 * 100 / (1_000 + 100)
+    let expected = swap_receive_amount(INITIAL_FUNDING, INITIAL_FUNDING, amount_to_swap).unwrap();
+
+    assert_eq!(expected, 90_909_090);
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &rum)).await?, USER_FUNDS - INITIAL_FUNDING - amount_to_swap);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &gold)).await?, USER_FUNDS - INITIAL_FUNDING + expected);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &rum)).await?, INITIAL_FUNDING + amount_to_swap);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING - expected);
+
+    // the next swap is priced off the new balances
+    send(&mut context, &[swap_ix(&program_id, &payer, &rum, &gold, expected, 0)?], &[]).await?;
+
+    let received = swap_receive_amount(INITIAL_FUNDING + amount_to_swap, INITIAL_FUNDING - expected, expected).unwrap();
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &rum)).await?, INITIAL_FUNDING + amount_to_swap - received);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_from_empty_asset_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+
+    // in the pool, but with nothing to pay out
+    let cannons = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &cannons, USER_FUNDS).await?;
+    send(&mut context, &[fund_pool_ix(&program_id, &payer, &cannons, 0)?], &[]).await?;
+
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &cannons, &mints[0], 1_000_000, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidSwapNotEnoughLiquidity as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_zero_amount_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &mints[0], &mints[1], 0, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidSwapZeroAmount as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_matching_assets_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &mints[0], &mints[0], 1_000_000, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidSwapMatchingAssets as u32));
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &mints[0])).await?, USER_FUNDS - INITIAL_FUNDING);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_slippage_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let amount_to_swap = 100_000_000;
+    let expected = swap_receive_amount(INITIAL_FUNDING, INITIAL_FUNDING, amount_to_swap).unwrap();
+
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, expected + 1)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::SlippageExceeded as u32));
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &rum)).await?, USER_FUNDS - INITIAL_FUNDING);
+
+    // exactly the minimum goes through
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, expected)?], &[]).await?;
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &gold)).await?, USER_FUNDS - INITIAL_FUNDING + expected);
+
+    // the same minimum no longer holds once the pool has moved
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, expected)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::SlippageExceeded as u32));
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::math::{swap_receive_amount, fee_amount};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn swap_fee_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool_with_fee(&mut context, &program_id, 2, FEE_BPS).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pool = pool_address(&program_id);
+    let amount_to_swap = 100_000_000;
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, 0)?], &[]).await?;
+
+    let gross = swap_receive_amount(INITIAL_FUNDING, INITIAL_FUNDING, amount_to_swap).unwrap();
+    let fee = fee_amount(gross, FEE_BPS).unwrap();
+
+    assert_eq!(fee, 272_727);
+
+    // the fee never leaves the pool's ATA
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &gold)).await?, USER_FUNDS - INITIAL_FUNDING + gross - fee);
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool, &gold)).await?, INITIAL_FUNDING - gross + fee);
+
+    assert_eq!(pool_state(&mut context, &pool).await?.fee_bps, FEE_BPS);
+    assert_eq!(pool_asset_state(&mut context, &program_id, &gold).await?.collected_fees, fee);
+    assert_eq!(pool_asset_state(&mut context, &program_id, &rum).await?.collected_fees, 0);
+
+    // fees in the other asset are tracked separately
+    send(&mut context, &[swap_ix(&program_id, &payer, &rum, &gold, 1_000_000, 0)?], &[]).await?;
+
+    assert_eq!(pool_asset_state(&mut context, &program_id, &gold).await?.collected_fees, fee);
+    assert!(pool_asset_state(&mut context, &program_id, &rum).await?.collected_fees > 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_fee_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    setup_pool(&mut context, &program_id, 2).await?;
+
+    let pool = pool_address(&program_id);
+
+    send(&mut context, &[set_fee_ix(&program_id, &payer, 100)?], &[]).await?;
+
+    assert_eq!(pool_state(&mut context, &pool).await?.fee_bps, 100);
+
+    let result = send(&mut context, &[set_fee_ix(&program_id, &payer, 10_001)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidFee as u32));
+
+    let attacker = Keypair::new();
+
+    let result = send(&mut context, &[set_fee_ix(&program_id, &attacker.pubkey(), 0)?], &[&attacker]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+    assert_eq!(pool_state(&mut context, &pool).await?.fee_bps, 100);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_invalid_fee_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let result = send(&mut context, &[create_pool_ix(&program_id, &payer, 10_001)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidFee as u32));
+
+    Ok(())
+}
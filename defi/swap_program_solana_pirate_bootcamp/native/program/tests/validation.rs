@@ -0,0 +1,189 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+
+use common::*;
+
+// FundPool, Swap, RemoveAsset and WithdrawFunding account indices, see
+// fund_pool_ix, swap_ix, remove_asset_ix and withdraw_funding_ix.
+const FUND_TOKEN_PROGRAM: usize = 6;
+const SWAP_POOL: usize = 0;
+const SWAP_POOL_RECEIVE_ATA: usize = 2;
+const SWAP_POOL_PAY_ATA: usize = 5;
+const SWAP_TOKEN_PROGRAM: usize = 8;
+const REMOVE_ASSET_TOKEN_PROGRAM: usize = 3;
+const WITHDRAW_TOKEN_PROGRAM: usize = 6;
+
+// A copy of `mint` that some other program owns, for passing off as a real one.
+async fn fake_mint(context: &mut ProgramTestContext, mint: &Pubkey) -> Result<Pubkey> {
+    let mut account: Account = context.banks_client.get_account(*mint).await?.unwrap();
+    account.owner = Pubkey::new_unique();
+
+    let fake = Pubkey::new_unique();
+    context.set_account(&fake, &AccountSharedData::from(account));
+
+    Ok(fake)
+}
+
+#[tokio::test]
+async fn fund_pool_fake_mint_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+    let fake = fake_mint(&mut context, &mints[0]).await?;
+
+    let result = send(&mut context, &[fund_pool_ix(&program_id, &payer, &fake, 1_000)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidMintOwner as u32));
+    assert_eq!(pool_state(&mut context, &pool_address(&program_id)).await?.asset_count, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fund_pool_wrong_token_program_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+
+    let mut instruction = fund_pool_ix(&program_id, &payer, &mints[0], 1_000)?;
+    instruction.accounts[FUND_TOKEN_PROGRAM] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidTokenProgram as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_fake_mint_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let fake = fake_mint(&mut context, &mints[1]).await?;
+
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &mints[0], &fake, 1_000, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidMintOwner as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_wrong_token_program_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+
+    let mut instruction = swap_ix(&program_id, &payer, &mints[0], &mints[1], 1_000, 0)?;
+    instruction.accounts[SWAP_TOKEN_PROGRAM] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidTokenProgram as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn remove_asset_wrong_token_program_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+
+    let mut instruction = remove_asset_ix(&program_id, &payer, &mints[0])?;
+    instruction.accounts[REMOVE_ASSET_TOKEN_PROGRAM] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidTokenProgram as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_funding_wrong_token_program_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+
+    let mut instruction = withdraw_funding_ix(&program_id, &payer, &mints[0], 1_000)?;
+    instruction.accounts[WITHDRAW_TOKEN_PROGRAM] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidTokenProgram as u32));
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&pool_address(&program_id), &mints[0])).await?, INITIAL_FUNDING);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_pay_asset_not_in_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+
+    // a real mint the pool has never been funded with
+    let parrots = create_mint(&mut context).await?;
+
+    create_funded_ata(&mut context, &payer, &parrots, USER_FUNDS).await?;
+
+    let result = send(&mut context, &[swap_ix(&program_id, &payer, &mints[0], &parrots, 1_000, 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::AssetNotInPool as u32));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_wrong_pool_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+
+    // a copy of the pool's data at an address that isn't the pool PDA
+    let account: Account = context.banks_client.get_account(pool_address(&program_id)).await?.unwrap();
+    let fake = Pubkey::new_unique();
+
+    context.set_account(&fake, &AccountSharedData::from(account));
+
+    let mut instruction = swap_ix(&program_id, &payer, &mints[0], &mints[1], 1_000, 0)?;
+    instruction.accounts[SWAP_POOL] = AccountMeta::new_readonly(fake, false);
+    instruction.accounts[SWAP_POOL_RECEIVE_ATA] = AccountMeta::new(get_associated_token_address(&fake, &mints[0]), false);
+    instruction.accounts[SWAP_POOL_PAY_ATA] = AccountMeta::new(get_associated_token_address(&fake, &mints[1]), false);
+
+    let result = send(&mut context, &[instruction], &[]).await;
+
+    assert_eq!(instruction_error(result), Some(InstructionError::InvalidSeeds));
+
+    Ok(())
+}
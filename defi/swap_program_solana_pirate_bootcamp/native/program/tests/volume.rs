@@ -0,0 +1,56 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+use program::math::{swap_receive_amount, fee_amount};
+
+use common::*;
+
+const FEE_BPS: u16 = 30;
+
+#[tokio::test]
+async fn swap_volume_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool_with_fee(&mut context, &program_id, 3, FEE_BPS).await?;
+    let (gold, rum, cannons) = (mints[0], mints[1], mints[2]);
+
+    for mint in &mints {
+        let pool_asset = pool_asset_state(&mut context, &program_id, mint).await?;
+
+        assert_eq!((pool_asset.pay_volume, pool_asset.receive_volume), (0, 0));
+    }
+
+    let amount_to_swap = 100_000_000;
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, 0)?], &[]).await?;
+
+    // what the payer got, the fee stays behind
+    let gross = swap_receive_amount(INITIAL_FUNDING, INITIAL_FUNDING, amount_to_swap).unwrap();
+    let received = gross - fee_amount(gross, FEE_BPS).unwrap();
+
+    let gold_asset = pool_asset_state(&mut context, &program_id, &gold).await?;
+    let rum_asset = pool_asset_state(&mut context, &program_id, &rum).await?;
+
+    assert_eq!((gold_asset.pay_volume, gold_asset.receive_volume), (0, received as u128));
+    assert_eq!((rum_asset.pay_volume, rum_asset.receive_volume), (amount_to_swap as u128, 0));
+
+    // volume adds up across swaps and pairs
+    send(&mut context, &[swap_ix(&program_id, &payer, &cannons, &rum, amount_to_swap, 0)?], &[]).await?;
+
+    let rum_asset = pool_asset_state(&mut context, &program_id, &rum).await?;
+    let cannons_asset = pool_asset_state(&mut context, &program_id, &cannons).await?;
+
+    assert_eq!(rum_asset.pay_volume, 2 * amount_to_swap as u128);
+    assert!(cannons_asset.receive_volume > 0);
+    assert_eq!(pool_asset_state(&mut context, &program_id, &gold).await?.receive_volume, received as u128);
+
+    Ok(())
+}
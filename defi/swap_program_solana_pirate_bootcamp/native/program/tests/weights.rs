@@ -0,0 +1,75 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::math::{swap_receive_amount, weighted_swap_receive_amount};
+use program::state::PoolAsset;
+
+use common::*;
+
+#[tokio::test]
+async fn weighted_swap_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    assert_eq!(pool_asset_state(&mut context, &program_id, &gold).await?.weight, PoolAsset::DEFAULT_WEIGHT);
+
+    // gold is scarce
+    send(&mut context, &[set_weight_ix(&program_id, &payer, &gold, 4 * PoolAsset::DEFAULT_WEIGHT)?], &[]).await?;
+
+    assert_eq!(pool_asset_state(&mut context, &program_id, &gold).await?.weight, 4 * PoolAsset::DEFAULT_WEIGHT);
+
+    let amount_to_swap = 100_000_000;
+
+    send(&mut context, &[swap_ix(&program_id, &payer, &gold, &rum, amount_to_swap, 0)?], &[]).await?;
+
+    let expected = weighted_swap_receive_amount(
+        INITIAL_FUNDING, 
+        INITIAL_FUNDING, 
+        amount_to_swap, 
+        4 * PoolAsset::DEFAULT_WEIGHT, 
+        PoolAsset::DEFAULT_WEIGHT,
+    ).unwrap();
+
+    assert!(expected < swap_receive_amount(INITIAL_FUNDING, INITIAL_FUNDING, amount_to_swap).unwrap());
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&payer, &gold)).await?, USER_FUNDS - INITIAL_FUNDING + expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_weight_invalid_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+
+    let result = send(&mut context, &[set_weight_ix(&program_id, &payer, &mints[0], 0)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::InvalidWeight as u32));
+
+    let result = send(&mut context, &[set_weight_ix(&program_id, &payer, &Pubkey::new_unique(), 50)?], &[]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::AssetNotInPool as u32));
+
+    let attacker = Keypair::new();
+
+    let result = send(&mut context, &[set_weight_ix(&program_id, &attacker.pubkey(), &mints[0], 50)?], &[&attacker]).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::NotPoolAdmin as u32));
+    assert_eq!(pool_asset_state(&mut context, &program_id, &mints[0]).await?.weight, PoolAsset::DEFAULT_WEIGHT);
+
+    Ok(())
+}
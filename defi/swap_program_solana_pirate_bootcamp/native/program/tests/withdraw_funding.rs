@@ -0,0 +1,94 @@
+mod common;
+
+use anyhow::Result;
+
+use solana_sdk::{
+    borsh1::try_from_slice_unchecked,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::get_associated_token_address;
+
+use program::error::SwapProgramError;
+use program::state::FundingReceipt;
+
+use common::*;
+
+async fn receipt_amount(context: &mut solana_program_test::ProgramTestContext, receipt: &Pubkey) -> Result<u64> {
+    let account = context.banks_client.get_account(*receipt).await?.unwrap();
+
+    Ok(try_from_slice_unchecked::<FundingReceipt>(&account.data)?.amount)
+}
+
+#[tokio::test]
+async fn withdraw_funding_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 2).await?;
+    let (gold, rum) = (mints[0], mints[1]);
+
+    let pool = pool_address(&program_id);
+    let receipt = funding_receipt_address(&program_id, &payer, &gold);
+    let payer_ata = get_associated_token_address(&payer, &gold);
+    let pool_ata = get_associated_token_address(&pool, &gold);
+
+    // swaps move the pool's balances around, but a receipt is paid out at face value
+    send(&mut context, &[swap_ix(&program_id, &payer, &rum, &gold, 50_000_000, 0)?], &[]).await?;
+
+    let payer_before = token_balance(&mut context, &payer_ata).await?;
+    let pool_before = token_balance(&mut context, &pool_ata).await?;
+
+    send(&mut context, &[withdraw_funding_ix(&program_id, &payer, &gold, 400_000_000)?], &[]).await?;
+
+    assert_eq!(token_balance(&mut context, &payer_ata).await?, payer_before + 400_000_000);
+    assert_eq!(token_balance(&mut context, &pool_ata).await?, pool_before - 400_000_000);
+    assert_eq!(receipt_amount(&mut context, &receipt).await?, INITIAL_FUNDING - 400_000_000);
+
+    let result = send(
+        &mut context,
+        &[withdraw_funding_ix(&program_id, &payer, &gold, INITIAL_FUNDING - 400_000_000 + 1)?],
+        &[],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::WithdrawExceedsReceipt as u32));
+
+    send(&mut context, &[withdraw_funding_ix(&program_id, &payer, &gold, INITIAL_FUNDING - 400_000_000)?], &[]).await?;
+
+    assert_eq!(receipt_amount(&mut context, &receipt).await?, 0);
+    assert_eq!(token_balance(&mut context, &pool_ata).await?, pool_before - (INITIAL_FUNDING - 400_000_000) - 400_000_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_over_receipt_test() -> Result<()> {
+    let program_id = Pubkey::new_unique();
+    let mut context = start(&program_id).await;
+    let payer = context.payer.pubkey();
+
+    let mints = setup_pool(&mut context, &program_id, 1).await?;
+
+    // a small funder can't draw on what others put in
+    let funder = Keypair::new();
+
+    send(&mut context, &[transfer(&payer, &funder.pubkey(), 1_000_000_000)], &[]).await?;
+    create_funded_ata(&mut context, &funder.pubkey(), &mints[0], 500_000).await?;
+    send(&mut context, &[fund_pool_ix(&program_id, &funder.pubkey(), &mints[0], 500_000)?], &[&funder]).await?;
+
+    let result = send(
+        &mut context,
+        &[withdraw_funding_ix(&program_id, &funder.pubkey(), &mints[0], 500_001)?],
+        &[&funder],
+    ).await;
+
+    assert_eq!(custom_error(result), Some(SwapProgramError::WithdrawExceedsReceipt as u32));
+
+    send(&mut context, &[withdraw_funding_ix(&program_id, &funder.pubkey(), &mints[0], 500_000)?], &[&funder]).await?;
+
+    assert_eq!(token_balance(&mut context, &get_associated_token_address(&funder.pubkey(), &mints[0])).await?, 500_000);
+
+    Ok(())
+}